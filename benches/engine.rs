@@ -0,0 +1,147 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use social_media_sandbox::{
+    bench::{synthetic_engine, synthetic_individual, synthetic_profile, synthetic_simulation},
+    engine::CreatorExclusions,
+    models::{AgentId, IdAllocator, RngStreams, SimulationConfig},
+};
+use std::hint::black_box;
+
+const POOL_SIZES: [usize; 3] = [1_000, 10_000, 100_000];
+const AGENT_COUNTS: [usize; 2] = [100, 1_000];
+const INTEREST_UPDATE_AGENTS: usize = 10_000;
+
+// Fixed so every run benchmarks the same synthetic platform
+const SEED: u64 = 1;
+
+fn config() -> SimulationConfig {
+    SimulationConfig {
+        seed: SEED,
+        ..SimulationConfig::default()
+    }
+}
+
+fn content_score(c: &mut Criterion) {
+    let config = config();
+    let mut ids = IdAllocator::new();
+    let engine = synthetic_engine(&config, &mut ids, 1);
+    let agent = synthetic_individual(ids.next_agent_id(), &config, &engine);
+    let post = &engine.content_pool[0];
+    let now = engine.now();
+
+    c.bench_function("calculate_content_score", |b| {
+        b.iter(|| engine.calculate_content_score(black_box(post), black_box(&agent), now))
+    });
+}
+
+fn post_recommendations(c: &mut Criterion) {
+    let config = config();
+    let mut group = c.benchmark_group("get_post_recommendations");
+
+    for pool_size in POOL_SIZES {
+        let mut ids = IdAllocator::new();
+        let engine = synthetic_engine(&config, &mut ids, pool_size);
+        let agent = synthetic_individual(AgentId(usize::MAX), &config, &engine);
+        let now = engine.now();
+        let exclusions = CreatorExclusions::for_agent(&agent);
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(pool_size),
+            &engine,
+            |b, engine| {
+                b.iter(|| engine.get_post_recommendations(black_box(&agent), 10, now, &exclusions))
+            },
+        );
+    }
+
+    group.finish();
+}
+
+// One read's worth of interest gain for every agent, updating the vector in
+// place against rebuilding and renormalising the whole profile
+fn interest_updates(c: &mut Criterion) {
+    let config = config();
+    let mut ids = IdAllocator::new();
+    let engine = synthetic_engine(&config, &mut ids, 1);
+    let post = &engine.content_pool[0].interest_profile;
+    let mut rng = RngStreams::stream(SEED, "profiles");
+    let profiles: Vec<_> = (0..INTEREST_UPDATE_AGENTS)
+        .map(|_| synthetic_profile(&config, &engine, &mut rng))
+        .collect();
+
+    let mut group = c.benchmark_group("interest_updates");
+    group.sample_size(20);
+
+    group.bench_function(
+        BenchmarkId::new("incremental", INTEREST_UPDATE_AGENTS),
+        |b| {
+            b.iter_batched_ref(
+                || profiles.clone(),
+                |profiles| {
+                    for profile in profiles.iter_mut() {
+                        profile.update_interest_from_profile(
+                            black_box(post),
+                            0.2,
+                            &engine.tag_similarity,
+                            &engine.tag_to_index,
+                        );
+                    }
+                },
+                criterion::BatchSize::LargeInput,
+            )
+        },
+    );
+
+    group.bench_function(BenchmarkId::new("rebuild", INTEREST_UPDATE_AGENTS), |b| {
+        b.iter_batched_ref(
+            || profiles.clone(),
+            |profiles| {
+                for profile in profiles.iter_mut() {
+                    profile.update_interest_from_profile(
+                        black_box(post),
+                        0.2,
+                        &engine.tag_similarity,
+                        &engine.tag_to_index,
+                    );
+                    profile.normalise_weights();
+                    engine.vectorise(profile);
+                }
+            },
+            criterion::BatchSize::LargeInput,
+        )
+    });
+
+    group.finish();
+}
+
+fn simulation_tick(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Simulation::tick");
+    group.sample_size(10);
+
+    for num_agents in AGENT_COUNTS {
+        for pool_size in POOL_SIZES {
+            let simulation = synthetic_simulation(SEED, num_agents, pool_size);
+
+            group.bench_function(
+                BenchmarkId::new(format!("{num_agents} agents"), pool_size),
+                |b| {
+                    b.iter_batched_ref(
+                        || simulation.clone(),
+                        |simulation| simulation.step(),
+                        criterion::BatchSize::LargeInput,
+                    )
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    content_score,
+    post_recommendations,
+    interest_updates,
+    simulation_tick
+);
+criterion_main!(benches);
@@ -0,0 +1,109 @@
+// Synthetic platforms for benchmarking the engine at realistic scales. Only
+// compiled with the `bench` feature. Everything is drawn from streams of the
+// config's seed, so a benchmark measures the same platform on every run
+use rand::{seq::SliceRandom, Rng};
+
+use crate::models::{
+    AgentId, IdAllocator, Individual, InterestProfile, Post, RngStreams, SimulationConfig, Topic,
+};
+use crate::{RecommendationEngine, Simulation};
+
+// A profile over a random handful of the sample tags, with its vector built
+// against the engine's tag indices so that similarity scoring does real work
+pub fn synthetic_profile<R: Rng + ?Sized>(
+    config: &SimulationConfig,
+    engine: &RecommendationEngine,
+    rng: &mut R,
+) -> InterestProfile {
+    let mut profile = InterestProfile::new(engine.vector_dimension);
+    let num_tags = rng.gen_range(1..=config.max_content_tags.max(1));
+
+    for tag in config.sample_tags.choose_multiple(rng, num_tags) {
+        profile.interests.insert(
+            tag.clone(),
+            Topic {
+                weighted_interest: rng.gen(),
+                agreement: rng.gen::<f32>() * 2.0 - 1.0,
+            },
+        );
+    }
+
+    profile.normalise_weights();
+    engine.vectorise(&mut profile);
+
+    profile
+}
+
+pub fn synthetic_engine(
+    config: &SimulationConfig,
+    ids: &mut IdAllocator,
+    num_posts: usize,
+) -> RecommendationEngine {
+    let mut engine = RecommendationEngine::new();
+    engine.register_tags(&config.sample_tags);
+    populate_pool(&mut engine, config, ids, num_posts);
+    engine
+}
+
+pub fn synthetic_individual(
+    id: AgentId,
+    config: &SimulationConfig,
+    engine: &RecommendationEngine,
+) -> Individual {
+    let mut rng = RngStreams::keyed(config.seed, "synthetic individual", id.0 as u64);
+    let mut individual = Individual::new(id, config, engine);
+    individual.core.interest_profile = synthetic_profile(config, engine, &mut rng);
+    individual
+}
+
+// A simulation of `num_agents` individuals over a pre-filled pool. Creators
+// are drawn from outside the simulated population
+pub fn synthetic_simulation(seed: u64, num_agents: usize, num_posts: usize) -> Simulation {
+    let config = SimulationConfig {
+        seed,
+        num_individuals: 0,
+        num_bots: 0,
+        num_organisations: 0,
+        ..SimulationConfig::default()
+    };
+    let mut simulation = Simulation::new(config);
+
+    populate_pool(
+        &mut simulation.engine,
+        &simulation.config,
+        &mut simulation.ids,
+        num_posts,
+    );
+
+    for _ in 0..num_agents {
+        let id = simulation.ids.next_agent_id();
+        let agent = synthetic_individual(id, &simulation.config, &simulation.engine);
+        simulation.agents.push(Box::new(agent));
+    }
+
+    simulation
+}
+
+fn populate_pool(
+    engine: &mut RecommendationEngine,
+    config: &SimulationConfig,
+    ids: &mut IdAllocator,
+    num_posts: usize,
+) {
+    let mut rng = RngStreams::stream(config.seed, "synthetic pool");
+    let creators: Vec<AgentId> = (0..(num_posts / 10).max(1))
+        .map(|_| ids.next_agent_id())
+        .collect();
+
+    for _ in 0..num_posts {
+        let creator = *creators.choose(&mut rng).unwrap();
+        let mut post = Post::new(
+            ids.next_post_id(),
+            creator,
+            synthetic_profile(config, engine, &mut rng),
+            config,
+        );
+        post.engagement_score = rng.gen();
+        engine.create_post(post);
+    }
+}
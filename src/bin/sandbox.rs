@@ -0,0 +1,641 @@
+use social_media_sandbox::{
+    bundle::{export_bundle, import_bundle, latest_checkpoint, write_checkpoint},
+    classroom::{Classroom, ClientId},
+    metrics::MetricsExport,
+    models::{
+        HealthWarning, InvalidConfig, PopulationMetric, Scenario, SimulationConfig, TradeoffCurve,
+    },
+    profiling,
+    worker::SimulationSummary,
+    Simulation,
+};
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, IsTerminal, Write};
+use std::net::{TcpListener, TcpStream};
+use std::process::ExitCode;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "usage:
+  sandbox validate <scenario.toml>
+  sandbox run <scenario.toml> --ticks <n> [--quiet] [--bundle <out.smsbundle>]
+      [--checkpoint <run_dir> [--checkpoint-every <n>]] [--profile <out.folded>]
+      [--transparency <out.md|out.json>]
+  sandbox run --resume <run_dir> --ticks <n> [--quiet] [--bundle <out.smsbundle>]
+      [--checkpoint-every <n>] [--profile <out.folded>] [--transparency <out.md|out.json>]
+  sandbox tradeoff <scenario.toml> --ticks <n> --values <a,b,...> [--key <parameter>]
+  sandbox classroom <scenario.toml> [--port <n>]";
+
+// What the host of a class can type while it runs
+const CLASSROOM_COMMANDS: &str = "commands:
+  approve <submission>
+  reject <submission>
+  step <n>
+  quit";
+
+// The port a class is hosted on when not told which
+const CLASSROOM_PORT: u16 = 7878;
+
+// The parameter a tradeoff sweep varies when not told which
+const TRADEOFF_KEY: &str = "engagement_weight";
+
+// How often the live progress line is redrawn on a terminal
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
+// How many times metrics are recorded over a run that is being bundled
+const METRIC_SAMPLES: u64 = 100;
+
+// How many checkpoints a run takes when not told how often
+const CHECKPOINTS: u64 = 10;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match args
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        ["validate", path] => validate(path),
+        ["run", options @ ..] => match RunOptions::parse(options) {
+            Some(options) => run(&options),
+            None => usage(),
+        },
+        ["tradeoff", options @ ..] => match TradeoffOptions::parse(options) {
+            Some(options) => tradeoff(&options),
+            None => usage(),
+        },
+        ["classroom", path] => classroom(path, CLASSROOM_PORT),
+        ["classroom", path, "--port", port] => match port.parse() {
+            Ok(port) => classroom(path, port),
+            Err(_) => usage(),
+        },
+        _ => usage(),
+    }
+}
+
+fn usage() -> ExitCode {
+    eprintln!("{USAGE}");
+    ExitCode::from(2)
+}
+
+fn load(path: &str) -> Option<Scenario> {
+    match Scenario::load(path) {
+        Ok(scenario) => Some(scenario),
+        Err(e) => {
+            eprintln!("{path}: {e}");
+            None
+        }
+    }
+}
+
+// Checks a scenario file and prints the config it amounts to once defaults
+// are filled in. Fails if the file doesn't parse or has any problems; unknown
+// and deprecated keys are only warned about
+fn validate(path: &str) -> ExitCode {
+    let Some(scenario) = load(path) else {
+        return ExitCode::FAILURE;
+    };
+
+    for warning in &scenario.warnings {
+        eprintln!("{path}: warning: {warning}");
+    }
+    let problems = scenario.validate();
+    for problem in &problems {
+        eprintln!("{path}: error: {problem}");
+    }
+
+    match Scenario::to_toml(&scenario.config) {
+        Ok(config) => println!("{config}"),
+        Err(e) => eprintln!("{path}: could not print effective config: {e}"),
+    }
+
+    if problems.is_empty() {
+        eprintln!("{path}: ok");
+        ExitCode::SUCCESS
+    } else {
+        eprintln!("{path}: {} problem(s)", problems.len());
+        ExitCode::FAILURE
+    }
+}
+
+struct RunOptions {
+    // Either a scenario to start from or a run directory to resume
+    scenario: Option<String>,
+    resume: Option<String>,
+    ticks: u64,
+    // Nothing but errors, for batch jobs whose logs nobody watches live
+    quiet: bool,
+    bundle: Option<String>,
+    // Where checkpoints go. Resumed runs keep checkpointing where they were
+    checkpoint: Option<String>,
+    checkpoint_every: Option<u64>,
+    // Where to write the run's timing spans as folded stacks for a flamegraph
+    profile: Option<String>,
+    // Where to write the run's transparency reports, as JSON when the path
+    // ends in .json and markdown otherwise
+    transparency: Option<String>,
+}
+
+impl RunOptions {
+    fn parse(args: &[&str]) -> Option<Self> {
+        let mut options = RunOptions {
+            scenario: None,
+            resume: None,
+            ticks: 0,
+            quiet: false,
+            bundle: None,
+            checkpoint: None,
+            checkpoint_every: None,
+            profile: None,
+            transparency: None,
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match *arg {
+                "--ticks" => options.ticks = args.next()?.parse().ok()?,
+                "--quiet" => options.quiet = true,
+                "--bundle" => options.bundle = Some(args.next()?.to_string()),
+                "--checkpoint" => options.checkpoint = Some(args.next()?.to_string()),
+                "--checkpoint-every" => {
+                    options.checkpoint_every = Some(args.next()?.parse().ok().filter(|n| *n > 0)?)
+                }
+                "--resume" => options.resume = Some(args.next()?.to_string()),
+                "--profile" => options.profile = Some(args.next()?.to_string()),
+                "--transparency" => options.transparency = Some(args.next()?.to_string()),
+                path if !path.starts_with("--") && options.scenario.is_none() => {
+                    options.scenario = Some(path.to_string())
+                }
+                _ => return None,
+            }
+        }
+
+        if options.resume.is_some() {
+            if options.scenario.is_some() || options.checkpoint.is_some() {
+                return None;
+            }
+            options.checkpoint = options.resume.clone();
+        } else if options.scenario.is_none() {
+            return None;
+        }
+        (options.ticks > 0).then_some(options)
+    }
+
+    // Metrics are recorded whenever they might end up in a bundle, so a
+    // resumed run has the same series as one that was never interrupted
+    fn records_metrics(&self) -> bool {
+        self.bundle.is_some() || self.checkpoint.is_some()
+    }
+}
+
+// A fresh run from the scenario file, or the latest checkpoint in the run
+// directory replayed up to the step it was taken at
+fn start(options: &RunOptions) -> Option<(Simulation, MetricsExport)> {
+    if let Some(dir) = &options.resume {
+        let path = match latest_checkpoint(dir) {
+            Ok(Some(path)) => path,
+            Ok(None) => {
+                eprintln!("{dir}: no checkpoints to resume from");
+                return None;
+            }
+            Err(e) => {
+                eprintln!("{dir}: {e}");
+                return None;
+            }
+        };
+        let bundle = match import_bundle(&path) {
+            Ok(bundle) => bundle,
+            Err(e) => {
+                eprintln!("{}: {e}", path.display());
+                return None;
+            }
+        };
+        for warning in &bundle.warnings {
+            eprintln!("{}: warning: {warning}", path.display());
+        }
+        if !options.quiet {
+            eprintln!(
+                "Resuming from step {} of {}",
+                bundle.manifest.step,
+                path.display()
+            );
+        }
+        return Some((bundle.resume(), bundle.metrics));
+    }
+
+    let path = options.scenario.as_deref()?;
+    Some((Simulation::new(configure(path)?), MetricsExport::new()))
+}
+
+// The config a scenario file amounts to, or None having said what's wrong
+// with it
+fn configure(path: &str) -> Option<SimulationConfig> {
+    match load(path)?.into_config() {
+        Ok(config) => Some(config),
+        Err(InvalidConfig(problems)) => {
+            for problem in &problems {
+                eprintln!("{path}: error: {problem}");
+            }
+            None
+        }
+    }
+}
+
+// Steps a scenario headlessly for a fixed number of ticks, showing progress
+// as it goes, checkpointing and optionally bundling the finished run
+fn run(options: &RunOptions) -> ExitCode {
+    let Some((mut simulation, mut metrics)) = start(options) else {
+        return ExitCode::FAILURE;
+    };
+
+    let record_every = (options.ticks / METRIC_SAMPLES).max(1);
+    let checkpoint_every = options
+        .checkpoint_every
+        .unwrap_or((options.ticks / CHECKPOINTS).max(1));
+    let mut progress = Progress::new(options, simulation.step_count);
+    if options.profile.is_some() {
+        profiling::start_spans();
+    }
+
+    for tick in simulation.step_count + 1..=options.ticks {
+        simulation.step();
+        if options.records_metrics() && tick % record_every == 0 {
+            metrics.record(&simulation);
+        }
+        if let Some(dir) = &options.checkpoint {
+            if tick % checkpoint_every == 0 {
+                if let Err(e) = write_checkpoint(&simulation, &metrics, dir) {
+                    progress.clear_line();
+                    eprintln!("{dir}: could not checkpoint: {e}");
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        progress.update(tick, &simulation);
+    }
+    // Analytics still running in the background are part of the finished run
+    simulation.flush_analytics();
+    progress.finish(&simulation);
+
+    if let (Some(path), Some(profile)) = (&options.profile, profiling::stop_spans()) {
+        if let Err(e) = std::fs::write(path, profile.folded()) {
+            eprintln!("{path}: {e}");
+            return ExitCode::FAILURE;
+        }
+        if !options.quiet {
+            eprintln!("Wrote {path}");
+        }
+    }
+
+    if let Some(path) = &options.transparency {
+        let reports = transparency_reports(&mut simulation, path.ends_with(".json"));
+        if let Err(e) = reports
+            .map_err(|e| e.to_string())
+            .and_then(|reports| std::fs::write(path, reports).map_err(|e| e.to_string()))
+        {
+            eprintln!("{path}: {e}");
+            return ExitCode::FAILURE;
+        }
+        if !options.quiet {
+            eprintln!("Wrote {path}");
+        }
+    }
+
+    if let Some(bundle) = &options.bundle {
+        if let Err(e) = export_bundle(&simulation, &metrics, bundle) {
+            eprintln!("{bundle}: {e}");
+            return ExitCode::FAILURE;
+        }
+        if !options.quiet {
+            eprintln!("Wrote {bundle}");
+        }
+    }
+
+    ExitCode::SUCCESS
+}
+
+// Every report published over the run, closed off with one for the steps
+// since the last
+fn transparency_reports(simulation: &mut Simulation, json: bool) -> serde_json::Result<String> {
+    if simulation.transparency.next_step() < simulation.step_count {
+        simulation.publish_transparency_report();
+    }
+    if json {
+        simulation.transparency.to_json()
+    } else {
+        Ok(simulation.transparency.to_markdown())
+    }
+}
+
+struct TradeoffOptions {
+    scenario: String,
+    ticks: u64,
+    key: String,
+    values: Vec<f32>,
+}
+
+impl TradeoffOptions {
+    fn parse(args: &[&str]) -> Option<Self> {
+        let (mut scenario, mut ticks, mut key, mut values) =
+            (None, 0, TRADEOFF_KEY.to_string(), Vec::new());
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match *arg {
+                "--ticks" => ticks = args.next()?.parse().ok()?,
+                "--key" => key = args.next()?.to_string(),
+                "--values" => {
+                    values = args
+                        .next()?
+                        .split(',')
+                        .map(|value| value.trim().parse().ok())
+                        .collect::<Option<_>>()?
+                }
+                path if !path.starts_with("--") && scenario.is_none() => {
+                    scenario = Some(path.to_string())
+                }
+                _ => return None,
+            }
+        }
+
+        (ticks > 0 && !values.is_empty()).then_some(TradeoffOptions {
+            scenario: scenario?,
+            ticks,
+            key,
+            values,
+        })
+    }
+}
+
+// Runs the scenario once per value of the swept parameter and prints how
+// engagement, the quality of what was read and well-being came out, as CSV
+// with the Pareto optimal runs marked
+fn tradeoff(options: &TradeoffOptions) -> ExitCode {
+    let Some(scenario) = load(&options.scenario) else {
+        return ExitCode::FAILURE;
+    };
+
+    match TradeoffCurve::sweep(
+        &scenario.config,
+        &options.key,
+        &options.values,
+        options.ticks,
+    ) {
+        Ok(curve) => {
+            print!("{}", curve.to_csv());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}: {e}", options.scenario);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+// Everything a class's host waits on: students' connections and what they
+// send, and the commands typed at the terminal
+enum ClassEvent {
+    Connected(ClientId, TcpStream),
+    Message(ClientId, String),
+    Left(ClientId),
+    Command(String),
+}
+
+// Hosts a class over TCP. Students' clients send their messages as JSON, one
+// per line, and hear back the same way, while the host approves their
+// submissions and steps the simulation from stdin. Prints the address it's
+// listening on first, so scripts can find it when given port 0
+fn classroom(path: &str, port: u16) -> ExitCode {
+    let Some(config) = configure(path) else {
+        return ExitCode::FAILURE;
+    };
+    let listener = match TcpListener::bind(("127.0.0.1", port)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("port {port}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match listener.local_addr() {
+        Ok(address) => println!("listening on {address}"),
+        Err(e) => {
+            eprintln!("port {port}: {e}");
+            return ExitCode::FAILURE;
+        }
+    }
+    eprintln!("{CLASSROOM_COMMANDS}");
+
+    let (events, inbox) = mpsc::channel();
+    let connections = events.clone();
+    thread::spawn(move || {
+        for (client, stream) in listener.incoming().enumerate() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+            let Ok(reader) = stream.try_clone() else {
+                continue;
+            };
+            let client = client as ClientId;
+            if connections
+                .send(ClassEvent::Connected(client, stream))
+                .is_err()
+            {
+                return;
+            }
+            let messages = connections.clone();
+            thread::spawn(move || {
+                for line in BufReader::new(reader).lines().map_while(Result::ok) {
+                    if messages.send(ClassEvent::Message(client, line)).is_err() {
+                        return;
+                    }
+                }
+                let _ = messages.send(ClassEvent::Left(client));
+            });
+        }
+    });
+    // The class ends with the host's input
+    thread::spawn(move || {
+        for line in std::io::stdin().lines().map_while(Result::ok) {
+            if events.send(ClassEvent::Command(line)).is_err() {
+                return;
+            }
+        }
+        let _ = events.send(ClassEvent::Command("quit".to_string()));
+    });
+
+    let mut simulation = Simulation::new(config);
+    let mut class = Classroom::default();
+    let mut clients: BTreeMap<ClientId, TcpStream> = BTreeMap::new();
+    for event in inbox {
+        match event {
+            ClassEvent::Connected(client, stream) => {
+                clients.insert(client, stream);
+            }
+            ClassEvent::Message(client, json) => {
+                let waiting = class.pending.len();
+                class.handle_json(client, &json, simulation.step_count);
+                for submission in &class.pending[waiting..] {
+                    println!(
+                        "submission {} from {}: {:?} ({})",
+                        submission.id, submission.student, submission.intervention, submission.note
+                    );
+                }
+            }
+            ClassEvent::Left(client) => {
+                class.leave(client);
+                clients.remove(&client);
+            }
+            ClassEvent::Command(command) => {
+                match command.split_whitespace().collect::<Vec<_>>().as_slice() {
+                    [verb @ ("approve" | "reject"), id] => {
+                        let decided = id.parse().is_ok_and(|id| match *verb {
+                            "approve" => class.approve(id, &mut simulation),
+                            _ => class.reject(id),
+                        });
+                        if !decided {
+                            eprintln!("no pending submission {id}");
+                        }
+                    }
+                    ["step", steps] => match steps.parse::<u64>() {
+                        Ok(steps) => {
+                            for _ in 0..steps {
+                                simulation.step();
+                            }
+                            println!("step {}", simulation.step_count);
+                            class.broadcast(&SimulationSummary::capture(&simulation, false, 0.0));
+                        }
+                        Err(_) => eprintln!("{CLASSROOM_COMMANDS}"),
+                    },
+                    ["quit"] => break,
+                    [] => {}
+                    _ => eprintln!("{CLASSROOM_COMMANDS}"),
+                }
+            }
+        }
+
+        for (client, message) in class.drain_outbox() {
+            // A client that has gone is forgotten once its reader notices
+            if let (Some(stream), Ok(json)) = (clients.get_mut(&client), message.to_json()) {
+                let _ = writeln!(stream, "{json}");
+            }
+        }
+    }
+
+    ExitCode::SUCCESS
+}
+
+// Progress reporting for a headless run. On a terminal one status line is
+// redrawn in place; when piped to a file a line is written every tenth of the
+// run instead, so logs stay readable
+struct Progress {
+    ticks: u64,
+    // The tick a resumed run picked up from, so rates only count this session
+    first: u64,
+    quiet: bool,
+    live: bool,
+    started: Instant,
+    last_drawn: Instant,
+    warned: Vec<HealthWarning>,
+}
+
+impl Progress {
+    fn new(options: &RunOptions, first: u64) -> Self {
+        let now = Instant::now();
+        Self {
+            ticks: options.ticks,
+            first,
+            quiet: options.quiet,
+            live: std::io::stderr().is_terminal(),
+            started: now,
+            last_drawn: now,
+            warned: Vec::new(),
+        }
+    }
+
+    fn update(&mut self, tick: u64, simulation: &Simulation) {
+        if self.quiet {
+            return;
+        }
+
+        // Warnings scroll above the status line as they're raised
+        for warning in &simulation.diagnostics.warnings {
+            if !self.warned.contains(warning) {
+                self.warned.push(*warning);
+                self.clear_line();
+                eprintln!(
+                    "warning at step {}: {}",
+                    simulation.step_count,
+                    warning.message()
+                );
+            }
+        }
+
+        if self.live {
+            if self.last_drawn.elapsed() >= REDRAW_INTERVAL {
+                self.last_drawn = Instant::now();
+                eprint!("\r\x1b[2K{}", self.status(tick, simulation));
+                let _ = std::io::stderr().flush();
+            }
+        } else if tick % (self.ticks / 10).max(1) == 0 {
+            eprintln!("{}", self.status(tick, simulation));
+        }
+    }
+
+    fn finish(&mut self, simulation: &Simulation) {
+        if self.quiet {
+            return;
+        }
+        self.clear_line();
+        eprintln!(
+            "Finished {} ticks in {:.1}s",
+            self.ticks.saturating_sub(self.first),
+            self.started.elapsed().as_secs_f32()
+        );
+        eprintln!("{}", metrics_summary(simulation));
+    }
+
+    fn clear_line(&self) {
+        if self.live {
+            eprint!("\r\x1b[2K");
+        }
+    }
+
+    fn status(&self, tick: u64, simulation: &Simulation) -> String {
+        let elapsed = self.started.elapsed().as_secs_f32();
+        let rate = (tick - self.first) as f32 / elapsed.max(f32::EPSILON);
+        let eta = (self.ticks - tick) as f32 / rate.max(f32::EPSILON);
+        format!(
+            "step {tick}/{} ({:.0}%)  {rate:.0} ticks/s  ETA {}  {}",
+            self.ticks,
+            tick as f32 / self.ticks as f32 * 100.0,
+            format_duration(eta),
+            metrics_summary(simulation)
+        )
+    }
+}
+
+fn metrics_summary(simulation: &Simulation) -> String {
+    let mean = |metric: PopulationMetric| {
+        metric
+            .mean_where(simulation, |_| true)
+            .map_or("-".to_string(), |mean| format!("{mean:.2}"))
+    };
+    format!(
+        "entropy {}  session {}  posts {}",
+        mean(PopulationMetric::InterestEntropy),
+        mean(PopulationMetric::SessionLength),
+        simulation.engine.content_pool.len()
+    )
+}
+
+fn format_duration(seconds: f32) -> String {
+    let seconds = seconds.round() as u64;
+    if seconds >= 3600 {
+        format!("{}h{:02}m", seconds / 3600, seconds % 3600 / 60)
+    } else if seconds >= 60 {
+        format!("{}m{:02}s", seconds / 60, seconds % 60)
+    } else {
+        format!("{seconds}s")
+    }
+}
@@ -0,0 +1,72 @@
+// The simulation half of the web app's performance mode. Trunk builds this as
+// a web worker next to the app; it steps the simulation in short slices,
+// posting a summary back after each one and picking up commands in between
+
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    use social_media_sandbox::{
+        models::SimulationConfig,
+        worker::{WorkerCommand, WorkerLoop},
+    };
+    use std::{cell::RefCell, rc::Rc};
+    use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+    use web_sys::{DedicatedWorkerGlobalScope, MessageEvent};
+
+    // Long enough to get real work done, short enough that commands from the
+    // UI are picked up promptly
+    const SLICE: instant::Duration = instant::Duration::from_millis(50);
+
+    // How often a paused simulation reports in, so single steps show up
+    const IDLE_MS: i32 = 100;
+
+    let scope: DedicatedWorkerGlobalScope = js_sys::global().unchecked_into();
+    let worker = Rc::new(RefCell::new(WorkerLoop::new(SimulationConfig::default())));
+
+    let onmessage = Closure::<dyn FnMut(MessageEvent)>::new({
+        let worker = worker.clone();
+        move |event: MessageEvent| {
+            let Some(json) = event.data().as_string() else {
+                return;
+            };
+            match WorkerCommand::from_json(&json) {
+                Ok(command) => worker.borrow_mut().handle(command),
+                Err(e) => web_sys::console::warn_1(&format!("Bad worker command: {e}").into()),
+            }
+        }
+    });
+    scope.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+    onmessage.forget();
+
+    // Each slice schedules the next with a timeout, yielding to the message
+    // queue in between
+    let slice: Rc<RefCell<Option<Closure<dyn FnMut()>>>> = Rc::new(RefCell::new(None));
+    let next = slice.clone();
+    let slice_scope = scope.clone();
+    *slice.borrow_mut() = Some(Closure::new(move || {
+        let summary = worker.borrow_mut().run_for(SLICE);
+        if let Ok(json) = summary.to_json() {
+            let _ = slice_scope.post_message(&JsValue::from_str(&json));
+        }
+
+        let delay = if summary.running { 0 } else { IDLE_MS };
+        if let Some(callback) = next.borrow().as_ref() {
+            let _ = slice_scope.set_timeout_with_callback_and_timeout_and_arguments_0(
+                callback.as_ref().unchecked_ref(),
+                delay,
+            );
+        }
+    }));
+
+    if let Some(callback) = slice.borrow().as_ref() {
+        let _ = scope.set_timeout_with_callback_and_timeout_and_arguments_0(
+            callback.as_ref().unchecked_ref(),
+            0,
+        );
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    eprintln!("sandbox_worker only runs as the web app's worker; native builds step the simulation in the app itself");
+    std::process::exit(1);
+}
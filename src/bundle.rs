@@ -0,0 +1,272 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+
+use crate::metrics::{Compatibility, MetricsError, MetricsExport};
+use crate::models::{
+    AgentId, InterestHistory, InterventionLog, Scenario, ScenarioError, SimulationConfig,
+};
+use crate::Simulation;
+
+// Bumped whenever a file in the bundle changes in a way older readers can't
+// follow. Readers refuse bundles newer than they are
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+pub const BUNDLE_EXTENSION: &str = "smsbundle";
+
+// Checkpoints in a run directory are named for the step they were taken at
+const CHECKPOINT_PREFIX: &str = "checkpoint-";
+
+const MANIFEST: &str = "manifest.json";
+const SCENARIO: &str = "scenario.toml";
+const EVENTS: &str = "events.json";
+const SNAPSHOTS: &str = "snapshots.json";
+const METRICS: &str = "metrics.json";
+
+#[derive(Debug)]
+pub enum BundleError {
+    Io(std::io::Error),
+    Zip(zip::result::ZipError),
+    Json(serde_json::Error),
+    Config(toml::ser::Error),
+    Scenario(ScenarioError),
+    Metrics(MetricsError),
+    Unsupported(u32),
+}
+
+impl fmt::Display for BundleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BundleError::Io(e) => write!(f, "could not access bundle: {e}"),
+            BundleError::Zip(e) => write!(f, "invalid bundle archive: {e}"),
+            BundleError::Json(e) => write!(f, "invalid bundle contents: {e}"),
+            BundleError::Config(e) => write!(f, "could not write scenario: {e}"),
+            BundleError::Scenario(e) => write!(f, "{e}"),
+            BundleError::Metrics(e) => write!(f, "{e}"),
+            BundleError::Unsupported(version) => write!(
+                f,
+                "bundle format version {version} is newer than {BUNDLE_FORMAT_VERSION}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BundleError {}
+
+impl From<std::io::Error> for BundleError {
+    fn from(e: std::io::Error) -> Self {
+        BundleError::Io(e)
+    }
+}
+
+impl From<zip::result::ZipError> for BundleError {
+    fn from(e: zip::result::ZipError) -> Self {
+        BundleError::Zip(e)
+    }
+}
+
+impl From<serde_json::Error> for BundleError {
+    fn from(e: serde_json::Error) -> Self {
+        BundleError::Json(e)
+    }
+}
+
+// What a bundle holds and what wrote it, read before anything else
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format_version: u32,
+    pub crate_version: String,
+    pub seed: u64,
+    // The step the run had reached when it was exported
+    pub step: u64,
+    pub files: Vec<String>,
+}
+
+// Everything needed to archive an experiment and pick it up again elsewhere:
+// the config it ran under, the interventions applied to it, each agent's
+// interest snapshots and the metrics recorded along the way
+#[derive(Debug, Clone)]
+pub struct RunBundle {
+    pub manifest: BundleManifest,
+    pub config: SimulationConfig,
+    pub events: InterventionLog,
+    pub snapshots: BTreeMap<AgentId, InterestHistory>,
+    pub metrics: MetricsExport,
+    // Anything an older or newer bundle had that this version couldn't fully
+    // read. Empty for bundles written by this version
+    pub warnings: Vec<String>,
+}
+
+impl RunBundle {
+    pub fn capture(simulation: &Simulation, metrics: &MetricsExport) -> Self {
+        Self {
+            manifest: BundleManifest {
+                format_version: BUNDLE_FORMAT_VERSION,
+                crate_version: env!("CARGO_PKG_VERSION").to_string(),
+                seed: simulation.config.seed,
+                step: simulation.step_count,
+                files: [SCENARIO, EVENTS, SNAPSHOTS, METRICS]
+                    .map(String::from)
+                    .to_vec(),
+            },
+            config: simulation.config.clone(),
+            events: simulation.interventions.clone(),
+            snapshots: simulation
+                .interest_history
+                .iter()
+                .map(|(id, history)| (*id, history.clone()))
+                .collect(),
+            metrics: metrics.clone(),
+            warnings: Vec::new(),
+        }
+    }
+
+    // A fresh simulation under the bundled config with every intervention,
+    // applied or still queued, scheduled at its step. Stepping it to
+    // `manifest.step` replays the archived run
+    pub fn simulation(&self) -> Simulation {
+        let mut simulation = Simulation::new(self.config.clone());
+        for scheduled in self.events.applied.iter().chain(&self.events.queued) {
+            simulation.schedule_intervention(scheduled.step, scheduled.intervention.clone());
+        }
+        simulation
+    }
+
+    // The archived run as it stood when it was bundled, rebuilt by replaying
+    // it from the start. The simulation is deterministic for a given seed, so
+    // this carries on exactly as the original would have
+    pub fn resume(&self) -> Simulation {
+        let mut simulation = self.simulation();
+        while simulation.step_count < self.manifest.step {
+            simulation.step();
+        }
+        simulation
+    }
+
+    pub fn write_to<W: Write + Seek>(&self, writer: W) -> Result<(), BundleError> {
+        let mut zip = zip::ZipWriter::new(writer);
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated);
+
+        let scenario = Scenario::to_toml(&self.config).map_err(BundleError::Config)?;
+        let files = [
+            (MANIFEST, serde_json::to_string_pretty(&self.manifest)?),
+            (SCENARIO, scenario),
+            (EVENTS, serde_json::to_string_pretty(&self.events)?),
+            (SNAPSHOTS, serde_json::to_string_pretty(&self.snapshots)?),
+            (METRICS, self.metrics.to_json()?),
+        ];
+        for (name, contents) in files {
+            zip.start_file(name, options)?;
+            zip.write_all(contents.as_bytes())?;
+        }
+
+        zip.finish()?;
+        Ok(())
+    }
+
+    pub fn read_from<R: Read + Seek>(reader: R) -> Result<Self, BundleError> {
+        let mut zip = zip::ZipArchive::new(reader)?;
+        let mut read = |name: &str| -> Result<String, BundleError> {
+            let mut contents = String::new();
+            zip.by_name(name)?.read_to_string(&mut contents)?;
+            Ok(contents)
+        };
+
+        let manifest: BundleManifest = serde_json::from_str(&read(MANIFEST)?)?;
+        if manifest.format_version > BUNDLE_FORMAT_VERSION {
+            return Err(BundleError::Unsupported(manifest.format_version));
+        }
+
+        // Configs go through the scenario reader, so keys from other versions
+        // become warnings rather than errors
+        let scenario = Scenario::parse(&read(SCENARIO)?).map_err(BundleError::Scenario)?;
+        let (metrics, compatibility) =
+            MetricsExport::from_json(&read(METRICS)?).map_err(BundleError::Metrics)?;
+
+        let mut warnings = scenario.warnings;
+        if let Compatibility::Compatible { added, removed } = compatibility {
+            if !removed.is_empty() {
+                warnings.push(format!(
+                    "metrics not recorded by this version: {}",
+                    removed.join(", ")
+                ));
+            }
+            if !added.is_empty() {
+                warnings.push(format!(
+                    "metrics missing from the bundle: {}",
+                    added.join(", ")
+                ));
+            }
+        }
+
+        Ok(Self {
+            config: scenario.config,
+            events: serde_json::from_str(&read(EVENTS)?)?,
+            snapshots: serde_json::from_str(&read(SNAPSHOTS)?)?,
+            metrics,
+            manifest,
+            warnings,
+        })
+    }
+}
+
+// Writes the run so far, with the metrics recorded for it, to a bundle file
+pub fn export_bundle(
+    simulation: &Simulation,
+    metrics: &MetricsExport,
+    path: impl AsRef<Path>,
+) -> Result<(), BundleError> {
+    let file = std::fs::File::create(path)?;
+    RunBundle::capture(simulation, metrics).write_to(file)
+}
+
+pub fn import_bundle(path: impl AsRef<Path>) -> Result<RunBundle, BundleError> {
+    let file = std::fs::File::open(path)?;
+    RunBundle::read_from(file)
+}
+
+// Bundles the run so far into `dir` as a checkpoint named for the current
+// step. It is written to a temporary file and renamed into place, so a run
+// killed mid-write leaves the previous checkpoint as the latest
+pub fn write_checkpoint(
+    simulation: &Simulation,
+    metrics: &MetricsExport,
+    dir: impl AsRef<Path>,
+) -> Result<PathBuf, BundleError> {
+    let dir = dir.as_ref();
+    std::fs::create_dir_all(dir)?;
+
+    let name = format!(
+        "{CHECKPOINT_PREFIX}{:010}.{BUNDLE_EXTENSION}",
+        simulation.step_count
+    );
+    let path = dir.join(&name);
+    let partial = dir.join(format!("{name}.partial"));
+    export_bundle(simulation, metrics, &partial)?;
+    std::fs::rename(&partial, &path)?;
+    Ok(path)
+}
+
+// The checkpoint in `dir` taken at the latest step, if there are any
+pub fn latest_checkpoint(dir: impl AsRef<Path>) -> Result<Option<PathBuf>, BundleError> {
+    let mut latest: Option<(u64, PathBuf)> = None;
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let step = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix(CHECKPOINT_PREFIX))
+            .and_then(|name| name.strip_suffix(BUNDLE_EXTENSION))
+            .and_then(|name| name.strip_suffix('.'))
+            .and_then(|step| step.parse::<u64>().ok());
+        if let Some(step) = step {
+            if latest.as_ref().map_or(true, |(best, _)| step > *best) {
+                latest = Some((step, path));
+            }
+        }
+    }
+    Ok(latest.map(|(_, path)| path))
+}
@@ -0,0 +1,206 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::Intervention;
+use crate::worker::SimulationSummary;
+use crate::Simulation;
+
+// However the transport numbers its connections, such as one per WebSocket
+pub type ClientId = u64;
+
+// What a student's client sends the host. Like the worker protocol these go
+// as JSON, one message per text frame, so any socket transport can carry them.
+// `sandbox classroom` hosts a class over TCP with one message per line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum StudentMessage {
+    Join {
+        name: String,
+    },
+    // Proposes an intervention for the host to approve, with a note saying
+    // why. Only post injections and ranking weights can be proposed
+    Submit {
+        intervention: Intervention,
+        #[serde(default)]
+        note: String,
+    },
+}
+
+impl StudentMessage {
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+// What the host sends a student's client
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum HostMessage {
+    Joined,
+    // The submission is waiting for the host, under this id
+    Received(usize),
+    // The submission will be applied at the start of `step`
+    Approved { submission: usize, step: u64 },
+    Rejected(usize),
+    // A message that couldn't be acted on, and why
+    Error(String),
+    // How the simulation is doing, sent to everyone whenever the host likes
+    Summary(SimulationSummary),
+}
+
+impl HostMessage {
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Submission {
+    pub id: usize,
+    pub client: ClientId,
+    pub student: String,
+    pub intervention: Intervention,
+    pub note: String,
+    // The simulation step it arrived on
+    pub step: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    // Applied at the start of this step
+    Approved(u64),
+    Rejected,
+}
+
+// The host's side of a class: students come and go, and their submissions
+// wait in `pending` until the host approves or rejects them. Replies collect
+// in the outbox for the transport to deliver
+#[derive(Debug, Clone, Default)]
+pub struct Classroom {
+    // Names by client, of everyone who has joined and not left
+    pub students: BTreeMap<ClientId, String>,
+    pub pending: Vec<Submission>,
+    // Every decided submission, in the order it was decided
+    pub decided: Vec<(Submission, Verdict)>,
+    outbox: Vec<(ClientId, HostMessage)>,
+    next_submission: usize,
+}
+
+impl Classroom {
+    // Acts on a message from `client`, arriving while the simulation is at
+    // `step`
+    pub fn handle(&mut self, client: ClientId, message: StudentMessage, step: u64) {
+        match message {
+            StudentMessage::Join { name } => {
+                self.students.insert(client, name);
+                self.send(client, HostMessage::Joined);
+            }
+            StudentMessage::Submit { intervention, note } => {
+                let Some(student) = self.students.get(&client) else {
+                    return self.send(client, HostMessage::Error("join the class first".into()));
+                };
+                if !matches!(
+                    intervention,
+                    Intervention::InjectPost { .. } | Intervention::SetWeight { .. }
+                ) {
+                    return self.send(
+                        client,
+                        HostMessage::Error(
+                            "students can only inject posts or suggest ranking weights".into(),
+                        ),
+                    );
+                }
+
+                let id = self.next_submission;
+                self.next_submission += 1;
+                self.pending.push(Submission {
+                    id,
+                    client,
+                    student: student.clone(),
+                    intervention,
+                    note,
+                    step,
+                });
+                self.send(client, HostMessage::Received(id));
+            }
+        }
+    }
+
+    // Handles a text frame as it came off the wire
+    pub fn handle_json(&mut self, client: ClientId, json: &str, step: u64) {
+        match StudentMessage::from_json(json) {
+            Ok(message) => self.handle(client, message, step),
+            Err(e) => self.send(client, HostMessage::Error(format!("invalid message: {e}"))),
+        }
+    }
+
+    // Forgets a student whose connection closed. Their pending submissions
+    // stay for the host to decide on
+    pub fn leave(&mut self, client: ClientId) {
+        self.students.remove(&client);
+    }
+
+    // Schedules the submission into the simulation for its next step. False
+    // if there's no such pending submission
+    pub fn approve(&mut self, id: usize, simulation: &mut Simulation) -> bool {
+        let step = simulation.step_count;
+        let Some(submission) = self.take_pending(id) else {
+            return false;
+        };
+        simulation.schedule_intervention(step, submission.intervention.clone());
+        self.decide(submission, Verdict::Approved(step));
+        true
+    }
+
+    pub fn reject(&mut self, id: usize) -> bool {
+        let Some(submission) = self.take_pending(id) else {
+            return false;
+        };
+        self.decide(submission, Verdict::Rejected);
+        true
+    }
+
+    // Queues a summary for every student
+    pub fn broadcast(&mut self, summary: &SimulationSummary) {
+        let clients: Vec<ClientId> = self.students.keys().copied().collect();
+        for client in clients {
+            self.send(client, HostMessage::Summary(summary.clone()));
+        }
+    }
+
+    // Replies waiting to go out, oldest first
+    pub fn drain_outbox(&mut self) -> Vec<(ClientId, HostMessage)> {
+        std::mem::take(&mut self.outbox)
+    }
+
+    fn take_pending(&mut self, id: usize) -> Option<Submission> {
+        let index = self.pending.iter().position(|pending| pending.id == id)?;
+        Some(self.pending.remove(index))
+    }
+
+    fn decide(&mut self, submission: Submission, verdict: Verdict) {
+        let reply = match verdict {
+            Verdict::Approved(step) => HostMessage::Approved {
+                submission: submission.id,
+                step,
+            },
+            Verdict::Rejected => HostMessage::Rejected(submission.id),
+        };
+        // Students who have left don't hear back
+        if self.students.contains_key(&submission.client) {
+            self.send(submission.client, reply);
+        }
+        self.decided.push((submission, verdict));
+    }
+
+    fn send(&mut self, client: ClientId, message: HostMessage) {
+        self.outbox.push((client, message));
+    }
+}
@@ -0,0 +1,49 @@
+use std::collections::BTreeMap;
+
+use crate::models::{AgentId, Post};
+
+// Counts what has gone into a feed so far, so that no creator or tag takes up
+// more than its share of it. A limit of 0 means no limit
+#[derive(Debug, Default)]
+pub struct FeedCaps {
+    max_per_creator: usize,
+    max_per_tag: usize,
+    creators: BTreeMap<AgentId, usize>,
+    tags: BTreeMap<String, usize>,
+}
+
+impl FeedCaps {
+    pub fn new(max_per_creator: usize, max_per_tag: usize) -> Self {
+        Self {
+            max_per_creator,
+            max_per_tag,
+            ..Self::default()
+        }
+    }
+
+    // Counts the post towards the caps if it fits under all of them
+    pub fn admit(&mut self, post: &Post) -> bool {
+        let tags = post.interest_profile.interests.keys();
+
+        let creator_full = self.max_per_creator > 0
+            && self.creators.get(&post.creator_id).copied().unwrap_or(0) >= self.max_per_creator;
+        let tag_full = self.max_per_tag > 0
+            && tags
+                .clone()
+                .any(|tag| self.tags.get(tag).copied().unwrap_or(0) >= self.max_per_tag);
+
+        if creator_full || tag_full {
+            return false;
+        }
+
+        if self.max_per_creator > 0 {
+            *self.creators.entry(post.creator_id).or_insert(0) += 1;
+        }
+        if self.max_per_tag > 0 {
+            for tag in tags {
+                *self.tags.entry(tag.clone()).or_insert(0) += 1;
+            }
+        }
+        true
+    }
+}
@@ -0,0 +1,289 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use nalgebra::DVector;
+
+use super::RecommendationEngine;
+use crate::models::{CascadeAction, CommentId, InterestProfile, PostId};
+
+// An invariant the engine's state breaks. Only the tag indices, content
+// vectors and per-post maps are derived, so those are all `rebuild_indices`
+// can repair; the rest mean content itself is wrong
+#[derive(Debug, Clone, PartialEq)]
+pub enum Inconsistency {
+    DuplicatePost(PostId),
+    DuplicateComment(CommentId),
+    // The two tag maps disagree about a tag or index
+    TagIndexMismatch(String),
+    // Content is tagged with a tag that has no index, so it's never scored
+    UnregisteredTag(String),
+    // A tag's index lies past the vector dimension, so it's never scored
+    TagIndexOutOfRange {
+        tag: String,
+        index: usize,
+    },
+    // A post's vector, or one of its comments', is the wrong length or
+    // wasn't built from its current interests
+    StaleVector {
+        post_id: PostId,
+        comment_id: Option<CommentId>,
+    },
+    // A comment recorded in the post's cascade that isn't on the post
+    MissingComment {
+        post_id: PostId,
+        comment_id: CommentId,
+    },
+    // Breaking, promotion, lock, rejection or sponsorship state kept for a
+    // post that isn't in the pool
+    OrphanedPostState(PostId),
+    // A reshare and the post it shares don't both link to each other
+    BrokenReshare {
+        post_id: PostId,
+        reshare: PostId,
+    },
+}
+
+impl fmt::Display for Inconsistency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Inconsistency::DuplicatePost(post_id) => {
+                write!(f, "post {post_id} is in the pool twice")
+            }
+            Inconsistency::DuplicateComment(comment_id) => {
+                write!(f, "comment {comment_id} appears more than once")
+            }
+            Inconsistency::TagIndexMismatch(tag) => {
+                write!(f, "the tag indices disagree about `{tag}`")
+            }
+            Inconsistency::UnregisteredTag(tag) => {
+                write!(f, "content is tagged `{tag}`, which has no index")
+            }
+            Inconsistency::TagIndexOutOfRange { tag, index } => {
+                write!(
+                    f,
+                    "tag `{tag}` has index {index}, past the vector dimension"
+                )
+            }
+            Inconsistency::StaleVector {
+                post_id,
+                comment_id: None,
+            } => write!(f, "post {post_id} has a stale vector"),
+            Inconsistency::StaleVector {
+                post_id,
+                comment_id: Some(comment_id),
+            } => write!(
+                f,
+                "comment {comment_id} on post {post_id} has a stale vector"
+            ),
+            Inconsistency::MissingComment {
+                post_id,
+                comment_id,
+            } => write!(
+                f,
+                "post {post_id} recorded comment {comment_id}, which it doesn't have"
+            ),
+            Inconsistency::OrphanedPostState(post_id) => {
+                write!(
+                    f,
+                    "state is kept for post {post_id}, which isn't in the pool"
+                )
+            }
+            Inconsistency::BrokenReshare { post_id, reshare } => write!(
+                f,
+                "post {post_id} and its reshare {reshare} don't both link to each other"
+            ),
+        }
+    }
+}
+
+impl RecommendationEngine {
+    // Checks every invariant, returning what's broken in a stable order.
+    // Empty when the engine is consistent
+    pub fn verify(&self) -> Vec<Inconsistency> {
+        let mut found = Vec::new();
+
+        let mut posts = BTreeSet::new();
+        let mut comments = BTreeSet::new();
+        for post in &self.content_pool {
+            if !posts.insert(post.id) {
+                found.push(Inconsistency::DuplicatePost(post.id));
+            }
+            for comment in &post.comments {
+                if !comments.insert(comment.id) {
+                    found.push(Inconsistency::DuplicateComment(comment.id));
+                }
+            }
+        }
+
+        let mut mismatched = BTreeSet::new();
+        for (tag, index) in &self.tag_to_index {
+            if self.index_to_tag.get(index) != Some(tag) {
+                mismatched.insert(tag);
+            }
+            if *index >= self.vector_dimension {
+                found.push(Inconsistency::TagIndexOutOfRange {
+                    tag: tag.clone(),
+                    index: *index,
+                });
+            }
+        }
+        for (index, tag) in &self.index_to_tag {
+            if self.tag_to_index.get(tag) != Some(index) {
+                mismatched.insert(tag);
+            }
+        }
+        found.extend(
+            mismatched
+                .into_iter()
+                .map(|tag| Inconsistency::TagIndexMismatch(tag.clone())),
+        );
+
+        let unregistered: BTreeSet<&String> = self
+            .profiles()
+            .flat_map(|(_, _, profile)| profile.interests.keys())
+            .filter(|tag| !self.tag_to_index.contains_key(*tag))
+            .collect();
+        found.extend(
+            unregistered
+                .into_iter()
+                .map(|tag| Inconsistency::UnregisteredTag(tag.clone())),
+        );
+
+        for (post_id, comment_id, profile) in self.profiles() {
+            if profile.vector_representation != self.expected_vector(profile) {
+                found.push(Inconsistency::StaleVector {
+                    post_id,
+                    comment_id,
+                });
+            }
+        }
+
+        for post in &self.content_pool {
+            let recorded = post.cascade.iter().filter_map(|event| match event.action {
+                CascadeAction::Commented(comment_id) => Some(comment_id),
+                _ => None,
+            });
+            for comment_id in recorded {
+                if !post.comments.iter().any(|comment| comment.id == comment_id) {
+                    found.push(Inconsistency::MissingComment {
+                        post_id: post.id,
+                        comment_id,
+                    });
+                }
+            }
+        }
+
+        let links: BTreeSet<(PostId, PostId)> = self
+            .content_pool
+            .iter()
+            .flat_map(|post| post.reshares.iter().map(move |reshare| (post.id, *reshare)))
+            .collect();
+        let shared: BTreeSet<(PostId, PostId)> = self
+            .content_pool
+            .iter()
+            .filter_map(|post| Some((post.reshare_of?, post.id)))
+            .collect();
+        found.extend(
+            links
+                .symmetric_difference(&shared)
+                .map(|&(post_id, reshare)| Inconsistency::BrokenReshare { post_id, reshare }),
+        );
+
+        let kept: BTreeSet<PostId> = self
+            .breaking
+            .keys()
+            .chain(self.promotions.keys())
+            .chain(self.comment_locks.keys())
+            .chain(self.rejected_comments.keys())
+            .chain(self.sponsored_exposures.keys())
+            .chain(&self.sponsored)
+            .copied()
+            .collect();
+        found.extend(
+            kept.into_iter()
+                .filter(|post_id| !posts.contains(post_id))
+                .map(Inconsistency::OrphanedPostState),
+        );
+
+        found
+    }
+
+    // Repairs the derived structures: missing or mismatched tag indices are
+    // reassigned, keeping every index already consistent so agents' vectors
+    // stay valid, content vectors are rebuilt, and state kept for posts no
+    // longer in the pool is dropped. Returns what's still broken
+    pub fn rebuild_indices(&mut self) -> Vec<Inconsistency> {
+        let mut tags: BTreeMap<String, usize> = self
+            .tag_to_index
+            .iter()
+            .filter(|(tag, index)| self.index_to_tag.get(index) == Some(tag))
+            .map(|(tag, index)| (tag.clone(), *index))
+            .collect();
+        let known: Vec<String> = self
+            .tag_to_index
+            .keys()
+            .chain(self.index_to_tag.values())
+            .chain(
+                self.profiles()
+                    .flat_map(|(_, _, profile)| profile.interests.keys()),
+            )
+            .cloned()
+            .collect();
+        for tag in known {
+            if !tags.contains_key(&tag) {
+                let index = tags.values().max().map_or(0, |index| index + 1);
+                tags.insert(tag, index);
+            }
+        }
+        self.index_to_tag = tags
+            .iter()
+            .map(|(tag, index)| (*index, tag.clone()))
+            .collect();
+        self.tag_to_index = tags;
+
+        let (tag_to_index, similarity) = (&self.tag_to_index, &self.tag_similarity);
+        let dimension = self.vector_dimension;
+        for post in &mut self.content_pool {
+            let comments = post
+                .comments
+                .iter_mut()
+                .map(|comment| &mut comment.interest_profile);
+            for profile in std::iter::once(&mut post.interest_profile).chain(comments) {
+                profile.vector_representation = DVector::zeros(dimension);
+                profile.build_vector(tag_to_index, similarity);
+            }
+        }
+
+        let pool: BTreeSet<PostId> = self.content_pool.iter().map(|post| post.id).collect();
+        self.breaking.retain(|post_id, _| pool.contains(post_id));
+        self.promotions.retain(|post_id, _| pool.contains(post_id));
+        self.comment_locks
+            .retain(|post_id, _| pool.contains(post_id));
+        self.rejected_comments
+            .retain(|post_id, _| pool.contains(post_id));
+        self.sponsored_exposures
+            .retain(|post_id, _| pool.contains(post_id));
+        self.sponsored.retain(|post_id| pool.contains(post_id));
+
+        self.verify()
+    }
+
+    // Every post's and comment's profile, with where it belongs
+    fn profiles(&self) -> impl Iterator<Item = (PostId, Option<CommentId>, &InterestProfile)> {
+        self.content_pool.iter().flat_map(|post| {
+            std::iter::once((post.id, None, &post.interest_profile)).chain(
+                post.comments
+                    .iter()
+                    .map(move |comment| (post.id, Some(comment.id), &comment.interest_profile)),
+            )
+        })
+    }
+
+    // The vector a profile would have if it were built afresh
+    fn expected_vector(&self, profile: &InterestProfile) -> DVector<f32> {
+        let mut expected = profile.clone();
+        expected.vector_representation = DVector::zeros(self.vector_dimension);
+        expected.build_vector(&self.tag_to_index, &self.tag_similarity);
+        expected.vector_representation
+    }
+}
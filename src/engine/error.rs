@@ -0,0 +1,38 @@
+use std::fmt;
+
+use crate::models::{CommentId, PostId};
+
+// Why the engine could not apply a mutation. These are recoverable: content
+// may legitimately disappear between an agent reading it and acting on it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngineError {
+    PostNotFound(PostId),
+    CommentNotFound {
+        post_id: PostId,
+        comment_id: CommentId,
+    },
+    // The post exists but has stopped taking new comments
+    CommentsLocked(PostId),
+    // The post has taken as many comments this step as the rate limit allows
+    RateLimited(PostId),
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineError::PostNotFound(post_id) => write!(f, "post {post_id} is not in the pool"),
+            EngineError::CommentNotFound {
+                post_id,
+                comment_id,
+            } => write!(f, "comment {comment_id} is not on post {post_id}"),
+            EngineError::CommentsLocked(post_id) => {
+                write!(f, "post {post_id} is locked to new comments")
+            }
+            EngineError::RateLimited(post_id) => {
+                write!(f, "post {post_id} has taken its comments for this step")
+            }
+        }
+    }
+}
+
+impl std::error::Error for EngineError {}
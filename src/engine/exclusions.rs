@@ -0,0 +1,31 @@
+use std::collections::BTreeSet;
+
+use crate::models::{AgentId, Individual};
+
+// Creators whose posts a recommendation request leaves out, on top of any the
+// engine has banned outright
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CreatorExclusions {
+    pub creators: BTreeSet<AgentId>,
+}
+
+impl CreatorExclusions {
+    // Excludes nothing, so agents may be shown their own posts
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    // The usual exclusions for an agent: its own posts
+    pub fn for_agent(agent: &Individual) -> Self {
+        Self::none().with(agent.core.id)
+    }
+
+    pub fn with(mut self, creator_id: AgentId) -> Self {
+        self.creators.insert(creator_id);
+        self
+    }
+
+    pub fn excludes(&self, creator_id: &AgentId) -> bool {
+        self.creators.contains(creator_id)
+    }
+}
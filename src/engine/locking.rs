@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::iter;
+
+use crate::models::Post;
+
+// Why a post stopped taking new comments
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum LockReason {
+    // By hand, through an intervention
+    Manual,
+    // Along with banning the post's creator
+    Moderation,
+    // Automatically, once the thread grew too heated
+    Conflict,
+}
+
+impl LockReason {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LockReason::Manual => "manual",
+            LockReason::Moderation => "moderation",
+            LockReason::Conflict => "conflict",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CommentLock {
+    // The step the post was locked on
+    pub step: u64,
+    pub reason: LockReason,
+}
+
+// How heated a thread is: the widest spread of agreement on any one tag
+// across the post and its comments, from 0 when everyone agrees up to 2
+pub fn thread_conflict(post: &Post) -> f32 {
+    let mut ranges: BTreeMap<&str, (f32, f32)> = BTreeMap::new();
+    let profiles = iter::once(&post.interest_profile).chain(
+        post.comments
+            .iter()
+            .map(|comment| &comment.interest_profile),
+    );
+    for profile in profiles {
+        for (tag, topic) in &profile.interests {
+            let (low, high) = ranges
+                .entry(tag)
+                .or_insert((topic.agreement, topic.agreement));
+            *low = low.min(topic.agreement);
+            *high = high.max(topic.agreement);
+        }
+    }
+    ranges
+        .values()
+        .map(|(low, high)| high - low)
+        .fold(0.0, f32::max)
+}
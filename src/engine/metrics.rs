@@ -0,0 +1,364 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write;
+
+use crate::models::{AgentId, AgentState, AgentView, Post};
+
+// Ticks kept in each series, oldest dropped first
+pub const MAX_TICKS: usize = 1000;
+
+// Aggregates recorded over the whole run every tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum TickAggregate {
+    PostsCreated,
+    CommentsCreated,
+    // Agents in any state but offline
+    OnlineAgents,
+    // Mean engagement score over every post in the pool
+    MeanEngagement,
+    // Mean over agents of the entropy of their interests, in bits
+    MeanInterestEntropy,
+    // How much the population's interest is concentrated on a few tags, as
+    // the sum of the squares of each tag's share: 1.0 when everyone is only
+    // interested in one tag, 1 / tags when interest is spread evenly
+    TopicConcentration,
+}
+
+impl TickAggregate {
+    pub const ALL: [TickAggregate; 6] = [
+        TickAggregate::PostsCreated,
+        TickAggregate::CommentsCreated,
+        TickAggregate::OnlineAgents,
+        TickAggregate::MeanEngagement,
+        TickAggregate::MeanInterestEntropy,
+        TickAggregate::TopicConcentration,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TickAggregate::PostsCreated => "posts created",
+            TickAggregate::CommentsCreated => "comments created",
+            TickAggregate::OnlineAgents => "online agents",
+            TickAggregate::MeanEngagement => "mean engagement",
+            TickAggregate::MeanInterestEntropy => "mean interest entropy",
+            TickAggregate::TopicConcentration => "topic concentration",
+        }
+    }
+}
+
+// A value for each of the last `MAX_TICKS` ticks, with the step it was taken
+// at
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TickSeries {
+    pub points: VecDeque<(u64, f32)>,
+}
+
+impl TickSeries {
+    pub fn push(&mut self, step: u64, value: f32) {
+        if self.points.len() == MAX_TICKS {
+            self.points.pop_front();
+        }
+        self.points.push_back((step, value));
+    }
+
+    pub fn latest(&self) -> Option<f32> {
+        self.points.back().map(|&(_, value)| value)
+    }
+
+    // The values in step order, for plotting
+    pub fn values(&self) -> Vec<f32> {
+        self.points.iter().map(|&(_, value)| value).collect()
+    }
+
+    // The points taken at or after `step`
+    pub fn since(&self, step: u64) -> impl Iterator<Item = (u64, f32)> + '_ {
+        self.points
+            .iter()
+            .copied()
+            .filter(move |&(at, _)| at >= step)
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+}
+
+// A series averaged over windows of `every` ticks, with a point at the end
+// of each window. Windows end on multiples of `every`, so every series
+// downsampled the same way lines up
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DownsampledSeries {
+    pub series: TickSeries,
+    sum: f32,
+    count: u64,
+}
+
+impl DownsampledSeries {
+    pub fn push(&mut self, every: u64, step: u64, value: f32) {
+        self.sum += value;
+        self.count += 1;
+        if step % every.max(1) == 0 {
+            self.series.push(step, self.sum / self.count as f32);
+            self.sum = 0.0;
+            self.count = 0;
+        }
+    }
+}
+
+// The collector's aggregates and topic popularity at one point per `every`
+// ticks, so the UI can keep up with long fast runs without drawing every
+// tick. Per-agent series aren't downsampled
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DownsampledStream {
+    pub every: u64,
+    pub aggregates: BTreeMap<TickAggregate, DownsampledSeries>,
+    pub topic_popularity: BTreeMap<String, DownsampledSeries>,
+}
+
+impl DownsampledStream {
+    // How far apart the points the UI draws should be at a speed, one per
+    // tick up to 1× and then the next power of ten above the multiplier
+    pub fn every_for(speed_multiplier: u32) -> u64 {
+        let mut every = 1;
+        while every < speed_multiplier as u64 {
+            every *= 10;
+        }
+        every
+    }
+
+    pub fn series(&self, aggregate: TickAggregate) -> Option<&TickSeries> {
+        self.aggregates.get(&aggregate).map(|points| &points.series)
+    }
+
+    pub fn topic(&self, tag: &str) -> Option<&TickSeries> {
+        self.topic_popularity.get(tag).map(|points| &points.series)
+    }
+
+    fn push_aggregate(&mut self, aggregate: TickAggregate, step: u64, value: f32) {
+        self.aggregates
+            .entry(aggregate)
+            .or_default()
+            .push(self.every, step, value);
+    }
+
+    fn push_topic(&mut self, tag: &str, step: u64, value: f32) {
+        self.topic_popularity
+            .entry(tag.to_string())
+            .or_default()
+            .push(self.every, step, value);
+    }
+
+    fn len(&self) -> usize {
+        self.aggregates
+            .values()
+            .chain(self.topic_popularity.values())
+            .map(|points| points.series.len())
+            .sum()
+    }
+}
+
+// Per-tick aggregates over the run, for watching how the simulation moves
+// rather than where it has got to. Posts and comments created are counted
+// from the growth of the pool since the previous tick. Every tick is kept for
+// export, and downsampled streams are fed alongside for whoever subscribes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MetricsCollector {
+    pub aggregates: BTreeMap<TickAggregate, TickSeries>,
+    pub interest_entropy: BTreeMap<AgentId, TickSeries>,
+    // Each tag's share of the population's interest
+    pub topic_popularity: BTreeMap<String, TickSeries>,
+    // Downsampled streams subscribed to, by how many ticks each point covers
+    pub streams: BTreeMap<u64, DownsampledStream>,
+    posts_seen: usize,
+    comments_seen: usize,
+}
+
+impl MetricsCollector {
+    pub fn record<'a>(
+        &mut self,
+        step: u64,
+        pool: &[Post],
+        agents: impl IntoIterator<Item = AgentView<'a>>,
+    ) {
+        let posts = pool.len();
+        let comments: usize = pool.iter().map(|post| post.comments.len()).sum();
+        let engagement: f32 = pool.iter().map(|post| post.engagement_score).sum();
+
+        let mut entropy_total = 0.0;
+        let mut agent_count = 0;
+        let mut online = 0;
+        let mut tag_weights: BTreeMap<&str, f32> = BTreeMap::new();
+        for agent in agents {
+            let profile = agent.interest_profile;
+            let entropy = profile.entropy();
+            if !matches!(agent.state, AgentState::Offline) {
+                online += 1;
+            }
+            self.interest_entropy
+                .entry(agent.id)
+                .or_default()
+                .push(step, entropy);
+            entropy_total += entropy;
+            agent_count += 1;
+            for (tag, weight) in profile.normalised_weights() {
+                *tag_weights.entry(tag.as_str()).or_default() += weight;
+            }
+        }
+        let total_weight: f32 = tag_weights.values().sum();
+        let mut concentration = 0.0;
+        for (tag, weight) in tag_weights {
+            let share = if total_weight > 0.0 {
+                weight / total_weight
+            } else {
+                0.0
+            };
+            concentration += share * share;
+            self.topic_popularity
+                .entry(tag.to_string())
+                .or_default()
+                .push(step, share);
+            for stream in self.streams.values_mut() {
+                stream.push_topic(tag, step, share);
+            }
+        }
+
+        for (aggregate, value) in [
+            (
+                TickAggregate::PostsCreated,
+                posts.saturating_sub(self.posts_seen) as f32,
+            ),
+            (
+                TickAggregate::CommentsCreated,
+                comments.saturating_sub(self.comments_seen) as f32,
+            ),
+            (TickAggregate::OnlineAgents, online as f32),
+            (
+                TickAggregate::MeanEngagement,
+                engagement / posts.max(1) as f32,
+            ),
+            (
+                TickAggregate::MeanInterestEntropy,
+                entropy_total / agent_count.max(1) as f32,
+            ),
+            (TickAggregate::TopicConcentration, concentration),
+        ] {
+            self.aggregates
+                .entry(aggregate)
+                .or_default()
+                .push(step, value);
+            for stream in self.streams.values_mut() {
+                stream.push_aggregate(aggregate, step, value);
+            }
+        }
+        self.posts_seen = posts;
+        self.comments_seen = comments;
+    }
+
+    // Starts keeping a stream with a point per `every` ticks, filled in from
+    // the ticks already recorded. Subscribing again to the same stream keeps
+    // it as it is
+    pub fn subscribe(&mut self, every: u64) -> &DownsampledStream {
+        let every = every.max(1);
+        if !self.streams.contains_key(&every) {
+            let mut stream = DownsampledStream {
+                every,
+                ..DownsampledStream::default()
+            };
+            for (&aggregate, series) in &self.aggregates {
+                for &(step, value) in &series.points {
+                    stream.push_aggregate(aggregate, step, value);
+                }
+            }
+            for (tag, series) in &self.topic_popularity {
+                for &(step, value) in &series.points {
+                    stream.push_topic(tag, step, value);
+                }
+            }
+            self.streams.insert(every, stream);
+        }
+        &self.streams[&every]
+    }
+
+    pub fn unsubscribe(&mut self, every: u64) {
+        self.streams.remove(&every);
+    }
+
+    pub fn stream(&self, every: u64) -> Option<&DownsampledStream> {
+        self.streams.get(&every)
+    }
+
+    // The aggregate at a point per `every` ticks, from the stream if one is
+    // subscribed to and at full resolution otherwise
+    pub fn series_every(&self, aggregate: TickAggregate, every: u64) -> Option<&TickSeries> {
+        match self.stream(every) {
+            Some(stream) => stream.series(aggregate),
+            None => self.series(aggregate),
+        }
+    }
+
+    pub fn topic_every(&self, tag: &str, every: u64) -> Option<&TickSeries> {
+        match self.stream(every) {
+            Some(stream) => stream.topic(tag),
+            None => self.topic(tag),
+        }
+    }
+
+    pub fn series(&self, aggregate: TickAggregate) -> Option<&TickSeries> {
+        self.aggregates.get(&aggregate)
+    }
+
+    pub fn agent_entropy(&self, agent_id: AgentId) -> Option<&TickSeries> {
+        self.interest_entropy.get(&agent_id)
+    }
+
+    pub fn topic(&self, tag: &str) -> Option<&TickSeries> {
+        self.topic_popularity.get(tag)
+    }
+
+    // Points kept across every series, for memory reporting
+    pub fn len(&self) -> usize {
+        self.aggregates
+            .values()
+            .chain(self.interest_entropy.values())
+            .chain(self.topic_popularity.values())
+            .map(TickSeries::len)
+            .sum::<usize>()
+            + self
+                .streams
+                .values()
+                .map(DownsampledStream::len)
+                .sum::<usize>()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // One row per point at full resolution, in long format, with the agent ID and tag left
+    // blank for series that aren't per agent or per tag
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("step,series,agent_id,tag,value\n");
+
+        for (aggregate, series) in &self.aggregates {
+            for (step, value) in &series.points {
+                writeln!(csv, "{step},{},,,{value}", aggregate.name()).unwrap();
+            }
+        }
+        for (agent_id, series) in &self.interest_entropy {
+            for (step, value) in &series.points {
+                writeln!(csv, "{step},interest entropy,{agent_id},,{value}").unwrap();
+            }
+        }
+        for (tag, series) in &self.topic_popularity {
+            for (step, value) in &series.points {
+                writeln!(csv, "{step},topic popularity,,{tag},{value}").unwrap();
+            }
+        }
+
+        csv
+    }
+}
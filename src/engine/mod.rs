@@ -1,3 +1,24 @@
+mod caps;
+mod consistency;
+mod error;
+mod exclusions;
+mod locking;
+pub mod metrics;
+pub mod offline_eval;
+mod ops;
+mod promotion;
 mod recommendation;
+mod reranking;
+mod retrieval;
+mod telemetry;
 
-pub use recommendation::RecommendationEngine;
+pub use consistency::Inconsistency;
+pub use error::EngineError;
+pub use exclusions::CreatorExclusions;
+pub use locking::{thread_conflict, CommentLock, LockReason};
+pub use ops::{EngineOp, OpQueue, QueuedOp};
+pub use promotion::{Promotion, PromotionKind};
+pub use recommendation::{Backfill, RankingMode, RecommendationEngine};
+pub use reranking::{CreatorQuota, ExposureQuotas, Mmr, RerankContext, Reranker, ScoredPost};
+pub use retrieval::{RetrievalConfig, RetrievalSource};
+pub use telemetry::{ScoreComponents, ScoreSnapshot, ScoreTelemetry};
@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+use super::{RecommendationEngine, ScoreComponents};
+use crate::models::{AgentId, PostId};
+
+// One post that was scored for a slate, whether or not it was served
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LoggedCandidate {
+    pub post_id: PostId,
+    // The score the engine ranked by, after the agent's feed settings
+    pub score: f32,
+    pub components: ScoreComponents,
+}
+
+// Everything the engine considered for one feed, and what it served
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoggedSlate {
+    pub step: u64,
+    pub agent_id: AgentId,
+    pub candidates: Vec<LoggedCandidate>,
+    pub served: Vec<PostId>,
+}
+
+// The most recent slates the engine served, for evaluating other rankers
+// against. Logging every candidate is expensive, so a capacity of 0 turns it
+// off
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExposureLog {
+    pub capacity: usize,
+    slates: RefCell<VecDeque<LoggedSlate>>,
+}
+
+impl ExposureLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            slates: RefCell::default(),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    pub fn record(&self, slate: LoggedSlate) {
+        if !self.enabled() {
+            return;
+        }
+
+        let mut slates = self.slates.borrow_mut();
+        if slates.len() >= self.capacity {
+            slates.pop_front();
+        }
+        slates.push_back(slate);
+    }
+
+    // The logged slates, oldest first
+    pub fn slates(&self) -> Vec<LoggedSlate> {
+        self.slates.borrow().iter().cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.slates.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// An alternative ranking to evaluate: a score for each logged candidate
+pub trait Ranker {
+    fn score(&self, candidate: &LoggedCandidate) -> f32;
+}
+
+impl<F: Fn(&LoggedCandidate) -> f32> Ranker for F {
+    fn score(&self, candidate: &LoggedCandidate) -> f32 {
+        self(candidate)
+    }
+}
+
+// The engine's own scoring with each term's weight multiplied by a factor,
+// e.g. `recency: 0.0` to ask what feeds would have been without recency
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reweighted {
+    pub interest: f32,
+    pub recency: f32,
+    pub engagement: f32,
+}
+
+impl Ranker for Reweighted {
+    fn score(&self, candidate: &LoggedCandidate) -> f32 {
+        let components = &candidate.components;
+        components.interest * self.interest
+            + components.recency * self.recency
+            + components.engagement * self.engagement
+    }
+}
+
+// How a ranker would have done on the logged slates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Evaluation {
+    pub slates: usize,
+    // Mean reward of the posts that were actually served
+    pub logged_value: f32,
+    // Mean reward of the ranker's picks, over those that were also served and
+    // so have an observed reward
+    pub estimated_value: f32,
+    // Share of the ranker's picks that were also served. The estimate is only
+    // as trustworthy as this is high
+    pub coverage: f32,
+}
+
+// Replays the log against `ranker`: each slate is re-ranked from its logged
+// candidates, and the ranker is credited with the rewards of the picks it
+// shares with what was served
+pub fn replay(
+    slates: &[LoggedSlate],
+    ranker: &impl Ranker,
+    reward: impl Fn(&LoggedSlate, PostId) -> f32,
+) -> Evaluation {
+    let (mut logged_total, mut logged_count) = (0.0, 0);
+    let (mut matched_total, mut matched_count, mut picks) = (0.0, 0, 0);
+
+    for slate in slates {
+        for post_id in &slate.served {
+            logged_total += reward(slate, *post_id);
+            logged_count += 1;
+        }
+
+        let mut reranked: Vec<(PostId, f32)> = slate
+            .candidates
+            .iter()
+            .map(|candidate| (candidate.post_id, ranker.score(candidate)))
+            .collect();
+        reranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        for (post_id, _) in reranked.iter().take(slate.served.len()) {
+            picks += 1;
+            if slate.served.contains(post_id) {
+                matched_total += reward(slate, *post_id);
+                matched_count += 1;
+            }
+        }
+    }
+
+    let mean = |total: f32, count: usize| {
+        if count == 0 {
+            0.0
+        } else {
+            total / count as f32
+        }
+    };
+
+    Evaluation {
+        slates: slates.len(),
+        logged_value: mean(logged_total, logged_count),
+        estimated_value: mean(matched_total, matched_count),
+        coverage: mean(matched_count as f32, picks),
+    }
+}
+
+// 1.0 if the agent the slate was served to went on to read the post
+pub fn read_reward(engine: &RecommendationEngine) -> impl Fn(&LoggedSlate, PostId) -> f32 + '_ {
+    |slate, post_id| {
+        let read = engine
+            .get_content_by_id(post_id)
+            .is_some_and(|post| post.readers.contains(&slate.agent_id));
+        if read {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use crate::models::{
+    content::{Comment, Post},
+    AgentId, CommentId, PostId,
+};
+use serde::{Deserialize, Serialize};
+
+// Typed mutations agents request of the engine during their tick. Agents queue
+// these rather than mutating the engine directly, and the simulation applies
+// them in order once every agent has finished ticking, so that all agents in a
+// step read the same engine state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum EngineOp {
+    PublishPost(Box<Post>),
+    AddComment {
+        post_id: PostId,
+        comment: Comment,
+    },
+    RecordPostRead {
+        post_id: PostId,
+        reader_id: AgentId,
+    },
+    RecordCommentRead {
+        post_id: PostId,
+        comment_id: CommentId,
+    },
+    // Promoted exposures are accounted to the post's promotion rather than
+    // its cascade
+    RecordExposure {
+        post_id: PostId,
+        agent_id: AgentId,
+        promoted: bool,
+    },
+    // Exposures through a sponsored slot are only counted against the post
+    RecordSponsoredExposure {
+        post_id: PostId,
+    },
+    LikePost {
+        post_id: PostId,
+        agent_id: AgentId,
+    },
+}
+
+impl EngineOp {
+    // Whether the op only feeds ranking signals, rather than adding content
+    // that agents could go on to read
+    pub fn is_engagement(&self) -> bool {
+        match self {
+            EngineOp::PublishPost(_) | EngineOp::AddComment { .. } => false,
+            EngineOp::RecordPostRead { .. }
+            | EngineOp::RecordCommentRead { .. }
+            | EngineOp::RecordExposure { .. }
+            | EngineOp::RecordSponsoredExposure { .. }
+            | EngineOp::LikePost { .. } => true,
+        }
+    }
+}
+
+// An op waiting to be applied at the end of `step`, with the agent that
+// queued it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedOp {
+    pub step: u64,
+    pub agent_id: AgentId,
+    pub op: EngineOp,
+}
+
+// Ops waiting to be applied, so that the engine can see some changes later
+// than they happened
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OpQueue {
+    pub queued: Vec<QueuedOp>,
+}
+
+impl OpQueue {
+    pub fn push(&mut self, step: u64, agent_id: AgentId, op: EngineOp) {
+        self.queued.push(QueuedOp { step, agent_id, op });
+        // Stable, so ops due at the same step keep the order they were queued in
+        self.queued.sort_by_key(|queued| queued.step);
+    }
+
+    // Removes and returns everything due at or before `step`
+    pub fn take_due(&mut self, step: u64) -> Vec<QueuedOp> {
+        let due = self
+            .queued
+            .iter()
+            .take_while(|queued| queued.step <= step)
+            .count();
+        self.queued.drain(..due).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.queued.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queued.is_empty()
+    }
+}
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::models::AgentId;
+
+// How the platform promotes a post it wants seen, such as an official
+// announcement
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PromotionKind {
+    // Held at the top of every feed the post is eligible for
+    Pin,
+    // Always considered for ranking, with its score multiplied by this
+    Boost(f32),
+}
+
+// A post's promotion window, with its exposures counted here rather than in
+// the post's cascade so they never pass for organic reach
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Promotion {
+    pub kind: PromotionKind,
+    pub from_step: u64,
+    pub until_step: u64,
+    pub exposures: usize,
+    pub reached: BTreeSet<AgentId>,
+}
+
+impl Promotion {
+    pub fn new(kind: PromotionKind, from_step: u64, until_step: u64) -> Self {
+        Self {
+            kind,
+            from_step,
+            until_step,
+            exposures: 0,
+            reached: BTreeSet::new(),
+        }
+    }
+
+    pub fn is_active(&self, step: u64) -> bool {
+        (self.from_step..self.until_step).contains(&step)
+    }
+
+    pub fn reach(&self) -> usize {
+        self.reached.len()
+    }
+}
@@ -1,76 +1,429 @@
 use crate::models::content::Comment;
-use crate::models::Agent;
+use crate::models::{
+    Agent, AgentId, CascadeAction, CascadeEvent, CommentId, CommentIdList, InterestProfile, PostId,
+    PostIdList, RngStreams, StreamRng, TagMatrix,
+};
 use nalgebra::DVector;
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    caps::FeedCaps,
+    metrics::MetricsCollector,
+    offline_eval::{ExposureLog, LoggedCandidate, LoggedSlate},
+    retrieval::retrieve,
+    thread_conflict, CommentLock, CreatorExclusions, EngineError, EngineOp, LockReason, Promotion,
+    PromotionKind, RerankContext, Reranker, RetrievalConfig, ScoreComponents, ScoreTelemetry,
+    ScoredPost,
+};
 
 use crate::models::Individual;
 use crate::models::Post;
-use std::collections::HashMap;
+use crate::profiling::span;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecommendationEngine {
-    pub tag_to_index: HashMap<String, usize>,
-    pub index_to_tag: HashMap<usize, String>,
+    pub tag_to_index: BTreeMap<String, usize>,
+    pub index_to_tag: BTreeMap<usize, String>,
     pub content_pool: Vec<Post>,
     pub vector_dimension: usize,
+    pub tag_similarity: TagMatrix,
     pub config: RecommendationEngineConfig,
+
+    // The simulation step being run, for timestamping cascade events and
+    // newly published content
+    pub current_step: u64,
+
+    // Simulated seconds elapsed by the current step, run up a step at a time
+    // so that changing the seconds per step mid-run never turns the clock
+    // back behind posts already published
+    #[serde(default)]
+    pub clock: f64,
+
+    // Creators whose posts are never recommended
+    pub banned_creators: BTreeSet<AgentId>,
+
+    // Breaking posts and the step their window closes at
+    pub breaking: BTreeMap<PostId, u64>,
+
+    // Posts the platform has promoted, kept once their window closes so
+    // their promoted exposures can still be reported
+    pub promotions: BTreeMap<PostId, Promotion>,
+
+    // Posts closed to new comments, and comments turned away from each post
+    // by a lock or the rate limit
+    pub comment_locks: BTreeMap<PostId, CommentLock>,
+    pub rejected_comments: BTreeMap<PostId, usize>,
+
+    // Posts whose organisations won this step's sponsored slots, in slot
+    // order, and every post's sponsored exposures so far. Like promoted
+    // exposures these stay out of the post's cascade
+    pub sponsored: Vec<PostId>,
+    pub sponsored_exposures: BTreeMap<PostId, usize>,
+
+    // Master seed for randomness in ranking, so that it doesn't depend on
+    // which agents asked for recommendations first
+    pub seed: u64,
+
+    #[serde(skip)]
+    candidates: RefCell<Candidates>,
+
+    // Passes over each feed's ranked posts before it's filled, run in the
+    // order they were registered. Rerankers are code rather than state, so
+    // they aren't saved and need registering again after a load
+    #[serde(skip)]
+    pub rerankers: Vec<Box<dyn Reranker>>,
+
+    // Scores of the posts actually served, for seeing what drives ranking
+    pub telemetry: ScoreTelemetry,
+
+    // Every candidate scored for recent feeds, for off-policy evaluation
+    pub exposures: ExposureLog,
+
+    // Aggregates over the run recorded every tick
+    pub metrics: MetricsCollector,
+}
+
+// Pool indices of retrieved posts, then scored candidates as (pool index,
+// score) split by whether they reach the minimum score. Kept between requests
+// so each doesn't allocate its own
+#[derive(Debug, Clone, Default)]
+struct Candidates {
+    retrieved: Vec<usize>,
+    above: Vec<(usize, f32)>,
+    below: Vec<(usize, f32)>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecommendationEngineConfig {
     pub interest_weight: f32,
     pub recency_weight: f32,
     pub engagement_weight: f32,
     pub recency_decay_rate: f32,
+
+    // Posts scoring below this are only recommended as backfill
+    pub min_score: f32,
+    pub backfill: Backfill,
+
+    // Most posts a single feed may hold from one creator, or on one tag. 0
+    // leaves it uncapped
+    pub max_per_creator: usize,
+    pub max_per_tag: usize,
+
+    pub ranking: RankingMode,
+    // How far sampled feeds stray from the top scores; near zero is top-k,
+    // large values approach a uniform shuffle
+    pub temperature: f32,
+
+    // Which posts are considered for ranking at all
+    pub retrieval: RetrievalConfig,
+
+    // How much breaking posts' recency is multiplied by while their window is
+    // open, and how many slots at the top of each feed are kept for them
+    pub breaking_boost: f32,
+    pub breaking_slots: usize,
+
+    // How agents' feed settings are respected: scores are scaled by the
+    // penalty once per tag the agent asked to see fewer of, and raised by the
+    // boost for followed creators when the agent prioritises them
+    pub fewer_tag_penalty: f32,
+    pub followed_boost: f32,
+
+    // Added to the score of every post by a creator the agent follows,
+    // whatever its feed settings, so feeds lean towards the follow graph
+    pub following_weight: f32,
+
+    // Share of another segment's posts open to an agent's feed, 1 when the
+    // population isn't segmented
+    pub segment_leakage: f32,
+
+    // Simulated seconds that pass each step. Post ages, and so recency, run
+    // on this clock rather than the wall clock, so they don't depend on how
+    // fast the simulation happens to be stepped
+    pub seconds_per_step: f32,
+
+    // Most comments a post takes in one step, and the thread conflict past
+    // which a post is locked automatically. 0 turns either off
+    pub comment_rate_limit: usize,
+    pub lock_conflict_threshold: f32,
+
+    // Most slots in a feed given to sponsored posts, taken after pinned and
+    // breaking posts
+    pub sponsored_slots: usize,
+}
+
+// How posts that reach the minimum score are turned into a feed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RankingMode {
+    // The highest scoring posts, in order
+    #[default]
+    TopK,
+    // Posts drawn without replacement from a softmax over their scores
+    Sampled,
+}
+
+impl RankingMode {
+    pub const ALL: [RankingMode; 2] = [RankingMode::TopK, RankingMode::Sampled];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RankingMode::TopK => "top-k",
+            RankingMode::Sampled => "sampled",
+        }
+    }
+}
+
+// How the remaining recommendation slots are filled when too few posts reach
+// the minimum score
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Backfill {
+    // Leave them empty
+    None,
+    // The most engaged-with of the remaining posts
+    #[default]
+    Trending,
+    // A random selection of the remaining posts
+    Random,
+}
+
+impl Backfill {
+    pub const ALL: [Backfill; 3] = [Backfill::None, Backfill::Trending, Backfill::Random];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Backfill::None => "none",
+            Backfill::Trending => "trending",
+            Backfill::Random => "random",
+        }
+    }
+}
+
+impl Default for RecommendationEngine {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl RecommendationEngine {
     pub fn new() -> Self {
         RecommendationEngine {
-            tag_to_index: HashMap::new(),
-            index_to_tag: HashMap::new(),
+            tag_to_index: BTreeMap::new(),
+            index_to_tag: BTreeMap::new(),
             content_pool: Vec::new(),
             vector_dimension: 100,
+            tag_similarity: TagMatrix::new(),
             config: RecommendationEngineConfig {
                 interest_weight: 0.5,
                 recency_weight: 0.3,
                 engagement_weight: 0.2,
                 recency_decay_rate: 0.05,
+                min_score: 0.05,
+                backfill: Backfill::Trending,
+                max_per_creator: 0,
+                max_per_tag: 0,
+                ranking: RankingMode::TopK,
+                temperature: 0.1,
+                retrieval: RetrievalConfig::default(),
+                breaking_boost: 5.0,
+                breaking_slots: 1,
+                fewer_tag_penalty: 0.5,
+                followed_boost: 0.2,
+                following_weight: 0.0,
+                segment_leakage: 1.0,
+                seconds_per_step: 0.1,
+                comment_rate_limit: 0,
+                lock_conflict_threshold: 0.0,
+                sponsored_slots: 0,
             },
+            current_step: 0,
+            clock: 0.0,
+            banned_creators: BTreeSet::new(),
+            breaking: BTreeMap::new(),
+            promotions: BTreeMap::new(),
+            comment_locks: BTreeMap::new(),
+            rejected_comments: BTreeMap::new(),
+            sponsored: Vec::new(),
+            sponsored_exposures: BTreeMap::new(),
+            seed: 0,
+            candidates: RefCell::default(),
+            rerankers: Vec::new(),
+            telemetry: ScoreTelemetry::default(),
+            exposures: ExposureLog::default(),
+            metrics: MetricsCollector::default(),
+        }
+    }
+
+    // Assigns each tag not yet known to the engine the next vector dimension
+    pub fn register_tags(&mut self, tags: &[String]) {
+        for tag in tags {
+            if !self.tag_to_index.contains_key(tag) {
+                let index = self.tag_to_index.len();
+                self.tag_to_index.insert(tag.clone(), index);
+                self.index_to_tag.insert(index, tag.clone());
+            }
         }
     }
 
-    pub fn get_content_by_id(&self, content_id: usize) -> Option<&Post> {
+    pub fn vectorise(&self, profile: &mut InterestProfile) {
+        profile.build_vector(&self.tag_to_index, &self.tag_similarity);
+    }
+
+    pub fn get_content_by_id(&self, content_id: PostId) -> Option<&Post> {
         self.content_pool.iter().find(|c| c.id == content_id)
     }
 
-    pub fn get_comments_by_post_id(&self, content_id: usize) -> Option<Vec<&Comment>> {
+    pub fn get_comments_by_post_id(&self, content_id: PostId) -> Option<Vec<&Comment>> {
         self.content_pool
             .iter()
             .find(|c| c.id == content_id)
             .map(|post| post.comments.iter().collect())
     }
 
+    // Boosts and pins the post into feeds until `until_step`. Windows that
+    // have already closed are forgotten
+    pub fn mark_breaking(&mut self, post_id: PostId, until_step: u64) {
+        let current_step = self.current_step;
+        self.breaking.retain(|_, until| *until > current_step);
+        self.breaking.insert(post_id, until_step);
+    }
+
+    pub fn is_breaking(&self, post_id: PostId) -> bool {
+        self.breaking
+            .get(&post_id)
+            .is_some_and(|until| *until > self.current_step)
+    }
+
+    // Pins or boosts the post in every feed from this step until
+    // `until_step`. Promoting a post again replaces its window but keeps
+    // counting its exposures
+    pub fn promote(
+        &mut self,
+        post_id: PostId,
+        kind: PromotionKind,
+        until_step: u64,
+    ) -> Result<(), EngineError> {
+        if self.get_content_by_id(post_id).is_none() {
+            return Err(EngineError::PostNotFound(post_id));
+        }
+        let from_step = self.current_step;
+        self.promotions
+            .entry(post_id)
+            .and_modify(|promotion| {
+                promotion.kind = kind;
+                promotion.from_step = from_step;
+                promotion.until_step = until_step;
+            })
+            .or_insert_with(|| Promotion::new(kind, from_step, until_step));
+        Ok(())
+    }
+
+    pub fn active_promotion(&self, post_id: PostId) -> Option<PromotionKind> {
+        self.promotions
+            .get(&post_id)
+            .filter(|promotion| promotion.is_active(self.current_step))
+            .map(|promotion| promotion.kind)
+    }
+
+    // Stops the post taking new comments. A post that's already locked keeps
+    // its original lock
+    pub fn lock_comments(
+        &mut self,
+        post_id: PostId,
+        reason: LockReason,
+    ) -> Result<(), EngineError> {
+        if self.get_content_by_id(post_id).is_none() {
+            return Err(EngineError::PostNotFound(post_id));
+        }
+        let step = self.current_step;
+        self.comment_locks
+            .entry(post_id)
+            .or_insert(CommentLock { step, reason });
+        Ok(())
+    }
+
+    // Reopens the post to comments, returning whether it was locked
+    pub fn unlock_comments(&mut self, post_id: PostId) -> bool {
+        self.comment_locks.remove(&post_id).is_some()
+    }
+
+    pub fn comments_locked(&self, post_id: PostId) -> bool {
+        self.comment_locks.contains_key(&post_id)
+    }
+
+    // Adds a reranker after those already registered, or in place of one
+    // with the same name
+    pub fn register_reranker(&mut self, reranker: impl Reranker + 'static) {
+        match self
+            .rerankers
+            .iter()
+            .position(|registered| registered.name() == reranker.name())
+        {
+            Some(index) => self.rerankers[index] = Box::new(reranker),
+            None => self.rerankers.push(Box::new(reranker)),
+        }
+    }
+
+    pub fn remove_reranker(&mut self, name: &str) -> bool {
+        let before = self.rerankers.len();
+        self.rerankers.retain(|reranker| reranker.name() != name);
+        self.rerankers.len() < before
+    }
+
+    pub fn has_reranker(&self, name: &str) -> bool {
+        self.rerankers
+            .iter()
+            .any(|reranker| reranker.name() == name)
+    }
+
+    pub fn is_sponsored(&self, post_id: PostId) -> bool {
+        self.sponsored.contains(&post_id)
+    }
+
     pub fn calculate_content_score(
         &self,
         content: &Post,
         agent: &Individual,
         current_time: i64,
     ) -> f32 {
+        self.score_components(content, agent, current_time).total()
+    }
+
+    // Each term of the score, already weighted
+    pub fn score_components(
+        &self,
+        content: &Post,
+        agent: &Individual,
+        current_time: i64,
+    ) -> ScoreComponents {
         let interest_alignment = self.calculate_vector_similarity(
             &agent.interest_profile().vector_representation,
             &content.interest_profile.vector_representation,
         );
 
+        ScoreComponents {
+            interest: interest_alignment * self.config.interest_weight,
+            ..self.heat(content, current_time)
+        }
+    }
+
+    // The part of a post's score that every feed shares, from its recency and
+    // engagement. Interest is left at 0 since it depends on who's asking
+    pub fn heat(&self, content: &Post, current_time: i64) -> ScoreComponents {
         let hours_old = (current_time - content.timestamp) as f32 / 3600.0;
-        let recency_score = (-0.05 * hours_old).exp(); // Decay by ~5% per hour
+        let mut recency_score = (-0.05 * hours_old).exp(); // Decay by ~5% per hour
+        if self.is_breaking(content.id) {
+            recency_score *= self.config.breaking_boost;
+        }
 
         let engagement_score = content.engagement_score; // Assuming this is already normalized 0.0-1.0
 
-        let score = interest_alignment * self.config.interest_weight
-            + recency_score * self.config.recency_weight
-            + engagement_score * self.config.engagement_weight;
-
-        score.clamp(0.0, 1.0)
+        ScoreComponents {
+            interest: 0.0,
+            recency: recency_score * self.config.recency_weight,
+            engagement: engagement_score * self.config.engagement_weight,
+        }
     }
 
     pub fn calculate_vector_similarity(&self, vec1: &DVector<f32>, vec2: &DVector<f32>) -> f32 {
@@ -90,71 +443,462 @@ impl RecommendationEngine {
         agent: &Individual,
         count: usize,
         current_time: i64,
-    ) -> Vec<usize> {
-        let mut scored_posts: Vec<(usize, f32)> = self
+        exclusions: &CreatorExclusions,
+    ) -> PostIdList {
+        let _recommendations = span("post recommendations");
+        let mut candidates = self.candidates.borrow_mut();
+        let Candidates {
+            retrieved,
+            above,
+            below,
+        } = &mut *candidates;
+        above.clear();
+        below.clear();
+
+        // Reshares of what the agent has already seen, or passed on itself,
+        // aren't shown again
+        let seen = |post_id: Option<PostId>| {
+            post_id.is_some_and(|post_id| agent.viewed_content.contains(&post_id))
+        };
+        let eligible = |content: &Post| {
+            !agent.viewed_content.contains(&content.id)
+                && !seen(content.reshare_of)
+                && !seen(content.original)
+                && !content.shared_by.contains(&agent.core.id)
+                && !self.banned_creators.contains(&content.creator_id)
+                && !exclusions.excludes(&content.creator_id)
+                && self.reaches_segment(content, agent)
+        };
+
+        // Promoted posts the agent could be shown, newest first
+        let promoted = |pinned: bool| {
+            self.promotions
+                .keys()
+                .rev()
+                .filter(move |&&post_id| match self.active_promotion(post_id) {
+                    Some(PromotionKind::Pin) => pinned,
+                    Some(PromotionKind::Boost(_)) => !pinned,
+                    None => false,
+                })
+                .filter_map(|&post_id| self.get_content_by_id(post_id))
+                .filter(|content| eligible(content))
+        };
+
+        // Pinned posts take the top slots whatever retrieval and ranking
+        // would have made of them, then breaking posts, each newest first,
+        // then sponsored posts in the order their slots were won
+        let mut pinned: PostIdList = promoted(true)
+            .take(count)
+            .map(|content| content.id)
+            .collect();
+        let breaking: PostIdList = self
             .content_pool
             .iter()
-            .filter(|content| !agent.viewed_content.contains(&content.id))
-            .map(|content| {
-                let score = self.calculate_content_score(content, agent, current_time);
-                (content.id, score)
+            .rev()
+            .filter(|content| {
+                self.is_breaking(content.id) && eligible(content) && !pinned.contains(&content.id)
             })
+            .take(self.config.breaking_slots.min(count - pinned.len()))
+            .map(|content| content.id)
+            .collect();
+        pinned.extend(breaking);
+        let sponsored: PostIdList = self
+            .sponsored
+            .iter()
+            .filter_map(|&post_id| self.get_content_by_id(post_id))
+            .filter(|content| eligible(content) && !pinned.contains(&content.id))
+            .take(self.config.sponsored_slots.min(count - pinned.len()))
+            .map(|content| content.id)
             .collect();
+        pinned.extend(sponsored);
+
+        // Retrieval: a cheap pass picking which posts are worth scoring
+        let retrieval = span("retrieval");
+        retrieve(
+            &self.content_pool,
+            agent,
+            &self.config.retrieval,
+            |content| eligible(content) && !pinned.contains(&content.id),
+            retrieved,
+        );
+        // Boosted posts are ranked whether or not retrieval found them
+        for content in promoted(false) {
+            if let Some(index) = self.content_pool.iter().position(|c| c.id == content.id) {
+                if !retrieved.contains(&index) {
+                    retrieved.push(index);
+                }
+            }
+        }
+        drop(retrieval);
 
-        scored_posts.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        let mut logged = Vec::new();
 
-        scored_posts
-            .into_iter()
-            .take(count)
-            .map(|(id, _)| id)
-            .collect()
+        // Ranking: scoring and ordering the retrieved posts
+        let scoring = span("scoring");
+        for &index in retrieved.iter() {
+            let content = &self.content_pool[index];
+            let components = self.score_components(content, agent, current_time);
+            let mut score = self.respect_preferences(components.total(), content, agent);
+            if content.followed_in(&agent.preferred_creators) {
+                score += self.config.following_weight;
+            }
+            if let Some(PromotionKind::Boost(boost)) = self.active_promotion(content.id) {
+                score *= boost;
+            }
+            if self.exposures.enabled() {
+                logged.push(LoggedCandidate {
+                    post_id: content.id,
+                    score,
+                    components,
+                });
+            }
+            if score >= self.config.min_score {
+                above.push((index, score));
+            } else {
+                below.push((index, score));
+            }
+        }
+
+        drop(scoring);
+
+        let _ranking = span("ranking");
+        if self.config.ranking == RankingMode::Sampled {
+            // Perturbing each score with Gumbel noise and taking the top k is
+            // the same as drawing k posts from the softmax one at a time
+            let mut rng = self.rng_for(agent, "ranking");
+            let temperature = self.config.temperature.max(1e-3);
+            for (_, score) in above.iter_mut() {
+                let uniform: f32 = rng.gen_range(f32::EPSILON..1.0);
+                *score = *score / temperature - (-uniform.ln()).ln();
+            }
+        }
+
+        above.sort_by(|a, b| b.1.total_cmp(&a.1));
+        self.order_backfill(agent, below);
+
+        let mut caps = FeedCaps::new(self.config.max_per_creator, self.config.max_per_tag);
+
+        // Pinned posts aren't held back by the caps, but still count towards
+        // them
+        for post_id in &pinned {
+            let content = self.get_content_by_id(*post_id).unwrap();
+            caps.admit(content);
+            self.telemetry
+                .record(self.score_components(content, agent, current_time));
+        }
+
+        let remaining = count - pinned.len();
+        let mut ranked = above
+            .iter()
+            .chain(below.iter())
+            .map(|&(index, score)| ScoredPost {
+                post: &self.content_pool[index],
+                score,
+            });
+        let mut fill = |posts: &mut dyn Iterator<Item = ScoredPost>| {
+            pinned.extend(
+                posts
+                    .map(|scored| scored.post)
+                    .filter(|content| caps.admit(content))
+                    .take(remaining)
+                    .map(|content| {
+                        self.telemetry
+                            .record(self.score_components(content, agent, current_time));
+                        content.id
+                    }),
+            )
+        };
+        if self.rerankers.is_empty() {
+            fill(&mut ranked)
+        } else {
+            let _reranking = span("reranking");
+            let mut slate: Vec<ScoredPost> = ranked.collect();
+            let context = RerankContext {
+                agent,
+                engine: self,
+                slots: remaining,
+            };
+            for reranker in &self.rerankers {
+                reranker.rerank(&mut slate, &context);
+            }
+            fill(&mut slate.into_iter())
+        }
+
+        if self.exposures.enabled() {
+            self.exposures.record(LoggedSlate {
+                step: self.current_step,
+                agent_id: agent.core.id,
+                candidates: logged,
+                served: pinned.to_vec(),
+            });
+        }
+
+        pinned
+    }
+
+    // Whether a post is open to the agent's feed. Only the leakage's share of
+    // posts from other segments get through, the same ones every time; posts
+    // and agents outside any segment go everywhere
+    fn reaches_segment(&self, content: &Post, agent: &Individual) -> bool {
+        match (&content.segment, &agent.core.segment) {
+            (Some(origin), Some(segment)) if origin != segment => {
+                let key = (agent.core.id.0 as u64).rotate_left(32) ^ content.id.0 as u64;
+                RngStreams::unit(self.seed, "leakage", key) < self.config.segment_leakage
+            }
+            _ => true,
+        }
+    }
+
+    // Adjusts a post's score for the feed settings the agent has chosen
+    fn respect_preferences(&self, mut score: f32, content: &Post, agent: &Individual) -> f32 {
+        let preferences = &agent.preferences;
+        if !preferences.enabled {
+            return score;
+        }
+
+        for tag in content.interest_profile.interests.keys() {
+            if preferences.fewer_tags.contains(tag) {
+                score *= self.config.fewer_tag_penalty;
+            }
+        }
+
+        if preferences.prioritise_followed && content.followed_in(&agent.preferred_creators) {
+            score += self.config.followed_boost;
+        }
+
+        score
+    }
+
+    // Puts posts below the minimum score in the order they should fill any
+    // slots left in a feed
+    fn order_backfill(&self, agent: &Individual, candidates: &mut Vec<(usize, f32)>) {
+        match self.config.backfill {
+            Backfill::None => candidates.clear(),
+            Backfill::Trending => candidates.sort_by(|(a, _), (b, _)| {
+                let (a, b) = (&self.content_pool[*a], &self.content_pool[*b]);
+                b.engagement_score.total_cmp(&a.engagement_score)
+            }),
+            Backfill::Random => candidates.shuffle(&mut self.rng_for(agent, "backfill")),
+        }
+    }
+
+    // A stream for one agent's request this step, so that random ranking
+    // doesn't depend on the order agents ask in
+    fn rng_for(&self, agent: &Individual, name: &str) -> StreamRng {
+        RngStreams::stream(
+            self.seed ^ self.current_step.rotate_left(32) ^ agent.core.id.0 as u64,
+            name,
+        )
     }
 
     pub fn get_comment_recommendations(
         &self,
-        post_id: usize,
-        current_comment_ids: Vec<usize>,
+        post_id: PostId,
+        current_comment_ids: &[CommentId],
         count: usize,
-    ) -> Option<Vec<usize>> {
+    ) -> Option<CommentIdList> {
+        let _recommendations = span("comment recommendations");
         self.get_content_by_id(post_id).map(|post| {
-            let current_ids: std::collections::HashSet<_> =
-                current_comment_ids.into_iter().collect();
-
-            let mut comments: Vec<(&Comment, usize)> = post
+            let mut comments: Vec<(&Comment, CommentId)> = post
                 .comments
                 .iter()
-                .filter(|comment| !current_ids.contains(&comment.id))
+                .filter(|comment| !current_comment_ids.contains(&comment.id))
                 .map(|comment| (comment, comment.id))
                 .collect();
 
-            comments.sort_by(|(a, _), (b, _)| {
-                b.engagement_score.partial_cmp(&a.engagement_score).unwrap()
-            });
+            comments.sort_by(|(a, _), (b, _)| b.engagement_score.total_cmp(&a.engagement_score));
             comments.into_iter().take(count).map(|(_, id)| id).collect()
         })
     }
 
-    pub fn increase_engagement_score(&mut self, content_id: usize) {
-        let post: &mut Post = self
-            .content_pool
-            .iter_mut()
-            .find(|c| c.id == content_id)
-            .unwrap();
+    // Simulated seconds since the run started, at the current step
+    pub fn now(&self) -> i64 {
+        self.clock as i64
+    }
 
-        post.increase_engagement();
+    // Moves on to `step`, running the clock forward at the current seconds
+    // per step for each step passed
+    pub fn advance_to(&mut self, step: u64) {
+        let passed = step.saturating_sub(self.current_step);
+        self.clock += passed as f64 * self.config.seconds_per_step as f64;
+        self.current_step = step;
     }
 
-    pub fn add_comment_to_post(&mut self, post_id: usize, comment: Comment) {
-        let post: &mut Post = self
-            .content_pool
+    pub fn apply_op(&mut self, op: EngineOp) -> Result<(), EngineError> {
+        match op {
+            EngineOp::PublishPost(mut post) => {
+                post.timestamp = self.now();
+                post.published_step = self.current_step;
+                let reshare = post
+                    .reshare_of
+                    .zip(post.sharer())
+                    .map(|shared| (post.id, shared));
+                self.create_post(*post);
+                match reshare {
+                    Some((post_id, (shared, sharer))) => {
+                        self.record_reshare(shared, post_id, sharer)
+                    }
+                    None => Ok(()),
+                }
+            }
+            EngineOp::AddComment {
+                post_id,
+                mut comment,
+            } => {
+                comment.timestamp = self.now();
+                self.add_comment_to_post(post_id, comment)
+            }
+            EngineOp::RecordPostRead { post_id, reader_id } => {
+                self.record_post_read(post_id, reader_id)
+            }
+            EngineOp::RecordCommentRead {
+                post_id,
+                comment_id,
+            } => self.increase_comment_engagement_score(post_id, comment_id),
+            EngineOp::RecordExposure {
+                post_id,
+                agent_id,
+                promoted: true,
+            } => {
+                let promotion = self
+                    .promotions
+                    .get_mut(&post_id)
+                    .ok_or(EngineError::PostNotFound(post_id))?;
+                promotion.exposures += 1;
+                promotion.reached.insert(agent_id);
+                Ok(())
+            }
+            EngineOp::RecordExposure {
+                post_id, agent_id, ..
+            } => self.record_cascade_event(post_id, agent_id, CascadeAction::Exposed),
+            EngineOp::RecordSponsoredExposure { post_id } => {
+                self.get_content_by_id(post_id)
+                    .ok_or(EngineError::PostNotFound(post_id))?;
+                *self.sponsored_exposures.entry(post_id).or_default() += 1;
+                Ok(())
+            }
+            EngineOp::LikePost { post_id, agent_id } => {
+                self.increase_engagement_score(post_id)?;
+                self.post_mut(post_id)?.likes.push(agent_id);
+                self.record_cascade_event(post_id, agent_id, CascadeAction::Liked)
+            }
+        }
+    }
+
+    fn post_mut(&mut self, post_id: PostId) -> Result<&mut Post, EngineError> {
+        self.content_pool
             .iter_mut()
             .find(|c| c.id == post_id)
-            .unwrap();
+            .ok_or(EngineError::PostNotFound(post_id))
+    }
 
-        post.comments.push(comment);
+    pub fn record_post_read(
+        &mut self,
+        post_id: PostId,
+        reader_id: AgentId,
+    ) -> Result<(), EngineError> {
+        self.increase_engagement_score(post_id)?;
+        self.post_mut(post_id)?.readers.push(reader_id);
+        self.record_cascade_event(post_id, reader_id, CascadeAction::Read)
     }
 
-    pub fn create_post(&mut self, post: Post) {
+    // Links a published reshare into the tree of the post it shares, which
+    // counts as engagement with that post
+    fn record_reshare(
+        &mut self,
+        shared: PostId,
+        reshare: PostId,
+        sharer: AgentId,
+    ) -> Result<(), EngineError> {
+        self.increase_engagement_score(shared)?;
+        self.post_mut(shared)?.reshares.push(reshare);
+        self.record_cascade_event(shared, sharer, CascadeAction::Reshared(reshare))
+    }
+
+    pub fn record_cascade_event(
+        &mut self,
+        post_id: PostId,
+        agent_id: AgentId,
+        action: CascadeAction,
+    ) -> Result<(), EngineError> {
+        let step = self.current_step;
+
+        self.post_mut(post_id)?.cascade.push(CascadeEvent {
+            step,
+            agent_id,
+            action,
+        });
+        Ok(())
+    }
+
+    pub fn increase_comment_engagement_score(
+        &mut self,
+        post_id: PostId,
+        comment_id: CommentId,
+    ) -> Result<(), EngineError> {
+        self.post_mut(post_id)?
+            .comments
+            .iter_mut()
+            .find(|c| c.id == comment_id)
+            .ok_or(EngineError::CommentNotFound {
+                post_id,
+                comment_id,
+            })?
+            .increase_engagement();
+        Ok(())
+    }
+
+    pub fn increase_engagement_score(&mut self, content_id: PostId) -> Result<(), EngineError> {
+        self.post_mut(content_id)?.increase_engagement();
+        Ok(())
+    }
+
+    pub fn add_comment_to_post(
+        &mut self,
+        post_id: PostId,
+        mut comment: Comment,
+    ) -> Result<(), EngineError> {
+        let (step, rate_limit) = (self.current_step, self.config.comment_rate_limit);
+        let post = self.post_mut(post_id)?;
+        let commented_this_step = post
+            .cascade
+            .iter()
+            .filter(|event| {
+                event.step == step && matches!(event.action, CascadeAction::Commented(_))
+            })
+            .count();
+        let rejection = if self.comment_locks.contains_key(&post_id) {
+            Some(EngineError::CommentsLocked(post_id))
+        } else if rate_limit > 0 && commented_this_step >= rate_limit {
+            Some(EngineError::RateLimited(post_id))
+        } else {
+            None
+        };
+        if let Some(rejection) = rejection {
+            *self.rejected_comments.entry(post_id).or_default() += 1;
+            return Err(rejection);
+        }
+
+        self.vectorise(&mut comment.interest_profile);
+        let (commentor_id, comment_id) = (comment.commentor_id, comment.id);
+        self.post_mut(post_id)?.comments.push(comment);
+        self.record_cascade_event(post_id, commentor_id, CascadeAction::Commented(comment_id))?;
+
+        let threshold = self.config.lock_conflict_threshold;
+        if threshold > 0.0 && thread_conflict(self.post_mut(post_id)?) > threshold {
+            self.lock_comments(post_id, LockReason::Conflict)?;
+        }
+        Ok(())
+    }
+
+    // Content vectors are built here so that every post in the pool is scored
+    // against the same tag indices and similarities
+    pub fn create_post(&mut self, mut post: Post) {
+        self.vectorise(&mut post.interest_profile);
+        for comment in post.comments.iter_mut() {
+            self.vectorise(&mut comment.interest_profile);
+        }
+
         self.content_pool.push(post);
     }
 }
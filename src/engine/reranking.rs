@@ -0,0 +1,295 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Debug;
+
+use super::RecommendationEngine;
+use crate::models::fairness::{ExposureTarget, UNASSIGNED};
+use crate::models::{AgentId, Individual, Post};
+
+// A ranked candidate for a feed, with the score it was ranked by
+#[derive(Debug, Clone, Copy)]
+pub struct ScoredPost<'a> {
+    pub post: &'a Post,
+    pub score: f32,
+}
+
+// The feed a reranker is reordering
+pub struct RerankContext<'a> {
+    pub agent: &'a Individual,
+    pub engine: &'a RecommendationEngine,
+    // Slots left once pinned, breaking and sponsored posts have theirs
+    pub slots: usize,
+}
+
+// A pass over each feed's ranked candidates once they've been scored, free to
+// reorder or drop them, so reranking can be experimented with without
+// touching the scoring code. Candidates arrive best first, with any backfill
+// after the posts that reached the minimum score, and the feed is filled from
+// the front of what's left, subject to the feed caps. Pinned, breaking and
+// sponsored posts are placed before reranking and never seen by it
+pub trait Reranker: Debug {
+    // Registering a reranker replaces any other with the same name
+    fn name(&self) -> &str;
+
+    fn rerank(&self, slate: &mut Vec<ScoredPost>, context: &RerankContext);
+
+    fn clone_box(&self) -> Box<dyn Reranker>;
+}
+
+impl Clone for Box<dyn Reranker> {
+    fn clone(&self) -> Box<dyn Reranker> {
+        self.clone_box()
+    }
+}
+
+// Maximal marginal relevance: fills the feed one slot at a time with the post
+// whose score, less a penalty for resembling the posts already picked, is
+// highest. Candidates it doesn't pick keep their order after those it does
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mmr {
+    // 1 ranks on score alone, 0 on novelty alone
+    pub lambda: f32,
+}
+
+impl Mmr {
+    pub const NAME: &'static str = "mmr";
+}
+
+impl Default for Mmr {
+    fn default() -> Self {
+        Self { lambda: 0.7 }
+    }
+}
+
+impl Reranker for Mmr {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn rerank(&self, slate: &mut Vec<ScoredPost>, context: &RerankContext) {
+        let similarity = |a: &Post, b: &Post| {
+            context.engine.calculate_vector_similarity(
+                &a.interest_profile.vector_representation,
+                &b.interest_profile.vector_representation,
+            )
+        };
+
+        let mut rest = std::mem::take(slate);
+        while slate.len() < context.slots && !rest.is_empty() {
+            let marginal = |candidate: &ScoredPost| {
+                let redundancy = slate
+                    .iter()
+                    .map(|picked| similarity(candidate.post, picked.post))
+                    .fold(0.0, f32::max);
+                self.lambda * candidate.score - (1.0 - self.lambda) * redundancy
+            };
+            // The first of any tie, so equal candidates keep their rank order
+            let mut best = 0;
+            let mut best_value = marginal(&rest[0]);
+            for (index, candidate) in rest.iter().enumerate().skip(1) {
+                let value = marginal(candidate);
+                if value > best_value {
+                    best = index;
+                    best_value = value;
+                }
+            }
+            slate.push(rest.remove(best));
+        }
+        slate.append(&mut rest);
+    }
+
+    fn clone_box(&self) -> Box<dyn Reranker> {
+        Box::new(*self)
+    }
+}
+
+// Keeps a minimum share of each feed for a group of creators, e.g. every
+// individual so organisations can't crowd them out, by moving the group's
+// best ranked posts up into the feed in place of the lowest ranked others
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreatorQuota {
+    pub name: String,
+    pub creators: BTreeSet<AgentId>,
+    // Share of the feed's slots, rounded up
+    pub min_share: f32,
+}
+
+impl Reranker for CreatorQuota {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn rerank(&self, slate: &mut Vec<ScoredPost>, context: &RerankContext) {
+        let slots = context.slots.min(slate.len());
+        let wanted = (self.min_share.clamp(0.0, 1.0) * context.slots as f32).ceil() as usize;
+        let in_group = |candidate: &ScoredPost| self.creators.contains(&candidate.post.creator_id);
+
+        let shown = slate[..slots].iter().filter(|c| in_group(c)).count();
+        let missing = wanted.saturating_sub(shown);
+        if missing == 0 {
+            return;
+        }
+
+        let demoted: Vec<usize> = (0..slots)
+            .rev()
+            .filter(|&index| !in_group(&slate[index]))
+            .take(missing)
+            .collect();
+        let promoted: Vec<usize> = (slots..slate.len())
+            .filter(|&index| in_group(&slate[index]))
+            .take(demoted.len())
+            .collect();
+        let demoted = &demoted[..promoted.len()];
+
+        // The feed keeps rank order, and demoted posts drop to just below it
+        // ahead of the rest
+        let mut feed: Vec<usize> = (0..slots)
+            .filter(|index| !demoted.contains(index))
+            .chain(promoted.iter().copied())
+            .collect();
+        feed.sort_unstable();
+        let order: Vec<usize> = feed
+            .into_iter()
+            .chain(demoted.iter().rev().copied())
+            .chain((slots..slate.len()).filter(|index| !promoted.contains(index)))
+            .collect();
+        *slate = order.into_iter().map(|index| slate[index]).collect();
+    }
+
+    fn clone_box(&self) -> Box<dyn Reranker> {
+        Box::new(self.clone())
+    }
+}
+
+// Holds each group of creators, e.g. agent types or communities, within its
+// share of every feed. A group's posts are capped at its maximum share of the
+// feed, rounded down, and kept to at least its minimum share of the slots,
+// rounded up, as far as it has candidates. With parity every group is also
+// capped at an even split of the feed among the groups in it. Caps are
+// strict: when the feed comes up short they are taken against what's in it,
+// and candidates beyond them are dropped rather than left to backfill
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExposureQuotas {
+    pub groups: BTreeMap<AgentId, String>,
+    pub targets: Vec<ExposureTarget>,
+    pub parity: bool,
+}
+
+impl ExposureQuotas {
+    pub const NAME: &'static str = "exposure quotas";
+
+    fn group(&self, candidate: &ScoredPost) -> &str {
+        self.groups
+            .get(&candidate.post.creator_id)
+            .map_or(UNASSIGNED, String::as_str)
+    }
+
+    fn target(&self, group: &str) -> Option<&ExposureTarget> {
+        self.targets.iter().find(|target| target.group == group)
+    }
+
+    // Most of a feed of `length` posts, shared among `groups`, the group may
+    // take, or None if it's uncapped
+    fn cap(&self, group: &str, length: usize, groups: usize) -> Option<usize> {
+        let max = self.target(group).and_then(|target| target.max_share);
+        let share = max.map(|max| (max.clamp(0.0, 1.0) * length as f32 + 1e-4) as usize);
+        let even = self.parity.then(|| length.div_ceil(groups.max(1)));
+        match (share, even) {
+            (Some(share), Some(even)) => Some(share.min(even)),
+            (share, even) => share.or(even),
+        }
+    }
+}
+
+impl Reranker for ExposureQuotas {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn rerank(&self, slate: &mut Vec<ScoredPost>, context: &RerankContext) {
+        let slots = context.slots;
+        let mut left: BTreeMap<&str, usize> = BTreeMap::new();
+        for candidate in slate.iter() {
+            *left.entry(self.group(candidate)).or_default() += 1;
+        }
+        let present = left.len();
+        let caps: BTreeMap<&str, usize> = left
+            .keys()
+            .map(|&group| {
+                let cap = self.cap(group, slots, present).unwrap_or(usize::MAX);
+                (group, cap)
+            })
+            .collect();
+        let floors: BTreeMap<&str, usize> = left
+            .iter()
+            .map(|(&group, &candidates)| {
+                let floor = self
+                    .target(group)
+                    .and_then(|target| target.min_share)
+                    .map_or(0, |min| {
+                        (min.clamp(0.0, 1.0) * slots as f32).ceil() as usize
+                    });
+                (group, floor.min(caps[group]).min(candidates))
+            })
+            .collect();
+
+        // Candidates join the feed in rank order unless their group is at its
+        // cap, or the slots left are owed to groups still short of their floor
+        let mut shown: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut feed = Vec::with_capacity(slots);
+        let mut rest = Vec::new();
+        for candidate in slate.drain(..) {
+            let group = self.group(&candidate);
+            *left.get_mut(group).unwrap() -= 1;
+            let count = shown.get(group).copied().unwrap_or(0);
+            if feed.len() == slots || count >= caps[group] {
+                rest.push(candidate);
+                continue;
+            }
+            let owed: usize = floors
+                .iter()
+                .filter(|&(&other, _)| other != group)
+                .map(|(&other, &floor)| {
+                    let count = shown.get(other).copied().unwrap_or(0);
+                    floor.saturating_sub(count).min(left[other])
+                })
+                .sum();
+            if count < floors[group] || feed.len() + 1 + owed <= slots {
+                *shown.entry(group).or_default() += 1;
+                feed.push(candidate);
+            } else {
+                rest.push(candidate);
+            }
+        }
+
+        // A short feed gives each post a bigger share, so groups over their
+        // cap of what's there lose their lowest ranked posts until none are
+        loop {
+            shown.retain(|_, count| *count > 0);
+            let over = shown.iter().find(|(&group, &count)| {
+                self.cap(group, feed.len(), shown.len())
+                    .is_some_and(|cap| count > cap)
+            });
+            let Some((&group, _)) = over else {
+                break;
+            };
+            let last = feed
+                .iter()
+                .rposition(|candidate| self.group(candidate) == group)
+                .unwrap();
+            feed.remove(last);
+            *shown.get_mut(group).unwrap() -= 1;
+        }
+
+        // Only uncapped groups stay behind the feed, to stand in for posts the
+        // feed caps turn away, so they can't tip a capped group over
+        feed.extend(
+            rest.into_iter()
+                .filter(|candidate| self.cap(self.group(candidate), slots, present).is_none()),
+        );
+        *slate = feed;
+    }
+
+    fn clone_box(&self) -> Box<dyn Reranker> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Agent, Individual, Post};
+
+// Where candidate posts for a feed come from, before any of them are scored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RetrievalSource {
+    // Every post the agent could be shown, so ranking sees the whole pool
+    All,
+    // The newest posts
+    Recent,
+    // The most engaged-with posts
+    Trending,
+    // The newest posts by creators the agent has read before
+    Followed,
+    // The newest posts sharing a tag with the agent's interests
+    TagMatched,
+}
+
+impl RetrievalSource {
+    pub const ALL: [RetrievalSource; 5] = [
+        RetrievalSource::All,
+        RetrievalSource::Recent,
+        RetrievalSource::Trending,
+        RetrievalSource::Followed,
+        RetrievalSource::TagMatched,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RetrievalSource::All => "all",
+            RetrievalSource::Recent => "recent",
+            RetrievalSource::Trending => "trending",
+            RetrievalSource::Followed => "followed",
+            RetrievalSource::TagMatched => "tag-matched",
+        }
+    }
+}
+
+// Which sources feed the ranking stage, and how many candidates each may
+// contribute
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetrievalConfig {
+    pub sources: Vec<RetrievalSource>,
+    pub per_source: usize,
+}
+
+impl Default for RetrievalConfig {
+    fn default() -> Self {
+        Self {
+            sources: vec![RetrievalSource::All],
+            per_source: 100,
+        }
+    }
+}
+
+// Collects the pool indices of candidate posts for `agent` from every
+// configured source into `out`, without duplicates. Only posts `eligible`
+// accepts count towards a source's share
+pub fn retrieve(
+    pool: &[Post],
+    agent: &Individual,
+    config: &RetrievalConfig,
+    eligible: impl Fn(&Post) -> bool,
+    out: &mut Vec<usize>,
+) {
+    out.clear();
+
+    if config.sources.contains(&RetrievalSource::All) {
+        out.extend((0..pool.len()).filter(|&index| eligible(&pool[index])));
+        return;
+    }
+
+    // The pool is in publishing order, so walking it backwards is newest first
+    let newest = || {
+        (0..pool.len())
+            .rev()
+            .filter(|&index| eligible(&pool[index]))
+    };
+
+    for source in &config.sources {
+        match source {
+            RetrievalSource::All => unreachable!(),
+            RetrievalSource::Recent => out.extend(newest().take(config.per_source)),
+            RetrievalSource::Trending => {
+                let mut trending: Vec<usize> = newest().collect();
+                trending.sort_by(|a, b| {
+                    pool[*b]
+                        .engagement_score
+                        .partial_cmp(&pool[*a].engagement_score)
+                        .unwrap()
+                });
+                out.extend(trending.into_iter().take(config.per_source));
+            }
+            RetrievalSource::Followed => {
+                let Some(followed) = agent.preferred_creators() else {
+                    continue;
+                };
+                out.extend(
+                    newest()
+                        .filter(|&index| pool[index].followed_in(followed))
+                        .take(config.per_source),
+                );
+            }
+            RetrievalSource::TagMatched => {
+                let interests = &agent.interest_profile().interests;
+                out.extend(
+                    newest()
+                        .filter(|&index| {
+                            pool[index]
+                                .interest_profile
+                                .interests
+                                .keys()
+                                .any(|tag| interests.contains_key(tag))
+                        })
+                        .take(config.per_source),
+                );
+            }
+        }
+    }
+
+    out.sort_unstable();
+    out.dedup();
+}
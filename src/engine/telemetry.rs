@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+use crate::models::Histogram;
+
+// How many snapshots are kept for plotting
+const MAX_SNAPSHOTS: usize = 100;
+const SCORE_BINS: usize = 20;
+
+// What each ranking term contributed to a score, after weighting
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScoreComponents {
+    pub interest: f32,
+    pub recency: f32,
+    pub engagement: f32,
+}
+
+impl ScoreComponents {
+    pub const NAMES: [&'static str; 3] = ["interest", "recency", "engagement"];
+
+    pub fn total(&self) -> f32 {
+        (self.interest + self.recency + self.engagement).clamp(0.0, 1.0)
+    }
+
+    // The components in the order of `NAMES`
+    pub fn values(&self) -> [f32; 3] {
+        [self.interest, self.recency, self.engagement]
+    }
+
+    fn add(&mut self, other: &ScoreComponents) {
+        self.interest += other.interest;
+        self.recency += other.recency;
+        self.engagement += other.engagement;
+    }
+
+    fn scaled(&self, factor: f32) -> ScoreComponents {
+        ScoreComponents {
+            interest: self.interest * factor,
+            recency: self.recency * factor,
+            engagement: self.engagement * factor,
+        }
+    }
+}
+
+// The scores of everything served over one window of steps
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreSnapshot {
+    pub step: u64,
+    pub served: usize,
+    pub histogram: Histogram,
+    // Mean weighted contribution of each term to a served post's score
+    pub mean: ScoreComponents,
+}
+
+impl ScoreSnapshot {
+    // Each term's share of the summed contributions, so they add up to 1.0
+    pub fn shares(&self) -> ScoreComponents {
+        let total = self.mean.interest + self.mean.recency + self.mean.engagement;
+        if total > 0.0 {
+            self.mean.scaled(1.0 / total)
+        } else {
+            ScoreComponents::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ScoreWindow {
+    scores: Vec<f32>,
+    sums: ScoreComponents,
+}
+
+// Collects the scores of served posts as recommendations are made, and turns
+// them into a snapshot at the end of each window
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScoreTelemetry {
+    window: RefCell<ScoreWindow>,
+    pub snapshots: VecDeque<ScoreSnapshot>,
+}
+
+impl ScoreTelemetry {
+    pub fn record(&self, components: ScoreComponents) {
+        let mut window = self.window.borrow_mut();
+        window.scores.push(components.total());
+        window.sums.add(&components);
+    }
+
+    pub fn snapshot(&mut self, step: u64) {
+        let window = std::mem::take(self.window.get_mut());
+        let served = window.scores.len();
+
+        if self.snapshots.len() == MAX_SNAPSHOTS {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(ScoreSnapshot {
+            step,
+            served,
+            histogram: Histogram::new(&window.scores, SCORE_BINS, 0.0, 1.0),
+            mean: window.sums.scaled(1.0 / served.max(1) as f32),
+        });
+    }
+
+    pub fn latest(&self) -> Option<&ScoreSnapshot> {
+        self.snapshots.back()
+    }
+}
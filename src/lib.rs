@@ -1,5 +1,17 @@
+#[cfg(feature = "bench")]
+pub mod bench;
+pub mod bundle;
+pub mod classroom;
 pub mod engine;
+pub mod locale;
+pub mod metrics;
 pub mod models;
+pub mod profiling;
+pub mod save;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_support;
+pub mod tutorial;
+pub mod worker;
 
 pub use engine::RecommendationEngine;
 pub use models::{Agent, InterestProfile, Post, Simulation, Topic};
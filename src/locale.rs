@@ -0,0 +1,390 @@
+use serde::{Deserialize, Serialize};
+
+// Languages the app's labels can be shown in. Labels are looked up by their
+// English text, so anything without a translation falls back to English
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Locale {
+    #[default]
+    English,
+    Spanish,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::English, Locale::Spanish];
+
+    // The language's name in itself, for the locale switcher
+    pub fn name(&self) -> &'static str {
+        match self {
+            Locale::English => "English",
+            Locale::Spanish => "Español",
+        }
+    }
+
+    // The (English, translated) pairs for this locale
+    pub fn table(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Locale::English => &[],
+            Locale::Spanish => SPANISH,
+        }
+    }
+
+    pub fn tr<'a>(&self, english: &'a str) -> &'a str {
+        self.table()
+            .iter()
+            .find(|(key, _)| *key == english)
+            .map_or(english, |(_, translated)| translated)
+    }
+
+    // Translates a template and fills its `{}` placeholders with `args` in
+    // order. Placeholders past the end of `args` are left as they are
+    pub fn fill(&self, template: &str, args: &[String]) -> String {
+        let mut args = args.iter();
+        let mut pieces = self.tr(template).split("{}");
+        let mut filled = pieces.next().unwrap_or_default().to_string();
+        for piece in pieces {
+            match args.next() {
+                Some(arg) => filled.push_str(arg),
+                None => filled.push_str("{}"),
+            }
+            filled.push_str(piece);
+        }
+        filled
+    }
+}
+
+// Templates keep their `{}` placeholders in the same order as the English
+const SPANISH: &[(&str, &str)] = &[
+    // Control panel
+    ("Configuration", "Configuración"),
+    ("Language", "Idioma"),
+    ("Start", "Iniciar"),
+    ("Stop", "Detener"),
+    ("Num. Individuals", "Núm. de personas"),
+    ("Num. Bots", "Núm. de bots"),
+    ("Num. Organisations", "Núm. de organizaciones"),
+    ("Base content length", "Longitud base del contenido"),
+    ("Max content length", "Longitud máxima del contenido"),
+    ("Bot create time", "Tiempo de creación de los bots"),
+    ("Diversity Weight", "Peso de la diversidad"),
+    (
+        "Not used in ranking yet",
+        "Todavía no se usa en la clasificación",
+    ),
+    ("Recency Weight", "Peso de la actualidad"),
+    ("Engagement Weight", "Peso de la interacción"),
+    ("Interest Decay Rate", "Tasa de olvido de intereses"),
+    ("Following Weight", "Peso de los seguidos"),
+    (
+        "Min. Recommendation Score",
+        "Puntuación mínima de recomendación",
+    ),
+    ("Backfill", "Relleno"),
+    (
+        "Feed Cap per Creator (0 = off)",
+        "Límite por creador en el feed (0 = sin límite)",
+    ),
+    (
+        "Feed Cap per Tag (0 = off)",
+        "Límite por etiqueta en el feed (0 = sin límite)",
+    ),
+    ("Ranking", "Ordenación"),
+    ("Temperature", "Temperatura"),
+    ("Diversify feeds (MMR)", "Diversificar los feeds (MMR)"),
+    ("Retrieval sources", "Fuentes de candidatos"),
+    ("Candidates per Source", "Candidatos por fuente"),
+    ("Position Bias", "Sesgo de posición"),
+    ("Appeal Effect", "Efecto del atractivo"),
+    ("Ads per Feed", "Anuncios por feed"),
+    ("Tip Chance", "Probabilidad de propina"),
+    ("Read time", "Tiempo de lectura"),
+    ("Behaviour", "Comportamiento"),
+    ("Freeze", "Congelar"),
+    ("Unfreeze", "Descongelar"),
+    (
+        "Stop this agent ticking, keeping it and its posts in the simulation",
+        "Detiene a este agente, manteniéndolo a él y sus publicaciones en la simulación",
+    ),
+    ("default", "predeterminado"),
+    ("addictive", "adictivo"),
+    ("casual", "ocasional"),
+    ("Like Chance", "Probabilidad de me gusta"),
+    ("Reshare Chance", "Probabilidad de compartir"),
+    ("Organisation Responsiveness", "Receptividad de las organizaciones"),
+    ("Subscription Fee", "Cuota de suscripción"),
+    ("Segment Leakage", "Filtración entre segmentos"),
+    ("Sponsored Slots", "Espacios patrocinados"),
+    ("Social Join Rate", "Tasa de adopción social"),
+    ("Leave Rate", "Tasa de abandono"),
+    ("Engagement Batch (steps)", "Lote de interacción (pasos)"),
+    ("Tick Rate (ms)", "Intervalo de tick (ms)"),
+    ("Speed multiplier", "Multiplicador de velocidad"),
+    ("Reset Simulation", "Reiniciar la simulación"),
+    ("Intervention console", "Consola de intervenciones"),
+    ("Performance", "Rendimiento"),
+    ("Follow graph", "Grafo de seguidores"),
+    ("Plots", "Gráficos"),
+    ("Metrics over time", "Métricas a lo largo del tiempo"),
+    ("Topic popularity", "Popularidad de los temas"),
+    ("online agents", "agentes en línea"),
+    ("Nothing recorded yet", "Aún no hay nada registrado"),
+    (
+        "Scroll to zoom, drag to pan, double-click to reset",
+        "Desplaza para ampliar, arrastra para moverte, doble clic para restablecer",
+    ),
+    ("One point per {} ticks", "Un punto cada {} ticks"),
+    ("Display settings", "Ajustes de visualización"),
+    ("Classroom", "Clase"),
+    (
+        "Community Detection Interval (0 = off)",
+        "Intervalo de detección de comunidades (0 = nunca)",
+    ),
+    ("Analytics in the background", "Analíticas en segundo plano"),
+    (
+        "Detect communities and sample creator wealth on another thread, applying each result when next due",
+        "Detecta comunidades y muestrea la riqueza de los creadores en otro hilo, aplicando cada resultado en su siguiente turno",
+    ),
+    (
+        "Survey Interval (0 = off)",
+        "Intervalo de encuesta (0 = nunca)",
+    ),
+    ("Copy survey as CSV", "Copiar la encuesta como CSV"),
+    (
+        "Transparency Report Interval (0 = off)",
+        "Intervalo del informe de transparencia (0 = nunca)",
+    ),
+    ("Load scenario", "Cargar escenario"),
+    ("Save", "Guardar"),
+    ("Load", "Cargar"),
+    ("Start tutorial", "Empezar el tutorial"),
+    // Display settings
+    ("Text size", "Tamaño del texto"),
+    ("Palette", "Paleta"),
+    ("Standard", "Estándar"),
+    ("Colourblind safe", "Apta para daltonismo"),
+    ("disagree", "en desacuerdo"),
+    ("agree", "de acuerdo"),
+    // Distributions and charts
+    ("Distributions", "Distribuciones"),
+    ("Interest entropy (bits)", "Entropía de intereses (bits)"),
+    ("Session length (ticks)", "Duración de la sesión (ticks)"),
+    (
+        "Position-driven selections",
+        "Selecciones debidas a la posición",
+    ),
+    ("Well-being", "Bienestar"),
+    ("Screen time (ticks)", "Tiempo de pantalla (ticks)"),
+    ("Late-session share", "Proporción al final de la sesión"),
+    ("Creator earnings", "Ingresos de los creadores"),
+    ("Over time", "A lo largo del tiempo"),
+    ("posts created", "publicaciones creadas"),
+    ("comments created", "comentarios creados"),
+    ("mean engagement", "interacción media"),
+    ("mean interest entropy", "entropía media de intereses"),
+    ("topic concentration", "concentración temática"),
+    ("Latest {}, peak {}", "Último {}, máximo {}"),
+    ("Share of peak", "Proporción del máximo"),
+    (
+        "Copy per-tick metrics as CSV",
+        "Copiar métricas por tick como CSV",
+    ),
+    (
+        "Feed tag entropy (bits)",
+        "Entropía de etiquetas del feed (bits)",
+    ),
+    ("Feed creator diversity", "Diversidad de creadores del feed"),
+    ("agents", "agentes"),
+    ("posts served", "publicaciones mostradas"),
+    ("{} {}, {} to {}", "{} {}, de {} a {}"),
+    (
+        "Mean entropy with feed controls: {}, without: {}",
+        "Entropía media con controles del feed: {}, sin ellos: {}",
+    ),
+    ("Not sampled yet", "Aún sin muestras"),
+    ("Step {}", "Paso {}"),
+    (
+        "Step {}: mean {}, lowest {}, {} ticks online on average",
+        "Paso {}: media {}, mínimo {}, {} ticks conectado de media",
+    ),
+    (
+        "Late-session share {}, conflictual reads {}",
+        "Proporción al final de la sesión {}, lecturas conflictivas {}",
+    ),
+    ("Mean well-being", "Bienestar medio"),
+    ("Lowest well-being", "Bienestar mínimo"),
+    ("Mean arousal", "Excitación media"),
+    ("Arousal", "Excitación"),
+    ("Revenue", "Ingresos"),
+    (
+        "Step {}: {} ad impressions, {} revenue",
+        "Paso {}: {} impresiones de anuncios, {} de ingresos",
+    ),
+    ("Revenue per step", "Ingresos por paso"),
+    ("Attention marketplace", "Mercado de la atención"),
+    (
+        "Step {}: {} bidders, price {}, {} of exposures sponsored",
+        "Paso {}: {} pujadores, precio {}, {} de exposiciones patrocinadas",
+    ),
+    ("Price", "Precio"),
+    ("Sponsored share", "Proporción patrocinada"),
+    (
+        "Agent {} ({}): spent {}, won {} slots",
+        "Agente {} ({}): gastó {}, ganó {} espacios",
+    ),
+    ("fixed", "fija"),
+    ("paced", "repartida"),
+    ("adaptive", "adaptativa"),
+    ("No sponsored slots", "Sin espacios patrocinados"),
+    ("Transparency report", "Informe de transparencia"),
+    (
+        "Steps {} to {}: {} organic exposures, ad load {}",
+        "Pasos {} a {}: {} exposiciones orgánicas, carga publicitaria {}",
+    ),
+    (
+        "Copy reports as Markdown",
+        "Copiar los informes como Markdown",
+    ),
+    ("Copy reports as JSON", "Copiar los informes como JSON"),
+    (
+        "No reports published yet",
+        "Aún no se ha publicado ningún informe",
+    ),
+    ("Exposure fairness", "Equidad de exposición"),
+    (
+        "Step {}: {} exposures by {}",
+        "Paso {}: {} exposiciones por {}",
+    ),
+    ("Fairness groups", "Grupos de equidad"),
+    ("agent type", "tipo de agente"),
+    ("community", "comunidad"),
+    (
+        "{}: share {}, target {} to {}",
+        "{}: proporción {}, objetivo de {} a {}",
+    ),
+    ("{}: share {}", "{}: proporción {}"),
+    (
+        "Exposure parity across groups",
+        "Paridad de exposición entre grupos",
+    ),
+    ("Creator economy", "Economía de los creadores"),
+    (
+        "Step {}: {} paid to {} creators, Gini {}, top tenth's share {}",
+        "Paso {}: {} pagado a {} creadores, Gini {}, parte del décimo superior {}",
+    ),
+    ("Gini", "Gini"),
+    ("Top tenth's share", "Parte del décimo superior"),
+    ("Adoption", "Adopción"),
+    (
+        "Step {}: {} of {} individuals on the platform, {} joined, {} left",
+        "Paso {}: {} de {} personas en la plataforma, {} se unieron, {} se fueron",
+    ),
+    (
+        "Fastest growth up to step {}",
+        "Crecimiento más rápido hasta el paso {}",
+    ),
+    ("Share on the platform", "Proporción en la plataforma"),
+    ("Segments", "Segmentos"),
+    ("One shared market", "Un único mercado compartido"),
+    (
+        "{}: {} agents, {} posts, {} of exposures from elsewhere",
+        "{}: {} agentes, {} publicaciones, {} de exposiciones de fuera",
+    ),
+    (
+        "Reads: {} at home, {} from elsewhere",
+        "Lecturas: {} propias, {} de fuera",
+    ),
+    ("Cohorts", "Cohortes"),
+    ("No cohorts", "Sin cohortes"),
+    ("Cohort", "Cohorte"),
+    ("Size", "Tamaño"),
+    ("Screen time", "Tiempo de pantalla"),
+    ("Entropy", "Entropía"),
+    ("Exposure", "Exposición"),
+    ("Communities", "Comunidades"),
+    (
+        "{} communities as of step {}",
+        "{} comunidades en el paso {}",
+    ),
+    ("Agreement", "Acuerdo"),
+    ("Agreement on {}", "Acuerdo sobre {}"),
+    ("Internal", "Interna"),
+    ("Not detected yet", "Aún sin detectar"),
+    ("Served scores", "Puntuaciones mostradas"),
+    ("Steps up to {}", "Pasos hasta el {}"),
+    ("What drives ranking", "Qué determina la ordenación"),
+    (
+        "Scores are summarised every {} steps",
+        "Las puntuaciones se resumen cada {} pasos",
+    ),
+    // Agent windows
+    ("Interests", "Intereses"),
+    (
+        "Point colour: red disagrees, green agrees",
+        "Color del punto: rojo en desacuerdo, verde de acuerdo",
+    ),
+    (
+        "Point colour: orange disagrees, blue agrees",
+        "Color del punto: naranja en desacuerdo, azul de acuerdo",
+    ),
+    ("Follower growth", "Crecimiento de seguidores"),
+    ("Audience interests", "Intereses de la audiencia"),
+    (
+        "Nobody has read this creator's posts yet",
+        "Nadie ha leído aún las publicaciones de este creador",
+    ),
+    // Tutorial
+    ("Step {} of {}", "Paso {} de {}"),
+    ("Back", "Atrás"),
+    ("Next", "Siguiente"),
+    ("Finish", "Terminar"),
+    ("Leave tutorial", "Salir del tutorial"),
+    ("Welcome", "Bienvenida"),
+    (
+        "This sandbox simulates people, bots and organisations using a social media \
+         platform whose feeds are picked by a recommendation algorithm. The tutorial \
+         has loaded a small scenario to explore",
+        "Este entorno simula personas, bots y organizaciones que usan una red social \
+         cuyos feeds elige un algoritmo de recomendación. El tutorial ha cargado un \
+         escenario pequeño para explorarlo",
+    ),
+    ("Control panel", "Panel de control"),
+    (
+        "The sliders here set up the population and weight what the algorithm ranks \
+         posts by. Press Start to set the simulation running",
+        "Estos controles configuran la población y lo que pesa el algoritmo al ordenar \
+         las publicaciones. Pulsa Iniciar para poner en marcha la simulación",
+    ),
+    ("Agents", "Agentes"),
+    (
+        "Every agent is shown here with what it is doing. Click an icon to open that \
+         agent's window",
+        "Aquí aparece cada agente con lo que está haciendo. Haz clic en un icono para \
+         abrir la ventana de ese agente",
+    ),
+    ("Agent window", "Ventana del agente"),
+    (
+        "The chart shows how interested the agent is in each tag, and how much it \
+         agrees with it. Watch it change as the agent reads its feed",
+        "El gráfico muestra cuánto le interesa al agente cada etiqueta y cuánto está de \
+         acuerdo con ella. Observa cómo cambia mientras el agente lee su feed",
+    ),
+    ("Content pool", "Conjunto de contenido"),
+    (
+        "Posts everyone has made end up here, with their tags and engagement. Open a \
+         post's cascade to see who saw, read and commented on it",
+        "Aquí acaban las publicaciones de todos, con sus etiquetas e interacción. Abre \
+         la cascada de una publicación para ver quién la vio, la leyó y la comentó",
+    ),
+    (
+        "These charts sum up the whole population. Try raising the Engagement Weight \
+         and see whether interests narrow",
+        "Estos gráficos resumen a toda la población. Prueba a subir el Peso de la \
+         interacción y mira si los intereses se concentran",
+    ),
+    ("Over to you", "Ahora te toca"),
+    (
+        "That's the tour. Change any slider or load a scenario of your own, and start \
+         the tutorial again from the control panel whenever you like",
+        "Ya has visto todo. Cambia cualquier control o carga tu propio escenario, y \
+         vuelve a empezar el tutorial desde el panel de control cuando quieras",
+    ),
+];
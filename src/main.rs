@@ -1,13 +1,337 @@
 use eframe::egui;
 use egui::Vec2;
+use serde::{Deserialize, Serialize};
 use social_media_sandbox::{
-    models::{AgentState, AgentType, SimulationConfig},
+    classroom::{Classroom, Verdict},
+    engine::{
+        metrics::{DownsampledStream, TickAggregate, TickSeries},
+        Backfill, Inconsistency, Mmr, PromotionKind, RankingMode, RetrievalSource, ScoreComponents,
+    },
+    locale::Locale,
+    models::{
+        bounds, AgentId, AgentType, AgentView, AttentionCurve, AuctionRound, Cascade,
+        CascadeAction, CohortStats, CreatorAnalytics, FairnessGrouping, FollowerHistory, Histogram,
+        Intervention, Nudge, PolicyKind, PopulationMetric, PopulationSummary, Post, PostId,
+        RankingWeight, ReadTimeModel, ReshareTree, Scenario, SegmentStats, SimulationConfig,
+        WealthSample, WellBeingSample, FEED_LENGTH,
+    },
+    profiling::{self, span, FlameProfile, Subsystem},
+    save::SAVE_EXTENSION,
+    tutorial::{Highlight, Tutorial, TutorialProgress, STEPS},
+    worker::SimulationSummary,
     Simulation,
 };
+use std::collections::{BTreeMap, BTreeSet};
+
+#[cfg(feature = "alloc-tracking")]
+#[global_allocator]
+static ALLOCATOR: social_media_sandbox::profiling::CountingAllocator =
+    social_media_sandbox::profiling::CountingAllocator;
+
 pub struct SimulationApp {
     running: bool,
     simulation: Simulation,
-    open_agent_windows: Vec<usize>, // Track multiple open windows
+    open_agent_windows: Vec<AgentId>, // Track multiple open windows
+    creator_tabs: Vec<AgentId>,       // Agent windows showing creator analytics
+    histogram_tag: String,            // Tag whose agreement is plotted
+    tick_aggregate: TickAggregate,    // Aggregate plotted tick by tick
+    open_cascade_windows: Vec<PostId>,
+    pool_view: PoolView, // How content pool cards are laid out
+    show_interventions: bool,
+    show_performance: bool,
+    show_graph: bool,
+    show_plots: bool,
+    plotted: BTreeSet<PlotSeries>, // Series charted in the plot panel
+    plot_view: PlotView,
+    plot_every: u64, // Ticks each point charted over time covers
+    show_display: bool,
+    show_classroom: bool,
+    classroom: Classroom, // Fed by whichever socket transport hosts the class
+    display: DisplaySettings, // Kept in app storage between runs
+    intervention_draft: InterventionDraft,
+    scenario_path: String,
+    pending_scenario: Option<Scenario>, // Loaded but not yet applied
+    scenario_error: Option<String>,
+    save_path: String,
+    save_error: Option<String>,
+    diff_against_default: bool,
+    tutorial: Option<Tutorial>, // Set while the tutorial is running
+    panel_rects: BTreeMap<Highlight, egui::Rect>, // Where each part was drawn this frame
+    flame_profile: Option<FlameProfile>, // The last recording, once stopped
+    consistency: Option<Vec<Inconsistency>>, // The last engine check, if any
+    #[cfg(all(target_arch = "wasm32", feature = "web-worker"))]
+    worker: Option<performance_mode::WorkerHandle>, // Set while in performance mode
+    #[cfg(all(target_arch = "wasm32", feature = "web-worker"))]
+    worker_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum InterventionKind {
+    InjectPost,
+    SetInterest,
+    SetWeight,
+    BanCreator,
+    SetNudge,
+    Promote,
+    LockComments,
+    UnlockComments,
+    CloneAgent,
+}
+
+// Whether content pool cards show posts' raw fields in pool order, or how
+// the engine currently ranks them, hottest first
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PoolView {
+    Operator,
+    Algorithm,
+}
+
+// A series from the metrics collector the plot panel can chart
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum PlotSeries {
+    Aggregate(TickAggregate),
+    Topic(String),
+}
+
+// The steps the plot panel is zoomed and panned to, or None to show the
+// whole of what's recorded
+#[derive(Debug, Clone, Copy, Default)]
+struct PlotView {
+    steps: Option<(f64, f64)>,
+}
+
+// The intervention being composed in the console, with fields for every kind
+// so switching kinds doesn't lose what was typed
+struct InterventionDraft {
+    kind: InterventionKind,
+    agent_id: usize,
+    tags: String,
+    length: i32,
+    breaking_ticks: u64,
+    weight: f32,
+    ranking_weight: RankingWeight,
+    nudge: Nudge,
+    post_id: usize,
+    promotion: PromotionKind,
+    promotion_ticks: u64,
+    copies: usize,
+    at_step: u64,
+}
+
+impl Default for InterventionDraft {
+    fn default() -> Self {
+        Self {
+            kind: InterventionKind::InjectPost,
+            agent_id: 0,
+            tags: String::new(),
+            length: 20,
+            breaking_ticks: 0,
+            weight: 0.5,
+            ranking_weight: RankingWeight::Interest,
+            nudge: Nudge::ReadBeforeShare,
+            post_id: 0,
+            promotion: PromotionKind::Pin,
+            promotion_ticks: 50,
+            copies: 5,
+            at_step: 0,
+        }
+    }
+}
+
+impl InterventionDraft {
+    fn tags(&self) -> Vec<String> {
+        self.tags
+            .split(',')
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect()
+    }
+
+    fn build(&self) -> Intervention {
+        let agent_id = AgentId(self.agent_id);
+        match self.kind {
+            InterventionKind::InjectPost => Intervention::InjectPost {
+                creator_id: agent_id,
+                tags: self.tags(),
+                length: self.length,
+                breaking_ticks: self.breaking_ticks,
+                labels: None,
+            },
+            InterventionKind::SetInterest => Intervention::SetInterest {
+                agent_id,
+                tag: self.tags().into_iter().next().unwrap_or_default(),
+                weight: self.weight,
+            },
+            InterventionKind::SetWeight => Intervention::SetWeight {
+                weight: self.ranking_weight,
+                value: self.weight,
+            },
+            InterventionKind::BanCreator => Intervention::BanCreator(agent_id),
+            InterventionKind::SetNudge => Intervention::SetNudge {
+                nudge: self.nudge,
+                compliance: self.weight,
+            },
+            InterventionKind::Promote => Intervention::Promote {
+                post_id: PostId(self.post_id),
+                kind: self.promotion,
+                ticks: self.promotion_ticks,
+            },
+            InterventionKind::LockComments => Intervention::LockComments(PostId(self.post_id)),
+            InterventionKind::UnlockComments => Intervention::UnlockComments(PostId(self.post_id)),
+            InterventionKind::CloneAgent => Intervention::CloneAgent {
+                agent_id,
+                copies: self.copies,
+            },
+        }
+    }
+}
+
+// Key the display settings are kept under in app storage
+const DISPLAY_KEY: &str = "display";
+
+// How large text is drawn and which colours encode values, chosen for
+// readability rather than per simulation
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+struct DisplaySettings {
+    font_scale: f32,
+    palette: Palette,
+    locale: Locale,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            font_scale: 1.0,
+            palette: Palette::Standard,
+            locale: Locale::English,
+        }
+    }
+}
+
+impl DisplaySettings {
+    // Scales every text style from egui's defaults, in both themes
+    fn apply(&self, ctx: &egui::Context) {
+        let scale = self.font_scale;
+        ctx.all_styles_mut(|style| {
+            style.text_styles = egui::style::default_text_styles()
+                .into_iter()
+                .map(|(text_style, font)| {
+                    (
+                        text_style,
+                        egui::FontId::new(font.size * scale, font.family),
+                    )
+                })
+                .collect();
+        });
+    }
+
+    // Size for text painted straight onto charts, which text styles don't reach
+    fn font_size(&self, size: f32) -> f32 {
+        size * self.font_scale
+    }
+}
+
+// Colours for chart series, agreement and community labels. The colourblind
+// safe palette is Okabe and Ito's, which avoids telling red from green
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+enum Palette {
+    #[default]
+    Standard,
+    ColourblindSafe,
+}
+
+impl Palette {
+    const ALL: [Palette; 2] = [Palette::Standard, Palette::ColourblindSafe];
+
+    const OKABE_ITO: [egui::Color32; 7] = [
+        egui::Color32::from_rgb(86, 180, 233),
+        egui::Color32::from_rgb(230, 159, 0),
+        egui::Color32::from_rgb(0, 158, 115),
+        egui::Color32::from_rgb(204, 121, 167),
+        egui::Color32::from_rgb(240, 228, 66),
+        egui::Color32::from_rgb(213, 94, 0),
+        egui::Color32::from_rgb(0, 114, 178),
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Palette::Standard => "Standard",
+            Palette::ColourblindSafe => "Colourblind safe",
+        }
+    }
+
+    // The colour of the nth series on a chart
+    fn series(&self, index: usize) -> egui::Color32 {
+        match self {
+            Palette::Standard => [
+                egui::Color32::from_rgb(100, 100, 255),
+                egui::Color32::from_rgb(255, 165, 0),
+                egui::Color32::from_rgb(0, 180, 120),
+                egui::Color32::LIGHT_RED,
+                egui::Color32::GOLD,
+            ][index % 5],
+            Palette::ColourblindSafe => Self::OKABE_ITO[index % Self::OKABE_ITO.len()],
+        }
+    }
+
+    // Evenly spaced hues, so neighbouring community labels are easy to tell
+    // apart. The colourblind safe palette has too few colours for that, so
+    // labels past seven share a colour
+    fn category(&self, label: usize) -> egui::Color32 {
+        match self {
+            Palette::Standard => {
+                let hue = (label as f32 * 0.618_034).fract();
+                egui::ecolor::Hsva::new(hue, 0.7, 0.9, 1.0).into()
+            }
+            Palette::ColourblindSafe => Self::OKABE_ITO[label % Self::OKABE_ITO.len()],
+        }
+    }
+
+    // Disagreement at -1.0 through grey at 0.0 to agreement at +1.0
+    fn agreement(&self, agreement: f32) -> egui::Color32 {
+        let (disagree, agree) = self.agreement_ends();
+        let target = if agreement < 0.0 { disagree } else { agree };
+        egui::Color32::GRAY.lerp_to_gamma(target, agreement.abs().min(1.0))
+    }
+
+    // Cold posts in grey, warming to the hottest in the pool at 1.0
+    fn heat(&self, heat: f32) -> egui::Color32 {
+        let hot = match self {
+            Palette::Standard => egui::Color32::from_rgb(255, 90, 0),
+            Palette::ColourblindSafe => Self::OKABE_ITO[5],
+        };
+        egui::Color32::GRAY.lerp_to_gamma(hot, heat.clamp(0.0, 1.0))
+    }
+
+    fn agreement_ends(&self) -> (egui::Color32, egui::Color32) {
+        match self {
+            Palette::Standard => (egui::Color32::RED, egui::Color32::GREEN),
+            Palette::ColourblindSafe => (Self::OKABE_ITO[1], Self::OKABE_ITO[6]),
+        }
+    }
+
+    fn agreement_legend(&self) -> &'static str {
+        match self {
+            Palette::Standard => "Point colour: red disagrees, green agrees",
+            Palette::ColourblindSafe => "Point colour: orange disagrees, blue agrees",
+        }
+    }
+}
+
+impl SimulationApp {
+    // Restores the display settings from the last run, if there was one
+    fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let display: DisplaySettings = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, DISPLAY_KEY))
+            .unwrap_or_default();
+        display.apply(&cc.egui_ctx);
+        Self {
+            display,
+            ..Self::default()
+        }
+    }
 }
 
 impl Default for SimulationApp {
@@ -16,43 +340,122 @@ impl Default for SimulationApp {
             running: false,
             simulation: Simulation::new(SimulationConfig::default()),
             open_agent_windows: Vec::new(),
+            creator_tabs: Vec::new(),
+            histogram_tag: SimulationConfig::default().sample_tags[0].clone(),
+            tick_aggregate: TickAggregate::PostsCreated,
+            open_cascade_windows: Vec::new(),
+            pool_view: PoolView::Operator,
+            show_interventions: false,
+            show_performance: false,
+            show_graph: false,
+            show_plots: false,
+            plotted: BTreeSet::from([
+                PlotSeries::Aggregate(TickAggregate::MeanEngagement),
+                PlotSeries::Aggregate(TickAggregate::OnlineAgents),
+            ]),
+            plot_view: PlotView::default(),
+            plot_every: 1,
+            show_display: false,
+            show_classroom: false,
+            classroom: Classroom::default(),
+            display: DisplaySettings::default(),
+            intervention_draft: InterventionDraft::default(),
+            scenario_path: "scenario.toml".to_string(),
+            pending_scenario: None,
+            scenario_error: None,
+            save_path: format!("simulation.{SAVE_EXTENSION}"),
+            save_error: None,
+            diff_against_default: false,
+            tutorial: None,
+            panel_rects: BTreeMap::new(),
+            flame_profile: None,
+            consistency: None,
+            #[cfg(all(target_arch = "wasm32", feature = "web-worker"))]
+            worker: None,
+            #[cfg(all(target_arch = "wasm32", feature = "web-worker"))]
+            worker_error: None,
         }
     }
 }
 
 impl eframe::App for SimulationApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        self.ui(ctx);
+        let _frame_span = span("frame");
+
+        #[cfg(all(target_arch = "wasm32", feature = "web-worker"))]
+        if self.worker.is_some() {
+            self.performance_mode(ctx);
+            return;
+        }
+
+        {
+            let _ui = span("ui");
+            self.ui(ctx);
+        }
 
         if self.running {
             ctx.request_repaint();
             self.simulation.tick()
         }
     }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, DISPLAY_KEY, &self.display);
+    }
 }
 
 impl SimulationApp {
+    // Charts over time read a downsampled stream once the speed multiplier
+    // makes drawing every tick pointless, while the collector still keeps
+    // every tick for export
+    fn subscribe_metrics(&mut self) {
+        let metrics = &mut self.simulation.engine.metrics;
+        let every = DownsampledStream::every_for(self.simulation.config.speed_multiplier);
+        if every != self.plot_every {
+            metrics.unsubscribe(self.plot_every);
+            self.plot_every = every;
+        }
+        if every > 1 {
+            metrics.subscribe(every);
+        }
+    }
+
     fn ui(&mut self, ctx: &egui::Context) {
-        egui::SidePanel::left("control_panel").show(ctx, |ui| {
-            ui.heading("Configuration");
+        let display = self.display;
+        let locale = display.locale;
+        self.panel_rects.clear();
+        self.subscribe_metrics();
+        if !self.simulation.diagnostics.warnings.is_empty() {
+            egui::TopBottomPanel::top("health").show(ctx, |ui| {
+                for warning in &self.simulation.diagnostics.warnings {
+                    ui.colored_label(egui::Color32::YELLOW, format!("⚠ {}", warning.message()));
+                }
+            });
+        }
+
+        let control_panel = egui::SidePanel::left("control_panel").show(ctx, |ui| {
+            ui.heading(locale.tr("Configuration"));
+
+            egui::ComboBox::from_label(locale.tr("Language"))
+                .selected_text(locale.name())
+                .show_ui(ui, |ui| {
+                    for option in Locale::ALL {
+                        ui.selectable_value(&mut self.display.locale, option, option.name());
+                    }
+                });
 
             if ui
-                .button(if self.running { "Stop" } else { "Start" })
+                .button(locale.tr(if self.running { "Stop" } else { "Start" }))
                 .clicked()
             {
                 self.running = !self.running;
             }
 
-            let current_individuals = self
-                .simulation
-                .agents
-                .iter()
-                .filter(|a| matches!(a.get_type(), AgentType::Individual))
-                .count();
+            let current_individuals = self.simulation.count_agents(AgentType::Individual);
             if ui
                 .add(
                     egui::Slider::new(&mut self.simulation.config.num_individuals, 0..=100)
-                        .text("Num. Individuals"),
+                        .text(locale.tr("Num. Individuals")),
                 )
                 .changed()
             {
@@ -63,16 +466,11 @@ impl SimulationApp {
                 );
             }
 
-            let current_bots = self
-                .simulation
-                .agents
-                .iter()
-                .filter(|a| matches!(a.get_type(), AgentType::Bot))
-                .count();
+            let current_bots = self.simulation.count_agents(AgentType::Bot);
             if ui
                 .add(
                     egui::Slider::new(&mut self.simulation.config.num_bots, 0..=100)
-                        .text("Num. Bots"),
+                        .text(locale.tr("Num. Bots")),
                 )
                 .changed()
             {
@@ -83,16 +481,11 @@ impl SimulationApp {
                 );
             }
 
-            let current_orgs = self
-                .simulation
-                .agents
-                .iter()
-                .filter(|a| matches!(a.get_type(), AgentType::Organisation))
-                .count();
+            let current_orgs = self.simulation.count_agents(AgentType::Organisation);
             if ui
                 .add(
                     egui::Slider::new(&mut self.simulation.config.num_organisations, 0..=100)
-                        .text("Num. Organisations"),
+                        .text(locale.tr("Num. Organisations")),
                 )
                 .changed()
             {
@@ -103,229 +496,2469 @@ impl SimulationApp {
                 );
             }
 
-            ui.add(
-                egui::Slider::new(&mut self.simulation.config.base_content_length, 0..=100)
-                    .text("Base content length"),
-            );
-            ui.add(
-                egui::Slider::new(&mut self.simulation.config.max_post_length, 0..=200)
-                    .text("Max content length"),
-            );
-            ui.add(
-                egui::Slider::new(&mut self.simulation.config.bot_creation_ticks, 0..=20)
-                    .text("Bot create time"),
-            );
+            ui.add(
+                egui::Slider::new(&mut self.simulation.config.base_content_length, 0..=100)
+                    .text(locale.tr("Base content length")),
+            );
+            ui.add(
+                egui::Slider::new(&mut self.simulation.config.max_post_length, 0..=200)
+                    .text(locale.tr("Max content length")),
+            );
+            ui.add(
+                egui::Slider::new(&mut self.simulation.config.bot_creation_ticks, 0..=20)
+                    .text(locale.tr("Bot create time")),
+            );
+
+            ui.add_enabled(
+                false,
+                egui::Slider::new(&mut self.simulation.config.diversity_weight, slider_range("diversity_weight", 1.0))
+                    .text(locale.tr("Diversity Weight"))
+                    .step_by(0.01),
+            )
+            .on_disabled_hover_text(locale.tr("Not used in ranking yet"));
+            ui.add(
+                egui::Slider::new(&mut self.simulation.config.recency_weight, slider_range("recency_weight", 1.0))
+                    .text(locale.tr("Recency Weight"))
+                    .step_by(0.01),
+            );
+            ui.add(
+                egui::Slider::new(&mut self.simulation.config.engagement_weight, slider_range("engagement_weight", 1.0))
+                    .text(locale.tr("Engagement Weight"))
+                    .step_by(0.01),
+            );
+            ui.add(
+                egui::Slider::new(&mut self.simulation.config.interest_decay_rate, slider_range("interest_decay_rate", 1.0))
+                    .text(locale.tr("Interest Decay Rate"))
+                    .step_by(0.001),
+            );
+            if ui
+                .add(
+                    egui::Slider::new(
+                        &mut self.simulation.config.following_weight,
+                        slider_range("following_weight", 1.0),
+                    )
+                    .text(locale.tr("Following Weight"))
+                    .step_by(0.01),
+                )
+                .changed()
+            {
+                self.simulation.engine.config.following_weight =
+                    self.simulation.config.following_weight;
+            }
+            if ui
+                .add(
+                    egui::Slider::new(
+                        &mut self.simulation.config.min_recommendation_score,
+                        0.0..=1.0,
+                    )
+                    .text(locale.tr("Min. Recommendation Score"))
+                    .step_by(0.01),
+                )
+                .changed()
+            {
+                self.simulation.engine.config.min_score =
+                    self.simulation.config.min_recommendation_score;
+            }
+            let backfill = self.simulation.config.recommendation_backfill;
+            egui::ComboBox::from_label(locale.tr("Backfill"))
+                .selected_text(backfill.name())
+                .show_ui(ui, |ui| {
+                    for option in Backfill::ALL {
+                        ui.selectable_value(
+                            &mut self.simulation.config.recommendation_backfill,
+                            option,
+                            option.name(),
+                        );
+                    }
+                });
+            if self.simulation.config.recommendation_backfill != backfill {
+                self.simulation.engine.config.backfill =
+                    self.simulation.config.recommendation_backfill;
+            }
+            if ui
+                .add(
+                    egui::Slider::new(
+                        &mut self.simulation.config.max_feed_posts_per_creator,
+                        0..=10,
+                    )
+                    .text(locale.tr("Feed Cap per Creator (0 = off)")),
+                )
+                .changed()
+            {
+                self.simulation.engine.config.max_per_creator =
+                    self.simulation.config.max_feed_posts_per_creator;
+            }
+            if ui
+                .add(
+                    egui::Slider::new(&mut self.simulation.config.max_feed_posts_per_tag, 0..=10)
+                        .text(locale.tr("Feed Cap per Tag (0 = off)")),
+                )
+                .changed()
+            {
+                self.simulation.engine.config.max_per_tag =
+                    self.simulation.config.max_feed_posts_per_tag;
+            }
+            let ranking = self.simulation.config.ranking_mode;
+            egui::ComboBox::from_label(locale.tr("Ranking"))
+                .selected_text(ranking.name())
+                .show_ui(ui, |ui| {
+                    for option in RankingMode::ALL {
+                        ui.selectable_value(
+                            &mut self.simulation.config.ranking_mode,
+                            option,
+                            option.name(),
+                        );
+                    }
+                });
+            if self.simulation.config.ranking_mode != ranking {
+                self.simulation.engine.config.ranking = self.simulation.config.ranking_mode;
+            }
+            if self.simulation.config.ranking_mode == RankingMode::Sampled
+                && ui
+                    .add(
+                        egui::Slider::new(
+                            &mut self.simulation.config.ranking_temperature,
+                            0.01..=2.0,
+                        )
+                        .text(locale.tr("Temperature"))
+                        .logarithmic(true),
+                    )
+                    .changed()
+            {
+                self.simulation.engine.config.temperature =
+                    self.simulation.config.ranking_temperature;
+            }
+            let read_time = self.simulation.config.read_time.name();
+            egui::ComboBox::from_label(locale.tr("Read time"))
+                .selected_text(read_time)
+                .show_ui(ui, |ui| {
+                    for preset in ReadTimeModel::presets() {
+                        let name = preset.name();
+                        // Picking the current model again keeps its parameters
+                        if ui.selectable_label(name == read_time, name).clicked() && name != read_time
+                        {
+                            self.simulation.config.read_time = preset;
+                        }
+                    }
+                });
+            let mut policy = self.simulation.config.policy;
+            egui::ComboBox::from_label(locale.tr("Behaviour"))
+                .selected_text(locale.tr(policy.name()))
+                .show_ui(ui, |ui| {
+                    for option in PolicyKind::ALL {
+                        ui.selectable_value(&mut policy, option, locale.tr(option.name()));
+                    }
+                });
+            if policy != self.simulation.config.policy {
+                self.simulation.set_policy(policy);
+            }
+            let mut diversify = self.simulation.engine.has_reranker(Mmr::NAME);
+            if ui
+                .checkbox(&mut diversify, locale.tr("Diversify feeds (MMR)"))
+                .changed()
+            {
+                if diversify {
+                    self.simulation.engine.register_reranker(Mmr::default());
+                } else {
+                    self.simulation.engine.remove_reranker(Mmr::NAME);
+                }
+            }
+            ui.label(locale.tr("Retrieval sources"));
+            let mut retrieval_changed = false;
+            ui.horizontal_wrapped(|ui| {
+                for source in RetrievalSource::ALL {
+                    let sources = &mut self.simulation.config.retrieval.sources;
+                    let mut enabled = sources.contains(&source);
+                    if ui.checkbox(&mut enabled, source.name()).changed() {
+                        if enabled {
+                            sources.push(source);
+                        } else {
+                            sources.retain(|s| *s != source);
+                        }
+                        retrieval_changed = true;
+                    }
+                }
+            });
+            retrieval_changed |= ui
+                .add(
+                    egui::Slider::new(&mut self.simulation.config.retrieval.per_source, 1..=500)
+                        .text(locale.tr("Candidates per Source")),
+                )
+                .changed();
+            if retrieval_changed {
+                self.simulation.engine.config.retrieval = self.simulation.config.retrieval.clone();
+            }
+            ui.add(
+                egui::Slider::new(&mut self.simulation.config.position_bias, slider_range("position_bias", 3.0))
+                    .text(locale.tr("Position Bias"))
+                    .step_by(0.05),
+            );
+            ui.add(
+                egui::Slider::new(&mut self.simulation.config.appeal_effect, slider_range("appeal_effect", 2.0))
+                    .text(locale.tr("Appeal Effect"))
+                    .step_by(0.05),
+            );
+            ui.add(
+                egui::Slider::new(
+                    &mut self.simulation.config.ads.per_feed,
+                    0..=FEED_LENGTH - 1,
+                )
+                .text(locale.tr("Ads per Feed")),
+            );
+            ui.add(
+                egui::Slider::new(&mut self.simulation.config.economy.tip_chance, slider_range("economy.tip_chance", 1.0))
+                    .text(locale.tr("Tip Chance"))
+                    .step_by(0.01),
+            );
+            ui.add(
+                egui::Slider::new(&mut self.simulation.config.sharing.like_chance, slider_range("sharing.like_chance", 1.0))
+                    .text(locale.tr("Like Chance"))
+                    .step_by(0.01),
+            );
+            ui.add(
+                egui::Slider::new(&mut self.simulation.config.sharing.reshare_chance, slider_range("sharing.reshare_chance", 1.0))
+                    .text(locale.tr("Reshare Chance"))
+                    .step_by(0.01),
+            );
+            ui.add(
+                egui::Slider::new(&mut self.simulation.config.drift.responsiveness, slider_range("drift.responsiveness", 1.0))
+                    .text(locale.tr("Organisation Responsiveness"))
+                    .step_by(0.01),
+            );
+            let slots_changed = ui
+                .add(
+                    egui::Slider::new(&mut self.simulation.config.marketplace.slots, 0..=3)
+                        .text(locale.tr("Sponsored Slots")),
+                )
+                .changed();
+            if slots_changed {
+                self.simulation.engine.config.sponsored_slots =
+                    self.simulation.config.marketplace.slots;
+            }
+            ui.add(
+                egui::Slider::new(
+                    &mut self.simulation.config.economy.subscription_fee,
+                    slider_range("economy.subscription_fee", 1.0),
+                )
+                .text(locale.tr("Subscription Fee"))
+                .step_by(0.01),
+            );
+            let leakage_changed = ui
+                .add(
+                    egui::Slider::new(&mut self.simulation.config.segments.leakage, slider_range("segments.leakage", 1.0))
+                        .text(locale.tr("Segment Leakage"))
+                        .step_by(0.01),
+                )
+                .changed();
+            if leakage_changed && !self.simulation.config.segments.fractions.is_empty() {
+                self.simulation.engine.config.segment_leakage =
+                    self.simulation.config.segments.leakage;
+            }
+            ui.add(
+                egui::Slider::new(&mut self.simulation.config.adoption.social_join, 0.0..=0.2)
+                    .text(locale.tr("Social Join Rate"))
+                    .step_by(0.005),
+            );
+            ui.add(
+                egui::Slider::new(&mut self.simulation.config.adoption.leave_rate, 0.0..=0.05)
+                    .text(locale.tr("Leave Rate"))
+                    .step_by(0.001),
+            );
+            ui.add(
+                egui::Slider::new(
+                    &mut self.simulation.config.engagement_batch_interval,
+                    1..=100,
+                )
+                .text(locale.tr("Engagement Batch (steps)")),
+            );
+            ui.add(
+                egui::Slider::new(&mut self.simulation.config.tick_rate_ms, 0..=10_000)
+                    .text(locale.tr("Tick Rate (ms)"))
+                    .step_by(10.0),
+            );
+            ui.add(
+                egui::Slider::new(&mut self.simulation.config.speed_multiplier, 1..=1000)
+                    .text(locale.tr("Speed multiplier"))
+                    .logarithmic(true),
+            );
+
+            if ui.button(locale.tr("Reset Simulation")).clicked() {
+                self.simulation = Simulation::new(SimulationConfig::default());
+
+                self.open_agent_windows.clear(); // Clear any open windows
+                self.open_cascade_windows.clear();
+            }
+
+            ui.checkbox(&mut self.show_interventions, locale.tr("Intervention console"));
+            ui.checkbox(&mut self.show_performance, locale.tr("Performance"));
+            ui.checkbox(&mut self.show_graph, locale.tr("Follow graph"));
+            ui.checkbox(&mut self.show_plots, locale.tr("Plots"));
+            ui.checkbox(&mut self.show_display, locale.tr("Display settings"));
+            ui.checkbox(&mut self.show_classroom, locale.tr("Classroom"));
+            if ui.button(locale.tr("Start tutorial")).clicked() {
+                self.start_tutorial();
+            }
+
+            #[cfg(all(target_arch = "wasm32", feature = "web-worker"))]
+            {
+                if ui
+                    .button("Performance mode")
+                    .on_hover_text(
+                        "Run the simulation in a web worker, showing only a summary, so large populations don't freeze the page",
+                    )
+                    .clicked()
+                {
+                    self.enter_performance_mode(ui.ctx());
+                }
+                if let Some(error) = &self.worker_error {
+                    ui.colored_label(egui::Color32::YELLOW, error);
+                }
+            }
+
+            ui.separator();
+            ui.add(
+                egui::Slider::new(
+                    &mut self.simulation.config.community_detection_interval,
+                    0..=500,
+                )
+                .text(locale.tr("Community Detection Interval (0 = off)")),
+            );
+            ui.checkbox(
+                &mut self.simulation.config.background_analytics,
+                locale.tr("Analytics in the background"),
+            )
+            .on_hover_text(locale.tr(
+                "Detect communities and sample creator wealth on another thread, applying each result when next due",
+            ));
+            ui.add(
+                egui::Slider::new(&mut self.simulation.config.survey.interval, 0..=500)
+                    .text(locale.tr("Survey Interval (0 = off)")),
+            );
+            ui.add(
+                egui::Slider::new(&mut self.simulation.config.transparency.interval, 0..=1000)
+                    .text(locale.tr("Transparency Report Interval (0 = off)")),
+            );
+            ui.checkbox(
+                &mut self.simulation.config.fairness.parity,
+                locale.tr("Exposure parity across groups"),
+            );
+            let grouping = self.simulation.config.fairness.grouping;
+            egui::ComboBox::from_label(locale.tr("Fairness groups"))
+                .selected_text(locale.tr(grouping.name()))
+                .show_ui(ui, |ui| {
+                    for option in FairnessGrouping::ALL {
+                        ui.selectable_value(
+                            &mut self.simulation.config.fairness.grouping,
+                            option,
+                            locale.tr(option.name()),
+                        );
+                    }
+                });
+            if let Some(wave) = self.simulation.survey.waves.last() {
+                ui.label(format!(
+                    "{} waves, last at step {} ({:.0}% responded)",
+                    self.simulation.survey.waves.len(),
+                    wave.step,
+                    wave.response_rate() * 100.0
+                ));
+                if ui.button(locale.tr("Copy survey as CSV")).clicked() {
+                    ui.ctx().copy_text(self.simulation.survey.to_csv());
+                }
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut self.scenario_path);
+                if ui.button(locale.tr("Load scenario")).clicked() {
+                    match Scenario::load(&self.scenario_path) {
+                        Ok(scenario) => {
+                            for warning in &scenario.warnings {
+                                log::warn!("{}: {}", self.scenario_path, warning);
+                            }
+                            self.pending_scenario = Some(scenario);
+                            self.scenario_error = None;
+                        }
+                        Err(e) => self.scenario_error = Some(e.to_string()),
+                    }
+                }
+            });
+            if let Some(error) = &self.scenario_error {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut self.save_path);
+                if ui.button(locale.tr("Save")).clicked() {
+                    self.save_error = self
+                        .simulation
+                        .save(&self.save_path)
+                        .err()
+                        .map(|e| e.to_string());
+                }
+                if ui.button(locale.tr("Load")).clicked() {
+                    match Simulation::load(&self.save_path) {
+                        Ok(simulation) => {
+                            self.simulation = simulation;
+                            self.running = false;
+                            self.open_agent_windows.clear();
+                            self.open_cascade_windows.clear();
+                            self.save_error = None;
+                        }
+                        Err(e) => self.save_error = Some(e.to_string()),
+                    }
+                }
+            });
+            if let Some(error) = &self.save_error {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+        });
+        self.panel_rects
+            .insert(Highlight::ControlPanel, control_panel.response.rect);
+
+        self.scenario_diff(ctx);
+
+        self.intervention_console(ctx);
+
+        self.classroom_panel(ctx);
+
+        self.performance_panel(ctx);
+
+        self.graph_view(ctx);
+
+        self.display_settings(ctx);
+
+        let palette = display.palette;
+        if self.show_plots {
+            egui::SidePanel::right("plots").show(ctx, |ui| {
+                ui.heading(locale.tr("Metrics over time"));
+                let every = self.plot_every;
+                if every > 1 {
+                    ui.label(locale.fill("One point per {} ticks", &[every.to_string()]));
+                }
+                let metrics = &self.simulation.engine.metrics;
+                let plotted = &mut self.plotted;
+                let mut toggle = |ui: &mut egui::Ui, series: PlotSeries, label: &str| {
+                    let mut shown = plotted.contains(&series);
+                    if ui.checkbox(&mut shown, label).changed() {
+                        if shown {
+                            plotted.insert(series);
+                        } else {
+                            plotted.remove(&series);
+                        }
+                    }
+                };
+                ui.horizontal_wrapped(|ui| {
+                    for aggregate in TickAggregate::ALL {
+                        toggle(
+                            ui,
+                            PlotSeries::Aggregate(aggregate),
+                            locale.tr(aggregate.name()),
+                        );
+                    }
+                });
+                ui.label(locale.tr("Topic popularity"));
+                ui.horizontal_wrapped(|ui| {
+                    for tag in metrics.topic_popularity.keys() {
+                        toggle(ui, PlotSeries::Topic(tag.clone()), tag);
+                    }
+                });
+
+                let series: Vec<(String, &TickSeries, egui::Color32)> = self
+                    .plotted
+                    .iter()
+                    .filter_map(|series| match series {
+                        PlotSeries::Aggregate(aggregate) => metrics
+                            .series_every(*aggregate, every)
+                            .map(|points| (locale.tr(aggregate.name()).to_string(), points)),
+                        PlotSeries::Topic(tag) => metrics
+                            .topic_every(tag, every)
+                            .map(|points| (tag.clone(), points)),
+                    })
+                    .enumerate()
+                    .map(|(index, (label, points))| (label, points, palette.series(index)))
+                    .collect();
+                draw_time_series(ui, &series, &mut self.plot_view, locale);
+            });
+        }
+        let distributions = egui::SidePanel::right("distributions").show(ctx, |ui| {
+            ui.heading(locale.tr("Distributions"));
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                let metrics = [
+                    PopulationMetric::InterestEntropy,
+                    PopulationMetric::Agreement(self.histogram_tag.clone()),
+                    PopulationMetric::SessionLength,
+                    PopulationMetric::PositionDriven,
+                    PopulationMetric::WellBeing,
+                    PopulationMetric::ScreenTime,
+                    PopulationMetric::LateSessionShare,
+                    PopulationMetric::Arousal,
+                    PopulationMetric::Earnings,
+                    PopulationMetric::FeedTagEntropy,
+                    PopulationMetric::FeedCreatorDiversity,
+                ];
+
+                for metric in metrics {
+                    ui.separator();
+                    ui.label(match &metric {
+                        PopulationMetric::Agreement(tag) => {
+                            locale.fill("Agreement on {}", std::slice::from_ref(tag))
+                        }
+                        metric => locale.tr(&metric.name()).to_string(),
+                    });
+                    if let PopulationMetric::Agreement(_) = metric {
+                        egui::ComboBox::from_id_salt("histogram_tag")
+                            .selected_text(&self.histogram_tag)
+                            .show_ui(ui, |ui| {
+                                for tag in &self.simulation.config.sample_tags {
+                                    ui.selectable_value(&mut self.histogram_tag, tag.clone(), tag);
+                                }
+                            });
+                    }
+                    draw_histogram(
+                        ui,
+                        &metric.histogram(&self.simulation, 10),
+                        locale.tr("agents"),
+                        display,
+                    );
+                }
+
+                let entropy_where = |uses_controls: bool| {
+                    PopulationMetric::InterestEntropy
+                        .mean_where(&self.simulation, |agent| {
+                            agent.uses_feed_controls() == Some(uses_controls)
+                        })
+                        .map_or("-".to_string(), |mean| format!("{:.2}", mean))
+                };
+                ui.label(locale.fill(
+                    "Mean entropy with feed controls: {}, without: {}",
+                    &[entropy_where(true), entropy_where(false)],
+                ));
+
+                ui.separator();
+                ui.heading(locale.tr("Over time"));
+                egui::ComboBox::from_id_salt("tick_aggregate")
+                    .selected_text(locale.tr(self.tick_aggregate.name()))
+                    .show_ui(ui, |ui| {
+                        for option in TickAggregate::ALL {
+                            ui.selectable_value(
+                                &mut self.tick_aggregate,
+                                option,
+                                locale.tr(option.name()),
+                            );
+                        }
+                    });
+                let metrics = &self.simulation.engine.metrics;
+                if let Some(series) = metrics.series_every(self.tick_aggregate, self.plot_every) {
+                    // Plotted as a share of the series' peak, so every
+                    // aggregate fills the chart whatever its units
+                    let values = series.values();
+                    let peak = values.iter().copied().fold(0.0, f32::max);
+                    ui.label(locale.fill(
+                        "Latest {}, peak {}",
+                        &[format_optional(series.latest()), format!("{:.2}", peak)],
+                    ));
+                    let scaled = values
+                        .iter()
+                        .map(|value| value / peak.max(f32::EPSILON))
+                        .collect();
+                    draw_line_chart(
+                        ui,
+                        &[(
+                            locale.tr("Share of peak").to_string(),
+                            scaled,
+                            palette.series(0),
+                        )],
+                        |index| {
+                            series
+                                .points
+                                .get(index)
+                                .map_or(String::new(), |&(step, _)| {
+                                    locale.fill("Step {}", &[step.to_string()])
+                                })
+                        },
+                    );
+                }
+                if ui
+                    .button(locale.tr("Copy per-tick metrics as CSV"))
+                    .clicked()
+                {
+                    ui.ctx().copy_text(metrics.to_csv());
+                }
+
+                ui.separator();
+                ui.heading(locale.tr("Well-being"));
+                let wellbeing = &self.simulation.wellbeing;
+                match wellbeing.latest() {
+                    Some(latest) => {
+                        ui.label(locale.fill(
+                            "Step {}: mean {}, lowest {}, {} ticks online on average",
+                            &[
+                                latest.step.to_string(),
+                                format!("{:.2}", latest.mean_index),
+                                format!("{:.2}", latest.min_index),
+                                format!("{:.0}", latest.mean_screen_time),
+                            ],
+                        ));
+                        ui.label(locale.fill(
+                            "Late-session share {}, conflictual reads {}",
+                            &[
+                                format_optional(latest.late_session_share),
+                                format_optional(latest.conflict_share),
+                            ],
+                        ));
+                        let over_time = |value: fn(&WellBeingSample) -> f32| {
+                            wellbeing.samples.iter().map(value).collect::<Vec<_>>()
+                        };
+                        let series = [
+                            (
+                                locale.tr("Mean well-being").to_string(),
+                                over_time(|sample| sample.mean_index),
+                                palette.series(0),
+                            ),
+                            (
+                                locale.tr("Lowest well-being").to_string(),
+                                over_time(|sample| sample.min_index),
+                                palette.series(1),
+                            ),
+                            (
+                                locale.tr("Late-session share").to_string(),
+                                over_time(|sample| sample.late_session_share.unwrap_or(0.0)),
+                                palette.series(2),
+                            ),
+                            (
+                                locale.tr("Mean arousal").to_string(),
+                                over_time(|sample| sample.mean_arousal),
+                                palette.series(3),
+                            ),
+                        ];
+                        draw_line_chart(ui, &series, |index| {
+                            wellbeing
+                                .samples
+                                .get(index)
+                                .map_or(String::new(), |sample| {
+                                    locale.fill("Step {}", &[sample.step.to_string()])
+                                })
+                        });
+                    }
+                    None => {
+                        ui.label(locale.tr("Not sampled yet"));
+                    }
+                }
+                let nudges = self.simulation.nudge_stats();
+                for nudge in Nudge::ALL {
+                    let counts = nudges.get(nudge);
+                    if counts.shown > 0 {
+                        ui.label(format!(
+                            "\"{}\" nudge: shown {} times, compliance {}",
+                            nudge.name(),
+                            counts.shown,
+                            format_optional(counts.compliance())
+                        ));
+                    }
+                }
+
+                ui.separator();
+                ui.heading(locale.tr("Revenue"));
+                let revenue = &self.simulation.revenue;
+                match revenue.latest() {
+                    Some(latest) => {
+                        ui.label(locale.fill(
+                            "Step {}: {} ad impressions, {} revenue",
+                            &[
+                                latest.step.to_string(),
+                                latest.impressions.to_string(),
+                                format!("{:.2}", latest.revenue),
+                            ],
+                        ));
+                        let per_step = revenue.per_step();
+                        let series = [(
+                            locale.tr("Revenue per step").to_string(),
+                            per_step.iter().map(|(_, revenue)| *revenue).collect(),
+                            palette.series(0),
+                        )];
+                        draw_line_chart(ui, &series, |index| {
+                            per_step.get(index).map_or(String::new(), |(step, _)| {
+                                locale.fill("Step {}", &[step.to_string()])
+                            })
+                        });
+                    }
+                    None => {
+                        ui.label(locale.tr("Not sampled yet"));
+                    }
+                }
+
+                ui.separator();
+                ui.heading(locale.tr("Attention marketplace"));
+                let marketplace = &self.simulation.marketplace;
+                match marketplace.latest() {
+                    Some(latest) => {
+                        ui.label(locale.fill(
+                            "Step {}: {} bidders, price {}, {} of exposures sponsored",
+                            &[
+                                latest.step.to_string(),
+                                latest.bidders.to_string(),
+                                format_optional(latest.price),
+                                format_optional(marketplace.crowding_out()),
+                            ],
+                        ));
+                        let over_time = |value: fn(&AuctionRound) -> Option<f32>| {
+                            marketplace
+                                .rounds
+                                .iter()
+                                .map(|round| value(round).unwrap_or(0.0))
+                                .collect::<Vec<_>>()
+                        };
+                        let series = [
+                            (
+                                locale.tr("Price").to_string(),
+                                over_time(|round| round.price),
+                                palette.series(0),
+                            ),
+                            (
+                                locale.tr("Sponsored share").to_string(),
+                                over_time(AuctionRound::sponsored_share),
+                                palette.series(1),
+                            ),
+                        ];
+                        draw_line_chart(ui, &series, |index| {
+                            marketplace
+                                .rounds
+                                .get(index)
+                                .map_or(String::new(), |round| {
+                                    locale.fill("Step {}", &[round.step.to_string()])
+                                })
+                        });
+                        for (agent_id, bidder) in &marketplace.bidders {
+                            ui.label(locale.fill(
+                                "Agent {} ({}): spent {}, won {} slots",
+                                &[
+                                    agent_id.to_string(),
+                                    locale.tr(bidder.strategy.name()).to_string(),
+                                    format!("{:.2}", bidder.spent),
+                                    bidder.wins.to_string(),
+                                ],
+                            ));
+                        }
+                    }
+                    None => {
+                        ui.label(locale.tr("No sponsored slots"));
+                    }
+                }
+
+                ui.separator();
+                ui.heading(locale.tr("Transparency report"));
+                match self.simulation.transparency.latest() {
+                    Some(report) => {
+                        ui.label(locale.fill(
+                            "Steps {} to {}: {} organic exposures, ad load {}",
+                            &[
+                                report.from_step.to_string(),
+                                report.to_step.to_string(),
+                                report.organic_exposures.to_string(),
+                                format_optional(report.ad_load),
+                            ],
+                        ));
+                        for tag in &report.top_tags {
+                            ui.label(format!("{}: {:.1}%", tag.name, tag.share * 100.0));
+                        }
+                        ui.horizontal(|ui| {
+                            if ui.button(locale.tr("Copy reports as Markdown")).clicked() {
+                                ui.ctx()
+                                    .copy_text(self.simulation.transparency.to_markdown());
+                            }
+                            if ui.button(locale.tr("Copy reports as JSON")).clicked() {
+                                match self.simulation.transparency.to_json() {
+                                    Ok(json) => ui.ctx().copy_text(json),
+                                    Err(e) => log::error!("Failed to serialise reports: {e}"),
+                                }
+                            }
+                        });
+                    }
+                    None => {
+                        ui.label(locale.tr("No reports published yet"));
+                    }
+                }
+
+                ui.separator();
+                ui.heading(locale.tr("Exposure fairness"));
+                let fairness = &self.simulation.config.fairness;
+                match self.simulation.fairness.latest() {
+                    Some(latest) => {
+                        ui.label(locale.fill(
+                            "Step {}: {} exposures by {}",
+                            &[
+                                latest.step.to_string(),
+                                latest.total().to_string(),
+                                locale.tr(fairness.grouping.name()).to_string(),
+                            ],
+                        ));
+                        let groups: BTreeSet<&str> = latest
+                            .exposures
+                            .keys()
+                            .map(String::as_str)
+                            .chain(fairness.targets.iter().map(|target| target.group.as_str()))
+                            .collect();
+                        for group in groups {
+                            let share = latest.share(group);
+                            match fairness.target(group) {
+                                Some(target) => {
+                                    let text = locale.fill(
+                                        "{}: share {}, target {} to {}",
+                                        &[
+                                            group.to_string(),
+                                            format_optional(share),
+                                            format_optional(target.min_share),
+                                            format_optional(target.max_share),
+                                        ],
+                                    );
+                                    if target.gap(share.unwrap_or(0.0)) > 0.0 {
+                                        ui.colored_label(egui::Color32::YELLOW, text);
+                                    } else {
+                                        ui.label(text);
+                                    }
+                                }
+                                None => {
+                                    ui.label(locale.fill(
+                                        "{}: share {}",
+                                        &[group.to_string(), format_optional(share)],
+                                    ));
+                                }
+                            }
+                        }
+                    }
+                    None => {
+                        ui.label(locale.tr("Not sampled yet"));
+                    }
+                }
+
+                ui.separator();
+                ui.heading(locale.tr("Creator economy"));
+                let wealth = &self.simulation.wealth;
+                match wealth.latest() {
+                    Some(latest) => {
+                        ui.label(locale.fill(
+                            "Step {}: {} paid to {} creators, Gini {}, top tenth's share {}",
+                            &[
+                                latest.step.to_string(),
+                                format!("{:.2}", latest.total),
+                                latest.creators.to_string(),
+                                format_optional(latest.gini),
+                                format_optional(latest.top_share),
+                            ],
+                        ));
+                        let over_time = |value: fn(&WealthSample) -> Option<f32>| {
+                            wealth
+                                .samples
+                                .iter()
+                                .map(|sample| value(sample).unwrap_or(0.0))
+                                .collect::<Vec<_>>()
+                        };
+                        let series = [
+                            (
+                                locale.tr("Gini").to_string(),
+                                over_time(|sample| sample.gini),
+                                palette.series(0),
+                            ),
+                            (
+                                locale.tr("Top tenth's share").to_string(),
+                                over_time(|sample| sample.top_share),
+                                palette.series(1),
+                            ),
+                        ];
+                        draw_line_chart(ui, &series, |index| {
+                            wealth.samples.get(index).map_or(String::new(), |sample| {
+                                locale.fill("Step {}", &[sample.step.to_string()])
+                            })
+                        });
+                    }
+                    None => {
+                        ui.label(locale.tr("Not sampled yet"));
+                    }
+                }
+
+                ui.separator();
+                ui.heading(locale.tr("Adoption"));
+                let adoption = &self.simulation.adoption;
+                match adoption.latest() {
+                    Some(latest) => {
+                        ui.label(locale.fill(
+                            "Step {}: {} of {} individuals on the platform, {} joined, {} left",
+                            &[
+                                latest.step.to_string(),
+                                latest.members.to_string(),
+                                latest.individuals.to_string(),
+                                latest.joined.to_string(),
+                                latest.left.to_string(),
+                            ],
+                        ));
+                        if let Some(step) = adoption.inflection() {
+                            ui.label(
+                                locale.fill("Fastest growth up to step {}", &[step.to_string()]),
+                            );
+                        }
+                        let series = [(
+                            locale.tr("Share on the platform").to_string(),
+                            adoption
+                                .samples
+                                .iter()
+                                .map(|sample| sample.share().unwrap_or(0.0))
+                                .collect(),
+                            palette.series(0),
+                        )];
+                        draw_line_chart(ui, &series, |index| {
+                            adoption.samples.get(index).map_or(String::new(), |sample| {
+                                locale.fill("Step {}", &[sample.step.to_string()])
+                            })
+                        });
+                    }
+                    None => {
+                        ui.label(locale.tr("Not sampled yet"));
+                    }
+                }
+
+                ui.separator();
+                ui.heading(locale.tr("Segments"));
+                let segments = SegmentStats::of(&self.simulation);
+                if segments.is_empty() {
+                    ui.label(locale.tr("One shared market"));
+                }
+                for (segment, stats) in &segments {
+                    let home = stats.reads_from.get(segment).copied().unwrap_or(0);
+                    let away = stats.reads_from.values().sum::<usize>() - home;
+                    ui.label(locale.fill(
+                        "{}: {} agents, {} posts, {} of exposures from elsewhere",
+                        &[
+                            segment.clone(),
+                            stats.agents.to_string(),
+                            stats.posts.to_string(),
+                            format_optional(stats.cross_exposure_share()),
+                        ],
+                    ));
+                    ui.label(locale.fill(
+                        "Reads: {} at home, {} from elsewhere",
+                        &[home.to_string(), away.to_string()],
+                    ));
+                }
+
+                ui.separator();
+                ui.heading(locale.tr("Cohorts"));
+                let cohorts = CohortStats::of(&self.simulation);
+                if cohorts.is_empty() {
+                    ui.label(locale.tr("No cohorts"));
+                } else {
+                    egui::Grid::new("cohorts").striped(true).show(ui, |ui| {
+                        for heading in ["Cohort", "Size", "Screen time", "Entropy", "Exposure"] {
+                            ui.strong(locale.tr(heading));
+                        }
+                        ui.end_row();
+                        for (cohort, stats) in &cohorts {
+                            ui.label(cohort);
+                            ui.label(stats.individuals.to_string());
+                            ui.label(format!("{:.0}", stats.screen_time));
+                            ui.label(format!("{:.2}", stats.interest_entropy));
+                            ui.label(format_optional(stats.exposure_diversity));
+                            ui.end_row();
+                        }
+                    });
+                }
+
+                ui.separator();
+                ui.heading(locale.tr("Communities"));
+                match &self.simulation.communities {
+                    Some(communities) => {
+                        ui.label(locale.fill(
+                            "{} communities as of step {}",
+                            &[
+                                communities.stats.len().to_string(),
+                                communities.step.to_string(),
+                            ],
+                        ));
+                        egui::Grid::new("communities").striped(true).show(ui, |ui| {
+                            for heading in ["#", "Size", "Agreement", "Internal"] {
+                                ui.strong(locale.tr(heading));
+                            }
+                            ui.end_row();
+                            for community in &communities.stats {
+                                ui.label(community.label.to_string());
+                                ui.label(community.size.to_string());
+                                ui.label(format!("{:+.2}", community.mean_agreement));
+                                ui.label(
+                                    community.internal_share().map_or("-".to_string(), |share| {
+                                        format!("{:.0}%", share * 100.0)
+                                    }),
+                                );
+                                ui.end_row();
+                            }
+                        });
+                    }
+                    None => {
+                        ui.label(locale.tr("Not detected yet"));
+                    }
+                }
+
+                ui.separator();
+                ui.heading(locale.tr("Served scores"));
+                match self.simulation.engine.telemetry.latest() {
+                    Some(snapshot) => {
+                        ui.label(locale.fill("Steps up to {}", &[snapshot.step.to_string()]));
+                        draw_histogram(ui, &snapshot.histogram, locale.tr("posts served"), display);
+
+                        ui.label(locale.tr("What drives ranking"));
+                        let shares = snapshot.shares();
+                        for (component, (mean, share)) in ScoreComponents::NAMES
+                            .iter()
+                            .zip(snapshot.mean.values().into_iter().zip(shares.values()))
+                        {
+                            ui.add(egui::ProgressBar::new(share).text(format!(
+                                "{component}: {:.0}% (mean {:.3})",
+                                share * 100.0,
+                                mean
+                            )));
+                        }
+                    }
+                    None => {
+                        ui.small(locale.fill(
+                            "Scores are summarised every {} steps",
+                            &[self.simulation.config.score_snapshot_interval.to_string()],
+                        ));
+                    }
+                }
+            });
+        });
+        self.panel_rects
+            .insert(Highlight::Distributions, distributions.response.rect);
+
+        let agents = egui::TopBottomPanel::top("Agents").show(ctx, |ui| {
+            ui.set_min_height(ctx.available_rect().height() / 2.0);
+            ui.set_max_height(ctx.available_rect().height() / 2.0);
+            let views: Vec<AgentView> = self.simulation.agent_views().collect();
+            ui.horizontal_wrapped(|ui| {
+                for (agent_type, states) in &PopulationSummary::of(views.iter().copied()).counts {
+                    egui::Frame::group(ui.style()).show(ui, |ui| {
+                        ui.vertical(|ui| {
+                            ui.strong(format!("{} {}", states.values().sum::<usize>(), agent_type));
+                            for (state, count) in states {
+                                ui.label(format!("{state}: {count}"));
+                            }
+                        });
+                    });
+                }
+            });
+
+            // Only the rows scrolled into view are drawn, so the panel stays
+            // responsive however many agents there are
+            let columns = grid_columns(ui.available_width(), AGENT_TILE.x, ui.spacing());
+            let rows = views.len().div_ceil(columns);
+            egui::ScrollArea::vertical().show_rows(ui, AGENT_TILE.y, rows, |ui, visible| {
+                for row in visible {
+                    ui.horizontal(|ui| {
+                        for &agent in views.iter().skip(row * columns).take(columns) {
+                            let agent_id = agent.id;
+                            ui.allocate_ui(AGENT_TILE, |ui| {
+                                ui.vertical(|ui| {
+                                    ui.add_space(10.0);
+                                    // Top section for icon
+                                    ui.vertical_centered(|ui| {
+                                        let response = match agent.agent_type {
+                                            AgentType::Bot => draw_bot_icon(ui),
+                                            AgentType::Organisation => draw_org_icon(ui),
+                                            AgentType::Individual => draw_person_icon(ui),
+                                        };
+                                        if response.clicked()
+                                            && !self.open_agent_windows.contains(&agent_id)
+                                        {
+                                            self.open_agent_windows.push(agent_id);
+                                        }
+                                    });
+
+                                    ui.with_layout(
+                                        egui::Layout::bottom_up(egui::Align::Center),
+                                        |ui| {
+                                            ui.add(
+                                                egui::ProgressBar::new(
+                                                    agent.progress().unwrap_or(0.0),
+                                                )
+                                                .text(if self.simulation.is_frozen(agent_id) {
+                                                    format!("❄ {}", agent.state_name())
+                                                } else {
+                                                    agent.state_name().to_string()
+                                                }),
+                                            );
+                                            ui.add_space(10.0);
+                                        },
+                                    );
+                                });
+                            });
+                        }
+                    });
+                }
+            });
+        });
+        self.panel_rects
+            .insert(Highlight::Agents, agents.response.rect);
+
+        // Applied once the windows are drawn, as they borrow the simulation
+        let mut freeze_toggles = Vec::new();
+        self.open_agent_windows.retain(|&agent_id| {
+            if let Some(agent) = self.simulation.agent_view(agent_id) {
+                let mut window_open = true;
+                let response = egui::Window::new(format!("Agent {}", agent_id))
+                    .open(&mut window_open)
+                    .show(ctx, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("Type: {:?}", agent.agent_type));
+                            let locale = self.display.locale;
+                            let frozen = self.simulation.is_frozen(agent_id);
+                            if ui
+                                .button(locale.tr(if frozen { "Unfreeze" } else { "Freeze" }))
+                                .on_hover_text(locale.tr(
+                                    "Stop this agent ticking, keeping it and its posts in the simulation",
+                                ))
+                                .clicked()
+                            {
+                                freeze_toggles.push(agent_id);
+                            }
+                        });
+                        let creator_tab = self.creator_tabs.contains(&agent_id);
+                        ui.horizontal(|ui| {
+                            if ui.selectable_label(!creator_tab, "Profile").clicked() {
+                                self.creator_tabs.retain(|&id| id != agent_id);
+                            }
+                            if ui.selectable_label(creator_tab, "Creator").clicked() && !creator_tab
+                            {
+                                self.creator_tabs.push(agent_id);
+                            }
+                        });
+                        ui.separator();
+                        if creator_tab {
+                            creator_analytics_tab(
+                                ui,
+                                &self.simulation.creator_analytics(agent_id),
+                                self.display,
+                            );
+                            return;
+                        }
+                        egui::Frame::new().show(ui, |ui| {
+                            ui.heading(self.display.locale.tr("Interests"));
+                            ui.set_height(200.0);
+                            draw_spider_chart(
+                                ui,
+                                &agent.interests(),
+                                Some(&agent.agreements()),
+                                self.display.font_size(14.0),
+                                self.display.palette,
+                            );
+                        });
+                        ui.small(
+                            self.display
+                                .locale
+                                .tr(self.display.palette.agreement_legend()),
+                        );
+                        if ui.button("Copy profile as JSON").clicked() {
+                            match agent.interest_profile.to_json() {
+                                Ok(json) => ui.ctx().copy_text(json),
+                                Err(e) => log::error!("Failed to serialise profile: {e}"),
+                            }
+                        }
+                        if let Some(history) = self.simulation.interest_history(agent_id) {
+                            ui.label(format!(
+                                "Drift since spawn: {:.2}",
+                                history.drift_since_spawn(agent.interest_profile)
+                            ));
+                            ui.collapsing("History", |ui| {
+                                // Shared axes so snapshots can be compared by eye
+                                let tags = history.tags(agent.interest_profile);
+                                egui::ScrollArea::horizontal().show(ui, |ui| {
+                                    ui.horizontal(|ui| {
+                                        for snapshot in &history.snapshots {
+                                            ui.allocate_ui(Vec2 { x: 120.0, y: 140.0 }, |ui| {
+                                                ui.vertical(|ui| {
+                                                    ui.label(format!("Step {}", snapshot.step));
+                                                    ui.set_min_size(Vec2 { x: 120.0, y: 140.0 });
+                                                    draw_spider_chart(
+                                                        ui,
+                                                        &snapshot.interests(&tags),
+                                                        None,
+                                                        self.display.font_size(9.0),
+                                                        self.display.palette,
+                                                    );
+                                                });
+                                            });
+                                        }
+                                    });
+                                });
+                            });
+                        }
+                        if let Some(drift) = agent.drift.filter(|drift| !drift.is_empty()) {
+                            ui.collapsing("Topic drift", |ui| {
+                                ui.label(format!(
+                                    "Responded to its audience {} times, moving {:.2} of its interest",
+                                    drift.len(),
+                                    drift.total_shift()
+                                ));
+                                egui::Grid::new(("drift", agent_id))
+                                    .striped(true)
+                                    .show(ui, |ui| {
+                                        for heading in ["Step", "Comments", "Shift", "Leading topic"]
+                                        {
+                                            ui.strong(heading);
+                                        }
+                                        ui.end_row();
+
+                                        // Most recent first
+                                        for sample in drift.samples.iter().rev() {
+                                            ui.label(sample.step.to_string());
+                                            ui.label(sample.comments.to_string());
+                                            ui.label(format!("{:.2}", sample.shift));
+                                            ui.label(sample.profile.first().map_or_else(
+                                                String::new,
+                                                |row| {
+                                                    format!(
+                                                        "{} {:.2} (agreement {:+.2})",
+                                                        row.tag, row.weight, row.agreement
+                                                    )
+                                                },
+                                            ));
+                                            ui.end_row();
+                                        }
+                                    });
+                            });
+                        }
+                        ui.separator();
+                        if let Some(community) = agent.community {
+                            ui.label(format!("Community: {}", community));
+                        }
+                        if let Some(segment) = agent.segment {
+                            ui.label(format!("Segment: {}", segment));
+                        }
+                        let bridge = self.simulation.bridges.last().and_then(|bridges| {
+                            bridges
+                                .bridges
+                                .iter()
+                                .find(|bridge| bridge.agent_id == agent.id)
+                        });
+                        if let Some(bridge) = bridge {
+                            ui.label(format!(
+                                "Bridges {} communities (betweenness {:.2})",
+                                bridge.communities_reached, bridge.betweenness
+                            ));
+                        }
+                        if let Some(persona) = agent.persona {
+                            ui.label(format!("Persona: {}", persona));
+                        }
+                        if let Some(cohort) = agent.cohort {
+                            ui.label(format!("Cohort: {}", cohort));
+                        }
+                        ui.heading("Activity");
+                        ui.label(match agent.progress() {
+                            Some(progress) => {
+                                format!("{} ({}%)", agent.state_name(), (progress * 100.0) as i32)
+                            }
+                            None => agent.state_name().to_string(),
+                        });
+                        if agent.member == Some(false) {
+                            ui.label("Not on the platform");
+                        }
+                        if let Some(selections) = agent.selections {
+                            ui.label(format!(
+                                "Selections: {} ({:.1} by relevance, {:.1} by position)",
+                                selections.selections,
+                                selections.relevance_driven(),
+                                selections.position_driven,
+                            ));
+                        }
+                        if let Some(nudges) = agent.nudges {
+                            for nudge in Nudge::ALL {
+                                let counts = nudges.get(nudge);
+                                if counts.shown > 0 {
+                                    ui.label(format!(
+                                        "Nudged \"{}\" {} times, complied {}",
+                                        nudge.name(),
+                                        counts.shown,
+                                        counts.complied
+                                    ));
+                                }
+                            }
+                        }
+                        if let Some(wellbeing) = agent.wellbeing {
+                            ui.label(format!(
+                                "Well-being: {:.2} (mean quality read {})",
+                                wellbeing.index,
+                                format_optional(wellbeing.mean_read_quality()),
+                            ));
+                        }
+                        if let Some(feeds) = agent.feeds.filter(|feeds| !feeds.is_empty()) {
+                            ui.collapsing("Feed archive", |ui| {
+                                ui.label(format!(
+                                    "{} feeds, mean tag entropy {} bits, creator diversity {}",
+                                    feeds.len(),
+                                    format_optional(feeds.mean_tag_entropy()),
+                                    format_optional(feeds.mean_creator_diversity()),
+                                ));
+                                if ui.button("Copy feed archive as JSON").clicked() {
+                                    match feeds.to_json() {
+                                        Ok(json) => ui.ctx().copy_text(json),
+                                        Err(e) => log::error!("Failed to serialise feeds: {e}"),
+                                    }
+                                }
+                                egui::Grid::new(("feeds", agent_id))
+                                    .striped(true)
+                                    .show(ui, |ui| {
+                                        for heading in
+                                            ["Step", "Posts", "Creators", "Tag entropy", "Picked"]
+                                        {
+                                            ui.strong(heading);
+                                        }
+                                        ui.end_row();
+
+                                        // Most recent first
+                                        for feed in feeds.feeds.iter().rev() {
+                                            let picked: Vec<String> = feed
+                                                .chosen_posts()
+                                                .map(|post_id| post_id.to_string())
+                                                .collect();
+                                            ui.label(feed.step.to_string());
+                                            ui.label(feed.slate.len().to_string());
+                                            ui.label(format_optional(feed.creator_diversity()));
+                                            ui.label(format!("{:.2}", feed.tag_entropy()));
+                                            ui.label(picked.join(", "));
+                                            ui.end_row();
+                                        }
+                                    });
+                            });
+                        }
+                        if let Some(decisions) = agent.decisions {
+                            ui.collapsing("Recent decisions", |ui| {
+                                egui::Grid::new(("decisions", agent_id)).striped(true).show(
+                                    ui,
+                                    |ui| {
+                                        for heading in
+                                            ["State", "Choice", "Outcome", "Roll", "Threshold"]
+                                        {
+                                            ui.strong(heading);
+                                        }
+                                        ui.end_row();
+
+                                        // Most recent first
+                                        for record in decisions.records.iter().rev() {
+                                            ui.label(record.state.as_ref());
+                                            ui.label(record.question.as_ref());
+                                            ui.label(record.outcome.as_ref());
+                                            ui.label(format_optional(record.roll));
+                                            ui.label(format_optional(record.threshold));
+                                            ui.end_row();
+                                        }
+                                    },
+                                );
+                            });
+                        }
+                    });
+                if let Some(response) = response {
+                    self.panel_rects
+                        .entry(Highlight::AgentWindow)
+                        .or_insert(response.response.rect);
+                }
+                window_open
+            } else {
+                false
+            }
+        });
+        for agent_id in freeze_toggles {
+            if self.simulation.is_frozen(agent_id) {
+                self.simulation.unfreeze(agent_id);
+            } else {
+                self.simulation.freeze(agent_id);
+            }
+        }
+        let open = &self.open_agent_windows;
+        self.creator_tabs.retain(|agent_id| open.contains(agent_id));
+
+        self.open_cascade_windows.retain(|&post_id| {
+            let Some(post) = self.simulation.engine.get_content_by_id(post_id) else {
+                return false;
+            };
+            let cascade = Cascade::of(post);
+            let steps_run = self.simulation.step_count;
+            let curve = AttentionCurve::of(post, steps_run);
+            let creator_type = self
+                .simulation
+                .agent_view(post.creator_id)
+                .map(|creator| creator.agent_type);
+
+            // Typical curves for posts like this one, to compare its shape against
+            let curves_where = |include: &dyn Fn(&Post) -> bool| {
+                let curves: Vec<AttentionCurve> = self
+                    .simulation
+                    .engine
+                    .content_pool
+                    .iter()
+                    .filter(|other| include(other))
+                    .map(|other| AttentionCurve::of(other, steps_run))
+                    .collect();
+                AttentionCurve::mean_shares(&curves)
+            };
+            let same_kind = curves_where(&|other| other.kind == post.kind);
+            let same_creator_type = curves_where(&|other| {
+                creator_type.is_some()
+                    && self
+                        .simulation
+                        .agent_view(other.creator_id)
+                        .map(|creator| creator.agent_type)
+                        == creator_type
+            });
+            let mut window_open = true;
+
+            egui::Window::new(format!("Cascade of post {}", post_id))
+                .open(&mut window_open)
+                .show(ctx, |ui| {
+                    ui.label(format!("Creator: {}", cascade.creator_id));
+                    // Hidden from the engine, shown here for evaluating it
+                    if let Some(truth) = self.simulation.labels.get(post_id) {
+                        ui.label(format!(
+                            "Ground truth: {} on {}, quality {:.2}",
+                            if truth.veracity { "true" } else { "false" },
+                            truth.topic,
+                            truth.quality
+                        ));
+                    }
+                    ui.label(format!(
+                        "Exposed: {}, read: {}, commented: {}",
+                        cascade.count(|action| *action == CascadeAction::Exposed),
+                        cascade.count(|action| *action == CascadeAction::Read),
+                        cascade.count(|action| matches!(action, CascadeAction::Commented(_))),
+                    ));
+                    if let Some(original) = post.original {
+                        ui.label(format!(
+                            "Reshare of post {original}, passed on by {} agents",
+                            post.shared_by.len()
+                        ));
+                    }
+                    if !post.reshares.is_empty() {
+                        let tree = ReshareTree::of(post, &self.simulation.engine.content_pool);
+                        ui.label(format!(
+                            "Likes: {}, reshares: {}, depth {}, breadth {}",
+                            post.likes.len(),
+                            tree.size(),
+                            tree.depth(),
+                            tree.breadth()
+                        ));
+                    } else if !post.likes.is_empty() {
+                        ui.label(format!("Likes: {}", post.likes.len()));
+                    }
+                    if ui.button("Copy cascade as JSON").clicked() {
+                        match cascade.to_json() {
+                            Ok(json) => ui.ctx().copy_text(json),
+                            Err(e) => log::error!("Failed to serialise cascade: {e}"),
+                        }
+                    }
+                    ui.collapsing("Attention", |ui| {
+                        ui.label(format!(
+                            "Kind: {}, published at step {}",
+                            post.kind.name(),
+                            post.published_step
+                        ));
+                        ui.label(match curve.half_life() {
+                            Some(half_life) => format!("Half-life: {} ticks", half_life),
+                            None => "Half-life: not read yet".to_string(),
+                        });
+                        let mut series =
+                            vec![("This post".to_string(), curve.shares(), palette.series(0))];
+                        series.push((
+                            format!("Mean of {} posts", post.kind.name()),
+                            same_kind.clone(),
+                            palette.series(1),
+                        ));
+                        if let Some(creator_type) = creator_type {
+                            series.push((
+                                format!("Mean of posts by {}s", creator_type.name()),
+                                same_creator_type.clone(),
+                                palette.series(2),
+                            ));
+                        }
+                        draw_line_chart(ui, &series, |tick| format!("Tick {tick}"));
+                    });
+                    ui.separator();
+
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        for branch in &cascade.branches {
+                            ui.collapsing(format!("Agent {}", branch.agent_id), |ui| {
+                                for event in &branch.events {
+                                    ui.label(match event.action {
+                                        CascadeAction::Exposed => {
+                                            format!("Step {}: exposed", event.step)
+                                        }
+                                        CascadeAction::Read => {
+                                            format!("Step {}: read", event.step)
+                                        }
+                                        CascadeAction::Commented(comment_id) => format!(
+                                            "Step {}: commented ({})",
+                                            event.step, comment_id
+                                        ),
+                                        CascadeAction::Liked => {
+                                            format!("Step {}: liked", event.step)
+                                        }
+                                        CascadeAction::Reshared(post_id) => {
+                                            format!("Step {}: reshared ({})", event.step, post_id)
+                                        }
+                                    });
+                                }
+                                if ui.button("Copy branch as JSON").clicked() {
+                                    match branch.to_json() {
+                                        Ok(json) => ui.ctx().copy_text(json),
+                                        Err(e) => {
+                                            log::error!("Failed to serialise branch: {e}")
+                                        }
+                                    }
+                                }
+                            });
+                        }
+                    });
+                });
+
+            window_open
+        });
+
+        let content_pool = egui::TopBottomPanel::bottom("Content Pool").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.heading("Content Pool");
+                ui.radio_value(&mut self.pool_view, PoolView::Operator, "Operator view");
+                ui.radio_value(&mut self.pool_view, PoolView::Algorithm, "Algorithm view");
+            });
+            ui.set_min_height(ctx.available_rect().height());
+
+            // Each post's share of the score that doesn't depend on the
+            // reader, summed before the engine clamps it so that posts past
+            // the cap still order and colour apart
+            let engine = &self.simulation.engine;
+            let now = engine.now();
+            let mut cards: Vec<_> = engine
+                .content_pool
+                .iter()
+                .map(|content| {
+                    let heat = engine.heat(content, now);
+                    (content, heat, heat.recency + heat.engagement)
+                })
+                .collect();
+            let algorithm_view = self.pool_view == PoolView::Algorithm;
+            if algorithm_view {
+                cards.sort_by(|(_, _, a), (_, _, b)| b.total_cmp(a));
+            }
+            let hottest = cards.iter().map(|(_, _, heat)| *heat).fold(0.0, f32::max);
+
+            let mut kinds: BTreeMap<&str, usize> = BTreeMap::new();
+            for (content, _, _) in &cards {
+                *kinds.entry(content.kind.name()).or_default() += 1;
+            }
+            let mean_engagement = cards
+                .iter()
+                .map(|(content, _, _)| content.engagement_score)
+                .sum::<f32>()
+                / cards.len().max(1) as f32;
+            let kinds: Vec<String> = kinds
+                .iter()
+                .map(|(kind, count)| format!("{count} {kind}"))
+                .collect();
+            ui.label(format!(
+                "{} posts ({}), mean engagement {:.2}",
+                cards.len(),
+                kinds.join(", "),
+                mean_engagement
+            ));
+
+            // Drawn a row at a time like the agents, only those in view
+            let columns = grid_columns(ui.available_width(), POST_CARD.x, ui.spacing());
+            let rows = cards.len().div_ceil(columns);
+            egui::ScrollArea::vertical().show_rows(ui, POST_CARD.y, rows, |ui, visible| {
+                for row in visible {
+                    ui.horizontal(|ui| {
+                        for &(content, heat, summed) in
+                            cards.iter().skip(row * columns).take(columns)
+                        {
+                            let interests: Vec<String> =
+                                content.interest_profile.interests.keys().cloned().collect();
+                            let mut frame = egui::Frame::group(ui.style());
+                            if algorithm_view && hottest > 0.0 {
+                                frame = frame.fill(
+                                    display.palette.heat(summed / hottest).gamma_multiply(0.35),
+                                );
+                            }
+
+                            ui.allocate_ui(POST_CARD, |ui| {
+                                frame.show(ui, |ui| {
+                                    ui.label(format!("Content {}", content.id));
+                                    ui.label(format!("Creator: {}", content.creator_id));
+                                    if algorithm_view {
+                                        ui.label(format!("Score: {:.3}", heat.total()));
+                                        ui.label(format!("Recency: {:.3}", heat.recency));
+                                        ui.label(format!("Engagement: {:.3}", heat.engagement));
+                                        if engine.is_breaking(content.id) {
+                                            ui.label("Breaking");
+                                        }
+                                        if engine.banned_creators.contains(&content.creator_id) {
+                                            ui.label("Creator banned");
+                                        }
+                                    } else {
+                                        ui.label(format!("Time: {}", content.timestamp));
+                                        ui.label(format!("Length: {}", content.length));
+                                        ui.label(format!(
+                                            "Engagement: {:.2}",
+                                            content.engagement_score
+                                        ));
+                                    }
+                                    ui.label(format!("Tags: {}", interests.join(", ")));
+                                    if let Some(promotion) = engine.promotions.get(&content.id) {
+                                        ui.label(format!(
+                                            "Promoted: {} exposures, {} reached",
+                                            promotion.exposures,
+                                            promotion.reach()
+                                        ));
+                                    }
+                                    if let Some(lock) = engine.comment_locks.get(&content.id) {
+                                        ui.label(format!(
+                                            "Comments locked at step {} ({})",
+                                            lock.step,
+                                            lock.reason.name()
+                                        ));
+                                    }
+                                    if ui.button("Cascade").clicked()
+                                        && !self.open_cascade_windows.contains(&content.id)
+                                    {
+                                        self.open_cascade_windows.push(content.id);
+                                    }
+                                });
+                            });
+                        }
+                    });
+                }
+            });
+        });
+        self.panel_rects
+            .insert(Highlight::ContentPool, content_pool.response.rect);
+
+        self.tutorial_overlay(ctx);
+    }
+
+    // Shows what a loaded scenario would change before it replaces the
+    // running simulation
+    fn scenario_diff(&mut self, ctx: &egui::Context) {
+        let Some(scenario) = &self.pending_scenario else {
+            return;
+        };
+        let mut apply = false;
+        let mut cancel = false;
+
+        egui::Window::new("Scenario changes").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Compare against:");
+                ui.radio_value(&mut self.diff_against_default, false, "current");
+                ui.radio_value(&mut self.diff_against_default, true, "default");
+            });
+
+            for warning in &scenario.warnings {
+                ui.colored_label(egui::Color32::YELLOW, format!("⚠ {}", warning));
+            }
+            let problems = scenario.validate();
+            for problem in &problems {
+                ui.colored_label(egui::Color32::RED, format!("✖ {}", problem));
+            }
+
+            let base = if self.diff_against_default {
+                SimulationConfig::default()
+            } else {
+                self.simulation.config.clone()
+            };
+            let changes = scenario.diff(&base);
+
+            ui.separator();
+            if changes.is_empty() {
+                ui.label("No parameters changed");
+            }
+            egui::ScrollArea::vertical()
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("scenario_diff")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for heading in ["Parameter", "From", "To"] {
+                                ui.strong(heading);
+                            }
+                            ui.end_row();
+
+                            for change in &changes {
+                                ui.label(&change.key);
+                                ui.label(change.from.as_deref().unwrap_or("-"));
+                                ui.colored_label(
+                                    egui::Color32::LIGHT_GREEN,
+                                    change.to.as_deref().unwrap_or("-"),
+                                );
+                                ui.end_row();
+                            }
+                        });
+                });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                apply = ui
+                    .add_enabled(problems.is_empty(), egui::Button::new("Apply"))
+                    .on_disabled_hover_text("Fix the problems above to apply this scenario")
+                    .clicked();
+                cancel = ui.button("Cancel").clicked();
+            });
+        });
+
+        if apply {
+            if let Some(Ok(config)) = self.pending_scenario.take().map(Scenario::into_config) {
+                self.simulation = Simulation::new(config);
+                self.open_agent_windows.clear();
+                self.open_cascade_windows.clear();
+            }
+        } else if cancel {
+            self.pending_scenario = None;
+        }
+    }
+
+    // Swaps in the tutorial's scenario, stopped, and starts from its first
+    // step
+    fn start_tutorial(&mut self) {
+        self.simulation = Simulation::new(Tutorial::scenario().config);
+        self.running = false;
+        self.open_agent_windows.clear();
+        self.open_cascade_windows.clear();
+        self.tutorial = Some(Tutorial::default());
+    }
+
+    // Dims everything but the part of the app the current tutorial step is
+    // about, with the step's explanation beside it
+    fn tutorial_overlay(&mut self, ctx: &egui::Context) {
+        let Some(tutorial) = &mut self.tutorial else {
+            return;
+        };
+        tutorial.update(TutorialProgress {
+            running: self.running,
+            agent_windows: self.open_agent_windows.len(),
+            step: self.simulation.engine.current_step,
+        });
+        let Some(step) = tutorial.current() else {
+            self.tutorial = None;
+            return;
+        };
+
+        let locale = self.display.locale;
+        let screen = ctx.screen_rect();
+        let target = self
+            .panel_rects
+            .get(&step.highlight)
+            .or_else(|| self.panel_rects.get(&Highlight::Agents))
+            .copied()
+            .unwrap_or(screen);
+
+        let painter = ctx.layer_painter(egui::LayerId::new(
+            egui::Order::Foreground,
+            egui::Id::new("tutorial_dim"),
+        ));
+        let dim = egui::Color32::from_black_alpha(150);
+        for shade in [
+            egui::Rect::from_x_y_ranges(screen.x_range(), screen.top()..=target.top()),
+            egui::Rect::from_x_y_ranges(screen.x_range(), target.bottom()..=screen.bottom()),
+            egui::Rect::from_x_y_ranges(screen.left()..=target.left(), target.y_range()),
+            egui::Rect::from_x_y_ranges(target.right()..=screen.right(), target.y_range()),
+        ] {
+            painter.rect_filled(shade, 0.0, dim);
+        }
+        painter.rect_stroke(
+            target,
+            4.0,
+            egui::Stroke::new(3.0, egui::Color32::GOLD),
+            egui::StrokeKind::Outside,
+        );
+
+        // Beside narrow targets, over the middle of wide ones
+        const CARD_WIDTH: f32 = 320.0;
+        let (position, pivot) = if target.right() + CARD_WIDTH < screen.right() {
+            (
+                target.right_top() + Vec2::splat(12.0),
+                egui::Align2::LEFT_TOP,
+            )
+        } else if target.left() - CARD_WIDTH > screen.left() {
+            (
+                target.left_top() + Vec2::new(-12.0, 12.0),
+                egui::Align2::RIGHT_TOP,
+            )
+        } else {
+            (target.center(), egui::Align2::CENTER_CENTER)
+        };
+
+        let (mut back, mut next, mut exit) = (false, false, false);
+        egui::Area::new(egui::Id::new("tutorial_card"))
+            .order(egui::Order::Tooltip)
+            .fixed_pos(position)
+            .pivot(pivot)
+            .constrain(true)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_width(CARD_WIDTH);
+                    ui.small(locale.fill(
+                        "Step {} of {}",
+                        &[(tutorial.step + 1).to_string(), STEPS.len().to_string()],
+                    ));
+                    ui.heading(locale.tr(step.title));
+                    ui.label(locale.tr(step.text));
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        back = ui
+                            .add_enabled(tutorial.step > 0, egui::Button::new(locale.tr("Back")))
+                            .clicked();
+                        let last = tutorial.step + 1 == STEPS.len();
+                        next = ui
+                            .button(locale.tr(if last { "Finish" } else { "Next" }))
+                            .clicked();
+                        exit = ui.button(locale.tr("Leave tutorial")).clicked();
+                    });
+                });
+            });
+
+        if back {
+            tutorial.back();
+        } else if next {
+            tutorial.next();
+        }
+        if exit || tutorial.is_finished() {
+            self.tutorial = None;
+        }
+    }
+
+    fn intervention_console(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_interventions;
+
+        egui::Window::new("Intervention console")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let draft = &mut self.intervention_draft;
+
+                egui::ComboBox::from_label("Intervention")
+                    .selected_text(format!("{:?}", draft.kind))
+                    .show_ui(ui, |ui| {
+                        for kind in [
+                            InterventionKind::InjectPost,
+                            InterventionKind::SetInterest,
+                            InterventionKind::SetWeight,
+                            InterventionKind::BanCreator,
+                            InterventionKind::SetNudge,
+                            InterventionKind::Promote,
+                            InterventionKind::LockComments,
+                            InterventionKind::UnlockComments,
+                            InterventionKind::CloneAgent,
+                        ] {
+                            ui.selectable_value(&mut draft.kind, kind, format!("{:?}", kind));
+                        }
+                    });
+
+                match draft.kind {
+                    InterventionKind::InjectPost => {
+                        ui.add(egui::DragValue::new(&mut draft.agent_id).prefix("Creator: "));
+                        ui.horizontal(|ui| {
+                            ui.label("Tags:");
+                            ui.text_edit_singleline(&mut draft.tags);
+                        });
+                        ui.add(egui::Slider::new(&mut draft.length, 1..=200).text("Length"));
+                        ui.add(
+                            egui::Slider::new(&mut draft.breaking_ticks, 0..=500)
+                                .text("Breaking for (ticks, 0 = off)"),
+                        );
+                    }
+                    InterventionKind::SetInterest => {
+                        ui.add(egui::DragValue::new(&mut draft.agent_id).prefix("Agent: "));
+                        ui.horizontal(|ui| {
+                            ui.label("Tag:");
+                            ui.text_edit_singleline(&mut draft.tags);
+                        });
+                        ui.add(egui::Slider::new(&mut draft.weight, 0.0..=1.0).text("Weight"));
+                    }
+                    InterventionKind::SetWeight => {
+                        egui::ComboBox::from_label("Ranking weight")
+                            .selected_text(format!("{:?}", draft.ranking_weight))
+                            .show_ui(ui, |ui| {
+                                for weight in [
+                                    RankingWeight::Interest,
+                                    RankingWeight::Recency,
+                                    RankingWeight::Engagement,
+                                ] {
+                                    ui.selectable_value(
+                                        &mut draft.ranking_weight,
+                                        weight,
+                                        format!("{:?}", weight),
+                                    );
+                                }
+                            });
+                        ui.add(egui::Slider::new(&mut draft.weight, 0.0..=1.0).text("Value"));
+                    }
+                    InterventionKind::BanCreator => {
+                        ui.add(egui::DragValue::new(&mut draft.agent_id).prefix("Creator: "));
+                    }
+                    InterventionKind::SetNudge => {
+                        egui::ComboBox::from_label("Nudge")
+                            .selected_text(draft.nudge.name())
+                            .show_ui(ui, |ui| {
+                                for nudge in Nudge::ALL {
+                                    ui.selectable_value(&mut draft.nudge, nudge, nudge.name());
+                                }
+                            });
+                        ui.add(
+                            egui::Slider::new(&mut draft.weight, 0.0..=1.0)
+                                .text("Compliance (0 = off)"),
+                        );
+                    }
+                    InterventionKind::Promote => {
+                        ui.add(egui::DragValue::new(&mut draft.post_id).prefix("Post: "));
+                        ui.horizontal(|ui| {
+                            ui.radio_value(&mut draft.promotion, PromotionKind::Pin, "Pin");
+                            let boosting = matches!(draft.promotion, PromotionKind::Boost(_));
+                            if ui.radio(boosting, "Boost").clicked() && !boosting {
+                                draft.promotion = PromotionKind::Boost(2.0);
+                            }
+                        });
+                        if let PromotionKind::Boost(boost) = &mut draft.promotion {
+                            ui.add(egui::Slider::new(boost, 1.0..=10.0).text("Score multiplier"));
+                        }
+                        ui.add(
+                            egui::Slider::new(&mut draft.promotion_ticks, 1..=500)
+                                .text("Promoted for (ticks)"),
+                        );
+                    }
+                    InterventionKind::LockComments | InterventionKind::UnlockComments => {
+                        ui.add(egui::DragValue::new(&mut draft.post_id).prefix("Post: "));
+                    }
+                    InterventionKind::CloneAgent => {
+                        ui.add(egui::DragValue::new(&mut draft.agent_id).prefix("Agent: "));
+                        ui.add(egui::Slider::new(&mut draft.copies, 1..=100).text("Copies"));
+                    }
+                }
+
+                ui.separator();
+                let current_step = self.simulation.step_count;
+                ui.horizontal(|ui| {
+                    if ui.button("Apply next step").clicked() {
+                        self.simulation
+                            .schedule_intervention(current_step, draft.build());
+                    }
+                    ui.add(egui::DragValue::new(&mut draft.at_step).prefix("Step: "));
+                    if ui.button("Schedule").clicked() {
+                        self.simulation
+                            .schedule_intervention(draft.at_step, draft.build());
+                    }
+                });
+
+                ui.separator();
+                ui.label(format!("Queued (now at step {})", current_step));
+                for scheduled in &self.simulation.interventions.queued {
+                    ui.label(format!(
+                        "{}: {}",
+                        scheduled.step,
+                        scheduled.intervention.describe()
+                    ));
+                }
+                ui.label("Applied");
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for scheduled in self.simulation.interventions.applied.iter().rev() {
+                        let mut line =
+                            format!("{}: {}", scheduled.step, scheduled.intervention.describe());
+                        if !scheduled.created.is_empty() {
+                            let ids: Vec<String> =
+                                scheduled.created.iter().map(ToString::to_string).collect();
+                            line += &format!(" (added {})", ids.join(", "));
+                        }
+                        ui.label(line);
+                    }
+                });
+            });
+
+        self.show_interventions = open;
+    }
+
+    // Students' submitted interventions, for the host to approve into the
+    // running simulation or turn down
+    fn classroom_panel(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_classroom;
+
+        egui::Window::new("Classroom")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let students: Vec<&str> = self
+                    .classroom
+                    .students
+                    .values()
+                    .map(String::as_str)
+                    .collect();
+                if students.is_empty() {
+                    ui.label("No students have joined");
+                } else {
+                    ui.label(format!(
+                        "{} students: {}",
+                        students.len(),
+                        students.join(", ")
+                    ));
+                }
+                if ui.button("Send summary to students").clicked() {
+                    let summary = SimulationSummary::capture(&self.simulation, self.running, 0.0);
+                    self.classroom.broadcast(&summary);
+                }
+
+                ui.separator();
+                ui.label(format!(
+                    "Waiting for approval (now at step {})",
+                    self.simulation.step_count
+                ));
+                let (mut approve, mut reject) = (None, None);
+                for submission in &self.classroom.pending {
+                    ui.group(|ui| {
+                        ui.label(format!(
+                            "{} at step {}: {}",
+                            submission.student,
+                            submission.step,
+                            submission.intervention.describe()
+                        ));
+                        if !submission.note.is_empty() {
+                            ui.small(&submission.note);
+                        }
+                        ui.horizontal(|ui| {
+                            if ui.button("Approve").clicked() {
+                                approve = Some(submission.id);
+                            }
+                            if ui.button("Reject").clicked() {
+                                reject = Some(submission.id);
+                            }
+                        });
+                    });
+                }
+                if let Some(id) = approve {
+                    self.classroom.approve(id, &mut self.simulation);
+                }
+                if let Some(id) = reject {
+                    self.classroom.reject(id);
+                }
+
+                ui.separator();
+                ui.label("Decided");
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for (submission, verdict) in self.classroom.decided.iter().rev() {
+                        let verdict = match verdict {
+                            Verdict::Approved(step) => format!("approved for step {step}"),
+                            Verdict::Rejected => "rejected".to_string(),
+                        };
+                        ui.label(format!(
+                            "{}: {}, {}",
+                            submission.student,
+                            submission.intervention.describe(),
+                            verdict
+                        ));
+                    }
+                });
+            });
+
+        self.show_classroom = open;
+    }
+
+    fn display_settings(&mut self, ctx: &egui::Context) {
+        let before = self.display;
+        let locale = before.locale;
+        egui::Window::new(locale.tr("Display settings"))
+            .id(egui::Id::new("display_settings"))
+            .open(&mut self.show_display)
+            .show(ctx, |ui| {
+                ui.add(
+                    egui::Slider::new(&mut self.display.font_scale, 0.75..=2.0)
+                        .text(locale.tr("Text size"))
+                        .step_by(0.05),
+                );
+                egui::ComboBox::from_label(locale.tr("Palette"))
+                    .selected_text(locale.tr(self.display.palette.name()))
+                    .show_ui(ui, |ui| {
+                        for palette in Palette::ALL {
+                            ui.selectable_value(
+                                &mut self.display.palette,
+                                palette,
+                                locale.tr(palette.name()),
+                            );
+                        }
+                    });
+                let (disagree, agree) = self.display.palette.agreement_ends();
+                ui.horizontal(|ui| {
+                    for index in 0..5 {
+                        ui.colored_label(self.display.palette.series(index), "■");
+                    }
+                    ui.colored_label(disagree, locale.tr("disagree"));
+                    ui.colored_label(agree, locale.tr("agree"));
+                });
+            });
+        if self.display.font_scale != before.font_scale {
+            self.display.apply(ctx);
+        }
+    }
+
+    // Agents around a circle grouped by community, with their follows drawn
+    // between them and bridges between communities ringed
+    fn graph_view(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_graph;
+
+        egui::Window::new("Follow graph")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let bridges = self.simulation.bridges.last();
+                match bridges {
+                    Some(bridges) => {
+                        let previous = self.simulation.bridges.iter().rev().nth(1);
+                        let change = previous.map_or(0, |previous| {
+                            bridges.bridges.len() as i64 - previous.bridges.len() as i64
+                        });
+                        ui.label(format!(
+                            "{} bridges ({change:+}) with total betweenness {:.2} at step {}",
+                            bridges.bridges.len(),
+                            bridges.influence(),
+                            bridges.step
+                        ));
+                    }
+                    None => {
+                        ui.label("Bridges are found when communities are detected");
+                    }
+                }
+                let graph = self.simulation.social_graph();
+                ui.label(format!(
+                    "{} follows, {} followed back",
+                    graph.edge_count(),
+                    format_optional(graph.reciprocity())
+                ));
+
+                let community = |id: AgentId| {
+                    self.simulation
+                        .communities
+                        .as_ref()
+                        .and_then(|communities| communities.label(id))
+                };
+                let mut agents: Vec<AgentId> = self
+                    .simulation
+                    .agent_views()
+                    .map(|agent| agent.id)
+                    .collect();
+                agents.sort_by_key(|id| (community(*id), *id));
+
+                let (rect, response) =
+                    ui.allocate_exact_size(Vec2 { x: 400.0, y: 400.0 }, egui::Sense::click());
+                let painter = ui.painter_at(rect);
+                let radius = rect.width().min(rect.height()) * 0.45;
+                let position = |index: usize| {
+                    let angle = index as f32 * 2.0 * std::f32::consts::PI / agents.len() as f32;
+                    rect.center() + radius * Vec2::angled(angle)
+                };
+                let positions: std::collections::HashMap<AgentId, egui::Pos2> = agents
+                    .iter()
+                    .enumerate()
+                    .map(|(index, id)| (*id, position(index)))
+                    .collect();
+
+                for (follower, followed) in graph.edges() {
+                    if let (Some(from), Some(to)) =
+                        (positions.get(&follower), positions.get(&followed))
+                    {
+                        painter.line_segment(
+                            [*from, *to],
+                            egui::Stroke::new(0.5, egui::Color32::from_gray(90)),
+                        );
+                    }
+                }
+
+                let hovered = response.hover_pos();
+                let mut clicked = None;
+                for (id, pos) in &positions {
+                    let colour = community(*id).map_or(egui::Color32::GRAY, |label| {
+                        self.display.palette.category(label)
+                    });
+                    painter.circle_filled(*pos, 4.0, colour);
+                    if bridges.is_some_and(|bridges| bridges.contains(*id)) {
+                        painter.circle_stroke(
+                            *pos,
+                            7.0,
+                            egui::Stroke::new(2.0, egui::Color32::YELLOW),
+                        );
+                    }
+
+                    if hovered.is_some_and(|hovered| hovered.distance(*pos) < 6.0) {
+                        painter.text(
+                            *pos + Vec2::new(8.0, -8.0),
+                            egui::Align2::LEFT_BOTTOM,
+                            format!("Agent {}", id),
+                            egui::FontId::proportional(self.display.font_size(12.0)),
+                            egui::Color32::WHITE,
+                        );
+                        if response.clicked() {
+                            clicked = Some(*id);
+                        }
+                    }
+                }
 
-            ui.add(
-                egui::Slider::new(&mut self.simulation.config.diversity_weight, 0.0..=1.0)
-                    .text("Diversity Weight")
-                    .step_by(0.01),
-            );
-            ui.add(
-                egui::Slider::new(&mut self.simulation.config.recency_weight, 0.0..=1.0)
-                    .text("Recency Weight")
-                    .step_by(0.01),
-            );
-            ui.add(
-                egui::Slider::new(&mut self.simulation.config.engagement_weight, 0.0..=1.0)
-                    .text("Engagement Weight")
-                    .step_by(0.01),
-            );
-            ui.add(
-                egui::Slider::new(&mut self.simulation.config.interest_decay_rate, 0.0..=1.0)
-                    .text("Interest Decay Rate")
-                    .step_by(0.001),
-            );
-            ui.add(
-                egui::Slider::new(&mut self.simulation.config.tick_rate_ms, 0..=10_000)
-                    .text("Tick Rate (ms)")
-                    .step_by(10.0),
-            );
+                if let Some(id) = clicked {
+                    if !self.open_agent_windows.contains(&id) {
+                        self.open_agent_windows.push(id);
+                    }
+                }
+            });
 
-            if ui.button("Reset Simulation").clicked() {
-                self.simulation = Simulation::new(SimulationConfig::default());
+        self.show_graph = open;
+    }
 
-                self.open_agent_windows.clear(); // Clear any open windows
-            }
-        });
+    fn performance_panel(&mut self, ctx: &egui::Context) {
+        egui::Window::new("Performance")
+            .open(&mut self.show_performance)
+            .show(ctx, |ui| {
+                ui.heading("Flame profile");
+                if profiling::spans_running() {
+                    ui.label("Recording frames and steps…");
+                    if ui.button("Stop recording").clicked() {
+                        self.flame_profile = profiling::stop_spans();
+                    }
+                } else if ui.button("Start recording").clicked() {
+                    profiling::start_spans();
+                }
 
-        egui::TopBottomPanel::top("Agents").show(ctx, |ui| {
-            ui.set_min_height(ctx.available_rect().height() / 2.0);
-            ui.set_max_height(ctx.available_rect().height() / 2.0);
-            egui::ScrollArea::vertical().show(ui, |ui| {
-                ui.horizontal_wrapped(|ui| {
-                    for agent in &self.simulation.agents {
-                        let agent_id = *agent.id();
-                        ui.allocate_ui(Vec2 { x: 150.0, y: 180.0 }, |ui| {
-                            ui.vertical(|ui| {
-                                ui.add_space(10.0);
-                                // Top section for icon
-                                ui.vertical_centered(|ui| {
-                                    let response = match agent.get_type() {
-                                        AgentType::Bot => draw_bot_icon(ui),
-                                        AgentType::Organisation => draw_org_icon(ui),
-                                        AgentType::Individual => draw_person_icon(ui),
-                                    };
-                                    if response.clicked()
-                                        && !self.open_agent_windows.contains(&agent_id)
-                                    {
-                                        self.open_agent_windows.push(agent_id);
-                                    }
-                                });
+                if let Some(profile) = &self.flame_profile {
+                    // The heaviest spans by time including what they call
+                    let mut names: Vec<&str> = profile
+                        .stacks
+                        .keys()
+                        .flat_map(|stack| stack.split(';'))
+                        .collect();
+                    names.sort();
+                    names.dedup();
+                    let mut spans: Vec<(&str, u64)> = names
+                        .into_iter()
+                        .map(|name| (name, profile.inclusive(name)))
+                        .collect();
+                    spans.sort_by_key(|&(_, nanos)| std::cmp::Reverse(nanos));
 
-                                ui.with_layout(
-                                    egui::Layout::bottom_up(egui::Align::Center),
-                                    |ui| {
-                                        match agent.state() {
-                                            AgentState::Offline => {
-                                                ui.add(egui::ProgressBar::new(0.0).text("Offline"));
-                                            }
-                                            AgentState::Scrolling { .. } => {
-                                                ui.add(
-                                                    egui::ProgressBar::new(0.0).text("Scrolling"),
-                                                );
-                                            }
-                                            AgentState::ReadingPost {
-                                                ticks_spent,
-                                                ticks_required,
-                                                ..
-                                            } => {
-                                                let progress =
-                                                    *ticks_spent as f32 / *ticks_required as f32;
-                                                ui.add(
-                                                    egui::ProgressBar::new(progress)
-                                                        .text("Reading Post"),
-                                                );
-                                            }
-                                            AgentState::CreatingPost {
-                                                ticks_spent,
-                                                ticks_required,
-                                                ..
-                                            } => {
-                                                let progress =
-                                                    *ticks_spent as f32 / *ticks_required as f32;
-                                                ui.add(
-                                                    egui::ProgressBar::new(progress)
-                                                        .text("Creating Post"),
-                                                );
-                                            }
-                                            AgentState::ReadingComments {
-                                                ticks_spent,
-                                                ticks_required,
-                                                ..
-                                            } => {
-                                                let progress =
-                                                    *ticks_spent as f32 / *ticks_required as f32;
-                                                ui.add(
-                                                    egui::ProgressBar::new(progress)
-                                                        .text("Reading Comments"),
-                                                );
-                                            }
-                                            AgentState::CreatingComment {
-                                                ticks_spent,
-                                                ticks_required,
-                                                ..
-                                            } => {
-                                                let progress =
-                                                    *ticks_spent as f32 / *ticks_required as f32;
-                                                ui.add(
-                                                    egui::ProgressBar::new(progress)
-                                                        .text("Creating Comment"),
-                                                );
-                                            }
-                                        }
-                                        ui.add_space(10.0);
-                                    },
-                                );
-                            });
-                        });
+                    let total = profile.total().max(1);
+                    egui::Grid::new("flame_spans").striped(true).show(ui, |ui| {
+                        for (name, nanos) in spans.into_iter().take(12) {
+                            ui.label(name);
+                            ui.label(format!("{:.1} ms", nanos as f64 / 1e6));
+                            ui.label(format!("{:.0}%", nanos as f64 / total as f64 * 100.0));
+                            ui.end_row();
+                        }
+                    });
+                    if ui
+                        .button("Copy folded stacks")
+                        .on_hover_text("Paste into inferno-flamegraph or flamegraph.pl")
+                        .clicked()
+                    {
+                        ui.ctx().copy_text(profile.folded());
+                    }
+                }
+                ui.separator();
+
+                ui.heading("Engine consistency");
+                ui.horizontal(|ui| {
+                    if ui.button("Verify").clicked() {
+                        self.consistency = Some(self.simulation.engine.verify());
+                    }
+                    if ui
+                        .button("Rebuild indices")
+                        .on_hover_text("Repairs tag indices, content vectors and per-post state")
+                        .clicked()
+                    {
+                        self.consistency = Some(self.simulation.engine.rebuild_indices());
                     }
                 });
-            });
-        });
+                match &self.consistency {
+                    Some(found) if found.is_empty() => {
+                        ui.label("Every invariant holds");
+                    }
+                    Some(found) => {
+                        for inconsistency in found {
+                            ui.colored_label(egui::Color32::YELLOW, inconsistency.to_string());
+                        }
+                    }
+                    None => {}
+                }
+                ui.separator();
 
-        self.open_agent_windows.retain(|&agent_id| {
-            if let Some(agent) = self.simulation.agents.iter().find(|a| *a.id() == agent_id) {
-                let mut window_open = true;
-                egui::Window::new(format!("Agent {}", agent_id))
-                    .open(&mut window_open)
-                    .show(ctx, |ui| {
-                        ui.label(format!("Type: {:?}", agent.get_type()));
-                        ui.separator();
-                        egui::Frame::new().show(ui, |ui| {
-                            ui.heading("Interests");
-                            ui.set_height(200.0);
-                            draw_spider_chart(
-                                ui,
-                                &agent
-                                    .interest_profile()
-                                    .interests
-                                    .iter()
-                                    .map(|(tag, topic)| (tag.clone(), topic.weighted_interest))
-                                    .collect::<Vec<_>>(),
-                            );
-                        });
-                        ui.separator();
-                        ui.heading("Activity");
-                        ui.label(match &agent.state() {
-                            AgentState::Offline => "Offline".to_string(),
-                            AgentState::Scrolling { .. } => "Scrolling Feed".to_string(),
-                            AgentState::ReadingPost {
-                                ticks_spent,
-                                ticks_required,
-                                ..
-                            } => {
-                                format!(
-                                    "Reading Post ({}%)",
-                                    (*ticks_spent as f32 / *ticks_required as f32 * 100.0) as i32
-                                )
-                            }
-                            AgentState::CreatingPost {
-                                ticks_spent,
-                                ticks_required,
-                                ..
-                            } => {
-                                format!(
-                                    "Creating Post ({}%)",
-                                    (*ticks_spent as f32 / *ticks_required as f32 * 100.0) as i32
-                                )
-                            }
-                            _ => "".to_string(),
-                        });
+                let memory = &self.simulation.memory;
+                let Some(report) = memory.latest() else {
+                    ui.label(format!(
+                        "No memory report yet, one is taken every {} steps",
+                        self.simulation.config.memory_report_interval
+                    ));
+                    return;
+                };
+
+                ui.label(format!("Memory at step {}", report.step));
+                match report.live_bytes {
+                    Some(bytes) => {
+                        ui.label(format!("Live heap: {}", format_bytes(bytes as i64)));
+
+                        ui.heading("Growth since last report");
+                        egui::Grid::new("memory_growth")
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for subsystem in [
+                                    Subsystem::Interventions,
+                                    Subsystem::Agents,
+                                    Subsystem::EngineOps,
+                                    Subsystem::History,
+                                    Subsystem::Diagnostics,
+                                ] {
+                                    let growth =
+                                        report.growth.get(&subsystem).copied().unwrap_or(0);
+                                    // Averaged over every kept report, so steady
+                                    // leaks stand out from one-off spikes
+                                    let total: i64 = memory
+                                        .reports
+                                        .iter()
+                                        .filter_map(|report| report.growth.get(&subsystem))
+                                        .sum();
+                                    ui.label(subsystem.name());
+                                    ui.label(format_bytes(growth));
+                                    ui.label(format!(
+                                        "avg {}",
+                                        format_bytes(total / memory.reports.len() as i64)
+                                    ));
+                                    ui.end_row();
+                                }
+                            });
+                    }
+                    None => {
+                        ui.small("Build with the alloc-tracking feature to see heap growth");
+                    }
+                }
+
+                ui.heading("Retained");
+                egui::Grid::new("memory_retained")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        let previous = memory.reports.iter().rev().nth(1);
+                        for (name, count) in &report.retained {
+                            let change = previous
+                                .and_then(|previous| previous.retained.get(name))
+                                .map(|before| *count as i64 - *before as i64)
+                                .unwrap_or(0);
+                            ui.label(*name);
+                            ui.label(count.to_string());
+                            ui.label(format!("{change:+}"));
+                            ui.end_row();
+                        }
                     });
-                window_open
-            } else {
-                false
+            });
+    }
+
+    // Hands the simulation's config to a web worker and runs it there. Falls
+    // back to running here if the worker can't be started
+    #[cfg(all(target_arch = "wasm32", feature = "web-worker"))]
+    fn enter_performance_mode(&mut self, ctx: &egui::Context) {
+        use social_media_sandbox::worker::WorkerCommand;
+
+        match performance_mode::WorkerHandle::spawn(ctx.clone()) {
+            Ok(worker) => {
+                worker.send(&WorkerCommand::Reset(Box::new(
+                    self.simulation.config.clone(),
+                )));
+                if self.running {
+                    worker.send(&WorkerCommand::Run);
+                }
+                self.worker = Some(worker);
+                self.worker_error = None;
             }
-        });
+            Err(e) => {
+                log::warn!("Could not start the simulation worker: {e}");
+                self.worker_error = Some(format!("Performance mode is unavailable: {e}"));
+            }
+        }
+    }
 
-        egui::TopBottomPanel::bottom("Content Pool").show(ctx, |ui| {
-            ui.heading("Content Pool");
-            ui.set_min_height(ctx.available_rect().height());
-            egui::ScrollArea::vertical().show(ui, |ui| {
-                ui.horizontal_wrapped(|ui| {
-                    for content in &self.simulation.engine.content_pool {
-                        let interests: Vec<String> = content
-                            .interest_profile
-                            .interests
-                            .iter()
-                            .map(|(tag, _)| tag.clone())
-                            .collect();
+    // In place of the whole UI while the simulation runs in a worker
+    #[cfg(all(target_arch = "wasm32", feature = "web-worker"))]
+    fn performance_mode(&mut self, ctx: &egui::Context) {
+        use social_media_sandbox::worker::WorkerCommand;
 
-                        ui.allocate_ui(Vec2 { x: 150.0, y: 150.0 }, |ui| {
-                            ui.group(|ui| {
-                                ui.label(format!("Content {}", content.id));
-                                ui.label(format!("Creator: {}", content.creator_id));
-                                ui.label(format!("Time: {}", content.timestamp));
-                                ui.label(format!("Length: {}", content.length));
-                                ui.label(format!("Tags: {}", interests.join(", ")));
-                                ui.label(format!("Engagement: {:.2}", content.engagement_score));
-                            });
-                        });
+        let Some(worker) = &self.worker else {
+            return;
+        };
+        if worker.failed() {
+            log::warn!("The simulation worker failed, running the simulation here instead");
+            self.worker = None;
+            self.worker_error =
+                Some("The simulation worker failed to load, so it runs here instead".to_string());
+            return;
+        }
+
+        let summary = worker.latest();
+        let running = summary.as_ref().is_some_and(|summary| summary.running);
+        let mut leave = false;
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading("Performance mode");
+            ui.label(
+                "The simulation is running in a web worker and only a summary is shown. Leave performance mode to inspect agents; the run here picks up from where it was when you entered.",
+            );
+
+            ui.horizontal(|ui| {
+                if ui.button(if running { "Pause" } else { "Run" }).clicked() {
+                    worker.send(if running {
+                        &WorkerCommand::Pause
+                    } else {
+                        &WorkerCommand::Run
+                    });
+                }
+                if ui.add_enabled(!running, egui::Button::new("Step")).clicked() {
+                    worker.send(&WorkerCommand::Step);
+                }
+                if ui.button("Reset").clicked() {
+                    worker.send(&WorkerCommand::Reset(Box::new(
+                        self.simulation.config.clone(),
+                    )));
+                }
+                if ui.button("Leave performance mode").clicked() {
+                    leave = true;
+                }
+            });
+            ui.separator();
+
+            let Some(summary) = summary else {
+                ui.spinner();
+                return;
+            };
+            egui::Grid::new("worker_summary")
+                .striped(true)
+                .show(ui, |ui| {
+                    let rows = [
+                        ("Step", summary.step.to_string()),
+                        ("Steps per second", format!("{:.0}", summary.step_rate)),
+                        ("Individuals", summary.individuals.to_string()),
+                        ("Bots", summary.bots.to_string()),
+                        ("Organisations", summary.organisations.to_string()),
+                        ("Posts", summary.posts.to_string()),
+                        (
+                            "Mean interest entropy",
+                            format_optional(summary.interest_entropy),
+                        ),
+                        (
+                            "Mean session length",
+                            format_optional(summary.session_length),
+                        ),
+                        (
+                            "Mean position-driven share",
+                            format_optional(summary.position_driven),
+                        ),
+                    ];
+                    for (name, value) in rows {
+                        ui.label(name);
+                        ui.label(value);
+                        ui.end_row();
                     }
                 });
-            });
+            for warning in &summary.warnings {
+                ui.colored_label(egui::Color32::YELLOW, format!("⚠ {warning}"));
+            }
         });
+
+        if leave {
+            self.worker = None;
+        }
     }
 
     fn handle_agent_count_change(
@@ -472,7 +3105,15 @@ fn draw_person_icon(ui: &mut egui::Ui) -> egui::Response {
     response
 }
 
-fn draw_spider_chart(ui: &mut egui::Ui, interests: &[(String, f32)]) {
+// `agreements`, when given, colours each spoke's point and label from
+// disagreement (-1.0) through grey to agreement (+1.0)
+fn draw_spider_chart(
+    ui: &mut egui::Ui,
+    interests: &[(String, f32)],
+    agreements: Option<&[f32]>,
+    label_size: f32,
+    palette: Palette,
+) {
     let painter = ui.painter();
     let rect = ui.available_rect_before_wrap();
     let center = rect.center();
@@ -500,7 +3141,7 @@ fn draw_spider_chart(ui: &mut egui::Ui, interests: &[(String, f32)]) {
     }
 
     // Draw spokes
-    for i in 0..n_points {
+    for (i, (tag, _)) in interests.iter().enumerate() {
         let angle =
             (i as f32 * 2.0 * std::f32::consts::PI / n_points as f32) - std::f32::consts::PI / 2.0;
         painter.line_segment(
@@ -522,9 +3163,13 @@ fn draw_spider_chart(ui: &mut egui::Ui, interests: &[(String, f32)]) {
         painter.text(
             label_pos,
             egui::Align2::CENTER_CENTER,
-            &interests[i].0,
-            egui::FontId::proportional(14.0),
-            egui::Color32::WHITE,
+            tag,
+            egui::FontId::proportional(label_size),
+            agreements
+                .and_then(|agreements| agreements.get(i))
+                .map_or(egui::Color32::WHITE, |agreement| {
+                    palette.agreement(*agreement)
+                }),
         );
     }
 
@@ -551,11 +3196,524 @@ fn draw_spider_chart(ui: &mut egui::Ui, interests: &[(String, f32)]) {
         })
         .collect();
 
+    let colour = palette.series(0);
     painter.add(egui::Shape::convex_polygon(
+        points.clone(),
+        colour.gamma_multiply(0.4),
+        egui::Stroke::new(2.0, colour),
+    ));
+
+    if let Some(agreements) = agreements {
+        for (point, agreement) in points.iter().zip(agreements) {
+            painter.circle_filled(*point, 4.0, palette.agreement(*agreement));
+        }
+    }
+}
+
+// A slider's range for a config key, the key's valid range up to `soft_max`
+// when it has no upper limit
+fn slider_range(key: &str, soft_max: f32) -> std::ops::RangeInclusive<f32> {
+    bounds(key)
+        .expect("sliders are only drawn for keys with bounds")
+        .slider(soft_max)
+}
+
+fn format_bytes(bytes: i64) -> String {
+    let magnitude = bytes.unsigned_abs() as f64;
+    if magnitude >= 1024.0 * 1024.0 {
+        format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+    } else if magnitude >= 1024.0 {
+        format!("{:.1} KiB", bytes as f64 / 1024.0)
+    } else {
+        format!("{bytes} B")
+    }
+}
+
+// Values over the run on shared axes, each series with its own colour.
+// Scrolling zooms about the pointer, dragging pans and double-clicking goes
+// back to showing everything recorded
+fn draw_time_series(
+    ui: &mut egui::Ui,
+    series: &[(String, &TickSeries, egui::Color32)],
+    view: &mut PlotView,
+    locale: Locale,
+) {
+    let recorded = series
+        .iter()
+        .flat_map(|(_, points, _)| {
+            points
+                .points
+                .front()
+                .into_iter()
+                .chain(points.points.back())
+        })
+        .map(|&(step, _)| step as f64);
+    let (first, last) = recorded.fold((f64::MAX, f64::MIN), |(first, last), step| {
+        (first.min(step), last.max(step))
+    });
+    if first > last {
+        ui.label(locale.tr("Nothing recorded yet"));
+        return;
+    }
+    let everything = (first, last.max(first + 1.0));
+
+    let (rect, response) = ui.allocate_exact_size(
+        Vec2 {
+            x: ui.available_width(),
+            y: 200.0,
+        },
+        egui::Sense::click_and_drag(),
+    );
+    let (mut start, mut end) = view.steps.unwrap_or(everything);
+    if let Some(pos) = response.hover_pos() {
+        let scroll = ui.input(|input| input.smooth_scroll_delta.y);
+        if scroll != 0.0 {
+            let at = start + (end - start) * ((pos.x - rect.left()) / rect.width()) as f64;
+            let factor = (-scroll as f64 / 200.0).exp();
+            start = at - (at - start) * factor;
+            end = (at + (end - at) * factor).max(start + 1.0);
+            view.steps = Some((start, end));
+        }
+    }
+    if response.dragged() {
+        let shift = -response.drag_delta().x as f64 / rect.width() as f64 * (end - start);
+        start += shift;
+        end += shift;
+        view.steps = Some((start, end));
+    }
+    if response.double_clicked() {
+        view.steps = None;
+        (start, end) = everything;
+    }
+
+    let visible = |&&(step, _): &&(u64, f32)| (start..=end).contains(&(step as f64));
+    let (low, high) = series
+        .iter()
+        .flat_map(|(_, points, _)| points.points.iter().filter(visible))
+        .fold((0.0_f32, f32::EPSILON), |(low, high), &(_, value)| {
+            (low.min(value), high.max(value))
+        });
+    let point = |step: u64, value: f32| {
+        egui::pos2(
+            rect.left() + rect.width() * ((step as f64 - start) / (end - start)) as f32,
+            rect.bottom() - rect.height() * (value - low) / (high - low),
+        )
+    };
+
+    let painter = ui.painter_at(rect);
+    painter.rect_stroke(
+        rect,
+        0.0,
+        egui::Stroke::new(1.0, egui::Color32::GRAY),
+        egui::StrokeKind::Inside,
+    );
+    for (_, points, colour) in series {
+        let line: Vec<egui::Pos2> = points
+            .points
+            .iter()
+            .filter(visible)
+            .map(|&(step, value)| point(step, value))
+            .collect();
+        painter.add(egui::Shape::line(line, egui::Stroke::new(1.5, *colour)));
+    }
+    let font = egui::FontId::proportional(10.0);
+    let text_colour = ui.visuals().weak_text_color();
+    for (pos, align, text) in [
+        (
+            rect.left_bottom(),
+            egui::Align2::LEFT_BOTTOM,
+            format!("{start:.0}"),
+        ),
+        (
+            rect.right_bottom(),
+            egui::Align2::RIGHT_BOTTOM,
+            format!("{end:.0}"),
+        ),
+        (
+            rect.left_top(),
+            egui::Align2::LEFT_TOP,
+            format!("{high:.2}"),
+        ),
+    ] {
+        painter.text(pos, align, text, font.clone(), text_colour);
+    }
+
+    if let Some(pos) = response.hover_pos() {
+        let step = (start + (end - start) * ((pos.x - rect.left()) / rect.width()) as f64).round();
+        painter.vline(
+            pos.x,
+            rect.y_range(),
+            egui::Stroke::new(1.0, egui::Color32::GRAY),
+        );
+        let values: Vec<String> = series
+            .iter()
+            .filter_map(|(label, points, _)| {
+                let nearest = points
+                    .points
+                    .iter()
+                    .min_by_key(|&&(at, _)| (at as f64 - step).abs() as u64)?;
+                Some(format!("{label}: {:.2}", nearest.1))
+            })
+            .collect();
+        response.on_hover_text(format!(
+            "{}\n{}",
+            locale.fill("Step {}", &[format!("{step:.0}")]),
+            values.join("\n")
+        ));
+    }
+
+    for (label, _, colour) in series {
+        ui.colored_label(*colour, label);
+    }
+    ui.label(locale.tr("Scroll to zoom, drag to pan, double-click to reset"));
+}
+
+// Sizes of the tiles in the agent grid and the cards in the content pool
+const AGENT_TILE: Vec2 = Vec2 { x: 150.0, y: 180.0 };
+const POST_CARD: Vec2 = Vec2 { x: 150.0, y: 150.0 };
+
+// How many tiles `tile_width` wide fit across `width`, at least one
+fn grid_columns(width: f32, tile_width: f32, spacing: &egui::style::Spacing) -> usize {
+    let gap = spacing.item_spacing.x;
+    (((width + gap) / (tile_width + gap)).floor() as usize).max(1)
+}
+
+fn format_optional(value: Option<f32>) -> String {
+    value.map_or_else(|| "-".to_string(), |value| format!("{:.2}", value))
+}
+
+fn draw_histogram(ui: &mut egui::Ui, histogram: &Histogram, unit: &str, display: DisplaySettings) {
+    let (rect, response) =
+        ui.allocate_exact_size(Vec2 { x: 200.0, y: 100.0 }, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+
+    painter.rect_stroke(
+        rect,
+        0.0,
+        egui::Stroke::new(1.0, egui::Color32::GRAY),
+        egui::StrokeKind::Inside,
+    );
+
+    let max_count = histogram.counts.iter().copied().max().unwrap_or(0).max(1);
+    let bar_width = rect.width() / histogram.counts.len() as f32;
+
+    for (i, &count) in histogram.counts.iter().enumerate() {
+        let height = rect.height() * count as f32 / max_count as f32;
+        let left = rect.left() + i as f32 * bar_width;
+        painter.rect_filled(
+            egui::Rect::from_min_max(
+                egui::pos2(left + 1.0, rect.bottom() - height),
+                egui::pos2(left + bar_width - 1.0, rect.bottom()),
+            ),
+            0.0,
+            display.palette.series(0),
+        );
+    }
+
+    // Show the bin under the cursor
+    if let Some(pos) = response.hover_pos() {
+        let bin = (((pos.x - rect.left()) / bar_width) as usize).min(histogram.counts.len() - 1);
+        let (low, high) = histogram.bin_range(bin);
+        response.on_hover_text(format!(
+            "{:.2} to {:.2}: {}",
+            low, high, histogram.counts[bin]
+        ));
+    }
+
+    ui.label(display.locale.fill(
+        "{} {}, {} to {}",
+        &[
+            histogram.total().to_string(),
+            unit.to_string(),
+            format!("{:.2}", histogram.min),
+            format!("{:.2}", histogram.max),
+        ],
+    ));
+}
+
+// A creator's dashboard: how far its posts got, how its audience engaged and
+// what that audience is interested in
+fn creator_analytics_tab(
+    ui: &mut egui::Ui,
+    analytics: &CreatorAnalytics,
+    display: DisplaySettings,
+) {
+    egui::Grid::new(("creator analytics", analytics.creator_id))
+        .striped(true)
+        .show(ui, |ui| {
+            let rows = [
+                ("Posts", analytics.posts.to_string()),
+                ("Reach", format!("{} agents", analytics.reach)),
+                ("Exposures", analytics.exposures.to_string()),
+                ("Reads", analytics.reads.to_string()),
+                ("Comments", analytics.comments.to_string()),
+                ("Likes", analytics.likes.to_string()),
+                ("Reshares", analytics.reshares.to_string()),
+                (
+                    "Engagement rate",
+                    format_optional(analytics.engagement_rate),
+                ),
+                (
+                    "Followers",
+                    format!(
+                        "{} ({:+} since spawn)",
+                        analytics.followers,
+                        analytics.follower_history.growth()
+                    ),
+                ),
+            ];
+            for (label, value) in rows {
+                ui.label(label);
+                ui.label(value);
+                ui.end_row();
+            }
+            if let Some(earnings) = analytics.earnings {
+                ui.label("Earnings");
+                ui.label(format!(
+                    "{:.2} ({} tips, {} subscribers, {:.2} per step)",
+                    earnings.total(),
+                    earnings.tip_count,
+                    earnings.subscribers,
+                    earnings.income
+                ));
+                ui.end_row();
+            }
+        });
+
+    ui.heading(display.locale.tr("Follower growth"));
+    draw_follower_history(ui, &analytics.follower_history, display.palette);
+
+    ui.heading(display.locale.tr("Audience interests"));
+    if analytics.audience_interests.is_empty() {
+        ui.label(
+            display
+                .locale
+                .tr("Nobody has read this creator's posts yet"),
+        );
+    } else {
+        egui::Frame::new().show(ui, |ui| {
+            ui.set_height(200.0);
+            draw_spider_chart(
+                ui,
+                &analytics.audience_interests,
+                None,
+                display.font_size(14.0),
+                display.palette,
+            );
+        });
+    }
+}
+
+fn draw_follower_history(ui: &mut egui::Ui, history: &FollowerHistory, palette: Palette) {
+    let (rect, response) = ui.allocate_exact_size(Vec2 { x: 240.0, y: 80.0 }, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+
+    painter.rect_stroke(
+        rect,
+        0.0,
+        egui::Stroke::new(1.0, egui::Color32::GRAY),
+        egui::StrokeKind::Inside,
+    );
+
+    let (Some(first), Some(last)) = (history.snapshots.first(), history.snapshots.last()) else {
+        return;
+    };
+    let steps = (last.step - first.step).max(1) as f32;
+    let max_followers = history
+        .snapshots
+        .iter()
+        .map(|snapshot| snapshot.followers)
+        .max()
+        .unwrap_or(0)
+        .max(1) as f32;
+    let points: Vec<egui::Pos2> = history
+        .snapshots
+        .iter()
+        .map(|snapshot| {
+            egui::pos2(
+                rect.left() + rect.width() * (snapshot.step - first.step) as f32 / steps,
+                rect.bottom() - rect.height() * snapshot.followers as f32 / max_followers,
+            )
+        })
+        .collect();
+    painter.add(egui::Shape::line(
         points,
-        egui::Color32::from_rgba_premultiplied(100, 100, 255, 100),
-        egui::Stroke::new(2.0, egui::Color32::BLUE),
+        egui::Stroke::new(1.5, palette.series(0)),
     ));
+
+    // Show the snapshot nearest the cursor
+    if let Some(pos) = response.hover_pos() {
+        let step = first.step as f32 + steps * (pos.x - rect.left()) / rect.width();
+        if let Some(snapshot) = history
+            .snapshots
+            .iter()
+            .min_by_key(|snapshot| (snapshot.step as f32 - step).abs() as u64)
+        {
+            response.on_hover_text(format!(
+                "Step {}: {} followers",
+                snapshot.step, snapshot.followers
+            ));
+        }
+    }
+}
+
+// Shares over time, such as reads per tick since publication, each series
+// overlaid on shared axes with its own colour. `x_label` names a point on the
+// x axis for the hover text
+fn draw_line_chart(
+    ui: &mut egui::Ui,
+    series: &[(String, Vec<f32>, egui::Color32)],
+    x_label: impl Fn(usize) -> String,
+) {
+    let (rect, response) =
+        ui.allocate_exact_size(Vec2 { x: 240.0, y: 100.0 }, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+
+    painter.rect_stroke(
+        rect,
+        0.0,
+        egui::Stroke::new(1.0, egui::Color32::GRAY),
+        egui::StrokeKind::Inside,
+    );
+
+    let ticks = series
+        .iter()
+        .map(|(_, shares, _)| shares.len())
+        .max()
+        .unwrap_or(0)
+        .max(2);
+    let max_share = series
+        .iter()
+        .flat_map(|(_, shares, _)| shares.iter().copied())
+        .fold(0.0, f32::max)
+        .max(f32::EPSILON);
+    let point = |tick: usize, share: f32| {
+        egui::pos2(
+            rect.left() + rect.width() * tick as f32 / (ticks - 1) as f32,
+            rect.bottom() - rect.height() * share / max_share,
+        )
+    };
+
+    for (_, shares, colour) in series {
+        let points: Vec<egui::Pos2> = shares
+            .iter()
+            .enumerate()
+            .map(|(tick, &share)| point(tick, share))
+            .collect();
+        painter.add(egui::Shape::line(points, egui::Stroke::new(1.5, *colour)));
+    }
+
+    if let Some(pos) = response.hover_pos() {
+        let tick = (((pos.x - rect.left()) / rect.width()) * (ticks - 1) as f32).round() as usize;
+        let shares: Vec<String> = series
+            .iter()
+            .filter_map(|(label, shares, _)| {
+                shares
+                    .get(tick)
+                    .map(|share| format!("{label}: {:.0}%", share * 100.0))
+            })
+            .collect();
+        response.on_hover_text(format!("{}\n{}", x_label(tick), shares.join("\n")));
+    }
+
+    for (label, _, colour) in series {
+        ui.colored_label(*colour, label);
+    }
+}
+
+// The app's end of the web worker that runs the simulation in performance
+// mode. Messages are JSON strings in both directions
+#[cfg(all(target_arch = "wasm32", feature = "web-worker"))]
+mod performance_mode {
+    use eframe::wasm_bindgen::{closure::Closure, JsCast as _, JsValue};
+    use social_media_sandbox::worker::{SimulationSummary, WorkerCommand};
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    };
+
+    // The loader trunk writes for the sandbox_worker binary
+    const WORKER_SCRIPT: &str = "./sandbox_worker_loader.js";
+
+    // The worker is stopped when this is dropped
+    pub struct WorkerHandle {
+        worker: web_sys::Worker,
+        latest: Rc<RefCell<Option<SimulationSummary>>>,
+        failed: Rc<Cell<bool>>,
+        _onmessage: Closure<dyn FnMut(web_sys::MessageEvent)>,
+        _onerror: Closure<dyn FnMut(JsValue)>,
+    }
+
+    impl WorkerHandle {
+        pub fn spawn(ctx: egui::Context) -> Result<Self, String> {
+            let worker = web_sys::Worker::new(WORKER_SCRIPT).map_err(|e| format!("{e:?}"))?;
+            let latest = Rc::new(RefCell::new(None));
+            let failed = Rc::new(Cell::new(false));
+
+            let onmessage = Closure::<dyn FnMut(web_sys::MessageEvent)>::new({
+                let latest = latest.clone();
+                let ctx = ctx.clone();
+                move |event: web_sys::MessageEvent| {
+                    let summary = event
+                        .data()
+                        .as_string()
+                        .and_then(|json| SimulationSummary::from_json(&json).ok());
+                    if let Some(summary) = summary {
+                        *latest.borrow_mut() = Some(summary);
+                        ctx.request_repaint();
+                    }
+                }
+            });
+            worker.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+
+            // Loading the script happens after construction, so a missing or
+            // broken worker only shows up here
+            let onerror = Closure::<dyn FnMut(JsValue)>::new({
+                let failed = failed.clone();
+                move |_| {
+                    failed.set(true);
+                    ctx.request_repaint();
+                }
+            });
+            worker.set_onerror(Some(onerror.as_ref().unchecked_ref()));
+
+            Ok(Self {
+                worker,
+                latest,
+                failed,
+                _onmessage: onmessage,
+                _onerror: onerror,
+            })
+        }
+
+        pub fn send(&self, command: &WorkerCommand) {
+            let sent = command
+                .to_json()
+                .map_err(|e| format!("{e}"))
+                .and_then(|json| {
+                    self.worker
+                        .post_message(&JsValue::from_str(&json))
+                        .map_err(|e| format!("{e:?}"))
+                });
+            if let Err(e) = sent {
+                log::warn!("Could not send {command:?} to the simulation worker: {e}");
+            }
+        }
+
+        pub fn latest(&self) -> Option<SimulationSummary> {
+            self.latest.borrow().clone()
+        }
+
+        pub fn failed(&self) -> bool {
+            self.failed.get()
+        }
+    }
+
+    impl Drop for WorkerHandle {
+        fn drop(&mut self) {
+            self.worker.terminate();
+        }
+    }
 }
 
 // When compiling natively:
@@ -565,7 +3723,7 @@ fn main() -> eframe::Result<()> {
     eframe::run_native(
         "Social Media Simulation",
         options,
-        Box::new(|_cc| Ok(Box::new(SimulationApp::default()))),
+        Box::new(|cc| Ok(Box::new(SimulationApp::new(cc)))),
     )
 }
 
@@ -595,7 +3753,7 @@ fn main() {
             .start(
                 canvas,
                 web_options,
-                Box::new(|_cc| Ok(Box::new(SimulationApp::default()))),
+                Box::new(|cc| Ok(Box::new(SimulationApp::new(cc)))),
             )
             .await;
 
@@ -0,0 +1,562 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use super::registry::MetricsRegistry;
+use super::schema::{
+    Compatibility, MetricSchema, ADOPTION_INFLECTION, ADOPTION_SHARE, AD_IMPRESSIONS, AD_REVENUE,
+    AGREEMENT, AROUSAL, AUCTION_BIDDERS, AUCTION_PRICE, BRIDGE_COUNT, BRIDGE_INFLUENCE,
+    COHORT_EXPOSURE_DIVERSITY, COHORT_INTEREST_ENTROPY, COHORT_SCREEN_TIME, COHORT_SIZE,
+    COMMENTS_REJECTED, COMMUNITY_AGREEMENT, COMMUNITY_INTERNAL_EXPOSURE, COMMUNITY_SIZE,
+    CREATOR_EARNINGS, CROSS_SEGMENT_EXPOSURE, EARNINGS_GINI, EARNINGS_TOP_SHARE,
+    ENTROPY_BY_FEED_CONTROLS, EXPOSED_QUALITY, EXPOSURE_SHARE, EXPOSURE_TARGET_GAP,
+    FALSE_EXPOSURE_SHARE, FEED_CREATOR_DIVERSITY, FEED_TAG_ENTROPY, HALF_LIFE_BY_CONTENT_TYPE,
+    HALF_LIFE_BY_CREATOR_TYPE, HALF_LIFE_BY_TAG, INTEREST_ENTROPY, LATE_SESSION_SHARE,
+    LOCK_COMMENTER_AROUSAL, LOCK_EXPOSURES, LOCK_READS, MODERATION_PRECISION, MODERATION_RECALL,
+    NUDGES_SHOWN, NUDGE_COMPLIANCE, PLATFORM_JOINS, PLATFORM_LEAVES, POSITION_DRIVEN_SHARE,
+    PROMOTED_EXPOSURES, PROMOTED_REACH, READ_QUALITY, RELEVANT_EXPOSURE_SHARE, RESHARE_BREADTH,
+    RESHARE_DEPTH, RESHARE_SIZE, REVENUE_PER_STEP, SCORE_CONTRIBUTION, SCREEN_TIME, SEGMENT_SIZE,
+    SEGMENT_SPILLOVER, SERVED_SCORE, SESSION_LENGTH, SPONSORED_EXPOSURES, SPONSORED_SHARE,
+    SPONSOR_SPEND, WELLBEING,
+};
+use crate::engine::ScoreComponents;
+use crate::models::{
+    AdoptionSample, CohortStats, HalfLives, LabelEvaluation, LockEffect, Nudge, PopulationMetric,
+    ReshareTree, RevenueSample, SegmentStats, Simulation, WealthSample,
+};
+
+#[derive(Debug)]
+pub enum MetricsError {
+    Parse(serde_json::Error),
+    Incompatible(Vec<String>),
+    // A custom metric registered under a name already in use
+    Duplicate(String),
+}
+
+impl fmt::Display for MetricsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetricsError::Parse(e) => write!(f, "invalid metrics export: {e}"),
+            MetricsError::Incompatible(problems) => {
+                write!(f, "incompatible metrics export: {}", problems.join("; "))
+            }
+            MetricsError::Duplicate(name) => write!(f, "metric `{name}` is already defined"),
+        }
+    }
+}
+
+impl std::error::Error for MetricsError {}
+
+// One value of a metric at a step, keyed by the metric's dimensions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricSample {
+    pub step: u64,
+    pub metric: String,
+    pub dimensions: BTreeMap<String, String>,
+    pub value: f32,
+}
+
+// Metrics recorded over a run, along with the schema and crate version they
+// were recorded under
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricsExport {
+    pub crate_version: String,
+    pub schema: MetricSchema,
+    pub samples: Vec<MetricSample>,
+}
+
+impl Default for MetricsExport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricsExport {
+    pub fn new() -> Self {
+        Self {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            schema: MetricSchema::current(),
+            samples: Vec::new(),
+        }
+    }
+
+    // Samples every metric for every agent, and the latest score snapshot,
+    // at the simulation's current step
+    pub fn record(&mut self, simulation: &Simulation) {
+        let step = simulation.step_count;
+
+        if let Some(snapshot) = simulation.engine.telemetry.latest() {
+            self.samples.push(MetricSample {
+                step,
+                metric: SERVED_SCORE.to_string(),
+                dimensions: BTreeMap::new(),
+                value: snapshot.mean.total(),
+            });
+            for (component, value) in ScoreComponents::NAMES.iter().zip(snapshot.mean.values()) {
+                self.samples.push(MetricSample {
+                    step,
+                    metric: SCORE_CONTRIBUTION.to_string(),
+                    dimensions: BTreeMap::from([("component".to_string(), component.to_string())]),
+                    value,
+                });
+            }
+        }
+
+        // Mean entropy of agents who use their feed settings against those who
+        // don't, for seeing whether user controls slow bubble formation
+        for uses_controls in [true, false] {
+            if let Some(value) = PopulationMetric::InterestEntropy.mean_where(simulation, |agent| {
+                agent.uses_feed_controls() == Some(uses_controls)
+            }) {
+                self.samples.push(MetricSample {
+                    step,
+                    metric: ENTROPY_BY_FEED_CONTROLS.to_string(),
+                    dimensions: BTreeMap::from([(
+                        "uses_controls".to_string(),
+                        uses_controls.to_string(),
+                    )]),
+                    value,
+                });
+            }
+        }
+
+        // Per community, as of the latest detection run
+        let communities = simulation.communities.iter().flat_map(|c| &c.stats);
+        for community in communities {
+            let dimensions =
+                BTreeMap::from([("community".to_string(), community.label.to_string())]);
+            let values = [
+                (COMMUNITY_SIZE, Some(community.size as f32)),
+                (COMMUNITY_AGREEMENT, Some(community.mean_agreement)),
+                (COMMUNITY_INTERNAL_EXPOSURE, community.internal_share()),
+            ];
+            for (metric, value) in values {
+                if let Some(value) = value {
+                    self.samples.push(MetricSample {
+                        step,
+                        metric: metric.to_string(),
+                        dimensions: dimensions.clone(),
+                        value,
+                    });
+                }
+            }
+        }
+
+        if let Some(bridges) = simulation.bridges.last() {
+            for (metric, value) in [
+                (BRIDGE_COUNT, bridges.bridges.len() as f32),
+                (BRIDGE_INFLUENCE, bridges.influence()),
+            ] {
+                self.samples.push(MetricSample {
+                    step,
+                    metric: metric.to_string(),
+                    dimensions: BTreeMap::new(),
+                    value,
+                });
+            }
+        }
+
+        // Median half-life of the posts read so far in each group
+        let half_lives = HalfLives::of(simulation);
+        let groups = [
+            (
+                HALF_LIFE_BY_CONTENT_TYPE,
+                "content_type",
+                &half_lives.by_kind,
+            ),
+            (
+                HALF_LIFE_BY_CREATOR_TYPE,
+                "creator_type",
+                &half_lives.by_creator_type,
+            ),
+        ];
+        for (metric, dimension, stats) in groups {
+            for (group, stats) in stats {
+                self.samples.push(MetricSample {
+                    step,
+                    metric: metric.to_string(),
+                    dimensions: BTreeMap::from([(dimension.to_string(), group.to_string())]),
+                    value: stats.median,
+                });
+            }
+        }
+        for (tag, stats) in &half_lives.by_tag {
+            self.samples.push(MetricSample {
+                step,
+                metric: HALF_LIFE_BY_TAG.to_string(),
+                dimensions: BTreeMap::from([("tag".to_string(), tag.clone())]),
+                value: stats.median,
+            });
+        }
+
+        // Ranking and moderation scored against the posts' ground truth
+        let evaluation = LabelEvaluation::of(simulation);
+        for (metric, value) in [
+            (EXPOSED_QUALITY, evaluation.exposed_quality),
+            (FALSE_EXPOSURE_SHARE, evaluation.false_exposure_share),
+            (RELEVANT_EXPOSURE_SHARE, evaluation.relevant_exposure_share),
+            (MODERATION_PRECISION, evaluation.moderation_precision),
+            (MODERATION_RECALL, evaluation.moderation_recall),
+            (READ_QUALITY, evaluation.read_quality),
+        ] {
+            if let Some(value) = value {
+                self.samples.push(MetricSample {
+                    step,
+                    metric: metric.to_string(),
+                    dimensions: BTreeMap::new(),
+                    value,
+                });
+            }
+        }
+
+        // Across the population, for nudges that have been shown
+        let nudges = simulation.nudge_stats();
+        for nudge in Nudge::ALL {
+            let counts = nudges.get(nudge);
+            let Some(compliance) = counts.compliance() else {
+                continue;
+            };
+            let dimensions = BTreeMap::from([("nudge".to_string(), nudge.name().to_string())]);
+            for (metric, value) in [
+                (NUDGES_SHOWN, counts.shown as f32),
+                (NUDGE_COMPLIANCE, compliance),
+            ] {
+                self.samples.push(MetricSample {
+                    step,
+                    metric: metric.to_string(),
+                    dimensions: dimensions.clone(),
+                    value,
+                });
+            }
+        }
+
+        // Ad revenue so far, and the rate over the latest sampled interval
+        let totals = RevenueSample::of(step, simulation.agent_views());
+        let rate = simulation.revenue.per_step().last().map(|(_, rate)| *rate);
+        for (metric, value) in [
+            (AD_IMPRESSIONS, Some(totals.impressions as f32)),
+            (AD_REVENUE, Some(totals.revenue)),
+            (REVENUE_PER_STEP, rate),
+        ] {
+            if let Some(value) = value {
+                self.samples.push(MetricSample {
+                    step,
+                    metric: metric.to_string(),
+                    dimensions: BTreeMap::new(),
+                    value,
+                });
+            }
+        }
+
+        // How concentrated creators' earnings are, once anything has been paid
+        let wealth = WealthSample::of(step, &simulation.creator_earnings());
+        for (metric, value) in [
+            (EARNINGS_GINI, wealth.gini),
+            (EARNINGS_TOP_SHARE, wealth.top_share),
+        ] {
+            if let Some(value) = value {
+                self.samples.push(MetricSample {
+                    step,
+                    metric: metric.to_string(),
+                    dimensions: BTreeMap::new(),
+                    value,
+                });
+            }
+        }
+
+        // How much of the population is on the platform, and where the
+        // adoption curve turned
+        let adoption = &simulation.adoption;
+        let members = AdoptionSample::of(step, simulation.agent_views(), adoption);
+        for (metric, value) in [
+            (ADOPTION_SHARE, members.share()),
+            (PLATFORM_JOINS, Some(members.joined as f32)),
+            (PLATFORM_LEAVES, Some(members.left as f32)),
+            (
+                ADOPTION_INFLECTION,
+                adoption.inflection().map(|step| step as f32),
+            ),
+        ] {
+            if let Some(value) = value {
+                self.samples.push(MetricSample {
+                    step,
+                    metric: metric.to_string(),
+                    dimensions: BTreeMap::new(),
+                    value,
+                });
+            }
+        }
+
+        // Per segment, with reads of posts from every segment including its
+        // own, so spillover can be compared with what stays at home
+        for (segment, stats) in SegmentStats::of(simulation) {
+            let dimensions = BTreeMap::from([("segment".to_string(), segment.clone())]);
+            for (metric, value) in [
+                (SEGMENT_SIZE, Some(stats.agents as f32)),
+                (CROSS_SEGMENT_EXPOSURE, stats.cross_exposure_share()),
+            ] {
+                if let Some(value) = value {
+                    self.samples.push(MetricSample {
+                        step,
+                        metric: metric.to_string(),
+                        dimensions: dimensions.clone(),
+                        value,
+                    });
+                }
+            }
+            for (origin, reads) in &stats.reads_from {
+                let mut dimensions = dimensions.clone();
+                dimensions.insert("from_segment".to_string(), origin.clone());
+                self.samples.push(MetricSample {
+                    step,
+                    metric: SEGMENT_SPILLOVER.to_string(),
+                    dimensions,
+                    value: *reads as f32,
+                });
+            }
+        }
+
+        // Averages over each cohort's individuals
+        for (cohort, stats) in CohortStats::of(simulation) {
+            let dimensions = BTreeMap::from([("cohort".to_string(), cohort)]);
+            for (metric, value) in [
+                (COHORT_SIZE, Some(stats.individuals as f32)),
+                (COHORT_SCREEN_TIME, Some(stats.screen_time)),
+                (COHORT_INTEREST_ENTROPY, Some(stats.interest_entropy)),
+                (COHORT_EXPOSURE_DIVERSITY, stats.exposure_diversity),
+            ] {
+                if let Some(value) = value {
+                    self.samples.push(MetricSample {
+                        step,
+                        metric: metric.to_string(),
+                        dimensions: dimensions.clone(),
+                        value,
+                    });
+                }
+            }
+        }
+
+        // Exposures bought by promotion, which organic reach leaves out
+        for (post_id, promotion) in &simulation.engine.promotions {
+            let dimensions = BTreeMap::from([("post".to_string(), post_id.to_string())]);
+            for (metric, value) in [
+                (PROMOTED_EXPOSURES, promotion.exposures),
+                (PROMOTED_REACH, promotion.reach()),
+            ] {
+                self.samples.push(MetricSample {
+                    step,
+                    metric: metric.to_string(),
+                    dimensions: dimensions.clone(),
+                    value: value as f32,
+                });
+            }
+        }
+
+        for (post_id, rejected) in &simulation.engine.rejected_comments {
+            self.samples.push(MetricSample {
+                step,
+                metric: COMMENTS_REJECTED.to_string(),
+                dimensions: BTreeMap::from([("post".to_string(), post_id.to_string())]),
+                value: *rejected as f32,
+            });
+        }
+
+        // Spread either side of each lock, and how its commenters are doing
+        let window = simulation.config.comment_locking.effect_window;
+        for effect in LockEffect::of(simulation, window) {
+            let dimensions = BTreeMap::from([
+                ("post".to_string(), effect.post_id.to_string()),
+                ("reason".to_string(), effect.lock.reason.name().to_string()),
+            ]);
+            for (metric, window, value) in [
+                (LOCK_EXPOSURES, "before", effect.exposures_before),
+                (LOCK_EXPOSURES, "after", effect.exposures_after),
+                (LOCK_READS, "before", effect.reads_before),
+                (LOCK_READS, "after", effect.reads_after),
+            ] {
+                let mut dimensions = dimensions.clone();
+                dimensions.insert("window".to_string(), window.to_string());
+                self.samples.push(MetricSample {
+                    step,
+                    metric: metric.to_string(),
+                    dimensions,
+                    value: value as f32,
+                });
+            }
+            if let Some(arousal) = effect.commenter_arousal {
+                self.samples.push(MetricSample {
+                    step,
+                    metric: LOCK_COMMENTER_AROUSAL.to_string(),
+                    dimensions,
+                    value: arousal,
+                });
+            }
+        }
+
+        // The latest auction's price, and how much of all feed exposure so
+        // far sponsored slots have taken from organic posts
+        let marketplace = &simulation.marketplace;
+        if let Some(round) = marketplace.latest() {
+            for (metric, value) in [
+                (AUCTION_PRICE, round.price),
+                (AUCTION_BIDDERS, Some(round.bidders as f32)),
+                (SPONSORED_SHARE, marketplace.crowding_out()),
+            ] {
+                if let Some(value) = value {
+                    self.samples.push(MetricSample {
+                        step,
+                        metric: metric.to_string(),
+                        dimensions: BTreeMap::new(),
+                        value,
+                    });
+                }
+            }
+        }
+        for (post_id, exposures) in &simulation.engine.sponsored_exposures {
+            self.samples.push(MetricSample {
+                step,
+                metric: SPONSORED_EXPOSURES.to_string(),
+                dimensions: BTreeMap::from([("post".to_string(), post_id.to_string())]),
+                value: *exposures as f32,
+            });
+        }
+        for (agent_id, bidder) in &marketplace.bidders {
+            self.samples.push(MetricSample {
+                step,
+                metric: SPONSOR_SPEND.to_string(),
+                dimensions: BTreeMap::from([
+                    ("agent_id".to_string(), agent_id.to_string()),
+                    ("strategy".to_string(), bidder.strategy.name().to_string()),
+                ]),
+                value: bidder.spent,
+            });
+        }
+
+        // Each fairness group's share of exposures in the latest sample, and
+        // how far it falls outside its target. Groups with a target are
+        // reported even when they got nothing
+        if let Some(latest) = simulation.fairness.latest() {
+            let fairness = &simulation.config.fairness;
+            let groups: BTreeSet<&str> = latest
+                .exposures
+                .keys()
+                .map(String::as_str)
+                .chain(fairness.targets.iter().map(|target| target.group.as_str()))
+                .collect();
+            for group in groups {
+                let Some(share) = latest.share(group) else {
+                    break;
+                };
+                let dimensions = BTreeMap::from([("group".to_string(), group.to_string())]);
+                self.samples.push(MetricSample {
+                    step,
+                    metric: EXPOSURE_SHARE.to_string(),
+                    dimensions: dimensions.clone(),
+                    value: share,
+                });
+                if let Some(target) = fairness.target(group) {
+                    self.samples.push(MetricSample {
+                        step,
+                        metric: EXPOSURE_TARGET_GAP.to_string(),
+                        dimensions,
+                        value: target.gap(share),
+                    });
+                }
+            }
+        }
+
+        // How far and wide each reshared post has spread
+        for tree in ReshareTree::all(&simulation.engine.content_pool) {
+            let dimensions = BTreeMap::from([("post".to_string(), tree.post_id.to_string())]);
+            for (metric, value) in [
+                (RESHARE_DEPTH, tree.depth()),
+                (RESHARE_BREADTH, tree.breadth()),
+                (RESHARE_SIZE, tree.size()),
+            ] {
+                self.samples.push(MetricSample {
+                    step,
+                    metric: metric.to_string(),
+                    dimensions: dimensions.clone(),
+                    value: value as f32,
+                });
+            }
+        }
+
+        for agent in simulation.agent_views() {
+            let agent_id = agent.id.to_string();
+            let mut sample = |metric: &str, tag: Option<&str>, value: f32| {
+                let mut dimensions = BTreeMap::from([("agent_id".to_string(), agent_id.clone())]);
+                if let Some(tag) = tag {
+                    dimensions.insert("tag".to_string(), tag.to_string());
+                }
+                self.samples.push(MetricSample {
+                    step,
+                    metric: metric.to_string(),
+                    dimensions,
+                    value,
+                });
+            };
+
+            for (metric, name) in [
+                (PopulationMetric::InterestEntropy, INTEREST_ENTROPY),
+                (PopulationMetric::SessionLength, SESSION_LENGTH),
+                (PopulationMetric::PositionDriven, POSITION_DRIVEN_SHARE),
+                (PopulationMetric::WellBeing, WELLBEING),
+                (PopulationMetric::ScreenTime, SCREEN_TIME),
+                (PopulationMetric::LateSessionShare, LATE_SESSION_SHARE),
+                (PopulationMetric::Arousal, AROUSAL),
+                (PopulationMetric::Earnings, CREATOR_EARNINGS),
+                (PopulationMetric::FeedTagEntropy, FEED_TAG_ENTROPY),
+                (
+                    PopulationMetric::FeedCreatorDiversity,
+                    FEED_CREATOR_DIVERSITY,
+                ),
+            ] {
+                if let Some(value) = metric.value(&agent) {
+                    sample(name, None, value);
+                }
+            }
+
+            let mut interests: Vec<_> = agent.interest_profile.interests.iter().collect();
+            interests.sort_by(|a, b| a.0.cmp(b.0));
+            for (tag, topic) in interests {
+                sample(AGREEMENT, Some(tag), topic.agreement);
+            }
+        }
+    }
+
+    // Samples the built-in metrics and every metric in `registry`, adding the
+    // registered ones to the export's schema the first time they're seen
+    pub fn record_with(&mut self, simulation: &Simulation, registry: &MetricsRegistry) {
+        self.record(simulation);
+
+        for def in registry.defs() {
+            if self.schema.get(&def.name).is_none() {
+                self.schema.metrics.push(def.clone());
+            }
+        }
+        for (def, value) in registry.evaluate(simulation) {
+            self.samples.push(MetricSample {
+                step: simulation.step_count,
+                metric: def.name.clone(),
+                dimensions: BTreeMap::new(),
+                value,
+            });
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    // Reads an export, checking its schema against this crate's so that runs
+    // from other versions are only compared where they mean the same thing
+    pub fn from_json(json: &str) -> Result<(Self, Compatibility), MetricsError> {
+        let export: Self = serde_json::from_str(json).map_err(MetricsError::Parse)?;
+
+        match export.schema.compatibility(&MetricSchema::current()) {
+            Compatibility::Incompatible(problems) => Err(MetricsError::Incompatible(problems)),
+            compatibility => Ok((export, compatibility)),
+        }
+    }
+}
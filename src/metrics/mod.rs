@@ -0,0 +1,7 @@
+mod export;
+mod registry;
+pub mod schema;
+
+pub use export::{MetricSample, MetricsError, MetricsExport};
+pub use registry::{Metric, MetricsRegistry};
+pub use schema::{Compatibility, MetricDef, MetricSchema, SCHEMA_VERSION};
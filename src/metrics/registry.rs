@@ -0,0 +1,91 @@
+use super::{MetricDef, MetricSchema, MetricsError};
+use crate::Simulation;
+
+// A user-defined metric: one value for the whole simulation each time metrics
+// are recorded
+pub trait Metric {
+    fn value(&self, simulation: &Simulation) -> f64;
+}
+
+impl<F: Fn(&Simulation) -> f64> Metric for F {
+    fn value(&self, simulation: &Simulation) -> f64 {
+        self(simulation)
+    }
+}
+
+struct Registered {
+    def: MetricDef,
+    metric: Box<dyn Metric>,
+}
+
+// Metrics registered by library users, recorded alongside the built-in ones
+// by `MetricsExport::record_with`, so new questions can be asked of a run
+// without changing this module
+#[derive(Default)]
+pub struct MetricsRegistry {
+    metrics: Vec<Registered>,
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(
+        &mut self,
+        name: &str,
+        metric: impl Metric + 'static,
+    ) -> Result<&mut Self, MetricsError> {
+        self.register_with_unit(name, "value", metric)
+    }
+
+    // Names must be unique among both built-in and registered metrics, so
+    // exported samples stay unambiguous
+    pub fn register_with_unit(
+        &mut self,
+        name: &str,
+        unit: &str,
+        metric: impl Metric + 'static,
+    ) -> Result<&mut Self, MetricsError> {
+        if MetricSchema::current().get(name).is_some() || self.get(name).is_some() {
+            return Err(MetricsError::Duplicate(name.to_string()));
+        }
+
+        self.metrics.push(Registered {
+            def: MetricDef::new(name, unit, &[]),
+            metric: Box::new(metric),
+        });
+        Ok(self)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&MetricDef> {
+        self.metrics
+            .iter()
+            .map(|registered| &registered.def)
+            .find(|def| def.name == name)
+    }
+
+    pub fn defs(&self) -> impl Iterator<Item = &MetricDef> {
+        self.metrics.iter().map(|registered| &registered.def)
+    }
+
+    // Every registered metric's value now. Values that aren't finite are left
+    // out, since they can't be exported
+    pub fn evaluate<'a>(
+        &'a self,
+        simulation: &'a Simulation,
+    ) -> impl Iterator<Item = (&'a MetricDef, f32)> + 'a {
+        self.metrics.iter().filter_map(move |registered| {
+            let value = registered.metric.value(simulation);
+            value.is_finite().then_some((&registered.def, value as f32))
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.metrics.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.metrics.is_empty()
+    }
+}
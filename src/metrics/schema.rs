@@ -0,0 +1,245 @@
+use serde::{Deserialize, Serialize};
+
+// Bumped whenever a metric's meaning, unit or dimensions change, so that runs
+// exported before and after can't be compared by mistake. Adding a metric
+// doesn't need a bump
+pub const SCHEMA_VERSION: u32 = 1;
+
+pub const INTEREST_ENTROPY: &str = "interest_entropy";
+pub const AGREEMENT: &str = "agreement";
+pub const SESSION_LENGTH: &str = "session_length";
+pub const POSITION_DRIVEN_SHARE: &str = "position_driven_share";
+pub const SERVED_SCORE: &str = "served_score";
+pub const SCORE_CONTRIBUTION: &str = "score_contribution";
+pub const ENTROPY_BY_FEED_CONTROLS: &str = "entropy_by_feed_controls";
+pub const COMMUNITY_SIZE: &str = "community_size";
+pub const COMMUNITY_AGREEMENT: &str = "community_agreement";
+pub const COMMUNITY_INTERNAL_EXPOSURE: &str = "community_internal_exposure";
+pub const BRIDGE_COUNT: &str = "bridge_count";
+pub const BRIDGE_INFLUENCE: &str = "bridge_influence";
+pub const HALF_LIFE_BY_CONTENT_TYPE: &str = "half_life_by_content_type";
+pub const HALF_LIFE_BY_CREATOR_TYPE: &str = "half_life_by_creator_type";
+pub const HALF_LIFE_BY_TAG: &str = "half_life_by_tag";
+pub const EXPOSED_QUALITY: &str = "exposed_quality";
+pub const READ_QUALITY: &str = "read_quality";
+pub const FALSE_EXPOSURE_SHARE: &str = "false_exposure_share";
+pub const RELEVANT_EXPOSURE_SHARE: &str = "relevant_exposure_share";
+pub const MODERATION_PRECISION: &str = "moderation_precision";
+pub const MODERATION_RECALL: &str = "moderation_recall";
+pub const WELLBEING: &str = "wellbeing";
+pub const SCREEN_TIME: &str = "screen_time";
+pub const LATE_SESSION_SHARE: &str = "late_session_share";
+pub const NUDGES_SHOWN: &str = "nudges_shown";
+pub const NUDGE_COMPLIANCE: &str = "nudge_compliance";
+pub const AD_IMPRESSIONS: &str = "ad_impressions";
+pub const AD_REVENUE: &str = "ad_revenue";
+pub const REVENUE_PER_STEP: &str = "revenue_per_step";
+pub const CREATOR_EARNINGS: &str = "creator_earnings";
+pub const EARNINGS_GINI: &str = "earnings_gini";
+pub const EARNINGS_TOP_SHARE: &str = "earnings_top_share";
+pub const ADOPTION_SHARE: &str = "adoption_share";
+pub const PLATFORM_JOINS: &str = "platform_joins";
+pub const PLATFORM_LEAVES: &str = "platform_leaves";
+pub const ADOPTION_INFLECTION: &str = "adoption_inflection";
+pub const SEGMENT_SIZE: &str = "segment_size";
+pub const CROSS_SEGMENT_EXPOSURE: &str = "cross_segment_exposure";
+pub const SEGMENT_SPILLOVER: &str = "segment_spillover";
+pub const COHORT_SIZE: &str = "cohort_size";
+pub const COHORT_SCREEN_TIME: &str = "cohort_screen_time";
+pub const COHORT_INTEREST_ENTROPY: &str = "cohort_interest_entropy";
+pub const COHORT_EXPOSURE_DIVERSITY: &str = "cohort_exposure_diversity";
+pub const PROMOTED_EXPOSURES: &str = "promoted_exposures";
+pub const PROMOTED_REACH: &str = "promoted_reach";
+pub const AROUSAL: &str = "arousal";
+pub const COMMENTS_REJECTED: &str = "comments_rejected";
+pub const LOCK_EXPOSURES: &str = "lock_exposures";
+pub const LOCK_READS: &str = "lock_reads";
+pub const LOCK_COMMENTER_AROUSAL: &str = "lock_commenter_arousal";
+pub const AUCTION_PRICE: &str = "auction_price";
+pub const AUCTION_BIDDERS: &str = "auction_bidders";
+pub const SPONSORED_SHARE: &str = "sponsored_share";
+pub const SPONSORED_EXPOSURES: &str = "sponsored_exposures";
+pub const SPONSOR_SPEND: &str = "sponsor_spend";
+pub const EXPOSURE_SHARE: &str = "exposure_share";
+pub const EXPOSURE_TARGET_GAP: &str = "exposure_target_gap";
+pub const RESHARE_DEPTH: &str = "reshare_depth";
+pub const RESHARE_BREADTH: &str = "reshare_breadth";
+pub const RESHARE_SIZE: &str = "reshare_size";
+pub const FEED_TAG_ENTROPY: &str = "feed_tag_entropy";
+pub const FEED_CREATOR_DIVERSITY: &str = "feed_creator_diversity";
+
+// What one exported metric measures: its name, the unit its values are in and
+// the dimensions each sample is keyed by
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetricDef {
+    pub name: String,
+    pub unit: String,
+    pub dimensions: Vec<String>,
+}
+
+impl MetricDef {
+    pub fn new(name: &str, unit: &str, dimensions: &[&str]) -> Self {
+        Self {
+            name: name.to_string(),
+            unit: unit.to_string(),
+            dimensions: dimensions.iter().map(|d| d.to_string()).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetricSchema {
+    pub version: u32,
+    pub metrics: Vec<MetricDef>,
+}
+
+// Whether samples recorded under one schema can be read under another
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Compatibility {
+    Identical,
+    // Metrics only one side has are left out of any comparison
+    Compatible {
+        added: Vec<String>,
+        removed: Vec<String>,
+    },
+    Incompatible(Vec<String>),
+}
+
+impl Compatibility {
+    pub fn is_compatible(&self) -> bool {
+        !matches!(self, Compatibility::Incompatible(_))
+    }
+}
+
+impl MetricSchema {
+    // The metrics this version of the crate exports
+    pub fn current() -> Self {
+        Self {
+            version: SCHEMA_VERSION,
+            metrics: vec![
+                MetricDef::new(INTEREST_ENTROPY, "bits", &["agent_id"]),
+                MetricDef::new(AGREEMENT, "agreement", &["agent_id", "tag"]),
+                MetricDef::new(SESSION_LENGTH, "ticks", &["agent_id"]),
+                MetricDef::new(POSITION_DRIVEN_SHARE, "fraction", &["agent_id"]),
+                MetricDef::new(SERVED_SCORE, "score", &[]),
+                MetricDef::new(SCORE_CONTRIBUTION, "score", &["component"]),
+                MetricDef::new(ENTROPY_BY_FEED_CONTROLS, "bits", &["uses_controls"]),
+                MetricDef::new(COMMUNITY_SIZE, "agents", &["community"]),
+                MetricDef::new(COMMUNITY_AGREEMENT, "agreement", &["community"]),
+                MetricDef::new(COMMUNITY_INTERNAL_EXPOSURE, "fraction", &["community"]),
+                MetricDef::new(BRIDGE_COUNT, "agents", &[]),
+                MetricDef::new(BRIDGE_INFLUENCE, "betweenness", &[]),
+                MetricDef::new(HALF_LIFE_BY_CONTENT_TYPE, "ticks", &["content_type"]),
+                MetricDef::new(HALF_LIFE_BY_CREATOR_TYPE, "ticks", &["creator_type"]),
+                MetricDef::new(HALF_LIFE_BY_TAG, "ticks", &["tag"]),
+                MetricDef::new(EXPOSED_QUALITY, "quality", &[]),
+                MetricDef::new(FALSE_EXPOSURE_SHARE, "fraction", &[]),
+                MetricDef::new(RELEVANT_EXPOSURE_SHARE, "fraction", &[]),
+                MetricDef::new(MODERATION_PRECISION, "fraction", &[]),
+                MetricDef::new(MODERATION_RECALL, "fraction", &[]),
+                MetricDef::new(READ_QUALITY, "quality", &[]),
+                MetricDef::new(WELLBEING, "index", &["agent_id"]),
+                MetricDef::new(SCREEN_TIME, "ticks", &["agent_id"]),
+                MetricDef::new(LATE_SESSION_SHARE, "fraction", &["agent_id"]),
+                MetricDef::new(NUDGES_SHOWN, "prompts", &["nudge"]),
+                MetricDef::new(NUDGE_COMPLIANCE, "fraction", &["nudge"]),
+                MetricDef::new(AD_IMPRESSIONS, "impressions", &[]),
+                MetricDef::new(AD_REVENUE, "revenue", &[]),
+                MetricDef::new(REVENUE_PER_STEP, "revenue", &[]),
+                MetricDef::new(CREATOR_EARNINGS, "earnings", &["agent_id"]),
+                MetricDef::new(EARNINGS_GINI, "gini", &[]),
+                MetricDef::new(EARNINGS_TOP_SHARE, "fraction", &[]),
+                MetricDef::new(ADOPTION_SHARE, "fraction", &[]),
+                MetricDef::new(PLATFORM_JOINS, "agents", &[]),
+                MetricDef::new(PLATFORM_LEAVES, "agents", &[]),
+                MetricDef::new(ADOPTION_INFLECTION, "step", &[]),
+                MetricDef::new(SEGMENT_SIZE, "agents", &["segment"]),
+                MetricDef::new(CROSS_SEGMENT_EXPOSURE, "fraction", &["segment"]),
+                MetricDef::new(SEGMENT_SPILLOVER, "reads", &["from_segment", "segment"]),
+                MetricDef::new(COHORT_SIZE, "agents", &["cohort"]),
+                MetricDef::new(COHORT_SCREEN_TIME, "ticks", &["cohort"]),
+                MetricDef::new(COHORT_INTEREST_ENTROPY, "bits", &["cohort"]),
+                MetricDef::new(COHORT_EXPOSURE_DIVERSITY, "bits", &["cohort"]),
+                MetricDef::new(PROMOTED_EXPOSURES, "exposures", &["post"]),
+                MetricDef::new(PROMOTED_REACH, "agents", &["post"]),
+                MetricDef::new(AROUSAL, "index", &["agent_id"]),
+                MetricDef::new(COMMENTS_REJECTED, "comments", &["post"]),
+                MetricDef::new(LOCK_EXPOSURES, "exposures", &["post", "reason", "window"]),
+                MetricDef::new(LOCK_READS, "reads", &["post", "reason", "window"]),
+                MetricDef::new(LOCK_COMMENTER_AROUSAL, "index", &["post", "reason"]),
+                MetricDef::new(AUCTION_PRICE, "price", &[]),
+                MetricDef::new(AUCTION_BIDDERS, "agents", &[]),
+                MetricDef::new(SPONSORED_SHARE, "fraction", &[]),
+                MetricDef::new(SPONSORED_EXPOSURES, "exposures", &["post"]),
+                MetricDef::new(SPONSOR_SPEND, "price", &["agent_id", "strategy"]),
+                MetricDef::new(EXPOSURE_SHARE, "fraction", &["group"]),
+                MetricDef::new(EXPOSURE_TARGET_GAP, "fraction", &["group"]),
+                MetricDef::new(RESHARE_DEPTH, "levels", &["post"]),
+                MetricDef::new(RESHARE_BREADTH, "reshares", &["post"]),
+                MetricDef::new(RESHARE_SIZE, "reshares", &["post"]),
+                MetricDef::new(FEED_TAG_ENTROPY, "bits", &["agent_id"]),
+                MetricDef::new(FEED_CREATOR_DIVERSITY, "fraction", &["agent_id"]),
+            ],
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&MetricDef> {
+        self.metrics.iter().find(|metric| metric.name == name)
+    }
+
+    // Checks whether a run exported under this schema can be read alongside
+    // runs exported under `current`
+    pub fn compatibility(&self, current: &MetricSchema) -> Compatibility {
+        let mut problems = Vec::new();
+
+        if self.version > current.version {
+            problems.push(format!(
+                "exported with schema version {}, newer than {}",
+                self.version, current.version
+            ));
+        }
+
+        for metric in &self.metrics {
+            let Some(known) = current.get(&metric.name) else {
+                continue;
+            };
+            if metric.unit != known.unit {
+                problems.push(format!(
+                    "`{}` is in {}, expected {}",
+                    metric.name, metric.unit, known.unit
+                ));
+            }
+            if metric.dimensions != known.dimensions {
+                problems.push(format!(
+                    "`{}` is keyed by [{}], expected [{}]",
+                    metric.name,
+                    metric.dimensions.join(", "),
+                    known.dimensions.join(", ")
+                ));
+            }
+        }
+
+        if !problems.is_empty() {
+            return Compatibility::Incompatible(problems);
+        }
+
+        let added: Vec<_> = current
+            .metrics
+            .iter()
+            .filter(|metric| self.get(&metric.name).is_none())
+            .map(|metric| metric.name.clone())
+            .collect();
+        let removed: Vec<_> = self
+            .metrics
+            .iter()
+            .filter(|metric| current.get(&metric.name).is_none())
+            .map(|metric| metric.name.clone())
+            .collect();
+
+        if added.is_empty() && removed.is_empty() {
+            Compatibility::Identical
+        } else {
+            Compatibility::Compatible { added, removed }
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use super::{AgentType, AgentView};
+
+// Adoption samples kept, oldest dropped first
+const MAX_SAMPLES: usize = 500;
+
+// How individuals join and leave the platform depending on how many of the
+// people they know are on it. Ties are follows between individuals, in either
+// direction
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdoptionConfig {
+    // Share of individuals on the platform at the start
+    pub initial_members: f32,
+    // Chance per step an individual with no ties on the platform joins
+    pub join_rate: f32,
+    // Extra chance per step of joining when every tie is on the platform,
+    // scaled down with the share that are
+    pub social_join: f32,
+    // Chance per step a member with no ties left on the platform leaves,
+    // scaled down with the share still on it. 0 for nobody leaving
+    pub leave_rate: f32,
+    // Steps between samples of how many individuals are members, 0 to never
+    // sample
+    pub interval: u64,
+}
+
+impl Default for AdoptionConfig {
+    fn default() -> Self {
+        Self {
+            initial_members: 1.0,
+            join_rate: 0.001,
+            social_join: 0.05,
+            leave_rate: 0.0,
+            interval: 10,
+        }
+    }
+}
+
+impl AdoptionConfig {
+    // Whether anyone is ever off the platform, so membership needs tracking
+    pub fn enabled(&self) -> bool {
+        self.initial_members < 1.0 || self.leave_rate > 0.0
+    }
+
+    // Chance per step of joining, given the share of ties on the platform, or
+    // None for individuals without ties
+    pub fn join_chance(&self, active_ties: Option<f32>) -> f32 {
+        self.join_rate + self.social_join * active_ties.unwrap_or(0.0)
+    }
+
+    pub fn leave_chance(&self, active_ties: Option<f32>) -> f32 {
+        self.leave_rate * (1.0 - active_ties.unwrap_or(0.0))
+    }
+}
+
+// How many individuals were on the platform as of one step, and how many
+// joined and left so far
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AdoptionSample {
+    pub step: u64,
+    pub individuals: usize,
+    pub members: usize,
+    pub joined: usize,
+    pub left: usize,
+}
+
+impl AdoptionSample {
+    pub fn of<'a>(
+        step: u64,
+        agents: impl Iterator<Item = AgentView<'a>>,
+        series: &AdoptionSeries,
+    ) -> Self {
+        let (mut individuals, mut members) = (0, 0);
+        for agent in agents.filter(|agent| agent.agent_type == AgentType::Individual) {
+            individuals += 1;
+            if agent.member != Some(false) {
+                members += 1;
+            }
+        }
+        Self {
+            step,
+            individuals,
+            members,
+            joined: series.joined,
+            left: series.left,
+        }
+    }
+
+    pub fn share(&self) -> Option<f32> {
+        (self.individuals > 0).then(|| self.members as f32 / self.individuals as f32)
+    }
+}
+
+// Platform membership sampled over the run, tracing out the adoption curve
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AdoptionSeries {
+    pub samples: VecDeque<AdoptionSample>,
+    // Individuals who have joined and left since the start
+    pub joined: usize,
+    pub left: usize,
+}
+
+impl AdoptionSeries {
+    pub fn record(&mut self, sample: AdoptionSample) {
+        if self.samples.len() == MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn latest(&self) -> Option<&AdoptionSample> {
+        self.samples.back()
+    }
+
+    // The step ending the interval membership grew fastest over, the middle of
+    // the S of an adoption curve. None until it has grown at all
+    pub fn inflection(&self) -> Option<u64> {
+        self.samples
+            .iter()
+            .zip(self.samples.iter().skip(1))
+            .map(|(before, after)| (after.step, after.members as i64 - before.members as i64))
+            .filter(|(_, growth)| *growth > 0)
+            .fold(
+                None,
+                |fastest: Option<(u64, i64)>, (step, growth)| match fastest {
+                    Some((_, most)) if most >= growth => fastest,
+                    _ => Some((step, growth)),
+                },
+            )
+            .map(|(step, _)| step)
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use super::AgentView;
+
+// Revenue samples kept, oldest dropped first
+const MAX_SAMPLES: usize = 500;
+
+// How many ads feeds carry and what the platform earns from them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdsConfig {
+    // Slots in every feed given to ads rather than posts, 0 for no ads
+    pub per_feed: usize,
+    // Revenue from an impression on a fully attentive agent
+    pub revenue_per_impression: f32,
+    // Ticks into a session after which an agent pays ads half the attention
+    // it did at the start
+    pub attention_half_life: f32,
+    // Steps between samples of the platform's revenue, 0 to never sample
+    pub interval: u64,
+}
+
+impl Default for AdsConfig {
+    fn default() -> Self {
+        Self {
+            per_feed: 0,
+            revenue_per_impression: 0.01,
+            attention_half_life: 100.0,
+            interval: 10,
+        }
+    }
+}
+
+impl AdsConfig {
+    // What one impression earns from an agent with the given attention span,
+    // `session_length` ticks into its session
+    pub fn impression_value(&self, attention_span: f32, session_length: i32) -> f32 {
+        let fatigue = 1.0 + session_length.max(0) as f32 / self.attention_half_life.max(1.0);
+        self.revenue_per_impression * attention_span.clamp(0.0, 1.0) / fatigue
+    }
+}
+
+// Ads one agent has been shown and what they earned
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct AdStats {
+    pub impressions: usize,
+    pub revenue: f32,
+}
+
+impl AdStats {
+    pub fn serve(&mut self, ads: usize, value: f32) {
+        self.impressions += ads;
+        self.revenue += ads as f32 * value;
+    }
+}
+
+// Platform totals as of one step
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RevenueSample {
+    pub step: u64,
+    pub impressions: usize,
+    pub revenue: f32,
+}
+
+impl RevenueSample {
+    pub fn of<'a>(step: u64, agents: impl Iterator<Item = AgentView<'a>>) -> Self {
+        let (impressions, revenue) = agents
+            .filter_map(|agent| agent.ads)
+            .fold((0, 0.0), |(impressions, revenue), ads| {
+                (impressions + ads.impressions, revenue + ads.revenue)
+            });
+        Self {
+            step,
+            impressions,
+            revenue,
+        }
+    }
+}
+
+// Platform revenue sampled over the run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RevenueSeries {
+    pub samples: VecDeque<RevenueSample>,
+}
+
+impl RevenueSeries {
+    pub fn record(&mut self, sample: RevenueSample) {
+        if self.samples.len() == MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn latest(&self) -> Option<&RevenueSample> {
+        self.samples.back()
+    }
+
+    // Revenue earned per step between consecutive samples, keyed by the later
+    // sample's step
+    pub fn per_step(&self) -> Vec<(u64, f32)> {
+        self.samples
+            .iter()
+            .zip(self.samples.iter().skip(1))
+            .map(|(before, after)| {
+                let steps = after.step.saturating_sub(before.step).max(1);
+                (after.step, (after.revenue - before.revenue) / steps as f32)
+            })
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
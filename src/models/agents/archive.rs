@@ -0,0 +1,143 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use crate::models::{AgentId, Post, PostId};
+
+// One post as it sat in a served feed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeedSlot {
+    pub post_id: PostId,
+    pub creator_id: AgentId,
+    pub tags: Vec<String>,
+    pub reshare: bool,
+}
+
+impl FeedSlot {
+    pub fn of(post: &Post) -> Self {
+        Self {
+            post_id: post.id,
+            creator_id: post.creator_id,
+            tags: post.interest_profile.interests.keys().cloned().collect(),
+            reshare: post.original.is_some(),
+        }
+    }
+}
+
+// A feed as the engine served it, top to bottom, and the positions in it the
+// agent went on to pick posts from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedFeed {
+    pub step: u64,
+    // Slots given to ads ahead of the posts
+    pub ads: usize,
+    pub slate: Vec<FeedSlot>,
+    pub chosen: Vec<usize>,
+}
+
+impl ArchivedFeed {
+    pub fn chosen_posts(&self) -> impl Iterator<Item = PostId> + '_ {
+        self.chosen
+            .iter()
+            .map(|&position| self.slate[position].post_id)
+    }
+
+    // Share of the feed's posts by different creators: 1.0 when no creator
+    // appears twice. None for an empty feed
+    pub fn creator_diversity(&self) -> Option<f32> {
+        let creators: BTreeSet<AgentId> = self.slate.iter().map(|slot| slot.creator_id).collect();
+        (!self.slate.is_empty()).then(|| creators.len() as f32 / self.slate.len() as f32)
+    }
+
+    // Shannon entropy in bits of the tags across the feed's posts, each post
+    // counting once for each of its tags
+    pub fn tag_entropy(&self) -> f32 {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for tag in self.slate.iter().flat_map(|slot| &slot.tags) {
+            *counts.entry(tag).or_default() += 1;
+        }
+        let total: usize = counts.values().sum();
+        counts
+            .values()
+            .map(|&count| count as f32 / total as f32)
+            .map(|p| -p * p.log2())
+            .sum()
+    }
+}
+
+// The last few feeds served to an agent, oldest dropped first, for auditing
+// what it was actually shown
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeedArchive {
+    pub feeds: VecDeque<ArchivedFeed>,
+    pub capacity: usize,
+}
+
+impl FeedArchive {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            feeds: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, feed: ArchivedFeed) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.feeds.len() == self.capacity {
+            self.feeds.pop_front();
+        }
+        self.feeds.push_back(feed);
+    }
+
+    // Marks the post as picked from the latest feed, if it was in it
+    pub fn choose(&mut self, post_id: PostId) {
+        let Some(feed) = self.feeds.back_mut() else {
+            return;
+        };
+        if let Some(position) = feed.slate.iter().position(|slot| slot.post_id == post_id) {
+            feed.chosen.push(position);
+        }
+    }
+
+    pub fn latest(&self) -> Option<&ArchivedFeed> {
+        self.feeds.back()
+    }
+
+    pub fn len(&self) -> usize {
+        self.feeds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.feeds.is_empty()
+    }
+
+    // Means over the archived feeds with posts in them, or None if there
+    // are none
+    pub fn mean_creator_diversity(&self) -> Option<f32> {
+        mean(
+            self.feeds
+                .iter()
+                .filter_map(ArchivedFeed::creator_diversity),
+        )
+    }
+
+    pub fn mean_tag_entropy(&self) -> Option<f32> {
+        mean(
+            self.feeds
+                .iter()
+                .filter(|feed| !feed.slate.is_empty())
+                .map(ArchivedFeed::tag_entropy),
+        )
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+fn mean(values: impl Iterator<Item = f32>) -> Option<f32> {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), value| (sum + value, count + 1));
+    (count > 0).then(|| sum / count as f32)
+}
@@ -1,17 +1,23 @@
-use super::{Agent, AgentCore, AgentState, AgentType};
+use super::{Agent, AgentCore, AgentState, AgentType, SavedAgent, TickContext};
 use crate::{
-    models::{InterestProfile, SimulationConfig, Topic},
-    RecommendationEngine,
+    engine::EngineOp,
+    models::{
+        AgentId, Earnings, IdAllocator, InterestProfile, PostId, RngStreams, SimulationConfig,
+        StreamRng, Topic,
+    },
 };
-use rand::{random, Rng, RngCore};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Bot {
     pub core: AgentCore,
+    // Tips and subscriptions from followers
+    pub earnings: Earnings,
 }
 
 impl Agent for Bot {
-    fn tick(&mut self, engine: &mut RecommendationEngine, config: &SimulationConfig) {
+    fn tick(&mut self, ctx: &mut TickContext) {
         // Extract data from current creation state
         let new_state = match &self.core.state {
             AgentState::CreatingPost {
@@ -19,15 +25,17 @@ impl Agent for Bot {
                 ticks_spent,
                 ticks_required,
             } => self.proceed_from_creating_post(
-                config,
-                engine,
+                ctx.config,
+                ctx.ops,
+                ctx.ids,
+                &mut ctx.rng.content,
                 *post_id,
                 *ticks_spent,
                 *ticks_required,
             ),
             _ => {
                 // Bot should always be creating, so initialize creation if in any other state
-                self.start_creating_post(config)
+                self.start_creating_post(ctx.ids, ctx.config)
             }
         };
 
@@ -38,6 +46,27 @@ impl Agent for Bot {
         Box::new(self.clone())
     }
 
+    fn duplicate(
+        &self,
+        id: AgentId,
+        ids: &mut IdAllocator,
+        config: &SimulationConfig,
+    ) -> Box<dyn Agent> {
+        let state = AgentState::CreatingPost {
+            post_id: ids.next_post_id(),
+            ticks_spent: 0,
+            ticks_required: config.bot_creation_ticks,
+        };
+        Box::new(Self {
+            core: self.core.duplicate(id, state),
+            earnings: Earnings::default(),
+        })
+    }
+
+    fn to_saved(&self) -> SavedAgent {
+        SavedAgent::Bot(self.clone())
+    }
+
     fn get_type(&self) -> AgentType {
         AgentType::Bot
     }
@@ -46,29 +75,53 @@ impl Agent for Bot {
         &self.core.interest_profile
     }
 
+    fn interest_profile_mut(&mut self) -> &mut InterestProfile {
+        &mut self.core.interest_profile
+    }
+
+    fn community(&self) -> Option<usize> {
+        self.core.community
+    }
+
+    fn set_community(&mut self, community: Option<usize>) {
+        self.core.community = community;
+    }
+
+    fn segment(&self) -> Option<&str> {
+        self.core.segment.as_deref()
+    }
+
     fn state(&self) -> &AgentState {
         &self.core.state
     }
 
-    fn id(&self) -> &usize {
+    fn earnings(&self) -> Option<&Earnings> {
+        Some(&self.earnings)
+    }
+
+    fn earnings_mut(&mut self) -> Option<&mut Earnings> {
+        Some(&mut self.earnings)
+    }
+
+    fn id(&self) -> &AgentId {
         &self.core.id
     }
 }
 
 impl Bot {
-    pub fn new(id: usize, config: &SimulationConfig) -> Self {
+    pub fn new(id: AgentId, ids: &mut IdAllocator, config: &SimulationConfig) -> Self {
         // Initialize interest profile
+        let mut rng = RngStreams::keyed(config.seed, "bot", id.0 as u64);
         let mut interest_profile = InterestProfile::new(100);
 
         // Add random starting interests
         for _ in 0..config.starting_tags.bot {
-            let tag =
-                &config.sample_tags[rand::thread_rng().gen_range(0..config.sample_tags.len())];
+            let tag = &config.sample_tags[rng.gen_range(0..config.sample_tags.len())];
             interest_profile.interests.insert(
                 tag.clone(),
                 Topic {
-                    weighted_interest: 1.0,                 // Will be normalized
-                    agreement: random::<f32>() * 2.0 - 1.0, // Random agreement between -1 and 1
+                    weighted_interest: 1.0,                  // Will be normalized
+                    agreement: rng.gen::<f32>() * 2.0 - 1.0, // Random agreement between -1 and 1
                 },
             );
         }
@@ -80,22 +133,29 @@ impl Bot {
                 id,
                 content_creation_frequency: 1.0, // Bots always create
                 created_content: Vec::new(),
+                created_comments: Vec::new(),
                 create_speed: 1.0, // Bots create at full speed
                 state: AgentState::CreatingPost {
-                    post_id: rand::thread_rng().next_u32() as usize,
+                    post_id: ids.next_post_id(),
                     ticks_spent: 0,
                     ticks_required: config.bot_creation_ticks,
                 },
                 interest_profile,
+                community: None,
+                segment: config.segments.assign(config.seed, id),
             },
+            earnings: Earnings::default(),
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn proceed_from_creating_post(
         &mut self,
         config: &SimulationConfig,
-        engine: &mut RecommendationEngine,
-        post_id: usize,
+        ops: &mut Vec<EngineOp>,
+        ids: &mut IdAllocator,
+        rng: &mut StreamRng,
+        post_id: PostId,
         ticks_spent: i32,
         ticks_required: i32,
     ) -> AgentState {
@@ -103,27 +163,32 @@ impl Bot {
 
         if new_ticks_spent >= ticks_required {
             // Generate content and start new creation
-            let content = self.core.generate_content(config);
+            let content = self
+                .core
+                .generate_content(post_id, AgentType::Bot, config, rng);
             self.core.created_content.push(content.id);
 
-            engine.create_post(content);
+            ops.push(EngineOp::PublishPost(Box::new(content)));
 
-            return self.start_creating_post(config);
+            self.start_creating_post(ids, config)
         } else {
             // Continue current creation
-            return AgentState::CreatingPost {
+            AgentState::CreatingPost {
                 post_id,
                 ticks_spent: new_ticks_spent,
                 ticks_required,
-            };
+            }
         }
     }
 
-    fn start_creating_post(&self, config: &SimulationConfig) -> AgentState {
+    // Bots that earn from their followers post more often
+    fn start_creating_post(&self, ids: &mut IdAllocator, config: &SimulationConfig) -> AgentState {
         AgentState::CreatingPost {
-            post_id: rand::thread_rng().next_u32() as usize,
+            post_id: ids.next_post_id(),
             ticks_spent: 0,
-            ticks_required: config.bot_creation_ticks,
+            ticks_required: config
+                .economy
+                .posting_ticks(config.bot_creation_ticks, self.earnings.income),
         }
     }
 }
@@ -0,0 +1,218 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::{
+    engine::RecommendationEngine,
+    models::{AgentId, PostId, RngStreams, Simulation},
+};
+
+use super::Individual;
+
+// When in the simulated day an individual tends to come online. Hours are
+// fractions of the day from midnight, and may wrap past it
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ActiveHours {
+    pub start: f32,
+    pub end: f32,
+    // How likely coming online is outside the hours, relative to inside them
+    pub off_peak: f32,
+}
+
+impl Default for ActiveHours {
+    // All day, every day
+    fn default() -> Self {
+        Self {
+            start: 0.0,
+            end: 1.0,
+            off_peak: 1.0,
+        }
+    }
+}
+
+impl ActiveHours {
+    // What the chance of coming online is scaled by on a step, with days of
+    // `steps_per_day` steps
+    pub fn activity(&self, step: u64, steps_per_day: u64) -> f32 {
+        let steps_per_day = steps_per_day.max(1);
+        let time_of_day = (step % steps_per_day) as f32 / steps_per_day as f32;
+        let active = if self.start <= self.end {
+            (self.start..self.end).contains(&time_of_day)
+        } else {
+            time_of_day >= self.start || time_of_day < self.end
+        };
+        if active {
+            1.0
+        } else {
+            self.off_peak
+        }
+    }
+}
+
+// A demographic group of individuals with its own daily rhythm, taste in post
+// length and openness to having its interests moved by what it reads
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cohort {
+    pub name: String,
+    pub active_hours: ActiveHours,
+    // How strongly the cohort favours short posts when picking from a feed,
+    // or long ones when negative. 0 for no preference
+    pub short_form_preference: f32,
+    // Interest gained from reading, relative to the population default
+    pub susceptibility: f32,
+}
+
+impl Cohort {
+    // The built-in cohorts, loosely after age groups
+    pub fn library() -> Vec<Cohort> {
+        vec![
+            Cohort {
+                name: "teen".to_string(),
+                active_hours: ActiveHours {
+                    start: 0.65,
+                    end: 0.05,
+                    off_peak: 0.3,
+                },
+                short_form_preference: 2.0,
+                susceptibility: 1.5,
+            },
+            Cohort {
+                name: "young adult".to_string(),
+                active_hours: ActiveHours {
+                    start: 0.5,
+                    end: 0.95,
+                    off_peak: 0.4,
+                },
+                short_form_preference: 1.0,
+                susceptibility: 1.2,
+            },
+            Cohort {
+                name: "adult".to_string(),
+                active_hours: ActiveHours {
+                    start: 0.3,
+                    end: 0.9,
+                    off_peak: 0.5,
+                },
+                short_form_preference: 0.0,
+                susceptibility: 1.0,
+            },
+            // Online early, reads long posts and is set in its views
+            Cohort {
+                name: "senior".to_string(),
+                active_hours: ActiveHours {
+                    start: 0.25,
+                    end: 0.75,
+                    off_peak: 0.2,
+                },
+                short_form_preference: -1.0,
+                susceptibility: 0.6,
+            },
+        ]
+    }
+
+    pub fn seed(&self, individual: &mut Individual) {
+        individual.active_hours = self.active_hours;
+        individual.short_form_preference = self.short_form_preference;
+        individual.susceptibility = self.susceptibility;
+        individual.cohort = Some(self.name.clone());
+    }
+}
+
+// The cohort, if any, an individual belongs to, drawn by the fractions. Unlike
+// personas cohorts are drawn per agent rather than handed out in blocks, so
+// the two don't line up; whatever the fractions leave over has no cohort
+pub fn assign_cohort<'a>(
+    cohorts: &'a [Cohort],
+    fractions: &BTreeMap<String, f32>,
+    seed: u64,
+    id: AgentId,
+) -> Option<&'a Cohort> {
+    if fractions.is_empty() {
+        return None;
+    }
+
+    let mut roll = RngStreams::keyed(seed, "cohort", id.0 as u64).gen::<f32>();
+    for cohort in cohorts {
+        if let Some(fraction) = fractions.get(&cohort.name) {
+            let fraction = fraction.max(0.0);
+            if roll < fraction {
+                return Some(cohort);
+            }
+            roll -= fraction;
+        }
+    }
+    None
+}
+
+// How much a post is more likely to be picked for its length, against one of
+// half the longest length
+pub fn length_weight(length: i32, max_length: i32, short_form_preference: f32) -> f32 {
+    let relative = length as f32 / max_length.max(1) as f32;
+    (short_form_preference * (0.5 - relative)).exp()
+}
+
+// Shannon entropy in bits of the tags across the posts an agent was shown,
+// each post's weights normalised. None if none of them are still in the pool
+pub fn exposure_diversity(engine: &RecommendationEngine, exposed: &[PostId]) -> Option<f32> {
+    let mut tags: BTreeMap<&str, f32> = BTreeMap::new();
+    for post in exposed
+        .iter()
+        .filter_map(|&id| engine.get_content_by_id(id))
+    {
+        for (tag, weight) in post.interest_profile.normalised_weights() {
+            *tags.entry(tag.as_str()).or_default() += weight;
+        }
+    }
+
+    let total: f32 = tags.values().sum();
+    (total > 0.0).then(|| {
+        tags.values()
+            .map(|weight| weight / total)
+            .filter(|p| *p > 0.0)
+            .map(|p| -p * p.log2())
+            .sum()
+    })
+}
+
+// How a cohort's individuals have fared, averaged over them
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CohortStats {
+    pub individuals: usize,
+    pub screen_time: f32,
+    pub interest_entropy: f32,
+    // Over those shown anything that is still in the pool
+    pub exposure_diversity: Option<f32>,
+}
+
+impl CohortStats {
+    // Stats for every cohort with individuals in it, by name
+    pub fn of(simulation: &Simulation) -> BTreeMap<String, CohortStats> {
+        let mut totals: BTreeMap<String, (CohortStats, usize, f32)> = BTreeMap::new();
+        for agent in &simulation.agents {
+            let Some(cohort) = agent.cohort() else {
+                continue;
+            };
+            let (stats, exposed, diversity) = totals.entry(cohort.to_string()).or_default();
+            stats.individuals += 1;
+            stats.screen_time += agent
+                .wellbeing()
+                .map_or(0.0, |wellbeing| wellbeing.screen_time as f32);
+            stats.interest_entropy += agent.interest_profile().entropy();
+            if let Some(bits) = exposure_diversity(&simulation.engine, agent.viewed_content()) {
+                *exposed += 1;
+                *diversity += bits;
+            }
+        }
+
+        totals
+            .into_iter()
+            .map(|(name, (mut stats, exposed, diversity))| {
+                let count = stats.individuals as f32;
+                stats.screen_time /= count;
+                stats.interest_entropy /= count;
+                stats.exposure_diversity = (exposed > 0).then(|| diversity / exposed as f32);
+                (name, stats)
+            })
+            .collect()
+    }
+}
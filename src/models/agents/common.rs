@@ -1,26 +1,163 @@
-use crate::models::{InterestProfile, SimulationConfig};
+use super::{
+    Bot, ChurnedEdge, DecisionLog, FeedArchive, FeedPreferences, Individual, NudgeStats,
+    Organisation, SelectionStats, TransitionPolicy,
+};
+use crate::engine::EngineOp;
+use crate::models::{
+    content::{draw_appeal, Comment, ContentKind},
+    AdStats, AgentId, CommentId, CommentIdList, Earnings, IdAllocator, InterestProfile, PostId,
+    PostIdList, RngStreams, SimulationConfig, StreamRng, TopicDrift, WellBeing,
+};
 use crate::{Post, RecommendationEngine};
-use rand::{random, RngCore};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::any::Any;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::fmt::Debug;
+use std::sync::Arc;
+
+// Everything an agent may read or request while it ticks
+pub struct TickContext<'a> {
+    pub engine: &'a RecommendationEngine,
+    pub ops: &'a mut Vec<EngineOp>,
+    pub ids: &'a mut IdAllocator,
+    pub config: &'a SimulationConfig,
+    pub rng: &'a mut RngStreams,
+}
 
 pub trait Agent: Debug + Any {
-    fn tick(&mut self, engine: &mut RecommendationEngine, config: &SimulationConfig);
+    fn tick(&mut self, ctx: &mut TickContext);
 
     fn clone_box(&self) -> Box<dyn Agent>;
 
+    // A copy of the agent under a new ID: alike in who it is and how it
+    // behaves, but starting out as a new agent would, with nothing done yet
+    fn duplicate(
+        &self,
+        id: AgentId,
+        ids: &mut IdAllocator,
+        config: &SimulationConfig,
+    ) -> Box<dyn Agent>;
+
+    // A copy of the agent as its concrete type, for saving
+    fn to_saved(&self) -> SavedAgent;
+
     fn get_type(&self) -> AgentType;
 
     fn interest_profile(&self) -> &InterestProfile;
 
-    fn preferred_creators(&self) -> Option<&HashMap<usize, f32>> {
+    fn interest_profile_mut(&mut self) -> &mut InterestProfile;
+
+    fn preferred_creators(&self) -> Option<&BTreeMap<AgentId, f32>> {
         None
     }
 
+    fn preferred_creators_mut(&mut self) -> Option<&mut BTreeMap<AgentId, f32>> {
+        None
+    }
+
+    // Follows the agent has dropped, oldest first
+    fn churned_edges(&self) -> &[ChurnedEdge] {
+        &[]
+    }
+
     fn state(&self) -> &AgentState;
 
-    fn id(&self) -> &usize;
+    fn community(&self) -> Option<usize>;
+
+    fn set_community(&mut self, community: Option<usize>);
+
+    fn segment(&self) -> Option<&str>;
+
+    // Whether the agent is on the platform, for agents that can join and
+    // leave it. Others are always on it
+    fn member(&self) -> Option<bool> {
+        None
+    }
+
+    fn set_member(&mut self, _member: bool) {}
+
+    // Ticks spent online in the current session, for agents that have sessions
+    fn session_length(&self) -> Option<i32> {
+        None
+    }
+
+    // The feed settings the agent has chosen, for agents that have a feed
+    fn feed_preferences(&self) -> Option<&FeedPreferences> {
+        None
+    }
+
+    // The persona the agent was seeded from, if any
+    fn persona(&self) -> Option<&str> {
+        None
+    }
+
+    // The demographic cohort the agent belongs to, if any
+    fn cohort(&self) -> Option<&str> {
+        None
+    }
+
+    // Swaps what makes the agent's choices between states, for agents that
+    // make them
+    fn set_policy(&mut self, _policy: Arc<dyn TransitionPolicy>) {}
+
+    // Recent policy decisions, for agents that make them
+    fn decisions(&self) -> Option<&DecisionLog> {
+        None
+    }
+
+    // Where in their feeds the agent picked posts from, for agents that pick
+    fn selections(&self) -> Option<&SelectionStats> {
+        None
+    }
+
+    // The last few feeds the agent was served, for agents that get them
+    fn feeds(&self) -> Option<&FeedArchive> {
+        None
+    }
+
+    // Every post recommended to the agent so far, for agents that scroll
+    fn viewed_content(&self) -> &[PostId] {
+        &[]
+    }
+
+    // How the agent is doing given what it reads, for agents that read. The
+    // simulation updates it, since only it knows how good posts really are
+    fn wellbeing(&self) -> Option<&WellBeing> {
+        None
+    }
+
+    fn wellbeing_mut(&mut self) -> Option<&mut WellBeing> {
+        None
+    }
+
+    // How often the agent was shown each nudge and complied, for agents the
+    // platform nudges
+    fn nudges(&self) -> Option<&NudgeStats> {
+        None
+    }
+
+    // Ads served to the agent, for agents with a feed
+    fn ads(&self) -> Option<&AdStats> {
+        None
+    }
+
+    // What followers have paid the agent, for agents that publish posts. The
+    // simulation pays it, since payments come from other agents
+    fn earnings(&self) -> Option<&Earnings> {
+        None
+    }
+
+    fn earnings_mut(&mut self) -> Option<&mut Earnings> {
+        None
+    }
+
+    // How the agent's topics have moved with its audience, for agents that
+    // respond to theirs
+    fn drift(&self) -> Option<&TopicDrift> {
+        None
+    }
+
+    fn id(&self) -> &AgentId;
 }
 
 impl Clone for Box<dyn Agent> {
@@ -29,6 +166,39 @@ impl Clone for Box<dyn Agent> {
     }
 }
 
+// An agent as its concrete type, since trait objects can't be saved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SavedAgent {
+    Individual(Box<Individual>),
+    Bot(Bot),
+    Organisation(Organisation),
+}
+
+impl SavedAgent {
+    pub fn into_agent(self) -> Box<dyn Agent> {
+        match self {
+            SavedAgent::Individual(agent) => agent,
+            SavedAgent::Bot(agent) => Box::new(agent),
+            SavedAgent::Organisation(agent) => Box::new(agent),
+        }
+    }
+}
+
+// Saves each agent in turn, so only one is copied at a time
+pub fn save_agents<S: Serializer>(
+    agents: &[Box<dyn Agent>],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(agents.iter().map(|agent| agent.to_saved()))
+}
+
+pub fn load_agents<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Box<dyn Agent>>, D::Error> {
+    let agents = Vec::<SavedAgent>::deserialize(deserializer)?;
+    Ok(agents.into_iter().map(SavedAgent::into_agent).collect())
+}
+
 #[derive(Debug, Clone, PartialEq, Copy)]
 pub enum AgentType {
     Individual,
@@ -36,71 +206,204 @@ pub enum AgentType {
     Organisation,
 }
 
-#[derive(Debug, Clone)]
+impl AgentType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AgentType::Individual => "individual",
+            AgentType::Bot => "bot",
+            AgentType::Organisation => "organisation",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AgentState {
     Offline,
     Scrolling {
-        recommended_post_ids: Vec<usize>,
+        recommended_post_ids: PostIdList,
     },
     ReadingPost {
-        post_id: usize,
-        creator_id: usize,
+        post_id: PostId,
+        creator_id: AgentId,
         ticks_spent: i32,
         ticks_required: i32,
         potential_interest_gain: f32,
     },
     ReadingComments {
-        post_id: usize,
-        creator_id: usize,
-        current_comment_ids: Vec<usize>,
+        post_id: PostId,
+        creator_id: AgentId,
+        current_comment_ids: CommentIdList,
         current_comment_index: usize,
         ticks_spent: i32,
         ticks_required: i32,
         potential_interest_gain: f32,
     },
     CreatingPost {
-        post_id: usize,
+        post_id: PostId,
         ticks_spent: i32,
         ticks_required: i32,
     },
     CreatingComment {
-        post_id: usize,
-        comment_id: usize,
+        post_id: PostId,
+        comment_id: CommentId,
         ticks_spent: i32,
         ticks_required: i32,
     },
 }
 
-#[derive(Debug, Clone)]
+impl AgentState {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AgentState::Offline => "Offline",
+            AgentState::Scrolling { .. } => "Scrolling",
+            AgentState::ReadingPost { .. } => "Reading Post",
+            AgentState::ReadingComments { .. } => "Reading Comments",
+            AgentState::CreatingPost { .. } => "Creating Post",
+            AgentState::CreatingComment { .. } => "Creating Comment",
+        }
+    }
+
+    // Moves out any post or comment ID lists the state holds, leaving them
+    // empty, so a transition can hand them on without cloning
+    pub fn take_id_lists(&mut self) -> (PostIdList, CommentIdList) {
+        match self {
+            AgentState::Scrolling {
+                recommended_post_ids,
+            } => (std::mem::take(recommended_post_ids), CommentIdList::new()),
+            AgentState::ReadingComments {
+                current_comment_ids,
+                ..
+            } => (PostIdList::new(), std::mem::take(current_comment_ids)),
+            _ => (PostIdList::new(), CommentIdList::new()),
+        }
+    }
+
+    // Fraction of the current activity completed, for states that take a
+    // fixed number of ticks
+    pub fn progress(&self) -> Option<f32> {
+        match self {
+            AgentState::Offline | AgentState::Scrolling { .. } => None,
+            AgentState::ReadingPost {
+                ticks_spent,
+                ticks_required,
+                ..
+            }
+            | AgentState::ReadingComments {
+                ticks_spent,
+                ticks_required,
+                ..
+            }
+            | AgentState::CreatingPost {
+                ticks_spent,
+                ticks_required,
+                ..
+            }
+            | AgentState::CreatingComment {
+                ticks_spent,
+                ticks_required,
+                ..
+            } => {
+                if *ticks_required <= 0 {
+                    Some(1.0)
+                } else {
+                    Some((*ticks_spent as f32 / *ticks_required as f32).clamp(0.0, 1.0))
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentCore {
-    pub id: usize,
+    pub id: AgentId,
     pub content_creation_frequency: f32, // 1 = the most frequent, 0 = never posts
-    pub created_content: Vec<usize>,
+    pub created_content: Vec<PostId>,
+    pub created_comments: Vec<CommentId>,
     pub create_speed: f32,
     pub state: AgentState,
 
     // Determines the interest profile of any content created, which is used for
     // content recommendations and updates of consumer interests
     pub interest_profile: InterestProfile,
+
+    // The community detection last put the agent in, if it has run
+    pub community: Option<usize>,
+
+    // The language or market segment the agent and its posts belong to, if
+    // the population is segmented
+    pub segment: Option<String>,
 }
 
 impl AgentCore {
-    pub fn generate_content(&self, config: &SimulationConfig) -> Post {
-        let selected_tags = self
-            .interest_profile
-            .select_content_tags(config.min_content_tags, config.max_content_tags);
-
-        let content_profile = self.interest_profile.filtered_clone(&selected_tags);
+    // The same creator under a new ID, with nothing made yet, no community
+    // until detection next runs, and starting out in `state`
+    pub fn duplicate(&self, id: AgentId, state: AgentState) -> Self {
+        Self {
+            id,
+            created_content: Vec::new(),
+            created_comments: Vec::new(),
+            state,
+            community: None,
+            ..self.clone()
+        }
+    }
 
+    // Publishes under the ID reserved when the agent started creating the post
+    pub fn generate_content(
+        &self,
+        post_id: PostId,
+        agent_type: AgentType,
+        config: &SimulationConfig,
+        rng: &mut StreamRng,
+    ) -> Post {
         Post {
-            id: rand::thread_rng().next_u32() as usize,
+            id: post_id,
             creator_id: self.id,
-            timestamp: chrono::Utc::now().timestamp(),
-            interest_profile: content_profile,
-            length: (random::<f32>() * config.max_post_length as f32) as i32,
+            // Stamped with the simulation's clock when published
+            timestamp: 0,
+            published_step: 0,
+            kind: ContentKind::Organic,
+            segment: self.segment.clone(),
+            appeal: draw_appeal(post_id, config),
+            interest_profile: self.content_profile(config, rng),
+            length: config.content.post_length(agent_type, config, rng),
             readers: Vec::new(),
             comments: Vec::new(),
             engagement_score: 0.0,
+            cascade: Vec::new(),
+            likes: Vec::new(),
+            shared_by: Vec::new(),
+            reshare_of: None,
+            original: None,
+            reshares: Vec::new(),
+        }
+    }
+
+    pub fn generate_comment(
+        &self,
+        comment_id: CommentId,
+        config: &SimulationConfig,
+        rng: &mut StreamRng,
+    ) -> Comment {
+        Comment {
+            id: comment_id,
+            commentor_id: self.id,
+            timestamp: 0,
+            interest_profile: self.content_profile(config, rng),
+            length: config.content.comment_length(config, rng),
+            engagement_score: 0.0,
         }
     }
+
+    // Content only covers a few of the creator's interests at a time
+    fn content_profile(&self, config: &SimulationConfig, rng: &mut StreamRng) -> InterestProfile {
+        let selected_tags = self.interest_profile.select_content_tags(
+            config.min_content_tags,
+            config.max_content_tags,
+            &config.content.tag_cooccurrence,
+            rng,
+        );
+
+        self.interest_profile.filtered_clone(&selected_tags)
+    }
 }
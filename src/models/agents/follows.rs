@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::models::AgentId;
+
+// Why a follow was dropped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChurnReason {
+    // Too many posts the follower disagreed with
+    Conflict,
+    // Too many posts the follower didn't care about
+    LowInterest,
+    // Left unused until its weight faded away
+    Decay,
+}
+
+impl ChurnReason {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChurnReason::Conflict => "conflict",
+            ChurnReason::LowInterest => "low interest",
+            ChurnReason::Decay => "decay",
+        }
+    }
+}
+
+// A follow that was dropped, and when
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChurnedEdge {
+    pub step: u64,
+    pub follower: AgentId,
+    pub followed: AgentId,
+    pub reason: ChurnReason,
+}
+
+// Tracks how an agent's follows wear down, so the graph can shrink as well as
+// grow
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FollowChurn {
+    // Bad reads in a row per followed creator
+    pub strikes: BTreeMap<AgentId, u32>,
+    pub churned: Vec<ChurnedEdge>,
+}
+
+impl FollowChurn {
+    // Updates the follow on `creator` after reading one of its posts. A good
+    // read strengthens it by `completion`; a bad one is a strike, and
+    // `max_strikes` in a row unfollows. 0 strikes never unfollows
+    #[allow(clippy::too_many_arguments)]
+    pub fn react(
+        &mut self,
+        follows: &mut BTreeMap<AgentId, f32>,
+        step: u64,
+        follower: AgentId,
+        creator: AgentId,
+        complaint: Option<ChurnReason>,
+        completion: f32,
+        max_strikes: u32,
+    ) {
+        let Some(reason) = complaint else {
+            self.strikes.remove(&creator);
+            *follows.entry(creator).or_default() += completion;
+            return;
+        };
+
+        // Only follows can be struck; a bad read from a stranger just doesn't
+        // start one
+        if max_strikes == 0 || !follows.contains_key(&creator) {
+            return;
+        }
+
+        let strikes = self.strikes.entry(creator).or_default();
+        *strikes += 1;
+        if *strikes >= max_strikes {
+            self.unfollow(follows, step, follower, creator, reason);
+        }
+    }
+
+    // Fades every follow by `rate`, dropping those that fall below
+    // `min_weight`
+    pub fn decay(
+        &mut self,
+        follows: &mut BTreeMap<AgentId, f32>,
+        step: u64,
+        follower: AgentId,
+        rate: f32,
+        min_weight: f32,
+    ) {
+        if rate <= 0.0 {
+            return;
+        }
+
+        let mut faded = Vec::new();
+        for (creator, weight) in follows.iter_mut() {
+            *weight *= 1.0 - rate;
+            if *weight < min_weight {
+                faded.push(*creator);
+            }
+        }
+
+        faded.sort();
+        for creator in faded {
+            self.unfollow(follows, step, follower, creator, ChurnReason::Decay);
+        }
+    }
+
+    fn unfollow(
+        &mut self,
+        follows: &mut BTreeMap<AgentId, f32>,
+        step: u64,
+        follower: AgentId,
+        followed: AgentId,
+        reason: ChurnReason,
+    ) {
+        follows.remove(&followed);
+        self.strikes.remove(&followed);
+        self.churned.push(ChurnedEdge {
+            step,
+            follower,
+            followed,
+            reason,
+        });
+    }
+}
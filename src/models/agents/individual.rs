@@ -1,12 +1,30 @@
 use crate::{
-    models::{content::Comment, InterestProfile, SimulationConfig},
+    engine::{CreatorExclusions, EngineOp},
+    models::{
+        content::{appeal_weight, Comment},
+        AdStats, AgentId, CommentId, CommentIdList, IdAllocator, InterestProfile, PostId,
+        PostIdList, PostIdSet, ReadMedia, RngStreams, SimulationConfig, StreamRng, Topic,
+        WellBeing,
+    },
+    profiling::span,
     Post, RecommendationEngine,
 };
-use rand::{random, RngCore};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use super::{
+    assign_cohort, length_weight, position_weight, ActiveHours, Agent, AgentCore, AgentState,
+    AgentType, ArchivedFeed, Choice, ChurnReason, ChurnedEdge, Decision, DecisionLog,
+    DecisionRecord, DefaultPolicy, FeedArchive, FeedPreferences, FeedSlot, FollowChurn, Nudge,
+    NudgeStats, Persona, SavedAgent, SelectionStats, TickContext, TransitionPolicy,
+};
 
-use super::{Agent, AgentCore, AgentState, AgentType};
+// Slots in every feed, shared between posts and any ads
+pub const FEED_LENGTH: usize = 10;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Individual {
     pub core: AgentCore,
 
@@ -21,20 +39,99 @@ pub struct Individual {
 
     // List of content IDs representing posts that have been previously
     // recommended while scrolling
-    pub viewed_content: Vec<usize>,
+    pub viewed_content: PostIdSet,
+
+    // Posts the agent has opened and read, as opposed to only seen in a feed
+    #[serde(default)]
+    pub read_posts: PostIdSet,
 
     // How many ticks the current online session has run for
     pub session_length_ticks: i32,
+
+    // Makes the probabilistic choices between states. Policies aren't saved,
+    // so a loaded agent goes back to the default until the simulation sets
+    // the configured one
+    #[serde(skip, default = "default_policy")]
+    pub policy: Arc<dyn TransitionPolicy>,
+
+    // The last few choices made, for tracing why the agent behaves as it does
+    pub decisions: DecisionLog,
+
+    // Where in the feed posts were picked from
+    pub selections: SelectionStats,
+
+    // The last few feeds served, and what was picked from each
+    pub feeds: FeedArchive,
+
+    // Well-being given the quality of what the agent has read
+    pub wellbeing: WellBeing,
+
+    // Nudges the agent has been shown and how often it complied
+    pub nudges: NudgeStats,
+
+    // Ads served in the agent's feeds and what they earned the platform
+    pub ads: AdStats,
+
+    // Creators the agent follows: those it was connected to at the start, and
+    // those whose posts it has read, weighted by how much of each post it got
+    // through
+    pub preferred_creators: BTreeMap<AgentId, f32>,
+
+    // The persona the starting profile and behaviour were drawn from, or None
+    // when they were fully random
+    pub persona: Option<String>,
+
+    // Feed settings the engine respects when ranking for this agent
+    pub preferences: FeedPreferences,
+
+    // Strikes against followed creators and the follows dropped so far
+    pub churn: FollowChurn,
+
+    // Whether the agent is on the platform at all. Those who aren't never
+    // come online
+    pub member: bool,
+
+    // The demographic cohort the agent belongs to, and what it brings: when
+    // in the day the agent tends to come online, how it favours posts by
+    // length, and how far reading moves its interests
+    pub cohort: Option<String>,
+    pub active_hours: ActiveHours,
+    pub short_form_preference: f32,
+    pub susceptibility: f32,
 }
 
 impl Agent for Individual {
-    fn tick(&mut self, engine: &mut RecommendationEngine, config: &SimulationConfig) {
-        self.session_length_ticks += 1;
+    fn tick(&mut self, ctx: &mut TickContext) {
+        let TickContext {
+            engine,
+            ops,
+            ids,
+            config,
+            rng,
+        } = ctx;
+        let (engine, config) = (*engine, *config);
+        let RngStreams {
+            agents: agent_rng,
+            content: content_rng,
+            policy: policy_rng,
+            ..
+        } = &mut **rng;
+        let previously_viewed = self.viewed_content.len();
+
+        // The lists are moved on to the next state rather than cloned
+        let (recommended_post_ids, current_comment_ids) = self.core.state.take_id_lists();
+
+        let _transition = span(self.core.state.name());
         let new_state = match &self.core.state {
-            AgentState::Offline => self.proceed_from_offline(engine, config),
-            AgentState::Scrolling {
+            AgentState::Offline => self.proceed_from_offline(engine, config, agent_rng),
+            AgentState::Scrolling { .. } => self.proceed_from_scrolling(
+                engine,
+                ids,
+                config,
+                agent_rng,
+                policy_rng,
                 recommended_post_ids,
-            } => self.proceed_from_scrolling(engine, config, recommended_post_ids.clone()),
+            ),
             AgentState::ReadingPost {
                 post_id,
                 creator_id,
@@ -43,7 +140,11 @@ impl Agent for Individual {
                 potential_interest_gain,
             } => self.proceed_from_reading_post(
                 engine,
+                ops,
+                ids,
                 config,
+                agent_rng,
+                policy_rng,
                 *post_id,
                 *creator_id,
                 *ticks_spent,
@@ -56,7 +157,10 @@ impl Agent for Individual {
                 ticks_required,
             } => self.proceed_from_creating_post(
                 engine,
+                ops,
                 config,
+                content_rng,
+                policy_rng,
                 *post_id,
                 *ticks_spent,
                 *ticks_required,
@@ -64,17 +168,21 @@ impl Agent for Individual {
             AgentState::ReadingComments {
                 post_id,
                 creator_id,
-                current_comment_ids,
                 current_comment_index,
                 ticks_spent,
                 ticks_required,
                 potential_interest_gain,
+                ..
             } => self.proceed_from_reading_comments(
                 engine,
+                ops,
+                ids,
                 config,
+                agent_rng,
+                policy_rng,
                 *post_id,
                 *creator_id,
-                current_comment_ids.clone(),
+                current_comment_ids,
                 *current_comment_index,
                 *ticks_spent,
                 *ticks_required,
@@ -87,13 +195,55 @@ impl Agent for Individual {
                 ticks_required,
             } => self.proceed_from_creating_comment(
                 engine,
+                ops,
                 config,
+                content_rng,
+                policy_rng,
                 *post_id,
                 *comment_id,
                 *ticks_spent,
                 *ticks_required,
             ),
         };
+
+        // Anything newly in viewed content was recommended to us this tick
+        for &post_id in &self.viewed_content[previously_viewed..] {
+            ops.push(if engine.is_sponsored(post_id) {
+                EngineOp::RecordSponsoredExposure { post_id }
+            } else {
+                EngineOp::RecordExposure {
+                    post_id,
+                    agent_id: self.core.id,
+                    promoted: engine.active_promotion(post_id).is_some(),
+                }
+            });
+        }
+
+        self.churn.decay(
+            &mut self.preferred_creators,
+            engine.current_step,
+            self.core.id,
+            config.follow_decay_rate,
+            config.min_follow_weight,
+        );
+
+        if matches!(new_state, AgentState::Offline) {
+            self.session_length_ticks = 0;
+            self.wellbeing.offline(&config.wellbeing);
+        } else {
+            self.session_length_ticks += 1;
+            let nudges = &config.nudges;
+            if nudges.enabled(Nudge::TimeReminder)
+                && self.session_length_ticks == nudges.reminder_ticks
+            {
+                self.nudges.time_reminder.shown += 1;
+            }
+            self.wellbeing.online(
+                self.session_length_ticks,
+                matches!(new_state, AgentState::Scrolling { .. }),
+                &config.wellbeing,
+            );
+        }
         self.core.state = new_state;
     }
 
@@ -101,6 +251,34 @@ impl Agent for Individual {
         Box::new(self.clone())
     }
 
+    // Keeps the traits, follows, persona, cohort, feed settings and policy,
+    // and forgets what the agent has seen and done
+    fn duplicate(
+        &self,
+        id: AgentId,
+        _ids: &mut IdAllocator,
+        config: &SimulationConfig,
+    ) -> Box<dyn Agent> {
+        Box::new(Self {
+            core: self.core.duplicate(id, AgentState::Offline),
+            viewed_content: PostIdSet::new(),
+            read_posts: PostIdSet::new(),
+            session_length_ticks: 0,
+            decisions: DecisionLog::new(config.decision_log_length),
+            selections: SelectionStats::default(),
+            feeds: FeedArchive::new(config.feed_archive_length),
+            wellbeing: WellBeing::new(&config.wellbeing),
+            nudges: NudgeStats::default(),
+            ads: AdStats::default(),
+            churn: FollowChurn::default(),
+            ..self.clone()
+        })
+    }
+
+    fn to_saved(&self) -> SavedAgent {
+        SavedAgent::Individual(Box::new(self.clone()))
+    }
+
     fn get_type(&self) -> AgentType {
         AgentType::Individual
     }
@@ -109,41 +287,212 @@ impl Agent for Individual {
         &self.core.interest_profile
     }
 
+    fn interest_profile_mut(&mut self) -> &mut InterestProfile {
+        &mut self.core.interest_profile
+    }
+
+    fn community(&self) -> Option<usize> {
+        self.core.community
+    }
+
+    fn set_community(&mut self, community: Option<usize>) {
+        self.core.community = community;
+    }
+
+    fn set_policy(&mut self, policy: Arc<dyn TransitionPolicy>) {
+        self.policy = policy;
+    }
+
+    fn segment(&self) -> Option<&str> {
+        self.core.segment.as_deref()
+    }
+
+    fn member(&self) -> Option<bool> {
+        Some(self.member)
+    }
+
+    // Leaving ends any session there and then
+    fn set_member(&mut self, member: bool) {
+        self.member = member;
+        if !member {
+            self.core.state = AgentState::Offline;
+            self.session_length_ticks = 0;
+        }
+    }
+
     fn state(&self) -> &AgentState {
         &self.core.state
     }
 
-    fn id(&self) -> &usize {
+    fn session_length(&self) -> Option<i32> {
+        Some(self.session_length_ticks)
+    }
+
+    fn decisions(&self) -> Option<&DecisionLog> {
+        Some(&self.decisions)
+    }
+
+    fn preferred_creators(&self) -> Option<&BTreeMap<AgentId, f32>> {
+        Some(&self.preferred_creators)
+    }
+
+    fn preferred_creators_mut(&mut self) -> Option<&mut BTreeMap<AgentId, f32>> {
+        Some(&mut self.preferred_creators)
+    }
+
+    fn churned_edges(&self) -> &[ChurnedEdge] {
+        &self.churn.churned
+    }
+
+    fn feed_preferences(&self) -> Option<&FeedPreferences> {
+        Some(&self.preferences)
+    }
+
+    fn persona(&self) -> Option<&str> {
+        self.persona.as_deref()
+    }
+
+    fn cohort(&self) -> Option<&str> {
+        self.cohort.as_deref()
+    }
+
+    fn selections(&self) -> Option<&SelectionStats> {
+        Some(&self.selections)
+    }
+
+    fn feeds(&self) -> Option<&FeedArchive> {
+        Some(&self.feeds)
+    }
+
+    fn viewed_content(&self) -> &[PostId] {
+        &self.viewed_content
+    }
+
+    fn wellbeing(&self) -> Option<&WellBeing> {
+        Some(&self.wellbeing)
+    }
+
+    fn wellbeing_mut(&mut self) -> Option<&mut WellBeing> {
+        Some(&mut self.wellbeing)
+    }
+
+    fn nudges(&self) -> Option<&NudgeStats> {
+        Some(&self.nudges)
+    }
+
+    fn ads(&self) -> Option<&AdStats> {
+        Some(&self.ads)
+    }
+
+    fn id(&self) -> &AgentId {
         &self.core.id
     }
 }
 
 impl Individual {
-    pub fn new(id: usize, _config: &SimulationConfig, _engine: &RecommendationEngine) -> Self {
-        Self {
+    pub fn new(id: AgentId, config: &SimulationConfig, engine: &RecommendationEngine) -> Self {
+        let mut rng = RngStreams::keyed(config.seed, "individual", id.0 as u64);
+        let mut interest_profile = InterestProfile::new(engine.vector_dimension);
+
+        // Without any starting interests, nothing can be recommended on merit
+        for _ in 0..config.starting_tags.individual {
+            let tag = &config.sample_tags[rng.gen_range(0..config.sample_tags.len())];
+            interest_profile.interests.insert(
+                tag.clone(),
+                Topic {
+                    weighted_interest: 1.0,                  // Will be normalized
+                    agreement: rng.gen::<f32>() * 2.0 - 1.0, // Random agreement between -1 and 1
+                },
+            );
+        }
+
+        interest_profile.normalise_weights();
+
+        let mut individual = Self {
             core: AgentCore {
                 id,
-                content_creation_frequency: random::<f32>().min(0.3),
+                content_creation_frequency: rng.gen::<f32>().min(0.3),
                 created_content: Vec::new(),
-                create_speed: random(),
+                created_comments: Vec::new(),
+                create_speed: rng.gen(),
                 state: AgentState::Offline,
-                interest_profile: InterestProfile::new(100),
+                interest_profile,
+                community: None,
+                segment: config.segments.assign(config.seed, id),
             },
-            next_post_likelihood: random(),
-            attention_span: random::<f32>().min(0.5),
-            viewed_content: Vec::new(),
-            read_speed: random(),
+            next_post_likelihood: rng.gen(),
+            attention_span: rng.gen::<f32>().min(0.5),
+            viewed_content: PostIdSet::new(),
+            read_posts: PostIdSet::new(),
+            read_speed: rng.gen(),
             session_length_ticks: 0,
+            policy: config.policy.build(),
+            decisions: DecisionLog::new(config.decision_log_length),
+            feeds: FeedArchive::new(config.feed_archive_length),
+            selections: SelectionStats::default(),
+            wellbeing: WellBeing::new(&config.wellbeing),
+            nudges: NudgeStats::default(),
+            ads: AdStats::default(),
+            preferred_creators: BTreeMap::new(),
+            persona: None,
+            preferences: FeedPreferences::new(config, &mut rng),
+            churn: FollowChurn::default(),
+            member: true,
+            cohort: None,
+            active_hours: ActiveHours::default(),
+            short_form_preference: 0.0,
+            susceptibility: 1.0,
+        };
+        if let Some(cohort) =
+            assign_cohort(&config.cohorts, &config.cohort_fractions, config.seed, id)
+        {
+            cohort.seed(&mut individual);
         }
+        engine.vectorise(&mut individual.core.interest_profile);
+        individual
+    }
+
+    pub fn from_persona<R: Rng + ?Sized>(
+        id: AgentId,
+        persona: &Persona,
+        config: &SimulationConfig,
+        engine: &RecommendationEngine,
+        rng: &mut R,
+    ) -> Self {
+        let mut individual = Self::new(id, config, engine);
+        persona.seed(&mut individual, rng);
+        engine.vectorise(&mut individual.core.interest_profile);
+        individual
+    }
+
+    pub fn with_policy(mut self, policy: Arc<dyn TransitionPolicy>) -> Self {
+        self.policy = policy;
+        self
     }
 
     fn proceed_from_offline(
         &mut self,
         engine: &RecommendationEngine,
         config: &SimulationConfig,
+        rng: &mut StreamRng,
     ) -> AgentState {
-        // Uses next post likelihood to determine whether to come online
-        if random::<f32>() < self.next_post_likelihood {
+        if !self.member {
+            return AgentState::Offline;
+        }
+
+        // Uses next post likelihood to determine whether to come online, less
+        // likely outside the agent's active hours
+        let threshold = self.next_post_likelihood
+            * self
+                .active_hours
+                .activity(engine.current_step, config.steps_per_day);
+        let roll = rng.gen::<f32>();
+        let choice = Choice {
+            taken: roll < threshold,
+            roll: Some(roll),
+            threshold: Some(threshold),
+        };
+        if self.record("come online", choice) {
             self.proceed_to_scrolling(engine, config)
         } else {
             AgentState::Offline
@@ -153,80 +502,82 @@ impl Individual {
     fn proceed_from_scrolling(
         &mut self,
         engine: &RecommendationEngine,
+        ids: &mut IdAllocator,
         config: &SimulationConfig,
-        current_recommendations: Vec<usize>,
+        rng: &mut StreamRng,
+        policy_rng: &mut StreamRng,
+        current_recommendations: PostIdList,
     ) -> AgentState {
+        let policy = Arc::clone(&self.policy);
+
         // First check if we should select a post to interact with
-        if self.should_select_post() {
+        if self.record("select post", policy.should_select_post(self, policy_rng)) {
             if let Some(selected_post_id) =
-                self.select_post_from_recommendations(current_recommendations, engine)
+                self.select_post_from_recommendations(current_recommendations, engine, config, rng)
             {
                 // Get the selected post
                 if let Some(selected_post) = engine.get_content_by_id(selected_post_id) {
                     // Decide what to do with the selected post
-                    if self.should_read_post() {
-                        return AgentState::ReadingPost {
-                            post_id: selected_post.id,
-                            creator_id: selected_post.creator_id,
-                            ticks_spent: 0,
-                            ticks_required: (selected_post.length as f32 * (1.0 - self.read_speed))
-                                as i32,
-                            potential_interest_gain: self
-                                .calculate_potential_interest_gain(selected_post, engine),
-                        };
+                    if self.record("read post", policy.should_read_post(self, policy_rng)) {
+                        return self.start_reading_post(selected_post, engine, config);
                     }
 
-                    if self.should_read_comments() {
+                    if self.record(
+                        "read comments",
+                        policy.should_read_comments(self, policy_rng),
+                    ) {
                         // Get initial batch of comments
                         if let Some(comment_ids) = engine.get_comment_recommendations(
                             selected_post.id,
-                            Vec::new(), // No viewed comments yet
+                            &[], // No viewed comments yet
                             10,
                         ) {
-                            if !comment_ids.is_empty() {
-                                // Find the first comment to start reading
-                                if let Some(first_comment) = selected_post
-                                    .comments
-                                    .iter()
-                                    .find(|c| c.id == comment_ids[0])
-                                {
-                                    return AgentState::ReadingComments {
-                                        post_id: selected_post.id,
-                                        creator_id: selected_post.creator_id,
-                                        current_comment_ids: comment_ids,
-                                        current_comment_index: 0,
-                                        ticks_spent: 0,
-                                        ticks_required: (first_comment.length as f32
-                                            * (1.0 - self.read_speed))
-                                            as i32,
-                                        potential_interest_gain: self
-                                            .calculate_potential_interest_gain_from_comment(
-                                                first_comment,
-                                                engine,
-                                            ),
-                                    };
-                                }
+                            if let Some(state) = self.start_reading_comment(
+                                selected_post,
+                                comment_ids,
+                                0,
+                                engine,
+                                config,
+                            ) {
+                                return state;
                             }
                         }
                     }
 
-                    if self.should_write_comment() {
-                        let comment_id = rand::thread_rng().next_u32() as usize;
-                        return AgentState::CreatingComment {
-                            post_id: selected_post.id,
-                            comment_id,
-                            ticks_spent: 0,
-                            ticks_required: (config.max_comment_length as f32
-                                * (1.0 - self.core.create_speed))
-                                as i32,
-                        };
+                    if self.record(
+                        "write comment",
+                        policy.should_write_comment(self, policy_rng),
+                    ) {
+                        if engine.comments_locked(selected_post.id) {
+                            // Turned away, it falls through to scrolling on
+                            // unless it reads the post instead
+                            if self.locked_out(selected_post.id, policy_rng) == Decision::ReadPost {
+                                return self.start_reading_post(selected_post, engine, config);
+                            }
+                        } else {
+                            // It passed up reading the post, so would comment
+                            // on it unread
+                            if config.nudges.enabled(Nudge::ReadBeforeShare)
+                                && self.nudge(Nudge::ReadBeforeShare, config, policy_rng)
+                            {
+                                return self.start_reading_post(selected_post, engine, config);
+                            }
+                            return self.start_creating_comment(selected_post.id, ids, config);
+                        }
                     }
                 }
             }
         }
 
+        if config.nudges.enabled(Nudge::CaughtUp)
+            && self.caught_up(engine, config.nudges.caught_up_window)
+            && self.nudge(Nudge::CaughtUp, config, policy_rng)
+        {
+            return AgentState::Offline;
+        }
+
         // Check if we should go offline
-        if self.should_go_offline() {
+        if self.go_offline(engine, config, policy_rng) {
             return AgentState::Offline;
         }
 
@@ -234,35 +585,58 @@ impl Individual {
         self.proceed_to_scrolling(engine, config)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn proceed_from_reading_post(
         &mut self,
         engine: &RecommendationEngine,
+        ops: &mut Vec<EngineOp>,
+        ids: &mut IdAllocator,
         config: &SimulationConfig,
-        post_id: usize,
-        creator_id: usize,
+        rng: &mut StreamRng,
+        policy_rng: &mut StreamRng,
+        post_id: PostId,
+        creator_id: AgentId,
         mut ticks_spent: i32,
         ticks_required: i32,
         potential_interest_gain: f32,
     ) -> AgentState {
         ticks_spent += 1;
 
-        if ticks_spent >= ticks_required || random::<f32>() > self.attention_span {
-            self.viewed_content.push(post_id);
+        if ticks_spent >= ticks_required || rng.gen::<f32>() > self.attention_span {
+            self.viewed_content.insert(post_id);
+            self.read_posts.insert(post_id);
 
-            // TODO: Update interests based on the post content
+            if let Some(post) = engine.get_content_by_id(post_id) {
+                let completion = completion_fraction(ticks_spent, ticks_required);
+                let complaint = self.complaint_about(engine, post, config);
+                if self.conflicts_with(post, config.wellbeing.conflict_threshold) {
+                    self.wellbeing.conflict(&config.wellbeing);
+                }
+                self.churn.react(
+                    &mut self.preferred_creators,
+                    engine.current_step,
+                    self.core.id,
+                    creator_id,
+                    complaint,
+                    completion,
+                    config.unfollow_strikes,
+                );
+                self.update_interest_from_post(engine, post, completion, potential_interest_gain);
+                ops.push(EngineOp::RecordPostRead {
+                    post_id,
+                    reader_id: self.core.id,
+                });
+                if config.sharing.enabled() {
+                    self.react_to_post(post, completion, ids, ops, config);
+                }
+            }
 
-            if self.should_go_offline() {
+            if self.go_offline(engine, config, policy_rng) {
                 AgentState::Offline
             } else {
                 self.proceed_to_scrolling(engine, config)
             }
         } else {
-            let post = engine.get_content_by_id(post_id).unwrap();
-            self.update_interests_from_profile(
-                &post.interest_profile,
-                ticks_spent,
-                potential_interest_gain,
-            );
             AgentState::ReadingPost {
                 post_id,
                 creator_id,
@@ -273,28 +647,88 @@ impl Individual {
         }
     }
 
-    fn update_interests_from_profile(
+    // Likes and reshares are rolled on a stream of their own for each post
+    // read, so turning them on leaves every other choice the agent makes as
+    // it was
+    fn react_to_post(
         &mut self,
-        interest_profile: &InterestProfile,
-        ticks_spent: i32,
-        potential_interest_gain: f32,
+        post: &Post,
+        completion: f32,
+        ids: &mut IdAllocator,
+        ops: &mut Vec<EngineOp>,
+        config: &SimulationConfig,
+    ) {
+        let policy = Arc::clone(&self.policy);
+        let sharing = &config.sharing;
+        let key = (self.core.id.0 as u64).rotate_left(32) ^ post.id.0 as u64;
+        let mut rng = RngStreams::keyed(config.seed, "sharing", key);
+
+        if sharing.like_chance > 0.0
+            && self.record(
+                "like post",
+                policy.should_like(self, sharing.like_chance * completion, &mut rng),
+            )
+        {
+            ops.push(EngineOp::LikePost {
+                post_id: post.id,
+                agent_id: self.core.id,
+            });
+        }
+        if sharing.reshare_chance > 0.0
+            && self.record(
+                "reshare post",
+                policy.should_reshare(self, sharing.reshare_chance * completion, &mut rng),
+            )
+        {
+            let reshare = post.reshare(ids.next_post_id(), self.core.id);
+            ops.push(EngineOp::PublishPost(Box::new(reshare)));
+        }
+    }
+
+    // Interest gain is only realised once the agent stops reading, scaled by
+    // how much of the content they actually got through
+    fn update_interest_from_post(
+        &mut self,
+        engine: &RecommendationEngine,
+        post: &Post,
+        completion: f32,
+        potential_gain: f32,
     ) {
-        // Starts at 1/2 gained in first tick, then progressively approaches
-        // 1/1 in subsequent ticks
-        let interest_this_tick = potential_interest_gain / (ticks_spent + 1) as f32;
+        self.core.interest_profile.update_interest_from_profile(
+            &post.interest_profile,
+            potential_gain * completion * self.susceptibility,
+            &engine.tag_similarity,
+            &engine.tag_to_index,
+        );
+    }
 
-        self.core
-            .interest_profile
-            .update_interest_from_profile(&interest_profile, interest_this_tick);
+    fn update_interest_from_comment(
+        &mut self,
+        engine: &RecommendationEngine,
+        comment: &Comment,
+        completion: f32,
+        potential_gain: f32,
+    ) {
+        self.core.interest_profile.update_interest_from_profile(
+            &comment.interest_profile,
+            potential_gain * completion * self.susceptibility,
+            &engine.tag_similarity,
+            &engine.tag_to_index,
+        );
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn proceed_from_reading_comments(
         &mut self,
         engine: &RecommendationEngine,
+        ops: &mut Vec<EngineOp>,
+        ids: &mut IdAllocator,
         config: &SimulationConfig,
-        post_id: usize,
-        creator_id: usize,
-        current_comment_ids: Vec<usize>,
+        rng: &mut StreamRng,
+        policy_rng: &mut StreamRng,
+        post_id: PostId,
+        creator_id: AgentId,
+        current_comment_ids: CommentIdList,
         current_comment_index: usize,
         mut ticks_spent: i32,
         ticks_required: i32,
@@ -303,93 +737,173 @@ impl Individual {
         // Progress reading current comment at start of tick
         ticks_spent += 1;
 
-        if let Some(post) = engine.get_content_by_id(post_id) {
-            if let Some(current_comment) = post
-                .comments
-                .iter()
-                .find(|c| c.id == current_comment_ids[current_comment_index])
-            {
-                self.update_interests_from_profile(
-                    &current_comment.interest_profile,
+        // The post may have disappeared from the pool while we were reading
+        let Some(post) = engine.get_content_by_id(post_id) else {
+            return self.proceed_to_scrolling(engine, config);
+        };
+
+        let current_comment = post
+            .comments
+            .iter()
+            .find(|c| c.id == current_comment_ids[current_comment_index]);
+
+        if let Some(comment) = current_comment {
+            // Keep reading until we're finished or bored
+            if ticks_spent < ticks_required && rng.gen::<f32>() <= self.attention_span {
+                return AgentState::ReadingComments {
+                    post_id,
+                    creator_id,
+                    current_comment_ids,
+                    current_comment_index,
                     ticks_spent,
+                    ticks_required,
                     potential_interest_gain,
-                );
+                };
+            }
 
-                // If we're finished or bored
-                if ticks_spent >= ticks_required || random::<f32>() > self.attention_span {
-                    // Maybe read the post if we haven't yet
-                    if self.should_read_post() && !self.viewed_content.contains(&post_id) {
-                        return AgentState::ReadingPost {
-                            post_id,
-                            creator_id,
-                            ticks_spent: 0,
-                            ticks_required: (post.length as f32 * (1.0 - self.read_speed)) as i32,
-                            potential_interest_gain: self
-                                .calculate_potential_interest_gain(post, engine),
-                        };
-                    }
+            self.update_interest_from_comment(
+                engine,
+                comment,
+                completion_fraction(ticks_spent, ticks_required),
+                potential_interest_gain,
+            );
+            ops.push(EngineOp::RecordCommentRead {
+                post_id,
+                comment_id: comment.id,
+            });
+        }
 
-                    // Maybe write our own comment
-                    if self.should_write_comment() {
-                        let comment_id = rand::thread_rng().next_u32() as usize;
-                        return AgentState::CreatingComment {
-                            post_id,
-                            comment_id,
-                            ticks_spent: 0,
-                            ticks_required: (config.max_comment_length as f32
-                                * (1.0 - self.core.create_speed))
-                                as i32,
-                        };
-                    }
+        let post_read = self.read_posts.contains(&post_id);
+
+        let mut decision = self.policy().after_comment(self, post_read, policy_rng);
+        self.decisions.push(DecisionRecord {
+            state: self.core.state.name().into(),
+            question: "after comment".into(),
+            outcome: decision.name().into(),
+            roll: None,
+            threshold: None,
+        });
+        if decision == Decision::WriteComment && engine.comments_locked(post_id) {
+            decision = self.locked_out(post_id, policy_rng);
+        }
 
-                    // Maybe go offline
-                    if self.should_go_offline() {
-                        return AgentState::Offline;
-                    }
+        let prompted = decision == Decision::WriteComment
+            && !post_read
+            && config.nudges.enabled(Nudge::ReadBeforeShare);
+        if prompted && self.nudge(Nudge::ReadBeforeShare, config, policy_rng) {
+            return self.start_reading_post(post, engine, config);
+        }
+        let staying = matches!(decision, Decision::Scroll | Decision::Continue);
+        if staying
+            && (self.leaves_off_peak(engine, config, policy_rng)
+                || self.heeds_reminder(config, policy_rng))
+        {
+            return AgentState::Offline;
+        }
 
-                    // Maybe move to next comment
-                    let next_index = current_comment_index + 1;
-                    if next_index < current_comment_ids.len() {
-                        if let Some(next_comment) = post
-                            .comments
-                            .iter()
-                            .find(|c| c.id == current_comment_ids[next_index])
-                        {
-                            return AgentState::ReadingComments {
-                                post_id,
-                                creator_id,
-                                current_comment_ids,
-                                current_comment_index: next_index,
-                                ticks_spent: 0,
-                                ticks_required: (next_comment.length as f32
-                                    * (1.0 - self.read_speed))
-                                    as i32,
-                                potential_interest_gain: self
-                                    .calculate_potential_interest_gain_from_comment(
-                                        next_comment,
-                                        engine,
-                                    ),
-                            };
-                        }
-                    }
+        match decision {
+            Decision::ReadPost => self.start_reading_post(post, engine, config),
+            Decision::WriteComment => self.start_creating_comment(post_id, ids, config),
+            Decision::GoOffline => AgentState::Offline,
+            Decision::Scroll => self.proceed_to_scrolling(engine, config),
+            Decision::Continue => self
+                .advance_to_next_comment(
+                    post,
+                    current_comment_ids,
+                    current_comment_index,
+                    engine,
+                    config,
+                )
+                .unwrap_or_else(|| self.proceed_to_scrolling(engine, config)),
+        }
+    }
 
-                    // Maybe go back to scrolling
-                    if self.should_scroll() {
-                        return self.proceed_to_scrolling(engine, config);
-                    }
-                }
-            }
+    // Moves on to the comment after `current_comment_index`, fetching a further
+    // batch of recommendations from the engine once the current list runs out
+    fn advance_to_next_comment(
+        &self,
+        post: &Post,
+        mut current_comment_ids: CommentIdList,
+        current_comment_index: usize,
+        engine: &RecommendationEngine,
+        config: &SimulationConfig,
+    ) -> Option<AgentState> {
+        let next_index = current_comment_index + 1;
+
+        if next_index >= current_comment_ids.len() {
+            let more_ids = engine.get_comment_recommendations(post.id, &current_comment_ids, 10)?;
+            current_comment_ids.extend(more_ids);
         }
 
-        // Continue reading current comment if we haven't finished and didn't transition to another state
-        AgentState::ReadingComments {
+        // Skip over any comments that can no longer be found on the post
+        let index = (next_index..current_comment_ids.len()).find(|&index| {
+            post.comments
+                .iter()
+                .any(|comment| comment.id == current_comment_ids[index])
+        })?;
+        self.start_reading_comment(post, current_comment_ids, index, engine, config)
+    }
+
+    // Builds the ReadingComments state for the comment at `index`, with ticks
+    // and interest gain recomputed for that comment
+    fn start_reading_comment(
+        &self,
+        post: &Post,
+        comment_ids: CommentIdList,
+        index: usize,
+        engine: &RecommendationEngine,
+        config: &SimulationConfig,
+    ) -> Option<AgentState> {
+        let comment_id = *comment_ids.get(index)?;
+        let comment = post.comments.iter().find(|c| c.id == comment_id)?;
+
+        Some(AgentState::ReadingComments {
+            post_id: post.id,
+            creator_id: post.creator_id,
+            ticks_required: config.read_time.ticks(
+                comment.length,
+                self.read_speed,
+                ReadMedia::Comment,
+            ),
+            potential_interest_gain: self
+                .calculate_potential_interest_gain_from_comment(comment, engine),
+            current_comment_ids: comment_ids,
+            current_comment_index: index,
+            ticks_spent: 0,
+        })
+    }
+
+    fn start_reading_post(
+        &self,
+        post: &Post,
+        engine: &RecommendationEngine,
+        config: &SimulationConfig,
+    ) -> AgentState {
+        AgentState::ReadingPost {
+            post_id: post.id,
+            creator_id: post.creator_id,
+            ticks_spent: 0,
+            ticks_required: config.read_time.ticks(
+                post.length,
+                self.read_speed,
+                ReadMedia::Post(post.kind),
+            ),
+            potential_interest_gain: self.calculate_potential_interest_gain(post, engine),
+        }
+    }
+
+    fn start_creating_comment(
+        &self,
+        post_id: PostId,
+        ids: &mut IdAllocator,
+        config: &SimulationConfig,
+    ) -> AgentState {
+        AgentState::CreatingComment {
             post_id,
-            creator_id,
-            current_comment_ids,
-            current_comment_index,
-            ticks_spent,
-            ticks_required,
-            potential_interest_gain,
+            comment_id: ids.next_comment_id(),
+            ticks_spent: 0,
+            ticks_required: (config.max_comment_length as f32 * (1.0 - self.core.create_speed))
+                as i32,
         }
     }
 
@@ -435,28 +949,34 @@ impl Individual {
         base_gain * similarity_multiplier
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn proceed_from_creating_post(
         &mut self,
-        engine: &mut RecommendationEngine,
+        engine: &RecommendationEngine,
+        ops: &mut Vec<EngineOp>,
         config: &SimulationConfig,
-        post_id: usize,
+        rng: &mut StreamRng,
+        policy_rng: &mut StreamRng,
+        post_id: PostId,
         mut ticks_spent: i32,
         ticks_required: i32,
     ) -> AgentState {
         ticks_spent += 1;
 
         if ticks_spent >= ticks_required {
-            let content = self.core.generate_content(config);
+            let content = self
+                .core
+                .generate_content(post_id, AgentType::Individual, config, rng);
 
             self.core.created_content.push(content.id);
 
-            engine.create_post(content);
+            ops.push(EngineOp::PublishPost(Box::new(content)));
 
-            if self.should_go_offline() {
-                return AgentState::Offline;
+            if self.go_offline(engine, config, policy_rng) {
+                AgentState::Offline
             } else {
-                return self.proceed_to_scrolling(engine, config);
-            };
+                self.proceed_to_scrolling(engine, config)
+            }
         } else {
             // Continue creating post
             AgentState::CreatingPost {
@@ -467,24 +987,37 @@ impl Individual {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn proceed_from_creating_comment(
         &mut self,
-        engine: &mut RecommendationEngine,
+        engine: &RecommendationEngine,
+        ops: &mut Vec<EngineOp>,
         config: &SimulationConfig,
-        post_id: usize,
-        comment_id: usize,
+        rng: &mut StreamRng,
+        policy_rng: &mut StreamRng,
+        post_id: PostId,
+        comment_id: CommentId,
         mut ticks_spent: i32,
         ticks_required: i32,
     ) -> AgentState {
         ticks_spent += 1;
 
         if ticks_spent >= ticks_required {
-            let comment = Comment::new(comment_id, self.core.interest_profile.clone(), config);
+            let comment = self.core.generate_comment(comment_id, config, rng);
+            // Arguing back winds the writer up
+            if engine
+                .get_content_by_id(post_id)
+                .is_some_and(|post| self.conflicts_with(post, config.wellbeing.conflict_threshold))
+            {
+                self.wellbeing.arouse(&config.wellbeing);
+            }
+
+            self.core.created_comments.push(comment.id);
 
-            engine.add_comment_to_post(post_id, comment);
+            ops.push(EngineOp::AddComment { post_id, comment });
 
             // After creating a comment, we might:
-            if self.should_go_offline() {
+            if self.go_offline(engine, config, policy_rng) {
                 return AgentState::Offline;
             }
 
@@ -506,104 +1039,267 @@ impl Individual {
     fn proceed_to_scrolling(
         &mut self,
         engine: &RecommendationEngine,
-        _config: &SimulationConfig,
+        config: &SimulationConfig,
     ) -> AgentState {
-        let recommended_post_ids =
-            engine.get_post_recommendations(&self, 10, chrono::Utc::now().timestamp());
+        self.preferences
+            .update(&self.core.interest_profile, config.feed_control_threshold);
+
+        // Ads take their slots first, and are worth more the more attention
+        // the agent is paying
+        let ads = config.ads.per_feed.min(FEED_LENGTH);
+        if ads > 0 {
+            let value = config
+                .ads
+                .impression_value(self.attention_span, self.session_length_ticks);
+            self.ads.serve(ads, value);
+        }
+
+        let recommended_post_ids = engine.get_post_recommendations(
+            self,
+            FEED_LENGTH - ads,
+            engine.now(),
+            &CreatorExclusions::for_agent(self),
+        );
 
         // Add retrieved recommendations to viewed content which the engine
         // filters out from future recommendations
-        self.viewed_content.extend(recommended_post_ids.clone());
+        self.viewed_content
+            .extend(recommended_post_ids.iter().copied());
+        self.feeds.push(ArchivedFeed {
+            step: engine.current_step,
+            ads,
+            slate: recommended_post_ids
+                .iter()
+                .filter_map(|&id| engine.get_content_by_id(id))
+                .map(FeedSlot::of)
+                .collect(),
+            chosen: Vec::new(),
+        });
 
         AgentState::Scrolling {
             recommended_post_ids,
         }
     }
 
-    fn select_post_from_recommendations(
+    // What, if anything, put the agent off a post: too little in common with
+    // its interests, or a stance too far from its own on a shared tag
+    fn complaint_about(
         &self,
-        recommended_post_ids: Vec<usize>,
         engine: &RecommendationEngine,
-    ) -> Option<usize> {
+        post: &Post,
+        config: &SimulationConfig,
+    ) -> Option<ChurnReason> {
+        let alignment = engine.calculate_vector_similarity(
+            &self.core.interest_profile.vector_representation,
+            &post.interest_profile.vector_representation,
+        );
+        if alignment < config.unfollow_interest_threshold {
+            return Some(ChurnReason::LowInterest);
+        }
+
+        self.conflicts_with(post, config.unfollow_conflict_threshold)
+            .then_some(ChurnReason::Conflict)
+    }
+
+    // Whether the post takes a stance on any tag the agent cares about that is
+    // more than `threshold` away from the agent's own
+    fn conflicts_with(&self, post: &Post, threshold: f32) -> bool {
+        post.interest_profile.interests.iter().any(|(tag, topic)| {
+            self.core
+                .interest_profile
+                .interests
+                .get(tag)
+                .is_some_and(|own| (own.agreement - topic.agreement).abs() > threshold)
+        })
+    }
+
+    // Picks a post weighted by how relevant it is, how appealing it is and by
+    // how near the top of the feed it sits
+    fn select_post_from_recommendations(
+        &mut self,
+        recommended_post_ids: PostIdList,
+        engine: &RecommendationEngine,
+        config: &SimulationConfig,
+        rng: &mut StreamRng,
+    ) -> Option<PostId> {
         if recommended_post_ids.is_empty() {
             return None;
         }
 
         let agent_vector = &self.core.interest_profile.vector_representation;
 
+        // (post, relevance, relevance scaled by appeal, position and length)
         let scored_recommendations: Vec<_> = recommended_post_ids
             .iter()
-            .map(|id| {
+            .enumerate()
+            .map(|(position, id)| {
                 // TODO: Unwrap bad, should properly handle when ID isn't found
                 let content = engine.get_content_by_id(*id).unwrap();
                 let similarity = engine.calculate_vector_similarity(
                     agent_vector,
                     &content.interest_profile.vector_representation,
                 );
-                (content, similarity)
+                let biased = similarity
+                    * appeal_weight(content.appeal, config.appeal_effect)
+                    * position_weight(position, config.position_bias)
+                    * length_weight(
+                        content.length,
+                        config.max_post_length,
+                        self.short_form_preference,
+                    );
+                (content.id, similarity, biased)
             })
             .collect();
 
-        let total_similarity: f32 = scored_recommendations
-            .iter()
-            .map(|(_, similarity)| similarity)
-            .sum();
+        let total_similarity: f32 = scored_recommendations.iter().map(|(_, s, _)| s).sum();
+        let total_biased: f32 = scored_recommendations.iter().map(|(_, _, b)| b).sum();
 
-        let mut random_value = random::<f32>() * total_similarity;
+        let mut random_value = rng.gen::<f32>() * total_biased;
 
-        for (content, similarity) in &scored_recommendations {
-            random_value -= similarity;
-            if random_value <= 0.0 {
-                return Some(content.id);
-            }
+        let position = scored_recommendations
+            .iter()
+            .position(|(_, _, biased)| {
+                random_value -= biased;
+                random_value <= 0.0
+            })
+            .unwrap_or(scored_recommendations.len() - 1);
+
+        let (post_id, similarity, biased) = scored_recommendations[position];
+        self.feeds.choose(post_id);
+        if total_biased > 0.0 {
+            self.selections.record(
+                position,
+                biased / total_biased,
+                similarity / total_similarity,
+            );
         }
 
-        scored_recommendations.last().map(|(content, _)| content.id)
+        Some(post_id)
     }
 
-    // TODO: Proper probability calculations for these functions
-    fn should_go_offline(&self) -> bool {
-        // Should get higher probability the longer we've been scrolling for
-        // if random::<f32>() > 0.9 {
-        //     return true;
-        // }
-        false
+    // Logs a choice made in the current state and returns whether it was taken
+    fn record(&mut self, question: &'static str, choice: Choice) -> bool {
+        self.decisions.push(DecisionRecord {
+            state: self.core.state.name().into(),
+            question: question.into(),
+            outcome: if choice.taken { "yes" } else { "no" }.into(),
+            roll: choice.roll,
+            threshold: choice.threshold,
+        });
+        choice.taken
     }
 
-    fn should_select_post(&self) -> bool {
-        // Higher interest alignment in the post should increase probability of
-        // selecting that post
-        if random::<f32>() > 0.5 {
+    // Asks the policy whether to go offline and, if the agent would stay on,
+    // whether it logs off for the time of day or heeds a time reminder instead
+    fn go_offline(
+        &mut self,
+        engine: &RecommendationEngine,
+        config: &SimulationConfig,
+        policy_rng: &mut StreamRng,
+    ) -> bool {
+        if self.record(
+            "go offline",
+            self.policy().should_go_offline(self, policy_rng),
+        ) {
             return true;
         }
-        false
+        self.leaves_off_peak(engine, config, policy_rng) || self.heeds_reminder(config, policy_rng)
     }
 
-    fn should_read_post(&self) -> bool {
-        if random::<f32>() > 0.5 {
-            return true;
-        }
-        false
+    // Outside its active hours every choice to stay on is a chance to log off,
+    // as likely as coming online there is not
+    fn leaves_off_peak(
+        &mut self,
+        engine: &RecommendationEngine,
+        config: &SimulationConfig,
+        rng: &mut StreamRng,
+    ) -> bool {
+        let activity = self
+            .active_hours
+            .activity(engine.current_step, config.steps_per_day);
+        activity < 1.0 && self.record("log off off-peak", Choice::roll_above(activity, rng))
     }
 
-    fn should_read_comments(&self) -> bool {
-        if random::<f32>() > 0.5 {
-            return true;
-        }
-        false
+    // What the agent does instead of commenting on a post locked to comments
+    fn locked_out(&mut self, post_id: PostId, rng: &mut StreamRng) -> Decision {
+        let post_read = self.read_posts.contains(&post_id);
+        let decision = self.policy().comments_locked(self, post_read, rng);
+        self.decisions.push(DecisionRecord {
+            state: self.core.state.name().into(),
+            question: "comments locked".into(),
+            outcome: decision.name().into(),
+            roll: None,
+            threshold: None,
+        });
+        decision
     }
 
-    fn should_write_comment(&self) -> bool {
-        if random::<f32>() > 0.5 {
-            return true;
-        }
-        false
+    // Shows the agent a nudge and returns whether it complies
+    fn nudge(&mut self, nudge: Nudge, config: &SimulationConfig, rng: &mut StreamRng) -> bool {
+        let complied = self.record(nudge.name(), comply(config.nudges.compliance(nudge), rng));
+        let counts = self.nudges.get_mut(nudge);
+        counts.shown += 1;
+        counts.complied += complied as usize;
+        complied
     }
 
-    fn should_scroll(&self) -> bool {
-        if random::<f32>() > 0.5 {
-            return true;
+    // Once a session is past the reminder, every choice to stay on is another
+    // chance to heed it. The reminder itself is only counted as shown once
+    fn heeds_reminder(&mut self, config: &SimulationConfig, rng: &mut StreamRng) -> bool {
+        let nudges = &config.nudges;
+        if !nudges.enabled(Nudge::TimeReminder) || self.session_length_ticks < nudges.reminder_ticks
+        {
+            return false;
         }
-        false
+        let complied = self.record(
+            Nudge::TimeReminder.name(),
+            comply(nudges.time_reminder, rng),
+        );
+        self.nudges.time_reminder.complied += complied as usize;
+        complied
     }
+
+    // Whether the agent has seen every post published in the last `window`
+    // steps that it could be shown
+    fn caught_up(&self, engine: &RecommendationEngine, window: u64) -> bool {
+        let since = engine.current_step.saturating_sub(window);
+        engine
+            .content_pool
+            .iter()
+            .rev()
+            .take_while(|post| post.published_step >= since)
+            .all(|post| {
+                post.creator_id == self.core.id
+                    || engine.banned_creators.contains(&post.creator_id)
+                    || self.viewed_content.contains(&post.id)
+            })
+    }
+
+    fn policy(&self) -> &dyn TransitionPolicy {
+        self.policy.as_ref()
+    }
+}
+
+// Whether an agent complies with a nudge, given the chance it does
+fn comply(compliance: f32, rng: &mut StreamRng) -> Choice {
+    let roll = rng.gen::<f32>();
+    Choice {
+        taken: roll < compliance,
+        roll: Some(roll),
+        threshold: Some(compliance),
+    }
+}
+
+// Fraction of an item that was consumed, where an item needing no ticks counts
+// as fully read
+fn completion_fraction(ticks_spent: i32, ticks_required: i32) -> f32 {
+    if ticks_required <= 0 {
+        return 1.0;
+    }
+
+    (ticks_spent as f32 / ticks_required as f32).min(1.0)
+}
+
+fn default_policy() -> Arc<dyn TransitionPolicy> {
+    Arc::new(DefaultPolicy)
 }
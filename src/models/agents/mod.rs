@@ -1,9 +1,25 @@
+pub mod archive;
 pub mod bot;
+pub mod cohort;
 pub mod common;
+pub mod follows;
 pub mod individual;
+pub mod nudges;
 pub mod organisation;
+pub mod persona;
+pub mod policy;
+pub mod preferences;
+pub mod selection;
 
+pub use archive::*;
 pub use bot::*;
+pub use cohort::*;
 pub use common::*;
+pub use follows::*;
 pub use individual::*;
+pub use nudges::*;
 pub use organisation::*;
+pub use persona::*;
+pub use policy::*;
+pub use preferences::*;
+pub use selection::*;
@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+
+// Prompts a platform can show to add friction or suggest a break
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Nudge {
+    // Shown to an agent about to comment on a post it hasn't read. Complying
+    // means reading the post first
+    ReadBeforeShare,
+    // Shown once the agent has seen every recent post. Complying ends the
+    // session
+    CaughtUp,
+    // Shown once a session has run long enough. Complying, at any later point
+    // the agent would otherwise stay on, ends the session
+    TimeReminder,
+}
+
+impl Nudge {
+    pub const ALL: [Nudge; 3] = [Nudge::ReadBeforeShare, Nudge::CaughtUp, Nudge::TimeReminder];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Nudge::ReadBeforeShare => "read before share",
+            Nudge::CaughtUp => "caught up",
+            Nudge::TimeReminder => "time reminder",
+        }
+    }
+}
+
+// Which nudges the platform shows and the chance an individual complies each
+// time it sees one. A nudge with compliance 0 is never shown
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NudgeConfig {
+    pub read_before_share: f32,
+    pub caught_up: f32,
+    pub time_reminder: f32,
+    // Steps back the "caught up" marker looks for posts the agent hasn't seen
+    pub caught_up_window: u64,
+    // Ticks into a session before the time reminder is shown
+    pub reminder_ticks: i32,
+}
+
+impl Default for NudgeConfig {
+    fn default() -> Self {
+        Self {
+            read_before_share: 0.0,
+            caught_up: 0.0,
+            time_reminder: 0.0,
+            caught_up_window: 50,
+            reminder_ticks: 200,
+        }
+    }
+}
+
+impl NudgeConfig {
+    pub fn compliance(&self, nudge: Nudge) -> f32 {
+        match nudge {
+            Nudge::ReadBeforeShare => self.read_before_share,
+            Nudge::CaughtUp => self.caught_up,
+            Nudge::TimeReminder => self.time_reminder,
+        }
+    }
+
+    pub fn set_compliance(&mut self, nudge: Nudge, compliance: f32) {
+        match nudge {
+            Nudge::ReadBeforeShare => self.read_before_share = compliance,
+            Nudge::CaughtUp => self.caught_up = compliance,
+            Nudge::TimeReminder => self.time_reminder = compliance,
+        }
+    }
+
+    pub fn enabled(&self, nudge: Nudge) -> bool {
+        self.compliance(nudge) > 0.0
+    }
+}
+
+// How often a nudge was shown and how often it was complied with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct NudgeCounts {
+    pub shown: usize,
+    pub complied: usize,
+}
+
+impl NudgeCounts {
+    pub fn compliance(&self) -> Option<f32> {
+        (self.shown > 0).then(|| self.complied as f32 / self.shown as f32)
+    }
+}
+
+// Counts for every nudge an agent has been shown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct NudgeStats {
+    pub read_before_share: NudgeCounts,
+    pub caught_up: NudgeCounts,
+    pub time_reminder: NudgeCounts,
+}
+
+impl NudgeStats {
+    pub fn get(&self, nudge: Nudge) -> &NudgeCounts {
+        match nudge {
+            Nudge::ReadBeforeShare => &self.read_before_share,
+            Nudge::CaughtUp => &self.caught_up,
+            Nudge::TimeReminder => &self.time_reminder,
+        }
+    }
+
+    pub fn get_mut(&mut self, nudge: Nudge) -> &mut NudgeCounts {
+        match nudge {
+            Nudge::ReadBeforeShare => &mut self.read_before_share,
+            Nudge::CaughtUp => &mut self.caught_up,
+            Nudge::TimeReminder => &mut self.time_reminder,
+        }
+    }
+
+    pub fn add(&mut self, other: &NudgeStats) {
+        for nudge in Nudge::ALL {
+            let (counts, other) = (self.get_mut(nudge), other.get(nudge));
+            counts.shown += other.shown;
+            counts.complied += other.complied;
+        }
+    }
+}
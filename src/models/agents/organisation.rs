@@ -1,32 +1,57 @@
-use super::{Agent, AgentCore, AgentState, AgentType};
+use super::{Agent, AgentCore, AgentState, AgentType, SavedAgent, TickContext};
 use crate::{
-    models::{InterestProfile, SimulationConfig, Topic},
+    engine::EngineOp,
+    models::{
+        AgentId, DriftSample, Earnings, IdAllocator, InterestProfile, PostId, RngStreams,
+        SimulationConfig, StreamRng, Topic, TopicDrift,
+    },
     RecommendationEngine,
 };
-use rand::{random, Rng, RngCore};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Organisation {
     pub core: AgentCore,
+    // Tips and subscriptions from followers
+    pub earnings: Earnings,
+    // How its topics have moved with its audience's response
+    #[serde(default)]
+    pub drift: TopicDrift,
 }
 
 impl Agent for Organisation {
-    fn tick(&mut self, engine: &mut RecommendationEngine, config: &SimulationConfig) {
+    fn tick(&mut self, ctx: &mut TickContext) {
+        // Takes stock of its audience as each post is finished, so the post
+        // reflects it
+        if let AgentState::CreatingPost {
+            ticks_spent,
+            ticks_required,
+            ..
+        } = self.core.state
+        {
+            if ctx.config.drift.enabled() && ticks_spent + 1 >= ticks_required {
+                self.respond_to_audience(ctx.engine, ctx.config);
+            }
+        }
+
         let new_state = match &self.core.state {
             AgentState::CreatingPost {
                 post_id,
                 ticks_spent,
                 ticks_required,
             } => self.proceed_from_creating_post(
-                config,
-                engine,
+                ctx.config,
+                ctx.ops,
+                ctx.ids,
+                &mut ctx.rng.content,
                 *post_id,
                 *ticks_spent,
                 *ticks_required,
             ),
             _ => {
                 // Organizations, like bots, should always be creating
-                self.start_creating_post()
+                self.start_creating_post(ctx.ids, ctx.config, &mut ctx.rng.content)
             }
         };
 
@@ -37,6 +62,29 @@ impl Agent for Organisation {
         Box::new(self.clone())
     }
 
+    fn duplicate(
+        &self,
+        id: AgentId,
+        ids: &mut IdAllocator,
+        config: &SimulationConfig,
+    ) -> Box<dyn Agent> {
+        let mut rng = RngStreams::keyed(config.seed, "organisation", id.0 as u64);
+        let state = AgentState::CreatingPost {
+            post_id: ids.next_post_id(),
+            ticks_spent: 0,
+            ticks_required: Self::calculate_post_ticks(&mut rng),
+        };
+        Box::new(Self {
+            core: self.core.duplicate(id, state),
+            earnings: Earnings::default(),
+            drift: TopicDrift::default(),
+        })
+    }
+
+    fn to_saved(&self) -> SavedAgent {
+        SavedAgent::Organisation(self.clone())
+    }
+
     fn get_type(&self) -> AgentType {
         AgentType::Organisation
     }
@@ -45,27 +93,56 @@ impl Agent for Organisation {
         &self.core.interest_profile
     }
 
+    fn interest_profile_mut(&mut self) -> &mut InterestProfile {
+        &mut self.core.interest_profile
+    }
+
+    fn community(&self) -> Option<usize> {
+        self.core.community
+    }
+
+    fn set_community(&mut self, community: Option<usize>) {
+        self.core.community = community;
+    }
+
+    fn segment(&self) -> Option<&str> {
+        self.core.segment.as_deref()
+    }
+
     fn state(&self) -> &AgentState {
         &self.core.state
     }
 
-    fn id(&self) -> &usize {
+    fn earnings(&self) -> Option<&Earnings> {
+        Some(&self.earnings)
+    }
+
+    fn earnings_mut(&mut self) -> Option<&mut Earnings> {
+        Some(&mut self.earnings)
+    }
+
+    fn drift(&self) -> Option<&TopicDrift> {
+        Some(&self.drift)
+    }
+
+    fn id(&self) -> &AgentId {
         &self.core.id
     }
 }
 
 impl Organisation {
-    pub fn new(id: usize, config: &SimulationConfig) -> Self {
+    pub fn new(id: AgentId, ids: &mut IdAllocator, config: &SimulationConfig) -> Self {
+        let mut rng = RngStreams::keyed(config.seed, "organisation", id.0 as u64);
         let mut interest_profile = InterestProfile::new(100);
 
         // Organizations are focused - they typically have strong opinions about few topics
-        let tag = &config.sample_tags[rand::thread_rng().gen_range(0..config.sample_tags.len())];
+        let tag = &config.sample_tags[rng.gen_range(0..config.sample_tags.len())];
 
         // Organizations tend to have strong opinions (agreements closer to +1 or -1)
-        let agreement = if random::<f32>() > 0.5 {
-            0.7 + random::<f32>() * 0.3 // Strong positive (0.7 to 1.0)
+        let agreement = if rng.gen::<f32>() > 0.5 {
+            0.7 + rng.gen::<f32>() * 0.3 // Strong positive (0.7 to 1.0)
         } else {
-            -1.0 + random::<f32>() * 0.3 // Strong negative (-1.0 to -0.7)
+            -1.0 + rng.gen::<f32>() * 0.3 // Strong negative (-1.0 to -0.7)
         };
 
         interest_profile.interests.insert(
@@ -83,22 +160,56 @@ impl Organisation {
                 id,
                 content_creation_frequency: 1.0, // Organizations always create
                 created_content: Vec::new(),
+                created_comments: Vec::new(),
                 create_speed: 1.0,
                 state: AgentState::CreatingPost {
-                    post_id: rand::thread_rng().next_u32() as usize,
+                    post_id: ids.next_post_id(),
                     ticks_spent: 0,
-                    ticks_required: Self::calculate_post_ticks(),
+                    ticks_required: Self::calculate_post_ticks(&mut rng),
                 },
                 interest_profile,
+                community: None,
+                segment: config.segments.assign(config.seed, id),
             },
+            earnings: Earnings::default(),
+            drift: TopicDrift::default(),
         }
     }
 
+    // Moves its interests towards the response to its latest posts, if there
+    // was any, and traces the move
+    fn respond_to_audience(&mut self, engine: &RecommendationEngine, config: &SimulationConfig) {
+        let latest = self
+            .core
+            .created_content
+            .iter()
+            .rev()
+            .take(config.drift.window);
+        let posts = latest.filter_map(|&post_id| engine.get_content_by_id(post_id));
+        let Some((comments, shift)) = config.drift.respond(&mut self.core.interest_profile, posts)
+        else {
+            return;
+        };
+        engine.vectorise(&mut self.core.interest_profile);
+        self.drift.push(
+            DriftSample {
+                step: engine.current_step,
+                comments,
+                shift,
+                profile: self.core.interest_profile.to_table(),
+            },
+            config.drift.trace_length,
+        );
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn proceed_from_creating_post(
         &mut self,
         config: &SimulationConfig,
-        engine: &mut RecommendationEngine,
-        post_id: usize,
+        ops: &mut Vec<EngineOp>,
+        ids: &mut IdAllocator,
+        rng: &mut StreamRng,
+        post_id: PostId,
         mut ticks_spent: i32,
         ticks_required: i32,
     ) -> AgentState {
@@ -106,31 +217,41 @@ impl Organisation {
 
         if ticks_spent >= ticks_required {
             // Generate content and start new creation
-            let content = self.core.generate_content(config);
+            let content = self
+                .core
+                .generate_content(post_id, AgentType::Organisation, config, rng);
             self.core.created_content.push(content.id);
-            engine.create_post(content);
+            ops.push(EngineOp::PublishPost(Box::new(content)));
 
-            return self.start_creating_post();
+            self.start_creating_post(ids, config, rng)
         } else {
             // Continue current creation
-            return AgentState::CreatingPost {
+            AgentState::CreatingPost {
                 post_id,
                 ticks_spent,
                 ticks_required,
-            };
+            }
         }
     }
 
-    fn start_creating_post(&self) -> AgentState {
+    // Organisations that earn from their followers post more often
+    fn start_creating_post(
+        &self,
+        ids: &mut IdAllocator,
+        config: &SimulationConfig,
+        rng: &mut StreamRng,
+    ) -> AgentState {
         AgentState::CreatingPost {
-            post_id: rand::thread_rng().next_u32() as usize,
+            post_id: ids.next_post_id(),
             ticks_spent: 0,
-            ticks_required: Self::calculate_post_ticks(),
+            ticks_required: config
+                .economy
+                .posting_ticks(Self::calculate_post_ticks(rng), self.earnings.income),
         }
     }
 
     // Organizations take longer to create posts than bots
-    fn calculate_post_ticks() -> i32 {
-        (random::<f32>() * 30.0) as i32
+    fn calculate_post_ticks(rng: &mut StreamRng) -> i32 {
+        (rng.gen::<f32>() * 30.0) as i32
     }
 }
@@ -0,0 +1,192 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::models::{InterestProfile, Topic};
+
+use super::Individual;
+
+// How much an archetype cares about one tag and how it leans on it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagPrior {
+    pub tag: String,
+
+    // Relative to the persona's other tags; jittered per agent
+    pub weight: f32,
+
+    // Agreement is drawn uniformly from this (min, max) range
+    pub agreement: (f32, f32),
+
+    // Polarised topics have the drawn agreement negated for half of agents,
+    // so the population splits into two camps rather than one
+    #[serde(default)]
+    pub either_side: bool,
+}
+
+impl TagPrior {
+    fn new(tag: &str, weight: f32, agreement: (f32, f32)) -> Self {
+        Self {
+            tag: tag.to_string(),
+            weight,
+            agreement,
+            either_side: false,
+        }
+    }
+
+    fn polarised(mut self) -> Self {
+        self.either_side = true;
+        self
+    }
+}
+
+// An archetypal user that agents can be seeded from instead of a fully random
+// profile. Behaviour parameters are (min, max) ranges drawn uniformly
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Persona {
+    pub name: String,
+    pub tags: Vec<TagPrior>,
+    pub next_post_likelihood: (f32, f32),
+    pub attention_span: (f32, f32),
+    pub read_speed: (f32, f32),
+    pub content_creation_frequency: (f32, f32),
+    pub create_speed: (f32, f32),
+}
+
+impl Persona {
+    // The built-in personas, tagged with the default sample tags
+    pub fn library() -> Vec<Persona> {
+        vec![
+            Persona {
+                name: "sports fan".to_string(),
+                tags: vec![
+                    TagPrior::new("sports", 1.0, (0.3, 1.0)),
+                    TagPrior::new("entertainment", 0.4, (0.0, 0.6)),
+                    TagPrior::new("health", 0.2, (0.0, 0.5)),
+                ],
+                next_post_likelihood: (0.5, 0.9),
+                attention_span: (0.2, 0.4),
+                read_speed: (0.5, 0.9),
+                content_creation_frequency: (0.05, 0.2),
+                create_speed: (0.4, 0.8),
+            },
+            Persona {
+                name: "political junkie".to_string(),
+                tags: vec![
+                    TagPrior::new("politics", 1.0, (0.6, 1.0)).polarised(),
+                    TagPrior::new("business", 0.3, (0.2, 0.8)).polarised(),
+                    TagPrior::new("education", 0.2, (-0.3, 0.5)),
+                ],
+                next_post_likelihood: (0.7, 1.0),
+                attention_span: (0.3, 0.5),
+                read_speed: (0.4, 0.8),
+                content_creation_frequency: (0.1, 0.3),
+                create_speed: (0.5, 0.9),
+            },
+            // Reads a little of everything and almost never posts
+            Persona {
+                name: "casual lurker".to_string(),
+                tags: vec![
+                    TagPrior::new("entertainment", 1.0, (-0.2, 0.4)),
+                    TagPrior::new("sports", 0.5, (-0.2, 0.4)),
+                    TagPrior::new("health", 0.5, (-0.2, 0.4)),
+                    TagPrior::new("technology", 0.3, (-0.2, 0.4)),
+                ],
+                next_post_likelihood: (0.2, 0.6),
+                attention_span: (0.0, 0.2),
+                read_speed: (0.3, 0.7),
+                content_creation_frequency: (0.0, 0.02),
+                create_speed: (0.1, 0.4),
+            },
+            Persona {
+                name: "tech professional".to_string(),
+                tags: vec![
+                    TagPrior::new("technology", 1.0, (0.2, 0.9)),
+                    TagPrior::new("science", 0.6, (0.3, 0.9)),
+                    TagPrior::new("business", 0.5, (-0.2, 0.6)),
+                ],
+                next_post_likelihood: (0.4, 0.8),
+                attention_span: (0.3, 0.5),
+                read_speed: (0.6, 1.0),
+                content_creation_frequency: (0.05, 0.15),
+                create_speed: (0.6, 1.0),
+            },
+        ]
+    }
+
+    // A starting profile drawn from the persona's priors, with weights summing
+    // to 1. Still needs vectorising
+    pub fn sample_profile<R: Rng + ?Sized>(
+        &self,
+        vector_dimension: usize,
+        rng: &mut R,
+    ) -> InterestProfile {
+        let mut profile = InterestProfile::new(vector_dimension);
+
+        for prior in &self.tags {
+            let mut agreement = sample(prior.agreement, rng);
+            if prior.either_side && rng.gen_bool(0.5) {
+                agreement = -agreement;
+            }
+            profile.interests.insert(
+                prior.tag.clone(),
+                Topic {
+                    weighted_interest: prior.weight * rng.gen_range(0.5..1.5),
+                    agreement: agreement.clamp(-1.0, 1.0),
+                },
+            );
+        }
+
+        profile.normalise_weights();
+        profile
+    }
+
+    // Overwrites an agent's starting profile and behaviour with draws from
+    // this persona
+    pub fn seed<R: Rng + ?Sized>(&self, individual: &mut Individual, rng: &mut R) {
+        let dimension = individual.core.interest_profile.vector_representation.len();
+        individual.core.interest_profile = self.sample_profile(dimension, rng);
+        individual.core.content_creation_frequency = sample(self.content_creation_frequency, rng);
+        individual.core.create_speed = sample(self.create_speed, rng);
+        individual.next_post_likelihood = sample(self.next_post_likelihood, rng);
+        individual.attention_span = sample(self.attention_span, rng);
+        individual.read_speed = sample(self.read_speed, rng);
+        individual.persona = Some(self.name.clone());
+    }
+}
+
+// Draws uniformly from a (min, max) range, tolerating empty ones
+fn sample<R: Rng + ?Sized>((min, max): (f32, f32), rng: &mut R) -> f32 {
+    if max <= min {
+        min
+    } else {
+        rng.gen_range(min..max)
+    }
+}
+
+// Which persona, if any, each of `count` agents is seeded from. Personas are
+// handed out in contiguous blocks sized by their fraction of the population;
+// whatever the fractions leave over gets fully random profiles
+pub fn assign_personas<'a>(
+    personas: &'a [Persona],
+    fractions: &BTreeMap<String, f32>,
+    count: usize,
+) -> Vec<Option<&'a Persona>> {
+    let mut bounds = Vec::new();
+    let mut cumulative = 0.0;
+    for persona in personas {
+        if let Some(fraction) = fractions.get(&persona.name) {
+            cumulative += fraction.max(0.0);
+            bounds.push((cumulative, persona));
+        }
+    }
+
+    (0..count)
+        .map(|index| {
+            let position = (index as f32 + 0.5) / count as f32;
+            bounds
+                .iter()
+                .find(|(bound, _)| position < *bound)
+                .map(|(_, persona)| *persona)
+        })
+        .collect()
+}
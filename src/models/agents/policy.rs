@@ -0,0 +1,310 @@
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use super::Individual;
+
+// What an agent does next once it has finished (or lost interest in) the item
+// it was consuming
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Decision {
+    // Move on to the next item in the current list, e.g. the next comment
+    Continue,
+    ReadPost,
+    WriteComment,
+    Scroll,
+    GoOffline,
+}
+
+impl Decision {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Decision::Continue => "continue",
+            Decision::ReadPost => "read post",
+            Decision::WriteComment => "write comment",
+            Decision::Scroll => "scroll",
+            Decision::GoOffline => "go offline",
+        }
+    }
+}
+
+// The outcome of a yes/no choice, with the roll and threshold behind it when
+// the policy rolled for it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Choice {
+    pub taken: bool,
+    pub roll: Option<f32>,
+    pub threshold: Option<f32>,
+}
+
+impl Choice {
+    // Taken when a uniform roll lands above `threshold`
+    pub fn roll_above<R: Rng + ?Sized>(threshold: f32, rng: &mut R) -> Self {
+        let roll = rng.gen::<f32>();
+        Self {
+            taken: roll > threshold,
+            roll: Some(roll),
+            threshold: Some(threshold),
+        }
+    }
+}
+
+impl From<bool> for Choice {
+    fn from(taken: bool) -> Self {
+        Self {
+            taken,
+            roll: None,
+            threshold: None,
+        }
+    }
+}
+
+// Encapsulates the probabilistic choices an Individual makes between states,
+// so that the state machine itself only deals with moving between them.
+// Policies roll with the simulation's policy stream, so that runs with the
+// same seed make the same choices
+pub trait TransitionPolicy: Debug + Send + Sync {
+    fn should_go_offline(&self, agent: &Individual, rng: &mut dyn RngCore) -> Choice;
+
+    fn should_select_post(&self, agent: &Individual, rng: &mut dyn RngCore) -> Choice;
+
+    fn should_read_post(&self, agent: &Individual, rng: &mut dyn RngCore) -> Choice;
+
+    fn should_read_comments(&self, agent: &Individual, rng: &mut dyn RngCore) -> Choice;
+
+    fn should_write_comment(&self, agent: &Individual, rng: &mut dyn RngCore) -> Choice;
+
+    fn should_scroll(&self, agent: &Individual, rng: &mut dyn RngCore) -> Choice;
+
+    // Called once the agent stops reading a post, with the configured chance
+    // scaled by how much of it was read
+    fn should_like(&self, _agent: &Individual, chance: f32, rng: &mut dyn RngCore) -> Choice {
+        Choice::roll_above(1.0 - chance, rng)
+    }
+
+    fn should_reshare(&self, _agent: &Individual, chance: f32, rng: &mut dyn RngCore) -> Choice {
+        Choice::roll_above(1.0 - chance, rng)
+    }
+
+    // Called when the agent finishes reading a comment. `post_read` is whether
+    // the agent has already read the post the comments belong to
+    fn after_comment(
+        &self,
+        agent: &Individual,
+        post_read: bool,
+        rng: &mut dyn RngCore,
+    ) -> Decision {
+        if !post_read && self.should_read_post(agent, rng).taken {
+            return Decision::ReadPost;
+        }
+
+        if self.should_write_comment(agent, rng).taken {
+            return Decision::WriteComment;
+        }
+
+        if self.should_go_offline(agent, rng).taken {
+            return Decision::GoOffline;
+        }
+
+        if self.should_scroll(agent, rng).taken {
+            return Decision::Scroll;
+        }
+
+        Decision::Continue
+    }
+
+    // Called in place of writing a comment when the post is locked to new
+    // ones. By default the agent reads the post if it hasn't yet and would
+    // like to, and otherwise scrolls on
+    fn comments_locked(
+        &self,
+        agent: &Individual,
+        post_read: bool,
+        rng: &mut dyn RngCore,
+    ) -> Decision {
+        if !post_read && self.should_read_post(agent, rng).taken {
+            return Decision::ReadPost;
+        }
+
+        Decision::Scroll
+    }
+}
+
+// TODO: Proper probability calculations for these functions
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultPolicy;
+
+impl TransitionPolicy for DefaultPolicy {
+    fn should_go_offline(&self, _agent: &Individual, _rng: &mut dyn RngCore) -> Choice {
+        // Should get higher probability the longer we've been scrolling for
+        // if random::<f32>() > 0.9 {
+        //     return true;
+        // }
+        false.into()
+    }
+
+    fn should_select_post(&self, _agent: &Individual, rng: &mut dyn RngCore) -> Choice {
+        // Higher interest alignment in the post should increase probability of
+        // selecting that post
+        Choice::roll_above(0.5, rng)
+    }
+
+    fn should_read_post(&self, _agent: &Individual, rng: &mut dyn RngCore) -> Choice {
+        Choice::roll_above(0.5, rng)
+    }
+
+    fn should_read_comments(&self, _agent: &Individual, rng: &mut dyn RngCore) -> Choice {
+        Choice::roll_above(0.5, rng)
+    }
+
+    fn should_write_comment(&self, _agent: &Individual, rng: &mut dyn RngCore) -> Choice {
+        Choice::roll_above(0.5, rng)
+    }
+
+    fn should_scroll(&self, _agent: &Individual, rng: &mut dyn RngCore) -> Choice {
+        Choice::roll_above(0.5, rng)
+    }
+}
+
+// Hooked: never chooses to log off, so sessions only end when the time of
+// day or a reminder ends them, and reads and scrolls more than the default
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AddictivePolicy;
+
+impl TransitionPolicy for AddictivePolicy {
+    fn should_go_offline(&self, _agent: &Individual, _rng: &mut dyn RngCore) -> Choice {
+        false.into()
+    }
+
+    fn should_select_post(&self, _agent: &Individual, rng: &mut dyn RngCore) -> Choice {
+        Choice::roll_above(0.2, rng)
+    }
+
+    fn should_read_post(&self, _agent: &Individual, rng: &mut dyn RngCore) -> Choice {
+        Choice::roll_above(0.2, rng)
+    }
+
+    fn should_read_comments(&self, _agent: &Individual, rng: &mut dyn RngCore) -> Choice {
+        Choice::roll_above(0.3, rng)
+    }
+
+    fn should_write_comment(&self, _agent: &Individual, rng: &mut dyn RngCore) -> Choice {
+        Choice::roll_above(0.5, rng)
+    }
+
+    fn should_scroll(&self, _agent: &Individual, rng: &mut dyn RngCore) -> Choice {
+        Choice::roll_above(0.1, rng)
+    }
+}
+
+// Dips in and out: more likely to log off the longer the session has run,
+// and passes over more of what it is shown
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CasualPolicy;
+
+impl CasualPolicy {
+    // Ticks into a session by when the agent is certain to log off
+    pub const SESSION_TICKS: i32 = 50;
+}
+
+impl TransitionPolicy for CasualPolicy {
+    fn should_go_offline(&self, agent: &Individual, rng: &mut dyn RngCore) -> Choice {
+        let progress = agent.session_length_ticks as f32 / Self::SESSION_TICKS as f32;
+        Choice::roll_above(1.0 - (0.1 + progress).min(1.0), rng)
+    }
+
+    fn should_select_post(&self, _agent: &Individual, rng: &mut dyn RngCore) -> Choice {
+        Choice::roll_above(0.7, rng)
+    }
+
+    fn should_read_post(&self, _agent: &Individual, rng: &mut dyn RngCore) -> Choice {
+        Choice::roll_above(0.6, rng)
+    }
+
+    fn should_read_comments(&self, _agent: &Individual, rng: &mut dyn RngCore) -> Choice {
+        Choice::roll_above(0.8, rng)
+    }
+
+    fn should_write_comment(&self, _agent: &Individual, rng: &mut dyn RngCore) -> Choice {
+        Choice::roll_above(0.8, rng)
+    }
+
+    fn should_scroll(&self, _agent: &Individual, rng: &mut dyn RngCore) -> Choice {
+        Choice::roll_above(0.5, rng)
+    }
+}
+
+// The built-in policies, for choosing between them in a config. Custom
+// policies are given to agents directly with `Individual::with_policy`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PolicyKind {
+    #[default]
+    Default,
+    Addictive,
+    Casual,
+}
+
+impl PolicyKind {
+    pub const ALL: [PolicyKind; 3] = [
+        PolicyKind::Default,
+        PolicyKind::Addictive,
+        PolicyKind::Casual,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PolicyKind::Default => "default",
+            PolicyKind::Addictive => "addictive",
+            PolicyKind::Casual => "casual",
+        }
+    }
+
+    pub fn build(&self) -> Arc<dyn TransitionPolicy> {
+        match self {
+            PolicyKind::Default => Arc::new(DefaultPolicy),
+            PolicyKind::Addictive => Arc::new(AddictivePolicy),
+            PolicyKind::Casual => Arc::new(CasualPolicy),
+        }
+    }
+}
+
+// One choice an agent made, for explaining its behaviour after the fact
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecisionRecord {
+    // Always borrowed while running, and only owned once loaded from a save
+    pub state: Cow<'static, str>,
+    pub question: Cow<'static, str>,
+    pub outcome: Cow<'static, str>,
+    pub roll: Option<f32>,
+    pub threshold: Option<f32>,
+}
+
+// The most recent decisions an agent made, oldest first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DecisionLog {
+    pub records: VecDeque<DecisionRecord>,
+    pub capacity: usize,
+}
+
+impl DecisionLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            records: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, record: DecisionRecord) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.records.len() == self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+    }
+}
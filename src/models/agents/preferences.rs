@@ -0,0 +1,45 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::models::{InterestProfile, SimulationConfig};
+
+// The feed settings a platform offers its users, which the engine respects
+// when ranking. Most agents never touch them
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FeedPreferences {
+    // Whether the agent uses its settings at all
+    pub enabled: bool,
+
+    // Tags the agent has asked to see fewer posts about
+    pub fewer_tags: BTreeSet<String>,
+
+    // Whether posts by creators the agent has read before are ranked higher
+    pub prioritise_followed: bool,
+}
+
+impl FeedPreferences {
+    // Settings for a new agent, which uses them with the configured probability
+    pub fn new<R: Rng + ?Sized>(config: &SimulationConfig, rng: &mut R) -> Self {
+        let enabled = rng.gen::<f32>() < config.feed_control_usage;
+        Self {
+            enabled,
+            fewer_tags: BTreeSet::new(),
+            prioritise_followed: enabled && rng.gen::<bool>(),
+        }
+    }
+
+    // Asks for fewer posts on any tag that has come to dominate the agent's
+    // interests, as a user tired of seeing the same thing would
+    pub fn update(&mut self, profile: &InterestProfile, threshold: f32) {
+        if !self.enabled {
+            return;
+        }
+
+        for (tag, weight) in profile.normalised_weights() {
+            if weight > threshold && !self.fewer_tags.contains(tag) {
+                self.fewer_tags.insert(tag.clone());
+            }
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+// How much more likely an agent is to look at the item at `position` (0 being
+// the top of the feed) than at one it would weigh purely on relevance. An
+// exponent of 0 gives every position the same weight
+pub fn position_weight(position: usize, exponent: f32) -> f32 {
+    ((position + 1) as f32).powf(-exponent)
+}
+
+// Which positions in its feed an agent picked posts from, and how much of
+// that was down to position rather than relevance
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SelectionStats {
+    pub selections: u32,
+    // Selections per feed position
+    pub by_position: Vec<u32>,
+    // Expected number of selections that only happened because of where the
+    // post sat in the feed
+    pub position_driven: f32,
+}
+
+impl SelectionStats {
+    // `biased` and `relevance` are the chances the selected post had with and
+    // without position bias
+    pub fn record(&mut self, position: usize, biased: f32, relevance: f32) {
+        self.selections += 1;
+
+        if self.by_position.len() <= position {
+            self.by_position.resize(position + 1, 0);
+        }
+        self.by_position[position] += 1;
+
+        if biased > 0.0 {
+            self.position_driven += (1.0 - relevance / biased).max(0.0);
+        }
+    }
+
+    pub fn relevance_driven(&self) -> f32 {
+        self.selections as f32 - self.position_driven
+    }
+
+    // Fraction of selections attributed to position
+    pub fn position_share(&self) -> Option<f32> {
+        (self.selections > 0).then(|| self.position_driven / self.selections as f32)
+    }
+}
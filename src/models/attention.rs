@@ -0,0 +1,174 @@
+use std::collections::BTreeMap;
+
+use super::{CascadeAction, Post, PostId, Simulation};
+
+// Reads a post got on each tick since it was published, starting with the
+// tick it was published on
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttentionCurve {
+    pub post_id: PostId,
+    pub published_step: u64,
+    pub reads: Vec<usize>,
+}
+
+impl AttentionCurve {
+    // The curve up to the post's age after `steps_run` steps, with a zero for
+    // every tick it went unread
+    pub fn of(post: &Post, steps_run: u64) -> Self {
+        let mut reads = vec![0; steps_run.saturating_sub(post.published_step) as usize];
+
+        for event in &post.cascade {
+            if event.action != CascadeAction::Read {
+                continue;
+            }
+            let tick = event.step.saturating_sub(post.published_step) as usize;
+            if tick >= reads.len() {
+                reads.resize(tick + 1, 0);
+            }
+            reads[tick] += 1;
+        }
+
+        Self {
+            post_id: post.id,
+            published_step: post.published_step,
+            reads,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.reads.iter().sum()
+    }
+
+    // Ticks after publication by which half of the post's reads so far had
+    // happened, or None if it hasn't been read. A post that's still being
+    // read has its half-life stretch as reads come in
+    pub fn half_life(&self) -> Option<u64> {
+        let total = self.total();
+        let mut cumulative = 0;
+
+        for (tick, reads) in self.reads.iter().enumerate() {
+            cumulative += reads;
+            if total > 0 && cumulative * 2 >= total {
+                return Some(tick as u64);
+            }
+        }
+        None
+    }
+
+    // Share of the post's reads on each tick, so posts that reached very
+    // different audiences can be drawn on the same axes
+    pub fn shares(&self) -> Vec<f32> {
+        let total = self.total().max(1) as f32;
+        self.reads
+            .iter()
+            .map(|&reads| reads as f32 / total)
+            .collect()
+    }
+
+    // The average shape of a set of posts' curves, each weighted equally and
+    // counted only for the ticks it has lived
+    pub fn mean_shares<'a>(curves: impl IntoIterator<Item = &'a AttentionCurve>) -> Vec<f32> {
+        let mut sums: Vec<f32> = Vec::new();
+        let mut counts: Vec<usize> = Vec::new();
+
+        for curve in curves {
+            if curve.total() == 0 {
+                continue;
+            }
+            for (tick, share) in curve.shares().into_iter().enumerate() {
+                if tick >= sums.len() {
+                    sums.push(0.0);
+                    counts.push(0);
+                }
+                sums[tick] += share;
+                counts[tick] += 1;
+            }
+        }
+
+        sums.iter()
+            .zip(&counts)
+            .map(|(sum, &count)| sum / count as f32)
+            .collect()
+    }
+}
+
+// Summary of a group of posts' half-lives, in ticks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HalfLifeStats {
+    pub posts: usize,
+    pub mean: f32,
+    pub median: f32,
+}
+
+impl HalfLifeStats {
+    pub fn of(half_lives: &[u64]) -> Option<Self> {
+        if half_lives.is_empty() {
+            return None;
+        }
+
+        let mut sorted = half_lives.to_vec();
+        sorted.sort_unstable();
+        let middle = sorted.len() / 2;
+        let median = if sorted.len() % 2 == 0 {
+            (sorted[middle - 1] + sorted[middle]) as f32 / 2.0
+        } else {
+            sorted[middle] as f32
+        };
+
+        Some(Self {
+            posts: sorted.len(),
+            mean: sorted.iter().sum::<u64>() as f32 / sorted.len() as f32,
+            median,
+        })
+    }
+}
+
+// Half-lives of every post read so far, grouped by the kind of content, the
+// type of agent that created it and each tag it carries. Posts whose creator
+// isn't an agent, such as injected posts by made-up IDs, only count towards
+// content kind and tag
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HalfLives {
+    pub by_kind: BTreeMap<&'static str, HalfLifeStats>,
+    pub by_creator_type: BTreeMap<&'static str, HalfLifeStats>,
+    pub by_tag: BTreeMap<String, HalfLifeStats>,
+}
+
+impl HalfLives {
+    pub fn of(simulation: &Simulation) -> Self {
+        let mut by_kind: BTreeMap<&'static str, Vec<u64>> = BTreeMap::new();
+        let mut by_creator_type: BTreeMap<&'static str, Vec<u64>> = BTreeMap::new();
+        let mut by_tag: BTreeMap<String, Vec<u64>> = BTreeMap::new();
+
+        for post in &simulation.engine.content_pool {
+            let Some(half_life) = AttentionCurve::of(post, simulation.step_count).half_life()
+            else {
+                continue;
+            };
+
+            by_kind.entry(post.kind.name()).or_default().push(half_life);
+            if let Some(creator) = simulation.agent_view(post.creator_id) {
+                by_creator_type
+                    .entry(creator.agent_type.name())
+                    .or_default()
+                    .push(half_life);
+            }
+            for tag in post.interest_profile.interests.keys() {
+                by_tag.entry(tag.clone()).or_default().push(half_life);
+            }
+        }
+
+        fn summarise<K: Ord>(groups: BTreeMap<K, Vec<u64>>) -> BTreeMap<K, HalfLifeStats> {
+            groups
+                .into_iter()
+                .filter_map(|(key, half_lives)| Some((key, HalfLifeStats::of(&half_lives)?)))
+                .collect()
+        }
+
+        Self {
+            by_kind: summarise(by_kind),
+            by_creator_type: summarise(by_creator_type),
+            by_tag: summarise(by_tag),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Condvar, Mutex};
+
+use super::{Bridges, Communities, WealthSample};
+
+// Work handed off to another thread. Clones share the one result, so a copy
+// of the simulation waits on the same work rather than losing it
+#[derive(Debug, Clone)]
+pub struct Pending<T> {
+    // None until the work finishes, then None inside if it panicked
+    slot: Arc<(Mutex<Option<Option<T>>>, Condvar)>,
+}
+
+impl<T: Clone + Send + 'static> Pending<T> {
+    // Starts the work on a thread of its own. Without threads, as on the web,
+    // it is done there and then
+    pub fn spawn(work: impl FnOnce() -> T + Send + 'static) -> Self {
+        let slot = Arc::new((Mutex::new(None), Condvar::new()));
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let posted = Arc::clone(&slot);
+            std::thread::spawn(move || {
+                let result = panic::catch_unwind(AssertUnwindSafe(work)).ok();
+                let (value, ready) = &*posted;
+                *value.lock().unwrap() = Some(result);
+                ready.notify_all();
+            });
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            *slot.0.lock().unwrap() = Some(panic::catch_unwind(AssertUnwindSafe(work)).ok());
+        }
+
+        Self { slot }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.slot.0.lock().unwrap().is_some()
+    }
+
+    // Blocks until the work is done. Panics if the work did
+    pub fn wait(&self) -> T {
+        let (value, ready) = &*self.slot;
+        let value = ready
+            .wait_while(value.lock().unwrap(), |value| value.is_none())
+            .unwrap();
+        value
+            .clone()
+            .flatten()
+            .expect("background analytics panicked")
+    }
+}
+
+// Analytics being worked out off the step. Each result is applied at the
+// next step the same analytics are due, or when flushed, never as soon as
+// the thread finishes, so a run comes out the same however long that takes
+#[derive(Debug, Clone, Default)]
+pub struct BackgroundAnalytics {
+    pub communities: Option<Pending<(Communities, Bridges)>>,
+    pub wealth: Option<Pending<WealthSample>>,
+}
+
+impl BackgroundAnalytics {
+    pub fn is_idle(&self) -> bool {
+        self.communities.is_none() && self.wealth.is_none()
+    }
+
+    // Whether everything started has finished, so flushing won't block
+    pub fn is_ready(&self) -> bool {
+        self.communities.as_ref().map_or(true, Pending::is_ready)
+            && self.wealth.as_ref().map_or(true, Pending::is_ready)
+    }
+}
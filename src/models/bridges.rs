@@ -0,0 +1,143 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, VecDeque};
+
+use super::{community::FollowSnapshot, Agent, AgentId, Communities};
+
+// An agent whose follows reach into more than one community and that sits on
+// many of the shortest paths between other agents
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Bridge {
+    pub agent_id: AgentId,
+    // Share of shortest paths between other pairs of agents that pass through
+    // this one, from 0 to 1
+    pub betweenness: f32,
+    // How many communities the agent or its neighbours belong to
+    pub communities_reached: usize,
+}
+
+// The bridges found at one step, most central first
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Bridges {
+    pub step: u64,
+    pub bridges: Vec<Bridge>,
+}
+
+impl Bridges {
+    // Agents with at least `min_betweenness` that connect communities. Ties
+    // in the follow graph are unweighted here, since a weak follow still
+    // carries posts across
+    pub fn find(
+        step: u64,
+        agents: &[Box<dyn Agent>],
+        communities: &Communities,
+        min_betweenness: f32,
+    ) -> Self {
+        Self::find_in(
+            step,
+            &FollowSnapshot::of(agents, &[]),
+            communities,
+            min_betweenness,
+        )
+    }
+
+    pub fn find_in(
+        step: u64,
+        snapshot: &FollowSnapshot,
+        communities: &Communities,
+        min_betweenness: f32,
+    ) -> Self {
+        let neighbours = &snapshot.neighbours;
+        let betweenness = betweenness(neighbours);
+
+        let mut bridges: Vec<Bridge> = snapshot
+            .ids
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &agent_id)| {
+                let reached: BTreeSet<usize> = std::iter::once(i)
+                    .chain(neighbours[i].iter().map(|(neighbour, _)| *neighbour))
+                    .filter_map(|j| communities.label(snapshot.ids[j]))
+                    .collect();
+                (reached.len() > 1 && betweenness[i] >= min_betweenness).then(|| Bridge {
+                    agent_id,
+                    betweenness: betweenness[i],
+                    communities_reached: reached.len(),
+                })
+            })
+            .collect();
+        bridges.sort_by(|a, b| {
+            b.betweenness
+                .partial_cmp(&a.betweenness)
+                .unwrap()
+                .then(a.agent_id.cmp(&b.agent_id))
+        });
+
+        Self { step, bridges }
+    }
+
+    pub fn contains(&self, agent_id: AgentId) -> bool {
+        self.bridges
+            .iter()
+            .any(|bridge| bridge.agent_id == agent_id)
+    }
+
+    // Total betweenness of all bridges, for how much of the graph's traffic
+    // depends on them
+    pub fn influence(&self) -> f32 {
+        self.bridges.iter().map(|bridge| bridge.betweenness).sum()
+    }
+}
+
+// Normalised betweenness centrality of every node in an undirected graph, by
+// Brandes' algorithm
+fn betweenness(neighbours: &[Vec<(usize, f32)>]) -> Vec<f32> {
+    let n = neighbours.len();
+    let mut centrality = vec![0.0; n];
+
+    for source in 0..n {
+        let mut order = Vec::with_capacity(n);
+        let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut paths = vec![0.0f32; n];
+        let mut distance: Vec<Option<usize>> = vec![None; n];
+        paths[source] = 1.0;
+        distance[source] = Some(0);
+
+        let mut queue = VecDeque::from([source]);
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            let next = distance[node].unwrap() + 1;
+            for &(neighbour, _) in &neighbours[node] {
+                if distance[neighbour].is_none() {
+                    distance[neighbour] = Some(next);
+                    queue.push_back(neighbour);
+                }
+                // Follows in both directions appear twice, but only count
+                // as one path
+                if distance[neighbour] == Some(next) && !predecessors[neighbour].contains(&node) {
+                    paths[neighbour] += paths[node];
+                    predecessors[neighbour].push(node);
+                }
+            }
+        }
+
+        let mut dependency = vec![0.0f32; n];
+        for &node in order.iter().rev() {
+            for &predecessor in &predecessors[node] {
+                dependency[predecessor] +=
+                    paths[predecessor] / paths[node] * (1.0 + dependency[node]);
+            }
+            if node != source {
+                centrality[node] += dependency[node];
+            }
+        }
+    }
+
+    // Each pair was counted from both ends, out of (n - 1)(n - 2) / 2 pairs
+    // not involving the node itself
+    let pairs = if n > 2 {
+        ((n - 1) * (n - 2)) as f32
+    } else {
+        1.0
+    };
+    centrality.iter().map(|total| total / pairs).collect()
+}
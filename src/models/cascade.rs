@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+
+use super::{AgentId, CommentId, Post, PostId};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CascadeAction {
+    // The post was shown to the agent in its recommendations
+    Exposed,
+    Read,
+    Commented(CommentId),
+    Liked,
+    // Passed on as the reshare with this ID
+    Reshared(PostId),
+}
+
+// Something an agent did with a post, and the simulation step it happened on
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CascadeEvent {
+    pub step: u64,
+    pub agent_id: AgentId,
+    pub action: CascadeAction,
+}
+
+// Everything one agent did with a post, in order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CascadeBranch {
+    pub agent_id: AgentId,
+    pub events: Vec<CascadeEvent>,
+}
+
+// How a post spread from its creator, grouped by the agents it reached in the
+// order they were first reached
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cascade {
+    pub post_id: PostId,
+    pub creator_id: AgentId,
+    pub branches: Vec<CascadeBranch>,
+}
+
+impl Cascade {
+    pub fn of(post: &Post) -> Self {
+        let mut branches: Vec<CascadeBranch> = Vec::new();
+
+        for event in &post.cascade {
+            match branches
+                .iter_mut()
+                .find(|branch| branch.agent_id == event.agent_id)
+            {
+                Some(branch) => branch.events.push(*event),
+                None => branches.push(CascadeBranch {
+                    agent_id: event.agent_id,
+                    events: vec![*event],
+                }),
+            }
+        }
+
+        Self {
+            post_id: post.id,
+            creator_id: post.creator_id,
+            branches,
+        }
+    }
+
+    pub fn count(&self, matches: impl Fn(&CascadeAction) -> bool) -> usize {
+        self.branches
+            .iter()
+            .filter(|branch| branch.events.iter().any(|event| matches(&event.action)))
+            .count()
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+impl CascadeBranch {
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
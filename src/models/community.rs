@@ -0,0 +1,228 @@
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::{Agent, AgentId, CascadeAction, Post};
+
+// Label propagation rarely needs more than a handful of passes to settle
+const MAX_PASSES: usize = 20;
+
+// What one community looks like at the step it was detected
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommunityStats {
+    pub label: usize,
+    pub size: usize,
+    // Mean over members of their mean agreement across their topics
+    pub mean_agreement: f32,
+    // Exposures of members to posts by members, and to posts by anyone else
+    pub internal_exposures: usize,
+    pub external_exposures: usize,
+}
+
+impl CommunityStats {
+    // Share of members' exposures that came from inside the community, or
+    // None before anyone has been exposed to anything
+    pub fn internal_share(&self) -> Option<f32> {
+        let total = self.internal_exposures + self.external_exposures;
+        (total > 0).then(|| self.internal_exposures as f32 / total as f32)
+    }
+}
+
+// The result of one community detection run, largest community first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Communities {
+    pub step: u64,
+    pub labels: BTreeMap<AgentId, usize>,
+    pub stats: Vec<CommunityStats>,
+}
+
+// What community detection reads of the agents and the pool, owned so it can
+// be taken on one thread and worked through on another. Agents are kept in the
+// order they were given
+#[derive(Debug, Clone, PartialEq)]
+pub struct FollowSnapshot {
+    pub ids: Vec<AgentId>,
+    pub neighbours: Vec<Vec<(usize, f32)>>,
+    // Each agent's mean agreement across its topics, None without any
+    pub agreement: Vec<Option<f32>>,
+    // The creator and the reader of every exposure in the pool
+    pub exposures: Vec<(AgentId, AgentId)>,
+}
+
+impl FollowSnapshot {
+    pub fn of(agents: &[Box<dyn Agent>], posts: &[Post]) -> Self {
+        Self {
+            ids: agents.iter().map(|agent| *agent.id()).collect(),
+            neighbours: follow_neighbours(agents),
+            agreement: agents
+                .iter()
+                .map(|agent| {
+                    let interests = &agent.interest_profile().interests;
+                    (!interests.is_empty()).then(|| {
+                        interests.values().map(|topic| topic.agreement).sum::<f32>()
+                            / interests.len() as f32
+                    })
+                })
+                .collect(),
+            exposures: posts
+                .iter()
+                .flat_map(|post| {
+                    post.cascade
+                        .iter()
+                        .filter(|event| event.action == CascadeAction::Exposed)
+                        .map(|event| (post.creator_id, event.agent_id))
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Communities {
+    // Splits the follow graph into communities by label propagation and
+    // summarises each one. Labels are numbered from 0 by size
+    pub fn detect<R: Rng + ?Sized>(
+        step: u64,
+        agents: &[Box<dyn Agent>],
+        posts: &[Post],
+        rng: &mut R,
+    ) -> Self {
+        Self::detect_in(step, &FollowSnapshot::of(agents, posts), rng)
+    }
+
+    pub fn detect_in<R: Rng + ?Sized>(step: u64, snapshot: &FollowSnapshot, rng: &mut R) -> Self {
+        let labels: BTreeMap<AgentId, usize> = snapshot
+            .ids
+            .iter()
+            .copied()
+            .zip(label_propagation(&snapshot.neighbours, rng))
+            .collect();
+
+        let mut stats: BTreeMap<usize, CommunityStats> = BTreeMap::new();
+        for (id, agreement) in snapshot.ids.iter().zip(&snapshot.agreement) {
+            let label = labels[id];
+            let entry = stats.entry(label).or_insert(CommunityStats {
+                label,
+                size: 0,
+                mean_agreement: 0.0,
+                internal_exposures: 0,
+                external_exposures: 0,
+            });
+            entry.size += 1;
+            if let Some(agreement) = agreement {
+                entry.mean_agreement += agreement;
+            }
+        }
+        for community in stats.values_mut() {
+            community.mean_agreement /= community.size as f32;
+        }
+
+        for (creator, reader) in &snapshot.exposures {
+            let Some(reader) = labels.get(reader) else {
+                continue;
+            };
+            let community = stats.get_mut(reader).unwrap();
+            if labels.get(creator) == Some(reader) {
+                community.internal_exposures += 1;
+            } else {
+                community.external_exposures += 1;
+            }
+        }
+
+        // Renumber so the largest community is 0, ties going to the lower
+        // original label so the numbering is stable
+        let mut stats: Vec<CommunityStats> = stats.into_values().collect();
+        stats.sort_by(|a, b| b.size.cmp(&a.size).then(a.label.cmp(&b.label)));
+        let renumbered: BTreeMap<usize, usize> = stats
+            .iter()
+            .enumerate()
+            .map(|(index, community)| (community.label, index))
+            .collect();
+        for community in &mut stats {
+            community.label = renumbered[&community.label];
+        }
+
+        Self {
+            step,
+            labels: labels
+                .into_iter()
+                .map(|(id, label)| (id, renumbered[&label]))
+                .collect(),
+            stats,
+        }
+    }
+
+    pub fn label(&self, id: AgentId) -> Option<usize> {
+        self.labels.get(&id).copied()
+    }
+}
+
+// The follow graph as undirected adjacency lists over agents' positions in
+// `agents`, weighted by follow weight. Follows count in both directions, since
+// either side can carry influence
+pub fn follow_neighbours(agents: &[Box<dyn Agent>]) -> Vec<Vec<(usize, f32)>> {
+    let index: BTreeMap<AgentId, usize> = agents
+        .iter()
+        .enumerate()
+        .map(|(i, agent)| (*agent.id(), i))
+        .collect();
+
+    let mut neighbours: Vec<Vec<(usize, f32)>> = vec![Vec::new(); agents.len()];
+    for (a, agent) in agents.iter().enumerate() {
+        let Some(follows) = agent.preferred_creators() else {
+            continue;
+        };
+        for (followed, weight) in follows {
+            if let Some(&b) = index.get(followed) {
+                if a != b {
+                    neighbours[a].push((b, *weight));
+                    neighbours[b].push((a, *weight));
+                }
+            }
+        }
+    }
+    neighbours
+}
+
+// Every agent starts in its own community and repeatedly joins whichever
+// community its follows and followers weigh most towards, until nobody moves
+fn label_propagation<R: Rng + ?Sized>(neighbours: &[Vec<(usize, f32)>], rng: &mut R) -> Vec<usize> {
+    let mut labels: Vec<usize> = (0..neighbours.len()).collect();
+    let mut order: Vec<usize> = (0..neighbours.len()).collect();
+
+    for _ in 0..MAX_PASSES {
+        order.shuffle(rng);
+        let mut moved = false;
+
+        for &node in &order {
+            let mut weights: BTreeMap<usize, f32> = BTreeMap::new();
+            for &(neighbour, weight) in &neighbours[node] {
+                *weights.entry(labels[neighbour]).or_default() += weight;
+            }
+
+            // The heaviest label, the lowest winning ties
+            let best =
+                weights
+                    .into_iter()
+                    .fold(
+                        None,
+                        |best: Option<(usize, f32)>, (label, weight)| match best {
+                            Some((_, best_weight)) if best_weight >= weight => best,
+                            _ => Some((label, weight)),
+                        },
+                    );
+
+            if let Some((label, _)) = best {
+                if label != labels[node] {
+                    labels[node] = label;
+                    moved = true;
+                }
+            }
+        }
+
+        if !moved {
+            break;
+        }
+    }
+
+    labels
+}
@@ -1,50 +1,171 @@
-use rand::{random, RngCore};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-use super::{InterestProfile, SimulationConfig};
+use super::generator::standard_normal;
+use super::{
+    AgentId, CascadeEvent, CommentId, InterestProfile, PostId, RngStreams, SimulationConfig,
+};
 
-#[derive(Debug, Clone)]
+// Where a post came from, for comparing how attention to each kind decays
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ContentKind {
+    // Created by an agent in the course of the simulation
+    #[default]
+    Organic,
+    // Published by an intervention
+    Injected,
+    // Published by an intervention and pinned into feeds for a while
+    Breaking,
+}
+
+impl ContentKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ContentKind::Organic => "organic",
+            ContentKind::Injected => "injected",
+            ContentKind::Breaking => "breaking",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Post {
-    pub id: usize,
-    pub creator_id: usize,
+    pub id: PostId,
+    pub creator_id: AgentId,
     pub timestamp: i64,
+    // The step the post was published on, stamped along with the timestamp
+    pub published_step: u64,
+    pub kind: ContentKind,
+    // The language or market segment of the post's creator, if the
+    // population is segmented
+    pub segment: Option<String>,
+    // How much the post draws readers regardless of its relevance to them,
+    // in standard deviations from the average post. Its quality is only
+    // weakly related to this
+    pub appeal: f32,
     pub interest_profile: InterestProfile,
     pub length: i32,
 
     // Reader agent IDs, for deriving engagement score
-    pub readers: Vec<usize>,
+    pub readers: Vec<AgentId>,
     // Comment IDs, for deriving engagement score
     pub comments: Vec<Comment>,
 
     pub engagement_score: f32,
+
+    // Who the post reached and what they did with it, in order
+    pub cascade: Vec<CascadeEvent>,
+
+    // Agents that liked the post, in the order they did
+    pub likes: Vec<AgentId>,
+
+    // A reshare is a copy of the post it shares, put back in the pool under
+    // its own ID and still credited to the original creator. `shared_by` is
+    // everyone who passed it on, from the first reshare to this one, and
+    // `reshare_of` and `original` the post it shares and the one the chain
+    // started from. Both are None for posts that aren't reshares
+    pub shared_by: Vec<AgentId>,
+    pub reshare_of: Option<PostId>,
+    pub original: Option<PostId>,
+    // Reshares of this post, in the order they were published, so the
+    // reshare tree can be walked down from any post in it
+    pub reshares: Vec<PostId>,
 }
 
 impl Post {
     pub fn new(
-        creator_id: usize,
+        id: PostId,
+        creator_id: AgentId,
         interest_profile: InterestProfile,
         config: &SimulationConfig,
     ) -> Self {
+        let mut rng = RngStreams::keyed(config.seed, "post", id.0 as u64);
         Self {
-            id: rand::thread_rng().next_u32() as usize,
+            id,
             creator_id,
-            timestamp: chrono::Utc::now().timestamp(),
+            // Stamped with the simulation's clock when published
+            timestamp: 0,
+            published_step: 0,
+            kind: ContentKind::Organic,
+            segment: None,
+            appeal: draw_appeal(id, config),
             interest_profile,
-            length: (random::<f32>() * config.max_post_length as f32) as i32,
+            length: (rng.gen::<f32>() * config.max_post_length as f32) as i32,
             readers: Vec::new(),
             comments: Vec::new(),
             engagement_score: 0.0,
+            cascade: Vec::new(),
+            likes: Vec::new(),
+            shared_by: Vec::new(),
+            reshare_of: None,
+            original: None,
+            reshares: Vec::new(),
         }
     }
 
     pub fn increase_engagement(&mut self) {
         self.engagement_score += 1.0;
     }
+
+    // A copy of the post for `sharer` to pass on, published under `id`
+    pub fn reshare(&self, id: PostId, sharer: AgentId) -> Post {
+        let mut shared_by = self.shared_by.clone();
+        shared_by.push(sharer);
+        Post {
+            id,
+            creator_id: self.creator_id,
+            timestamp: 0,
+            published_step: 0,
+            kind: self.kind,
+            segment: self.segment.clone(),
+            appeal: self.appeal,
+            interest_profile: self.interest_profile.clone(),
+            length: self.length,
+            readers: Vec::new(),
+            comments: Vec::new(),
+            engagement_score: 0.0,
+            cascade: Vec::new(),
+            likes: Vec::new(),
+            shared_by,
+            reshare_of: Some(self.id),
+            original: Some(self.original.unwrap_or(self.id)),
+            reshares: Vec::new(),
+        }
+    }
+
+    // Who put this copy of the post in front of people: the last to reshare
+    // it, or None for the original
+    pub fn sharer(&self) -> Option<AgentId> {
+        self.shared_by.last().copied()
+    }
+
+    // Whether the post comes from someone in `follows`, either its creator
+    // or whoever reshared it
+    pub fn followed_in(&self, follows: &BTreeMap<AgentId, f32>) -> bool {
+        follows.contains_key(&self.creator_id)
+            || self
+                .sharer()
+                .is_some_and(|sharer| follows.contains_key(&sharer))
+    }
+}
+
+// Drawn from a stream of the post's own, so it doesn't disturb how anything
+// else about the post is generated
+pub fn draw_appeal(id: PostId, config: &SimulationConfig) -> f32 {
+    standard_normal(&mut RngStreams::keyed(config.seed, "appeal", id.0 as u64))
 }
 
-#[derive(Debug, Clone)]
+// How much more likely a post is to be picked from a feed given its appeal,
+// against an average post
+pub fn appeal_weight(appeal: f32, effect: f32) -> f32 {
+    (appeal * effect).exp()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Comment {
-    pub id: usize,
-    pub commentor_id: usize,
+    pub id: CommentId,
+    pub commentor_id: AgentId,
     pub timestamp: i64,
     pub interest_profile: InterestProfile,
     pub length: i32,
@@ -53,17 +174,24 @@ pub struct Comment {
 
 impl Comment {
     pub fn new(
-        commentor_id: usize,
+        id: CommentId,
+        commentor_id: AgentId,
         interest_profile: InterestProfile,
         config: &SimulationConfig,
     ) -> Self {
+        let mut rng = RngStreams::keyed(config.seed, "comment", id.0 as u64);
         Self {
-            id: rand::thread_rng().next_u32() as usize,
+            id,
             commentor_id,
-            timestamp: chrono::Utc::now().timestamp(),
+            // Stamped with the simulation's clock when published
+            timestamp: 0,
             interest_profile,
-            length: (random::<f32>() * config.max_comment_length as f32) as i32,
+            length: (rng.gen::<f32>() * config.max_comment_length as f32) as i32,
             engagement_score: 0.0,
         }
     }
+
+    pub fn increase_engagement(&mut self) {
+        self.engagement_score += 1.0;
+    }
 }
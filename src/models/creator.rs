@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{AgentId, CascadeAction, Earnings, Simulation};
+
+// How many agents followed a creator at a given simulation step
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FollowerSnapshot {
+    pub step: u64,
+    pub followers: usize,
+}
+
+// Periodic follower counts for one creator, taken alongside interest
+// snapshots. Like interest history, the first is always kept so growth can be
+// measured from spawn
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FollowerHistory {
+    pub snapshots: Vec<FollowerSnapshot>,
+}
+
+impl FollowerHistory {
+    pub fn record(&mut self, step: u64, followers: usize, max_snapshots: usize) {
+        self.snapshots.push(FollowerSnapshot { step, followers });
+
+        while self.snapshots.len() > max_snapshots.max(2) {
+            self.snapshots.remove(1);
+        }
+    }
+
+    // Followers gained since the first snapshot, negative if lost
+    pub fn growth(&self) -> i64 {
+        match (self.snapshots.first(), self.snapshots.last()) {
+            (Some(first), Some(last)) => last.followers as i64 - first.followers as i64,
+            _ => 0,
+        }
+    }
+}
+
+// A creator's performance as it would see it on a creator dashboard: who its
+// posts reached, how they engaged and what its audience is interested in
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreatorAnalytics {
+    pub creator_id: AgentId,
+    pub posts: usize,
+    // Distinct agents shown or reading any of the creator's posts
+    pub reach: usize,
+    pub exposures: usize,
+    pub reads: usize,
+    pub comments: usize,
+    pub likes: usize,
+    pub reshares: usize,
+    // Reads and comments per exposure, once any post has been shown
+    pub engagement_rate: Option<f32>,
+    pub followers: usize,
+    pub follower_history: FollowerHistory,
+    // Tips and subscriptions, for creators that can be paid
+    pub earnings: Option<Earnings>,
+    // Mean interest weights of the agents who read the creator's posts, as
+    // they are now, sorted by tag
+    pub audience_interests: Vec<(String, f32)>,
+}
+
+impl CreatorAnalytics {
+    pub fn of(simulation: &Simulation, creator_id: AgentId) -> Self {
+        let mut reached = BTreeSet::new();
+        let mut readers = BTreeSet::new();
+        let (mut posts, mut exposures, mut reads, mut comments) = (0, 0, 0, 0);
+        let (mut likes, mut reshares) = (0, 0);
+
+        let authored = simulation
+            .engine
+            .content_pool
+            .iter()
+            .filter(|post| post.creator_id == creator_id);
+        // Reshared copies are still the creator's, and count towards reach
+        // and engagement but not posts
+        for post in authored {
+            if post.original.is_none() {
+                posts += 1;
+            }
+            for event in &post.cascade {
+                reached.insert(event.agent_id);
+                match event.action {
+                    CascadeAction::Exposed => exposures += 1,
+                    CascadeAction::Read => {
+                        reads += 1;
+                        readers.insert(event.agent_id);
+                    }
+                    CascadeAction::Commented(_) => comments += 1,
+                    CascadeAction::Liked => likes += 1,
+                    CascadeAction::Reshared(_) => reshares += 1,
+                }
+            }
+        }
+
+        let mut sums: BTreeMap<String, f32> = BTreeMap::new();
+        let audience: Vec<_> = simulation
+            .agent_views()
+            .filter(|agent| readers.contains(&agent.id))
+            .collect();
+        for agent in &audience {
+            for (tag, weight) in agent.interest_profile.normalised_weights() {
+                *sums.entry(tag.clone()).or_default() += weight;
+            }
+        }
+
+        Self {
+            creator_id,
+            posts,
+            reach: reached.len(),
+            exposures,
+            reads,
+            comments,
+            likes,
+            reshares,
+            engagement_rate: (exposures > 0).then(|| (reads + comments) as f32 / exposures as f32),
+            followers: simulation.follower_count(creator_id),
+            follower_history: simulation
+                .follower_history
+                .get(&creator_id)
+                .cloned()
+                .unwrap_or_default(),
+            earnings: simulation
+                .agent_view(creator_id)
+                .and_then(|agent| agent.earnings.copied()),
+            audience_interests: sums
+                .into_iter()
+                .map(|(tag, sum)| (tag, sum / audience.len() as f32))
+                .collect(),
+        }
+    }
+}
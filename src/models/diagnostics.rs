@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+
+use crate::RecommendationEngine;
+
+use super::{Agent, AgentState, AgentType};
+
+// How long every individual may stay offline before it is reported
+const ALL_OFFLINE_GRACE_STEPS: u64 = 50;
+
+// A degenerate state the simulation can run in without failing, but which
+// makes its output meaningless
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HealthWarning {
+    EmptyContentPool,
+    AllAgentsOffline,
+    ZeroInterestVectors,
+    NonFiniteValues,
+}
+
+impl HealthWarning {
+    pub fn message(&self) -> &'static str {
+        match self {
+            HealthWarning::EmptyContentPool => {
+                "The content pool is empty, so there is nothing to recommend. Add bots or organisations, or inject a post."
+            }
+            HealthWarning::AllAgentsOffline => {
+                "Every individual has been offline for a while. Check the transition policy's go-offline and come-online chances."
+            }
+            HealthWarning::ZeroInterestVectors => {
+                "Every individual's interest vector is zero, so recommendations ignore interests. Check that tags are registered with the engine and vectors are built."
+            }
+            HealthWarning::NonFiniteValues => {
+                "Some interest weights, vectors or engagement scores are NaN or infinite, so recommendation scores are meaningless."
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Diagnostics {
+    pub warnings: Vec<HealthWarning>,
+    last_online_step: u64,
+}
+
+impl Diagnostics {
+    // Re-checks the simulation, logging any warning that wasn't already raised
+    pub fn update(&mut self, step: u64, agents: &[Box<dyn Agent>], engine: &RecommendationEngine) {
+        let individuals: Vec<&Box<dyn Agent>> = agents
+            .iter()
+            .filter(|agent| agent.get_type() == AgentType::Individual)
+            .collect();
+
+        let mut warnings = Vec::new();
+
+        if engine.content_pool.is_empty() && step > 0 {
+            warnings.push(HealthWarning::EmptyContentPool);
+        }
+
+        if individuals
+            .iter()
+            .any(|agent| !matches!(agent.state(), AgentState::Offline))
+        {
+            self.last_online_step = step;
+        }
+        if !individuals.is_empty() && step - self.last_online_step >= ALL_OFFLINE_GRACE_STEPS {
+            warnings.push(HealthWarning::AllAgentsOffline);
+        }
+
+        if !individuals.is_empty()
+            && individuals.iter().all(|agent| {
+                agent
+                    .interest_profile()
+                    .vector_representation
+                    .iter()
+                    .all(|value| *value == 0.0)
+            })
+        {
+            warnings.push(HealthWarning::ZeroInterestVectors);
+        }
+
+        let agents_finite = agents.iter().all(|agent| {
+            let profile = agent.interest_profile();
+            profile.vector_representation.iter().all(|v| v.is_finite())
+                && profile
+                    .interests
+                    .values()
+                    .all(|topic| topic.weighted_interest.is_finite())
+        });
+        let posts_finite = engine.content_pool.iter().all(|post| {
+            post.engagement_score.is_finite()
+                && post
+                    .interest_profile
+                    .vector_representation
+                    .iter()
+                    .all(|v| v.is_finite())
+        });
+        if !agents_finite || !posts_finite {
+            warnings.push(HealthWarning::NonFiniteValues);
+        }
+
+        for warning in &warnings {
+            if !self.warnings.contains(warning) {
+                log::warn!("Step {}: {}", step, warning.message());
+            }
+        }
+        self.warnings = warnings;
+    }
+}
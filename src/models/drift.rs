@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use super::{InterestProfile, InterestRow, Post, Topic};
+
+// How organisations come round to what their audience responds to, as
+// institutions are captured by theirs. Each time an organisation finishes a
+// post it measures the response to its latest few, from the interests and
+// agreement of those who commented, and moves part of the way towards it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DriftConfig {
+    // How far it moves each time, from 0 for not at all, as by default, to 1
+    // for taking on its audience's interests outright
+    pub responsiveness: f32,
+    // How many of its latest posts the response is measured over
+    pub window: usize,
+    // Samples kept in each organisation's trace, oldest dropped first
+    pub trace_length: usize,
+}
+
+impl Default for DriftConfig {
+    fn default() -> Self {
+        Self {
+            responsiveness: 0.0,
+            window: 5,
+            trace_length: 100,
+        }
+    }
+}
+
+impl DriftConfig {
+    pub fn enabled(&self) -> bool {
+        self.responsiveness > 0.0 && self.window > 0
+    }
+
+    // Moves `profile` towards the response to `posts`, returning the comments
+    // it was measured from and how much interest moved between tags, or None
+    // if nobody commented
+    pub fn respond<'a>(
+        &self,
+        profile: &mut InterestProfile,
+        posts: impl IntoIterator<Item = &'a Post>,
+    ) -> Option<(usize, f32)> {
+        // Each comment's interest in a tag, and that weighted by its agreement
+        let mut response: BTreeMap<&str, (f32, f32)> = BTreeMap::new();
+        let mut comments = 0;
+        for comment in posts.into_iter().flat_map(|post| &post.comments) {
+            comments += 1;
+            let commented = &comment.interest_profile;
+            for (tag, weight) in commented.normalised_weights() {
+                let entry = response.entry(tag.as_str()).or_default();
+                entry.0 += weight;
+                entry.1 += weight * commented.interests[tag].agreement;
+            }
+        }
+        let total: f32 = response.values().map(|&(weight, _)| weight).sum();
+        if comments == 0 || total <= 0.0 {
+            return None;
+        }
+
+        let before: BTreeMap<String, f32> = profile
+            .normalised_weights()
+            .map(|(tag, weight)| (tag.clone(), weight))
+            .collect();
+        let rate = self.responsiveness.clamp(0.0, 1.0);
+        // Both sides add up to one, so the weights still do afterwards
+        for (tag, weight) in &before {
+            if let Some(topic) = profile.interests.get_mut(tag) {
+                topic.weighted_interest = weight * (1.0 - rate);
+            }
+        }
+        for (tag, (weight, agreed)) in response {
+            let agreement = if weight > 0.0 { agreed / weight } else { 0.0 };
+            // A tag new to the profile is taken up with the audience's view of it
+            let topic = profile.interests.entry(tag.to_string()).or_insert(Topic {
+                weighted_interest: 0.0,
+                agreement,
+            });
+            topic.weighted_interest += rate * weight / total;
+            topic.agreement =
+                (topic.agreement + rate * (agreement - topic.agreement)).clamp(-1.0, 1.0);
+        }
+        profile.normalise_weights();
+
+        let tags: BTreeSet<&String> = before.keys().chain(profile.interests.keys()).collect();
+        let moved: f32 = tags
+            .into_iter()
+            .map(|tag| {
+                let was = before.get(tag).copied().unwrap_or(0.0);
+                (profile.weight(tag).unwrap_or(0.0) - was).abs()
+            })
+            .sum();
+        Some((comments, moved / 2.0))
+    }
+}
+
+// One time an organisation took stock of its audience
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DriftSample {
+    pub step: u64,
+    // Comments the response was measured from
+    pub comments: usize,
+    // Share of its interest that moved between tags, from 0 to 1
+    pub shift: f32,
+    // Its interests afterwards
+    pub profile: Vec<InterestRow>,
+}
+
+// How an organisation's topics and agreement have moved with its audience
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TopicDrift {
+    pub samples: VecDeque<DriftSample>,
+}
+
+impl TopicDrift {
+    pub fn push(&mut self, sample: DriftSample, capacity: usize) {
+        if capacity == 0 {
+            return;
+        }
+
+        if self.samples.len() == capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn latest(&self) -> Option<&DriftSample> {
+        self.samples.back()
+    }
+
+    // Interest moved between tags over the samples kept
+    pub fn total_shift(&self) -> f32 {
+        self.samples.iter().map(|sample| sample.shift).sum()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
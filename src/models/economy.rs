@@ -0,0 +1,168 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+// Wealth samples kept, oldest dropped first
+const MAX_SAMPLES: usize = 500;
+
+// How followers pay the creators they are most attached to, and how much
+// earning spurs creators to post
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EconomyConfig {
+    // Follow weight a follower needs before it tips or subscribes
+    pub affinity: f32,
+    // Chance a read by an affine follower comes with a tip, 0 for no tips
+    pub tip_chance: f32,
+    pub tip_amount: f32,
+    // What each affine follower pays a creator per step, 0 for no
+    // subscriptions
+    pub subscription_fee: f32,
+    // How much quicker a creator posts per unit it earns per step
+    pub incentive: f32,
+    // Steps between subscription payments, which are also when creators take
+    // stock of their income and wealth is sampled. 0 for neither
+    pub interval: u64,
+}
+
+impl Default for EconomyConfig {
+    fn default() -> Self {
+        Self {
+            affinity: 3.0,
+            tip_chance: 0.0,
+            tip_amount: 1.0,
+            subscription_fee: 0.0,
+            incentive: 1.0,
+            interval: 10,
+        }
+    }
+}
+
+impl EconomyConfig {
+    pub fn affine(&self, follow_weight: f32) -> bool {
+        follow_weight >= self.affinity
+    }
+
+    // Ticks a creator earning `income` per step spends on a post that would
+    // otherwise take `ticks`
+    pub fn posting_ticks(&self, ticks: i32, income: f32) -> i32 {
+        (ticks as f32 / (1.0 + self.incentive.max(0.0) * income.max(0.0))).round() as i32
+    }
+}
+
+// What one creator has been paid by its followers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Earnings {
+    pub tips: f32,
+    pub tip_count: usize,
+    pub subscriptions: f32,
+    // Affine followers at the last subscription payment
+    pub subscribers: usize,
+    // Earned per step over the last interval, which is what the creator's
+    // posting responds to
+    pub income: f32,
+    // Earned since the last interval closed
+    pub pending: f32,
+}
+
+impl Earnings {
+    pub fn total(&self) -> f32 {
+        self.tips + self.subscriptions
+    }
+
+    pub fn tip(&mut self, amount: f32) {
+        self.tips += amount;
+        self.tip_count += 1;
+        self.pending += amount;
+    }
+
+    // Collects `steps` worth of subscriptions and closes the interval
+    pub fn settle(&mut self, subscribers: usize, fee: f32, steps: u64) {
+        let subscriptions = subscribers as f32 * fee * steps as f32;
+        self.subscriptions += subscriptions;
+        self.subscribers = subscribers;
+        self.income = (self.pending + subscriptions) / steps.max(1) as f32;
+        self.pending = 0.0;
+    }
+}
+
+// Gini coefficient of the values, from 0 when everyone holds the same to
+// nearly 1 when one holds everything. None when there is nothing to share
+pub fn gini(values: &[f32]) -> Option<f32> {
+    let total: f32 = values.iter().sum();
+    if values.is_empty() || total <= 0.0 {
+        return None;
+    }
+
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f32::total_cmp);
+    let n = sorted.len() as f32;
+    let ranked: f32 = sorted
+        .iter()
+        .enumerate()
+        .map(|(rank, value)| (rank + 1) as f32 * value)
+        .sum();
+    Some(2.0 * ranked / (n * total) - (n + 1.0) / n)
+}
+
+// Share of the total held by the richest tenth, or the richest one when there
+// are fewer than ten
+pub fn top_share(values: &[f32]) -> Option<f32> {
+    let total: f32 = values.iter().sum();
+    if values.is_empty() || total <= 0.0 {
+        return None;
+    }
+
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| b.total_cmp(a));
+    let top = sorted.len().div_ceil(10);
+    Some(sorted[..top].iter().sum::<f32>() / total)
+}
+
+// How creators' earnings are spread as of one step
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WealthSample {
+    pub step: u64,
+    pub creators: usize,
+    pub total: f32,
+    pub gini: Option<f32>,
+    pub top_share: Option<f32>,
+}
+
+impl WealthSample {
+    pub fn of(step: u64, earnings: &[f32]) -> Self {
+        Self {
+            step,
+            creators: earnings.len(),
+            total: earnings.iter().sum(),
+            gini: gini(earnings),
+            top_share: top_share(earnings),
+        }
+    }
+}
+
+// Creator wealth sampled over the run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WealthSeries {
+    pub samples: VecDeque<WealthSample>,
+}
+
+impl WealthSeries {
+    pub fn record(&mut self, sample: WealthSample) {
+        if self.samples.len() == MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn latest(&self) -> Option<&WealthSample> {
+        self.samples.back()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
@@ -0,0 +1,192 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+
+use super::{AgentId, AgentType, CascadeAction, Simulation};
+
+// Samples kept, oldest dropped first
+const MAX_SAMPLES: usize = 500;
+
+// Group for creators not yet put in a community
+pub const UNASSIGNED: &str = "unassigned";
+
+// How creators are put in groups for exposure targets
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FairnessGrouping {
+    // "individual", "bot" and "organisation"
+    #[default]
+    AgentType,
+    // "community 0", "community 1" and so on, as of the latest detection run
+    Community,
+}
+
+impl FairnessGrouping {
+    pub const ALL: [FairnessGrouping; 2] =
+        [FairnessGrouping::AgentType, FairnessGrouping::Community];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FairnessGrouping::AgentType => "agent type",
+            FairnessGrouping::Community => "community",
+        }
+    }
+}
+
+// Bounds on the share of each feed one group's posts may take
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExposureTarget {
+    pub group: String,
+    #[serde(default)]
+    pub min_share: Option<f32>,
+    #[serde(default)]
+    pub max_share: Option<f32>,
+}
+
+impl ExposureTarget {
+    // How far a share falls outside the target, 0 when within it
+    pub fn gap(&self, share: f32) -> f32 {
+        let below = self.min_share.map_or(0.0, |min| min - share);
+        let above = self.max_share.map_or(0.0, |max| share - max);
+        below.max(above).max(0.0)
+    }
+}
+
+// Exposure quotas the engine enforces in every feed, e.g. organisations
+// capped at 30% of each feed, with the shares actually realised sampled over
+// the run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FairnessConfig {
+    pub grouping: FairnessGrouping,
+    pub targets: Vec<ExposureTarget>,
+    // Caps every group at an even split of each feed among the groups with
+    // posts in the running for it
+    pub parity: bool,
+    // Steps between samples of realised shares, 0 to never sample
+    pub interval: u64,
+}
+
+impl Default for FairnessConfig {
+    fn default() -> Self {
+        Self {
+            grouping: FairnessGrouping::AgentType,
+            targets: Vec::new(),
+            parity: false,
+            interval: 50,
+        }
+    }
+}
+
+impl FairnessConfig {
+    pub fn enabled(&self) -> bool {
+        self.parity || !self.targets.is_empty()
+    }
+
+    pub fn target(&self, group: &str) -> Option<&ExposureTarget> {
+        self.targets.iter().find(|target| target.group == group)
+    }
+
+    // The group each agent's posts count towards
+    pub fn groups(&self, simulation: &Simulation) -> BTreeMap<AgentId, String> {
+        simulation
+            .agent_views()
+            .map(|agent| {
+                let group = match (self.grouping, agent.community) {
+                    (FairnessGrouping::AgentType, _) => agent.agent_type.name().to_string(),
+                    (FairnessGrouping::Community, Some(label)) => format!("community {label}"),
+                    (FairnessGrouping::Community, None) => UNASSIGNED.to_string(),
+                };
+                (agent.id, group)
+            })
+            .collect()
+    }
+
+    // Groups that can be named before the run starts
+    pub fn known_groups(&self) -> Option<Vec<&'static str>> {
+        match self.grouping {
+            FairnessGrouping::AgentType => Some(
+                [
+                    AgentType::Individual,
+                    AgentType::Bot,
+                    AgentType::Organisation,
+                ]
+                .iter()
+                .map(AgentType::name)
+                .collect(),
+            ),
+            FairnessGrouping::Community => None,
+        }
+    }
+}
+
+// Organic exposures each group's posts got since the previous sample
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FairnessSample {
+    pub step: u64,
+    pub exposures: BTreeMap<String, usize>,
+}
+
+impl FairnessSample {
+    // Counts the exposures from `from_step` up to the simulation's current
+    // step by the group of each post's creator
+    pub fn of(simulation: &Simulation, from_step: u64, groups: &BTreeMap<AgentId, String>) -> Self {
+        let mut exposures: BTreeMap<String, usize> = BTreeMap::new();
+        for post in &simulation.engine.content_pool {
+            let count = post
+                .cascade
+                .iter()
+                .filter(|event| event.step >= from_step && event.action == CascadeAction::Exposed)
+                .count();
+            if count > 0 {
+                let group = groups
+                    .get(&post.creator_id)
+                    .map_or(UNASSIGNED, String::as_str);
+                *exposures.entry(group.to_string()).or_default() += count;
+            }
+        }
+        Self {
+            step: simulation.step_count,
+            exposures,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.exposures.values().sum()
+    }
+
+    pub fn share(&self, group: &str) -> Option<f32> {
+        let total = self.total();
+        (total > 0).then(|| self.exposures.get(group).copied().unwrap_or(0) as f32 / total as f32)
+    }
+}
+
+// Realised exposure shares over the run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FairnessSeries {
+    pub samples: VecDeque<FairnessSample>,
+}
+
+impl FairnessSeries {
+    pub fn record(&mut self, sample: FairnessSample) {
+        if self.samples.len() == MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    // The step the next sample starts counting from
+    pub fn next_step(&self) -> u64 {
+        self.latest().map_or(0, |sample| sample.step)
+    }
+
+    pub fn latest(&self) -> Option<&FairnessSample> {
+        self.samples.back()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
@@ -0,0 +1,119 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{AgentType, SimulationConfig, TagMatrix};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum LengthDistribution {
+    Uniform { min: f32, max: f32 },
+    Normal { mean: f32, std_dev: f32 },
+    // Long-tailed, like most real post lengths. `median` is e^mu
+    LogNormal { median: f32, sigma: f32 },
+}
+
+impl LengthDistribution {
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f32 {
+        match self {
+            LengthDistribution::Uniform { min, max } => {
+                if max <= min {
+                    *min
+                } else {
+                    rng.gen_range(*min..*max)
+                }
+            }
+            LengthDistribution::Normal { mean, std_dev } => mean + std_dev * standard_normal(rng),
+            LengthDistribution::LogNormal { median, sigma } => {
+                median * (sigma * standard_normal(rng)).exp()
+            }
+        }
+    }
+}
+
+// Box-Muller transform, to avoid pulling in rand_distr for one distribution
+pub fn standard_normal<R: Rng + ?Sized>(rng: &mut R) -> f32 {
+    let u1: f32 = rng.gen_range(f32::EPSILON..1.0);
+    let u2: f32 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f32::consts::PI * u2).cos()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LengthDistributions {
+    pub individual: LengthDistribution,
+    pub bot: LengthDistribution,
+    pub organisation: LengthDistribution,
+}
+
+impl LengthDistributions {
+    pub fn for_type(&self, agent_type: AgentType) -> &LengthDistribution {
+        match agent_type {
+            AgentType::Individual => &self.individual,
+            AgentType::Bot => &self.bot,
+            AgentType::Organisation => &self.organisation,
+        }
+    }
+}
+
+// Statistical description of the content on the platform, which generated
+// posts and comments are sampled from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContentCorpus {
+    pub post_lengths: LengthDistributions,
+    pub comment_lengths: LengthDistribution,
+
+    // How much more likely a tag is to be added to a post that already has
+    // the other tag, on top of the creator's own interest in it
+    pub tag_cooccurrence: TagMatrix,
+}
+
+impl Default for ContentCorpus {
+    fn default() -> Self {
+        Self {
+            post_lengths: LengthDistributions {
+                individual: LengthDistribution::LogNormal {
+                    median: 20.0,
+                    sigma: 0.6,
+                },
+                // Bots churn out short, uniform posts
+                bot: LengthDistribution::Normal {
+                    mean: 10.0,
+                    std_dev: 3.0,
+                },
+                organisation: LengthDistribution::Normal {
+                    mean: 45.0,
+                    std_dev: 10.0,
+                },
+            },
+            comment_lengths: LengthDistribution::LogNormal {
+                median: 4.0,
+                sigma: 0.5,
+            },
+            tag_cooccurrence: TagMatrix::new()
+                .with_pair("science", "technology", 0.6)
+                .with_pair("science", "health", 0.4)
+                .with_pair("science", "education", 0.3)
+                .with_pair("politics", "business", 0.4)
+                .with_pair("politics", "education", 0.2)
+                .with_pair("sports", "entertainment", 0.3)
+                .with_pair("technology", "business", 0.3),
+        }
+    }
+}
+
+impl ContentCorpus {
+    // Lengths are capped by the config's maximums so the UI sliders still apply
+    pub fn post_length<R: Rng + ?Sized>(
+        &self,
+        agent_type: AgentType,
+        config: &SimulationConfig,
+        rng: &mut R,
+    ) -> i32 {
+        let length = self.post_lengths.for_type(agent_type).sample(rng);
+        (length.round() as i32).clamp(1, config.max_post_length.max(1))
+    }
+
+    pub fn comment_length<R: Rng + ?Sized>(&self, config: &SimulationConfig, rng: &mut R) -> i32 {
+        let length = self.comment_lengths.sample(rng);
+        (length.round() as i32).clamp(1, config.max_comment_length.max(1))
+    }
+}
@@ -0,0 +1,206 @@
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{Agent, AgentId};
+
+// How the follow graph looks at tick zero. Edges are mutual follows between
+// agents, numbered in the order they were created
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum GraphGenerator {
+    // Nobody follows anybody until they start reading
+    #[default]
+    Empty,
+    // Every pair is connected independently with probability `p`
+    ErdosRenyi {
+        p: f32,
+    },
+    // Each new agent connects to `m` existing agents, preferring those that
+    // already have many connections, giving a few hubs and a long tail
+    BarabasiAlbert {
+        m: usize,
+    },
+    // A ring where each agent knows its `k` nearest neighbours, with each edge
+    // rewired to a random agent with probability `beta`
+    WattsStrogatz {
+        k: usize,
+        beta: f32,
+    },
+    // Agents split into `communities` contiguous blocks, densely connected
+    // within a block with probability `p_in` and sparsely across with `p_out`
+    StochasticBlock {
+        communities: usize,
+        p_in: f32,
+        p_out: f32,
+    },
+}
+
+impl GraphGenerator {
+    pub fn name(&self) -> &'static str {
+        match self {
+            GraphGenerator::Empty => "empty",
+            GraphGenerator::ErdosRenyi { .. } => "Erdős–Rényi",
+            GraphGenerator::BarabasiAlbert { .. } => "Barabási–Albert",
+            GraphGenerator::WattsStrogatz { .. } => "Watts–Strogatz",
+            GraphGenerator::StochasticBlock { .. } => "stochastic block",
+        }
+    }
+
+    // Undirected edges between agents 0..count, each as (lower, higher)
+    pub fn generate<R: Rng + ?Sized>(&self, count: usize, rng: &mut R) -> BTreeSet<(usize, usize)> {
+        let mut edges = BTreeSet::new();
+        let mut connect = |a: usize, b: usize| {
+            if a != b {
+                edges.insert((a.min(b), a.max(b)));
+            }
+        };
+
+        match *self {
+            GraphGenerator::Empty => {}
+            GraphGenerator::ErdosRenyi { p } => {
+                for a in 0..count {
+                    for b in a + 1..count {
+                        if rng.gen::<f32>() < p {
+                            connect(a, b);
+                        }
+                    }
+                }
+            }
+            GraphGenerator::BarabasiAlbert { m } => {
+                let m = m.max(1);
+                // Each agent appears once per edge it has, so drawing from
+                // this is drawing in proportion to degree
+                let mut endpoints: Vec<usize> = Vec::new();
+
+                // Start from a small fully connected core
+                let core = (m + 1).min(count);
+                for a in 0..core {
+                    for b in a + 1..core {
+                        connect(a, b);
+                        endpoints.extend([a, b]);
+                    }
+                }
+
+                for new in core..count {
+                    let mut targets = BTreeSet::new();
+                    while targets.len() < m.min(new) {
+                        let target = if endpoints.is_empty() {
+                            rng.gen_range(0..new)
+                        } else {
+                            *endpoints.choose(rng).unwrap()
+                        };
+                        targets.insert(target);
+                    }
+                    for target in targets {
+                        connect(new, target);
+                        endpoints.extend([new, target]);
+                    }
+                }
+            }
+            GraphGenerator::WattsStrogatz { k, beta } => {
+                for a in 0..count {
+                    for offset in 1..=(k / 2).max(1) {
+                        let mut b = (a + offset) % count;
+                        if rng.gen::<f32>() < beta {
+                            b = rng.gen_range(0..count);
+                        }
+                        connect(a, b);
+                    }
+                }
+            }
+            GraphGenerator::StochasticBlock {
+                communities,
+                p_in,
+                p_out,
+            } => {
+                let block_size = count.div_ceil(communities.max(1)).max(1);
+                for a in 0..count {
+                    for b in a + 1..count {
+                        let p = if a / block_size == b / block_size {
+                            p_in
+                        } else {
+                            p_out
+                        };
+                        if rng.gen::<f32>() < p {
+                            connect(a, b);
+                        }
+                    }
+                }
+            }
+        }
+
+        edges
+    }
+}
+
+// Who follows whom, and how strongly, as of when it was taken. Follows live
+// with the agents that make them, so this is a view to query rather than
+// something to change
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SocialGraph {
+    pub following: BTreeMap<AgentId, BTreeMap<AgentId, f32>>,
+}
+
+impl SocialGraph {
+    // Agents that can't follow anyone are left out
+    pub fn of(agents: &[Box<dyn Agent>]) -> Self {
+        Self {
+            following: agents
+                .iter()
+                .filter_map(|agent| Some((*agent.id(), agent.preferred_creators()?.clone())))
+                .collect(),
+        }
+    }
+
+    pub fn follows(&self, follower: AgentId, followed: AgentId) -> bool {
+        self.weight(follower, followed).is_some()
+    }
+
+    pub fn weight(&self, follower: AgentId, followed: AgentId) -> Option<f32> {
+        self.following.get(&follower)?.get(&followed).copied()
+    }
+
+    pub fn following(&self, follower: AgentId) -> impl Iterator<Item = AgentId> + '_ {
+        self.following
+            .get(&follower)
+            .into_iter()
+            .flat_map(|follows| follows.keys().copied())
+    }
+
+    pub fn followers(&self, followed: AgentId) -> impl Iterator<Item = AgentId> + '_ {
+        self.following
+            .iter()
+            .filter(move |(_, follows)| follows.contains_key(&followed))
+            .map(|(follower, _)| *follower)
+    }
+
+    // Every (follower, followed) pair, sorted
+    pub fn edges(&self) -> impl Iterator<Item = (AgentId, AgentId)> + '_ {
+        self.following.iter().flat_map(|(follower, follows)| {
+            follows.keys().map(move |followed| (*follower, *followed))
+        })
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.following.values().map(BTreeMap::len).sum()
+    }
+
+    // How many agents follow each agent with any followers
+    pub fn follower_counts(&self) -> BTreeMap<AgentId, usize> {
+        let mut counts = BTreeMap::new();
+        for (_, followed) in self.edges() {
+            *counts.entry(followed).or_default() += 1;
+        }
+        counts
+    }
+
+    // Share of follows that are followed back, None with no follows
+    pub fn reciprocity(&self) -> Option<f32> {
+        let edges = self.edge_count();
+        let mutual = self
+            .edges()
+            .filter(|&(follower, followed)| self.follows(followed, follower))
+            .count();
+        (edges > 0).then(|| mutual as f32 / edges as f32)
+    }
+}
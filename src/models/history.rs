@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::InterestProfile;
+
+// An agent's interest weights as they were at a given simulation step
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InterestSnapshot {
+    pub step: u64,
+    pub weights: BTreeMap<String, f32>,
+}
+
+impl InterestSnapshot {
+    pub fn new(step: u64, profile: &InterestProfile) -> Self {
+        Self {
+            step,
+            weights: weights_of(profile),
+        }
+    }
+
+    // (tag, weight) pairs for exactly `tags`, so that charts of different
+    // snapshots share the same axes
+    pub fn interests(&self, tags: &[String]) -> Vec<(String, f32)> {
+        tags.iter()
+            .map(|tag| (tag.clone(), self.weights.get(tag).copied().unwrap_or(0.0)))
+            .collect()
+    }
+}
+
+// Periodic snapshots of one agent's interests. The first snapshot is taken at
+// spawn and is always kept so that drift can be measured against it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InterestHistory {
+    pub snapshots: Vec<InterestSnapshot>,
+}
+
+impl InterestHistory {
+    pub fn record(&mut self, step: u64, profile: &InterestProfile, max_snapshots: usize) {
+        self.snapshots.push(InterestSnapshot::new(step, profile));
+
+        // Thin out the oldest snapshots after spawn once over capacity
+        while self.snapshots.len() > max_snapshots.max(2) {
+            self.snapshots.remove(1);
+        }
+    }
+
+    pub fn spawn(&self) -> Option<&InterestSnapshot> {
+        self.snapshots.first()
+    }
+
+    // Total variation distance between the spawn weights and `profile`: 0.0
+    // when unchanged, 1.0 when no interest is shared at all
+    pub fn drift_since_spawn(&self, profile: &InterestProfile) -> f32 {
+        let Some(spawn) = self.spawn() else {
+            return 0.0;
+        };
+
+        let current = weights_of(profile);
+        let mut tags: Vec<&String> = spawn.weights.keys().chain(current.keys()).collect();
+        tags.sort();
+        tags.dedup();
+
+        // A profile with no interests yet has nothing to drift from
+        if spawn.weights.is_empty() || current.is_empty() {
+            return if spawn.weights.is_empty() == current.is_empty() {
+                0.0
+            } else {
+                1.0
+            };
+        }
+
+        tags.iter()
+            .map(|tag| {
+                let before = spawn.weights.get(*tag).copied().unwrap_or(0.0);
+                let after = current.get(*tag).copied().unwrap_or(0.0);
+                (after - before).abs()
+            })
+            .sum::<f32>()
+            / 2.0
+    }
+
+    // Every tag that appears in any snapshot or in `profile`, sorted
+    pub fn tags(&self, profile: &InterestProfile) -> Vec<String> {
+        let mut tags: Vec<String> = self
+            .snapshots
+            .iter()
+            .flat_map(|snapshot| snapshot.weights.keys().cloned())
+            .chain(profile.interests.keys().cloned())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+}
+
+fn weights_of(profile: &InterestProfile) -> BTreeMap<String, f32> {
+    profile
+        .normalised_weights()
+        .map(|(tag, weight)| (tag.clone(), weight))
+        .collect()
+}
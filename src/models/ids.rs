@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+use std::fmt;
+use std::ops::Deref;
+
+macro_rules! id_type {
+    ($name:ident) => {
+        #[derive(
+            Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+        )]
+        pub struct $name(pub usize);
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+    };
+}
+
+id_type!(AgentId);
+id_type!(PostId);
+id_type!(CommentId);
+
+// Short ID lists carried in agent states, such as a feed or a batch of
+// comments, kept inline so moving between states doesn't touch the heap
+pub type PostIdList = SmallVec<[PostId; 16]>;
+pub type CommentIdList = SmallVec<[CommentId; 16]>;
+
+// Post IDs in the order they were added, with constant-time membership. Post
+// IDs are handed out densely from zero, so membership is a bitset indexed by
+// ID
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PostIdSet {
+    ids: Vec<PostId>,
+    bits: Vec<u64>,
+}
+
+impl PostIdSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn contains(&self, id: &PostId) -> bool {
+        self.bits
+            .get(id.0 / 64)
+            .is_some_and(|word| word & (1 << (id.0 % 64)) != 0)
+    }
+
+    // Adds the ID if it isn't already present, returning whether it was added
+    pub fn insert(&mut self, id: PostId) -> bool {
+        if self.contains(&id) {
+            return false;
+        }
+
+        let word = id.0 / 64;
+        if self.bits.len() <= word {
+            self.bits.resize(word + 1, 0);
+        }
+        self.bits[word] |= 1 << (id.0 % 64);
+        self.ids.push(id);
+        true
+    }
+
+    pub fn as_slice(&self) -> &[PostId] {
+        &self.ids
+    }
+}
+
+impl Deref for PostIdSet {
+    type Target = [PostId];
+
+    fn deref(&self) -> &[PostId] {
+        &self.ids
+    }
+}
+
+impl Extend<PostId> for PostIdSet {
+    fn extend<T: IntoIterator<Item = PostId>>(&mut self, iter: T) {
+        for id in iter {
+            self.insert(id);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a PostIdSet {
+    type Item = &'a PostId;
+    type IntoIter = std::slice::Iter<'a, PostId>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ids.iter()
+    }
+}
+
+// Hands out IDs for everything in a simulation. Each kind counts up from zero
+// independently, so IDs are never reused even after agents are removed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IdAllocator {
+    next_agent: usize,
+    next_post: usize,
+    next_comment: usize,
+}
+
+impl IdAllocator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn next_agent_id(&mut self) -> AgentId {
+        self.next_agent += 1;
+        AgentId(self.next_agent - 1)
+    }
+
+    pub fn next_post_id(&mut self) -> PostId {
+        self.next_post += 1;
+        PostId(self.next_post - 1)
+    }
+
+    pub fn next_comment_id(&mut self) -> CommentId {
+        self.next_comment += 1;
+        CommentId(self.next_comment - 1)
+    }
+}
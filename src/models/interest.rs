@@ -1,24 +1,33 @@
 use nalgebra::DVector;
-use rand::{random, Rng};
-use std::collections::HashMap;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-#[derive(Debug, Clone)]
+use super::TagMatrix;
+
+// Weights are left unnormalised between reads, and only rescaled once their
+// total passes this, to keep them well within f32 precision
+const RENORMALISE_ABOVE: f32 = 1_000.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Topic {
-    // Represents the Agent's weighted interest in the Topic - an f32 between
-    // 0.0 and 1.0 that adds up to 1.0 when combined with all the other Topic
-    // weights of that Agent
+    // Represents the Agent's weighted interest in the Topic, relative to the
+    // other Topic weights of that Agent. Weights are normalised lazily, so
+    // read them through `InterestProfile::weight` or `normalised_weights`
+    // where they need to add up to 1.0
     pub weighted_interest: f32,
     // A value from -1.0 to 1.0 that represents the Agent's level of disagreement
     // or agreement with the Topic
     pub agreement: f32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InterestProfile {
     // String representation attached to that Topic, which is like a tag
-    pub interests: HashMap<String, Topic>,
+    pub interests: BTreeMap<String, Topic>,
 
-    // Should add to 1.0 except when being updated and prior to being normalised
+    // The sum of the weights as of the last normalisation plus any interest
+    // gained since
     pub total_weight: f32,
 
     // Vector representation of an Interest only considers the weights of the interests,
@@ -29,15 +38,101 @@ pub struct InterestProfile {
     pub vector_representation: DVector<f32>,
 }
 
+// One row of the flat, human-readable form of a profile
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InterestRow {
+    pub tag: String,
+    pub weight: f32,
+    pub agreement: f32,
+}
+
 impl InterestProfile {
     pub fn new(dimension_size: usize) -> Self {
         Self {
-            interests: HashMap::new(),
+            interests: BTreeMap::new(),
             total_weight: 0.0,
             vector_representation: DVector::zeros(dimension_size),
         }
     }
 
+    // The weight of `tag` as a share of all the agent's interests
+    pub fn weight(&self, tag: &str) -> Option<f32> {
+        let total = self.raw_total();
+        self.interests
+            .get(tag)
+            .map(|topic| Self::share(topic.weighted_interest, total))
+    }
+
+    // (tag, weight) pairs with the weights adding up to 1.0
+    pub fn normalised_weights(&self) -> impl Iterator<Item = (&String, f32)> {
+        let total = self.raw_total();
+        self.interests
+            .iter()
+            .map(move |(tag, topic)| (tag, Self::share(topic.weighted_interest, total)))
+    }
+
+    fn raw_total(&self) -> f32 {
+        self.interests
+            .values()
+            .map(|topic| topic.weighted_interest)
+            .sum()
+    }
+
+    fn share(weight: f32, total: f32) -> f32 {
+        if total > 0.0 {
+            weight / total
+        } else {
+            0.0
+        }
+    }
+
+    // Rows sorted by descending weight, then tag, so dumps are stable
+    pub fn to_table(&self) -> Vec<InterestRow> {
+        let mut rows: Vec<_> = self
+            .normalised_weights()
+            .map(|(tag, weight)| InterestRow {
+                tag: tag.clone(),
+                weight,
+                agreement: self.interests[tag].agreement,
+            })
+            .collect();
+
+        rows.sort_by(|a, b| {
+            b.weight
+                .partial_cmp(&a.weight)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.tag.cmp(&b.tag))
+        });
+        rows
+    }
+
+    // Weights are normalised, so rows may use any positive scale
+    pub fn from_table(rows: &[InterestRow], dimension_size: usize) -> Self {
+        let mut profile = InterestProfile::new(dimension_size);
+
+        for row in rows {
+            profile.interests.insert(
+                row.tag.clone(),
+                Topic {
+                    weighted_interest: row.weight.max(0.0),
+                    agreement: row.agreement.clamp(-1.0, 1.0),
+                },
+            );
+        }
+
+        profile.normalise_weights();
+        profile
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&self.to_table())
+    }
+
+    pub fn from_json(json: &str, dimension_size: usize) -> serde_json::Result<Self> {
+        let rows: Vec<InterestRow> = serde_json::from_str(json)?;
+        Ok(Self::from_table(&rows, dimension_size))
+    }
+
     pub fn filtered_clone(&self, selected_tags: &[String]) -> Self {
         let mut filtered = InterestProfile::new(self.vector_representation.len());
 
@@ -57,28 +152,110 @@ impl InterestProfile {
         filtered
     }
 
-    pub fn update_interest_from_profile(&mut self, profile: &InterestProfile, interest: f32) {
+    // Each tag's weight lands on its own dimension and bleeds into the
+    // dimensions of similar tags, so related topics are not orthogonal
+    pub fn build_vector(&mut self, tag_to_index: &BTreeMap<String, usize>, similarity: &TagMatrix) {
+        let mut vector = DVector::zeros(self.vector_representation.len());
+
+        for (tag, topic) in &self.interests {
+            for (other_tag, &index) in tag_to_index {
+                if index >= vector.len() {
+                    continue;
+                }
+
+                let relation = if other_tag == tag {
+                    1.0
+                } else {
+                    similarity.get(tag, other_tag)
+                };
+                vector[index] += topic.weighted_interest * relation;
+            }
+        }
+
+        self.vector_representation = vector;
+    }
+
+    // Interest in a topic also grows interest in the similar topics the agent
+    // already follows, without introducing new tags to the profile. Only the
+    // weights and vector dimensions the new interest touches are updated;
+    // weights are normalised lazily where they are read
+    pub fn update_interest_from_profile(
+        &mut self,
+        profile: &InterestProfile,
+        interest: f32,
+        similarity: &TagMatrix,
+        tag_to_index: &BTreeMap<String, usize>,
+    ) {
+        let mut additions: BTreeMap<String, f32> = BTreeMap::new();
+
         for (tag, content_interest) in &profile.interests {
             let weighted_addition = content_interest.weighted_interest * interest;
+            *additions.entry(tag.clone()).or_insert(0.0) += weighted_addition;
+
+            for other_tag in self.interests.keys() {
+                if other_tag != tag {
+                    *additions.entry(other_tag.clone()).or_insert(0.0) +=
+                        weighted_addition * similarity.get(tag, other_tag);
+                }
+            }
+        }
+
+        for (tag, addition) in additions {
+            // The vector is linear in the weights, so it can take the same
+            // addition as the weight it was built from
+            for (other_tag, &index) in tag_to_index {
+                if index >= self.vector_representation.len() {
+                    continue;
+                }
 
-            let topic = self.interests.entry(tag.clone()).or_insert(Topic {
+                let relation = if *other_tag == tag {
+                    1.0
+                } else {
+                    similarity.get(&tag, other_tag)
+                };
+                self.vector_representation[index] += addition * relation;
+            }
+
+            let topic = self.interests.entry(tag).or_insert(Topic {
                 weighted_interest: 0.0,
                 agreement: 0.0,
             });
 
-            topic.weighted_interest += weighted_addition;
+            topic.weighted_interest += addition;
+            self.total_weight += addition;
         }
 
-        self.normalise_weights();
+        if self.total_weight > RENORMALISE_ABOVE {
+            self.normalise_weights();
+        }
     }
 
-    pub fn normalise_weights(&mut self) {
-        self.total_weight = self
+    // Shannon entropy of the weights in bits: 0.0 for a single interest, higher
+    // the more evenly spread the agent's interests are
+    pub fn entropy(&self) -> f32 {
+        let total: f32 = self
             .interests
             .values()
             .map(|topic| topic.weighted_interest)
             .sum();
 
+        if total <= 0.0 {
+            return 0.0;
+        }
+
+        self.interests
+            .values()
+            .map(|topic| topic.weighted_interest / total)
+            .filter(|p| *p > 0.0)
+            .map(|p| -p * p.log2())
+            .sum()
+    }
+
+    // Rescales the weights to add up to 1.0, and the vector with them so it
+    // stays in step
+    pub fn normalise_weights(&mut self) {
+        self.total_weight = self.raw_total();
+
         if self.total_weight == 0.0 {
             return;
         }
@@ -86,26 +263,38 @@ impl InterestProfile {
         for topic in self.interests.values_mut() {
             topic.weighted_interest /= self.total_weight;
         }
+        self.vector_representation /= self.total_weight;
 
         self.total_weight = 1.0;
     }
 
-    pub fn select_content_tags(&self, min_tags: usize, max_tags: usize) -> Vec<String> {
+    // Picks the first tag in proportion to interest, then fills up to a random
+    // count from the remaining interests, favouring tags that co-occur with
+    // those already chosen
+    pub fn select_content_tags<R: Rng + ?Sized>(
+        &self,
+        min_tags: usize,
+        max_tags: usize,
+        cooccurrence: &TagMatrix,
+        rng: &mut R,
+    ) -> Vec<String> {
         let mut interests: Vec<_> = self
-            .interests
-            .iter()
-            .map(|(tag, topic)| (tag.clone(), topic.weighted_interest))
+            .normalised_weights()
+            .map(|(tag, weight)| (tag.clone(), weight))
             .collect();
 
-        interests.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        // Ties are broken by tag so that selection only depends on the RNG
+        interests.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap().then_with(|| a.0.cmp(&b.0)));
 
         let mut selected_tags = Vec::new();
         let mut remaining_tags = interests.clone();
 
-        let num_tags = rand::thread_rng().gen_range(min_tags..=max_tags.min(interests.len()));
+        // Creators with fewer interests than the minimum just use all of them
+        let max_tags = max_tags.min(interests.len());
+        let num_tags = rng.gen_range(min_tags.min(max_tags)..=max_tags);
 
         if !interests.is_empty() {
-            let mut random_weight = random::<f32>();
+            let mut random_weight = rng.gen::<f32>();
 
             for (tag, weight) in interests.iter() {
                 random_weight -= weight;
@@ -123,7 +312,26 @@ impl InterestProfile {
         }
 
         while selected_tags.len() < num_tags && !remaining_tags.is_empty() {
-            let index = rand::thread_rng().gen_range(0..remaining_tags.len());
+            let weights: Vec<f32> = remaining_tags
+                .iter()
+                .map(|(tag, weight)| {
+                    let boost: f32 = selected_tags
+                        .iter()
+                        .map(|selected| cooccurrence.get(selected, tag))
+                        .sum();
+                    (weight + f32::EPSILON) * (1.0 + boost)
+                })
+                .collect();
+
+            let mut random_weight = rng.gen::<f32>() * weights.iter().sum::<f32>();
+            let index = weights
+                .iter()
+                .position(|weight| {
+                    random_weight -= weight;
+                    random_weight <= 0.0
+                })
+                .unwrap_or(remaining_tags.len() - 1);
+
             selected_tags.push(remaining_tags.remove(index).0);
         }
 
@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+
+use super::{AgentId, GroundTruth, Nudge, PostId};
+use crate::engine::PromotionKind;
+
+// The engine ranking weights an intervention can change
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RankingWeight {
+    Interest,
+    Recency,
+    Engagement,
+}
+
+// A change made to a running simulation from outside it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Intervention {
+    // Publishes a post evenly covering `tags` on behalf of `creator_id`. A
+    // breaking post is boosted and pinned into feeds for `breaking_ticks`
+    // steps before decaying like any other; 0 publishes an ordinary post.
+    // `labels` sets the post's ground truth rather than drawing it, for
+    // planting posts evaluators know the truth about
+    InjectPost {
+        creator_id: AgentId,
+        tags: Vec<String>,
+        length: i32,
+        #[serde(default)]
+        breaking_ticks: u64,
+        #[serde(default)]
+        labels: Option<GroundTruth>,
+    },
+    // Sets one of an agent's interest weights before renormalising
+    SetInterest {
+        agent_id: AgentId,
+        tag: String,
+        weight: f32,
+    },
+    SetWeight {
+        weight: RankingWeight,
+        value: f32,
+    },
+    // Stops the creator's posts being recommended
+    BanCreator(AgentId),
+    // Pins or boosts a post already in the pool in every feed for `ticks`
+    // steps, as the platform would an official announcement
+    Promote {
+        post_id: PostId,
+        kind: PromotionKind,
+        ticks: u64,
+    },
+    // Closes a post to new comments, or reopens it
+    LockComments(PostId),
+    UnlockComments(PostId),
+    // Shows a nudge to individuals with the given chance of compliance, or
+    // stops showing it when that's 0
+    SetNudge {
+        nudge: Nudge,
+        compliance: f32,
+    },
+    // Adds `copies` new agents alike in profile and policy to an existing
+    // one, under fresh IDs, to amplify a particular kind of behaviour
+    CloneAgent {
+        agent_id: AgentId,
+        copies: usize,
+    },
+}
+
+impl Intervention {
+    pub fn describe(&self) -> String {
+        match self {
+            Intervention::InjectPost {
+                creator_id,
+                tags,
+                length,
+                breaking_ticks,
+                labels,
+            } => {
+                let mut description = format!(
+                    "Inject post by {} on [{}], length {}",
+                    creator_id,
+                    tags.join(", "),
+                    length
+                );
+                if *breaking_ticks > 0 {
+                    description += &format!(", breaking for {} ticks", breaking_ticks);
+                }
+                if let Some(labels) = labels {
+                    description += &format!(
+                        ", truly {} {} post of quality {:.2}",
+                        if labels.veracity { "a true" } else { "a false" },
+                        labels.topic,
+                        labels.quality
+                    );
+                }
+                description
+            }
+            Intervention::SetInterest {
+                agent_id,
+                tag,
+                weight,
+            } => format!(
+                "Set agent {}'s interest in {} to {:.2}",
+                agent_id, tag, weight
+            ),
+            Intervention::SetWeight { weight, value } => {
+                format!("Set {:?} weight to {:.2}", weight, value)
+            }
+            Intervention::BanCreator(creator_id) => format!("Ban creator {}", creator_id),
+            Intervention::Promote {
+                post_id,
+                kind: PromotionKind::Pin,
+                ticks,
+            } => format!("Pin post {} for {} ticks", post_id, ticks),
+            Intervention::Promote {
+                post_id,
+                kind: PromotionKind::Boost(boost),
+                ticks,
+            } => format!("Boost post {} {:.1}x for {} ticks", post_id, boost, ticks),
+            Intervention::LockComments(post_id) => format!("Lock comments on post {}", post_id),
+            Intervention::UnlockComments(post_id) => {
+                format!("Unlock comments on post {}", post_id)
+            }
+            Intervention::SetNudge { nudge, compliance } if *compliance > 0.0 => format!(
+                "Show {} nudge with compliance {:.2}",
+                nudge.name(),
+                compliance
+            ),
+            Intervention::SetNudge { nudge, .. } => format!("Stop {} nudge", nudge.name()),
+            Intervention::CloneAgent { agent_id, copies } => {
+                format!("Clone agent {} {} times", agent_id, copies)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledIntervention {
+    pub step: u64,
+    pub intervention: Intervention,
+    // Agents the intervention brought in, such as clones, so a replay can
+    // be checked against the IDs they were given
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub created: Vec<AgentId>,
+}
+
+// Interventions waiting for their step, and those already applied in the
+// order they were applied
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InterventionLog {
+    pub queued: Vec<ScheduledIntervention>,
+    pub applied: Vec<ScheduledIntervention>,
+}
+
+impl InterventionLog {
+    pub fn schedule(&mut self, step: u64, intervention: Intervention) {
+        self.queued.push(ScheduledIntervention {
+            step,
+            intervention,
+            created: Vec::new(),
+        });
+        // Stable, so interventions for the same step keep their order
+        self.queued.sort_by_key(|scheduled| scheduled.step);
+    }
+
+    // Removes and returns everything due at or before `step`
+    pub fn take_due(&mut self, step: u64) -> Vec<ScheduledIntervention> {
+        let due = self
+            .queued
+            .iter()
+            .take_while(|scheduled| scheduled.step <= step)
+            .count();
+        self.queued.drain(..due).collect()
+    }
+}
@@ -0,0 +1,227 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::generator::standard_normal;
+use super::{AgentId, AgentType, CascadeAction, Post, PostId, RngStreams, Simulation};
+
+// What a post really is, as opposed to what its tags say. Kept by the
+// simulation rather than the engine, so ranking can't see it but evaluators
+// can score ranking and moderation against it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroundTruth {
+    // The tag the post is really about
+    pub topic: String,
+    // Whether what the post claims is true
+    pub veracity: bool,
+    // Intrinsic quality, from 0 to 1
+    pub quality: f32,
+}
+
+// A value for each type of creator
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CreatorRates {
+    pub individual: f32,
+    pub bot: f32,
+    pub organisation: f32,
+}
+
+impl CreatorRates {
+    pub fn for_type(&self, agent_type: AgentType) -> f32 {
+        match agent_type {
+            AgentType::Individual => self.individual,
+            AgentType::Bot => self.bot,
+            AgentType::Organisation => self.organisation,
+        }
+    }
+}
+
+// How ground truth is drawn for posts that weren't given labels explicitly
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GroundTruthConfig {
+    // Chance a post by each type of creator is false
+    pub false_rate: CreatorRates,
+    // Mean quality of posts by each type of creator, and the standard
+    // deviation of individual posts around it
+    pub quality: CreatorRates,
+    pub quality_spread: f32,
+    // Correlation, from -1 to 1, between a post's quality and its appeal.
+    // Weak by default: engaging posts are only a little more likely to be good
+    pub quality_appeal_correlation: f32,
+}
+
+impl Default for GroundTruthConfig {
+    fn default() -> Self {
+        Self {
+            false_rate: CreatorRates {
+                individual: 0.05,
+                bot: 0.4,
+                organisation: 0.02,
+            },
+            quality: CreatorRates {
+                individual: 0.5,
+                bot: 0.25,
+                organisation: 0.7,
+            },
+            quality_spread: 0.15,
+            quality_appeal_correlation: 0.2,
+        }
+    }
+}
+
+impl GroundTruthConfig {
+    // Labels for a post, drawn from a stream of its own so they don't depend
+    // on which posts were labelled before it. Its true topic is its most
+    // heavily weighted tag
+    pub fn label(&self, post: &Post, creator_type: AgentType, seed: u64) -> GroundTruth {
+        let mut rng = RngStreams::keyed(seed, "ground truth", post.id.0 as u64);
+        let topic = post
+            .interest_profile
+            .normalised_weights()
+            .max_by(|a, b| a.1.total_cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(tag, _)| tag.clone())
+            .unwrap_or_default();
+        let correlation = self.quality_appeal_correlation.clamp(-1.0, 1.0);
+        let deviation = correlation * post.appeal
+            + (1.0 - correlation * correlation).sqrt() * standard_normal(&mut rng);
+        let quality = self.quality.for_type(creator_type) + self.quality_spread * deviation;
+
+        GroundTruth {
+            topic,
+            veracity: rng.gen::<f32>() >= self.false_rate.for_type(creator_type),
+            quality: quality.clamp(0.0, 1.0),
+        }
+    }
+}
+
+// Ground truth for every post published so far
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContentLabels {
+    pub labels: BTreeMap<PostId, GroundTruth>,
+    // How much of the content pool has been through `update`
+    labelled: usize,
+}
+
+impl ContentLabels {
+    pub fn get(&self, post_id: PostId) -> Option<&GroundTruth> {
+        self.labels.get(&post_id)
+    }
+
+    // Labels given up front, such as an injected post's, which `update`
+    // leaves alone
+    pub fn insert(&mut self, post_id: PostId, truth: GroundTruth) {
+        self.labels.insert(post_id, truth);
+    }
+
+    // Labels every post published since the last update. Posts by creators
+    // that aren't agents are labelled as an individual's would be
+    pub fn update(
+        &mut self,
+        pool: &[Post],
+        creator_type: impl Fn(AgentId) -> Option<AgentType>,
+        config: &GroundTruthConfig,
+        seed: u64,
+    ) {
+        for post in pool.iter().skip(self.labelled) {
+            // A reshare is as true as what it shares, which is always
+            // labelled first
+            let shared = post
+                .original
+                .and_then(|original| self.labels.get(&original))
+                .cloned();
+            let agent_type = creator_type(post.creator_id).unwrap_or(AgentType::Individual);
+            self.labels
+                .entry(post.id)
+                .or_insert_with(|| shared.unwrap_or_else(|| config.label(post, agent_type, seed)));
+        }
+        self.labelled = pool.len();
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+}
+
+// Ranking and moderation scored against ground truth over the whole run so
+// far. Each is None until there's something to score
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LabelEvaluation {
+    // Mean quality of the posts agents were shown
+    pub exposed_quality: Option<f32>,
+    // Mean quality of the posts agents went on to read
+    pub read_quality: Option<f32>,
+    // Share of exposures that were to false posts
+    pub false_exposure_share: Option<f32>,
+    // Share of exposures whose true topic is the agent's strongest interest
+    pub relevant_exposure_share: Option<f32>,
+    // Share of banned creators that mostly post false content, and share of
+    // such creators that have been banned
+    pub moderation_precision: Option<f32>,
+    pub moderation_recall: Option<f32>,
+}
+
+impl LabelEvaluation {
+    pub fn of(simulation: &Simulation) -> Self {
+        let top_interests: BTreeMap<AgentId, String> = simulation
+            .agent_views()
+            .filter_map(|agent| {
+                let (tag, _) = agent
+                    .interest_profile
+                    .normalised_weights()
+                    .max_by(|a, b| a.1.total_cmp(&b.1).then_with(|| b.0.cmp(a.0)))?;
+                Some((agent.id, tag.clone()))
+            })
+            .collect();
+
+        let (mut exposures, mut quality, mut false_exposures, mut relevant) = (0, 0.0, 0, 0);
+        let (mut reads, mut read_quality) = (0, 0.0);
+        // (false posts, posts) per creator
+        let mut by_creator: BTreeMap<AgentId, (usize, usize)> = BTreeMap::new();
+
+        for post in &simulation.engine.content_pool {
+            let Some(truth) = simulation.labels.get(post.id) else {
+                continue;
+            };
+            let counts = by_creator.entry(post.creator_id).or_default();
+            counts.0 += !truth.veracity as usize;
+            counts.1 += 1;
+
+            for event in &post.cascade {
+                if event.action == CascadeAction::Read {
+                    reads += 1;
+                    read_quality += truth.quality;
+                }
+                if event.action != CascadeAction::Exposed {
+                    continue;
+                }
+                exposures += 1;
+                quality += truth.quality;
+                false_exposures += !truth.veracity as usize;
+                relevant += (top_interests.get(&event.agent_id) == Some(&truth.topic)) as usize;
+            }
+        }
+
+        let share = |count: usize, total: usize| (total > 0).then(|| count as f32 / total as f32);
+        let false_sources: BTreeSet<AgentId> = by_creator
+            .into_iter()
+            .filter(|(_, (false_posts, posts))| false_posts * 2 > *posts)
+            .map(|(creator, _)| creator)
+            .collect();
+        let banned = &simulation.engine.banned_creators;
+        let correctly_banned = banned.intersection(&false_sources).count();
+
+        Self {
+            exposed_quality: (exposures > 0).then(|| quality / exposures as f32),
+            read_quality: (reads > 0).then(|| read_quality / reads as f32),
+            false_exposure_share: share(false_exposures, exposures),
+            relevant_exposure_share: share(relevant, exposures),
+            moderation_precision: share(correctly_banned, banned.len()),
+            moderation_recall: share(correctly_banned, false_sources.len()),
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use super::{CascadeAction, PostId, Simulation};
+use crate::engine::CommentLock;
+
+// When posts stop taking new comments, beyond being locked by hand
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommentLockConfig {
+    // Most comments a post takes in one step, 0 for no limit
+    pub rate_limit: usize,
+    // Thread conflict past which a post is locked automatically, 0 to never
+    // lock for conflict
+    pub conflict_threshold: f32,
+    // Whether banning a creator also locks their posts
+    pub lock_banned: bool,
+    // Steps either side of a lock compared when measuring its effect
+    pub effect_window: u64,
+}
+
+impl Default for CommentLockConfig {
+    fn default() -> Self {
+        Self {
+            rate_limit: 0,
+            conflict_threshold: 0.0,
+            lock_banned: false,
+            effect_window: 20,
+        }
+    }
+}
+
+// How a locked post spread in the window before its lock against the window
+// from the lock on, and how worked up the people who had commented on it are
+#[derive(Debug, Clone, PartialEq)]
+pub struct LockEffect {
+    pub post_id: PostId,
+    pub lock: CommentLock,
+    pub exposures_before: usize,
+    pub exposures_after: usize,
+    pub reads_before: usize,
+    pub reads_after: usize,
+    pub comments_before: usize,
+    // Comments turned away from the post, by the lock or the rate limit
+    pub rejected_comments: usize,
+    // Mean arousal now of the individuals who commented before the lock
+    pub commenter_arousal: Option<f32>,
+    // Whether the window after the lock has fully passed
+    pub complete: bool,
+}
+
+impl LockEffect {
+    // One per locked post, in post order
+    pub fn of(simulation: &Simulation, window: u64) -> Vec<Self> {
+        let engine = &simulation.engine;
+        engine
+            .comment_locks
+            .iter()
+            .filter_map(|(post_id, lock)| {
+                let post = engine.get_content_by_id(*post_id)?;
+                let before = lock.step.saturating_sub(window)..lock.step;
+                let after = lock.step..lock.step + window;
+                let count = |steps: &std::ops::Range<u64>, wanted: fn(&CascadeAction) -> bool| {
+                    post.cascade
+                        .iter()
+                        .filter(|event| steps.contains(&event.step) && wanted(&event.action))
+                        .count()
+                };
+                let exposed = |action: &CascadeAction| *action == CascadeAction::Exposed;
+                let read = |action: &CascadeAction| *action == CascadeAction::Read;
+                let commented =
+                    |action: &CascadeAction| matches!(action, CascadeAction::Commented(_));
+
+                let commenters: BTreeSet<_> = post
+                    .cascade
+                    .iter()
+                    .filter(|event| event.step <= lock.step && commented(&event.action))
+                    .map(|event| event.agent_id)
+                    .collect();
+                let arousals: Vec<f32> = simulation
+                    .agent_views()
+                    .filter(|agent| commenters.contains(&agent.id))
+                    .filter_map(|agent| agent.wellbeing.map(|wellbeing| wellbeing.arousal))
+                    .collect();
+
+                Some(Self {
+                    post_id: *post_id,
+                    lock: *lock,
+                    exposures_before: count(&before, exposed),
+                    exposures_after: count(&after, exposed),
+                    reads_before: count(&before, read),
+                    reads_after: count(&after, read),
+                    comments_before: count(&before, commented),
+                    rejected_comments: engine.rejected_comments.get(post_id).copied().unwrap_or(0),
+                    commenter_arousal: (!arousals.is_empty())
+                        .then(|| arousals.iter().sum::<f32>() / arousals.len() as f32),
+                    complete: simulation.step_count >= after.end,
+                })
+            })
+            .collect()
+    }
+}
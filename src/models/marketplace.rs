@@ -0,0 +1,238 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+
+use super::{AgentId, PostId};
+
+// Auction rounds kept, oldest dropped first
+const MAX_ROUNDS: usize = 500;
+
+// How an organisation decides what to bid for a sponsored slot
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum BidStrategy {
+    // The same amount every step, while the budget lasts
+    Fixed(f32),
+    // What's left of the day's budget spread evenly over the day's remaining
+    // steps
+    Paced,
+    // Raises its bid after losing a step and lowers it after winning one,
+    // hunting for the lowest price that still wins
+    Adaptive,
+}
+
+impl BidStrategy {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BidStrategy::Fixed(_) => "fixed",
+            BidStrategy::Paced => "paced",
+            BidStrategy::Adaptive => "adaptive",
+        }
+    }
+}
+
+// Organisations bidding each step for a few sponsored slots at the top of
+// every feed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarketplaceConfig {
+    // Sponsored slots sold each step, 0 for no marketplace
+    pub slots: usize,
+    // What each organisation may spend in a simulated day
+    pub daily_budget: f32,
+    // The lowest bid that can win, and the price when there are too few bids
+    // to set one
+    pub reserve_price: f32,
+    // Handed to organisations in turn as they enter the market
+    pub strategies: Vec<BidStrategy>,
+    // Share adaptive bidders raise their bid by after losing, and lower it by
+    // after winning
+    pub adaptation: f32,
+}
+
+impl Default for MarketplaceConfig {
+    fn default() -> Self {
+        Self {
+            slots: 0,
+            daily_budget: 10.0,
+            reserve_price: 0.01,
+            strategies: vec![BidStrategy::Paced, BidStrategy::Adaptive],
+            adaptation: 0.1,
+        }
+    }
+}
+
+impl MarketplaceConfig {
+    pub fn enabled(&self) -> bool {
+        self.slots > 0
+    }
+}
+
+// One organisation in the market
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bidder {
+    pub strategy: BidStrategy,
+    // What's left to spend today
+    pub budget: f32,
+    // The adaptive strategy's current bid
+    pub bid: f32,
+    pub spent: f32,
+    pub wins: usize,
+}
+
+impl Bidder {
+    fn new(strategy: BidStrategy, config: &MarketplaceConfig, steps_per_day: u64) -> Self {
+        Self {
+            strategy,
+            budget: config.daily_budget,
+            bid: (config.daily_budget / steps_per_day.max(1) as f32).max(config.reserve_price),
+            spent: 0.0,
+            wins: 0,
+        }
+    }
+
+    // What it bids this step, never more than it has left
+    pub fn bid_for(&self, steps_left_today: u64) -> f32 {
+        let bid = match self.strategy {
+            BidStrategy::Fixed(amount) => amount,
+            BidStrategy::Paced => self.budget / steps_left_today.max(1) as f32,
+            BidStrategy::Adaptive => self.bid,
+        };
+        bid.min(self.budget)
+    }
+}
+
+// One step's auction, and how the feeds it bought into were split
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuctionRound {
+    pub step: u64,
+    // Organisations that bid at least the reserve price
+    pub bidders: usize,
+    // Winners in slot order, with the post each sponsored
+    pub winners: Vec<(AgentId, PostId)>,
+    // What each winner paid, None when nobody won
+    pub price: Option<f32>,
+    // Exposures applied to the engine this step, sponsored and not
+    pub sponsored_exposures: usize,
+    pub organic_exposures: usize,
+}
+
+impl AuctionRound {
+    // Share of the step's exposures that went to sponsored posts, and so
+    // were taken from organic ones
+    pub fn sponsored_share(&self) -> Option<f32> {
+        let total = self.sponsored_exposures + self.organic_exposures;
+        (total > 0).then(|| self.sponsored_exposures as f32 / total as f32)
+    }
+}
+
+// The market for sponsored slots over the run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Marketplace {
+    pub bidders: BTreeMap<AgentId, Bidder>,
+    pub rounds: VecDeque<AuctionRound>,
+}
+
+impl Marketplace {
+    // Sells this step's slots to the highest bidders, each offering its
+    // newest post. Winners all pay the highest losing bid, or the reserve
+    // price when every bidder wins, and the winning posts are returned in
+    // slot order. Budgets are refilled at the start of each day
+    pub fn auction(
+        &mut self,
+        step: u64,
+        steps_per_day: u64,
+        config: &MarketplaceConfig,
+        offers: &[(AgentId, PostId)],
+    ) -> Vec<PostId> {
+        let steps_per_day = steps_per_day.max(1);
+        if step % steps_per_day == 0 {
+            for bidder in self.bidders.values_mut() {
+                bidder.budget = config.daily_budget;
+            }
+        }
+        for (agent_id, _) in offers {
+            if !self.bidders.contains_key(agent_id) && !config.strategies.is_empty() {
+                let strategy = config.strategies[self.bidders.len() % config.strategies.len()];
+                self.bidders
+                    .insert(*agent_id, Bidder::new(strategy, config, steps_per_day));
+            }
+        }
+
+        let steps_left = steps_per_day - step % steps_per_day;
+        let mut bids: Vec<(AgentId, PostId, f32)> = offers
+            .iter()
+            .filter_map(|(agent_id, post_id)| {
+                let bid = self.bidders.get(agent_id)?.bid_for(steps_left);
+                (bid >= config.reserve_price && bid > 0.0).then_some((*agent_id, *post_id, bid))
+            })
+            .collect();
+        // Highest first, ties going to the lower agent id
+        bids.sort_by(|a, b| b.2.total_cmp(&a.2).then(a.0.cmp(&b.0)));
+
+        let slots = config.slots.min(bids.len());
+        let price = bids.get(slots).map_or(config.reserve_price, |bid| bid.2);
+        for (index, (agent_id, _, _)) in bids.iter().enumerate() {
+            let bidder = self.bidders.get_mut(agent_id).expect("only bidders bid");
+            let won = index < slots;
+            if won {
+                bidder.budget = (bidder.budget - price).max(0.0);
+                bidder.spent += price;
+                bidder.wins += 1;
+            }
+            if bidder.strategy == BidStrategy::Adaptive {
+                let change = if won {
+                    1.0 - config.adaptation
+                } else {
+                    1.0 + config.adaptation
+                };
+                bidder.bid = (bidder.bid * change).max(config.reserve_price);
+            }
+        }
+
+        let winners: Vec<(AgentId, PostId)> = bids[..slots]
+            .iter()
+            .map(|(agent_id, post_id, _)| (*agent_id, *post_id))
+            .collect();
+        if self.rounds.len() == MAX_ROUNDS {
+            self.rounds.pop_front();
+        }
+        self.rounds.push_back(AuctionRound {
+            step,
+            bidders: bids.len(),
+            price: (slots > 0).then_some(price),
+            winners: winners.clone(),
+            sponsored_exposures: 0,
+            organic_exposures: 0,
+        });
+        winners.into_iter().map(|(_, post_id)| post_id).collect()
+    }
+
+    // Records how the latest round's step split its exposures
+    pub fn settle(&mut self, sponsored: usize, organic: usize) {
+        if let Some(round) = self.rounds.back_mut() {
+            round.sponsored_exposures += sponsored;
+            round.organic_exposures += organic;
+        }
+    }
+
+    pub fn latest(&self) -> Option<&AuctionRound> {
+        self.rounds.back()
+    }
+
+    // Share of every exposure in the kept rounds that went to sponsored
+    // posts, displacing organic ones from the same feed slots
+    pub fn crowding_out(&self) -> Option<f32> {
+        let (sponsored, organic) = self.rounds.iter().fold((0, 0), |(s, o), round| {
+            (s + round.sponsored_exposures, o + round.organic_exposures)
+        });
+        let total = sponsored + organic;
+        (total > 0).then(|| sponsored as f32 / total as f32)
+    }
+
+    pub fn len(&self) -> usize {
+        self.rounds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rounds.is_empty()
+    }
+}
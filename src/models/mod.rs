@@ -1,9 +1,83 @@
+pub mod adoption;
+pub mod ads;
 mod agents;
+pub mod attention;
+pub mod background;
+pub mod bridges;
+pub mod cascade;
+pub mod community;
 pub mod content;
+pub mod creator;
+pub mod diagnostics;
+pub mod drift;
+pub mod economy;
+pub mod fairness;
+pub mod generator;
+pub mod graph;
+pub mod history;
+pub mod ids;
 pub mod interest;
+pub mod intervention;
+pub mod labels;
+pub mod locking;
+pub mod marketplace;
+pub mod optimiser;
+pub mod reading;
+pub mod rng;
+pub mod scenario;
+pub mod segments;
+pub mod sharing;
 pub mod simulation;
+pub mod stats;
+pub mod survey;
+pub mod tags;
+pub mod tradeoff;
+pub mod transparency;
+pub mod twin;
+pub mod validation;
+pub mod view;
+pub mod wellbeing;
 
+pub use adoption::{AdoptionConfig, AdoptionSample, AdoptionSeries};
+pub use ads::{AdStats, AdsConfig, RevenueSample, RevenueSeries};
 pub use agents::*;
-pub use content::Post;
+pub use attention::{AttentionCurve, HalfLifeStats, HalfLives};
+pub use background::{BackgroundAnalytics, Pending};
+pub use bridges::{Bridge, Bridges};
+pub use cascade::{Cascade, CascadeAction, CascadeBranch, CascadeEvent};
+pub use community::{Communities, CommunityStats, FollowSnapshot};
+pub use content::{ContentKind, Post};
+pub use creator::{CreatorAnalytics, FollowerHistory, FollowerSnapshot};
+pub use diagnostics::{Diagnostics, HealthWarning};
+pub use drift::{DriftConfig, DriftSample, TopicDrift};
+pub use economy::{Earnings, EconomyConfig, WealthSample, WealthSeries};
+pub use fairness::{
+    ExposureTarget, FairnessConfig, FairnessGrouping, FairnessSample, FairnessSeries,
+};
+pub use generator::{ContentCorpus, LengthDistribution, LengthDistributions};
+pub use graph::{GraphGenerator, SocialGraph};
+pub use history::{InterestHistory, InterestSnapshot};
+pub use ids::{AgentId, CommentId, CommentIdList, IdAllocator, PostId, PostIdList, PostIdSet};
 pub use interest::*;
-pub use simulation::{Simulation, SimulationConfig};
+pub use intervention::{Intervention, InterventionLog, RankingWeight, ScheduledIntervention};
+pub use labels::{ContentLabels, CreatorRates, GroundTruth, GroundTruthConfig, LabelEvaluation};
+pub use locking::{CommentLockConfig, LockEffect};
+pub use marketplace::{AuctionRound, BidStrategy, Bidder, Marketplace, MarketplaceConfig};
+pub use optimiser::{Optimiser, ParameterRange, Statistic, Target, Trial};
+pub use reading::{MediaRates, ReadMedia, ReadTimeModel};
+pub use rng::{RngStreams, StreamRng};
+pub use scenario::{diff_configs, validate, with_parameter, ConfigChange, Scenario, ScenarioError};
+pub use segments::{SegmentConfig, SegmentStats};
+pub use sharing::{ReshareTree, SharingConfig};
+pub use simulation::{RunSummary, Simulation, SimulationConfig};
+pub use stats::{Histogram, PopulationMetric};
+pub use survey::{Reported, Survey, SurveyConfig, SurveyResponse, SurveyWave, TopicResponse};
+pub use tags::TagMatrix;
+pub use tradeoff::{TradeoffCurve, TradeoffPoint};
+pub use transparency::{
+    Share, Tallies, TransparencyConfig, TransparencyReport, TransparencyReports,
+};
+pub use twin::{PairedMetric, Treatment, TwinRun, TwinStep};
+pub use validation::{bounds, Bounds, ConfigBuilder, ConfigError, InvalidConfig};
+pub use view::{AgentView, PopulationSummary};
+pub use wellbeing::{WellBeing, WellBeingConfig, WellBeingSample, WellBeingSeries};
@@ -0,0 +1,288 @@
+use rand::Rng;
+
+use super::generator::standard_normal;
+use super::StreamRng;
+use super::{
+    with_parameter, PopulationMetric, RngStreams, ScenarioError, Simulation, SimulationConfig,
+};
+
+// Share of the trials drawn uniformly before the search starts refining around
+// the best candidate so far
+const EXPLORE_SHARE: f32 = 0.5;
+
+// Spread of refinement draws as a share of each parameter's range, narrowing
+// towards the end of the search
+const REFINE_SPREAD: (f32, f32) = (0.2, 0.02);
+
+// Targets are compared relative to their value, with this as the floor on the
+// scale so targets of zero still work
+const MIN_SCALE: f32 = 1e-3;
+
+// A config parameter to search over, by dotted key, between `min` and `max`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterRange {
+    pub key: String,
+    pub min: f32,
+    pub max: f32,
+    // Whole numbers only, for counts such as `num_individuals`
+    pub integer: bool,
+}
+
+impl ParameterRange {
+    pub fn float(key: &str, min: f32, max: f32) -> Self {
+        Self {
+            key: key.to_string(),
+            min,
+            max,
+            integer: false,
+        }
+    }
+
+    pub fn integer(key: &str, min: u32, max: u32) -> Self {
+        Self {
+            key: key.to_string(),
+            min: min as f32,
+            max: max as f32,
+            integer: true,
+        }
+    }
+
+    fn clamp(&self, value: f32) -> f32 {
+        let value = value.clamp(self.min, self.max);
+        if self.integer {
+            value.round()
+        } else {
+            value
+        }
+    }
+
+    // The value as TOML, for `with_parameter`
+    fn toml(&self, value: f32) -> String {
+        if self.integer {
+            format!("{}", value as i64)
+        } else {
+            format!("{value:?}")
+        }
+    }
+}
+
+// How a metric's values across the population are summarised before being
+// compared with a target
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Statistic {
+    Mean,
+    // The value below which this share of agents fall, e.g. 0.5 for the median
+    Quantile(f32),
+}
+
+impl Statistic {
+    pub fn of(&self, values: &[f32]) -> Option<f32> {
+        if values.is_empty() {
+            return None;
+        }
+        match *self {
+            Statistic::Mean => Some(values.iter().sum::<f32>() / values.len() as f32),
+            Statistic::Quantile(share) => {
+                let mut sorted = values.to_vec();
+                sorted.sort_by(|a, b| a.total_cmp(b));
+                let index = (share.clamp(0.0, 1.0) * (sorted.len() - 1) as f32).round();
+                Some(sorted[index as usize])
+            }
+        }
+    }
+}
+
+// One value the simulation should reproduce, such as a reference dataset's
+// median session length
+#[derive(Debug, Clone, PartialEq)]
+pub struct Target {
+    pub metric: PopulationMetric,
+    pub statistic: Statistic,
+    pub value: f32,
+    // How much missing this target counts against a candidate
+    pub weight: f32,
+}
+
+impl Target {
+    pub fn mean(metric: PopulationMetric, value: f32) -> Self {
+        Self {
+            metric,
+            statistic: Statistic::Mean,
+            value,
+            weight: 1.0,
+        }
+    }
+
+    // Targets matching a reference dataset's distribution of a metric at each
+    // of the given quantiles
+    pub fn distribution(
+        metric: PopulationMetric,
+        reference: &[f32],
+        quantiles: &[f32],
+    ) -> Vec<Self> {
+        quantiles
+            .iter()
+            .filter_map(|&share| {
+                let statistic = Statistic::Quantile(share);
+                statistic.of(reference).map(|value| Self {
+                    metric: metric.clone(),
+                    statistic,
+                    value,
+                    weight: 1.0,
+                })
+            })
+            .collect()
+    }
+
+    // Squared error relative to the target, weighted. A metric no agent has a
+    // value for misses completely
+    fn loss(&self, simulated: Option<f32>) -> f32 {
+        let scale = self.value.abs().max(MIN_SCALE);
+        let error = match simulated {
+            Some(simulated) => (simulated - self.value) / scale,
+            None => 1.0,
+        };
+        self.weight * error * error
+    }
+}
+
+// One candidate the optimiser ran, with the parameter values it tried in the
+// order they were given and each target's simulated statistic
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trial {
+    pub values: Vec<f32>,
+    pub simulated: Vec<Option<f32>>,
+    pub loss: f32,
+}
+
+// Searches config parameters for the values whose runs best reproduce a set
+// of targets. Every candidate runs from the base config's seed, so candidates
+// differ only in their parameters. Trials start as uniform random draws and
+// then concentrate around the best found so far
+#[derive(Debug, Clone)]
+pub struct Optimiser {
+    pub base: SimulationConfig,
+    pub parameters: Vec<ParameterRange>,
+    pub targets: Vec<Target>,
+    // Steps each candidate runs for before it's measured
+    pub ticks: u64,
+    // The number of trials the search is planned over, which sets when it
+    // switches from exploring to refining
+    pub budget: usize,
+    pub trials: Vec<Trial>,
+    rng: StreamRng,
+}
+
+impl Optimiser {
+    pub fn new(
+        base: SimulationConfig,
+        parameters: Vec<ParameterRange>,
+        targets: Vec<Target>,
+        ticks: u64,
+        budget: usize,
+    ) -> Result<Self, ScenarioError> {
+        // Catch unknown keys now rather than at the first trial
+        for parameter in &parameters {
+            with_parameter(&base, &parameter.key, &parameter.toml(parameter.min))?;
+        }
+
+        Ok(Self {
+            rng: RngStreams::stream(base.seed, "optimiser"),
+            base,
+            parameters,
+            targets,
+            ticks,
+            budget,
+            trials: Vec::new(),
+        })
+    }
+
+    pub fn finished(&self) -> bool {
+        self.trials.len() >= self.budget
+    }
+
+    pub fn best(&self) -> Option<&Trial> {
+        self.trials.iter().min_by(|a, b| a.loss.total_cmp(&b.loss))
+    }
+
+    // The base config with a trial's parameter values applied
+    pub fn config(&self, values: &[f32]) -> Result<SimulationConfig, ScenarioError> {
+        let mut config = self.base.clone();
+        for (parameter, value) in self.parameters.iter().zip(values) {
+            config = with_parameter(&config, &parameter.key, &parameter.toml(*value))?;
+        }
+        Ok(config)
+    }
+
+    // Draws the next candidate, runs it and scores it against the targets
+    pub fn trial(&mut self) -> Result<&Trial, ScenarioError> {
+        let values = self.candidate();
+        let mut simulation = Simulation::new(self.config(&values)?);
+        for _ in 0..self.ticks {
+            simulation.step();
+        }
+
+        let simulated: Vec<Option<f32>> = self
+            .targets
+            .iter()
+            .map(|target| target.statistic.of(&target.metric.values(&simulation)))
+            .collect();
+        let loss = self
+            .targets
+            .iter()
+            .zip(&simulated)
+            .map(|(target, simulated)| target.loss(*simulated))
+            .sum();
+
+        self.trials.push(Trial {
+            values,
+            simulated,
+            loss,
+        });
+        Ok(self.trials.last().unwrap())
+    }
+
+    // Runs trials until the budget is spent and returns the best
+    pub fn run(&mut self) -> Result<Option<&Trial>, ScenarioError> {
+        while !self.finished() {
+            self.trial()?;
+        }
+        Ok(self.best())
+    }
+
+    fn candidate(&mut self) -> Vec<f32> {
+        let explore = (self.budget as f32 * EXPLORE_SHARE).ceil() as usize;
+        let best = match self.best() {
+            Some(best) if self.trials.len() >= explore => best.values.clone(),
+            _ => {
+                return self
+                    .parameters
+                    .iter()
+                    .map(|parameter| parameter.clamp(uniform(parameter, &mut self.rng)))
+                    .collect()
+            }
+        };
+
+        // Refinement narrows linearly over the rest of the budget
+        let progress = (self.trials.len() - explore) as f32
+            / (self.budget.saturating_sub(explore)).max(1) as f32;
+        let spread = REFINE_SPREAD.0 + (REFINE_SPREAD.1 - REFINE_SPREAD.0) * progress.min(1.0);
+
+        self.parameters
+            .iter()
+            .zip(best)
+            .map(|(parameter, value)| {
+                let width = parameter.max - parameter.min;
+                parameter.clamp(value + spread * width * standard_normal(&mut self.rng))
+            })
+            .collect()
+    }
+}
+
+fn uniform(parameter: &ParameterRange, rng: &mut StreamRng) -> f32 {
+    if parameter.max <= parameter.min {
+        parameter.min
+    } else {
+        rng.gen_range(parameter.min..=parameter.max)
+    }
+}
@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+
+use super::ContentKind;
+
+// What an agent is reading, for read-time models that time each differently
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReadMedia {
+    Post(ContentKind),
+    Comment,
+}
+
+// How long each kind of thing takes to read, as a multiple of the linear time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MediaRates {
+    pub organic: f32,
+    pub injected: f32,
+    pub breaking: f32,
+    pub comment: f32,
+}
+
+impl Default for MediaRates {
+    fn default() -> Self {
+        Self {
+            organic: 1.0,
+            injected: 1.2,
+            breaking: 0.6,
+            comment: 0.5,
+        }
+    }
+}
+
+impl MediaRates {
+    pub fn rate(&self, media: ReadMedia) -> f32 {
+        match media {
+            ReadMedia::Post(ContentKind::Organic) => self.organic,
+            ReadMedia::Post(ContentKind::Injected) => self.injected,
+            ReadMedia::Post(ContentKind::Breaking) => self.breaking,
+            ReadMedia::Comment => self.comment,
+        }
+    }
+}
+
+// How many ticks reading something takes, given its length and how fast the
+// agent reads. Session structure is very sensitive to this, so it can be
+// swapped out
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum ReadTimeModel {
+    // length × (1 − read speed)
+    #[default]
+    Linear,
+    // Longer items take longer, but less than in proportion to their length:
+    // `scale` × ln(1 + length) × (1 − read speed)
+    LogLength {
+        scale: f32,
+    },
+    // Linear, scaled by a rate for each kind of thing read
+    PerMedia {
+        rates: MediaRates,
+    },
+    // The first `full` units of length are read properly and only `rate` of
+    // the rest, so long items are skimmed
+    Skim {
+        full: i32,
+        rate: f32,
+    },
+}
+
+impl ReadTimeModel {
+    // Each model with its default parameters, for choosing between them
+    pub fn presets() -> [ReadTimeModel; 4] {
+        [
+            ReadTimeModel::Linear,
+            ReadTimeModel::LogLength { scale: 10.0 },
+            ReadTimeModel::PerMedia {
+                rates: MediaRates::default(),
+            },
+            ReadTimeModel::Skim {
+                full: 20,
+                rate: 0.3,
+            },
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ReadTimeModel::Linear => "linear",
+            ReadTimeModel::LogLength { .. } => "log-length",
+            ReadTimeModel::PerMedia { .. } => "per media type",
+            ReadTimeModel::Skim { .. } => "skim",
+        }
+    }
+
+    pub fn ticks(&self, length: i32, read_speed: f32, media: ReadMedia) -> i32 {
+        let length = length.max(0) as f32;
+        let effort = match self {
+            ReadTimeModel::Linear => length,
+            ReadTimeModel::LogLength { scale } => scale * length.ln_1p(),
+            ReadTimeModel::PerMedia { rates } => length * rates.rate(media),
+            ReadTimeModel::Skim { full, rate } => {
+                let full = length.min(*full as f32);
+                full + (length - full) * rate
+            }
+        };
+        (effort * (1.0 - read_speed)) as i32
+    }
+}
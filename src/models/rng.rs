@@ -0,0 +1,74 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+
+// The generator behind every stream. It's the one rand's StdRng wraps, named
+// directly so its state can be saved along with the simulation
+pub type StreamRng = ChaCha12Rng;
+
+// Independent random streams for each subsystem, all derived from one master
+// seed. Changing how much randomness one subsystem consumes then leaves the
+// sequences every other subsystem sees untouched. The simulation hands each
+// agent a set of its own every step, and the engine, which ranks for one
+// agent at a time, likewise keys its streams by the agent and step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RngStreams {
+    // Agent behaviour: coming online, attention, choosing between posts
+    pub agents: StreamRng,
+    // Generated post and comment lengths and tags
+    pub content: StreamRng,
+    // Rolls made by transition policies
+    pub policy: StreamRng,
+}
+
+impl RngStreams {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            agents: Self::stream(seed, "agents"),
+            content: Self::stream(seed, "content"),
+            policy: Self::stream(seed, "policy"),
+        }
+    }
+
+    // One agent's streams, keyed by its ID as `keyed` is, so what it draws
+    // doesn't depend on which agents drew before it
+    pub fn agent(seed: u64, key: u64) -> Self {
+        Self {
+            agents: Self::keyed(seed, "agents", key),
+            content: Self::keyed(seed, "content", key),
+            policy: Self::keyed(seed, "policy", key),
+        }
+    }
+
+    // A stream seeded from the master seed and its name, for subsystems that
+    // want one of their own
+    pub fn stream(seed: u64, name: &str) -> StreamRng {
+        StreamRng::seed_from_u64(splitmix64(seed ^ fnv1a(name)))
+    }
+
+    // A stream for one thing among many, such as one agent's starting traits,
+    // so what it draws doesn't depend on how many were drawn for before it
+    pub fn keyed(seed: u64, name: &str, key: u64) -> StreamRng {
+        Self::stream(seed ^ splitmix64(key), name)
+    }
+
+    // A uniform draw in [0, 1) for one thing among many, for checks made too
+    // often to seed a whole stream for each
+    pub fn unit(seed: u64, name: &str, key: u64) -> f32 {
+        (splitmix64(seed ^ fnv1a(name) ^ splitmix64(key)) >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+// Stable across platforms and Rust versions, unlike std's hashers
+fn fnv1a(name: &str) -> u64 {
+    name.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
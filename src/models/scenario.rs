@@ -0,0 +1,335 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use super::validation::{check, ConfigBuilder, InvalidConfig};
+use super::SimulationConfig;
+
+// Keys that are still accepted but no longer do anything, with what to use
+// instead
+const DEPRECATED_KEYS: &[(&str, &str)] = &[(
+    "base_content_length",
+    "post lengths are sampled from content.post_lengths",
+)];
+
+// The key naming the scenarios and libraries a file builds on
+const EXTENDS: &str = "extends";
+
+#[derive(Debug)]
+pub enum ScenarioError {
+    Io(std::io::Error),
+    Parse(toml::de::Error),
+    UnknownKey(String),
+    // A file a scenario extends couldn't be loaded
+    Base(PathBuf, Box<ScenarioError>),
+    // A scenario that ends up extending itself
+    Cycle(PathBuf),
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioError::Io(e) => write!(f, "could not read scenario: {e}"),
+            ScenarioError::Parse(e) => write!(f, "invalid scenario: {e}"),
+            ScenarioError::UnknownKey(key) => write!(f, "unknown parameter `{key}`"),
+            ScenarioError::Base(path, e) => write!(f, "in {}: {e}", path.display()),
+            ScenarioError::Cycle(path) => {
+                write!(f, "{} extends itself", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {}
+
+// One parameter whose value differs between two configs, by dotted key
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigChange {
+    pub key: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+// Either one file or several, applied in order
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Extends {
+    One(PathBuf),
+    Many(Vec<PathBuf>),
+}
+
+// A config read from a scenario file. Keys the file leaves out take their
+// default values.
+//
+// A file can build on others with `extends = "base.toml"` or a list of paths,
+// relative to the file. Each is applied in turn and the file's own keys go
+// last, so later ones override earlier ones key by key. Lists are replaced
+// whole, except lists of named tables such as `personas`, which are merged by
+// name so a shared persona library can be extended or tweaked per scenario
+#[derive(Debug, Clone)]
+pub struct Scenario {
+    pub config: SimulationConfig,
+    pub warnings: Vec<String>,
+}
+
+impl Scenario {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ScenarioError> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path).map_err(ScenarioError::Io)?;
+        let dir = path.parent().unwrap_or(Path::new("."));
+        let chain = path.canonicalize().into_iter().collect();
+        Self::parse_from(&source, dir, chain)
+    }
+
+    // A scenario whose `extends` paths are relative to the working directory
+    pub fn parse(source: &str) -> Result<Self, ScenarioError> {
+        Self::parse_in(source, Path::new("."))
+    }
+
+    pub fn parse_in(source: &str, dir: &Path) -> Result<Self, ScenarioError> {
+        Self::parse_from(source, dir, Vec::new())
+    }
+
+    fn parse_from(
+        source: &str,
+        dir: &Path,
+        mut chain: Vec<PathBuf>,
+    ) -> Result<Self, ScenarioError> {
+        let mut table: toml::Table = toml::from_str(source).map_err(ScenarioError::Parse)?;
+
+        // Parsing the source directly gives errors with line numbers, which
+        // are lost once files have been merged
+        let config: SimulationConfig = if table.contains_key(EXTENDS) {
+            table = extend(table, dir, &mut chain)?;
+            toml::Value::Table(table.clone())
+                .try_into()
+                .map_err(ScenarioError::Parse)?
+        } else {
+            toml::from_str(source).map_err(ScenarioError::Parse)?
+        };
+        let value = toml::Value::Table(table);
+
+        // Serde drops keys it doesn't recognise, so anything missing from the
+        // parsed config was not understood
+        let known = flatten(&config_value(&config));
+        let mut warnings = Vec::new();
+
+        for key in flatten(&value).keys() {
+            if let Some((_, hint)) = DEPRECATED_KEYS
+                .iter()
+                .find(|(deprecated, _)| deprecated == key)
+            {
+                warnings.push(format!("`{key}` is deprecated: {hint}"));
+            } else if !known.contains_key(key) {
+                warnings.push(format!("unknown key `{key}` is ignored"));
+            }
+        }
+
+        Ok(Self { config, warnings })
+    }
+
+    // `config` as a scenario file, leaving out deprecated keys so reading it
+    // back doesn't warn about them
+    pub fn to_toml(config: &SimulationConfig) -> Result<String, toml::ser::Error> {
+        let mut table = toml::Table::try_from(config)?;
+        for (deprecated, _) in DEPRECATED_KEYS {
+            table.remove(*deprecated);
+        }
+        toml::to_string(&table)
+    }
+
+    // Everything in the config that parses but can't be what was meant
+    pub fn validate(&self) -> Vec<String> {
+        validate(&self.config)
+    }
+
+    // The scenario's config, if it has no problems
+    pub fn into_config(self) -> Result<SimulationConfig, InvalidConfig> {
+        ConfigBuilder::from(self.config).build()
+    }
+
+    // Parameters this scenario changes relative to `base`
+    pub fn diff(&self, base: &SimulationConfig) -> Vec<ConfigChange> {
+        diff_configs(base, &self.config)
+    }
+}
+
+pub fn diff_configs(from: &SimulationConfig, to: &SimulationConfig) -> Vec<ConfigChange> {
+    let from = flatten(&config_value(from));
+    let to = flatten(&config_value(to));
+
+    let mut keys: Vec<&String> = from.keys().chain(to.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    keys.into_iter()
+        .filter(|key| from.get(*key) != to.get(*key))
+        .map(|key| ConfigChange {
+            key: key.clone(),
+            from: from.get(key).map(display_value),
+            to: to.get(key).map(display_value),
+        })
+        .collect()
+}
+
+// `config` with the parameter at a dotted key set to a TOML value, e.g.
+// ("ranking_temperature", "0.5") or ("ranking_mode", "\"Sampled\"")
+pub fn with_parameter(
+    config: &SimulationConfig,
+    key: &str,
+    value: &str,
+) -> Result<SimulationConfig, ScenarioError> {
+    let mut root = config_value(config);
+    let value: toml::Value = toml::from_str::<toml::Table>(&format!("value = {value}"))
+        .map_err(ScenarioError::Parse)?
+        .remove("value")
+        .expect("parsed table has the key it was given");
+
+    let mut target = &mut root;
+    for part in key.split('.') {
+        target = target
+            .get_mut(part)
+            .ok_or_else(|| ScenarioError::UnknownKey(key.to_string()))?;
+    }
+    *target = value;
+
+    root.try_into().map_err(ScenarioError::Parse)
+}
+
+// Each of `validation::check`'s problems as a sentence naming the offending
+// key
+pub fn validate(config: &SimulationConfig) -> Vec<String> {
+    check(config).iter().map(ToString::to_string).collect()
+}
+
+// Loads a scenario file with everything it extends merged in. `chain` holds
+// the files being loaded above this one, to catch cycles
+fn resolve(path: &Path, chain: &mut Vec<PathBuf>) -> Result<toml::Table, ScenarioError> {
+    let base_error = |e| ScenarioError::Base(path.to_path_buf(), Box::new(e));
+
+    let canonical = path
+        .canonicalize()
+        .map_err(|e| base_error(ScenarioError::Io(e)))?;
+    if chain.contains(&canonical) {
+        return Err(ScenarioError::Cycle(path.to_path_buf()));
+    }
+
+    let source = std::fs::read_to_string(path).map_err(|e| base_error(ScenarioError::Io(e)))?;
+    let table: toml::Table =
+        toml::from_str(&source).map_err(|e| base_error(ScenarioError::Parse(e)))?;
+
+    chain.push(canonical);
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let resolved = extend(table, dir, chain);
+    chain.pop();
+    resolved
+}
+
+// `table` laid over the files its `extends` key names, in order
+fn extend(
+    mut table: toml::Table,
+    dir: &Path,
+    chain: &mut Vec<PathBuf>,
+) -> Result<toml::Table, ScenarioError> {
+    let Some(extends) = table.remove(EXTENDS) else {
+        return Ok(table);
+    };
+    let paths = match extends.try_into().map_err(ScenarioError::Parse)? {
+        Extends::One(path) => vec![path],
+        Extends::Many(paths) => paths,
+    };
+
+    let mut merged = toml::Table::new();
+    for path in paths {
+        merge(&mut merged, resolve(&dir.join(path), chain)?);
+    }
+    merge(&mut merged, table);
+    Ok(merged)
+}
+
+fn merge(base: &mut toml::Table, over: toml::Table) {
+    for (key, value) in over {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(over)) => merge(base, over),
+            (Some(toml::Value::Array(base)), toml::Value::Array(over))
+                if all_named(base) && all_named(&over) =>
+            {
+                merge_named(base, over)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn name(value: &toml::Value) -> Option<&str> {
+    value.get("name").and_then(toml::Value::as_str)
+}
+
+fn all_named(array: &[toml::Value]) -> bool {
+    !array.is_empty() && array.iter().all(|value| name(value).is_some())
+}
+
+// Entries whose name is already in `base` are merged into it; the rest are
+// added after
+fn merge_named(base: &mut Vec<toml::Value>, over: Vec<toml::Value>) {
+    for value in over {
+        let existing = base
+            .iter_mut()
+            .find(|existing| name(existing) == name(&value));
+        match (existing, value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(over)) => merge(existing, over),
+            (_, value) => base.push(value),
+        }
+    }
+}
+
+fn config_value(config: &SimulationConfig) -> toml::Value {
+    toml::Value::try_from(config).expect("config always serialises to TOML")
+}
+
+// Config floats are f32, so print them as such rather than with the noise of
+// their f64 widening
+fn display_value(value: &toml::Value) -> String {
+    match value {
+        toml::Value::Float(float) => (*float as f32).to_string(),
+        toml::Value::String(string) => string.clone(),
+        toml::Value::Array(array) => format!(
+            "[{}]",
+            array
+                .iter()
+                .map(display_value)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        other => other.to_string(),
+    }
+}
+
+// Tables become dotted keys; arrays and scalars are compared whole
+fn flatten(value: &toml::Value) -> BTreeMap<String, toml::Value> {
+    let mut flat = BTreeMap::new();
+    flatten_into(value, String::new(), &mut flat);
+    flat
+}
+
+fn flatten_into(value: &toml::Value, prefix: String, flat: &mut BTreeMap<String, toml::Value>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten_into(value, key, flat);
+            }
+        }
+        _ => {
+            flat.insert(prefix, value.clone());
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::{AgentId, CascadeAction, RngStreams, Simulation};
+
+// Language or market segments agents are split between. Posts belong to their
+// creator's segment, and feeds only carry the `leakage` share of posts from
+// other segments
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SegmentConfig {
+    // Each segment's share of the population, normalised over their total.
+    // Empty for one shared market
+    pub fractions: BTreeMap<String, f32>,
+    // Share of another segment's posts open to each agent's feed: 0 keeps
+    // segments apart, 1 ignores them
+    pub leakage: f32,
+}
+
+impl Default for SegmentConfig {
+    fn default() -> Self {
+        Self {
+            fractions: BTreeMap::new(),
+            leakage: 0.05,
+        }
+    }
+}
+
+impl SegmentConfig {
+    // The segment an agent belongs to, drawn by the fractions. The same agent
+    // always lands in the same segment for a seed
+    pub fn assign(&self, seed: u64, id: AgentId) -> Option<String> {
+        let total: f32 = self.fractions.values().sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut roll = RngStreams::keyed(seed, "segment", id.0 as u64).gen::<f32>() * total;
+        for (name, fraction) in &self.fractions {
+            if roll < *fraction {
+                return Some(name.clone());
+            }
+            roll -= fraction;
+        }
+        self.fractions.keys().last().cloned()
+    }
+}
+
+// How one segment's agents see and read posts, from inside and outside it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SegmentStats {
+    pub agents: usize,
+    pub posts: usize,
+    // Exposures of the segment's agents, and those to posts from elsewhere
+    pub exposures: usize,
+    pub cross_exposures: usize,
+    // Reads by the segment's agents, keyed by the segment the post came from
+    pub reads_from: BTreeMap<String, usize>,
+}
+
+impl SegmentStats {
+    // Stats for every segment with agents or posts in it, by name
+    pub fn of(simulation: &Simulation) -> BTreeMap<String, SegmentStats> {
+        let mut stats: BTreeMap<String, SegmentStats> = BTreeMap::new();
+        let mut segments = BTreeMap::new();
+        for agent in simulation.agent_views() {
+            if let Some(segment) = agent.segment {
+                segments.insert(agent.id, segment);
+                stats.entry(segment.to_string()).or_default().agents += 1;
+            }
+        }
+
+        for post in &simulation.engine.content_pool {
+            let Some(origin) = &post.segment else {
+                continue;
+            };
+            stats.entry(origin.clone()).or_default().posts += 1;
+            for event in &post.cascade {
+                let Some(&segment) = segments.get(&event.agent_id) else {
+                    continue;
+                };
+                let stats = stats.entry(segment.to_string()).or_default();
+                match event.action {
+                    CascadeAction::Exposed => {
+                        stats.exposures += 1;
+                        if segment != origin {
+                            stats.cross_exposures += 1;
+                        }
+                    }
+                    CascadeAction::Read => {
+                        *stats.reads_from.entry(origin.clone()).or_default() += 1;
+                    }
+                    CascadeAction::Commented(_)
+                    | CascadeAction::Liked
+                    | CascadeAction::Reshared(_) => {}
+                }
+            }
+        }
+
+        stats
+    }
+
+    pub fn cross_exposure_share(&self) -> Option<f32> {
+        (self.exposures > 0).then(|| self.cross_exposures as f32 / self.exposures as f32)
+    }
+}
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::{Post, PostId};
+
+// How often individuals like or reshare the posts they read. Each chance is
+// scaled by how much of the post was read, and both are 0 by default
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SharingConfig {
+    pub like_chance: f32,
+    pub reshare_chance: f32,
+}
+
+impl Default for SharingConfig {
+    fn default() -> Self {
+        Self {
+            like_chance: 0.0,
+            reshare_chance: 0.0,
+        }
+    }
+}
+
+impl SharingConfig {
+    pub fn enabled(&self) -> bool {
+        self.like_chance > 0.0 || self.reshare_chance > 0.0
+    }
+}
+
+// The reshares spreading out from a post, a level for each step down the
+// tree: the post's own reshares, then theirs, and so on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReshareTree {
+    pub post_id: PostId,
+    pub levels: Vec<Vec<PostId>>,
+}
+
+impl ReshareTree {
+    pub fn of(post: &Post, pool: &[Post]) -> Self {
+        let by_id: BTreeMap<PostId, &Post> = pool.iter().map(|post| (post.id, post)).collect();
+        Self::walk(post, &by_id)
+    }
+
+    // The tree of every original post in the pool that was reshared at all
+    pub fn all(pool: &[Post]) -> Vec<ReshareTree> {
+        let by_id: BTreeMap<PostId, &Post> = pool.iter().map(|post| (post.id, post)).collect();
+        pool.iter()
+            .filter(|post| post.original.is_none() && !post.reshares.is_empty())
+            .map(|post| Self::walk(post, &by_id))
+            .collect()
+    }
+
+    fn walk(post: &Post, by_id: &BTreeMap<PostId, &Post>) -> Self {
+        let mut levels: Vec<Vec<PostId>> = Vec::new();
+        let mut level = post.reshares.clone();
+        while !level.is_empty() {
+            let next = level
+                .iter()
+                .filter_map(|id| by_id.get(id))
+                .flat_map(|reshare| reshare.reshares.iter().copied())
+                .collect();
+            levels.push(std::mem::replace(&mut level, next));
+        }
+        Self {
+            post_id: post.id,
+            levels,
+        }
+    }
+
+    // How many reshares the longest chain runs to
+    pub fn depth(&self) -> usize {
+        self.levels.len()
+    }
+
+    // The most reshares at any one level
+    pub fn breadth(&self) -> usize {
+        self.levels.iter().map(Vec::len).max().unwrap_or(0)
+    }
+
+    pub fn size(&self) -> usize {
+        self.levels.iter().map(Vec::len).sum()
+    }
+}
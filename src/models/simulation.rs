@@ -1,29 +1,229 @@
-use crate::{models::AgentType, RecommendationEngine};
+use crate::{
+    engine::{
+        offline_eval::ExposureLog, Backfill, EngineError, EngineOp, ExposureQuotas, LockReason,
+        OpQueue, QueuedOp, RankingMode, RetrievalConfig,
+    },
+    models::AgentType,
+    profiling::{span, MemoryProfile, Subsystem},
+    RecommendationEngine,
+};
 use chrono::{DateTime, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 
-use super::{Agent, Bot, Individual, Organisation};
+use super::{
+    assign_personas, load_agents, save_agents, AdoptionConfig, AdoptionSample, AdoptionSeries,
+    AdsConfig, Agent, AgentId, AgentView, BackgroundAnalytics, Bot, Bridges, ChurnedEdge, Cohort,
+    CommentLockConfig, Communities, ContentCorpus, ContentKind, ContentLabels, CreatorAnalytics,
+    Diagnostics, DriftConfig, EconomyConfig, FairnessConfig, FairnessSample, FairnessSeries,
+    FollowSnapshot, FollowerHistory, GraphGenerator, GroundTruthConfig, IdAllocator, Individual,
+    InterestHistory, InterestProfile, Intervention, InterventionLog, Marketplace,
+    MarketplaceConfig, NudgeConfig, NudgeStats, Organisation, Pending, Persona, PolicyKind, Post,
+    PostId, RankingWeight, ReadTimeModel, RevenueSample, RevenueSeries, RngStreams,
+    ScheduledIntervention, SegmentConfig, SharingConfig, SocialGraph, Survey, SurveyConfig,
+    TagMatrix, Tallies, TickContext, Topic, TransparencyConfig, TransparencyReport,
+    TransparencyReports, WealthSample, WealthSeries, WellBeingConfig, WellBeingSeries,
+};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SimulationConfig {
+    // Master seed every random stream in the simulation is derived from
+    pub seed: u64,
     pub num_individuals: usize,
     pub num_bots: usize,
     pub num_organisations: usize,
     pub max_post_length: i32,
     pub max_comment_length: i32,
+    // How many ticks reading a post or comment takes
+    pub read_time: ReadTimeModel,
     pub bot_creation_ticks: i32,
     pub sample_tags: Vec<String>,
     pub starting_tags: StartingTags,
+
+    // Archetypes individuals are seeded from, and the share of the population
+    // drawn from each by name. Whatever share is left over starts with fully
+    // random profiles
+    pub personas: Vec<Persona>,
+    pub persona_fractions: BTreeMap<String, f32>,
+
+    // Demographic groups with their own daily rhythms and tastes, and the
+    // share of individuals in each by name. Empty fractions for none
+    pub cohorts: Vec<Cohort>,
+    pub cohort_fractions: BTreeMap<String, f32>,
+
+    // Steps in a simulated day, for cohorts' active hours
+    pub steps_per_day: u64,
     pub base_content_length: i32,
+    // Not read by the engine yet, which has no diversity term in its scores.
+    // Kept so configs and scenarios that set it still load
     pub diversity_weight: f32,
     pub recency_weight: f32,
     pub engagement_weight: f32,
     pub tick_rate_ms: i32,
+    // Steps taken each time the tick rate comes round
+    pub speed_multiplier: u32,
     pub interest_decay_rate: f32,
     pub min_content_tags: usize,
     pub max_content_tags: usize,
+    pub content: ContentCorpus,
+
+    // How strongly interest in one tag implies interest in another, used for
+    // content vectors and interest gain
+    pub tag_similarity: TagMatrix,
+
+    // How often, in steps, each agent's interests are snapshotted, and how
+    // many snapshots are kept per agent
+    pub interest_snapshot_interval: u64,
+    pub max_interest_snapshots: usize,
+
+    // How many recent policy decisions each individual keeps
+    pub decision_log_length: usize,
+    // How many recent feeds each individual keeps, as served and with what
+    // was picked from them
+    pub feed_archive_length: usize,
+
+    // How often, in steps, the simulation checks itself for degenerate states
+    pub health_check_interval: u64,
+
+    // How often, in steps, memory use is reported to the performance panel
+    pub memory_report_interval: u64,
+
+    // How many steps of served scores go into each score snapshot
+    pub score_snapshot_interval: u64,
+
+    // The follow graph at tick zero
+    pub initial_graph: GraphGenerator,
+
+    // Reads in a row of a followed creator's posts that put an agent off
+    // before it unfollows, 0 to never unfollow. A read puts it off when the
+    // post aligns with its interests less than the interest threshold, or its
+    // agreement on a shared tag differs by more than the conflict threshold
+    pub unfollow_strikes: u32,
+    pub unfollow_interest_threshold: f32,
+    pub unfollow_conflict_threshold: f32,
+
+    // Steps between community detection runs, 0 to never run it
+    pub community_detection_interval: u64,
+    // Runs community detection and the wealth sample on another thread,
+    // applying each result when next due instead of straight away, so they
+    // don't hold up the step
+    pub background_analytics: bool,
+
+    // Makes individuals' choices between states. Switch it mid-run with
+    // `Simulation::set_policy`, so agents already running pick it up
+    pub policy: PolicyKind,
+
+    // Betweenness an agent needs, on top of reaching more than one
+    // community, to count as a bridge between communities
+    pub bridge_min_betweenness: f32,
+
+    // Share of its weight every follow loses per tick, and the weight below
+    // which it's dropped
+    pub follow_decay_rate: f32,
+    pub min_follow_weight: f32,
+
+    // A panel survey of individuals' self-reported interests and satisfaction
+    pub survey: SurveyConfig,
+
+    // How many served feeds to keep with their full candidate sets, for
+    // evaluating other rankers offline. 0 turns logging off
+    pub exposure_log_capacity: usize,
+
+    // Posts scoring below this are only recommended when too few score above
+    // it, and are then picked according to the backfill
+    pub min_recommendation_score: f32,
+    pub recommendation_backfill: Backfill,
+
+    // Most posts one feed may show from a single creator or on a single tag,
+    // 0 for no limit
+    pub max_feed_posts_per_creator: usize,
+    pub max_feed_posts_per_tag: usize,
+
+    // Whether feeds are the top scoring posts or sampled from their scores,
+    // and how flat the sampling is
+    pub ranking_mode: RankingMode,
+    pub ranking_temperature: f32,
+
+    // While a breaking post's window is open its recency score is multiplied
+    // by this, and this many slots at the top of every feed are kept for
+    // breaking posts the agent hasn't seen
+    pub breaking_recency_boost: f32,
+    pub breaking_feed_slots: usize,
+
+    // Chance that an individual uses its feed settings, and the interest
+    // share past which it asks to see fewer posts on a tag
+    pub feed_control_usage: f32,
+    pub feed_control_threshold: f32,
+
+    // How much the engine scales scores of posts on a tag the agent asked to
+    // see fewer of, and adds to posts by creators it follows when it asked
+    // for them first
+    pub fewer_tag_penalty: f32,
+    pub followed_creator_boost: f32,
+
+    // Added to the score of every post by a creator the agent follows, 0 for
+    // a purely algorithmic feed
+    pub following_weight: f32,
+
+    // Which sources supply the posts that get ranked for a feed
+    pub retrieval: RetrievalConfig,
+
+    // How steeply agents favour posts near the top of their feed over ones
+    // further down, regardless of relevance. 0 turns position bias off
+    pub position_bias: f32,
+
+    // How strongly agents favour appealing posts over others equally relevant
+    // to them. 0 means appeal doesn't draw anyone in
+    pub appeal_effect: f32,
+
+    // The engine only sees reads and exposures in batches every this many
+    // steps, to model ranking signals lagging behind what users do
+    pub engagement_batch_interval: u64,
+
+    // How the hidden ground truth of generated posts is drawn
+    pub ground_truth: GroundTruthConfig,
+
+    // How individuals' well-being responds to the quality of what they read
+    pub wellbeing: WellBeingConfig,
+
+    // Prompts shown to individuals to add friction or suggest a break
+    pub nudges: NudgeConfig,
+
+    // Ads in feeds and the revenue they earn
+    pub ads: AdsConfig,
+
+    // Tips and subscriptions from followers to the creators they like most
+    pub economy: EconomyConfig,
+
+    // Individuals joining and leaving the platform as the people they know do
+    pub adoption: AdoptionConfig,
+
+    // Language or market segments that keep feeds mostly to their own posts
+    pub segments: SegmentConfig,
+
+    // Rate limits and locks that close posts to new comments
+    pub comment_locking: CommentLockConfig,
+
+    // Organisations bidding for sponsored slots in feeds
+    pub marketplace: MarketplaceConfig,
+
+    // Periodic reports on what the engine amplified
+    pub transparency: TransparencyConfig,
+
+    // Exposure quotas or parity across agent types or communities
+    pub fairness: FairnessConfig,
+
+    // Likes and reshares of the posts individuals read
+    pub sharing: SharingConfig,
+
+    // Organisations shifting their topics and agreement towards what their
+    // audience responds to
+    pub drift: DriftConfig,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StartingTags {
     pub individual: usize,
     pub bot: usize,
@@ -33,11 +233,14 @@ pub struct StartingTags {
 impl Default for SimulationConfig {
     fn default() -> Self {
         SimulationConfig {
+            // Kept within i64 so it survives being written out as TOML
+            seed: rand::random::<u64>() >> 1,
             num_individuals: 3,
             num_bots: 2,
             num_organisations: 2,
             max_post_length: 60,
             max_comment_length: 10,
+            read_time: ReadTimeModel::Linear,
             bot_creation_ticks: 4,
             sample_tags: vec![
                 "politics".to_string(),
@@ -54,76 +257,258 @@ impl Default for SimulationConfig {
                 bot: 3,
                 organisation: 3,
             },
+            personas: Persona::library(),
+            persona_fractions: BTreeMap::from([
+                ("sports fan".to_string(), 0.25),
+                ("political junkie".to_string(), 0.2),
+                ("casual lurker".to_string(), 0.35),
+                ("tech professional".to_string(), 0.2),
+            ]),
+            cohorts: Cohort::library(),
+            cohort_fractions: BTreeMap::new(),
+            steps_per_day: 240,
             base_content_length: 20,
             diversity_weight: 0.2,
             recency_weight: 0.2,
             engagement_weight: 0.2,
             tick_rate_ms: 100,
+            speed_multiplier: 1,
             interest_decay_rate: 0.0,
             min_content_tags: 1,
             max_content_tags: 3,
+            content: ContentCorpus::default(),
+            tag_similarity: TagMatrix::new()
+                .with_pair("science", "technology", 0.5)
+                .with_pair("science", "health", 0.4)
+                .with_pair("science", "education", 0.3)
+                .with_pair("technology", "business", 0.3)
+                .with_pair("politics", "business", 0.3)
+                .with_pair("politics", "education", 0.2)
+                .with_pair("health", "sports", 0.2)
+                .with_pair("sports", "entertainment", 0.3),
+            interest_snapshot_interval: 50,
+            max_interest_snapshots: 20,
+            decision_log_length: 20,
+            feed_archive_length: 50,
+            health_check_interval: 10,
+            memory_report_interval: 100,
+            score_snapshot_interval: 20,
+            initial_graph: GraphGenerator::Empty,
+            unfollow_strikes: 3,
+            unfollow_interest_threshold: 0.2,
+            unfollow_conflict_threshold: 1.0,
+            community_detection_interval: 50,
+            background_analytics: false,
+            policy: PolicyKind::default(),
+            bridge_min_betweenness: 0.05,
+            follow_decay_rate: 0.001,
+            min_follow_weight: 0.05,
+            survey: SurveyConfig::default(),
+            exposure_log_capacity: 0,
+            min_recommendation_score: 0.05,
+            recommendation_backfill: Backfill::Trending,
+            max_feed_posts_per_creator: 0,
+            max_feed_posts_per_tag: 0,
+            ranking_mode: RankingMode::TopK,
+            ranking_temperature: 0.1,
+            breaking_recency_boost: 5.0,
+            breaking_feed_slots: 1,
+            feed_control_usage: 0.2,
+            feed_control_threshold: 0.5,
+            fewer_tag_penalty: 0.5,
+            followed_creator_boost: 0.2,
+            following_weight: 0.0,
+            retrieval: RetrievalConfig::default(),
+            position_bias: 1.0,
+            appeal_effect: 0.5,
+            engagement_batch_interval: 1,
+            ground_truth: GroundTruthConfig::default(),
+            wellbeing: WellBeingConfig::default(),
+            nudges: NudgeConfig::default(),
+            ads: AdsConfig::default(),
+            economy: EconomyConfig::default(),
+            adoption: AdoptionConfig::default(),
+            segments: SegmentConfig::default(),
+            comment_locking: CommentLockConfig::default(),
+            marketplace: MarketplaceConfig::default(),
+            transparency: TransparencyConfig::default(),
+            fairness: FairnessConfig::default(),
+            sharing: SharingConfig::default(),
+            drift: DriftConfig::default(),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Simulation {
     pub config: SimulationConfig,
     pub engine: RecommendationEngine,
+    #[serde(serialize_with = "save_agents", deserialize_with = "load_agents")]
     pub agents: Vec<Box<dyn Agent>>,
+    pub ids: IdAllocator,
+    pub op_queue: OpQueue,
+    pub step_count: u64,
+    pub interest_history: BTreeMap<AgentId, InterestHistory>,
+    pub follower_history: BTreeMap<AgentId, FollowerHistory>,
+    // Ground truth for every post, which the engine never sees
+    pub labels: ContentLabels,
+    // Individuals' well-being, screen time and late-session use sampled over
+    // the run
+    pub wellbeing: WellBeingSeries,
+    // Platform ad revenue sampled over the run
+    pub revenue: RevenueSeries,
+    // How creators' earnings are spread, sampled over the run
+    pub wealth: WealthSeries,
+    // How many individuals are on the platform, sampled over the run
+    pub adoption: AdoptionSeries,
+    // Organisations' bids for sponsored slots, and what they bought
+    pub marketplace: Marketplace,
+    // Transparency reports published so far
+    pub transparency: TransparencyReports,
+    // Realised exposure shares of the fairness groups, sampled over the run
+    pub fairness: FairnessSeries,
+    pub interventions: InterventionLog,
+    pub diagnostics: Diagnostics,
+    // Measured in this process, so not saved
+    #[serde(skip)]
+    pub memory: MemoryProfile,
+    pub survey: Survey,
+    // The latest community detection run, if there has been one
+    pub communities: Option<Communities>,
+    // The bridges between communities found at each detection run
+    pub bridges: Vec<Bridges>,
+    // Agents that have stopped ticking, though they stay in the simulation
+    // and their posts stay in the pool
+    #[serde(default)]
+    pub frozen: BTreeSet<AgentId>,
+    // Analytics started on other threads and not yet applied
+    #[serde(skip)]
+    pub analytics: BackgroundAnalytics,
+    #[serde(skip, default = "Utc::now")]
     pub current_tick: DateTime<Utc>,
+    #[serde(skip, default = "Utc::now")]
     pub last_tick: DateTime<Utc>,
 }
 
+// What happened over a headless run of the simulation
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunSummary {
+    pub ticks: u64,
+    // The step the run finished on
+    pub step: u64,
+    pub posts_created: usize,
+    pub posts_by_individuals: usize,
+    pub posts_by_bots: usize,
+    pub posts_by_organisations: usize,
+    pub reads_completed: usize,
+    pub comments_created: usize,
+    // Agents whose interests or agreement on any tag they care about moved
+    pub interests_changed: usize,
+}
+
 impl Simulation {
     pub fn new(config: SimulationConfig) -> Self {
         let mut engine = RecommendationEngine::new();
         let mut agents: Vec<Box<dyn Agent>> = Vec::new();
-        let mut id_counter = 0;
-
-        let sample_tags = vec![
-            "politics",
-            "technology",
-            "science",
-            "entertainment",
-            "sports",
-            "health",
-            "education",
-            "business",
-        ];
-
-        for (i, tag) in sample_tags.iter().enumerate() {
-            engine.tag_to_index.insert(tag.to_string(), i);
-            engine.index_to_tag.insert(i, tag.to_string());
-        }
-
-        for _ in 0..config.num_individuals {
-            let agent = Individual::new(id_counter, &config, &engine);
+        let mut ids = IdAllocator::new();
+
+        engine.register_tags(&config.sample_tags);
+        configure_engine(&mut engine, &config);
+        engine.exposures = ExposureLog::new(config.exposure_log_capacity);
+
+        let mut persona_rng = RngStreams::stream(config.seed, "personas");
+        for persona in assign_personas(
+            &config.personas,
+            &config.persona_fractions,
+            config.num_individuals,
+        ) {
+            let id = ids.next_agent_id();
+            let agent = match persona {
+                Some(persona) => {
+                    Individual::from_persona(id, persona, &config, &engine, &mut persona_rng)
+                }
+                None => Individual::new(id, &config, &engine),
+            };
             agents.push(Box::new(agent));
-            id_counter += 1;
         }
 
         for _ in 0..config.num_bots {
-            let agent = Bot::new(id_counter, &config);
+            let id = ids.next_agent_id();
+            let agent = Bot::new(id, &mut ids, &config);
             agents.push(Box::new(agent));
-            id_counter += 1;
         }
 
         for _ in 0..config.num_organisations {
-            let agent = Organisation::new(id_counter, &config);
+            let id = ids.next_agent_id();
+            let agent = Organisation::new(id, &mut ids, &config);
             agents.push(Box::new(agent));
-            id_counter += 1;
+        }
+
+        // Initial follows, for agents that follow anyone
+        let mut graph_rng = RngStreams::stream(config.seed, "graph");
+        for (a, b) in config.initial_graph.generate(agents.len(), &mut graph_rng) {
+            let (id_a, id_b) = (*agents[a].id(), *agents[b].id());
+            if let Some(follows) = agents[a].preferred_creators_mut() {
+                follows.insert(id_b, 1.0);
+            }
+            if let Some(follows) = agents[b].preferred_creators_mut() {
+                follows.insert(id_a, 1.0);
+            }
+        }
+
+        // Everyone else waits to be drawn in by the people they know
+        for agent in agents.iter_mut().filter(|agent| agent.member().is_some()) {
+            let mut rng = RngStreams::keyed(config.seed, "membership", agent.id().0 as u64);
+            if rng.gen::<f32>() >= config.adoption.initial_members {
+                agent.set_member(false);
+            }
         }
 
         let now = Utc::now();
 
-        Simulation {
+        let mut simulation = Simulation {
             config,
             engine,
             agents,
+            ids,
+            op_queue: OpQueue::default(),
+            step_count: 0,
+            interest_history: BTreeMap::new(),
+            follower_history: BTreeMap::new(),
+            labels: ContentLabels::default(),
+            wellbeing: WellBeingSeries::default(),
+            revenue: RevenueSeries::default(),
+            wealth: WealthSeries::default(),
+            adoption: AdoptionSeries::default(),
+            marketplace: Marketplace::default(),
+            transparency: TransparencyReports::default(),
+            fairness: FairnessSeries::default(),
+            interventions: InterventionLog::default(),
+            diagnostics: Diagnostics::default(),
+            memory: MemoryProfile::default(),
+            survey: Survey::default(),
+            communities: None,
+            bridges: Vec::new(),
+            frozen: BTreeSet::new(),
+            analytics: BackgroundAnalytics::default(),
             current_tick: now,
             last_tick: now,
+        };
+        simulation.record_interests();
+        simulation.record_followers();
+        if simulation.config.wellbeing.interval > 0 {
+            simulation.record_wellbeing();
+        }
+        if simulation.config.ads.interval > 0 {
+            simulation.record_revenue();
+        }
+        if simulation.config.economy.interval > 0 {
+            simulation.record_wealth();
+        }
+        if simulation.config.adoption.interval > 0 {
+            simulation.record_adoption();
         }
+        simulation
     }
 
     pub fn tick(&mut self) {
@@ -132,20 +517,911 @@ impl Simulation {
 
         if elapsed >= self.config.tick_rate_ms as i64 {
             self.last_tick = self.current_tick;
+            for _ in 0..self.config.speed_multiplier.max(1) {
+                self.step();
+            }
+        }
+    }
+
+    // Steps `ticks` times without waiting on the tick rate, and sums up what
+    // the agents did meanwhile. Needs no UI, so tests and tools can drive the
+    // whole loop
+    pub fn run(&mut self, ticks: u64) -> RunSummary {
+        let interests_before: Vec<_> = self
+            .agents
+            .iter()
+            .map(|agent| interests_of(&**agent))
+            .collect();
+        let posts_before = self.engine.content_pool.len();
+        let (reads_before, comments_before) = self.reads_and_comments();
+
+        for _ in 0..ticks {
+            self.step();
+        }
+
+        let types: BTreeMap<AgentId, AgentType> = self
+            .agents
+            .iter()
+            .map(|agent| (*agent.id(), agent.get_type()))
+            .collect();
+        let mut summary = RunSummary {
+            ticks,
+            step: self.step_count,
+            ..RunSummary::default()
+        };
+        for post in &self.engine.content_pool[posts_before..] {
+            summary.posts_created += 1;
+            match types.get(&post.creator_id) {
+                Some(AgentType::Individual) => summary.posts_by_individuals += 1,
+                Some(AgentType::Bot) => summary.posts_by_bots += 1,
+                Some(AgentType::Organisation) => summary.posts_by_organisations += 1,
+                // Posts injected on behalf of nobody
+                None => {}
+            }
+        }
+        let (reads, comments) = self.reads_and_comments();
+        summary.reads_completed = reads - reads_before;
+        summary.comments_created = comments - comments_before;
+        // Agents who joined during the run are compared against nothing
+        summary.interests_changed = self
+            .agents
+            .iter()
+            .zip(&interests_before)
+            .filter(|(agent, before)| interests_of(&***agent) != **before)
+            .count();
+        summary
+    }
+
+    fn reads_and_comments(&self) -> (usize, usize) {
+        self.engine
+            .content_pool
+            .iter()
+            .fold((0, 0), |(reads, comments), post| {
+                (reads + post.readers.len(), comments + post.comments.len())
+            })
+    }
+
+    // Advances every agent by one tick, regardless of the configured tick rate
+    pub fn step(&mut self) {
+        let _step = span("step");
+        let mut ops = Vec::new();
+        self.engine.advance_to(self.step_count);
+
+        let mark = self.memory.mark();
+        let interventions = span(Subsystem::Interventions.name());
+        for scheduled in self.interventions.take_due(self.step_count) {
+            let created = self.apply_intervention(&scheduled.intervention);
+            self.interventions.applied.push(ScheduledIntervention {
+                step: self.step_count,
+                intervention: scheduled.intervention,
+                created,
+            });
+        }
+        drop(interventions);
+        self.memory.charge(Subsystem::Interventions, mark);
+
+        if self.config.adoption.enabled() {
+            let _adoption = span("adoption");
+            self.update_membership();
+        }
+
+        if self.config.marketplace.enabled() {
+            let _marketplace = span("marketplace");
+            self.run_auction();
+        } else {
+            self.engine.sponsored.clear();
+        }
+
+        // The quotas follow the config, with groups taken afresh each step as
+        // agents join and communities are detected
+        if self.config.fairness.enabled() {
+            self.engine.register_reranker(ExposureQuotas {
+                groups: self.config.fairness.groups(self),
+                targets: self.config.fairness.targets.clone(),
+                parity: self.config.fairness.parity,
+            });
+        } else {
+            self.engine.remove_reranker(ExposureQuotas::NAME);
+        }
+
+        // Engagement is held back until the last step of its batch
+        let interval = self.config.engagement_batch_interval.max(1);
+        let batch_end = (self.step_count / interval + 1) * interval - 1;
+
+        // Every agent reads the engine as it was at the start of the step, and
+        // all of their changes are applied once everyone has ticked. Each
+        // draws from streams keyed by its ID too, so the order agents tick
+        // in, or an agent joining or leaving, doesn't change what the others
+        // see
+
+        // Tips are paid once everyone has ticked, as the creators are agents
+        // too
+        let economy = &self.config.economy;
+        let mut tip_rng = RngStreams::stream(self.config.seed ^ self.step_count, "tips");
+        let mut tips: BTreeMap<AgentId, usize> = BTreeMap::new();
+
+        let mark = self.memory.mark();
+        let agents = span(Subsystem::Agents.name());
+        for agent in self
+            .agents
+            .iter_mut()
+            .filter(|agent| !self.frozen.contains(agent.id()))
+        {
+            let _agent = span(agent.get_type().name());
+            let agent_id = *agent.id();
+            agent.tick(&mut TickContext {
+                engine: &self.engine,
+                ops: &mut ops,
+                ids: &mut self.ids,
+                config: &self.config,
+                rng: &mut RngStreams::agent(self.config.seed ^ self.step_count, agent_id.0 as u64),
+            });
+
+            for op in ops.drain(..) {
+                // Read posts were all published before this step, so their
+                // ground truth is already known
+                if let EngineOp::RecordPostRead { post_id, .. } = &op {
+                    if let (Some(truth), Some(wellbeing)) =
+                        (self.labels.get(*post_id), agent.wellbeing_mut())
+                    {
+                        wellbeing.read(truth.quality, &self.config.wellbeing);
+                    }
+
+                    let creator_id = self
+                        .engine
+                        .get_content_by_id(*post_id)
+                        .map(|post| post.creator_id);
+                    let follow_weight = creator_id.and_then(|creator_id| {
+                        agent.preferred_creators()?.get(&creator_id).copied()
+                    });
+                    if let (Some(creator_id), Some(follow_weight)) = (creator_id, follow_weight) {
+                        if economy.tip_chance > 0.0
+                            && economy.affine(follow_weight)
+                            && tip_rng.gen::<f32>() < economy.tip_chance
+                        {
+                            *tips.entry(creator_id).or_default() += 1;
+                        }
+                    }
+                }
+                let step = if op.is_engagement() {
+                    batch_end
+                } else {
+                    self.step_count
+                };
+                self.op_queue.push(step, agent_id, op);
+            }
+        }
+        for agent in self.agents.iter_mut() {
+            let count = tips.get(agent.id()).copied().unwrap_or(0);
+            if let Some(earnings) = agent.earnings_mut() {
+                for _ in 0..count {
+                    earnings.tip(self.config.economy.tip_amount);
+                }
+            }
+        }
+        drop(agents);
+        self.memory.charge(Subsystem::Agents, mark);
+
+        // A failed op only affects the agent that queued it, so the rest of
+        // the step carries on
+        let mark = self.memory.mark();
+        let engine_ops = span(Subsystem::EngineOps.name());
+        let (mut sponsored, mut organic) = (0, 0);
+        for QueuedOp { agent_id, op, .. } in self.op_queue.take_due(self.step_count) {
+            match op {
+                EngineOp::RecordSponsoredExposure { .. } => sponsored += 1,
+                EngineOp::RecordExposure {
+                    promoted: false, ..
+                } => organic += 1,
+                _ => {}
+            }
+            match self.engine.apply_op(op) {
+                // Turned-away comments are counted by the engine, and are
+                // how locks and rate limits are meant to work
+                Ok(()) | Err(EngineError::CommentsLocked(_) | EngineError::RateLimited(_)) => {}
+                Err(e) => log::warn!("Step {}: agent {}: {}", self.step_count, agent_id, e),
+            }
+        }
+        if self.config.marketplace.enabled() {
+            self.marketplace.settle(sponsored, organic);
+        }
+        self.label_posts();
+        drop(engine_ops);
+        self.memory.charge(Subsystem::EngineOps, mark);
 
-            for agent in self.agents.iter_mut() {
-                agent.tick(&mut self.engine, &self.config);
+        self.step_count += 1;
+        self.engine.metrics.record(
+            self.step_count,
+            &self.engine.content_pool,
+            self.agents
+                .iter()
+                .map(|agent| AgentView::new(agent.as_ref())),
+        );
+        if self.step_count % self.config.interest_snapshot_interval.max(1) == 0 {
+            let mark = self.memory.mark();
+            let _history = span(Subsystem::History.name());
+            self.record_interests();
+            self.record_followers();
+            self.memory.charge(Subsystem::History, mark);
+        }
+        if self.step_count % self.config.health_check_interval.max(1) == 0 {
+            let mark = self.memory.mark();
+            let _diagnostics = span(Subsystem::Diagnostics.name());
+            self.diagnostics
+                .update(self.step_count, &self.agents, &self.engine);
+            self.memory.charge(Subsystem::Diagnostics, mark);
+        }
+        let interval = self.config.wellbeing.interval;
+        if interval > 0 && self.step_count % interval == 0 {
+            self.record_wellbeing();
+        }
+        let interval = self.config.ads.interval;
+        if interval > 0 && self.step_count % interval == 0 {
+            self.record_revenue();
+        }
+        let interval = self.config.economy.interval;
+        if interval > 0 && self.step_count % interval == 0 {
+            self.settle_earnings();
+            if self.config.background_analytics {
+                self.record_wealth_in_background();
+            } else {
+                self.apply_pending_wealth();
+                self.record_wealth();
             }
         }
+        let interval = self.config.adoption.interval;
+        if interval > 0 && self.step_count % interval == 0 {
+            self.record_adoption();
+        }
+        let interval = self.config.transparency.interval;
+        if interval > 0 && self.step_count % interval == 0 {
+            self.publish_transparency_report();
+        }
+        let interval = self.config.fairness.interval;
+        if interval > 0 && self.step_count % interval == 0 {
+            self.record_fairness();
+        }
+        if self.step_count % self.config.score_snapshot_interval.max(1) == 0 {
+            self.engine.telemetry.snapshot(self.step_count);
+        }
+        if self.step_count % self.config.memory_report_interval.max(1) == 0 {
+            self.memory.report(self.step_count, self.retained());
+        }
+        let interval = self.config.community_detection_interval;
+        if interval > 0 && self.step_count % interval == 0 {
+            let _communities = span("communities");
+            if self.config.background_analytics {
+                self.detect_communities_in_background();
+            } else {
+                self.apply_pending_communities();
+                self.detect_communities();
+            }
+        }
+        let survey = &self.config.survey;
+        if survey.interval > 0 && self.step_count % survey.interval == 0 {
+            let _survey = span("survey");
+            let mut rng = RngStreams::stream(self.config.seed ^ self.step_count, "survey");
+            self.survey.conduct(
+                self.step_count,
+                &self.agents,
+                &self.engine,
+                survey,
+                &mut rng,
+            );
+        }
+    }
+
+    // Groups agents by who follows whom, labelling each agent with its
+    // community, and finds the agents bridging those communities
+    pub fn detect_communities(&mut self) {
+        let mut rng = RngStreams::stream(self.config.seed ^ self.step_count, "communities");
+        let snapshot = FollowSnapshot::of(&self.agents, &self.engine.content_pool);
+        let communities = Communities::detect_in(self.step_count, &snapshot, &mut rng);
+        let bridges = Bridges::find_in(
+            self.step_count,
+            &snapshot,
+            &communities,
+            self.config.bridge_min_betweenness,
+        );
+        self.apply_communities(communities, bridges);
+    }
+
+    fn apply_communities(&mut self, communities: Communities, bridges: Bridges) {
+        for agent in &mut self.agents {
+            let label = communities.label(*agent.id());
+            agent.set_community(label);
+        }
+        self.bridges.push(bridges);
+        self.communities = Some(communities);
+    }
+
+    // Applies the communities detected last time, then snapshots the follow
+    // graph and detects them again on another thread
+    fn detect_communities_in_background(&mut self) {
+        self.apply_pending_communities();
+
+        let step = self.step_count;
+        let min_betweenness = self.config.bridge_min_betweenness;
+        let mut rng = RngStreams::stream(self.config.seed ^ step, "communities");
+        let snapshot = FollowSnapshot::of(&self.agents, &self.engine.content_pool);
+        self.analytics.communities = Some(Pending::spawn(move || {
+            let communities = Communities::detect_in(step, &snapshot, &mut rng);
+            let bridges = Bridges::find_in(step, &snapshot, &communities, min_betweenness);
+            (communities, bridges)
+        }));
+    }
+
+    fn record_wealth_in_background(&mut self) {
+        self.apply_pending_wealth();
+
+        let step = self.step_count;
+        let earnings = self.creator_earnings();
+        self.analytics.wealth = Some(Pending::spawn(move || WealthSample::of(step, &earnings)));
+    }
+
+    // Waits for any analytics still running in the background and applies
+    // them, such as before reading results at the end of a run
+    pub fn flush_analytics(&mut self) {
+        self.apply_pending_communities();
+        self.apply_pending_wealth();
+    }
+
+    fn apply_pending_communities(&mut self) {
+        if let Some(pending) = self.analytics.communities.take() {
+            let (communities, bridges) = pending.wait();
+            self.apply_communities(communities, bridges);
+        }
+    }
+
+    fn apply_pending_wealth(&mut self) {
+        if let Some(pending) = self.analytics.wealth.take() {
+            self.wealth.record(pending.wait());
+        }
+    }
+
+    // Sizes of everything that grows as the run goes on, which is where leaks
+    // in long runs come from
+    pub fn retained(&self) -> BTreeMap<&'static str, usize> {
+        let pool = &self.engine.content_pool;
+
+        BTreeMap::from([
+            ("posts", pool.len()),
+            ("tick metric points", self.engine.metrics.len()),
+            (
+                "comments",
+                pool.iter().map(|post| post.comments.len()).sum(),
+            ),
+            (
+                "post readers",
+                pool.iter().map(|post| post.readers.len()).sum(),
+            ),
+            (
+                "cascade events",
+                pool.iter().map(|post| post.cascade.len()).sum(),
+            ),
+            (
+                "viewed content",
+                self.agents
+                    .iter()
+                    .map(|agent| agent.viewed_content().len())
+                    .sum(),
+            ),
+            (
+                "decision records",
+                self.agents
+                    .iter()
+                    .filter_map(|agent| agent.decisions())
+                    .map(|decisions| decisions.records.len())
+                    .sum(),
+            ),
+            (
+                "interest snapshots",
+                self.interest_history
+                    .values()
+                    .map(|history| history.snapshots.len())
+                    .sum(),
+            ),
+            (
+                "follower snapshots",
+                self.follower_history
+                    .values()
+                    .map(|history| history.snapshots.len())
+                    .sum(),
+            ),
+            ("queued ops", self.op_queue.len()),
+            ("applied interventions", self.interventions.applied.len()),
+            ("survey waves", self.survey.waves.len()),
+            ("bridge snapshots", self.bridges.len()),
+            (
+                "churned follows",
+                self.agents
+                    .iter()
+                    .map(|agent| agent.churned_edges().len())
+                    .sum(),
+            ),
+            ("logged slates", self.engine.exposures.len()),
+            ("labelled posts", self.labels.len()),
+            ("wellbeing samples", self.wellbeing.len()),
+            ("revenue samples", self.revenue.len()),
+            ("auction rounds", self.marketplace.len()),
+            ("transparency reports", self.transparency.reports.len()),
+            ("fairness samples", self.fairness.len()),
+            ("wealth samples", self.wealth.len()),
+            ("adoption samples", self.adoption.len()),
+        ])
+    }
+
+    // Snapshots every agent's current interests into its history
+    fn record_interests(&mut self) {
+        for agent in &self.agents {
+            self.interest_history
+                .entry(*agent.id())
+                .or_default()
+                .record(
+                    self.step_count,
+                    agent.interest_profile(),
+                    self.config.max_interest_snapshots,
+                );
+        }
+    }
+
+    // Draws ground truth for posts published since the last step
+    fn label_posts(&mut self) {
+        let agents = &self.agents;
+        self.labels.update(
+            &self.engine.content_pool,
+            |creator_id| {
+                agents
+                    .iter()
+                    .find(|agent| *agent.id() == creator_id)
+                    .map(|agent| agent.get_type())
+            },
+            &self.config.ground_truth,
+            self.config.seed,
+        );
+    }
+
+    // Samples the population's well-being into its series
+    fn record_wellbeing(&mut self) {
+        let agents = self
+            .agents
+            .iter()
+            .map(|agent| AgentView::new(agent.as_ref()));
+        self.wellbeing.record(self.step_count, agents);
+    }
+
+    // Samples the platform's ad revenue so far into its series
+    fn record_revenue(&mut self) {
+        let sample = RevenueSample::of(self.step_count, self.agent_views());
+        self.revenue.record(sample);
+    }
+
+    // Charges every affine follower its subscriptions for the interval just
+    // ended, and has creators take stock of what they earned over it
+    fn settle_earnings(&mut self) {
+        let economy = &self.config.economy;
+        let mut subscribers: BTreeMap<AgentId, usize> = BTreeMap::new();
+        for follows in self
+            .agents
+            .iter()
+            .filter_map(|agent| agent.preferred_creators())
+        {
+            for (&creator_id, &weight) in follows {
+                if economy.affine(weight) {
+                    *subscribers.entry(creator_id).or_default() += 1;
+                }
+            }
+        }
+
+        for agent in self.agents.iter_mut() {
+            let count = subscribers.get(agent.id()).copied().unwrap_or(0);
+            if let Some(earnings) = agent.earnings_mut() {
+                earnings.settle(count, economy.subscription_fee, economy.interval);
+            }
+        }
+    }
+
+    // Has individuals join or leave the platform, more likely to join the
+    // more of their ties are on it and to leave the fewer are. Everyone
+    // decides on membership as it was at the start of the step
+    fn update_membership(&mut self) {
+        let members: BTreeMap<AgentId, bool> = self
+            .agents
+            .iter()
+            .filter_map(|agent| Some((*agent.id(), agent.member()?)))
+            .collect();
+        let mut ties: BTreeMap<AgentId, BTreeSet<AgentId>> = BTreeMap::new();
+        for (follower, followed) in self.follow_edges() {
+            if members.contains_key(&follower) && members.contains_key(&followed) {
+                ties.entry(follower).or_default().insert(followed);
+                ties.entry(followed).or_default().insert(follower);
+            }
+        }
+
+        let adoption = &self.config.adoption;
+        let mut rng = RngStreams::stream(self.config.seed ^ self.step_count, "adoption");
+        for agent in self.agents.iter_mut() {
+            let Some(member) = agent.member() else {
+                continue;
+            };
+            let active_ties = ties.get(agent.id()).map(|ties| {
+                ties.iter().filter(|tie| members[*tie]).count() as f32 / ties.len() as f32
+            });
+            let roll = rng.gen::<f32>();
+            if member && roll < adoption.leave_chance(active_ties) {
+                agent.set_member(false);
+                self.adoption.left += 1;
+            } else if !member && roll < adoption.join_chance(active_ties) {
+                agent.set_member(true);
+                self.adoption.joined += 1;
+            }
+        }
+    }
+
+    // Samples how many individuals are on the platform into the adoption
+    // series
+    fn record_adoption(&mut self) {
+        let sample = AdoptionSample::of(self.step_count, self.agent_views(), &self.adoption);
+        self.adoption.record(sample);
+    }
+
+    // Samples each fairness group's share of exposures since the last sample
+    fn record_fairness(&mut self) {
+        let groups = self.config.fairness.groups(self);
+        let sample = FairnessSample::of(self, self.fairness.next_step(), &groups);
+        self.fairness.record(sample);
+    }
+
+    // Samples how creators' earnings are spread into the wealth series
+    fn record_wealth(&mut self) {
+        let sample = WealthSample::of(self.step_count, &self.creator_earnings());
+        self.wealth.record(sample);
+    }
+
+    // Snapshots how many followers every agent has into its follower history
+    fn record_followers(&mut self) {
+        let counts = self.follower_counts();
+        for agent in &self.agents {
+            self.follower_history
+                .entry(*agent.id())
+                .or_default()
+                .record(
+                    self.step_count,
+                    counts.get(agent.id()).copied().unwrap_or(0),
+                    self.config.max_interest_snapshots,
+                );
+        }
+    }
+
+    // A report on everything since the last one published, up to now
+    pub fn transparency_report(&self) -> TransparencyReport {
+        TransparencyReport::of(
+            self,
+            self.transparency.next_step(),
+            &self.transparency.tallies,
+            self.config.transparency.top_tags,
+        )
+    }
+
+    pub fn publish_transparency_report(&mut self) {
+        let report = self.transparency_report();
+        self.transparency.tallies = Tallies::of(self);
+        self.transparency.reports.push(report);
+    }
+
+    // Auctions this step's sponsored slots among organisations with a post
+    // to sponsor, each offering its newest, and hands the winners to the
+    // engine
+    fn run_auction(&mut self) {
+        let organisations: BTreeSet<AgentId> = self
+            .agents
+            .iter()
+            .filter(|agent| agent.get_type() == AgentType::Organisation)
+            .map(|agent| *agent.id())
+            .filter(|id| !self.engine.banned_creators.contains(id))
+            .collect();
+        let mut newest: BTreeMap<AgentId, PostId> = BTreeMap::new();
+        for post in self.engine.content_pool.iter().rev() {
+            if organisations.contains(&post.creator_id) {
+                newest.entry(post.creator_id).or_insert(post.id);
+            }
+        }
+        let offers: Vec<(AgentId, PostId)> = newest.into_iter().collect();
+
+        self.engine.sponsored = self.marketplace.auction(
+            self.step_count,
+            self.config.steps_per_day,
+            &self.config.marketplace,
+            &offers,
+        );
+    }
+
+    // Swaps in a new config mid-run. Settings only read when the simulation is
+    // built, like population sizes, keep their original effect
+    pub fn set_config(&mut self, config: SimulationConfig) {
+        configure_engine(&mut self.engine, &config);
+        let policy = (config.policy != self.config.policy).then_some(config.policy);
+        self.config = config;
+        if let Some(policy) = policy {
+            self.set_policy(policy);
+        }
+    }
+
+    // Hands every agent that makes choices a fresh built-in policy, replacing
+    // any custom one
+    pub fn set_policy(&mut self, policy: PolicyKind) {
+        self.config.policy = policy;
+        let built = policy.build();
+        for agent in &mut self.agents {
+            agent.set_policy(built.clone());
+        }
+    }
+
+    // Queues an intervention to be applied at the start of `step`, or of the
+    // next step if that has already passed
+    pub fn schedule_intervention(&mut self, step: u64, intervention: Intervention) {
+        self.interventions.schedule(step, intervention);
+    }
+
+    // Returns the agents the intervention added, if any
+    fn apply_intervention(&mut self, intervention: &Intervention) -> Vec<AgentId> {
+        match intervention {
+            Intervention::InjectPost {
+                creator_id,
+                tags,
+                length,
+                breaking_ticks,
+                labels,
+            } => {
+                let mut profile = InterestProfile::new(self.engine.vector_dimension);
+                for tag in tags {
+                    profile.interests.insert(
+                        tag.clone(),
+                        Topic {
+                            weighted_interest: 1.0,
+                            agreement: 0.0,
+                        },
+                    );
+                }
+                profile.normalise_weights();
+
+                let mut post =
+                    Post::new(self.ids.next_post_id(), *creator_id, profile, &self.config);
+                post.length = *length;
+                post.timestamp = self.engine.now();
+                post.published_step = self.step_count;
+                post.kind = ContentKind::Injected;
+                post.segment = self
+                    .agent_view(*creator_id)
+                    .and_then(|creator| creator.segment)
+                    .map(str::to_string);
+                if let Some(labels) = labels {
+                    self.labels.insert(post.id, labels.clone());
+                }
+                if *breaking_ticks > 0 {
+                    post.kind = ContentKind::Breaking;
+                    self.engine
+                        .mark_breaking(post.id, self.step_count + breaking_ticks);
+                }
+                self.engine.create_post(post);
+            }
+            Intervention::SetInterest {
+                agent_id,
+                tag,
+                weight,
+            } => {
+                if let Some(agent) = self.agents.iter_mut().find(|agent| agent.id() == agent_id) {
+                    let profile = agent.interest_profile_mut();
+                    profile
+                        .interests
+                        .entry(tag.clone())
+                        .or_insert(Topic {
+                            weighted_interest: 0.0,
+                            agreement: 0.0,
+                        })
+                        .weighted_interest = weight.max(0.0);
+                    profile.normalise_weights();
+                    self.engine.vectorise(profile);
+                }
+            }
+            Intervention::SetWeight { weight, value } => {
+                let config = &mut self.engine.config;
+                match weight {
+                    RankingWeight::Interest => config.interest_weight = *value,
+                    RankingWeight::Recency => config.recency_weight = *value,
+                    RankingWeight::Engagement => config.engagement_weight = *value,
+                }
+            }
+            Intervention::BanCreator(creator_id) => {
+                self.engine.banned_creators.insert(*creator_id);
+                if self.config.comment_locking.lock_banned {
+                    let posts: Vec<PostId> = self
+                        .engine
+                        .content_pool
+                        .iter()
+                        .filter(|post| post.creator_id == *creator_id)
+                        .map(|post| post.id)
+                        .collect();
+                    for post_id in posts {
+                        // Can't fail, as the posts were just found in the pool
+                        let _ = self.engine.lock_comments(post_id, LockReason::Moderation);
+                    }
+                }
+            }
+            Intervention::LockComments(post_id) => {
+                if let Err(e) = self.engine.lock_comments(*post_id, LockReason::Manual) {
+                    log::warn!("Step {}: can't lock comments: {}", self.step_count, e);
+                }
+            }
+            Intervention::UnlockComments(post_id) => {
+                self.engine.unlock_comments(*post_id);
+            }
+            Intervention::Promote {
+                post_id,
+                kind,
+                ticks,
+            } => {
+                if let Err(e) = self
+                    .engine
+                    .promote(*post_id, *kind, self.step_count + ticks)
+                {
+                    log::warn!("Step {}: can't promote: {}", self.step_count, e);
+                }
+            }
+            Intervention::SetNudge { nudge, compliance } => {
+                self.config.nudges.set_compliance(*nudge, *compliance);
+            }
+            Intervention::CloneAgent { agent_id, copies } => {
+                let Some(pos) = self.agents.iter().position(|agent| agent.id() == agent_id) else {
+                    log::warn!("Step {}: no agent {} to clone", self.step_count, agent_id);
+                    return Vec::new();
+                };
+                let mut created = Vec::with_capacity(*copies);
+                for _ in 0..*copies {
+                    let id = self.ids.next_agent_id();
+                    let clone = self.agents[pos].duplicate(id, &mut self.ids, &self.config);
+                    self.register_agent(clone);
+                    created.push(id);
+                }
+                return created;
+            }
+        }
+        Vec::new()
+    }
+
+    pub fn interest_history(&self, id: AgentId) -> Option<&InterestHistory> {
+        self.interest_history.get(&id)
+    }
+
+    pub fn agent_views(&self) -> impl Iterator<Item = AgentView<'_>> {
+        self.agents
+            .iter()
+            .map(|agent| AgentView::new(agent.as_ref()))
+    }
+
+    pub fn agent_view(&self, id: AgentId) -> Option<AgentView<'_>> {
+        self.agent_views().find(|view| view.id == id)
+    }
+
+    // Every agent of one type, e.g. for a creator quota
+    pub fn agents_of(&self, agent_type: AgentType) -> BTreeSet<AgentId> {
+        self.agents
+            .iter()
+            .filter(|agent| agent.get_type() == agent_type)
+            .map(|agent| *agent.id())
+            .collect()
+    }
+
+    // Every follow dropped so far, in the order they were dropped
+    pub fn churned_edges(&self) -> Vec<ChurnedEdge> {
+        let mut churned: Vec<_> = self
+            .agents
+            .iter()
+            .flat_map(|agent| agent.churned_edges().iter().copied())
+            .collect();
+        churned.sort_by_key(|edge| (edge.step, edge.follower));
+        churned
+    }
+
+    // The follow graph as it stands
+    pub fn social_graph(&self) -> SocialGraph {
+        SocialGraph::of(&self.agents)
+    }
+
+    // Every (follower, followed) pair, sorted
+    pub fn follow_edges(&self) -> Vec<(AgentId, AgentId)> {
+        self.social_graph().edges().collect()
+    }
+
+    // How many agents follow each agent with any followers
+    pub fn follower_counts(&self) -> BTreeMap<AgentId, usize> {
+        self.social_graph().follower_counts()
+    }
+
+    // Has the follower follow the creator at full weight, as the starting
+    // graph does. False if the follower can't follow anyone
+    pub fn follow(&mut self, follower: AgentId, followed: AgentId) -> bool {
+        let follows = self
+            .agents
+            .iter_mut()
+            .find(|agent| *agent.id() == follower)
+            .and_then(|agent| agent.preferred_creators_mut());
+        match follows {
+            Some(follows) => {
+                follows.insert(followed, 1.0);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // False if the follower wasn't following the creator
+    pub fn unfollow(&mut self, follower: AgentId, followed: AgentId) -> bool {
+        self.agents
+            .iter_mut()
+            .find(|agent| *agent.id() == follower)
+            .and_then(|agent| agent.preferred_creators_mut())
+            .is_some_and(|follows| follows.remove(&followed).is_some())
+    }
+
+    pub fn follower_count(&self, id: AgentId) -> usize {
+        self.agents
+            .iter()
+            .filter_map(|agent| agent.preferred_creators())
+            .filter(|follows| follows.contains_key(&id))
+            .count()
+    }
+
+    pub fn creator_analytics(&self, id: AgentId) -> CreatorAnalytics {
+        CreatorAnalytics::of(self, id)
+    }
+
+    // Everything each creator has earned so far, in agent order
+    pub fn creator_earnings(&self) -> Vec<f32> {
+        self.agent_views()
+            .filter_map(|agent| agent.earnings.map(|earnings| earnings.total()))
+            .collect()
+    }
+
+    // Nudges shown and complied with across every agent
+    pub fn nudge_stats(&self) -> NudgeStats {
+        let mut stats = NudgeStats::default();
+        for nudges in self.agent_views().filter_map(|agent| agent.nudges) {
+            stats.add(nudges);
+        }
+        stats
+    }
+
+    pub fn count_agents(&self, agent_type: AgentType) -> usize {
+        self.agent_views()
+            .filter(|view| view.agent_type == agent_type)
+            .count()
     }
 
     pub fn add_agent(&mut self, agent_type: AgentType) {
-        let id = self.agents.len();
+        let id = self.ids.next_agent_id();
         let new_agent: Box<dyn Agent> = match agent_type {
             AgentType::Individual => Box::new(Individual::new(id, &self.config, &self.engine)),
-            AgentType::Bot => Box::new(Bot::new(id, &self.config)),
-            AgentType::Organisation => Box::new(Organisation::new(id, &self.config)),
+            AgentType::Bot => Box::new(Bot::new(id, &mut self.ids, &self.config)),
+            AgentType::Organisation => Box::new(Organisation::new(id, &mut self.ids, &self.config)),
         };
+        self.register_agent(new_agent);
+    }
+
+    // Starts the histories of an agent new to the run and adds it
+    fn register_agent(&mut self, new_agent: Box<dyn Agent>) {
+        let id = *new_agent.id();
+        self.interest_history.entry(id).or_default().record(
+            self.step_count,
+            new_agent.interest_profile(),
+            self.config.max_interest_snapshots,
+        );
+        let followers = self.follower_count(id);
+        self.follower_history.entry(id).or_default().record(
+            self.step_count,
+            followers,
+            self.config.max_interest_snapshots,
+        );
         self.agents.push(new_agent);
     }
 
@@ -155,10 +1431,32 @@ impl Simulation {
             .iter()
             .rposition(|agent| agent.get_type() == agent_type)
         {
-            self.agents.remove(pos);
+            let agent = self.agents.remove(pos);
+            self.interest_history.remove(agent.id());
+            self.follower_history.remove(agent.id());
+            self.frozen.remove(agent.id());
         }
     }
 
+    // Stops the agent ticking, for isolating what particular actors do
+    // mid-run. It keeps whatever state it was in, and what it already
+    // published or queued still plays out. False if there is no such agent
+    pub fn freeze(&mut self, id: AgentId) -> bool {
+        let exists = self.agents.iter().any(|agent| *agent.id() == id);
+        if exists {
+            self.frozen.insert(id);
+        }
+        exists
+    }
+
+    pub fn unfreeze(&mut self, id: AgentId) {
+        self.frozen.remove(&id);
+    }
+
+    pub fn is_frozen(&self, id: AgentId) -> bool {
+        self.frozen.contains(&id)
+    }
+
     pub fn print_statistics(&self) {
         println!("\nTick {:?}", self.current_tick);
         println!("Content pool size: {}", self.engine.content_pool.len());
@@ -190,25 +1488,56 @@ impl Simulation {
 
         // Print some example agent states
         for agent in &self.agents {
-            match agent.get_type() {
-                AgentType::Individual => {
-                    println!("\nIndividual State:");
-                    println!("\nState: {:?}", agent.state());
-                    println!("Interests: {:?}", agent.interest_profile());
-                    println!(
-                        "Preferred creators: {:?}",
-                        agent.preferred_creators().unwrap()
-                    );
-                } // AgentType::Organisation => {
-                //     println!("\nOrganisation State:");
-                //     println!("Interests: {:?}", agent.interests());
-                // }
-                // AgentType::Bot => {
-                //     println!("\nBot State:");
-                //     println!("Interests: {:?}", agent.interests());
-                // }
-                _ => (),
+            if agent.get_type() == AgentType::Individual {
+                println!("\nIndividual State:");
+                println!("\nState: {:?}", agent.state());
+                println!("Interests: {:?}", agent.interest_profile());
+                println!(
+                    "Preferred creators: {:?}",
+                    agent.preferred_creators().unwrap()
+                );
             }
         }
     }
 }
+
+// An agent's interest and agreement on each tag it has any interest in.
+// Reading can add tags at no weight, which doesn't count as a change
+fn interests_of(agent: &dyn Agent) -> Vec<(String, f32, f32)> {
+    agent
+        .interest_profile()
+        .interests
+        .iter()
+        .filter(|(_, topic)| topic.weighted_interest > 0.0)
+        .map(|(tag, topic)| (tag.clone(), topic.weighted_interest, topic.agreement))
+        .collect()
+}
+
+// Copies the settings the engine keeps its own copy of from the config
+fn configure_engine(engine: &mut RecommendationEngine, config: &SimulationConfig) {
+    engine.tag_similarity = config.tag_similarity.clone();
+    engine.seed = config.seed;
+    engine.config.recency_weight = config.recency_weight;
+    engine.config.engagement_weight = config.engagement_weight;
+    engine.config.min_score = config.min_recommendation_score;
+    engine.config.backfill = config.recommendation_backfill;
+    engine.config.max_per_creator = config.max_feed_posts_per_creator;
+    engine.config.max_per_tag = config.max_feed_posts_per_tag;
+    engine.config.ranking = config.ranking_mode;
+    engine.config.temperature = config.ranking_temperature;
+    engine.config.retrieval = config.retrieval.clone();
+    engine.config.breaking_boost = config.breaking_recency_boost;
+    engine.config.breaking_slots = config.breaking_feed_slots;
+    engine.config.fewer_tag_penalty = config.fewer_tag_penalty;
+    engine.config.followed_boost = config.followed_creator_boost;
+    engine.config.following_weight = config.following_weight;
+    engine.config.segment_leakage = if config.segments.fractions.is_empty() {
+        1.0
+    } else {
+        config.segments.leakage
+    };
+    engine.config.seconds_per_step = config.tick_rate_ms as f32 / 1000.0;
+    engine.config.comment_rate_limit = config.comment_locking.rate_limit;
+    engine.config.lock_conflict_threshold = config.comment_locking.conflict_threshold;
+    engine.config.sponsored_slots = config.marketplace.slots;
+}
@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+
+use super::{AgentView, Simulation};
+
+// Counts of values in equal-width bins over [min, max]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Histogram {
+    pub min: f32,
+    pub max: f32,
+    pub counts: Vec<usize>,
+}
+
+impl Histogram {
+    // Values outside the range are clamped into the end bins
+    pub fn new(values: &[f32], bins: usize, min: f32, max: f32) -> Self {
+        let bins = bins.max(1);
+        let mut counts = vec![0; bins];
+        let width = (max - min).max(f32::EPSILON);
+
+        for value in values.iter().filter(|value| value.is_finite()) {
+            let bin = (((value - min) / width) * bins as f32).floor() as isize;
+            counts[bin.clamp(0, bins as isize - 1) as usize] += 1;
+        }
+
+        Self { min, max, counts }
+    }
+
+    // Bins over the observed range of the values
+    pub fn fitted(values: &[f32], bins: usize) -> Self {
+        let finite = values.iter().copied().filter(|value| value.is_finite());
+        let min = finite.clone().fold(f32::INFINITY, f32::min);
+        let max = finite.fold(f32::NEG_INFINITY, f32::max);
+
+        if min > max {
+            return Self::new(&[], bins, 0.0, 1.0);
+        }
+
+        Self::new(values, bins, min, max.max(min + 1.0))
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    pub fn bin_range(&self, bin: usize) -> (f32, f32) {
+        let width = (self.max - self.min) / self.counts.len() as f32;
+        (
+            self.min + width * bin as f32,
+            self.min + width * (bin + 1) as f32,
+        )
+    }
+}
+
+// Per-agent quantities that can be summarised across the population
+#[derive(Debug, Clone, PartialEq)]
+pub enum PopulationMetric {
+    InterestEntropy,
+    Agreement(String),
+    SessionLength,
+    // Share of an agent's post selections attributed to feed position rather
+    // than relevance
+    PositionDriven,
+    WellBeing,
+    // Ticks spent online over the whole run, and the share of them spent past
+    // the late-session threshold
+    ScreenTime,
+    LateSessionShare,
+    // How worked up an individual is from clashing with others
+    Arousal,
+    // Everything a creator has been paid by its followers
+    Earnings,
+    // How varied an individual's archived feeds were, by tag and by creator,
+    // averaged over the feeds
+    FeedTagEntropy,
+    FeedCreatorDiversity,
+}
+
+impl PopulationMetric {
+    pub fn name(&self) -> String {
+        match self {
+            PopulationMetric::InterestEntropy => "Interest entropy (bits)".to_string(),
+            PopulationMetric::Agreement(tag) => format!("Agreement on {tag}"),
+            PopulationMetric::SessionLength => "Session length (ticks)".to_string(),
+            PopulationMetric::PositionDriven => "Position-driven selections".to_string(),
+            PopulationMetric::WellBeing => "Well-being".to_string(),
+            PopulationMetric::ScreenTime => "Screen time (ticks)".to_string(),
+            PopulationMetric::LateSessionShare => "Late-session share".to_string(),
+            PopulationMetric::Arousal => "Arousal".to_string(),
+            PopulationMetric::Earnings => "Creator earnings".to_string(),
+            PopulationMetric::FeedTagEntropy => "Feed tag entropy (bits)".to_string(),
+            PopulationMetric::FeedCreatorDiversity => "Feed creator diversity".to_string(),
+        }
+    }
+
+    // The value for a single agent, if the metric applies to it
+    pub fn value(&self, agent: &AgentView) -> Option<f32> {
+        match self {
+            PopulationMetric::InterestEntropy => Some(agent.interest_profile.entropy()),
+            PopulationMetric::Agreement(tag) => agent
+                .interest_profile
+                .interests
+                .get(tag)
+                .map(|topic| topic.agreement),
+            PopulationMetric::SessionLength => agent.session_length.map(|ticks| ticks as f32),
+            PopulationMetric::PositionDriven => agent
+                .selections
+                .and_then(|selections| selections.position_share()),
+            PopulationMetric::WellBeing => agent.wellbeing.map(|wellbeing| wellbeing.index),
+            PopulationMetric::ScreenTime => agent
+                .wellbeing
+                .map(|wellbeing| wellbeing.screen_time as f32),
+            PopulationMetric::LateSessionShare => agent
+                .wellbeing
+                .and_then(|wellbeing| wellbeing.late_session_share()),
+            PopulationMetric::Arousal => agent.wellbeing.map(|wellbeing| wellbeing.arousal),
+            PopulationMetric::Earnings => agent.earnings.map(|earnings| earnings.total()),
+            PopulationMetric::FeedTagEntropy => {
+                agent.feeds.and_then(|feeds| feeds.mean_tag_entropy())
+            }
+            PopulationMetric::FeedCreatorDiversity => {
+                agent.feeds.and_then(|feeds| feeds.mean_creator_diversity())
+            }
+        }
+    }
+
+    pub fn values(&self, simulation: &Simulation) -> Vec<f32> {
+        simulation
+            .agent_views()
+            .filter_map(|agent| self.value(&agent))
+            .collect()
+    }
+
+    // Mean over the agents `include` accepts, or None if none of them have a
+    // value
+    pub fn mean_where(
+        &self,
+        simulation: &Simulation,
+        include: impl Fn(&AgentView) -> bool,
+    ) -> Option<f32> {
+        let values: Vec<f32> = simulation
+            .agent_views()
+            .filter(|agent| include(agent))
+            .filter_map(|agent| self.value(&agent))
+            .collect();
+
+        if values.is_empty() {
+            None
+        } else {
+            Some(values.iter().sum::<f32>() / values.len() as f32)
+        }
+    }
+
+    // Bins over the metric's natural range where it has one
+    pub fn histogram(&self, simulation: &Simulation, bins: usize) -> Histogram {
+        let values = self.values(simulation);
+
+        match self {
+            PopulationMetric::InterestEntropy | PopulationMetric::FeedTagEntropy => {
+                let max = (simulation.config.sample_tags.len().max(2) as f32).log2();
+                Histogram::new(&values, bins, 0.0, max)
+            }
+            PopulationMetric::Agreement(_) => Histogram::new(&values, bins, -1.0, 1.0),
+            PopulationMetric::SessionLength
+            | PopulationMetric::ScreenTime
+            | PopulationMetric::Earnings => Histogram::fitted(&values, bins),
+            PopulationMetric::PositionDriven
+            | PopulationMetric::WellBeing
+            | PopulationMetric::LateSessionShare
+            | PopulationMetric::Arousal
+            | PopulationMetric::FeedCreatorDiversity => Histogram::new(&values, bins, 0.0, 1.0),
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+use super::generator::standard_normal;
+use super::{Agent, AgentId, AgentType};
+use crate::RecommendationEngine;
+
+// How many of an agent's most recently viewed posts its true satisfaction is
+// judged on
+const RECENT_POSTS: usize = 10;
+
+// How the virtual survey is run and how far answers stray from the truth
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SurveyConfig {
+    // Steps between waves, 0 to never survey
+    pub interval: u64,
+
+    // Individuals drawn into the panel at the first wave, 0 for all of them.
+    // The same panel is asked every wave
+    pub panel_size: usize,
+
+    // Chance a panel member skips a wave, plus an extra chance scaled by how
+    // dissatisfied they are, since unhappy users answer less
+    pub nonresponse: f32,
+    pub dissatisfied_nonresponse: f32,
+
+    // Standard deviation of the noise on every reported value
+    pub noise: f32,
+
+    // Added to reported agreement, for respondents' tendency to agree
+    pub acquiescence: f32,
+
+    // Added to reported satisfaction
+    pub satisfaction_bias: f32,
+}
+
+impl Default for SurveyConfig {
+    fn default() -> Self {
+        Self {
+            interval: 0,
+            panel_size: 0,
+            nonresponse: 0.1,
+            dissatisfied_nonresponse: 0.2,
+            noise: 0.05,
+            acquiescence: 0.1,
+            satisfaction_bias: 0.1,
+        }
+    }
+}
+
+// A value as a respondent reported it, next to what it actually was
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Reported {
+    pub reported: f32,
+    pub truth: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopicResponse {
+    pub tag: String,
+    pub interest: Reported,
+    pub agreement: Reported,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SurveyResponse {
+    pub agent_id: AgentId,
+    pub satisfaction: Reported,
+    pub topics: Vec<TopicResponse>,
+}
+
+// Everyone who answered at one step, and the panel members who didn't
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SurveyWave {
+    pub step: u64,
+    pub responses: Vec<SurveyResponse>,
+    pub nonrespondents: Vec<AgentId>,
+}
+
+impl SurveyWave {
+    pub fn response_rate(&self) -> f32 {
+        let asked = self.responses.len() + self.nonrespondents.len();
+        if asked == 0 {
+            return 0.0;
+        }
+        self.responses.len() as f32 / asked as f32
+    }
+}
+
+// A panel survey of individuals, for comparing what agents say about
+// themselves against what the simulation knows to be true
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Survey {
+    pub panel: Vec<AgentId>,
+    pub waves: Vec<SurveyWave>,
+}
+
+impl Survey {
+    // Asks the panel, drawing it first if this is the first wave
+    pub fn conduct<R: Rng + ?Sized>(
+        &mut self,
+        step: u64,
+        agents: &[Box<dyn Agent>],
+        engine: &RecommendationEngine,
+        config: &SurveyConfig,
+        rng: &mut R,
+    ) {
+        if self.panel.is_empty() {
+            let mut individuals: Vec<AgentId> = agents
+                .iter()
+                .filter(|agent| agent.get_type() == AgentType::Individual)
+                .map(|agent| *agent.id())
+                .collect();
+            individuals.shuffle(rng);
+            if config.panel_size > 0 {
+                individuals.truncate(config.panel_size);
+            }
+            individuals.sort();
+            self.panel = individuals;
+        }
+
+        let mut wave = SurveyWave {
+            step,
+            responses: Vec::new(),
+            nonrespondents: Vec::new(),
+        };
+
+        for agent_id in &self.panel {
+            // Panel members who have left the simulation can't answer
+            let Some(agent) = agents.iter().find(|agent| agent.id() == agent_id) else {
+                wave.nonrespondents.push(*agent_id);
+                continue;
+            };
+
+            let satisfaction = satisfaction(agent.as_ref(), engine);
+            let skip_chance =
+                config.nonresponse + config.dissatisfied_nonresponse * (1.0 - satisfaction);
+            if rng.gen::<f32>() < skip_chance {
+                wave.nonrespondents.push(*agent_id);
+                continue;
+            }
+
+            let mut report = |truth: f32, bias: f32, min: f32, max: f32| Reported {
+                reported: (truth + bias + config.noise * standard_normal(rng)).clamp(min, max),
+                truth,
+            };
+
+            let profile = agent.interest_profile();
+            let mut topics: Vec<TopicResponse> = profile
+                .normalised_weights()
+                .map(|(tag, weight)| TopicResponse {
+                    tag: tag.clone(),
+                    interest: Reported {
+                        reported: 0.0,
+                        truth: weight,
+                    },
+                    agreement: Reported {
+                        reported: 0.0,
+                        truth: profile.interests[tag].agreement,
+                    },
+                })
+                .collect();
+            topics.sort_by(|a, b| a.tag.cmp(&b.tag));
+            for topic in &mut topics {
+                topic.interest = report(topic.interest.truth, 0.0, 0.0, 1.0);
+                topic.agreement = report(topic.agreement.truth, config.acquiescence, -1.0, 1.0);
+            }
+
+            wave.responses.push(SurveyResponse {
+                agent_id: *agent_id,
+                satisfaction: report(satisfaction, config.satisfaction_bias, 0.0, 1.0),
+                topics,
+            });
+        }
+
+        self.waves.push(wave);
+    }
+
+    // One row per answer, in long panel format
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("step,agent_id,question,tag,reported,truth\n");
+
+        for wave in &self.waves {
+            for response in &wave.responses {
+                let mut row = |question: &str, tag: &str, value: &Reported| {
+                    writeln!(
+                        csv,
+                        "{},{},{},{},{},{}",
+                        wave.step, response.agent_id, question, tag, value.reported, value.truth
+                    )
+                    .unwrap();
+                };
+
+                row("satisfaction", "", &response.satisfaction);
+                for topic in &response.topics {
+                    row("interest", &topic.tag, &topic.interest);
+                    row("agreement", &topic.tag, &topic.agreement);
+                }
+            }
+        }
+
+        csv
+    }
+}
+
+// How well the agent's recent feed matched its interests, from 0 to 1. Agents
+// that haven't seen anything yet are neither happy nor unhappy
+fn satisfaction(agent: &dyn Agent, engine: &RecommendationEngine) -> f32 {
+    let viewed = agent.viewed_content();
+    let recent = &viewed[viewed.len().saturating_sub(RECENT_POSTS)..];
+
+    let similarities: Vec<f32> = recent
+        .iter()
+        .filter_map(|post_id| engine.get_content_by_id(*post_id))
+        .map(|post| {
+            engine.calculate_vector_similarity(
+                &agent.interest_profile().vector_representation,
+                &post.interest_profile.vector_representation,
+            )
+        })
+        .collect();
+
+    if similarities.is_empty() {
+        0.5
+    } else {
+        similarities.iter().sum::<f32>() / similarities.len() as f32
+    }
+}
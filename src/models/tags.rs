@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+// A symmetric tag-by-tag relation, e.g. how often two tags appear together.
+// Pairs not listed are 0.0, and a tag is never related to itself here
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TagMatrix {
+    pub pairs: Vec<(String, String, f32)>,
+}
+
+impl TagMatrix {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_pair(mut self, a: &str, b: &str, value: f32) -> Self {
+        self.set(a, b, value);
+        self
+    }
+
+    pub fn set(&mut self, a: &str, b: &str, value: f32) {
+        match self.position(a, b) {
+            Some(index) => self.pairs[index].2 = value,
+            None => self.pairs.push((a.to_string(), b.to_string(), value)),
+        }
+    }
+
+    pub fn get(&self, a: &str, b: &str) -> f32 {
+        self.position(a, b)
+            .map(|index| self.pairs[index].2)
+            .unwrap_or(0.0)
+    }
+
+    fn position(&self, a: &str, b: &str) -> Option<usize> {
+        self.pairs
+            .iter()
+            .position(|(x, y, _)| (x == a && y == b) || (x == b && y == a))
+    }
+}
@@ -0,0 +1,139 @@
+use std::fmt::Write;
+
+use super::{
+    with_parameter, AgentType, CascadeAction, LabelEvaluation, PopulationMetric, RevenueSample,
+    ScenarioError, Simulation, SimulationConfig,
+};
+
+// One run of a sweep: the value the swept parameter took and what the run
+// traded off
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeoffPoint {
+    pub value: f32,
+    // Reads and comments per individual per step
+    pub engagement: f32,
+    // Mean quality of the posts read, None if nothing was
+    pub quality: Option<f32>,
+    // Mean well-being of individuals at the end of the run
+    pub wellbeing: Option<f32>,
+    // Ad revenue per individual per step
+    pub revenue: f32,
+    // Whether no other point in the sweep matched it on both engagement and
+    // quality while beating it on one
+    pub pareto: bool,
+}
+
+impl TradeoffPoint {
+    pub fn of(simulation: &Simulation, value: f32) -> Self {
+        let interactions: usize = simulation
+            .engine
+            .content_pool
+            .iter()
+            .flat_map(|post| &post.cascade)
+            .filter(|event| event.action != CascadeAction::Exposed)
+            .count();
+        let individual_steps = (simulation.count_agents(AgentType::Individual) as u64
+            * simulation.step_count)
+            .max(1) as f32;
+        let revenue = RevenueSample::of(simulation.step_count, simulation.agent_views()).revenue;
+
+        Self {
+            value,
+            engagement: interactions as f32 / individual_steps,
+            quality: LabelEvaluation::of(simulation).read_quality,
+            wellbeing: PopulationMetric::WellBeing.mean_where(simulation, |_| true),
+            revenue: revenue / individual_steps,
+            pareto: false,
+        }
+    }
+
+    fn dominated_by(&self, other: &TradeoffPoint) -> bool {
+        let (Some(quality), Some(other_quality)) = (self.quality, other.quality) else {
+            return false;
+        };
+        other.engagement >= self.engagement
+            && other_quality >= quality
+            && (other.engagement > self.engagement || other_quality > quality)
+    }
+}
+
+// How engagement and the quality of what gets read move as one config
+// parameter, typically a ranking weight or the ad load, is swept. Revenue is
+// reported alongside, though the front is only over engagement and quality.
+// Every run starts from the base config's seed, so the points differ only in
+// that parameter
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeoffCurve {
+    pub key: String,
+    pub points: Vec<TradeoffPoint>,
+}
+
+impl TradeoffCurve {
+    pub fn sweep(
+        base: &SimulationConfig,
+        key: &str,
+        values: &[f32],
+        ticks: u64,
+    ) -> Result<Self, ScenarioError> {
+        let mut points = Vec::new();
+        for &value in values {
+            // Whole values are written as integers so counts such as
+            // `ads.per_feed` can be swept too
+            let mut simulation = Simulation::new(with_parameter(base, key, &format!("{value}"))?);
+            for _ in 0..ticks {
+                simulation.step();
+            }
+            points.push(TradeoffPoint::of(&simulation, value));
+        }
+
+        let mut curve = Self {
+            key: key.to_string(),
+            points,
+        };
+        curve.mark_pareto();
+        Ok(curve)
+    }
+
+    // Flags the points no other point dominates. Points where nothing was read
+    // have no quality to compare, so are never on the front
+    pub fn mark_pareto(&mut self) {
+        let dominated: Vec<bool> = self
+            .points
+            .iter()
+            .map(|point| {
+                point.quality.is_none() || self.points.iter().any(|other| point.dominated_by(other))
+            })
+            .collect();
+        for (point, dominated) in self.points.iter_mut().zip(dominated) {
+            point.pareto = !dominated;
+        }
+    }
+
+    // The Pareto front, in order of increasing engagement
+    pub fn front(&self) -> Vec<&TradeoffPoint> {
+        let mut front: Vec<_> = self.points.iter().filter(|point| point.pareto).collect();
+        front.sort_by(|a, b| a.engagement.total_cmp(&b.engagement));
+        front
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!("{},engagement,quality,wellbeing,revenue,pareto\n", self.key);
+        let optional = |value: Option<f32>| value.map(|v| v.to_string()).unwrap_or_default();
+
+        for point in &self.points {
+            writeln!(
+                csv,
+                "{},{},{},{},{},{}",
+                point.value,
+                point.engagement,
+                optional(point.quality),
+                optional(point.wellbeing),
+                point.revenue,
+                point.pareto
+            )
+            .unwrap();
+        }
+
+        csv
+    }
+}
@@ -0,0 +1,339 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use super::{AgentId, CascadeAction, Intervention, RevenueSample, Simulation, FEED_LENGTH};
+
+// How often the platform publishes a transparency report
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TransparencyConfig {
+    // Steps each report covers, 0 to never publish one
+    pub interval: u64,
+    // Tags listed in each report, most exposed first
+    pub top_tags: usize,
+}
+
+impl Default for TransparencyConfig {
+    fn default() -> Self {
+        Self {
+            interval: 0,
+            top_tags: 5,
+        }
+    }
+}
+
+// Running totals the engine and agents keep, so a report can count only what
+// happened in its own window
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Tallies {
+    pub ad_impressions: usize,
+    pub promoted_exposures: usize,
+    pub sponsored_exposures: usize,
+    pub comments_rejected: usize,
+}
+
+impl Tallies {
+    pub fn of(simulation: &Simulation) -> Self {
+        let engine = &simulation.engine;
+        Self {
+            ad_impressions: RevenueSample::of(simulation.step_count, simulation.agent_views())
+                .impressions,
+            promoted_exposures: engine
+                .promotions
+                .values()
+                .map(|promotion| promotion.exposures)
+                .sum(),
+            sponsored_exposures: engine.sponsored_exposures.values().sum(),
+            comments_rejected: engine.rejected_comments.values().sum(),
+        }
+    }
+
+    fn since(&self, earlier: &Tallies) -> Self {
+        Self {
+            ad_impressions: self.ad_impressions.saturating_sub(earlier.ad_impressions),
+            promoted_exposures: self
+                .promoted_exposures
+                .saturating_sub(earlier.promoted_exposures),
+            sponsored_exposures: self
+                .sponsored_exposures
+                .saturating_sub(earlier.sponsored_exposures),
+            comments_rejected: self
+                .comments_rejected
+                .saturating_sub(earlier.comments_rejected),
+        }
+    }
+}
+
+// Organic exposures that went to one tag or kind of creator
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Share {
+    pub name: String,
+    pub exposures: usize,
+    pub share: f32,
+}
+
+// What the engine amplified over a window of steps, and what the platform
+// did to shape it, set out the way real platforms' transparency reports are
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransparencyReport {
+    pub from_step: u64,
+    // Exclusive
+    pub to_step: u64,
+    // Posts shown in feeds on their merits, leaving out paid and promoted
+    // placements
+    pub organic_exposures: usize,
+    // A post's exposure counts once for each of its tags, so shares can add
+    // up to more than 1
+    pub top_tags: Vec<Share>,
+    pub creator_types: Vec<Share>,
+    // Counts from this window alone
+    pub activity: Tallies,
+    // Ads, sponsored and promoted placements as a share of everything shown
+    pub ad_load: Option<f32>,
+    pub ad_slots_per_feed: usize,
+    pub creators_banned: usize,
+    // Locks made in the window, by reason
+    pub comment_locks: BTreeMap<String, usize>,
+    // Moderation interventions applied in the window, described
+    pub moderation_actions: Vec<String>,
+}
+
+impl TransparencyReport {
+    // Covers from `from_step` up to the simulation's current step, counting
+    // running totals against `earlier`, the tallies as of `from_step`
+    pub fn of(simulation: &Simulation, from_step: u64, earlier: &Tallies, top_tags: usize) -> Self {
+        let to_step = simulation.step_count;
+        let window = from_step..to_step;
+        let types: BTreeMap<AgentId, &str> = simulation
+            .agent_views()
+            .map(|agent| (agent.id, agent.agent_type.name()))
+            .collect();
+
+        let mut organic_exposures = 0;
+        let mut tags: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut creators: BTreeMap<&str, usize> = BTreeMap::new();
+        for post in &simulation.engine.content_pool {
+            let exposures = post
+                .cascade
+                .iter()
+                .filter(|event| {
+                    window.contains(&event.step) && event.action == CascadeAction::Exposed
+                })
+                .count();
+            if exposures == 0 {
+                continue;
+            }
+            organic_exposures += exposures;
+            for tag in post.interest_profile.interests.keys() {
+                *tags.entry(tag).or_default() += exposures;
+            }
+            let creator_type = types.get(&post.creator_id).copied().unwrap_or("unknown");
+            *creators.entry(creator_type).or_default() += exposures;
+        }
+        let shares = |counts: BTreeMap<&str, usize>| {
+            let mut shares: Vec<Share> = counts
+                .into_iter()
+                .map(|(name, exposures)| Share {
+                    name: name.to_string(),
+                    exposures,
+                    share: exposures as f32 / organic_exposures as f32,
+                })
+                .collect();
+            shares.sort_by_key(|share| std::cmp::Reverse(share.exposures));
+            shares
+        };
+        let mut top = shares(tags);
+        top.truncate(top_tags);
+
+        let activity = Tallies::of(simulation).since(earlier);
+        let paid =
+            activity.ad_impressions + activity.sponsored_exposures + activity.promoted_exposures;
+        let shown = paid + organic_exposures;
+
+        let mut comment_locks = BTreeMap::new();
+        for lock in simulation.engine.comment_locks.values() {
+            if window.contains(&lock.step) {
+                *comment_locks
+                    .entry(lock.reason.name().to_string())
+                    .or_default() += 1;
+            }
+        }
+        let moderation_actions = simulation
+            .interventions
+            .applied
+            .iter()
+            .filter(|scheduled| window.contains(&scheduled.step))
+            .filter(|scheduled| {
+                matches!(
+                    scheduled.intervention,
+                    Intervention::BanCreator(_)
+                        | Intervention::LockComments(_)
+                        | Intervention::UnlockComments(_)
+                )
+            })
+            .map(|scheduled| {
+                format!(
+                    "Step {}: {}",
+                    scheduled.step,
+                    scheduled.intervention.describe()
+                )
+            })
+            .collect();
+
+        Self {
+            from_step,
+            to_step,
+            organic_exposures,
+            top_tags: top,
+            creator_types: shares(creators),
+            activity,
+            ad_load: (shown > 0).then(|| paid as f32 / shown as f32),
+            ad_slots_per_feed: simulation.config.ads.per_feed,
+            creators_banned: simulation.engine.banned_creators.len(),
+            comment_locks,
+            moderation_actions,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        let percent = |share: f32| format!("{:.1}%", share * 100.0);
+        let table = |markdown: &mut String, heading: &str, shares: &[Share]| {
+            if shares.is_empty() {
+                markdown.push_str("Nothing was shown in this window.\n\n");
+                return;
+            }
+            writeln!(markdown, "| {heading} | Exposures | Share |").unwrap();
+            markdown.push_str("|---|---:|---:|\n");
+            for share in shares {
+                writeln!(
+                    markdown,
+                    "| {} | {} | {} |",
+                    share.name,
+                    share.exposures,
+                    percent(share.share)
+                )
+                .unwrap();
+            }
+            markdown.push('\n');
+        };
+
+        writeln!(
+            markdown,
+            "# Transparency report: steps {} to {}\n",
+            self.from_step, self.to_step
+        )
+        .unwrap();
+        writeln!(
+            markdown,
+            "## What was amplified\n\n{} organic exposures.\n",
+            self.organic_exposures
+        )
+        .unwrap();
+        table(&mut markdown, "Tag", &self.top_tags);
+        markdown.push_str("## Exposure by creator type\n\n");
+        table(&mut markdown, "Creator type", &self.creator_types);
+
+        let activity = &self.activity;
+        markdown.push_str("## Ads and paid reach\n\n");
+        writeln!(
+            markdown,
+            "- Ad slots per feed: {} of {FEED_LENGTH}",
+            self.ad_slots_per_feed
+        )
+        .unwrap();
+        writeln!(markdown, "- Ad impressions: {}", activity.ad_impressions).unwrap();
+        writeln!(
+            markdown,
+            "- Sponsored exposures: {}",
+            activity.sponsored_exposures
+        )
+        .unwrap();
+        writeln!(
+            markdown,
+            "- Promoted exposures: {}",
+            activity.promoted_exposures
+        )
+        .unwrap();
+        writeln!(
+            markdown,
+            "- Ad load: {}\n",
+            self.ad_load.map_or("-".to_string(), percent)
+        )
+        .unwrap();
+
+        markdown.push_str("## Moderation\n\n");
+        writeln!(
+            markdown,
+            "- Creators banned to date: {}",
+            self.creators_banned
+        )
+        .unwrap();
+        let locks: Vec<String> = self
+            .comment_locks
+            .iter()
+            .map(|(reason, count)| format!("{count} {reason}"))
+            .collect();
+        writeln!(
+            markdown,
+            "- Posts locked to comments: {}",
+            if locks.is_empty() {
+                "none".to_string()
+            } else {
+                locks.join(", ")
+            }
+        )
+        .unwrap();
+        writeln!(
+            markdown,
+            "- Comments turned away: {}",
+            activity.comments_rejected
+        )
+        .unwrap();
+        if self.moderation_actions.is_empty() {
+            markdown.push_str("- No moderation actions taken\n");
+        } else {
+            markdown.push_str("- Actions taken:\n");
+            for action in &self.moderation_actions {
+                writeln!(markdown, "  - {action}").unwrap();
+            }
+        }
+
+        markdown
+    }
+}
+
+// Reports published over the run, with the tallies the next one counts from
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TransparencyReports {
+    pub reports: Vec<TransparencyReport>,
+    pub tallies: Tallies,
+}
+
+impl TransparencyReports {
+    // Where the next report starts
+    pub fn next_step(&self) -> u64 {
+        self.reports.last().map_or(0, |report| report.to_step)
+    }
+
+    pub fn latest(&self) -> Option<&TransparencyReport> {
+        self.reports.last()
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&self.reports)
+    }
+
+    pub fn to_markdown(&self) -> String {
+        self.reports
+            .iter()
+            .map(TransparencyReport::to_markdown)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
@@ -0,0 +1,107 @@
+use super::{
+    with_parameter, Intervention, PopulationMetric, ScenarioError, Simulation, SimulationConfig,
+};
+
+// The one thing the treated twin does differently
+#[derive(Debug, Clone, PartialEq)]
+pub enum Treatment {
+    // A config parameter by dotted key, set to a TOML value
+    Parameter {
+        key: String,
+        value: String,
+    },
+    // An intervention applied to the treated twin at `step`
+    Intervention {
+        step: u64,
+        intervention: Intervention,
+    },
+}
+
+// One metric in both twins at the same step
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PairedMetric {
+    pub metric: &'static str,
+    pub baseline: f32,
+    pub treated: f32,
+}
+
+impl PairedMetric {
+    // Treated minus baseline
+    pub fn difference(&self) -> f32 {
+        self.treated - self.baseline
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TwinStep {
+    pub step: u64,
+    pub metrics: Vec<PairedMetric>,
+}
+
+// Two simulations stepped in lockstep from one starting state, differing only
+// in the treatment, so that differences between them are down to the
+// treatment rather than to a different draw of agents or content
+#[derive(Debug, Clone)]
+pub struct TwinRun {
+    pub baseline: Simulation,
+    pub treated: Simulation,
+    pub treatment: Treatment,
+    pub steps: Vec<TwinStep>,
+}
+
+impl TwinRun {
+    pub fn new(config: SimulationConfig, treatment: Treatment) -> Result<Self, ScenarioError> {
+        let baseline = Simulation::new(config);
+        let mut treated = baseline.clone();
+
+        match &treatment {
+            Treatment::Parameter { key, value } => {
+                treated.set_config(with_parameter(&baseline.config, key, value)?);
+            }
+            Treatment::Intervention { step, intervention } => {
+                treated.schedule_intervention(*step, intervention.clone());
+            }
+        }
+
+        Ok(Self {
+            baseline,
+            treated,
+            treatment,
+            steps: Vec::new(),
+        })
+    }
+
+    // Advances both twins by one step and returns how they compare after it
+    pub fn step(&mut self) -> &TwinStep {
+        self.baseline.step();
+        self.treated.step();
+
+        let pair = |metric: &'static str, value: &dyn Fn(&Simulation) -> f32| PairedMetric {
+            metric,
+            baseline: value(&self.baseline),
+            treated: value(&self.treated),
+        };
+        let mean = |metric: PopulationMetric| {
+            move |simulation: &Simulation| metric.mean_where(simulation, |_| true).unwrap_or(0.0)
+        };
+
+        let metrics = vec![
+            pair("interest_entropy", &mean(PopulationMetric::InterestEntropy)),
+            pair("session_length", &mean(PopulationMetric::SessionLength)),
+            pair(
+                "position_driven_share",
+                &mean(PopulationMetric::PositionDriven),
+            ),
+            pair("wellbeing", &mean(PopulationMetric::WellBeing)),
+            pair("posts", &|simulation| {
+                simulation.engine.content_pool.len() as f32
+            }),
+        ];
+
+        self.steps.push(TwinStep {
+            step: self.baseline.step_count,
+            metrics,
+        });
+        self.steps.last().unwrap()
+    }
+}
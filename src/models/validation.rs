@@ -0,0 +1,643 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use super::{GraphGenerator, ReadTimeModel, SimulationConfig, FEED_LENGTH};
+use crate::engine::RankingMode;
+
+// The values a numeric parameter may take
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub min: f32,
+    // None when there's no upper limit
+    pub max: Option<f32>,
+}
+
+impl Bounds {
+    pub const FRACTION: Bounds = Bounds {
+        min: 0.0,
+        max: Some(1.0),
+    };
+    pub const AGREEMENT: Bounds = Bounds {
+        min: -1.0,
+        max: Some(1.0),
+    };
+    pub const NON_NEGATIVE: Bounds = Bounds::at_least(0.0);
+
+    pub const fn at_least(min: f32) -> Self {
+        Self { min, max: None }
+    }
+
+    pub const fn between(min: f32, max: f32) -> Self {
+        Self {
+            min,
+            max: Some(max),
+        }
+    }
+
+    // False for NaN, which no bound admits
+    pub fn contains(&self, value: f32) -> bool {
+        value >= self.min && self.max.map_or(true, |max| value <= max)
+    }
+
+    // What a slider over the parameter covers, stopping at `soft_max` where
+    // there's no upper limit
+    pub fn slider(&self, soft_max: f32) -> RangeInclusive<f32> {
+        self.min..=self.max.unwrap_or(soft_max)
+    }
+}
+
+impl fmt::Display for Bounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.max {
+            Some(max) => write!(f, "{} to {max}", self.min),
+            None if self.min == 0.0 => write!(f, "0 or more"),
+            None => write!(f, "at least {}", self.min),
+        }
+    }
+}
+
+// Ranges of the numeric parameters that have one, by dotted key. Validation
+// checks against these, and the UI draws its sliders over them
+pub const BOUNDS: &[(&str, Bounds)] = &[
+    ("diversity_weight", Bounds::FRACTION),
+    ("recency_weight", Bounds::FRACTION),
+    ("engagement_weight", Bounds::FRACTION),
+    ("feed_control_usage", Bounds::FRACTION),
+    ("feed_control_threshold", Bounds::FRACTION),
+    ("interest_decay_rate", Bounds::FRACTION),
+    ("follow_decay_rate", Bounds::FRACTION),
+    ("min_follow_weight", Bounds::FRACTION),
+    ("bridge_min_betweenness", Bounds::FRACTION),
+    ("fewer_tag_penalty", Bounds::NON_NEGATIVE),
+    ("following_weight", Bounds::NON_NEGATIVE),
+    ("position_bias", Bounds::NON_NEGATIVE),
+    ("appeal_effect", Bounds::NON_NEGATIVE),
+    ("steps_per_day", Bounds::at_least(1.0)),
+    ("speed_multiplier", Bounds::at_least(1.0)),
+    ("max_content_tags", Bounds::at_least(1.0)),
+    ("ground_truth.quality_appeal_correlation", Bounds::AGREEMENT),
+    ("wellbeing.initial", Bounds::FRACTION),
+    ("wellbeing.quality_rate", Bounds::FRACTION),
+    ("wellbeing.conflict_penalty", Bounds::FRACTION),
+    ("wellbeing.doomscroll_penalty", Bounds::FRACTION),
+    ("wellbeing.recovery_rate", Bounds::FRACTION),
+    ("wellbeing.arousal_gain", Bounds::FRACTION),
+    ("wellbeing.arousal_decay", Bounds::FRACTION),
+    ("nudges.read_before_share", Bounds::FRACTION),
+    ("nudges.caught_up", Bounds::FRACTION),
+    ("nudges.time_reminder", Bounds::FRACTION),
+    ("economy.tip_chance", Bounds::FRACTION),
+    ("economy.affinity", Bounds::NON_NEGATIVE),
+    ("economy.tip_amount", Bounds::NON_NEGATIVE),
+    ("economy.subscription_fee", Bounds::NON_NEGATIVE),
+    ("economy.incentive", Bounds::NON_NEGATIVE),
+    ("segments.leakage", Bounds::FRACTION),
+    ("adoption.initial_members", Bounds::FRACTION),
+    ("adoption.join_rate", Bounds::FRACTION),
+    ("adoption.social_join", Bounds::FRACTION),
+    ("adoption.leave_rate", Bounds::FRACTION),
+    ("survey.nonresponse", Bounds::FRACTION),
+    ("survey.dissatisfied_nonresponse", Bounds::FRACTION),
+    (
+        "comment_locking.conflict_threshold",
+        Bounds::between(0.0, 2.0),
+    ),
+    ("marketplace.daily_budget", Bounds::NON_NEGATIVE),
+    ("marketplace.reserve_price", Bounds::NON_NEGATIVE),
+    ("marketplace.adaptation", Bounds::FRACTION),
+    ("sharing.like_chance", Bounds::FRACTION),
+    ("sharing.reshare_chance", Bounds::FRACTION),
+    ("drift.responsiveness", Bounds::FRACTION),
+];
+
+pub fn bounds(key: &str) -> Option<Bounds> {
+    BOUNDS
+        .iter()
+        .find(|(known, _)| *known == key)
+        .map(|(_, bounds)| *bounds)
+}
+
+// What kind of thing a config refers to by name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reference {
+    Persona,
+    Cohort,
+    Tag,
+    AgentType,
+}
+
+impl Reference {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Reference::Persona => "persona",
+            Reference::Cohort => "cohort",
+            Reference::Tag => "tag",
+            Reference::AgentType => "agent type",
+        }
+    }
+}
+
+// A value that parses but can't be what was meant
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    OutOfRange {
+        key: String,
+        value: f32,
+        bounds: Bounds,
+    },
+    // Parameters that are each in range but don't work together, and why
+    Inconsistent {
+        keys: Vec<String>,
+        problem: String,
+    },
+    // A name for a persona, cohort or tag the config doesn't define
+    Unknown {
+        key: String,
+        reference: Reference,
+        name: String,
+    },
+}
+
+impl ConfigError {
+    fn inconsistent(keys: &[&str], problem: String) -> Self {
+        ConfigError::Inconsistent {
+            keys: keys.iter().map(|key| key.to_string()).collect(),
+            problem,
+        }
+    }
+
+    // The keys to look at to fix it
+    pub fn keys(&self) -> Vec<&str> {
+        match self {
+            ConfigError::OutOfRange { key, .. } | ConfigError::Unknown { key, .. } => vec![key],
+            ConfigError::Inconsistent { keys, .. } => keys.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::OutOfRange { key, value, bounds } => {
+                write!(f, "`{key}` is {value}, expected {bounds}")
+            }
+            ConfigError::Inconsistent { problem, .. } => write!(f, "{problem}"),
+            ConfigError::Unknown {
+                key,
+                reference: Reference::Tag,
+                name,
+            } => write!(f, "`{key}` uses tag `{name}`, which isn't in `sample_tags`"),
+            ConfigError::Unknown {
+                key,
+                reference,
+                name,
+            } => write!(f, "`{key}` names unknown {} `{name}`", reference.name()),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+// Every problem found with a config that was to be built
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidConfig(pub Vec<ConfigError>);
+
+impl fmt::Display for InvalidConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let problems: Vec<String> = self.0.iter().map(ConfigError::to_string).collect();
+        write!(f, "invalid config: {}", problems.join("; "))
+    }
+}
+
+impl std::error::Error for InvalidConfig {}
+
+// Problems with values that are well formed but out of range, inconsistent
+// with each other or naming personas and tags that don't exist
+pub fn check(config: &SimulationConfig) -> Vec<ConfigError> {
+    let mut problems = Vec::new();
+
+    for (key, value) in [
+        ("diversity_weight", config.diversity_weight),
+        ("recency_weight", config.recency_weight),
+        ("engagement_weight", config.engagement_weight),
+        ("feed_control_usage", config.feed_control_usage),
+        ("feed_control_threshold", config.feed_control_threshold),
+        ("interest_decay_rate", config.interest_decay_rate),
+        ("follow_decay_rate", config.follow_decay_rate),
+        ("min_follow_weight", config.min_follow_weight),
+        ("bridge_min_betweenness", config.bridge_min_betweenness),
+        ("fewer_tag_penalty", config.fewer_tag_penalty),
+        ("following_weight", config.following_weight),
+        ("position_bias", config.position_bias),
+        ("appeal_effect", config.appeal_effect),
+        ("steps_per_day", config.steps_per_day as f32),
+        ("speed_multiplier", config.speed_multiplier as f32),
+        ("max_content_tags", config.max_content_tags as f32),
+        (
+            "ground_truth.quality_appeal_correlation",
+            config.ground_truth.quality_appeal_correlation,
+        ),
+        ("wellbeing.initial", config.wellbeing.initial),
+        ("wellbeing.quality_rate", config.wellbeing.quality_rate),
+        (
+            "wellbeing.conflict_penalty",
+            config.wellbeing.conflict_penalty,
+        ),
+        (
+            "wellbeing.doomscroll_penalty",
+            config.wellbeing.doomscroll_penalty,
+        ),
+        ("wellbeing.recovery_rate", config.wellbeing.recovery_rate),
+        ("wellbeing.arousal_gain", config.wellbeing.arousal_gain),
+        ("wellbeing.arousal_decay", config.wellbeing.arousal_decay),
+        ("nudges.read_before_share", config.nudges.read_before_share),
+        ("nudges.caught_up", config.nudges.caught_up),
+        ("nudges.time_reminder", config.nudges.time_reminder),
+        ("economy.tip_chance", config.economy.tip_chance),
+        ("economy.affinity", config.economy.affinity),
+        ("economy.tip_amount", config.economy.tip_amount),
+        ("economy.subscription_fee", config.economy.subscription_fee),
+        ("economy.incentive", config.economy.incentive),
+        ("segments.leakage", config.segments.leakage),
+        ("adoption.initial_members", config.adoption.initial_members),
+        ("adoption.join_rate", config.adoption.join_rate),
+        ("adoption.social_join", config.adoption.social_join),
+        ("adoption.leave_rate", config.adoption.leave_rate),
+        ("survey.nonresponse", config.survey.nonresponse),
+        (
+            "survey.dissatisfied_nonresponse",
+            config.survey.dissatisfied_nonresponse,
+        ),
+        (
+            "comment_locking.conflict_threshold",
+            config.comment_locking.conflict_threshold,
+        ),
+        ("marketplace.daily_budget", config.marketplace.daily_budget),
+        (
+            "marketplace.reserve_price",
+            config.marketplace.reserve_price,
+        ),
+        ("marketplace.adaptation", config.marketplace.adaptation),
+        ("sharing.like_chance", config.sharing.like_chance),
+        ("sharing.reshare_chance", config.sharing.reshare_chance),
+        ("drift.responsiveness", config.drift.responsiveness),
+    ] {
+        let bounds = bounds(key).expect("every checked key has bounds");
+        check_bounds(&mut problems, key, value, bounds);
+    }
+    match config.initial_graph {
+        GraphGenerator::ErdosRenyi { p } => {
+            check_fraction(&mut problems, "initial_graph.ErdosRenyi.p", p)
+        }
+        GraphGenerator::WattsStrogatz { beta, .. } => {
+            check_fraction(&mut problems, "initial_graph.WattsStrogatz.beta", beta)
+        }
+        GraphGenerator::StochasticBlock { p_in, p_out, .. } => {
+            check_fraction(&mut problems, "initial_graph.StochasticBlock.p_in", p_in);
+            check_fraction(&mut problems, "initial_graph.StochasticBlock.p_out", p_out);
+        }
+        GraphGenerator::Empty | GraphGenerator::BarabasiAlbert { .. } => {}
+    }
+    match &config.read_time {
+        ReadTimeModel::LogLength { scale } => check_bounds(
+            &mut problems,
+            "read_time.LogLength.scale",
+            *scale,
+            Bounds::NON_NEGATIVE,
+        ),
+        ReadTimeModel::PerMedia { rates } => {
+            for (media, rate) in [
+                ("organic", rates.organic),
+                ("injected", rates.injected),
+                ("breaking", rates.breaking),
+                ("comment", rates.comment),
+            ] {
+                check_bounds(
+                    &mut problems,
+                    &format!("read_time.PerMedia.rates.{media}"),
+                    rate,
+                    Bounds::NON_NEGATIVE,
+                );
+            }
+        }
+        ReadTimeModel::Skim { full, rate } => {
+            check_bounds(
+                &mut problems,
+                "read_time.Skim.full",
+                *full as f32,
+                Bounds::NON_NEGATIVE,
+            );
+            check_fraction(&mut problems, "read_time.Skim.rate", *rate);
+        }
+        ReadTimeModel::Linear => {}
+    }
+
+    // Interest alignment is weighted on top of these, and scores are capped
+    // at 1, so past this most posts would tie at the cap
+    let shared = config.recency_weight + config.engagement_weight;
+    if shared > 1.0 {
+        problems.push(ConfigError::inconsistent(
+            &["recency_weight", "engagement_weight"],
+            format!(
+                "`recency_weight` and `engagement_weight` add up to {shared}, so most posts \
+                 score the capped 1 and interest stops mattering"
+            ),
+        ));
+    }
+    if config.ranking_mode == RankingMode::Sampled && config.ranking_temperature <= 0.0 {
+        problems.push(ConfigError::inconsistent(
+            &["ranking_temperature", "ranking_mode"],
+            "`ranking_temperature` must be above 0 when sampling feeds".to_string(),
+        ));
+    }
+    if config.sample_tags.is_empty() {
+        problems.push(ConfigError::inconsistent(
+            &["sample_tags"],
+            "`sample_tags` is empty, so content has no tags to be ranked on".to_string(),
+        ));
+    }
+    if config.min_content_tags > config.max_content_tags {
+        problems.push(ConfigError::inconsistent(
+            &["min_content_tags", "max_content_tags"],
+            format!(
+                "`min_content_tags` ({}) is above `max_content_tags` ({})",
+                config.min_content_tags, config.max_content_tags
+            ),
+        ));
+    }
+    if config.max_content_tags > config.sample_tags.len() {
+        problems.push(ConfigError::inconsistent(
+            &["max_content_tags", "sample_tags"],
+            format!(
+                "`max_content_tags` ({}) is more than the {} `sample_tags`",
+                config.max_content_tags,
+                config.sample_tags.len()
+            ),
+        ));
+    }
+    if config.retrieval.sources.is_empty() {
+        problems.push(ConfigError::inconsistent(
+            &["retrieval.sources"],
+            "`retrieval.sources` is empty, so feeds are always empty".to_string(),
+        ));
+    }
+    if config.marketplace.enabled() && config.marketplace.strategies.is_empty() {
+        problems.push(ConfigError::inconsistent(
+            &["marketplace.strategies"],
+            "`marketplace.strategies` is empty, so no organisation ever bids".to_string(),
+        ));
+    }
+    if config.ads.per_feed >= FEED_LENGTH {
+        problems.push(ConfigError::inconsistent(
+            &["ads.per_feed"],
+            format!(
+                "`ads.per_feed` is {}, which leaves no room for posts in a feed of {FEED_LENGTH}",
+                config.ads.per_feed
+            ),
+        ));
+    }
+
+    let total: f32 = config.persona_fractions.values().sum();
+    if total > 1.0 + 1e-4 {
+        problems.push(ConfigError::inconsistent(
+            &["persona_fractions"],
+            format!("`persona_fractions` add up to {total}, more than 1"),
+        ));
+    }
+    for (name, fraction) in &config.persona_fractions {
+        check_fraction(
+            &mut problems,
+            &format!("persona_fractions.{name}"),
+            *fraction,
+        );
+        if !config.personas.iter().any(|persona| &persona.name == name) {
+            problems.push(ConfigError::Unknown {
+                key: "persona_fractions".to_string(),
+                reference: Reference::Persona,
+                name: name.clone(),
+            });
+        }
+    }
+
+    let total: f32 = config.cohort_fractions.values().sum();
+    if total > 1.0 + 1e-4 {
+        problems.push(ConfigError::inconsistent(
+            &["cohort_fractions"],
+            format!("`cohort_fractions` add up to {total}, more than 1"),
+        ));
+    }
+    for (name, fraction) in &config.cohort_fractions {
+        check_fraction(
+            &mut problems,
+            &format!("cohort_fractions.{name}"),
+            *fraction,
+        );
+        if !config.cohorts.iter().any(|cohort| &cohort.name == name) {
+            problems.push(ConfigError::Unknown {
+                key: "cohort_fractions".to_string(),
+                reference: Reference::Cohort,
+                name: name.clone(),
+            });
+        }
+    }
+    for cohort in &config.cohorts {
+        let hours = cohort.active_hours;
+        for (field, value) in [
+            ("active_hours.start", hours.start),
+            ("active_hours.end", hours.end),
+            ("active_hours.off_peak", hours.off_peak),
+        ] {
+            check_fraction(
+                &mut problems,
+                &format!("cohorts.{}.{field}", cohort.name),
+                value,
+            );
+        }
+        check_bounds(
+            &mut problems,
+            &format!("cohorts.{}.susceptibility", cohort.name),
+            cohort.susceptibility,
+            Bounds::NON_NEGATIVE,
+        );
+    }
+
+    for (name, fraction) in &config.segments.fractions {
+        check_bounds(
+            &mut problems,
+            &format!("segments.fractions.{name}"),
+            *fraction,
+            Bounds::NON_NEGATIVE,
+        );
+    }
+
+    let fairness = &config.fairness;
+    let mut floors = 0.0;
+    for target in &fairness.targets {
+        let key = |field: &str| format!("fairness.targets.{}.{field}", target.group);
+        if let Some(min) = target.min_share {
+            check_fraction(&mut problems, &key("min_share"), min);
+            floors += min;
+        }
+        if let Some(max) = target.max_share {
+            check_fraction(&mut problems, &key("max_share"), max);
+        }
+        if let (Some(min), Some(max)) = (target.min_share, target.max_share) {
+            if min > max {
+                problems.push(ConfigError::inconsistent(
+                    &[&key("min_share"), &key("max_share")],
+                    format!(
+                        "`{}` ({min}) is above `{}` ({max})",
+                        key("min_share"),
+                        key("max_share")
+                    ),
+                ));
+            }
+        }
+        let known = fairness.known_groups();
+        if known.is_some_and(|known| !known.contains(&target.group.as_str())) {
+            problems.push(ConfigError::Unknown {
+                key: "fairness.targets".to_string(),
+                reference: Reference::AgentType,
+                name: target.group.clone(),
+            });
+        }
+    }
+    if floors > 1.0 + 1e-4 {
+        problems.push(ConfigError::inconsistent(
+            &["fairness.targets"],
+            format!("the `min_share`s in `fairness.targets` add up to {floors}, more than 1"),
+        ));
+    }
+
+    let mut unknown_tag = |key: String, tag: &str| {
+        if !config.sample_tags.iter().any(|known| known == tag) {
+            problems.push(ConfigError::Unknown {
+                key,
+                reference: Reference::Tag,
+                name: tag.to_string(),
+            });
+        }
+    };
+    for persona in &config.personas {
+        for prior in &persona.tags {
+            unknown_tag(format!("personas.{}", persona.name), &prior.tag);
+        }
+    }
+    for (key, matrix) in [
+        ("tag_similarity", &config.tag_similarity),
+        ("content.tag_cooccurrence", &config.content.tag_cooccurrence),
+    ] {
+        for (a, b, _) in &matrix.pairs {
+            unknown_tag(key.to_string(), a);
+            unknown_tag(key.to_string(), b);
+        }
+    }
+
+    for persona in &config.personas {
+        for (field, (min, max)) in [
+            ("next_post_likelihood", persona.next_post_likelihood),
+            ("attention_span", persona.attention_span),
+            ("read_speed", persona.read_speed),
+            (
+                "content_creation_frequency",
+                persona.content_creation_frequency,
+            ),
+            ("create_speed", persona.create_speed),
+        ] {
+            if min > max {
+                let key = format!("personas.{}.{field}", persona.name);
+                problems.push(ConfigError::Inconsistent {
+                    problem: format!("`{key}` has min {min} above max {max}"),
+                    keys: vec![key],
+                });
+            }
+        }
+    }
+
+    problems
+}
+
+fn check_bounds(problems: &mut Vec<ConfigError>, key: &str, value: f32, bounds: Bounds) {
+    if !bounds.contains(value) {
+        problems.push(ConfigError::OutOfRange {
+            key: key.to_string(),
+            value,
+            bounds,
+        });
+    }
+}
+
+fn check_fraction(problems: &mut Vec<ConfigError>, key: &str, value: f32) {
+    check_bounds(problems, key, value, Bounds::FRACTION);
+}
+
+// Builds a config from the defaults, or from one already loaded, checking it
+// before handing it over
+#[derive(Debug, Clone)]
+pub struct ConfigBuilder {
+    config: SimulationConfig,
+}
+
+impl SimulationConfig {
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::from(SimulationConfig::default())
+    }
+}
+
+impl From<SimulationConfig> for ConfigBuilder {
+    fn from(config: SimulationConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl ConfigBuilder {
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.config.seed = seed;
+        self
+    }
+
+    pub fn agents(mut self, individuals: usize, bots: usize, organisations: usize) -> Self {
+        self.config.num_individuals = individuals;
+        self.config.num_bots = bots;
+        self.config.num_organisations = organisations;
+        self
+    }
+
+    pub fn ranking_weights(mut self, diversity: f32, recency: f32, engagement: f32) -> Self {
+        self.config.diversity_weight = diversity;
+        self.config.recency_weight = recency;
+        self.config.engagement_weight = engagement;
+        self
+    }
+
+    pub fn sample_tags(mut self, tags: &[&str]) -> Self {
+        self.config.sample_tags = tags.iter().map(|tag| tag.to_string()).collect();
+        self
+    }
+
+    pub fn content_tags(mut self, min: usize, max: usize) -> Self {
+        self.config.min_content_tags = min;
+        self.config.max_content_tags = max;
+        self
+    }
+
+    pub fn steps_per_day(mut self, steps: u64) -> Self {
+        self.config.steps_per_day = steps;
+        self
+    }
+
+    // Sets anything without a method of its own
+    pub fn with(mut self, change: impl FnOnce(&mut SimulationConfig)) -> Self {
+        change(&mut self.config);
+        self
+    }
+
+    pub fn build(self) -> Result<SimulationConfig, InvalidConfig> {
+        let problems = check(&self.config);
+        if problems.is_empty() {
+            Ok(self.config)
+        } else {
+            Err(InvalidConfig(problems))
+        }
+    }
+}
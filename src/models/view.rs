@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+
+use super::{
+    AdStats, Agent, AgentId, AgentState, AgentType, DecisionLog, Earnings, FeedArchive,
+    FeedPreferences, InterestProfile, NudgeStats, SelectionStats, TopicDrift, WellBeing,
+};
+
+// Read-only snapshot of an agent for display, so that the UI doesn't need to
+// know which concrete agent kind it is looking at
+#[derive(Debug, Clone, Copy)]
+pub struct AgentView<'a> {
+    pub id: AgentId,
+    pub agent_type: AgentType,
+    pub state: &'a AgentState,
+    pub interest_profile: &'a InterestProfile,
+    pub session_length: Option<i32>,
+    pub persona: Option<&'a str>,
+    pub cohort: Option<&'a str>,
+    pub community: Option<usize>,
+    pub segment: Option<&'a str>,
+    pub member: Option<bool>,
+    pub decisions: Option<&'a DecisionLog>,
+    pub selections: Option<&'a SelectionStats>,
+    pub feeds: Option<&'a FeedArchive>,
+    pub feed_preferences: Option<&'a FeedPreferences>,
+    pub wellbeing: Option<&'a WellBeing>,
+    pub nudges: Option<&'a NudgeStats>,
+    pub ads: Option<&'a AdStats>,
+    pub earnings: Option<&'a Earnings>,
+    pub drift: Option<&'a TopicDrift>,
+}
+
+impl<'a> AgentView<'a> {
+    pub fn new(agent: &'a dyn Agent) -> Self {
+        Self {
+            id: *agent.id(),
+            agent_type: agent.get_type(),
+            state: agent.state(),
+            interest_profile: agent.interest_profile(),
+            session_length: agent.session_length(),
+            persona: agent.persona(),
+            cohort: agent.cohort(),
+            community: agent.community(),
+            segment: agent.segment(),
+            member: agent.member(),
+            decisions: agent.decisions(),
+            selections: agent.selections(),
+            feeds: agent.feeds(),
+            feed_preferences: agent.feed_preferences(),
+            wellbeing: agent.wellbeing(),
+            nudges: agent.nudges(),
+            ads: agent.ads(),
+            earnings: agent.earnings(),
+            drift: agent.drift(),
+        }
+    }
+
+    pub fn state_name(&self) -> &'static str {
+        self.state.name()
+    }
+
+    pub fn progress(&self) -> Option<f32> {
+        self.state.progress()
+    }
+
+    // Whether the agent uses its feed settings, for agents that have a feed
+    pub fn uses_feed_controls(&self) -> Option<bool> {
+        self.feed_preferences.map(|preferences| preferences.enabled)
+    }
+
+    // (tag, weighted interest) pairs, sorted by tag so that charts are stable
+    // between frames
+    pub fn interests(&self) -> Vec<(String, f32)> {
+        let mut interests: Vec<_> = self
+            .interest_profile
+            .normalised_weights()
+            .map(|(tag, weight)| (tag.clone(), weight))
+            .collect();
+
+        interests.sort_by(|a, b| a.0.cmp(&b.0));
+        interests
+    }
+
+    // Agreement per tag, in the same order as `interests`
+    pub fn agreements(&self) -> Vec<f32> {
+        let mut agreements: Vec<_> = self
+            .interest_profile
+            .interests
+            .iter()
+            .map(|(tag, topic)| (tag, topic.agreement))
+            .collect();
+
+        agreements.sort_by(|a, b| a.0.cmp(b.0));
+        agreements
+            .into_iter()
+            .map(|(_, agreement)| agreement)
+            .collect()
+    }
+}
+
+// How many agents of each type are in each state, for summing up populations
+// too large to look at agent by agent
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PopulationSummary {
+    // Agent type name to state name to count
+    pub counts: BTreeMap<&'static str, BTreeMap<&'static str, usize>>,
+}
+
+impl PopulationSummary {
+    pub fn of<'a>(agents: impl IntoIterator<Item = AgentView<'a>>) -> Self {
+        let mut counts: BTreeMap<&'static str, BTreeMap<&'static str, usize>> = BTreeMap::new();
+        for agent in agents {
+            *counts
+                .entry(agent.agent_type.name())
+                .or_default()
+                .entry(agent.state_name())
+                .or_default() += 1;
+        }
+        Self { counts }
+    }
+
+    pub fn total(&self, agent_type: AgentType) -> usize {
+        self.counts
+            .get(agent_type.name())
+            .map_or(0, |states| states.values().sum())
+    }
+}
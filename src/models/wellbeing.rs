@@ -0,0 +1,209 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use super::AgentView;
+
+// Population samples kept, oldest dropped first
+const MAX_SAMPLES: usize = 500;
+
+// How individuals' well-being responds to what they read and how long they
+// stay online
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WellBeingConfig {
+    // Where every individual's index starts, and where it recovers to offline
+    pub initial: f32,
+    // Share of the way each read moves the index towards the quality of the
+    // post read
+    pub quality_rate: f32,
+    // Agreement gap on a shared tag past which a post is conflictual to its
+    // reader, and how much reading one costs
+    pub conflict_threshold: f32,
+    pub conflict_penalty: f32,
+    // Ticks into a session after which time online counts as late-session
+    // use, and how much each late tick spent scrolling costs
+    pub late_session_ticks: i32,
+    pub doomscroll_penalty: f32,
+    // Share of the way each tick offline moves the index back to `initial`
+    pub recovery_rate: f32,
+    // Share of the way a conflictual read, or a comment on a post the writer
+    // disagrees with, moves arousal towards 1, and the share it loses each tick
+    pub arousal_gain: f32,
+    pub arousal_decay: f32,
+    // Steps between samples of the population's well-being, 0 to never sample
+    pub interval: u64,
+}
+
+impl Default for WellBeingConfig {
+    fn default() -> Self {
+        Self {
+            initial: 0.5,
+            quality_rate: 0.05,
+            conflict_threshold: 1.0,
+            conflict_penalty: 0.02,
+            late_session_ticks: 100,
+            doomscroll_penalty: 0.002,
+            recovery_rate: 0.01,
+            arousal_gain: 0.2,
+            arousal_decay: 0.02,
+            interval: 10,
+        }
+    }
+}
+
+// A proxy for how an individual is doing, from 0 to 1. It drifts towards the
+// quality of what the individual reads, so a diet of poor posts wears it down
+// however engaging they were. Conflictual posts and scrolling on late into a
+// session wear it down further, and time offline restores it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WellBeing {
+    pub index: f32,
+    // Posts read with known quality, and the sum of their quality
+    pub reads: usize,
+    pub quality_read: f32,
+    // Posts read whose stance clashed with the reader's
+    pub conflicts: usize,
+    // Ticks spent online in total, and those past the late-session threshold
+    pub screen_time: u64,
+    pub late_session_time: u64,
+    // How worked up the individual is from clashing with others, from 0 to 1
+    pub arousal: f32,
+}
+
+impl WellBeing {
+    pub fn new(config: &WellBeingConfig) -> Self {
+        Self {
+            index: config.initial.clamp(0.0, 1.0),
+            reads: 0,
+            quality_read: 0.0,
+            conflicts: 0,
+            screen_time: 0,
+            late_session_time: 0,
+            arousal: 0.0,
+        }
+    }
+
+    pub fn read(&mut self, quality: f32, config: &WellBeingConfig) {
+        self.reads += 1;
+        self.quality_read += quality;
+        self.index += config.quality_rate * (quality - self.index);
+        self.index = self.index.clamp(0.0, 1.0);
+    }
+
+    pub fn conflict(&mut self, config: &WellBeingConfig) {
+        self.conflicts += 1;
+        self.index = (self.index - config.conflict_penalty).clamp(0.0, 1.0);
+        self.arouse(config);
+    }
+
+    pub fn arouse(&mut self, config: &WellBeingConfig) {
+        self.arousal += config.arousal_gain * (1.0 - self.arousal);
+        self.arousal = self.arousal.clamp(0.0, 1.0);
+    }
+
+    // A tick spent online, `session_length` ticks into the session
+    pub fn online(&mut self, session_length: i32, scrolling: bool, config: &WellBeingConfig) {
+        self.screen_time += 1;
+        self.calm(config);
+        if session_length > config.late_session_ticks {
+            self.late_session_time += 1;
+            if scrolling {
+                self.index = (self.index - config.doomscroll_penalty).clamp(0.0, 1.0);
+            }
+        }
+    }
+
+    pub fn offline(&mut self, config: &WellBeingConfig) {
+        self.index += config.recovery_rate * (config.initial - self.index);
+        self.calm(config);
+    }
+
+    // Arousal wears off a little each tick, online or not
+    fn calm(&mut self, config: &WellBeingConfig) {
+        self.arousal = (self.arousal * (1.0 - config.arousal_decay)).clamp(0.0, 1.0);
+    }
+
+    // Mean quality of everything read so far
+    pub fn mean_read_quality(&self) -> Option<f32> {
+        (self.reads > 0).then(|| self.quality_read / self.reads as f32)
+    }
+
+    // Share of screen time that was late-session use
+    pub fn late_session_share(&self) -> Option<f32> {
+        (self.screen_time > 0).then(|| self.late_session_time as f32 / self.screen_time as f32)
+    }
+}
+
+// The population's well-being at one step, over every agent that has it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WellBeingSample {
+    pub step: u64,
+    pub agents: usize,
+    pub mean_index: f32,
+    // Lowest index of any agent, to show whether a few are suffering while
+    // the mean holds up
+    pub min_index: f32,
+    pub mean_screen_time: f32,
+    #[serde(default)]
+    pub mean_arousal: f32,
+    // Late-session ticks and conflictual reads as shares of all screen time
+    // and all reads, None until there are any
+    pub late_session_share: Option<f32>,
+    pub conflict_share: Option<f32>,
+}
+
+impl WellBeingSample {
+    pub fn of<'a>(step: u64, agents: impl Iterator<Item = AgentView<'a>>) -> Option<Self> {
+        let wellbeings: Vec<&WellBeing> = agents.filter_map(|agent| agent.wellbeing).collect();
+        if wellbeings.is_empty() {
+            return None;
+        }
+
+        let count = wellbeings.len() as f32;
+        let sum = |value: fn(&WellBeing) -> u64| wellbeings.iter().map(|w| value(w)).sum::<u64>();
+        let (screen_time, late) = (sum(|w| w.screen_time), sum(|w| w.late_session_time));
+        let (reads, conflicts) = (sum(|w| w.reads as u64), sum(|w| w.conflicts as u64));
+
+        Some(Self {
+            step,
+            agents: wellbeings.len(),
+            mean_index: wellbeings.iter().map(|w| w.index).sum::<f32>() / count,
+            min_index: wellbeings.iter().map(|w| w.index).fold(1.0, f32::min),
+            mean_screen_time: screen_time as f32 / count,
+            mean_arousal: wellbeings.iter().map(|w| w.arousal).sum::<f32>() / count,
+            late_session_share: (screen_time > 0).then(|| late as f32 / screen_time as f32),
+            conflict_share: (reads > 0).then(|| conflicts as f32 / reads as f32),
+        })
+    }
+}
+
+// Population well-being sampled over the run, for experiments where it's the
+// outcome being measured
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WellBeingSeries {
+    pub samples: VecDeque<WellBeingSample>,
+}
+
+impl WellBeingSeries {
+    pub fn record<'a>(&mut self, step: u64, agents: impl Iterator<Item = AgentView<'a>>) {
+        let Some(sample) = WellBeingSample::of(step, agents) else {
+            return;
+        };
+        if self.samples.len() == MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn latest(&self) -> Option<&WellBeingSample> {
+        self.samples.back()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
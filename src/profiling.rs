@@ -0,0 +1,245 @@
+use instant::{Duration, Instant};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, VecDeque};
+
+// How many memory reports are kept for the performance panel
+const MAX_REPORTS: usize = 60;
+
+#[cfg(feature = "alloc-tracking")]
+mod allocator {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    pub static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+    pub static DEALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+    // Wraps the system allocator, counting the bytes that pass through it.
+    // Install it in the binary with `#[global_allocator]`
+    pub struct CountingAllocator;
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            DEALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+            System.dealloc(ptr, layout)
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            ALLOCATED.fetch_add(new_size, Ordering::Relaxed);
+            DEALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+            System.realloc(ptr, layout, new_size)
+        }
+    }
+}
+
+#[cfg(feature = "alloc-tracking")]
+pub use allocator::CountingAllocator;
+
+// Bytes currently allocated, when the counting allocator is installed
+#[cfg(feature = "alloc-tracking")]
+pub fn live_bytes() -> Option<usize> {
+    use std::sync::atomic::Ordering;
+
+    let allocated = allocator::ALLOCATED.load(Ordering::Relaxed);
+    let deallocated = allocator::DEALLOCATED.load(Ordering::Relaxed);
+    Some(allocated.saturating_sub(deallocated))
+}
+
+#[cfg(not(feature = "alloc-tracking"))]
+pub fn live_bytes() -> Option<usize> {
+    None
+}
+
+// The parts of a step that memory growth is attributed to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Subsystem {
+    Interventions,
+    Agents,
+    EngineOps,
+    History,
+    Diagnostics,
+}
+
+impl Subsystem {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Subsystem::Interventions => "interventions",
+            Subsystem::Agents => "agent ticks",
+            Subsystem::EngineOps => "engine ops",
+            Subsystem::History => "interest history",
+            Subsystem::Diagnostics => "diagnostics",
+        }
+    }
+}
+
+// Memory use over one reporting interval
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryReport {
+    pub step: u64,
+    // None without the counting allocator
+    pub live_bytes: Option<usize>,
+    // Net bytes each subsystem allocated over the interval
+    pub growth: BTreeMap<Subsystem, i64>,
+    // Lengths of the collections that grow with the run, by name
+    pub retained: BTreeMap<&'static str, usize>,
+}
+
+// Attributes allocations made during a step to the subsystem making them, and
+// reports the totals every so many steps
+#[derive(Debug, Clone, Default)]
+pub struct MemoryProfile {
+    growth: BTreeMap<Subsystem, i64>,
+    pub reports: VecDeque<MemoryReport>,
+}
+
+impl MemoryProfile {
+    // The point to measure a subsystem's allocations from
+    pub fn mark(&self) -> Option<usize> {
+        live_bytes()
+    }
+
+    // Charges everything allocated since `mark` to `subsystem`
+    pub fn charge(&mut self, subsystem: Subsystem, mark: Option<usize>) {
+        if let (Some(before), Some(after)) = (mark, live_bytes()) {
+            *self.growth.entry(subsystem).or_insert(0) += after as i64 - before as i64;
+        }
+    }
+
+    pub fn report(&mut self, step: u64, retained: BTreeMap<&'static str, usize>) {
+        if self.reports.len() == MAX_REPORTS {
+            self.reports.pop_front();
+        }
+        self.reports.push_back(MemoryReport {
+            step,
+            live_bytes: live_bytes(),
+            growth: std::mem::take(&mut self.growth),
+            retained,
+        });
+    }
+
+    pub fn latest(&self) -> Option<&MemoryReport> {
+        self.reports.back()
+    }
+}
+
+// Hierarchical timing spans, for attributing a step's time to agent
+// transitions, engine scoring and so on, and for the UI's frame time. Spans
+// only record between `start_spans` and `stop_spans`; otherwise opening one
+// costs a thread-local check. They're kept per thread so that code deep in the
+// engine can open spans without a profiler being passed down to it
+thread_local! {
+    static SPANS: RefCell<Option<SpanRecorder>> = const { RefCell::new(None) };
+}
+
+struct OpenSpan {
+    name: &'static str,
+    started: Instant,
+    // Time spent in spans opened inside this one, which isn't its own
+    children: Duration,
+}
+
+#[derive(Default)]
+struct SpanRecorder {
+    open: Vec<OpenSpan>,
+    profile: FlameProfile,
+}
+
+// Time spent in each stack of spans, as the folded stacks flamegraph and
+// inferno read. Each stack holds only the time not spent in a deeper span
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FlameProfile {
+    // Span names joined by `;`, outermost first, to nanoseconds
+    pub stacks: BTreeMap<String, u64>,
+}
+
+impl FlameProfile {
+    // One `stack nanoseconds` line per stack, ready for `inferno-flamegraph`
+    pub fn folded(&self) -> String {
+        let mut folded = String::new();
+        for (stack, nanos) in &self.stacks {
+            folded.push_str(&format!("{stack} {nanos}\n"));
+        }
+        folded
+    }
+
+    // Nanoseconds spent in a span and everything inside it, wherever it was
+    // opened
+    pub fn inclusive(&self, name: &str) -> u64 {
+        self.stacks
+            .iter()
+            .filter(|(stack, _)| stack.split(';').any(|frame| frame == name))
+            .map(|(_, nanos)| nanos)
+            .sum()
+    }
+
+    pub fn total(&self) -> u64 {
+        self.stacks.values().sum()
+    }
+}
+
+// Starts recording spans on this thread, discarding any earlier recording
+pub fn start_spans() {
+    SPANS.with_borrow_mut(|spans| *spans = Some(SpanRecorder::default()));
+}
+
+// Stops recording and returns what was recorded, if spans were running.
+// Spans still open are left out
+pub fn stop_spans() -> Option<FlameProfile> {
+    SPANS.with_borrow_mut(|spans| spans.take().map(|recorder| recorder.profile))
+}
+
+pub fn spans_running() -> bool {
+    SPANS.with_borrow(|spans| spans.is_some())
+}
+
+// Opens a span that closes when the returned guard is dropped
+pub fn span(name: &'static str) -> Span {
+    let open = SPANS.with_borrow_mut(|spans| match spans {
+        Some(recorder) => {
+            recorder.open.push(OpenSpan {
+                name,
+                started: Instant::now(),
+                children: Duration::ZERO,
+            });
+            true
+        }
+        None => false,
+    });
+    Span { open }
+}
+
+#[must_use = "a span closes as soon as its guard is dropped"]
+pub struct Span {
+    open: bool,
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if !self.open {
+            return;
+        }
+        SPANS.with_borrow_mut(|spans| {
+            // Recording may have been restarted or stopped while this span
+            // was open, taking its entry with it
+            let Some(recorder) = spans else {
+                return;
+            };
+            let stack: Vec<&str> = recorder.open.iter().map(|span| span.name).collect();
+            let stack = stack.join(";");
+            let Some(span) = recorder.open.pop() else {
+                return;
+            };
+
+            let elapsed = span.started.elapsed();
+            if let Some(parent) = recorder.open.last_mut() {
+                parent.children += elapsed;
+            }
+            let own = elapsed.saturating_sub(span.children).as_nanos() as u64;
+            *recorder.profile.stacks.entry(stack).or_insert(0) += own;
+        });
+    }
+}
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::Simulation;
+
+// Bumped whenever the saved state changes in a way older readers can't
+// follow. Readers refuse saves newer than they are
+pub const SAVE_FORMAT_VERSION: u32 = 1;
+
+pub const SAVE_EXTENSION: &str = "smssave";
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Unsupported(u32),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(e) => write!(f, "could not access save: {e}"),
+            SaveError::Json(e) => write!(f, "invalid save contents: {e}"),
+            SaveError::Unsupported(version) => write!(
+                f,
+                "save format version {version} is newer than {SAVE_FORMAT_VERSION}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl From<std::io::Error> for SaveError {
+    fn from(e: std::io::Error) -> Self {
+        SaveError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(e: serde_json::Error) -> Self {
+        SaveError::Json(e)
+    }
+}
+
+// The version is read on its own first, so a save from a newer version is
+// refused rather than failing somewhere inside the simulation
+#[derive(Deserialize)]
+struct SaveHeader {
+    format_version: u32,
+}
+
+#[derive(Serialize)]
+struct SaveFile<'a> {
+    format_version: u32,
+    crate_version: &'static str,
+    simulation: &'a Simulation,
+}
+
+#[derive(Deserialize)]
+struct LoadedFile {
+    simulation: Simulation,
+}
+
+impl Simulation {
+    // Writes the whole state of the simulation, so it can carry on from
+    // exactly this step later. Unlike a bundle nothing is replayed on load,
+    // so saves of long runs load quickly, but they are far larger. Rerankers
+    // and custom transition policies are code rather than state, and aren't
+    // saved, so agents come back with the configured built-in policy.
+    // Analytics still running in the background aren't saved either, so
+    // flush them first
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SaveError> {
+        let mut writer = BufWriter::new(std::fs::File::create(path)?);
+        serde_json::to_writer(
+            &mut writer,
+            &SaveFile {
+                format_version: SAVE_FORMAT_VERSION,
+                crate_version: env!("CARGO_PKG_VERSION"),
+                simulation: self,
+            },
+        )?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Simulation, SaveError> {
+        let contents = std::fs::read(path)?;
+        let header: SaveHeader = serde_json::from_slice(&contents)?;
+        if header.format_version > SAVE_FORMAT_VERSION {
+            return Err(SaveError::Unsupported(header.format_version));
+        }
+        let mut loaded: LoadedFile = serde_json::from_slice(&contents)?;
+        let policy = loaded.simulation.config.policy;
+        loaded.simulation.set_policy(policy);
+        Ok(loaded.simulation)
+    }
+}
@@ -0,0 +1,254 @@
+// Builders for engine state in tests, so each test doesn't reinvent its setup.
+// Compiled for this crate's own tests and, with the `test-util` feature, for
+// downstream users
+use crate::models::{
+    content::{Comment, ContentKind},
+    AgentId, CommentId, IdAllocator, InterestProfile, Post, PostId, SimulationConfig, Topic,
+};
+use crate::RecommendationEngine;
+
+// Builds a normalised profile from (tag, weight, agreement) entries
+#[derive(Debug, Clone)]
+pub struct ProfileBuilder {
+    dimension: usize,
+    interests: Vec<(String, f32, f32)>,
+}
+
+impl Default for ProfileBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProfileBuilder {
+    pub fn new() -> Self {
+        Self {
+            dimension: 100,
+            interests: Vec::new(),
+        }
+    }
+
+    pub fn dimension(mut self, dimension: usize) -> Self {
+        self.dimension = dimension;
+        self
+    }
+
+    pub fn tag(self, tag: &str, weight: f32) -> Self {
+        self.tag_with_agreement(tag, weight, 0.0)
+    }
+
+    pub fn tag_with_agreement(mut self, tag: &str, weight: f32, agreement: f32) -> Self {
+        self.interests.push((tag.to_string(), weight, agreement));
+        self
+    }
+
+    pub fn build(self) -> InterestProfile {
+        let mut profile = InterestProfile::new(self.dimension);
+        for (tag, weight, agreement) in self.interests {
+            profile.interests.insert(
+                tag,
+                Topic {
+                    weighted_interest: weight,
+                    agreement,
+                },
+            );
+        }
+        profile.normalise_weights();
+        profile
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CommentBuilder {
+    comment: Comment,
+}
+
+impl CommentBuilder {
+    pub fn new(id: CommentId) -> Self {
+        Self {
+            comment: Comment {
+                id,
+                commentor_id: AgentId(0),
+                timestamp: 0,
+                interest_profile: InterestProfile::new(100),
+                length: 1,
+                engagement_score: 0.0,
+            },
+        }
+    }
+
+    pub fn commentor(mut self, commentor_id: AgentId) -> Self {
+        self.comment.commentor_id = commentor_id;
+        self
+    }
+
+    pub fn profile(mut self, profile: InterestProfile) -> Self {
+        self.comment.interest_profile = profile;
+        self
+    }
+
+    pub fn length(mut self, length: i32) -> Self {
+        self.comment.length = length;
+        self
+    }
+
+    pub fn engagement(mut self, engagement_score: f32) -> Self {
+        self.comment.engagement_score = engagement_score;
+        self
+    }
+
+    pub fn build(self) -> Comment {
+        self.comment
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PostBuilder {
+    post: Post,
+}
+
+impl PostBuilder {
+    pub fn new(id: PostId) -> Self {
+        Self {
+            post: Post {
+                id,
+                creator_id: AgentId(0),
+                timestamp: 0,
+                published_step: 0,
+                kind: ContentKind::Organic,
+                segment: None,
+                appeal: 0.0,
+                interest_profile: InterestProfile::new(100),
+                length: 1,
+                readers: Vec::new(),
+                comments: Vec::new(),
+                engagement_score: 0.0,
+                cascade: Vec::new(),
+                likes: Vec::new(),
+                shared_by: Vec::new(),
+                reshare_of: None,
+                original: None,
+                reshares: Vec::new(),
+            },
+        }
+    }
+
+    pub fn creator(mut self, creator_id: AgentId) -> Self {
+        self.post.creator_id = creator_id;
+        self
+    }
+
+    pub fn timestamp(mut self, timestamp: i64) -> Self {
+        self.post.timestamp = timestamp;
+        self
+    }
+
+    pub fn segment(mut self, segment: &str) -> Self {
+        self.post.segment = Some(segment.to_string());
+        self
+    }
+
+    pub fn appeal(mut self, appeal: f32) -> Self {
+        self.post.appeal = appeal;
+        self
+    }
+
+    pub fn published_step(mut self, step: u64) -> Self {
+        self.post.published_step = step;
+        self
+    }
+
+    pub fn profile(mut self, profile: InterestProfile) -> Self {
+        self.post.interest_profile = profile;
+        self
+    }
+
+    pub fn length(mut self, length: i32) -> Self {
+        self.post.length = length;
+        self
+    }
+
+    pub fn engagement(mut self, engagement_score: f32) -> Self {
+        self.post.engagement_score = engagement_score;
+        self
+    }
+
+    pub fn comment(mut self, comment: Comment) -> Self {
+        self.post.comments.push(comment);
+        self
+    }
+
+    pub fn build(self) -> Post {
+        self.post
+    }
+}
+
+// An engine with tags registered and a pool of posts. Post `i` covers tag
+// `i % tags.len()`, so fixtures are deterministic
+#[derive(Debug, Clone)]
+pub struct EngineFixture {
+    num_posts: usize,
+    comments_per_post: usize,
+    tags: Vec<String>,
+    creator: AgentId,
+}
+
+impl EngineFixture {
+    pub fn with_posts(num_posts: usize) -> Self {
+        Self {
+            num_posts,
+            comments_per_post: 0,
+            tags: SimulationConfig::default().sample_tags,
+            creator: AgentId(0),
+        }
+    }
+
+    pub fn with_tags(mut self, tags: &[&str]) -> Self {
+        self.tags = tags.iter().map(|tag| tag.to_string()).collect();
+        self
+    }
+
+    pub fn with_comments(mut self, comments_per_post: usize) -> Self {
+        self.comments_per_post = comments_per_post;
+        self
+    }
+
+    pub fn with_creator(mut self, creator: AgentId) -> Self {
+        self.creator = creator;
+        self
+    }
+
+    pub fn build(self) -> RecommendationEngine {
+        self.build_with_ids(&mut IdAllocator::new())
+    }
+
+    // Allocates post and comment IDs from `ids`, so the caller can keep
+    // creating content without clashing with the fixture's
+    pub fn build_with_ids(self, ids: &mut IdAllocator) -> RecommendationEngine {
+        let mut engine = RecommendationEngine::new();
+        engine.register_tags(&self.tags);
+
+        for i in 0..self.num_posts {
+            let profile = match self.tags.get(i % self.tags.len().max(1)) {
+                Some(tag) => ProfileBuilder::new().tag(tag, 1.0).build(),
+                None => InterestProfile::new(engine.vector_dimension),
+            };
+
+            let mut post = PostBuilder::new(ids.next_post_id())
+                .creator(self.creator)
+                .profile(profile.clone());
+            for _ in 0..self.comments_per_post {
+                post = post.comment(
+                    CommentBuilder::new(ids.next_comment_id())
+                        .commentor(self.creator)
+                        .profile(profile.clone())
+                        .build(),
+                );
+            }
+
+            engine.create_post(post.build());
+        }
+
+        engine
+    }
+}
@@ -0,0 +1,153 @@
+use crate::models::Scenario;
+
+// A small population that fits on screen, so every agent and post can be
+// pointed at while the tutorial runs
+const SCENARIO: &str = r#"
+seed = 2024
+num_individuals = 8
+num_bots = 1
+num_organisations = 1
+max_post_length = 60
+"#;
+
+// The part of the app a tutorial step points at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Highlight {
+    ControlPanel,
+    Agents,
+    // The first open agent window, or the agents it was opened from
+    AgentWindow,
+    ContentPool,
+    Distributions,
+}
+
+// What moves a step on, besides the Next button
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Advance {
+    // Only the Next button
+    Manual,
+    Running,
+    AgentWindowOpen,
+    // The simulation has run this many steps since the step began
+    Steps(u64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TutorialStep {
+    pub title: &'static str,
+    pub text: &'static str,
+    pub highlight: Highlight,
+    pub advance: Advance,
+}
+
+// The state of the app a step is waiting on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TutorialProgress {
+    pub running: bool,
+    pub agent_windows: usize,
+    pub step: u64,
+}
+
+pub const STEPS: &[TutorialStep] = &[
+    TutorialStep {
+        title: "Welcome",
+        text: "This sandbox simulates people, bots and organisations using a social media \
+               platform whose feeds are picked by a recommendation algorithm. The tutorial \
+               has loaded a small scenario to explore",
+        highlight: Highlight::Agents,
+        advance: Advance::Manual,
+    },
+    TutorialStep {
+        title: "Control panel",
+        text: "The sliders here set up the population and weight what the algorithm ranks \
+               posts by. Press Start to set the simulation running",
+        highlight: Highlight::ControlPanel,
+        advance: Advance::Running,
+    },
+    TutorialStep {
+        title: "Agents",
+        text: "Every agent is shown here with what it is doing. Click an icon to open that \
+               agent's window",
+        highlight: Highlight::Agents,
+        advance: Advance::AgentWindowOpen,
+    },
+    TutorialStep {
+        title: "Agent window",
+        text: "The chart shows how interested the agent is in each tag, and how much it \
+               agrees with it. Watch it change as the agent reads its feed",
+        highlight: Highlight::AgentWindow,
+        advance: Advance::Steps(20),
+    },
+    TutorialStep {
+        title: "Content pool",
+        text: "Posts everyone has made end up here, with their tags and engagement. Open a \
+               post's cascade to see who saw, read and commented on it",
+        highlight: Highlight::ContentPool,
+        advance: Advance::Manual,
+    },
+    TutorialStep {
+        title: "Distributions",
+        text: "These charts sum up the whole population. Try raising the Engagement Weight \
+               and see whether interests narrow",
+        highlight: Highlight::Distributions,
+        advance: Advance::Manual,
+    },
+    TutorialStep {
+        title: "Over to you",
+        text: "That's the tour. Change any slider or load a scenario of your own, and start \
+               the tutorial again from the control panel whenever you like",
+        highlight: Highlight::ControlPanel,
+        advance: Advance::Manual,
+    },
+];
+
+// Where someone is in the tutorial
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Tutorial {
+    pub step: usize,
+    // The simulation step the current tutorial step began on, once seen
+    started_at: Option<u64>,
+}
+
+impl Tutorial {
+    // The scenario the tutorial runs on
+    pub fn scenario() -> Scenario {
+        Scenario::parse(SCENARIO).expect("the tutorial scenario is valid")
+    }
+
+    // None once the tutorial is over
+    pub fn current(&self) -> Option<&'static TutorialStep> {
+        STEPS.get(self.step)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.step >= STEPS.len()
+    }
+
+    pub fn next(&mut self) {
+        self.step = (self.step + 1).min(STEPS.len());
+        self.started_at = None;
+    }
+
+    pub fn back(&mut self) {
+        self.step = self.step.saturating_sub(1);
+        self.started_at = None;
+    }
+
+    // Moves on if the app has done what the current step asks for
+    pub fn update(&mut self, progress: TutorialProgress) {
+        let Some(step) = self.current() else {
+            return;
+        };
+        let started_at = *self.started_at.get_or_insert(progress.step);
+        let done = match step.advance {
+            Advance::Manual => false,
+            Advance::Running => progress.running,
+            Advance::AgentWindowOpen => progress.agent_windows > 0,
+            Advance::Steps(steps) => progress.step >= started_at + steps,
+        };
+        if done {
+            self.next();
+        }
+    }
+}
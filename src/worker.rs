@@ -0,0 +1,147 @@
+use instant::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{AgentType, Intervention, PopulationMetric, SimulationConfig};
+use crate::Simulation;
+
+// What the UI asks of a simulation running off the render thread, such as in
+// a web worker. Messages go both ways as JSON, so either end can be swapped
+// out without the other noticing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum WorkerCommand {
+    // Starts over with a new simulation, paused
+    Reset(Box<SimulationConfig>),
+    Run,
+    Pause,
+    // Advances one step while paused
+    Step,
+    Schedule {
+        step: u64,
+        intervention: Intervention,
+    },
+}
+
+impl WorkerCommand {
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+// How the simulation is doing, small enough to send every slice. The UI shows
+// this in place of the full inspector, which needs the simulation itself
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SimulationSummary {
+    pub step: u64,
+    pub running: bool,
+    // Steps per second over the last slice
+    pub step_rate: f32,
+    pub individuals: usize,
+    pub bots: usize,
+    pub organisations: usize,
+    pub posts: usize,
+    pub interest_entropy: Option<f32>,
+    pub session_length: Option<f32>,
+    pub position_driven: Option<f32>,
+    pub warnings: Vec<String>,
+}
+
+impl SimulationSummary {
+    pub fn capture(simulation: &Simulation, running: bool, step_rate: f32) -> Self {
+        let count = |agent_type: AgentType| {
+            simulation
+                .agents
+                .iter()
+                .filter(|agent| agent.get_type() == agent_type)
+                .count()
+        };
+        let mean = |metric: PopulationMetric| metric.mean_where(simulation, |_| true);
+
+        Self {
+            step: simulation.step_count,
+            running,
+            step_rate,
+            individuals: count(AgentType::Individual),
+            bots: count(AgentType::Bot),
+            organisations: count(AgentType::Organisation),
+            posts: simulation.engine.content_pool.len(),
+            interest_entropy: mean(PopulationMetric::InterestEntropy),
+            session_length: mean(PopulationMetric::SessionLength),
+            position_driven: mean(PopulationMetric::PositionDriven),
+            warnings: simulation
+                .diagnostics
+                .warnings
+                .iter()
+                .map(|warning| warning.message().to_string())
+                .collect(),
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+// The worker's side: a simulation that runs in slices, answering commands
+// between them. Whoever hosts it calls `run_for` repeatedly, yielding in
+// between so commands can arrive, and sends back each summary
+#[derive(Debug, Clone)]
+pub struct WorkerLoop {
+    pub simulation: Simulation,
+    pub running: bool,
+}
+
+impl WorkerLoop {
+    pub fn new(config: SimulationConfig) -> Self {
+        Self {
+            simulation: Simulation::new(config),
+            running: false,
+        }
+    }
+
+    pub fn handle(&mut self, command: WorkerCommand) {
+        match command {
+            WorkerCommand::Reset(config) => *self = Self::new(*config),
+            WorkerCommand::Run => self.running = true,
+            WorkerCommand::Pause => self.running = false,
+            WorkerCommand::Step => {
+                if !self.running {
+                    self.simulation.step();
+                }
+            }
+            WorkerCommand::Schedule { step, intervention } => {
+                self.simulation.schedule_intervention(step, intervention)
+            }
+        }
+    }
+
+    // Steps for up to `budget` if running, always taking at least one step so
+    // slow steps still make progress, and summarises where that got to
+    pub fn run_for(&mut self, budget: Duration) -> SimulationSummary {
+        let started = Instant::now();
+        let mut steps = 0;
+        while self.running && (steps == 0 || started.elapsed() < budget) {
+            self.simulation.step();
+            steps += 1;
+        }
+
+        let elapsed = started.elapsed().as_secs_f32();
+        let step_rate = if steps > 0 && elapsed > 0.0 {
+            steps as f32 / elapsed
+        } else {
+            0.0
+        };
+        self.summary(step_rate)
+    }
+
+    pub fn summary(&self, step_rate: f32) -> SimulationSummary {
+        SimulationSummary::capture(&self.simulation, self.running, step_rate)
+    }
+}
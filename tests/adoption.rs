@@ -0,0 +1,188 @@
+use social_media_sandbox::{
+    metrics::MetricsExport,
+    models::{
+        validate, AdoptionConfig, AdoptionSample, AdoptionSeries, GraphGenerator, PopulationMetric,
+        SimulationConfig,
+    },
+    Simulation,
+};
+
+fn config(adoption: AdoptionConfig, initial_graph: GraphGenerator) -> SimulationConfig {
+    SimulationConfig {
+        seed: 52,
+        num_individuals: 40,
+        num_bots: 2,
+        num_organisations: 1,
+        initial_graph,
+        adoption,
+        ..SimulationConfig::default()
+    }
+}
+
+fn run(adoption: AdoptionConfig, initial_graph: GraphGenerator, ticks: u64) -> Simulation {
+    let mut simulation = Simulation::new(config(adoption, initial_graph));
+    for _ in 0..ticks {
+        simulation.step();
+    }
+    simulation
+}
+
+fn members(simulation: &Simulation) -> usize {
+    simulation
+        .agent_views()
+        .filter(|agent| agent.member == Some(true))
+        .count()
+}
+
+fn sample(step: u64, members: usize) -> AdoptionSample {
+    AdoptionSample {
+        step,
+        individuals: 10,
+        members,
+        joined: 0,
+        left: 0,
+    }
+}
+
+#[test]
+fn ties_on_the_platform_draw_people_in_and_hold_them() {
+    let adoption = AdoptionConfig {
+        join_rate: 0.01,
+        social_join: 0.1,
+        leave_rate: 0.2,
+        ..AdoptionConfig::default()
+    };
+
+    assert!(!AdoptionConfig::default().enabled());
+    assert!(adoption.enabled());
+    assert_eq!(adoption.join_chance(None), 0.01);
+    assert!((adoption.join_chance(Some(0.5)) - 0.06).abs() < 1e-6);
+    assert_eq!(adoption.leave_chance(None), 0.2);
+    assert_eq!(adoption.leave_chance(Some(1.0)), 0.0);
+}
+
+#[test]
+fn everyone_stays_on_the_platform_by_default() {
+    let simulation = run(AdoptionConfig::default(), GraphGenerator::Empty, 100);
+    let latest = simulation.adoption.latest().unwrap();
+
+    assert_eq!(latest.members, 40);
+    assert_eq!(latest.share(), Some(1.0));
+    assert_eq!((latest.joined, latest.left), (0, 0));
+}
+
+#[test]
+fn outsiders_never_come_online() {
+    let simulation = run(
+        AdoptionConfig {
+            initial_members: 0.0,
+            join_rate: 0.0,
+            social_join: 0.0,
+            ..AdoptionConfig::default()
+        },
+        GraphGenerator::Empty,
+        100,
+    );
+
+    assert_eq!(members(&simulation), 0);
+    assert!(PopulationMetric::ScreenTime
+        .values(&simulation)
+        .iter()
+        .all(|&ticks| ticks == 0.0));
+}
+
+#[test]
+fn adoption_spreads_through_the_social_graph() {
+    let adoption = AdoptionConfig {
+        initial_members: 0.1,
+        join_rate: 0.0005,
+        social_join: 0.05,
+        ..AdoptionConfig::default()
+    };
+    let connected = run(
+        adoption.clone(),
+        GraphGenerator::WattsStrogatz { k: 6, beta: 0.1 },
+        600,
+    );
+    let isolated = run(adoption, GraphGenerator::Empty, 600);
+
+    let first = connected.adoption.samples.front().unwrap();
+    let latest = connected.adoption.latest().unwrap();
+    assert!(latest.members > 2 * first.members);
+    assert_eq!(latest.joined, latest.members - first.members);
+    assert!(members(&connected) > members(&isolated));
+
+    let inflection = connected.adoption.inflection().unwrap();
+    assert!(first.step < inflection && inflection <= latest.step);
+}
+
+#[test]
+fn well_connected_populations_hold_together_while_isolated_ones_drain_away() {
+    let adoption = AdoptionConfig {
+        join_rate: 0.0,
+        social_join: 0.0,
+        leave_rate: 0.05,
+        ..AdoptionConfig::default()
+    };
+    let dense = run(adoption.clone(), GraphGenerator::ErdosRenyi { p: 0.3 }, 200);
+    let isolated = run(adoption, GraphGenerator::Empty, 200);
+
+    assert_eq!(members(&dense), 40);
+    assert!(members(&isolated) < 5);
+    assert_eq!(isolated.adoption.left, 40 - members(&isolated));
+    let shares: Vec<_> = isolated
+        .adoption
+        .samples
+        .iter()
+        .map(|sample| sample.members)
+        .collect();
+    assert!(shares.windows(2).all(|pair| pair[1] <= pair[0]));
+}
+
+#[test]
+fn inflection_is_where_membership_grew_fastest() {
+    let mut series = AdoptionSeries::default();
+    assert_eq!(series.inflection(), None);
+    for (step, members) in [(0, 1), (10, 2), (20, 6), (30, 9), (40, 10)] {
+        series.record(sample(step, members));
+    }
+    assert_eq!(series.inflection(), Some(20));
+
+    let simulation = run(
+        AdoptionConfig {
+            initial_members: 0.5,
+            ..AdoptionConfig::default()
+        },
+        GraphGenerator::ErdosRenyi { p: 0.1 },
+        50,
+    );
+    assert_eq!(simulation.retained()["adoption samples"], 6);
+    let mut export = MetricsExport::new();
+    export.record(&simulation);
+    let value = |metric: &str| {
+        export
+            .samples
+            .iter()
+            .find(|sample| sample.metric == metric)
+            .map(|sample| sample.value)
+    };
+    assert_eq!(
+        value("adoption_share"),
+        simulation.adoption.latest().unwrap().share()
+    );
+    assert_eq!(
+        value("platform_joins"),
+        Some(simulation.adoption.joined as f32)
+    );
+
+    let invalid = config(
+        AdoptionConfig {
+            leave_rate: 2.0,
+            ..AdoptionConfig::default()
+        },
+        GraphGenerator::Empty,
+    );
+    assert!(validate(&invalid)
+        .iter()
+        .any(|problem| problem.contains("adoption.leave_rate")));
+}
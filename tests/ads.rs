@@ -0,0 +1,135 @@
+use social_media_sandbox::{
+    metrics::MetricsExport,
+    models::{validate, AdsConfig, RevenueSample, RevenueSeries, SimulationConfig, TradeoffCurve},
+    Simulation,
+};
+
+fn config(per_feed: usize) -> SimulationConfig {
+    SimulationConfig {
+        seed: 31,
+        num_individuals: 8,
+        num_bots: 1,
+        num_organisations: 1,
+        ads: AdsConfig {
+            per_feed,
+            ..AdsConfig::default()
+        },
+        ..SimulationConfig::default()
+    }
+}
+
+fn run(per_feed: usize, ticks: u64) -> Simulation {
+    let mut simulation = Simulation::new(config(per_feed));
+    for _ in 0..ticks {
+        simulation.step();
+    }
+    simulation
+}
+
+fn totals(simulation: &Simulation) -> RevenueSample {
+    RevenueSample::of(simulation.step_count, simulation.agent_views())
+}
+
+#[test]
+fn impressions_are_worth_less_to_tired_and_distracted_agents() {
+    let ads = AdsConfig {
+        revenue_per_impression: 1.0,
+        attention_half_life: 10.0,
+        ..AdsConfig::default()
+    };
+
+    assert_eq!(ads.impression_value(1.0, 0), 1.0);
+    assert_eq!(ads.impression_value(1.0, 10), 0.5);
+    assert_eq!(ads.impression_value(0.5, 0), 0.5);
+    assert_eq!(ads.impression_value(2.0, 0), 1.0);
+}
+
+#[test]
+fn feeds_without_ads_earn_nothing() {
+    let simulation = run(0, 200);
+    let totals = totals(&simulation);
+
+    assert_eq!(totals.impressions, 0);
+    assert_eq!(totals.revenue, 0.0);
+}
+
+#[test]
+fn heavier_ad_loads_serve_more_impressions() {
+    let light = totals(&run(1, 200));
+    let heavy = totals(&run(4, 200));
+
+    assert!(light.impressions > 0);
+    assert!(light.revenue > 0.0);
+    assert_eq!(heavy.impressions % 4, 0);
+    assert!(heavy.impressions > light.impressions);
+}
+
+#[test]
+fn revenue_is_sampled_at_the_interval_and_exported() {
+    let simulation = run(2, 50);
+    let interval = simulation.config.ads.interval;
+
+    let steps: Vec<_> = simulation
+        .revenue
+        .samples
+        .iter()
+        .map(|sample| sample.step)
+        .collect();
+    assert_eq!(
+        steps,
+        (0..=50 / interval)
+            .map(|n| n * interval)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(simulation.retained()["revenue samples"], steps.len());
+
+    let per_step = simulation.revenue.per_step();
+    assert_eq!(per_step.len(), steps.len() - 1);
+    assert!(per_step.iter().all(|(_, revenue)| *revenue >= 0.0));
+
+    let mut export = MetricsExport::new();
+    export.record(&simulation);
+    let value = |metric: &str| {
+        export
+            .samples
+            .iter()
+            .find(|sample| sample.metric == metric)
+            .map(|sample| sample.value)
+    };
+    assert_eq!(
+        value("ad_revenue"),
+        simulation.revenue.latest().map(|sample| sample.revenue)
+    );
+    assert_eq!(
+        value("revenue_per_step"),
+        per_step.last().map(|(_, revenue)| *revenue)
+    );
+}
+
+#[test]
+fn series_drop_the_oldest_samples_first() {
+    let mut series = RevenueSeries::default();
+    for step in 0..600 {
+        series.record(RevenueSample {
+            step,
+            impressions: step as usize,
+            revenue: step as f32,
+        });
+    }
+
+    assert_eq!(series.len(), 500);
+    assert_eq!(series.samples.front().unwrap().step, 100);
+    assert!(series.per_step().iter().all(|(_, revenue)| *revenue == 1.0));
+}
+
+#[test]
+fn ad_loads_that_fill_the_feed_are_rejected_and_loads_can_be_swept() {
+    assert!(validate(&config(9)).is_empty());
+    assert!(validate(&config(10))
+        .iter()
+        .any(|problem| problem.contains("ads.per_feed")));
+
+    let curve = TradeoffCurve::sweep(&config(0), "ads.per_feed", &[0.0, 3.0], 60).unwrap();
+    assert_eq!(curve.points[0].revenue, 0.0);
+    assert!(curve.points[1].revenue > 0.0);
+}
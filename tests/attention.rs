@@ -0,0 +1,125 @@
+use social_media_sandbox::{
+    metrics::MetricsExport,
+    models::{
+        AgentId, AttentionCurve, CascadeAction, CascadeEvent, ContentKind, HalfLifeStats,
+        HalfLives, Intervention, PostId, SimulationConfig,
+    },
+    test_support::PostBuilder,
+    Post, Simulation,
+};
+
+fn read_at(post: &mut Post, steps: &[u64]) {
+    for (reader, &step) in steps.iter().enumerate() {
+        post.cascade.push(CascadeEvent {
+            step,
+            agent_id: AgentId(reader),
+            action: CascadeAction::Read,
+        });
+    }
+}
+
+#[test]
+fn curves_count_reads_by_ticks_since_publication() {
+    let mut post = PostBuilder::new(PostId(0)).published_step(10).build();
+    read_at(&mut post, &[10, 11, 11, 14]);
+    post.cascade.push(CascadeEvent {
+        step: 12,
+        agent_id: AgentId(9),
+        action: CascadeAction::Exposed,
+    });
+
+    let curve = AttentionCurve::of(&post, 20);
+
+    assert_eq!(curve.reads, vec![1, 2, 0, 0, 1, 0, 0, 0, 0, 0]);
+    assert_eq!(curve.total(), 4);
+    assert_eq!(curve.shares()[1], 0.5);
+}
+
+#[test]
+fn half_life_is_when_half_the_reads_had_happened() {
+    let mut post = PostBuilder::new(PostId(0)).published_step(5).build();
+    assert_eq!(AttentionCurve::of(&post, 10).half_life(), None);
+
+    read_at(&mut post, &[6, 6, 8, 9, 12, 15]);
+
+    assert_eq!(AttentionCurve::of(&post, 20).half_life(), Some(3));
+}
+
+#[test]
+fn mean_shares_weight_every_read_post_equally() {
+    let mut popular = PostBuilder::new(PostId(0)).build();
+    read_at(&mut popular, &[0; 10]);
+    let mut niche = PostBuilder::new(PostId(1)).build();
+    read_at(&mut niche, &[1]);
+    let unread = PostBuilder::new(PostId(2)).build();
+
+    let curves: Vec<_> = [popular, niche, unread]
+        .iter()
+        .map(|post| AttentionCurve::of(post, 2))
+        .collect();
+
+    assert_eq!(AttentionCurve::mean_shares(&curves), vec![0.5, 0.5]);
+}
+
+#[test]
+fn stats_summarise_half_lives() {
+    let stats = HalfLifeStats::of(&[4, 1, 3, 10]).unwrap();
+
+    assert_eq!(stats.posts, 4);
+    assert_eq!(stats.mean, 4.5);
+    assert_eq!(stats.median, 3.5);
+    assert_eq!(HalfLifeStats::of(&[]), None);
+}
+
+#[test]
+fn half_lives_are_grouped_and_exported() {
+    let mut simulation = Simulation::new(SimulationConfig {
+        seed: 4,
+        num_individuals: 8,
+        ..SimulationConfig::default()
+    });
+    simulation.schedule_intervention(
+        5,
+        Intervention::InjectPost {
+            creator_id: AgentId(0),
+            tags: vec!["politics".to_string()],
+            length: 10,
+            breaking_ticks: 30,
+            labels: None,
+        },
+    );
+    for _ in 0..150 {
+        simulation.step();
+    }
+
+    let breaking = simulation
+        .engine
+        .content_pool
+        .iter()
+        .find(|post| post.kind == ContentKind::Breaking)
+        .unwrap();
+    assert_eq!(breaking.published_step, 5);
+    assert!(simulation
+        .engine
+        .content_pool
+        .iter()
+        .filter(|post| post.kind == ContentKind::Organic)
+        .any(|post| post.published_step > 0));
+
+    let half_lives = HalfLives::of(&simulation);
+    assert!(half_lives.by_kind.contains_key("organic"));
+    assert!(half_lives.by_creator_type.contains_key("individual"));
+    assert!(!half_lives.by_tag.is_empty());
+
+    let mut export = MetricsExport::new();
+    export.record(&simulation);
+    let exported = export
+        .samples
+        .iter()
+        .find(|sample| {
+            sample.metric == "half_life_by_content_type"
+                && sample.dimensions["content_type"] == "organic"
+        })
+        .unwrap();
+    assert_eq!(exported.value, half_lives.by_kind["organic"].median);
+}
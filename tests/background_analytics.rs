@@ -0,0 +1,54 @@
+use social_media_sandbox::models::{EconomyConfig, Simulation, SimulationConfig};
+
+fn config(background_analytics: bool) -> SimulationConfig {
+    SimulationConfig {
+        seed: 3,
+        num_individuals: 20,
+        num_bots: 2,
+        num_organisations: 2,
+        community_detection_interval: 40,
+        economy: EconomyConfig {
+            tip_chance: 0.5,
+            subscription_fee: 0.1,
+            ..EconomyConfig::default()
+        },
+        background_analytics,
+        ..SimulationConfig::default()
+    }
+}
+
+#[test]
+fn background_analytics_land_when_next_due_and_match_inline() {
+    let mut inline = Simulation::new(config(false));
+    inline.run(100);
+    assert!(inline.analytics.is_idle());
+
+    let mut background = Simulation::new(config(true));
+    background.run(100);
+    // The detection from step 40 was applied at step 80, and the one from
+    // step 80 is still out, as is the wealth sample from step 100
+    assert_eq!(background.communities.as_ref().map(|c| c.step), Some(40));
+    assert_eq!(background.bridges.len(), 1);
+    assert_eq!(background.wealth.len(), inline.wealth.len() - 1);
+    assert!(!background.analytics.is_idle());
+
+    // A copy waits on the same work rather than losing it
+    let mut copy = background.clone();
+    background.flush_analytics();
+    copy.flush_analytics();
+    assert!(background.analytics.is_idle());
+
+    for simulation in [&background, &copy] {
+        assert_eq!(simulation.communities, inline.communities);
+        assert_eq!(simulation.bridges, inline.bridges);
+        assert_eq!(simulation.wealth, inline.wealth);
+        assert!(simulation
+            .agent_views()
+            .zip(inline.agent_views())
+            .all(|(a, b)| a.community == b.community));
+    }
+    assert_eq!(
+        background.engine.content_pool.len(),
+        inline.engine.content_pool.len()
+    );
+}
@@ -0,0 +1,77 @@
+use social_media_sandbox::{
+    bundle::{BundleError, RunBundle, BUNDLE_FORMAT_VERSION},
+    metrics::MetricsExport,
+    models::{AgentId, Intervention, SimulationConfig},
+    Simulation,
+};
+use std::io::Cursor;
+
+fn run() -> (Simulation, MetricsExport) {
+    let mut simulation = Simulation::new(SimulationConfig {
+        seed: 7,
+        num_individuals: 5,
+        ..SimulationConfig::default()
+    });
+    simulation.schedule_intervention(1, Intervention::BanCreator(AgentId(0)));
+    simulation.schedule_intervention(50, Intervention::BanCreator(AgentId(1)));
+
+    let mut metrics = MetricsExport::new();
+    for _ in 0..3 {
+        simulation.step();
+        metrics.record(&simulation);
+    }
+    (simulation, metrics)
+}
+
+fn write(bundle: &RunBundle) -> Vec<u8> {
+    let mut bytes = Cursor::new(Vec::new());
+    bundle.write_to(&mut bytes).unwrap();
+    bytes.into_inner()
+}
+
+#[test]
+fn bundles_round_trip() {
+    let (simulation, metrics) = run();
+    let bundle = RunBundle::capture(&simulation, &metrics);
+
+    let loaded = RunBundle::read_from(Cursor::new(write(&bundle))).unwrap();
+
+    assert_eq!(loaded.manifest, bundle.manifest);
+    assert_eq!(loaded.manifest.seed, 7);
+    assert_eq!(loaded.manifest.step, 3);
+    assert_eq!(loaded.config.num_individuals, 5);
+    assert_eq!(loaded.events.applied, simulation.interventions.applied);
+    assert_eq!(loaded.events.queued, simulation.interventions.queued);
+    assert_eq!(loaded.snapshots, bundle.snapshots);
+    assert_eq!(loaded.metrics, metrics);
+    assert!(loaded.warnings.is_empty(), "{:?}", loaded.warnings);
+}
+
+#[test]
+fn reloaded_runs_schedule_every_intervention_again() {
+    let (simulation, metrics) = run();
+    let bundle = RunBundle::capture(&simulation, &metrics);
+
+    let reloaded = bundle.simulation();
+
+    assert_eq!(reloaded.config.seed, 7);
+    assert_eq!(reloaded.step_count, 0);
+    let steps: Vec<u64> = reloaded
+        .interventions
+        .queued
+        .iter()
+        .map(|scheduled| scheduled.step)
+        .collect();
+    assert_eq!(steps, vec![1, 50]);
+}
+
+#[test]
+fn bundles_from_newer_formats_are_refused() {
+    let (simulation, metrics) = run();
+    let mut bundle = RunBundle::capture(&simulation, &metrics);
+    bundle.manifest.format_version = BUNDLE_FORMAT_VERSION + 1;
+
+    let result = RunBundle::read_from(Cursor::new(write(&bundle)));
+
+    assert!(matches!(result, Err(BundleError::Unsupported(_))));
+}
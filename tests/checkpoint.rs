@@ -0,0 +1,134 @@
+use social_media_sandbox::{
+    bundle::{import_bundle, latest_checkpoint, write_checkpoint, RunBundle},
+    metrics::MetricsExport,
+    models::{AgentId, GraphGenerator, Intervention, SimulationConfig},
+    Simulation,
+};
+use std::io::Cursor;
+
+const TICKS: u64 = 300;
+const INTERRUPTED_AT: u64 = 120;
+
+fn config() -> SimulationConfig {
+    SimulationConfig {
+        seed: 11,
+        num_individuals: 12,
+        initial_graph: GraphGenerator::ErdosRenyi { p: 0.2 },
+        community_detection_interval: 40,
+        ..SimulationConfig::default()
+    }
+}
+
+fn simulation() -> Simulation {
+    let mut simulation = Simulation::new(config());
+    simulation.schedule_intervention(60, Intervention::BanCreator(AgentId(2)));
+    simulation.schedule_intervention(200, Intervention::BanCreator(AgentId(3)));
+    simulation
+}
+
+fn run_to(simulation: &mut Simulation, metrics: &mut MetricsExport, ticks: u64) {
+    while simulation.step_count < ticks {
+        simulation.step();
+        if simulation.step_count % 10 == 0 {
+            metrics.record(simulation);
+        }
+    }
+}
+
+// Everything observable about a run: every post with its readers and
+// comments, and every agent's state, interests and history
+fn fingerprint(simulation: &Simulation) -> String {
+    format!(
+        "{:?}\n{:?}\n{:?}",
+        simulation.engine.content_pool,
+        simulation.agent_views().collect::<Vec<_>>(),
+        simulation.communities
+    )
+}
+
+#[test]
+fn runs_with_the_same_seed_are_identical() {
+    let (mut a, mut b) = (simulation(), simulation());
+
+    for _ in 0..TICKS {
+        a.step();
+        b.step();
+        assert_eq!(fingerprint(&a), fingerprint(&b), "step {}", a.step_count);
+    }
+    assert!(!a.engine.content_pool.is_empty());
+}
+
+#[test]
+fn resumed_runs_match_uninterrupted_ones() {
+    let mut uninterrupted = simulation();
+    let mut uninterrupted_metrics = MetricsExport::new();
+    run_to(&mut uninterrupted, &mut uninterrupted_metrics, TICKS);
+
+    // Interrupted partway, with only the checkpoint surviving
+    let mut interrupted = simulation();
+    let mut metrics = MetricsExport::new();
+    run_to(&mut interrupted, &mut metrics, INTERRUPTED_AT);
+    let mut bytes = Cursor::new(Vec::new());
+    RunBundle::capture(&interrupted, &metrics)
+        .write_to(&mut bytes)
+        .unwrap();
+    drop(interrupted);
+
+    let bundle = RunBundle::read_from(Cursor::new(bytes.into_inner())).unwrap();
+    let mut resumed = bundle.resume();
+    let mut resumed_metrics = bundle.metrics;
+    assert_eq!(resumed.step_count, INTERRUPTED_AT);
+    run_to(&mut resumed, &mut resumed_metrics, TICKS);
+
+    assert_eq!(fingerprint(&resumed), fingerprint(&uninterrupted));
+    assert_eq!(resumed_metrics, uninterrupted_metrics);
+    assert_eq!(
+        resumed.interventions.applied,
+        uninterrupted.interventions.applied
+    );
+}
+
+#[test]
+fn the_latest_checkpoint_is_resumed() {
+    let dir = std::env::temp_dir().join(format!("sandbox-checkpoints-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    assert_eq!(latest_checkpoint(&dir).ok().flatten(), None);
+
+    let mut simulation = simulation();
+    let mut metrics = MetricsExport::new();
+    for ticks in [5, 20, 150] {
+        run_to(&mut simulation, &mut metrics, ticks);
+        write_checkpoint(&simulation, &metrics, &dir).unwrap();
+    }
+
+    let latest = latest_checkpoint(&dir).unwrap().unwrap();
+    let bundle = import_bundle(&latest).unwrap();
+    assert_eq!(bundle.manifest.step, 150);
+    assert_eq!(fingerprint(&bundle.resume()), fingerprint(&simulation));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn slowing_the_tick_rate_never_turns_the_clock_back() {
+    let mut simulation = Simulation::new(SimulationConfig {
+        tick_rate_ms: 1000,
+        ..config()
+    });
+    simulation.run(100);
+    let before = simulation.engine.now();
+    simulation.set_config(SimulationConfig {
+        tick_rate_ms: 100,
+        ..simulation.config.clone()
+    });
+    simulation.run(10);
+
+    // Ten steps at a tenth of a second each, on top of the time already run
+    assert_eq!(simulation.engine.now(), before + 1);
+    let now = simulation.engine.now();
+    assert!(simulation
+        .engine
+        .content_pool
+        .iter()
+        .all(|post| post.timestamp <= now));
+}
@@ -0,0 +1,253 @@
+use social_media_sandbox::{
+    classroom::{Classroom, HostMessage, StudentMessage, Verdict},
+    models::{AgentId, Intervention, RankingWeight, SimulationConfig},
+    Simulation,
+};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+fn simulation() -> Simulation {
+    Simulation::new(SimulationConfig {
+        seed: 3,
+        num_individuals: 6,
+        num_bots: 1,
+        num_organisations: 1,
+        ..SimulationConfig::default()
+    })
+}
+
+// Messages as the host receives them, having been through JSON
+fn send(classroom: &mut Classroom, client: u64, message: StudentMessage, step: u64) {
+    classroom.handle_json(client, &message.to_json().unwrap(), step);
+}
+
+fn join(classroom: &mut Classroom, client: u64, name: &str) {
+    send(
+        classroom,
+        client,
+        StudentMessage::Join {
+            name: name.to_string(),
+        },
+        0,
+    );
+}
+
+fn inject(creator: usize) -> StudentMessage {
+    StudentMessage::Submit {
+        intervention: Intervention::InjectPost {
+            creator_id: AgentId(creator),
+            tags: vec!["sports".to_string()],
+            length: 20,
+            breaking_ticks: 0,
+            labels: None,
+        },
+        note: "see whether sport spreads".to_string(),
+    }
+}
+
+// Replies as the client receives them
+fn replies(classroom: &mut Classroom) -> Vec<(u64, HostMessage)> {
+    classroom
+        .drain_outbox()
+        .into_iter()
+        .map(|(client, message)| {
+            let json = message.to_json().unwrap();
+            (client, HostMessage::from_json(&json).unwrap())
+        })
+        .collect()
+}
+
+#[test]
+fn students_must_join_and_stick_to_posts_and_weights() {
+    let mut classroom = Classroom::default();
+    send(&mut classroom, 1, inject(0), 0);
+    join(&mut classroom, 2, "Ada");
+    send(
+        &mut classroom,
+        2,
+        StudentMessage::Submit {
+            intervention: Intervention::BanCreator(AgentId(0)),
+            note: String::new(),
+        },
+        0,
+    );
+    classroom.handle_json(2, "{\"Shout\":1}", 0);
+
+    let replies = replies(&mut classroom);
+    assert!(matches!(replies[0], (1, HostMessage::Error(_))));
+    assert_eq!(replies[1], (2, HostMessage::Joined));
+    assert!(matches!(replies[2], (2, HostMessage::Error(_))));
+    assert!(matches!(replies[3], (2, HostMessage::Error(_))));
+    assert!(classroom.pending.is_empty());
+}
+
+#[test]
+fn approved_submissions_reach_the_simulation() {
+    let mut simulation = simulation();
+    for _ in 0..5 {
+        simulation.step();
+    }
+    let mut classroom = Classroom::default();
+    join(&mut classroom, 1, "Ada");
+    join(&mut classroom, 2, "Grace");
+    send(&mut classroom, 1, inject(0), simulation.step_count);
+    send(
+        &mut classroom,
+        2,
+        StudentMessage::Submit {
+            intervention: Intervention::SetWeight {
+                weight: RankingWeight::Recency,
+                value: 0.9,
+            },
+            note: String::new(),
+        },
+        simulation.step_count,
+    );
+    replies(&mut classroom);
+    assert_eq!(classroom.pending.len(), 2);
+    assert_eq!(classroom.pending[0].student, "Ada");
+
+    assert!(classroom.approve(0, &mut simulation));
+    assert!(classroom.reject(1));
+    assert!(!classroom.approve(1, &mut simulation));
+    assert_eq!(
+        replies(&mut classroom),
+        [
+            (
+                1,
+                HostMessage::Approved {
+                    submission: 0,
+                    step: 5
+                }
+            ),
+            (2, HostMessage::Rejected(1)),
+        ]
+    );
+    assert_eq!(classroom.decided[0].1, Verdict::Approved(5));
+
+    let posts = simulation.engine.content_pool.len();
+    simulation.step();
+    assert_eq!(simulation.interventions.applied.len(), 1);
+    assert!(simulation.engine.content_pool.len() > posts);
+    assert_ne!(simulation.engine.config.recency_weight, 0.9);
+}
+
+#[test]
+fn students_who_leave_keep_their_submissions_but_hear_nothing() {
+    let mut simulation = simulation();
+    let mut classroom = Classroom::default();
+    join(&mut classroom, 1, "Ada");
+    join(&mut classroom, 2, "Grace");
+    send(&mut classroom, 1, inject(0), 0);
+    classroom.leave(1);
+    replies(&mut classroom);
+
+    assert!(classroom.approve(0, &mut simulation));
+    classroom.broadcast(&Default::default());
+    let replies = replies(&mut classroom);
+    assert_eq!(replies.len(), 1);
+    assert!(matches!(replies[0], (2, HostMessage::Summary(_))));
+}
+
+// A student's client of `sandbox classroom`, speaking JSON a line at a time
+struct Client {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Client {
+    fn connect(address: &str) -> Self {
+        let writer = TcpStream::connect(address).unwrap();
+        // A host that never answers fails the test rather than hanging it
+        writer
+            .set_read_timeout(Some(Duration::from_secs(10)))
+            .unwrap();
+        Self {
+            reader: BufReader::new(writer.try_clone().unwrap()),
+            writer,
+        }
+    }
+
+    fn send(&mut self, message: StudentMessage) {
+        writeln!(self.writer, "{}", message.to_json().unwrap()).unwrap();
+    }
+
+    fn receive(&mut self) -> HostMessage {
+        let mut line = String::new();
+        self.reader.read_line(&mut line).unwrap();
+        HostMessage::from_json(&line).unwrap()
+    }
+}
+
+#[test]
+fn the_sandbox_hosts_a_class_over_tcp() {
+    let dir = std::env::temp_dir().join(format!("sandbox-classroom-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let scenario = dir.join("class.toml");
+    std::fs::write(
+        &scenario,
+        "seed = 3\nnum_individuals = 6\nnum_bots = 1\nnum_organisations = 1\n",
+    )
+    .unwrap();
+
+    let mut host = Command::new(env!("CARGO_BIN_EXE_sandbox"))
+        .arg("classroom")
+        .arg(&scenario)
+        .args(["--port", "0"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut output = BufReader::new(host.stdout.take().unwrap());
+    let mut listening = String::new();
+    output.read_line(&mut listening).unwrap();
+    let address = listening
+        .trim()
+        .strip_prefix("listening on ")
+        .unwrap()
+        .to_string();
+
+    let mut alice = Client::connect(&address);
+    let mut bob = Client::connect(&address);
+    for (client, name) in [(&mut alice, "alice"), (&mut bob, "bob")] {
+        client.send(StudentMessage::Join {
+            name: name.to_string(),
+        });
+        assert_eq!(client.receive(), HostMessage::Joined);
+    }
+    alice.send(inject(0));
+    assert_eq!(alice.receive(), HostMessage::Received(0));
+    bob.send(StudentMessage::Submit {
+        intervention: Intervention::SetWeight {
+            weight: RankingWeight::Engagement,
+            value: 0.9,
+        },
+        note: String::new(),
+    });
+    assert_eq!(bob.receive(), HostMessage::Received(1));
+
+    let mut teacher = host.stdin.take().unwrap();
+    writeln!(teacher, "approve 0\nreject 1\nstep 3").unwrap();
+    assert_eq!(
+        alice.receive(),
+        HostMessage::Approved {
+            submission: 0,
+            step: 0
+        }
+    );
+    assert_eq!(bob.receive(), HostMessage::Rejected(1));
+    // Everyone hears how the simulation is doing after it steps
+    for client in [&mut alice, &mut bob] {
+        match client.receive() {
+            HostMessage::Summary(summary) => assert_eq!(summary.step, 3),
+            other => panic!("expected a summary, got {other:?}"),
+        }
+    }
+
+    writeln!(teacher, "quit").unwrap();
+    assert!(host.wait().unwrap().success());
+    let _ = std::fs::remove_dir_all(&dir);
+}
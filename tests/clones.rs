@@ -0,0 +1,107 @@
+use social_media_sandbox::{
+    models::{AgentId, AgentType, Intervention, SimulationConfig},
+    Simulation,
+};
+
+fn config() -> SimulationConfig {
+    SimulationConfig {
+        seed: 6,
+        num_individuals: 10,
+        num_bots: 2,
+        num_organisations: 1,
+        ..SimulationConfig::default()
+    }
+}
+
+fn cloned(steps: u64) -> Simulation {
+    let mut simulation = Simulation::new(config());
+    let individual = *simulation
+        .agents_of(AgentType::Individual)
+        .iter()
+        .next()
+        .unwrap();
+    let bot = *simulation.agents_of(AgentType::Bot).iter().next().unwrap();
+    simulation.schedule_intervention(
+        20,
+        Intervention::CloneAgent {
+            agent_id: individual,
+            copies: 3,
+        },
+    );
+    simulation.schedule_intervention(
+        20,
+        Intervention::CloneAgent {
+            agent_id: bot,
+            copies: 2,
+        },
+    );
+    simulation.schedule_intervention(
+        20,
+        Intervention::CloneAgent {
+            agent_id: AgentId(9999),
+            copies: 4,
+        },
+    );
+    simulation.run(steps);
+    simulation
+}
+
+#[test]
+fn clones_join_under_fresh_ids_with_the_original_profile() {
+    let simulation = cloned(100);
+    assert_eq!(simulation.agent_views().count(), 13 + 5);
+
+    let applied = &simulation.interventions.applied;
+    assert_eq!(applied.len(), 3);
+    let [individuals, bots, missing] = [0, 1, 2].map(|i| applied[i].created.clone());
+    // IDs carry on from those already given out, and nothing is added for an
+    // agent that isn't there
+    assert_eq!(individuals.len(), 3);
+    assert_eq!(bots.len(), 2);
+    assert!(missing.is_empty());
+    assert!(individuals
+        .iter()
+        .chain(&bots)
+        .all(|id| id.0 >= 13 && simulation.interest_history(*id).is_some()));
+    assert!(individuals.windows(2).all(|pair| pair[0] < pair[1]));
+
+    let Intervention::CloneAgent { agent_id: bot, .. } = applied[1].intervention else {
+        panic!("expected the bot's clone to be applied second");
+    };
+    let original = simulation.agent_view(bot).unwrap();
+    for &id in &bots {
+        let clone = simulation.agent_view(id).unwrap();
+        assert_eq!(clone.agent_type, AgentType::Bot);
+        // Bots keep to their profile, so the clones still match it
+        assert_eq!(
+            clone.interest_profile.to_table(),
+            original.interest_profile.to_table()
+        );
+        assert!(simulation
+            .engine
+            .content_pool
+            .iter()
+            .any(|post| post.creator_id == id));
+    }
+    for &id in &individuals {
+        assert_eq!(
+            simulation.agent_view(id).unwrap().agent_type,
+            AgentType::Individual
+        );
+    }
+}
+
+#[test]
+fn cloning_is_reproducible() {
+    let first = cloned(60);
+    let second = cloned(60);
+    assert_eq!(first.interventions.applied, second.interventions.applied);
+    let ids = |simulation: &Simulation| -> Vec<AgentId> {
+        simulation.agent_views().map(|view| view.id).collect()
+    };
+    assert_eq!(ids(&first), ids(&second));
+    assert_eq!(
+        first.engine.content_pool.len(),
+        second.engine.content_pool.len()
+    );
+}
@@ -0,0 +1,225 @@
+use std::collections::BTreeMap;
+
+use social_media_sandbox::{
+    metrics::MetricsExport,
+    models::{
+        assign_cohort, length_weight, validate, ActiveHours, AgentId, AgentState, AgentView,
+        Cohort, CohortStats, SimulationConfig,
+    },
+    Simulation,
+};
+
+fn cohort(name: &str, short_form_preference: f32, susceptibility: f32) -> Cohort {
+    Cohort {
+        name: name.to_string(),
+        active_hours: ActiveHours::default(),
+        short_form_preference,
+        susceptibility,
+    }
+}
+
+fn config(cohorts: Vec<Cohort>, fractions: &[(&str, f32)]) -> SimulationConfig {
+    SimulationConfig {
+        seed: 71,
+        num_individuals: 30,
+        num_bots: 4,
+        num_organisations: 2,
+        cohorts,
+        cohort_fractions: fractions
+            .iter()
+            .map(|(name, fraction)| (name.to_string(), *fraction))
+            .collect(),
+        ..SimulationConfig::default()
+    }
+}
+
+fn run(config: SimulationConfig, ticks: u64) -> Simulation {
+    let mut simulation = Simulation::new(config);
+    for _ in 0..ticks {
+        simulation.step();
+    }
+    simulation
+}
+
+// Reading can add tags at no weight, which doesn't move anything
+fn interests(agent: &AgentView) -> Vec<(String, f32, f32)> {
+    agent
+        .interest_profile
+        .interests
+        .iter()
+        .filter(|(_, topic)| topic.weighted_interest > 0.0)
+        .map(|(tag, topic)| (tag.clone(), topic.weighted_interest, topic.agreement))
+        .collect()
+}
+
+#[test]
+fn active_hours_scale_coming_online_by_time_of_day() {
+    let evenings = ActiveHours {
+        start: 0.75,
+        end: 0.25,
+        off_peak: 0.1,
+    };
+
+    assert_eq!(ActiveHours::default().activity(37, 100), 1.0);
+    assert_eq!(evenings.activity(80, 100), 1.0);
+    assert_eq!(evenings.activity(110, 100), 1.0);
+    assert_eq!(evenings.activity(50, 100), 0.1);
+    assert_eq!(evenings.activity(50, 0), 1.0);
+
+    assert_eq!(length_weight(30, 60, 2.0), 1.0);
+    assert!(length_weight(5, 60, 2.0) > 1.0);
+    assert!(length_weight(5, 60, -2.0) < 1.0);
+    assert_eq!(length_weight(60, 60, 0.0), 1.0);
+}
+
+#[test]
+fn cohorts_are_drawn_by_fraction() {
+    let cohorts = Cohort::library();
+    let fractions = BTreeMap::from([("teen".to_string(), 0.3), ("senior".to_string(), 0.3)]);
+    let assigned: Vec<_> = (0..300)
+        .map(|id| assign_cohort(&cohorts, &fractions, 5, AgentId(id)).map(|c| c.name.as_str()))
+        .collect();
+    let count = |name| assigned.iter().filter(|c| **c == name).count();
+
+    assert!((60..120).contains(&count(Some("teen"))));
+    assert!((60..120).contains(&count(Some("senior"))));
+    assert!((90..150).contains(&count(None)));
+    assert_eq!(count(Some("adult")), 0);
+    assert_eq!(
+        assign_cohort(&cohorts, &fractions, 5, AgentId(9)).map(|c| c.name.as_str()),
+        assigned[9]
+    );
+    assert!(assign_cohort(&cohorts, &BTreeMap::new(), 5, AgentId(0)).is_none());
+
+    let simulation = run(config(Cohort::library(), &[]), 0);
+    assert!(simulation.agent_views().all(|agent| agent.cohort.is_none()));
+    assert!(CohortStats::of(&simulation).is_empty());
+}
+
+#[test]
+fn individuals_come_online_mostly_in_their_active_hours() {
+    let mut daytime = cohort("daytime", 0.0, 1.0);
+    daytime.active_hours = ActiveHours {
+        start: 0.0,
+        end: 0.5,
+        off_peak: 0.05,
+    };
+    let mut simulation = Simulation::new(SimulationConfig {
+        steps_per_day: 100,
+        ..config(vec![daytime], &[("daytime", 1.0)])
+    });
+
+    let (mut day, mut night) = (0, 0);
+    for _ in 0..400 {
+        simulation.step();
+        let online = simulation
+            .agent_views()
+            .filter(|agent| agent.cohort.is_some() && !matches!(agent.state, AgentState::Offline))
+            .count();
+        if simulation.engine.current_step % 100 < 50 {
+            day += online;
+        } else {
+            night += online;
+        }
+    }
+
+    assert!(day > 2 * night, "{day} by day, {night} by night");
+}
+
+#[test]
+fn susceptible_cohorts_have_their_interests_moved_further() {
+    let config = config(
+        vec![cohort("unmoved", 0.0, 0.0), cohort("moved", 0.0, 3.0)],
+        &[("unmoved", 0.5), ("moved", 0.5)],
+    );
+    let start = Simulation::new(config.clone());
+    let simulation = run(config, 300);
+
+    let mut changed = BTreeMap::<&str, usize>::new();
+    for (before, after) in start.agent_views().zip(simulation.agent_views()) {
+        let Some(cohort) = after.cohort else {
+            continue;
+        };
+        if interests(&before) != interests(&after) {
+            *changed.entry(cohort).or_default() += 1;
+        }
+    }
+
+    assert_eq!(changed.get("unmoved"), None);
+    assert!(changed["moved"] > 0);
+}
+
+#[test]
+fn short_form_cohorts_read_shorter_posts() {
+    let simulation = run(
+        config(
+            vec![cohort("short", 6.0, 1.0), cohort("long", -6.0, 1.0)],
+            &[("short", 0.5), ("long", 0.5)],
+        ),
+        400,
+    );
+    let cohorts: BTreeMap<_, _> = simulation
+        .agent_views()
+        .filter_map(|agent| agent.cohort.map(|cohort| (agent.id, cohort)))
+        .collect();
+
+    let mut lengths = BTreeMap::<&str, (f32, usize)>::new();
+    for post in &simulation.engine.content_pool {
+        for reader in &post.readers {
+            if let Some(cohort) = cohorts.get(reader) {
+                let (total, count) = lengths.entry(cohort).or_default();
+                *total += post.length as f32;
+                *count += 1;
+            }
+        }
+    }
+    let mean = |cohort| {
+        let (total, count) = lengths[cohort];
+        total / count as f32
+    };
+
+    assert!(mean("short") < mean("long"));
+}
+
+#[test]
+fn cohort_outcomes_are_exported_and_validated() {
+    let simulation = run(
+        config(Cohort::library(), &[("teen", 0.5), ("senior", 0.5)]),
+        200,
+    );
+    let stats = CohortStats::of(&simulation);
+    assert_eq!(
+        stats.values().map(|stats| stats.individuals).sum::<usize>(),
+        30
+    );
+    assert!(stats
+        .values()
+        .all(|stats| stats.exposure_diversity.is_some()));
+
+    let mut export = MetricsExport::new();
+    export.record(&simulation);
+    let diversity: Vec<_> = export
+        .samples
+        .iter()
+        .filter(|sample| sample.metric == "cohort_exposure_diversity")
+        .map(|sample| sample.dimensions["cohort"].as_str())
+        .collect();
+    assert_eq!(diversity, ["senior", "teen"]);
+
+    let mut config = simulation.config.clone();
+    config.cohort_fractions.insert("toddler".to_string(), 0.5);
+    config.cohorts[0].active_hours.off_peak = 2.0;
+    config.steps_per_day = 0;
+    let problems = validate(&config);
+    for key in [
+        "cohort_fractions` add up",
+        "unknown cohort `toddler`",
+        "cohorts.teen.active_hours.off_peak",
+        "steps_per_day",
+    ] {
+        assert!(
+            problems.iter().any(|problem| problem.contains(key)),
+            "{key}"
+        );
+    }
+}
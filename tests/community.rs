@@ -0,0 +1,136 @@
+use social_media_sandbox::models::{AgentId, GraphGenerator, Simulation, SimulationConfig};
+
+// Three blocks with no follows between them
+fn three_cliques() -> Simulation {
+    Simulation::new(SimulationConfig {
+        seed: 1,
+        num_individuals: 12,
+        num_bots: 0,
+        num_organisations: 0,
+        initial_graph: GraphGenerator::StochasticBlock {
+            communities: 3,
+            p_in: 1.0,
+            p_out: 0.0,
+        },
+        ..Default::default()
+    })
+}
+
+#[test]
+fn disconnected_cliques_are_separate_communities() {
+    let mut simulation = three_cliques();
+    simulation.detect_communities();
+
+    let communities = simulation.communities.as_ref().unwrap();
+    assert_eq!(communities.stats.len(), 3);
+    assert!(communities
+        .stats
+        .iter()
+        .all(|community| community.size == 4));
+
+    // Agents are numbered into blocks in the order they were created
+    let agents: Vec<_> = simulation.agent_views().collect();
+    for (i, a) in agents.iter().enumerate() {
+        for (j, b) in agents.iter().enumerate() {
+            assert_eq!(a.community == b.community, i / 4 == j / 4);
+        }
+    }
+}
+
+#[test]
+fn unconnected_agents_are_communities_of_one_numbered_after_larger_ones() {
+    let mut simulation = three_cliques();
+    // Break up the last block entirely
+    for agent in simulation.agents.iter_mut().skip(8) {
+        agent.preferred_creators_mut().unwrap().clear();
+    }
+    simulation.detect_communities();
+
+    let communities = simulation.communities.as_ref().unwrap();
+    let sizes: Vec<usize> = communities.stats.iter().map(|c| c.size).collect();
+    assert_eq!(sizes, vec![4, 4, 1, 1, 1, 1]);
+    assert_eq!(communities.stats[0].label, 0);
+    assert!(simulation
+        .agent_views()
+        .skip(8)
+        .all(|agent| agent.community >= Some(2)));
+}
+
+#[test]
+fn communities_are_detected_on_the_configured_interval() {
+    let mut simulation = Simulation::new(SimulationConfig {
+        seed: 1,
+        num_individuals: 5,
+        num_bots: 0,
+        num_organisations: 0,
+        community_detection_interval: 2,
+        ..Default::default()
+    });
+
+    simulation.step();
+    assert!(simulation.communities.is_none());
+
+    simulation.step();
+    simulation.step();
+    assert_eq!(simulation.communities.as_ref().map(|c| c.step), Some(2));
+}
+
+// Two cliques of four with one agent following an agent in each
+fn joined_cliques() -> Simulation {
+    let mut simulation = Simulation::new(SimulationConfig {
+        seed: 1,
+        num_individuals: 9,
+        num_bots: 0,
+        num_organisations: 0,
+        ..Default::default()
+    });
+    let ids: Vec<AgentId> = simulation.agent_views().map(|agent| agent.id).collect();
+    let mut follow = |a: usize, b: usize| {
+        let follows = simulation.agents[a].preferred_creators_mut().unwrap();
+        follows.insert(ids[b], 1.0);
+    };
+    for block in [0..4, 4..8] {
+        for a in block.clone() {
+            for b in block.clone() {
+                if a != b {
+                    follow(a, b);
+                }
+            }
+        }
+    }
+    follow(8, 0);
+    follow(8, 4);
+    simulation
+}
+
+#[test]
+fn the_agent_joining_two_cliques_is_a_bridge() {
+    let mut simulation = joined_cliques();
+    simulation.detect_communities();
+
+    let bridge_id = simulation.agent_views().nth(8).unwrap().id;
+    let bridges = simulation.bridges.last().unwrap();
+    let found: Vec<AgentId> = bridges
+        .bridges
+        .iter()
+        .map(|bridge| bridge.agent_id)
+        .collect();
+
+    // The agents it follows sit on those paths too, so may count as well
+    assert!(found.contains(&bridge_id));
+    assert!(bridges.bridges[0].betweenness > 0.4);
+    assert!(bridges.influence() > 0.0);
+    assert!(bridges
+        .bridges
+        .iter()
+        .all(|bridge| bridge.communities_reached >= 2));
+}
+
+#[test]
+fn disconnected_cliques_have_no_bridges() {
+    let mut simulation = three_cliques();
+    simulation.detect_communities();
+
+    assert_eq!(simulation.bridges.len(), 1);
+    assert!(simulation.bridges[0].bridges.is_empty());
+}
@@ -0,0 +1,97 @@
+use nalgebra::DVector;
+use social_media_sandbox::{
+    engine::Inconsistency,
+    models::{AgentId, CascadeAction, CascadeEvent, CommentId, PostId, SimulationConfig, Topic},
+    test_support::EngineFixture,
+    Simulation,
+};
+
+#[test]
+fn a_running_simulation_stays_consistent() {
+    let mut simulation = Simulation::new(SimulationConfig {
+        seed: 5,
+        num_individuals: 10,
+        num_bots: 2,
+        num_organisations: 1,
+        ..SimulationConfig::default()
+    });
+    simulation.run(100);
+
+    assert!(simulation.engine.content_pool.len() > 1);
+    assert_eq!(simulation.engine.verify(), []);
+    assert_eq!(simulation.engine.rebuild_indices(), []);
+}
+
+#[test]
+fn rebuilding_repairs_derived_state_and_reports_the_rest() {
+    let mut engine = EngineFixture::with_posts(3)
+        .with_tags(&["science", "sports"])
+        .with_comments(1)
+        .build();
+    let second = engine.content_pool[1].id;
+    assert_eq!(engine.verify(), []);
+
+    // Derived state gone wrong
+    engine.index_to_tag.remove(&1);
+    engine.content_pool[0].interest_profile.interests.insert(
+        "health".to_string(),
+        Topic {
+            weighted_interest: 0.5,
+            agreement: 0.0,
+        },
+    );
+    engine.content_pool[1].comments[0]
+        .interest_profile
+        .vector_representation = DVector::zeros(3);
+    engine.mark_breaking(PostId(99), 10);
+    // Content gone wrong
+    let duplicate = engine.content_pool[2].clone();
+    engine.content_pool.push(duplicate.clone());
+    engine.content_pool[1].cascade.push(CascadeEvent {
+        step: 0,
+        agent_id: AgentId(4),
+        action: CascadeAction::Commented(CommentId(99)),
+    });
+
+    let comment = engine.content_pool[1].comments[0].id;
+    assert_eq!(
+        engine.verify(),
+        [
+            Inconsistency::DuplicatePost(duplicate.id),
+            Inconsistency::DuplicateComment(duplicate.comments[0].id),
+            Inconsistency::TagIndexMismatch("sports".to_string()),
+            Inconsistency::UnregisteredTag("health".to_string()),
+            Inconsistency::StaleVector {
+                post_id: second,
+                comment_id: Some(comment),
+            },
+            Inconsistency::MissingComment {
+                post_id: second,
+                comment_id: CommentId(99),
+            },
+            Inconsistency::OrphanedPostState(PostId(99)),
+        ]
+    );
+
+    assert_eq!(
+        engine.rebuild_indices(),
+        [
+            Inconsistency::DuplicatePost(duplicate.id),
+            Inconsistency::DuplicateComment(duplicate.comments[0].id),
+            Inconsistency::MissingComment {
+                post_id: second,
+                comment_id: CommentId(99),
+            },
+        ]
+    );
+    // Consistent indices are kept
+    assert_eq!(engine.tag_to_index["science"], 0);
+    let health = engine.tag_to_index["health"];
+    assert_eq!(engine.index_to_tag[&health], "health");
+    assert!(
+        engine.content_pool[0]
+            .interest_profile
+            .vector_representation[health]
+            > 0.0
+    );
+}
@@ -0,0 +1,132 @@
+use social_media_sandbox::{
+    models::{
+        AgentId, CascadeAction, CascadeEvent, CommentId, FollowerHistory, GraphGenerator, PostId,
+        SimulationConfig,
+    },
+    test_support::PostBuilder,
+    Simulation,
+};
+
+fn simulation(initial_graph: GraphGenerator) -> Simulation {
+    Simulation::new(SimulationConfig {
+        seed: 2,
+        num_individuals: 4,
+        num_bots: 1,
+        num_organisations: 1,
+        initial_graph,
+        ..SimulationConfig::default()
+    })
+}
+
+fn event(step: u64, agent: usize, action: CascadeAction) -> CascadeEvent {
+    CascadeEvent {
+        step,
+        agent_id: AgentId(agent),
+        action,
+    }
+}
+
+#[test]
+fn analytics_count_reach_and_engagement_across_posts() {
+    let mut simulation = simulation(GraphGenerator::Empty);
+    let creator = AgentId(4);
+    let mut first = PostBuilder::new(PostId(100)).creator(creator).build();
+    first.cascade = vec![
+        event(1, 0, CascadeAction::Exposed),
+        event(1, 1, CascadeAction::Exposed),
+        event(2, 0, CascadeAction::Read),
+        event(2, 0, CascadeAction::Commented(CommentId(7))),
+    ];
+    let mut second = PostBuilder::new(PostId(101)).creator(creator).build();
+    second.cascade = vec![
+        event(3, 0, CascadeAction::Exposed),
+        event(3, 2, CascadeAction::Exposed),
+        event(4, 2, CascadeAction::Read),
+    ];
+    let unrelated = PostBuilder::new(PostId(102)).creator(AgentId(0)).build();
+    simulation.engine.content_pool = vec![first, second, unrelated];
+
+    let analytics = simulation.creator_analytics(creator);
+
+    assert_eq!(analytics.posts, 2);
+    assert_eq!(analytics.reach, 3);
+    assert_eq!(analytics.exposures, 4);
+    assert_eq!(analytics.reads, 2);
+    assert_eq!(analytics.comments, 1);
+    assert_eq!(analytics.engagement_rate, Some(0.75));
+}
+
+#[test]
+fn audience_interests_average_the_readers_profiles() {
+    let mut simulation = simulation(GraphGenerator::Empty);
+    let mut post = PostBuilder::new(PostId(100)).creator(AgentId(5)).build();
+    post.cascade = vec![
+        event(1, 0, CascadeAction::Read),
+        event(1, 1, CascadeAction::Read),
+        event(2, 1, CascadeAction::Read),
+    ];
+    simulation.engine.content_pool = vec![post];
+
+    let analytics = simulation.creator_analytics(AgentId(5));
+    let readers: Vec<_> = [0, 1]
+        .map(|id| simulation.agent_view(AgentId(id)).unwrap().interests())
+        .to_vec();
+
+    for (tag, weight) in &analytics.audience_interests {
+        let expected: f32 = readers
+            .iter()
+            .flat_map(|interests| interests.iter().filter(|(t, _)| t == tag))
+            .map(|(_, weight)| weight / 2.0)
+            .sum();
+        assert!((weight - expected).abs() < 1e-5, "{tag}");
+    }
+    let total: f32 = analytics.audience_interests.iter().map(|(_, w)| w).sum();
+    assert!((total - 1.0).abs() < 1e-4);
+}
+
+#[test]
+fn creators_without_posts_have_no_engagement_rate() {
+    let simulation = simulation(GraphGenerator::Empty);
+    let analytics = simulation.creator_analytics(AgentId(5));
+
+    assert_eq!(analytics.posts, 0);
+    assert_eq!(analytics.engagement_rate, None);
+    assert!(analytics.audience_interests.is_empty());
+}
+
+#[test]
+fn followers_are_counted_and_snapshotted() {
+    let mut simulation = simulation(GraphGenerator::ErdosRenyi { p: 1.0 });
+    let counts = simulation.follower_counts();
+
+    for id in (0..6).map(AgentId) {
+        assert_eq!(
+            simulation.follower_count(id),
+            counts.get(&id).copied().unwrap_or(0)
+        );
+    }
+    // Only individuals follow anyone, and every one of them follows the bot
+    assert_eq!(simulation.follower_count(AgentId(4)), 4);
+
+    let interval = simulation.config.interest_snapshot_interval;
+    for _ in 0..interval {
+        simulation.step();
+    }
+    let history = &simulation.creator_analytics(AgentId(4)).follower_history;
+    assert_eq!(history.snapshots.len(), 2);
+    assert_eq!(history.snapshots[0].followers, 4);
+    assert_eq!(history.snapshots[1].step, interval);
+}
+
+#[test]
+fn follower_history_keeps_spawn_and_measures_growth() {
+    let mut history = FollowerHistory::default();
+    for (step, followers) in [(0, 3), (10, 5), (20, 4), (30, 9)] {
+        history.record(step, followers, 3);
+    }
+
+    let steps: Vec<_> = history.snapshots.iter().map(|s| s.step).collect();
+    assert_eq!(steps, vec![0, 20, 30]);
+    assert_eq!(history.growth(), 6);
+    assert_eq!(FollowerHistory::default().growth(), 0);
+}
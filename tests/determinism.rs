@@ -0,0 +1,207 @@
+use social_media_sandbox::{
+    engine::RankingMode,
+    metrics::MetricsExport,
+    models::{
+        AgentId, AgentType, Cascade, GraphGenerator, Intervention, PolicyKind, RankingWeight,
+        SimulationConfig, SurveyConfig,
+    },
+    Simulation,
+};
+
+const TICKS: u64 = 400;
+const SEEDS: [u64; 2] = [5, 23];
+
+// Individuals alone, then bots and organisations on top, then everything
+// with moderation and the graph subsystems busy
+fn individuals(seed: u64) -> SimulationConfig {
+    SimulationConfig {
+        seed,
+        num_individuals: 10,
+        num_bots: 0,
+        num_organisations: 0,
+        ..SimulationConfig::default()
+    }
+}
+
+fn mixed(seed: u64) -> SimulationConfig {
+    SimulationConfig {
+        seed,
+        num_individuals: 8,
+        num_bots: 4,
+        num_organisations: 3,
+        initial_graph: GraphGenerator::BarabasiAlbert { m: 2 },
+        ranking_mode: RankingMode::Sampled,
+        ..SimulationConfig::default()
+    }
+}
+
+fn moderated(seed: u64) -> SimulationConfig {
+    SimulationConfig {
+        seed,
+        num_individuals: 12,
+        num_bots: 3,
+        num_organisations: 2,
+        initial_graph: GraphGenerator::StochasticBlock {
+            communities: 3,
+            p_in: 0.5,
+            p_out: 0.05,
+        },
+        community_detection_interval: 40,
+        unfollow_strikes: 2,
+        follow_decay_rate: 0.01,
+        survey: SurveyConfig {
+            interval: 50,
+            ..SurveyConfig::default()
+        },
+        exposure_log_capacity: 50,
+        ..SimulationConfig::default()
+    }
+}
+
+fn simulation(config: SimulationConfig, moderation: bool) -> Simulation {
+    let mut simulation = Simulation::new(config);
+    if moderation {
+        simulation.schedule_intervention(50, Intervention::BanCreator(AgentId(1)));
+        simulation.schedule_intervention(
+            100,
+            Intervention::InjectPost {
+                creator_id: AgentId(0),
+                tags: vec!["politics".to_string(), "science".to_string()],
+                length: 30,
+                breaking_ticks: 20,
+                labels: None,
+            },
+        );
+        simulation.schedule_intervention(
+            150,
+            Intervention::SetWeight {
+                weight: RankingWeight::Engagement,
+                value: 0.6,
+            },
+        );
+        simulation.schedule_intervention(
+            250,
+            Intervention::SetInterest {
+                agent_id: AgentId(2),
+                tag: "sports".to_string(),
+                weight: 0.9,
+            },
+        );
+        simulation.schedule_intervention(300, Intervention::BanCreator(AgentId(4)));
+    }
+    simulation
+}
+
+// A run's event log and metrics as the bytes they'd be exported as: every
+// post's cascade, the interventions applied, the follow graph and survey
+// responses, then the metrics recorded every ten steps
+fn run(config: SimulationConfig, moderation: bool) -> (String, String) {
+    let mut simulation = simulation(config, moderation);
+    let mut metrics = MetricsExport::new();
+    for _ in 0..TICKS {
+        simulation.step();
+        if simulation.step_count % 10 == 0 {
+            metrics.record(&simulation);
+        }
+    }
+
+    let mut events = String::new();
+    for post in simulation.engine.content_pool.iter() {
+        events += &Cascade::of(post).to_json().unwrap();
+    }
+    events += &serde_json::to_string(&simulation.interventions).unwrap();
+    events += &format!(
+        "{:?}\n{:?}\n",
+        simulation.follow_edges(),
+        simulation.churned_edges()
+    );
+    events += &simulation.survey.to_csv();
+
+    (events, metrics.to_json().unwrap())
+}
+
+fn assert_reproducible(config: impl Fn(u64) -> SimulationConfig, moderation: bool) {
+    for seed in SEEDS {
+        let (events, metrics) = run(config(seed), moderation);
+        let (again_events, again_metrics) = run(config(seed), moderation);
+
+        assert!(events == again_events, "event logs differ for seed {seed}");
+        assert!(metrics == again_metrics, "metrics differ for seed {seed}");
+    }
+}
+
+#[test]
+fn individuals_only_runs_are_reproducible() {
+    assert_reproducible(individuals, false);
+}
+
+#[test]
+fn runs_under_other_policies_are_reproducible() {
+    for policy in [PolicyKind::Addictive, PolicyKind::Casual] {
+        let config = |seed| SimulationConfig {
+            policy,
+            ..mixed(seed)
+        };
+        // The policy's rolls are actually made, and change the run
+        assert_ne!(run(config(SEEDS[0]), false), run(mixed(SEEDS[0]), false));
+        assert_reproducible(config, false);
+    }
+}
+
+#[test]
+fn runs_with_every_agent_type_are_reproducible() {
+    let simulation = simulation(mixed(SEEDS[0]), false);
+    for agent_type in [
+        AgentType::Individual,
+        AgentType::Bot,
+        AgentType::Organisation,
+    ] {
+        assert!(simulation.count_agents(agent_type) > 0);
+    }
+
+    assert_reproducible(mixed, false);
+}
+
+#[test]
+fn moderated_runs_with_graph_subsystems_are_reproducible() {
+    assert_reproducible(moderated, true);
+
+    // The subsystems under test actually did something
+    let mut simulation = simulation(moderated(SEEDS[0]), true);
+    for _ in 0..TICKS {
+        simulation.step();
+    }
+    assert_eq!(simulation.interventions.applied.len(), 5);
+    assert!(simulation.communities.is_some());
+    assert!(!simulation.survey.waves.is_empty());
+    assert!(!simulation.engine.exposures.is_empty());
+}
+
+#[test]
+fn different_seeds_give_different_runs() {
+    assert_ne!(
+        run(moderated(SEEDS[0]), true).0,
+        run(moderated(SEEDS[1]), true).0
+    );
+}
+
+#[test]
+fn same_seeds_build_the_same_agents_and_content() {
+    let finished = |seed| {
+        let mut simulation = simulation(mixed(seed), false);
+        simulation.run(TICKS);
+        simulation
+    };
+    let (first, second) = (finished(SEEDS[0]), finished(SEEDS[0]));
+
+    let pool =
+        |simulation: &Simulation| serde_json::to_string(&simulation.engine.content_pool).unwrap();
+    assert!(pool(&first) == pool(&second), "content pools differ");
+    let agents = |simulation: &Simulation| -> Vec<_> {
+        simulation
+            .agent_views()
+            .map(|view| (view.id, view.interest_profile.to_table()))
+            .collect()
+    };
+    assert_eq!(agents(&first), agents(&second));
+}
@@ -0,0 +1,131 @@
+use social_media_sandbox::{
+    models::{
+        content::Comment, AgentId, AgentType, CommentId, DriftConfig, InterestProfile, PostId,
+        SimulationConfig, Topic, TopicDrift,
+    },
+    Post, Simulation,
+};
+
+fn profile(topics: &[(&str, f32, f32)]) -> InterestProfile {
+    let mut profile = InterestProfile::new(0);
+    for &(tag, weighted_interest, agreement) in topics {
+        profile.interests.insert(
+            tag.to_string(),
+            Topic {
+                weighted_interest,
+                agreement,
+            },
+        );
+    }
+    profile.normalise_weights();
+    profile
+}
+
+#[test]
+fn organisations_move_part_of_the_way_towards_their_commenters() {
+    let config = SimulationConfig::default();
+    let post = |id| {
+        Post::new(
+            PostId(id),
+            AgentId(0),
+            profile(&[("politics", 1.0, 0.9)]),
+            &config,
+        )
+    };
+    let (quiet, mut commented) = (post(0), post(1));
+    commented.comments.push(Comment::new(
+        CommentId(0),
+        AgentId(1),
+        profile(&[("politics", 1.0, -0.5), ("sports", 1.0, 0.5)]),
+        &config,
+    ));
+
+    let mut organisation = profile(&[("politics", 1.0, 0.9)]);
+    let drift = DriftConfig {
+        responsiveness: 0.5,
+        ..DriftConfig::default()
+    };
+    let (comments, shift) = drift
+        .respond(&mut organisation, [&quiet, &commented])
+        .unwrap();
+
+    assert_eq!(comments, 1);
+    // Half of the commenters' interest was in sports, and it moved half way
+    assert!((shift - 0.25).abs() < 1e-5);
+    assert!((organisation.weight("politics").unwrap() - 0.75).abs() < 1e-5);
+    assert!((organisation.weight("sports").unwrap() - 0.25).abs() < 1e-5);
+    assert!((organisation.interests["politics"].agreement - 0.2).abs() < 1e-5);
+    assert!((organisation.interests["sports"].agreement - 0.5).abs() < 1e-5);
+
+    // Nothing to respond to without comments, nor with responsiveness off
+    assert_eq!(drift.respond(&mut organisation, [&quiet]), None);
+    assert!(!DriftConfig::default().enabled());
+}
+
+fn run(responsiveness: f32) -> Simulation {
+    let mut simulation = Simulation::new(SimulationConfig {
+        seed: 4,
+        num_individuals: 30,
+        num_bots: 0,
+        num_organisations: 3,
+        drift: DriftConfig {
+            responsiveness,
+            trace_length: 10,
+            ..DriftConfig::default()
+        },
+        ..SimulationConfig::default()
+    });
+    simulation.run(300);
+    simulation
+}
+
+#[test]
+fn responsive_organisations_trace_their_drift() {
+    let drifting = run(0.3);
+    let organisations = drifting.agents_of(AgentType::Organisation);
+    let traces: Vec<&TopicDrift> = organisations
+        .iter()
+        .map(|&id| drifting.agent_view(id).unwrap().drift.unwrap())
+        .collect();
+    assert!(traces.iter().any(|trace| !trace.is_empty()));
+    for (&id, trace) in organisations.iter().zip(&traces) {
+        assert!(trace.len() <= 10);
+        assert!(trace
+            .samples
+            .iter()
+            .all(|sample| sample.comments > 0 && (0.0..=1.0).contains(&sample.shift)));
+        assert!(trace
+            .samples
+            .iter()
+            .zip(trace.samples.iter().skip(1))
+            .all(|(a, b)| a.step <= b.step));
+        if let Some(latest) = trace.latest() {
+            let view = drifting.agent_view(id).unwrap();
+            assert_eq!(latest.profile, view.interest_profile.to_table());
+        }
+    }
+    // Organisations start out on a single topic, so moving at all means
+    // they took up some of their audience's
+    assert!(organisations.iter().any(|&id| drifting
+        .agent_view(id)
+        .unwrap()
+        .interest_profile
+        .interests
+        .len()
+        > 1));
+
+    // Individuals have no trace, and organisations that don't respond keep
+    // to the topic they started on
+    let individual = *drifting
+        .agents_of(AgentType::Individual)
+        .iter()
+        .next()
+        .unwrap();
+    assert!(drifting.agent_view(individual).unwrap().drift.is_none());
+    let steady = run(0.0);
+    for id in steady.agents_of(AgentType::Organisation) {
+        let view = steady.agent_view(id).unwrap();
+        assert!(view.drift.unwrap().is_empty());
+        assert_eq!(view.interest_profile.interests.len(), 1);
+    }
+}
@@ -0,0 +1,180 @@
+use social_media_sandbox::{
+    metrics::MetricsExport,
+    models::{
+        economy::{gini, top_share},
+        validate, AgentType, Earnings, EconomyConfig, PopulationMetric, SimulationConfig,
+    },
+    Simulation,
+};
+
+fn config(economy: EconomyConfig) -> SimulationConfig {
+    SimulationConfig {
+        seed: 44,
+        num_individuals: 12,
+        num_bots: 3,
+        num_organisations: 2,
+        economy,
+        ..SimulationConfig::default()
+    }
+}
+
+fn run(economy: EconomyConfig, ticks: u64) -> Simulation {
+    let mut simulation = Simulation::new(config(economy));
+    for _ in 0..ticks {
+        simulation.step();
+    }
+    simulation
+}
+
+fn posts(simulation: &Simulation) -> usize {
+    simulation.engine.content_pool.len()
+}
+
+#[test]
+fn concentration_measures_tell_equal_from_winner_takes_all() {
+    assert_eq!(gini(&[2.0, 2.0, 2.0, 2.0]), Some(0.0));
+    assert_eq!(gini(&[0.0, 0.0, 0.0, 8.0]), Some(0.75));
+    assert_eq!(gini(&[0.0, 0.0]), None);
+    assert_eq!(gini(&[]), None);
+
+    assert_eq!(top_share(&[1.0, 1.0, 2.0]), Some(0.5));
+    let mut twenty = vec![1.0; 18];
+    twenty.extend([3.0, 3.0]);
+    assert_eq!(top_share(&twenty), Some(0.25));
+}
+
+#[test]
+fn income_over_an_interval_speeds_up_posting() {
+    let economy = EconomyConfig {
+        incentive: 1.0,
+        ..EconomyConfig::default()
+    };
+    let mut earnings = Earnings::default();
+    earnings.tip(2.0);
+    earnings.tip(2.0);
+    earnings.settle(3, 0.5, 4);
+
+    assert_eq!(earnings.total(), 10.0);
+    assert_eq!(earnings.tip_count, 2);
+    assert_eq!(earnings.subscribers, 3);
+    assert_eq!(earnings.income, 2.5);
+    assert_eq!(earnings.pending, 0.0);
+
+    assert_eq!(economy.posting_ticks(10, 0.0), 10);
+    assert_eq!(economy.posting_ticks(10, 1.0), 5);
+    earnings.settle(0, 0.5, 4);
+    assert_eq!(earnings.income, 0.0);
+}
+
+#[test]
+fn nothing_is_paid_by_default() {
+    let simulation = run(EconomyConfig::default(), 200);
+
+    assert!(simulation
+        .creator_earnings()
+        .iter()
+        .all(|&total| total == 0.0));
+    let latest = simulation.wealth.latest().unwrap();
+    assert_eq!(latest.creators, 5);
+    assert_eq!(latest.gini, None);
+}
+
+#[test]
+fn affine_followers_tip_and_subscribe_to_creators() {
+    let simulation = run(
+        EconomyConfig {
+            affinity: 0.0,
+            tip_chance: 1.0,
+            subscription_fee: 0.1,
+            ..EconomyConfig::default()
+        },
+        200,
+    );
+    let earnings: Vec<_> = simulation
+        .agent_views()
+        .filter_map(|agent| agent.earnings.map(|earnings| (agent, *earnings)))
+        .collect();
+
+    assert_eq!(earnings.len(), 5);
+    assert!(earnings
+        .iter()
+        .all(|(agent, _)| agent.agent_type != AgentType::Individual));
+    assert!(earnings.iter().any(|(_, earnings)| earnings.tip_count > 0));
+    for (agent, earnings) in &earnings {
+        assert_eq!(earnings.subscribers, simulation.follower_count(agent.id));
+        assert_eq!(earnings.tips, earnings.tip_count as f32);
+    }
+    assert_eq!(PopulationMetric::Earnings.values(&simulation).len(), 5);
+
+    let analytics = simulation.creator_analytics(earnings[0].0.id);
+    assert_eq!(analytics.earnings, Some(earnings[0].1));
+}
+
+#[test]
+fn creators_post_more_the_more_earning_spurs_them() {
+    let economy = |incentive| EconomyConfig {
+        affinity: 0.0,
+        tip_chance: 1.0,
+        incentive,
+        ..EconomyConfig::default()
+    };
+    let unmoved = run(economy(0.0), 300);
+    let spurred = run(economy(5.0), 300);
+
+    assert!(posts(&spurred) > posts(&unmoved));
+}
+
+#[test]
+fn wealth_is_sampled_at_the_interval_and_exported() {
+    let simulation = run(
+        EconomyConfig {
+            affinity: 0.0,
+            tip_chance: 0.5,
+            ..EconomyConfig::default()
+        },
+        100,
+    );
+    let interval = simulation.config.economy.interval;
+
+    let steps: Vec<_> = simulation
+        .wealth
+        .samples
+        .iter()
+        .map(|sample| sample.step)
+        .collect();
+    assert_eq!(
+        steps,
+        (0..=100 / interval)
+            .map(|n| n * interval)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(simulation.retained()["wealth samples"], steps.len());
+    let latest = simulation.wealth.latest().unwrap();
+    assert!((0.0..1.0).contains(&latest.gini.unwrap()));
+
+    let mut export = MetricsExport::new();
+    export.record(&simulation);
+    let count = |metric: &str| {
+        export
+            .samples
+            .iter()
+            .filter(|sample| sample.metric == metric)
+            .count()
+    };
+    assert_eq!(count("creator_earnings"), 5);
+    assert_eq!(count("earnings_gini"), 1);
+    assert_eq!(count("earnings_top_share"), 1);
+
+    let invalid = config(EconomyConfig {
+        tip_chance: 1.5,
+        subscription_fee: -1.0,
+        ..EconomyConfig::default()
+    });
+    let problems = validate(&invalid);
+    assert!(problems
+        .iter()
+        .any(|problem| problem.contains("economy.tip_chance")));
+    assert!(problems
+        .iter()
+        .any(|problem| problem.contains("economy.subscription_fee")));
+}
@@ -0,0 +1,185 @@
+use social_media_sandbox::{
+    engine::{ExposureQuotas, RerankContext, Reranker, ScoredPost},
+    metrics::{schema::EXPOSURE_SHARE, MetricsExport},
+    models::{
+        AgentId, ConfigError, ExposureTarget, FairnessConfig, Individual, InvalidConfig, PostId,
+        SimulationConfig,
+    },
+    test_support::{PostBuilder, ProfileBuilder},
+    RecommendationEngine, Simulation,
+};
+
+// Posts 0 to 5 by organisation 1, posts 6 to 11 by individuals 2 and 3
+fn engine() -> RecommendationEngine {
+    let mut engine = RecommendationEngine::new();
+    let profile = ProfileBuilder::new().tag("science", 1.0).build();
+    for id in 0..12 {
+        let creator = if id < 6 { 1 } else { 2 + id % 2 };
+        engine.create_post(
+            PostBuilder::new(PostId(id))
+                .creator(AgentId(creator))
+                .profile(profile.clone())
+                .build(),
+        );
+    }
+    engine
+}
+
+fn quotas(targets: &[(&str, Option<f32>, Option<f32>)], parity: bool) -> ExposureQuotas {
+    ExposureQuotas {
+        groups: [
+            (AgentId(1), "organisation".to_string()),
+            (AgentId(2), "individual".to_string()),
+            (AgentId(3), "individual".to_string()),
+        ]
+        .into(),
+        targets: targets
+            .iter()
+            .map(|&(group, min_share, max_share)| ExposureTarget {
+                group: group.to_string(),
+                min_share,
+                max_share,
+            })
+            .collect(),
+        parity,
+    }
+}
+
+// Reranks the posts, ranked in the order given, for a feed of `slots`
+fn rerank(
+    engine: &RecommendationEngine,
+    quotas: &ExposureQuotas,
+    order: &[usize],
+    slots: usize,
+) -> Vec<usize> {
+    let agent = Individual::new(AgentId(9), &SimulationConfig::default(), engine);
+    let context = RerankContext {
+        agent: &agent,
+        engine,
+        slots,
+    };
+    let mut slate: Vec<ScoredPost> = order
+        .iter()
+        .enumerate()
+        .map(|(rank, &index)| ScoredPost {
+            post: &engine.content_pool[index],
+            score: 1.0 - rank as f32 / 100.0,
+        })
+        .collect();
+    quotas.rerank(&mut slate, &context);
+    slate.iter().map(|scored| scored.post.id.0).collect()
+}
+
+#[test]
+fn quotas_cap_and_lift_groups_within_each_feed() {
+    let engine = engine();
+    let organisations_first: Vec<usize> = (0..12).collect();
+
+    // A 30% cap on a feed of 5 leaves organisations 1 post, and the rest of
+    // theirs are dropped rather than left to backfill
+    let capped = quotas(&[("organisation", None, Some(0.3))], false);
+    assert_eq!(
+        rerank(&engine, &capped, &organisations_first, 5),
+        [0, 6, 7, 8, 9, 10, 11]
+    );
+
+    // A floor lifts the group's best posts in ahead of others, in rank order
+    let lifted = quotas(&[("individual", Some(0.4), None)], false);
+    assert_eq!(
+        rerank(&engine, &lifted, &organisations_first, 5)[..5],
+        [0, 1, 2, 6, 7]
+    );
+
+    // With parity neither group takes more than half the feed
+    let even = quotas(&[], true);
+    assert_eq!(
+        rerank(&engine, &even, &organisations_first, 4),
+        [0, 1, 6, 7]
+    );
+}
+
+#[test]
+fn organisations_stay_under_their_cap_across_a_run() {
+    let config = SimulationConfig {
+        seed: 4,
+        num_individuals: 20,
+        num_bots: 2,
+        num_organisations: 6,
+        ..SimulationConfig::default()
+    };
+    // Shares are sampled even with no targets to enforce
+    let mut uncapped = Simulation::new(config.clone());
+    uncapped.run(50);
+    let share = uncapped.fairness.latest().unwrap().share("organisation");
+    assert!(share.unwrap() > 0.3, "{share:?}");
+
+    let mut config = config;
+    config.fairness = FairnessConfig {
+        targets: vec![ExposureTarget {
+            group: "organisation".to_string(),
+            min_share: None,
+            max_share: Some(0.3),
+        }],
+        interval: 50,
+        ..FairnessConfig::default()
+    };
+    let mut simulation = Simulation::new(config);
+    simulation.run(200);
+
+    assert_eq!(simulation.fairness.len(), 4);
+    for sample in &simulation.fairness.samples {
+        let share = sample.share("organisation").unwrap();
+        assert!(share <= 0.3, "step {}: {share}", sample.step);
+    }
+
+    let mut export = MetricsExport::new();
+    export.record(&simulation);
+    let shares: Vec<_> = export
+        .samples
+        .iter()
+        .filter(|sample| sample.metric == EXPOSURE_SHARE)
+        .collect();
+    assert!(shares
+        .iter()
+        .any(|sample| sample.dimensions["group"] == "bot"));
+    let total: f32 = shares.iter().map(|sample| sample.value).sum();
+    assert!((total - 1.0).abs() < 1e-4);
+}
+
+#[test]
+fn targets_that_cannot_be_met_are_refused() {
+    let target = |group: &str, min_share, max_share| ExposureTarget {
+        group: group.to_string(),
+        min_share,
+        max_share,
+    };
+    let Err(InvalidConfig(problems)) = SimulationConfig::builder()
+        .with(|config| {
+            config.fairness.targets = vec![
+                target("organisation", Some(0.5), Some(0.2)),
+                target("individual", Some(0.7), None),
+                target("organisations", None, Some(1.5)),
+            ]
+        })
+        .build()
+    else {
+        panic!("config should be invalid");
+    };
+
+    assert!(problems.iter().any(|problem| problem.keys()
+        == [
+            "fairness.targets.organisation.min_share",
+            "fairness.targets.organisation.max_share"
+        ]));
+    assert!(problems
+        .iter()
+        .any(|problem| problem.keys() == ["fairness.targets"]
+            && matches!(problem, ConfigError::Inconsistent { .. })));
+    assert!(problems.iter().any(|problem| matches!(
+        problem,
+        ConfigError::Unknown { name, .. } if name == "organisations"
+    )));
+    assert!(problems
+        .iter()
+        .any(|problem| problem.keys() == ["fairness.targets.organisations.max_share"]));
+}
@@ -0,0 +1,84 @@
+use social_media_sandbox::{
+    metrics::{schema::FEED_TAG_ENTROPY, MetricsExport},
+    models::{AgentId, ArchivedFeed, FeedSlot, PostId, SimulationConfig},
+    Simulation,
+};
+
+fn slot(post: usize, creator: usize, tags: &[&str]) -> FeedSlot {
+    FeedSlot {
+        post_id: PostId(post),
+        creator_id: AgentId(creator),
+        tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        reshare: false,
+    }
+}
+
+#[test]
+fn feeds_are_scored_on_how_varied_they_are() {
+    let feed = ArchivedFeed {
+        step: 3,
+        ads: 0,
+        slate: vec![
+            slot(0, 1, &["science"]),
+            slot(1, 1, &["sports"]),
+            slot(2, 2, &["science", "sports"]),
+            slot(3, 3, &["science", "sports"]),
+        ],
+        chosen: vec![2],
+    };
+
+    assert_eq!(feed.chosen_posts().collect::<Vec<_>>(), [PostId(2)]);
+    assert_eq!(feed.creator_diversity(), Some(0.75));
+    assert!((feed.tag_entropy() - 1.0).abs() < 1e-6);
+
+    let narrow = ArchivedFeed {
+        slate: vec![slot(0, 1, &["science"]), slot(1, 1, &["science"])],
+        ..feed
+    };
+    assert_eq!(narrow.creator_diversity(), Some(0.5));
+    assert_eq!(narrow.tag_entropy(), 0.0);
+}
+
+#[test]
+fn individuals_keep_the_feeds_they_were_served() {
+    let mut simulation = Simulation::new(SimulationConfig {
+        seed: 12,
+        num_individuals: 10,
+        num_bots: 2,
+        num_organisations: 2,
+        feed_archive_length: 5,
+        ..SimulationConfig::default()
+    });
+    simulation.run(150);
+
+    let mut picked = 0;
+    for agent in simulation.agent_views() {
+        let Some(feeds) = agent.feeds else {
+            continue;
+        };
+        assert!(feeds.len() <= 5);
+        // Feeds are archived oldest first, and only posts in them are picked
+        assert!(feeds
+            .feeds
+            .iter()
+            .zip(feeds.feeds.iter().skip(1))
+            .all(|(older, newer)| older.step <= newer.step));
+        for feed in &feeds.feeds {
+            assert!(feed
+                .chosen
+                .iter()
+                .all(|&position| position < feed.slate.len()));
+            picked += feed.chosen.len();
+        }
+    }
+    assert!(picked > 0);
+
+    let mut export = MetricsExport::new();
+    export.record(&simulation);
+    let entropies = export
+        .samples
+        .iter()
+        .filter(|sample| sample.metric == FEED_TAG_ENTROPY)
+        .count();
+    assert_eq!(entropies, 10);
+}
@@ -0,0 +1,52 @@
+use social_media_sandbox::{
+    models::{AgentId, AgentType, SimulationConfig},
+    Simulation,
+};
+
+fn posts_by(simulation: &Simulation, creator: AgentId) -> usize {
+    simulation
+        .engine
+        .content_pool
+        .iter()
+        .filter(|post| post.creator_id == creator && post.original.is_none())
+        .count()
+}
+
+#[test]
+fn frozen_agents_stop_ticking_but_stay_in_the_simulation() {
+    let mut simulation = Simulation::new(SimulationConfig {
+        seed: 2,
+        num_individuals: 10,
+        num_bots: 2,
+        num_organisations: 1,
+        ..SimulationConfig::default()
+    });
+    simulation.run(50);
+    let bot = *simulation.agents_of(AgentType::Bot).iter().next().unwrap();
+    let individual = *simulation
+        .agents_of(AgentType::Individual)
+        .iter()
+        .next()
+        .unwrap();
+    assert!(simulation.freeze(bot));
+    assert!(simulation.freeze(individual));
+    assert!(!simulation.freeze(AgentId(9999)));
+
+    let posted = posts_by(&simulation, bot);
+    let state = simulation.agent_view(individual).unwrap().state_name();
+    let session = simulation.agent_view(individual).unwrap().session_length;
+    simulation.run(100);
+
+    assert_eq!(posts_by(&simulation, bot), posted);
+    assert!(posted > 0);
+    let frozen = simulation.agent_view(individual).unwrap();
+    assert_eq!(frozen.state_name(), state);
+    assert_eq!(frozen.session_length, session);
+    assert_eq!(simulation.agent_views().count(), 13);
+
+    simulation.unfreeze(bot);
+    assert!(!simulation.is_frozen(bot));
+    assert!(simulation.is_frozen(individual));
+    simulation.run(100);
+    assert!(posts_by(&simulation, bot) > posted);
+}
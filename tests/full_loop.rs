@@ -0,0 +1,113 @@
+use social_media_sandbox::{
+    models::{AgentType, PopulationSummary, RunSummary, SimulationConfig},
+    Simulation,
+};
+
+fn config() -> SimulationConfig {
+    SimulationConfig {
+        seed: 1000,
+        num_individuals: 20,
+        num_bots: 3,
+        num_organisations: 2,
+        ..SimulationConfig::default()
+    }
+}
+
+// Guards the whole loop against tick paths that quietly stop doing anything:
+// bots and organisations have to post, and individuals have to read, comment
+// and have their interests moved by it. Individuals have no way into posting
+// yet, so their posts aren't asked for
+#[test]
+fn a_thousand_ticks_show_emergent_activity() {
+    let mut simulation = Simulation::new(config());
+    let summary = simulation.run(1_000);
+
+    assert_eq!(summary.ticks, 1_000);
+    assert_eq!(summary.step, 1_000);
+    assert!(summary.posts_by_bots > 0, "{summary:?}");
+    assert!(summary.posts_by_organisations > 0, "{summary:?}");
+    assert_eq!(
+        summary.posts_created,
+        summary.posts_by_individuals + summary.posts_by_bots + summary.posts_by_organisations
+    );
+    assert!(
+        summary.reads_completed > summary.ticks as usize,
+        "{summary:?}"
+    );
+    assert!(summary.comments_created > 0, "{summary:?}");
+    assert!(summary.interests_changed >= 10, "{summary:?}");
+}
+
+#[test]
+fn runs_are_deterministic_and_add_up() {
+    let whole = Simulation::new(config()).run(200);
+
+    let mut split = Simulation::new(config());
+    let first = split.run(120);
+    let second = split.run(80);
+    assert_eq!(second.step, whole.step);
+    assert_eq!(
+        first.posts_created + second.posts_created,
+        whole.posts_created
+    );
+    assert_eq!(
+        first.reads_completed + second.reads_completed,
+        whole.reads_completed
+    );
+
+    assert_eq!(Simulation::new(config()).run(200), whole);
+    assert_eq!(Simulation::new(config()).run(0), RunSummary::default());
+}
+
+// The agent panel sums large populations up this way rather than drawing
+// every agent
+#[test]
+fn population_summaries_count_every_agent_once() {
+    let mut simulation = Simulation::new(SimulationConfig {
+        num_individuals: 2_000,
+        ..config()
+    });
+    simulation.run(5);
+    let summary = PopulationSummary::of(simulation.agent_views());
+
+    assert_eq!(summary.total(AgentType::Individual), 2_000);
+    assert_eq!(summary.total(AgentType::Bot), 3);
+    assert_eq!(summary.total(AgentType::Organisation), 2);
+    assert!(summary.counts["individual"].len() > 1, "{summary:?}");
+    let offline = simulation
+        .agent_views()
+        .filter(|agent| agent.state_name() == "Offline")
+        .count();
+    let counted: usize = summary
+        .counts
+        .values()
+        .filter_map(|states| states.get("Offline"))
+        .sum();
+    assert_eq!(counted, offline);
+}
+
+// Bots and organisations added to a running simulation tick and post like
+// the ones it started with
+#[test]
+fn bots_and_organisations_can_be_added_mid_run() {
+    let mut simulation = Simulation::new(SimulationConfig {
+        num_bots: 0,
+        num_organisations: 0,
+        ..config()
+    });
+    simulation.run(10);
+    assert_eq!(simulation.count_agents(AgentType::Bot), 0);
+
+    simulation.add_agent(AgentType::Bot);
+    simulation.add_agent(AgentType::Organisation);
+    simulation.add_agent(AgentType::Organisation);
+    assert_eq!(simulation.count_agents(AgentType::Bot), 1);
+    assert_eq!(simulation.count_agents(AgentType::Organisation), 2);
+
+    let summary = simulation.run(500);
+    assert!(summary.posts_by_bots > 0, "{summary:?}");
+    assert!(summary.posts_by_organisations > 0, "{summary:?}");
+
+    simulation.remove_agent(AgentType::Organisation);
+    assert_eq!(simulation.count_agents(AgentType::Organisation), 1);
+}
@@ -0,0 +1,151 @@
+use social_media_sandbox::models::{
+    AgentId, ChurnReason, FollowChurn, GraphGenerator, RngStreams, Scenario, Simulation,
+    SimulationConfig,
+};
+use std::collections::BTreeMap;
+
+fn generate(generator: GraphGenerator, count: usize) -> Vec<(usize, usize)> {
+    let mut rng = RngStreams::stream(3, "graph");
+    generator.generate(count, &mut rng).into_iter().collect()
+}
+
+#[test]
+fn certain_erdos_renyi_graphs_are_complete() {
+    assert_eq!(
+        generate(GraphGenerator::ErdosRenyi { p: 1.0 }, 10).len(),
+        45
+    );
+    assert!(generate(GraphGenerator::ErdosRenyi { p: 0.0 }, 10).is_empty());
+}
+
+#[test]
+fn barabasi_albert_adds_m_edges_per_agent() {
+    let edges = generate(GraphGenerator::BarabasiAlbert { m: 2 }, 50);
+
+    // A triangle to start, then two edges for each of the other 47 agents
+    assert_eq!(edges.len(), 3 + 2 * 47);
+}
+
+#[test]
+fn unrewired_watts_strogatz_graphs_are_rings() {
+    let edges = generate(GraphGenerator::WattsStrogatz { k: 4, beta: 0.0 }, 20);
+
+    assert_eq!(edges.len(), 40);
+    assert!(edges.contains(&(0, 1)) && edges.contains(&(0, 2)));
+    assert!(edges.contains(&(0, 19)) && edges.contains(&(0, 18)));
+}
+
+#[test]
+fn stochastic_blocks_only_connect_within_communities() {
+    let edges = generate(
+        GraphGenerator::StochasticBlock {
+            communities: 3,
+            p_in: 1.0,
+            p_out: 0.0,
+        },
+        9,
+    );
+
+    assert_eq!(edges.len(), 3 * 3);
+    assert!(edges.iter().all(|(a, b)| a / 3 == b / 3));
+}
+
+#[test]
+fn simulations_start_from_the_generated_graph() {
+    let simulation = Simulation::new(SimulationConfig {
+        seed: 1,
+        num_individuals: 6,
+        num_bots: 0,
+        num_organisations: 0,
+        initial_graph: GraphGenerator::ErdosRenyi { p: 1.0 },
+        ..SimulationConfig::default()
+    });
+
+    assert_eq!(simulation.follow_edges().len(), 6 * 5);
+}
+
+#[test]
+fn scenarios_choose_the_generator() {
+    let scenario =
+        Scenario::parse("seed = 1\n\n[initial_graph.WattsStrogatz]\nk = 4\nbeta = 0.1\n").unwrap();
+
+    assert_eq!(
+        scenario.config.initial_graph,
+        GraphGenerator::WattsStrogatz { k: 4, beta: 0.1 }
+    );
+    assert!(scenario.warnings.is_empty());
+}
+
+#[test]
+fn the_social_graph_follows_agents_follows() {
+    let mut simulation = Simulation::new(SimulationConfig {
+        seed: 1,
+        num_individuals: 4,
+        num_bots: 1,
+        num_organisations: 0,
+        initial_graph: GraphGenerator::Empty,
+        ..SimulationConfig::default()
+    });
+    let bot = AgentId(4);
+
+    assert!(simulation.follow(AgentId(0), bot));
+    assert!(simulation.follow(AgentId(1), bot));
+    // Bots don't follow anyone
+    assert!(!simulation.follow(bot, AgentId(0)));
+    assert!(simulation.follow(AgentId(0), AgentId(1)));
+    assert!(simulation.follow(AgentId(1), AgentId(0)));
+
+    let graph = simulation.social_graph();
+    assert!(graph.follows(AgentId(0), bot) && !graph.follows(bot, AgentId(0)));
+    assert_eq!(
+        graph.followers(bot).collect::<Vec<_>>(),
+        [AgentId(0), AgentId(1)]
+    );
+    assert_eq!(
+        graph.following(AgentId(1)).collect::<Vec<_>>(),
+        [AgentId(0), bot]
+    );
+    assert_eq!(graph.edge_count(), 4);
+    assert_eq!(graph.reciprocity(), Some(0.5));
+    assert_eq!(simulation.follower_counts()[&bot], 2);
+
+    assert!(simulation.unfollow(AgentId(0), bot));
+    assert!(!simulation.unfollow(AgentId(0), bot));
+    assert_eq!(simulation.social_graph().followers(bot).count(), 1);
+}
+
+const FOLLOWER: AgentId = AgentId(1);
+const CREATOR: AgentId = AgentId(2);
+
+#[test]
+fn repeated_bad_reads_unfollow() {
+    let mut follows = BTreeMap::from([(CREATOR, 1.0)]);
+    let mut churn = FollowChurn::default();
+    let conflict = Some(ChurnReason::Conflict);
+
+    churn.react(&mut follows, 1, FOLLOWER, CREATOR, conflict, 1.0, 3);
+    churn.react(&mut follows, 2, FOLLOWER, CREATOR, conflict, 1.0, 3);
+    // A good read in between starts the count again
+    churn.react(&mut follows, 3, FOLLOWER, CREATOR, None, 1.0, 3);
+    churn.react(&mut follows, 4, FOLLOWER, CREATOR, conflict, 1.0, 3);
+    churn.react(&mut follows, 5, FOLLOWER, CREATOR, conflict, 1.0, 3);
+    assert!(follows.contains_key(&CREATOR));
+
+    churn.react(&mut follows, 6, FOLLOWER, CREATOR, conflict, 1.0, 3);
+    assert!(follows.is_empty());
+    assert_eq!(churn.churned.len(), 1);
+    assert_eq!(churn.churned[0].step, 6);
+    assert_eq!(churn.churned[0].reason, ChurnReason::Conflict);
+}
+
+#[test]
+fn unused_follows_decay_away() {
+    let mut follows = BTreeMap::from([(CREATOR, 1.0), (AgentId(3), 0.06)]);
+    let mut churn = FollowChurn::default();
+
+    churn.decay(&mut follows, 1, FOLLOWER, 0.5, 0.05);
+
+    assert_eq!(follows, BTreeMap::from([(CREATOR, 0.5)]));
+    assert_eq!(churn.churned[0].followed, AgentId(3));
+    assert_eq!(churn.churned[0].reason, ChurnReason::Decay);
+}
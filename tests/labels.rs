@@ -0,0 +1,167 @@
+use social_media_sandbox::{
+    metrics::MetricsExport,
+    models::{
+        AgentId, AgentType, CascadeAction, CascadeEvent, CreatorRates, GroundTruth,
+        GroundTruthConfig, Intervention, LabelEvaluation, PostId, Scenario, SimulationConfig,
+    },
+    test_support::{PostBuilder, ProfileBuilder},
+    Post, Simulation,
+};
+
+fn config() -> SimulationConfig {
+    SimulationConfig {
+        seed: 8,
+        num_individuals: 6,
+        num_bots: 2,
+        num_organisations: 1,
+        ..SimulationConfig::default()
+    }
+}
+
+fn truth(topic: &str, veracity: bool, quality: f32) -> GroundTruth {
+    GroundTruth {
+        topic: topic.to_string(),
+        veracity,
+        quality,
+    }
+}
+
+fn exposed(post: &mut Post, agents: &[usize]) {
+    for &agent in agents {
+        post.cascade.push(CascadeEvent {
+            step: 1,
+            agent_id: AgentId(agent),
+            action: CascadeAction::Exposed,
+        });
+    }
+}
+
+#[test]
+fn every_published_post_gets_ground_truth() {
+    let mut simulation = Simulation::new(config());
+    for _ in 0..100 {
+        simulation.step();
+    }
+
+    assert!(!simulation.engine.content_pool.is_empty());
+    for post in &simulation.engine.content_pool {
+        let truth = simulation.labels.get(post.id).unwrap();
+        assert!(post.interest_profile.interests.contains_key(&truth.topic));
+        assert!((0.0..=1.0).contains(&truth.quality));
+    }
+}
+
+#[test]
+fn labels_follow_the_creator_type_rates() {
+    let config = GroundTruthConfig {
+        false_rate: CreatorRates {
+            individual: 0.0,
+            bot: 1.0,
+            organisation: 0.0,
+        },
+        quality_spread: 0.0,
+        ..GroundTruthConfig::default()
+    };
+    let post = PostBuilder::new(PostId(3))
+        .profile(
+            ProfileBuilder::new()
+                .tag("science", 0.7)
+                .tag("health", 0.3)
+                .build(),
+        )
+        .build();
+
+    let bot = config.label(&post, AgentType::Bot, 1);
+    let organisation = config.label(&post, AgentType::Organisation, 1);
+
+    assert_eq!(bot, truth("science", false, config.quality.bot));
+    assert_eq!(
+        organisation,
+        truth("science", true, config.quality.organisation)
+    );
+    assert_eq!(config.label(&post, AgentType::Bot, 1), bot);
+}
+
+#[test]
+fn injected_posts_keep_the_labels_they_were_given() {
+    let mut simulation = Simulation::new(config());
+    let planted = truth("politics", false, 0.1);
+    simulation.schedule_intervention(
+        2,
+        Intervention::InjectPost {
+            creator_id: AgentId(0),
+            tags: vec!["politics".to_string()],
+            length: 20,
+            breaking_ticks: 0,
+            labels: Some(planted.clone()),
+        },
+    );
+    for _ in 0..5 {
+        simulation.step();
+    }
+
+    let post = simulation
+        .engine
+        .content_pool
+        .iter()
+        .find(|post| post.published_step == 2 && post.length == 20)
+        .unwrap();
+    assert_eq!(simulation.labels.get(post.id), Some(&planted));
+}
+
+#[test]
+fn evaluation_scores_exposures_and_bans_against_the_truth() {
+    let mut simulation = Simulation::new(config());
+    let mut false_post = PostBuilder::new(PostId(100)).creator(AgentId(6)).build();
+    exposed(&mut false_post, &[0, 1, 2]);
+    let mut true_post = PostBuilder::new(PostId(101)).creator(AgentId(8)).build();
+    exposed(&mut true_post, &[0]);
+    simulation.engine.content_pool = vec![false_post, true_post];
+    simulation
+        .labels
+        .insert(PostId(100), truth("nothing", false, 0.2));
+    simulation
+        .labels
+        .insert(PostId(101), truth("nothing", true, 0.6));
+
+    let evaluation = LabelEvaluation::of(&simulation);
+    assert_eq!(evaluation.exposed_quality, Some(0.3));
+    assert_eq!(evaluation.false_exposure_share, Some(0.75));
+    assert_eq!(evaluation.relevant_exposure_share, Some(0.0));
+    assert_eq!(evaluation.moderation_precision, None);
+    assert_eq!(evaluation.moderation_recall, Some(0.0));
+
+    simulation.engine.banned_creators.insert(AgentId(6));
+    simulation.engine.banned_creators.insert(AgentId(8));
+    let evaluation = LabelEvaluation::of(&simulation);
+    assert_eq!(evaluation.moderation_precision, Some(0.5));
+    assert_eq!(evaluation.moderation_recall, Some(1.0));
+}
+
+#[test]
+fn scenarios_configure_ground_truth_and_metrics_report_it() {
+    let scenario = Scenario::parse(
+        "seed = 8\n\
+         num_individuals = 6\n\
+         [ground_truth.false_rate]\n\
+         individual = 1.0\n\
+         bot = 1.0\n\
+         organisation = 1.0\n",
+    )
+    .unwrap();
+    assert!(scenario.warnings.is_empty(), "{:?}", scenario.warnings);
+
+    let mut simulation = Simulation::new(scenario.config);
+    for _ in 0..100 {
+        simulation.step();
+    }
+    let mut export = MetricsExport::new();
+    export.record(&simulation);
+
+    let false_share = export
+        .samples
+        .iter()
+        .find(|sample| sample.metric == "false_exposure_share")
+        .unwrap();
+    assert_eq!(false_share.value, 1.0);
+}
@@ -0,0 +1,48 @@
+use std::collections::BTreeSet;
+
+use social_media_sandbox::locale::Locale;
+
+#[test]
+fn labels_are_translated_or_fall_back_to_english() {
+    assert_eq!(Locale::English.tr("Start"), "Start");
+    assert_eq!(Locale::Spanish.tr("Start"), "Iniciar");
+    assert_eq!(Locale::Spanish.tr("Not a label"), "Not a label");
+    assert_eq!(Locale::default(), Locale::English);
+}
+
+#[test]
+fn templates_are_filled_in_order() {
+    let args = ["3".to_string(), "40".to_string()];
+    assert_eq!(
+        Locale::English.fill("{} communities as of step {}", &args),
+        "3 communities as of step 40"
+    );
+    assert_eq!(
+        Locale::Spanish.fill("{} communities as of step {}", &args),
+        "3 comunidades en el paso 40"
+    );
+    assert_eq!(Locale::English.fill("Step {}", &[]), "Step {}");
+    assert_eq!(
+        Locale::English.fill("No placeholders", &args),
+        "No placeholders"
+    );
+}
+
+#[test]
+fn every_translation_is_unique_and_keeps_its_placeholders() {
+    for locale in Locale::ALL {
+        let mut keys = BTreeSet::new();
+        for (english, translated) in locale.table() {
+            assert!(keys.insert(english), "{english} is translated twice");
+            assert!(!translated.is_empty(), "{english} has no translation");
+            assert_eq!(
+                english.matches("{}").count(),
+                translated.matches("{}").count(),
+                "{english}"
+            );
+        }
+    }
+
+    let names: BTreeSet<_> = Locale::ALL.iter().map(|locale| locale.name()).collect();
+    assert_eq!(names.len(), Locale::ALL.len());
+}
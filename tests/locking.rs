@@ -0,0 +1,264 @@
+use rand::RngCore;
+use social_media_sandbox::{
+    engine::{thread_conflict, EngineError, EngineOp, LockReason},
+    metrics::MetricsExport,
+    models::{
+        Agent, AgentId, AgentState, AgentType, CascadeAction, Choice, CommentId, IdAllocator,
+        Individual, Intervention, LockEffect, PostId, RngStreams, SimulationConfig, TickContext,
+        TransitionPolicy, WellBeing, WellBeingConfig,
+    },
+    test_support::{CommentBuilder, EngineFixture, PostBuilder, ProfileBuilder},
+    RecommendationEngine, Simulation,
+};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+fn comment(
+    engine: &mut RecommendationEngine,
+    post_id: PostId,
+    id: usize,
+) -> Result<(), EngineError> {
+    engine.apply_op(EngineOp::AddComment {
+        post_id,
+        comment: CommentBuilder::new(CommentId(id))
+            .commentor(AgentId(id))
+            .build(),
+    })
+}
+
+#[test]
+fn locked_posts_turn_comments_away_until_unlocked() {
+    let mut engine = EngineFixture::with_posts(2).build();
+    let post_id = engine.content_pool[0].id;
+    engine.lock_comments(post_id, LockReason::Manual).unwrap();
+    engine.current_step = 3;
+    engine.lock_comments(post_id, LockReason::Conflict).unwrap();
+
+    assert_eq!(
+        comment(&mut engine, post_id, 1),
+        Err(EngineError::CommentsLocked(post_id))
+    );
+    assert_eq!(engine.comment_locks[&post_id].reason, LockReason::Manual);
+    assert_eq!(engine.comment_locks[&post_id].step, 0);
+    assert_eq!(engine.rejected_comments[&post_id], 1);
+    assert!(engine
+        .get_content_by_id(post_id)
+        .unwrap()
+        .comments
+        .is_empty());
+
+    assert!(engine.unlock_comments(post_id));
+    assert!(!engine.unlock_comments(post_id));
+    comment(&mut engine, post_id, 2).unwrap();
+    assert_eq!(engine.get_content_by_id(post_id).unwrap().comments.len(), 1);
+
+    assert_eq!(
+        engine.lock_comments(PostId(99), LockReason::Manual),
+        Err(EngineError::PostNotFound(PostId(99)))
+    );
+}
+
+// Wants to comment, and only thinks to read the post when turned away
+#[derive(Debug, Default)]
+struct TurnedAway {
+    asked_to_read: AtomicUsize,
+}
+
+impl TransitionPolicy for TurnedAway {
+    fn should_go_offline(&self, _agent: &Individual, _rng: &mut dyn RngCore) -> Choice {
+        false.into()
+    }
+
+    fn should_select_post(&self, _agent: &Individual, _rng: &mut dyn RngCore) -> Choice {
+        false.into()
+    }
+
+    fn should_read_post(&self, _agent: &Individual, _rng: &mut dyn RngCore) -> Choice {
+        (self.asked_to_read.fetch_add(1, Ordering::Relaxed) > 0).into()
+    }
+
+    fn should_read_comments(&self, _agent: &Individual, _rng: &mut dyn RngCore) -> Choice {
+        false.into()
+    }
+
+    fn should_write_comment(&self, _agent: &Individual, _rng: &mut dyn RngCore) -> Choice {
+        true.into()
+    }
+
+    fn should_scroll(&self, _agent: &Individual, _rng: &mut dyn RngCore) -> Choice {
+        false.into()
+    }
+}
+
+#[test]
+fn locked_posts_send_agents_that_havent_read_them_to_the_post() {
+    let config = SimulationConfig::default();
+    let mut ids = IdAllocator::new();
+    let mut engine = EngineFixture::with_posts(1)
+        .with_comments(1)
+        .build_with_ids(&mut ids);
+    let post = engine.content_pool[0].clone();
+    engine.lock_comments(post.id, LockReason::Manual).unwrap();
+    let mut rng = RngStreams::new(1);
+    let mut finish_a_comment = |read: bool| {
+        let mut agent = Individual::new(AgentId(50), &config, &engine)
+            .with_policy(Arc::new(TurnedAway::default()));
+        // Seen in a feed either way, but only opened when `read`
+        agent.viewed_content.insert(post.id);
+        if read {
+            agent.read_posts.insert(post.id);
+        }
+        agent.core.state = AgentState::ReadingComments {
+            post_id: post.id,
+            creator_id: post.creator_id,
+            current_comment_ids: post.comments.iter().map(|comment| comment.id).collect(),
+            current_comment_index: 0,
+            ticks_spent: 0,
+            ticks_required: 1,
+            potential_interest_gain: 0.0,
+        };
+        agent.tick(&mut TickContext {
+            engine: &engine,
+            ops: &mut Vec::new(),
+            ids: &mut ids,
+            config: &config,
+            rng: &mut rng,
+        });
+        agent.core.state
+    };
+
+    assert!(matches!(
+        finish_a_comment(false),
+        AgentState::ReadingPost { post_id, .. } if post_id == post.id
+    ));
+    assert!(matches!(
+        finish_a_comment(true),
+        AgentState::Scrolling { .. }
+    ));
+}
+
+#[test]
+fn rate_limits_reset_each_step() {
+    let mut engine = EngineFixture::with_posts(1).build();
+    engine.config.comment_rate_limit = 2;
+    let post_id = engine.content_pool[0].id;
+
+    comment(&mut engine, post_id, 1).unwrap();
+    comment(&mut engine, post_id, 2).unwrap();
+    assert_eq!(
+        comment(&mut engine, post_id, 3),
+        Err(EngineError::RateLimited(post_id))
+    );
+    engine.current_step = 1;
+    comment(&mut engine, post_id, 4).unwrap();
+
+    assert_eq!(engine.get_content_by_id(post_id).unwrap().comments.len(), 3);
+    assert_eq!(engine.rejected_comments[&post_id], 1);
+    assert!(!engine.comments_locked(post_id));
+}
+
+#[test]
+fn heated_threads_lock_themselves() {
+    let mut engine = RecommendationEngine::new();
+    engine.register_tags(&["politics".to_string()]);
+    engine.config.lock_conflict_threshold = 1.0;
+    let stance = |agreement| {
+        ProfileBuilder::new()
+            .tag_with_agreement("politics", 1.0, agreement)
+            .build()
+    };
+    let post_id = PostId(0);
+    engine.create_post(PostBuilder::new(post_id).profile(stance(0.6)).build());
+
+    let mut reply = |id: usize, agreement: f32| {
+        engine.apply_op(EngineOp::AddComment {
+            post_id,
+            comment: CommentBuilder::new(CommentId(id))
+                .profile(stance(agreement))
+                .build(),
+        })
+    };
+    reply(0, 0.2).unwrap();
+    reply(1, -0.5).unwrap();
+    assert_eq!(reply(2, 0.0), Err(EngineError::CommentsLocked(post_id)));
+
+    let post = engine.get_content_by_id(post_id).unwrap();
+    assert!((thread_conflict(post) - 1.1).abs() < 1e-6);
+    assert_eq!(engine.comment_locks[&post_id].reason, LockReason::Conflict);
+
+    // Clashes wind individuals up, and it wears off
+    let config = WellBeingConfig::default();
+    let mut wellbeing = WellBeing::new(&config);
+    wellbeing.conflict(&config);
+    let aroused = wellbeing.arousal;
+    assert!(aroused > 0.0);
+    wellbeing.offline(&config);
+    assert!(wellbeing.arousal < aroused);
+}
+
+#[test]
+fn banning_a_creator_can_lock_their_threads_and_the_effect_is_measured() {
+    let mut config = SimulationConfig {
+        seed: 12,
+        num_individuals: 12,
+        num_bots: 2,
+        num_organisations: 1,
+        ..SimulationConfig::default()
+    };
+    config.comment_locking.lock_banned = true;
+    config.comment_locking.effect_window = 10;
+    let mut simulation = Simulation::new(config);
+    simulation.run(40);
+
+    let bot = simulation
+        .agent_views()
+        .find(|agent| agent.agent_type == AgentType::Bot)
+        .unwrap()
+        .id;
+    let posts = simulation
+        .engine
+        .content_pool
+        .iter()
+        .filter(|post| post.creator_id == bot)
+        .count();
+    assert!(posts > 0);
+    simulation.schedule_intervention(40, Intervention::BanCreator(bot));
+    simulation.run(30);
+
+    let locks = &simulation.engine.comment_locks;
+    assert_eq!(locks.len(), posts);
+    assert!(locks
+        .values()
+        .all(|lock| lock.reason == LockReason::Moderation && lock.step == 40));
+    for post_id in locks.keys() {
+        let post = simulation.engine.get_content_by_id(*post_id).unwrap();
+        assert!(!post.cascade.iter().any(|event| {
+            event.step > 40 && matches!(event.action, CascadeAction::Commented(_))
+        }));
+    }
+
+    let effects = LockEffect::of(&simulation, 10);
+    assert_eq!(effects.len(), posts);
+    assert!(effects.iter().all(|effect| effect.complete));
+    assert!(
+        effects
+            .iter()
+            .map(|effect| effect.comments_before)
+            .sum::<usize>()
+            > 0
+    );
+
+    let mut export = MetricsExport::new();
+    export.record(&simulation);
+    let count = |metric: &str| {
+        export
+            .samples
+            .iter()
+            .filter(|sample| sample.metric == metric)
+            .count()
+    };
+    assert_eq!(count("lock_reads"), 2 * posts);
+    assert_eq!(count("arousal"), 12);
+}
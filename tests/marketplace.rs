@@ -0,0 +1,158 @@
+use social_media_sandbox::{
+    engine::{CreatorExclusions, EngineOp, PromotionKind},
+    metrics::MetricsExport,
+    models::{
+        AgentId, BidStrategy, Individual, Marketplace, MarketplaceConfig, PostId, PostIdList,
+        SimulationConfig,
+    },
+    test_support::EngineFixture,
+    RecommendationEngine, Simulation,
+};
+
+fn market(slots: usize, strategies: Vec<BidStrategy>) -> MarketplaceConfig {
+    MarketplaceConfig {
+        slots,
+        daily_budget: 1.0,
+        reserve_price: 0.1,
+        strategies,
+        adaptation: 0.5,
+    }
+}
+
+fn recommend(engine: &RecommendationEngine, count: usize) -> PostIdList {
+    let agent = Individual::new(AgentId(1), &SimulationConfig::default(), engine);
+    engine.get_post_recommendations(&agent, count, 0, &CreatorExclusions::none())
+}
+
+#[test]
+fn winners_pay_the_highest_losing_bid_out_of_a_daily_budget() {
+    let config = market(
+        1,
+        vec![
+            BidStrategy::Fixed(0.6),
+            BidStrategy::Fixed(0.4),
+            BidStrategy::Fixed(0.05),
+        ],
+    );
+    let offers = [
+        (AgentId(1), PostId(10)),
+        (AgentId(2), PostId(20)),
+        (AgentId(3), PostId(30)),
+    ];
+    let mut marketplace = Marketplace::default();
+
+    assert_eq!(marketplace.auction(0, 4, &config, &offers), [PostId(10)]);
+    let round = marketplace.latest().unwrap();
+    assert_eq!(round.bidders, 2);
+    assert_eq!(round.price, Some(0.4));
+    assert!((marketplace.bidders[&AgentId(1)].budget - 0.6).abs() < 1e-6);
+
+    // Once its budget can't cover its usual bid it bids what's left, and
+    // loses to a bid it used to beat
+    assert_eq!(marketplace.auction(1, 4, &config, &offers), [PostId(10)]);
+    assert_eq!(marketplace.auction(2, 4, &config, &offers), [PostId(20)]);
+    let price = marketplace.latest().unwrap().price.unwrap();
+    assert!((price - 0.2).abs() < 1e-6);
+    assert_eq!(marketplace.bidders[&AgentId(1)].wins, 2);
+
+    // Budgets refill with the day
+    assert_eq!(marketplace.auction(4, 4, &config, &offers), [PostId(10)]);
+    assert_eq!(marketplace.bidders[&AgentId(1)].wins, 3);
+}
+
+#[test]
+fn adaptive_bidders_chase_the_clearing_price() {
+    let config = market(1, vec![BidStrategy::Fixed(0.5), BidStrategy::Adaptive]);
+    let offers = [(AgentId(1), PostId(10)), (AgentId(2), PostId(20))];
+    let mut marketplace = Marketplace::default();
+
+    // Starts at its paced share of the budget, below the fixed bid
+    marketplace.auction(0, 10, &config, &offers);
+    assert!(marketplace.bidders[&AgentId(2)].bid > 0.1);
+    let mut winners = Vec::new();
+    for step in 1..6 {
+        winners.extend(marketplace.auction(step, 10, &config, &offers));
+    }
+    assert!(winners.contains(&PostId(20)));
+    assert!(marketplace
+        .rounds
+        .iter()
+        .filter_map(|round| round.price)
+        .any(|price| price > 0.1));
+}
+
+#[test]
+fn sponsored_posts_take_capped_slots_below_pins_and_count_apart() {
+    let mut engine = EngineFixture::with_posts(10)
+        .with_tags(&["science"])
+        .with_creator(AgentId(2))
+        .build();
+    let ids: Vec<PostId> = engine.content_pool.iter().map(|post| post.id).collect();
+    engine.promote(ids[0], PromotionKind::Pin, 10).unwrap();
+    engine.sponsored = vec![ids[1], ids[2], ids[3]];
+    engine.config.sponsored_slots = 2;
+
+    assert_eq!(recommend(&engine, 5)[..3], [ids[0], ids[1], ids[2]]);
+    assert_eq!(recommend(&engine, 2)[..], [ids[0], ids[1]]);
+
+    engine
+        .apply_op(EngineOp::RecordSponsoredExposure { post_id: ids[1] })
+        .unwrap();
+    assert_eq!(engine.sponsored_exposures[&ids[1]], 1);
+    assert!(engine.content_pool[1].cascade.is_empty());
+}
+
+#[test]
+fn organisations_buy_exposure_from_organic_posts() {
+    let mut config = SimulationConfig {
+        seed: 5,
+        num_individuals: 12,
+        num_bots: 2,
+        num_organisations: 3,
+        ..SimulationConfig::default()
+    };
+    config.marketplace = MarketplaceConfig {
+        slots: 1,
+        daily_budget: 2.0,
+        ..MarketplaceConfig::default()
+    };
+    let steps_per_day = config.steps_per_day;
+    let mut simulation = Simulation::new(config);
+    simulation.run(steps_per_day + 20);
+
+    let marketplace = &simulation.marketplace;
+    assert_eq!(marketplace.len() as u64, steps_per_day + 20);
+    assert_eq!(marketplace.bidders.len(), 3);
+    assert!(marketplace
+        .rounds
+        .iter()
+        .all(|round| round.winners.len() <= 1));
+    let share = marketplace.crowding_out().unwrap();
+    assert!(share > 0.0 && share < 1.0, "{share}");
+    // Nobody spends more than a day's budget for each day it's been in
+    let days = 2.0;
+    assert!(marketplace
+        .bidders
+        .values()
+        .all(|bidder| bidder.spent <= days * 2.0 + 1e-4));
+
+    let sponsored: usize = simulation.engine.sponsored_exposures.values().sum();
+    let recorded: usize = marketplace
+        .rounds
+        .iter()
+        .map(|round| round.sponsored_exposures)
+        .sum();
+    assert_eq!(sponsored, recorded);
+
+    let mut export = MetricsExport::new();
+    export.record(&simulation);
+    let count = |metric: &str| {
+        export
+            .samples
+            .iter()
+            .filter(|sample| sample.metric == metric)
+            .count()
+    };
+    assert_eq!(count("sponsor_spend"), 3);
+    assert_eq!(count("sponsored_share"), 1);
+}
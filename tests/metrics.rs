@@ -0,0 +1,144 @@
+use social_media_sandbox::{
+    metrics::{
+        Compatibility, Metric, MetricDef, MetricSchema, MetricsError, MetricsExport,
+        MetricsRegistry,
+    },
+    models::SimulationConfig,
+    Simulation,
+};
+
+fn simulation() -> Simulation {
+    Simulation::new(SimulationConfig {
+        seed: 1,
+        ..SimulationConfig::default()
+    })
+}
+
+#[test]
+fn exports_round_trip_with_the_current_schema() {
+    let mut simulation = simulation();
+    let mut export = MetricsExport::new();
+    export.record(&simulation);
+    simulation.step();
+    export.record(&simulation);
+
+    let (loaded, compatibility) = MetricsExport::from_json(&export.to_json().unwrap()).unwrap();
+
+    assert_eq!(compatibility, Compatibility::Identical);
+    assert_eq!(loaded, export);
+    assert!(export
+        .samples
+        .iter()
+        .all(|sample| export.schema.get(&sample.metric).is_some()));
+}
+
+#[test]
+fn older_schemas_missing_metrics_are_compatible() {
+    let mut old = MetricSchema::current();
+    old.metrics.pop();
+    old.metrics.push(MetricDef {
+        name: "retired".to_string(),
+        unit: "count".to_string(),
+        dimensions: vec!["agent_id".to_string()],
+    });
+
+    let current = MetricSchema::current();
+    let Compatibility::Compatible { added, removed } = old.compatibility(&current) else {
+        panic!("expected a compatible schema");
+    };
+
+    assert_eq!(added, vec![current.metrics.last().unwrap().name.clone()]);
+    assert_eq!(removed, vec!["retired".to_string()]);
+}
+
+#[test]
+fn changed_units_or_dimensions_are_incompatible() {
+    let mut old = MetricSchema::current();
+    old.metrics[0].unit = "nats".to_string();
+    old.metrics[1].dimensions.pop();
+
+    let Compatibility::Incompatible(problems) = old.compatibility(&MetricSchema::current()) else {
+        panic!("expected an incompatible schema");
+    };
+
+    assert_eq!(problems.len(), 2);
+}
+
+#[test]
+fn exports_from_newer_schemas_are_rejected() {
+    let mut export = MetricsExport::new();
+    export.schema.version += 1;
+
+    assert!(matches!(
+        MetricsExport::from_json(&export.to_json().unwrap()),
+        Err(MetricsError::Incompatible(_))
+    ));
+}
+
+struct PostCount;
+
+impl Metric for PostCount {
+    fn value(&self, simulation: &Simulation) -> f64 {
+        simulation.engine.content_pool.len() as f64
+    }
+}
+
+#[test]
+fn registered_metrics_are_recorded_and_exported() {
+    let mut registry = MetricsRegistry::new();
+    registry
+        .register("step_squared", |simulation: &Simulation| {
+            (simulation.step_count as f64).powi(2)
+        })
+        .unwrap()
+        .register_with_unit("posts", "posts", PostCount)
+        .unwrap()
+        .register("undefined", |_: &Simulation| f64::NAN)
+        .unwrap();
+
+    let mut simulation = simulation();
+    let mut export = MetricsExport::new();
+    for _ in 0..3 {
+        simulation.step();
+        export.record_with(&simulation, &registry);
+    }
+
+    let values = |metric: &str| -> Vec<(u64, f32)> {
+        export
+            .samples
+            .iter()
+            .filter(|sample| sample.metric == metric)
+            .map(|sample| (sample.step, sample.value))
+            .collect()
+    };
+    assert_eq!(values("step_squared"), vec![(1, 1.0), (2, 4.0), (3, 9.0)]);
+    assert_eq!(values("posts").len(), 3);
+    assert!(values("undefined").is_empty());
+    assert_eq!(export.schema.get("posts").unwrap().unit, "posts");
+    assert_eq!(
+        export
+            .schema
+            .metrics
+            .iter()
+            .filter(|metric| metric.name == "step_squared")
+            .count(),
+        1
+    );
+
+    let (loaded, _) = MetricsExport::from_json(&export.to_json().unwrap()).unwrap();
+    assert_eq!(loaded, export);
+}
+
+#[test]
+fn metric_names_cannot_be_registered_twice() {
+    let mut registry = MetricsRegistry::new();
+    registry.register("mine", |_: &Simulation| 1.0).unwrap();
+
+    for name in ["mine", "interest_entropy"] {
+        assert!(matches!(
+            registry.register(name, |_: &Simulation| 1.0),
+            Err(MetricsError::Duplicate(_))
+        ));
+    }
+    assert_eq!(registry.len(), 1);
+}
@@ -0,0 +1,223 @@
+use rand::RngCore;
+use social_media_sandbox::{
+    metrics::MetricsExport,
+    models::{
+        Agent, AgentId, AgentState, Choice, IdAllocator, Individual, Intervention, Nudge,
+        NudgeConfig, PopulationMetric, RngStreams, SimulationConfig, TickContext, TransitionPolicy,
+    },
+    test_support::EngineFixture,
+    Simulation,
+};
+use std::sync::Arc;
+
+fn run(nudges: NudgeConfig, ticks: u64) -> Simulation {
+    let mut simulation = Simulation::new(SimulationConfig {
+        seed: 17,
+        num_individuals: 10,
+        num_bots: 1,
+        num_organisations: 1,
+        nudges,
+        ..SimulationConfig::default()
+    });
+    for _ in 0..ticks {
+        simulation.step();
+    }
+    simulation
+}
+
+fn mean_screen_time(simulation: &Simulation) -> f32 {
+    PopulationMetric::ScreenTime
+        .mean_where(simulation, |_| true)
+        .unwrap()
+}
+
+#[test]
+fn nudges_are_never_shown_when_switched_off() {
+    let simulation = run(NudgeConfig::default(), 200);
+    let stats = simulation.nudge_stats();
+
+    for nudge in Nudge::ALL {
+        assert_eq!(stats.get(nudge).shown, 0, "{}", nudge.name());
+    }
+}
+
+#[test]
+fn fully_compliant_agents_read_before_commenting() {
+    let simulation = run(
+        NudgeConfig {
+            read_before_share: 1.0,
+            ..NudgeConfig::default()
+        },
+        300,
+    );
+    let counts = *simulation.nudge_stats().get(Nudge::ReadBeforeShare);
+
+    assert!(counts.shown > 0);
+    assert_eq!(counts.complied, counts.shown);
+}
+
+// Never opens a post of its own accord, and always wants to comment
+#[derive(Debug)]
+struct Commenter;
+
+impl TransitionPolicy for Commenter {
+    fn should_go_offline(&self, _agent: &Individual, _rng: &mut dyn RngCore) -> Choice {
+        false.into()
+    }
+
+    fn should_select_post(&self, _agent: &Individual, _rng: &mut dyn RngCore) -> Choice {
+        false.into()
+    }
+
+    fn should_read_post(&self, _agent: &Individual, _rng: &mut dyn RngCore) -> Choice {
+        false.into()
+    }
+
+    fn should_read_comments(&self, _agent: &Individual, _rng: &mut dyn RngCore) -> Choice {
+        false.into()
+    }
+
+    fn should_write_comment(&self, _agent: &Individual, _rng: &mut dyn RngCore) -> Choice {
+        true.into()
+    }
+
+    fn should_scroll(&self, _agent: &Individual, _rng: &mut dyn RngCore) -> Choice {
+        false.into()
+    }
+}
+
+#[test]
+fn agents_reading_the_comments_are_nudged_to_read_the_post() {
+    let config = SimulationConfig {
+        nudges: NudgeConfig {
+            read_before_share: 1.0,
+            ..NudgeConfig::default()
+        },
+        ..SimulationConfig::default()
+    };
+    let mut ids = IdAllocator::new();
+    let engine = EngineFixture::with_posts(1)
+        .with_comments(1)
+        .build_with_ids(&mut ids);
+    let post = &engine.content_pool[0];
+    let mut agent = Individual::new(AgentId(50), &config, &engine).with_policy(Arc::new(Commenter));
+    // Shown the post in a feed without ever opening it
+    agent.viewed_content.insert(post.id);
+    let mut rng = RngStreams::new(1);
+    let mut finish_a_comment = |agent: &mut Individual| {
+        agent.core.state = AgentState::ReadingComments {
+            post_id: post.id,
+            creator_id: post.creator_id,
+            current_comment_ids: post.comments.iter().map(|comment| comment.id).collect(),
+            current_comment_index: 0,
+            ticks_spent: 0,
+            ticks_required: 1,
+            potential_interest_gain: 0.0,
+        };
+        agent.tick(&mut TickContext {
+            engine: &engine,
+            ops: &mut Vec::new(),
+            ids: &mut ids,
+            config: &config,
+            rng: &mut rng,
+        });
+    };
+
+    finish_a_comment(&mut agent);
+    assert!(matches!(
+        agent.core.state,
+        AgentState::ReadingPost { post_id, .. } if post_id == post.id
+    ));
+    let counts = *agent.nudges.get(Nudge::ReadBeforeShare);
+    assert_eq!((counts.shown, counts.complied), (1, 1));
+
+    // Once it has read the post there is nothing to nudge about
+    agent.read_posts.insert(post.id);
+    finish_a_comment(&mut agent);
+    assert!(matches!(
+        agent.core.state,
+        AgentState::CreatingComment { post_id, .. } if post_id == post.id
+    ));
+    assert_eq!(agent.nudges.get(Nudge::ReadBeforeShare).shown, 1);
+}
+
+#[test]
+fn caught_up_markers_end_sessions() {
+    let baseline = run(NudgeConfig::default(), 300);
+    let nudged = run(
+        NudgeConfig {
+            caught_up: 0.5,
+            caught_up_window: 1000,
+            ..NudgeConfig::default()
+        },
+        300,
+    );
+    let counts = *nudged.nudge_stats().get(Nudge::CaughtUp);
+
+    assert!(counts.complied > 0);
+    assert!(counts.complied < counts.shown);
+    assert!(mean_screen_time(&nudged) < mean_screen_time(&baseline));
+}
+
+#[test]
+fn time_reminders_are_shown_once_per_session_and_cut_screen_time() {
+    let baseline = run(NudgeConfig::default(), 300);
+    let nudged = run(
+        NudgeConfig {
+            time_reminder: 1.0,
+            reminder_ticks: 30,
+            ..NudgeConfig::default()
+        },
+        300,
+    );
+    let counts = *nudged.nudge_stats().get(Nudge::TimeReminder);
+
+    assert!(counts.shown > 0);
+    assert!(counts.complied <= counts.shown);
+    assert!(mean_screen_time(&nudged) < mean_screen_time(&baseline));
+    for wellbeing in nudged.agent_views().filter_map(|agent| agent.wellbeing) {
+        assert_eq!(wellbeing.late_session_time, 0);
+    }
+}
+
+#[test]
+fn interventions_switch_nudges_and_compliance_is_exported() {
+    let mut simulation = run(NudgeConfig::default(), 0);
+    let intervention = Intervention::SetNudge {
+        nudge: Nudge::TimeReminder,
+        compliance: 0.8,
+    };
+    assert_eq!(
+        intervention.describe(),
+        "Show time reminder nudge with compliance 0.80"
+    );
+    simulation.config.nudges.reminder_ticks = 10;
+    simulation.schedule_intervention(5, intervention);
+    for _ in 0..200 {
+        simulation.step();
+    }
+    assert_eq!(simulation.config.nudges.time_reminder, 0.8);
+
+    let mut export = MetricsExport::new();
+    export.record(&simulation);
+    let compliance = export
+        .samples
+        .iter()
+        .find(|sample| {
+            sample.metric == "nudge_compliance" && sample.dimensions["nudge"] == "time reminder"
+        })
+        .unwrap();
+    assert_eq!(
+        Some(compliance.value),
+        simulation
+            .nudge_stats()
+            .get(Nudge::TimeReminder)
+            .compliance()
+    );
+
+    let off = Intervention::SetNudge {
+        nudge: Nudge::CaughtUp,
+        compliance: 0.0,
+    };
+    assert_eq!(off.describe(), "Stop caught up nudge");
+}
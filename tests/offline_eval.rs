@@ -0,0 +1,91 @@
+use social_media_sandbox::{
+    engine::{
+        offline_eval::{replay, ExposureLog, LoggedCandidate, Reweighted},
+        CreatorExclusions,
+    },
+    models::{AgentId, IdAllocator, Individual, SimulationConfig},
+    test_support::PostBuilder,
+    RecommendationEngine,
+};
+
+const AGENT: AgentId = AgentId(1);
+const OTHER: AgentId = AgentId(2);
+
+// Ten posts whose scores only differ by engagement, with every slate logged
+fn logged_engine(ids: &mut IdAllocator) -> RecommendationEngine {
+    let mut engine = RecommendationEngine::new();
+    engine.config.min_score = 0.0;
+    engine.exposures = ExposureLog::new(10);
+
+    for i in 0..10 {
+        engine.create_post(
+            PostBuilder::new(ids.next_post_id())
+                .creator(OTHER)
+                .engagement(i as f32 / 16.0)
+                .build(),
+        );
+    }
+
+    engine
+}
+
+#[test]
+fn slates_log_every_candidate_and_what_was_served() {
+    let mut ids = IdAllocator::new();
+    let engine = logged_engine(&mut ids);
+    let agent = Individual::new(AGENT, &SimulationConfig::default(), &engine);
+
+    let served = engine.get_post_recommendations(&agent, 3, 0, &CreatorExclusions::none());
+
+    let slates = engine.exposures.slates();
+    assert_eq!(slates.len(), 1);
+    assert_eq!(slates[0].agent_id, AGENT);
+    assert_eq!(slates[0].candidates.len(), 10);
+    assert_eq!(slates[0].served, served.to_vec());
+}
+
+#[test]
+fn logging_is_off_by_default() {
+    let mut ids = IdAllocator::new();
+    let mut engine = logged_engine(&mut ids);
+    engine.exposures = ExposureLog::default();
+    let agent = Individual::new(AGENT, &SimulationConfig::default(), &engine);
+
+    engine.get_post_recommendations(&agent, 3, 0, &CreatorExclusions::none());
+
+    assert!(engine.exposures.is_empty());
+}
+
+#[test]
+fn replaying_the_logging_ranker_covers_every_slot() {
+    let mut ids = IdAllocator::new();
+    let engine = logged_engine(&mut ids);
+    let agent = Individual::new(AGENT, &SimulationConfig::default(), &engine);
+    engine.get_post_recommendations(&agent, 3, 0, &CreatorExclusions::none());
+
+    let same = Reweighted {
+        interest: 1.0,
+        recency: 1.0,
+        engagement: 1.0,
+    };
+    let evaluation = replay(&engine.exposures.slates(), &same, |_, _| 1.0);
+
+    assert_eq!(evaluation.slates, 1);
+    assert_eq!(evaluation.coverage, 1.0);
+    assert_eq!(evaluation.estimated_value, evaluation.logged_value);
+}
+
+#[test]
+fn replaying_a_reversed_ranker_only_credits_shared_picks() {
+    let mut ids = IdAllocator::new();
+    let engine = logged_engine(&mut ids);
+    let agent = Individual::new(AGENT, &SimulationConfig::default(), &engine);
+    engine.get_post_recommendations(&agent, 3, 0, &CreatorExclusions::none());
+
+    let reversed = |candidate: &LoggedCandidate| -candidate.score;
+    let evaluation = replay(&engine.exposures.slates(), &reversed, |_, _| 1.0);
+
+    assert_eq!(evaluation.coverage, 0.0);
+    assert_eq!(evaluation.estimated_value, 0.0);
+    assert_eq!(evaluation.logged_value, 1.0);
+}
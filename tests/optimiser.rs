@@ -0,0 +1,110 @@
+use social_media_sandbox::{
+    models::{
+        Optimiser, ParameterRange, PopulationMetric, ScenarioError, SimulationConfig, Statistic,
+        Target,
+    },
+    Simulation,
+};
+
+const TICKS: u64 = 150;
+
+fn base() -> SimulationConfig {
+    SimulationConfig {
+        seed: 3,
+        num_individuals: 10,
+        ..SimulationConfig::default()
+    }
+}
+
+// Targets taken from a run with a known position bias, standing in for a
+// reference dataset
+fn reference(position_bias: f32) -> Vec<Target> {
+    let mut simulation = Simulation::new(SimulationConfig {
+        position_bias,
+        ..base()
+    });
+    for _ in 0..TICKS {
+        simulation.step();
+    }
+    Target::distribution(
+        PopulationMetric::PositionDriven,
+        &PopulationMetric::PositionDriven.values(&simulation),
+        &[0.25, 0.5, 0.75],
+    )
+}
+
+fn optimiser(budget: usize) -> Optimiser {
+    Optimiser::new(
+        base(),
+        vec![ParameterRange::float("position_bias", 0.0, 3.0)],
+        reference(1.2),
+        TICKS,
+        budget,
+    )
+    .unwrap()
+}
+
+#[test]
+fn the_search_gets_close_to_the_reference() {
+    let mut optimiser = optimiser(16);
+    let best = optimiser.run().unwrap().unwrap().clone();
+
+    assert_eq!(optimiser.trials.len(), 16);
+    assert!(optimiser.trials.iter().all(|trial| trial.loss >= best.loss));
+    assert!((best.values[0] - 1.2).abs() < 0.3, "{best:?}");
+
+    let config = optimiser.config(&best.values).unwrap();
+    assert_eq!(config.position_bias, best.values[0]);
+}
+
+#[test]
+fn searches_are_reproducible() {
+    let (mut a, mut b) = (optimiser(4), optimiser(4));
+    a.run().unwrap();
+    b.run().unwrap();
+
+    assert_eq!(a.trials, b.trials);
+}
+
+#[test]
+fn integer_parameters_stay_whole_and_in_range() {
+    let mut optimiser = Optimiser::new(
+        base(),
+        vec![ParameterRange::integer("num_individuals", 2, 6)],
+        vec![Target::mean(PopulationMetric::SessionLength, 20.0)],
+        5,
+        6,
+    )
+    .unwrap();
+    optimiser.run().unwrap();
+
+    for trial in &optimiser.trials {
+        let count = trial.values[0];
+        assert_eq!(count, count.round());
+        assert!((2.0..=6.0).contains(&count));
+    }
+}
+
+#[test]
+fn unknown_parameters_are_refused_up_front() {
+    let result = Optimiser::new(
+        base(),
+        vec![ParameterRange::float("no_such_key", 0.0, 1.0)],
+        Vec::new(),
+        TICKS,
+        1,
+    );
+
+    assert!(matches!(result, Err(ScenarioError::UnknownKey(_))));
+}
+
+#[test]
+fn quantiles_pick_from_the_sorted_values() {
+    let values = [5.0, 1.0, 4.0, 2.0, 3.0];
+
+    assert_eq!(Statistic::Mean.of(&values), Some(3.0));
+    assert_eq!(Statistic::Quantile(0.0).of(&values), Some(1.0));
+    assert_eq!(Statistic::Quantile(0.5).of(&values), Some(3.0));
+    assert_eq!(Statistic::Quantile(1.0).of(&values), Some(5.0));
+    assert_eq!(Statistic::Quantile(0.5).of(&[]), None);
+}
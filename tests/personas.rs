@@ -0,0 +1,66 @@
+use social_media_sandbox::models::{
+    assign_personas, Persona, RngStreams, Simulation, SimulationConfig,
+};
+use std::collections::BTreeMap;
+
+#[test]
+fn personas_are_assigned_by_population_fraction() {
+    let library = Persona::library();
+    let fractions = BTreeMap::from([
+        ("sports fan".to_string(), 0.5),
+        ("tech professional".to_string(), 0.25),
+    ]);
+
+    let assigned = assign_personas(&library, &fractions, 100);
+    let count = |name: &str| {
+        assigned
+            .iter()
+            .filter(|persona| persona.is_some_and(|persona| persona.name == name))
+            .count()
+    };
+
+    assert_eq!(count("sports fan"), 50);
+    assert_eq!(count("tech professional"), 25);
+    assert_eq!(
+        assigned.iter().filter(|persona| persona.is_none()).count(),
+        25
+    );
+}
+
+#[test]
+fn sampled_profiles_follow_the_persona_priors() {
+    let mut rng = RngStreams::stream(7, "personas");
+
+    for persona in Persona::library() {
+        let profile = persona.sample_profile(8, &mut rng);
+
+        assert_eq!(profile.interests.len(), persona.tags.len());
+        let total: f32 = profile.normalised_weights().map(|(_, weight)| weight).sum();
+        assert!((total - 1.0).abs() < 1e-3);
+
+        for prior in &persona.tags {
+            let agreement = profile.interests[&prior.tag].agreement;
+            let magnitude = if prior.either_side {
+                agreement.abs()
+            } else {
+                agreement
+            };
+            assert!(magnitude >= prior.agreement.0 && magnitude <= prior.agreement.1);
+        }
+    }
+}
+
+#[test]
+fn simulation_seeds_individuals_from_personas() {
+    let config = SimulationConfig {
+        num_individuals: 20,
+        ..SimulationConfig::default()
+    };
+    let simulation = Simulation::new(config);
+
+    assert!(simulation
+        .agents
+        .iter()
+        .filter_map(|agent| agent.persona())
+        .any(|persona| persona == "casual lurker"));
+}
@@ -0,0 +1,78 @@
+use social_media_sandbox::{
+    models::{PolicyKind, PopulationMetric, RunSummary, SimulationConfig},
+    Simulation,
+};
+
+fn simulation(policy: PolicyKind) -> Simulation {
+    Simulation::new(SimulationConfig {
+        seed: 4,
+        num_individuals: 30,
+        num_bots: 0,
+        num_organisations: 1,
+        policy,
+        ..SimulationConfig::default()
+    })
+}
+
+fn session_length(simulation: &Simulation) -> f32 {
+    PopulationMetric::SessionLength
+        .mean_where(simulation, |_| true)
+        .unwrap()
+}
+
+#[test]
+fn built_in_policies_change_how_individuals_behave() {
+    let run = |policy| -> (RunSummary, f32) {
+        let mut simulation = simulation(policy);
+        let summary = simulation.run(300);
+        (summary, session_length(&simulation))
+    };
+    let (default, default_session) = run(PolicyKind::Default);
+    let (addictive, addictive_session) = run(PolicyKind::Addictive);
+    let (casual, casual_session) = run(PolicyKind::Casual);
+
+    assert!(addictive.reads_completed > default.reads_completed);
+    assert!(casual.reads_completed < default.reads_completed);
+    assert!(addictive_session >= default_session);
+    assert!(casual_session < default_session / 10.0);
+}
+
+#[test]
+fn switching_policy_mid_run_reaches_running_agents() {
+    let mut simulation = simulation(PolicyKind::Default);
+    simulation.run(100);
+    assert!(session_length(&simulation) > 90.0);
+
+    simulation.set_policy(PolicyKind::Casual);
+    simulation.run(100);
+    assert_eq!(simulation.config.policy, PolicyKind::Casual);
+    assert!(session_length(&simulation) < 50.0);
+
+    // Swapping in a whole config switches too
+    let config = SimulationConfig {
+        policy: PolicyKind::Addictive,
+        ..simulation.config.clone()
+    };
+    let mut casual = simulation.clone();
+    simulation.set_config(config);
+    assert!(simulation.run(100).reads_completed > casual.run(100).reads_completed);
+}
+
+#[test]
+fn agents_are_built_the_same_whatever_the_policy() {
+    let profiles = |policy| -> Vec<_> {
+        simulation(policy)
+            .agent_views()
+            .map(|view| {
+                (
+                    view.id,
+                    view.interest_profile.to_table(),
+                    view.persona.map(str::to_string),
+                )
+            })
+            .collect()
+    };
+    let default = profiles(PolicyKind::Default);
+    assert_eq!(profiles(PolicyKind::Addictive), default);
+    assert_eq!(profiles(PolicyKind::Casual), default);
+}
@@ -0,0 +1,57 @@
+use social_media_sandbox::{
+    models::SimulationConfig,
+    profiling::{span, spans_running, start_spans, stop_spans},
+    Simulation,
+};
+
+#[test]
+fn nested_spans_fold_into_stacks() {
+    start_spans();
+    {
+        let _outer = span("outer");
+        for _ in 0..2 {
+            let _inner = span("inner");
+        }
+    }
+    let profile = stop_spans().unwrap();
+
+    let stacks: Vec<&str> = profile.stacks.keys().map(String::as_str).collect();
+    assert_eq!(stacks, ["outer", "outer;inner"]);
+    assert_eq!(profile.inclusive("outer"), profile.total());
+    assert!(profile.inclusive("inner") <= profile.total());
+    for line in profile.folded().lines() {
+        let (_, nanos) = line.rsplit_once(' ').unwrap();
+        nanos.parse::<u64>().unwrap();
+    }
+}
+
+#[test]
+fn steps_are_attributed_to_agents_and_the_engine() {
+    let mut simulation = Simulation::new(SimulationConfig {
+        seed: 1,
+        num_individuals: 5,
+        ..SimulationConfig::default()
+    });
+
+    start_spans();
+    for _ in 0..50 {
+        simulation.step();
+    }
+    let profile = stop_spans().unwrap();
+
+    assert!(profile.stacks.keys().all(|stack| stack.starts_with("step")));
+    assert!(profile
+        .stacks
+        .keys()
+        .any(|stack| stack.starts_with("step;agent ticks;individual;")
+            && stack.ends_with("post recommendations;scoring")));
+    assert!(profile.inclusive("agent ticks") <= profile.inclusive("step"));
+}
+
+#[test]
+fn nothing_is_recorded_unless_started() {
+    assert!(!spans_running());
+    drop(span("ignored"));
+
+    assert_eq!(stop_spans(), None);
+}
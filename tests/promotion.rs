@@ -0,0 +1,135 @@
+use social_media_sandbox::{
+    engine::{
+        CreatorExclusions, EngineError, EngineOp, PromotionKind, RetrievalConfig, RetrievalSource,
+    },
+    metrics::MetricsExport,
+    models::{
+        AgentId, CascadeAction, Individual, Intervention, PostId, PostIdList, SimulationConfig,
+    },
+    test_support::EngineFixture,
+    RecommendationEngine, Simulation,
+};
+
+const AGENT: AgentId = AgentId(1);
+
+fn engine() -> RecommendationEngine {
+    EngineFixture::with_posts(20)
+        .with_tags(&["science"])
+        .with_creator(AgentId(2))
+        .build()
+}
+
+fn recommend(engine: &RecommendationEngine, count: usize) -> PostIdList {
+    let agent = Individual::new(AGENT, &SimulationConfig::default(), engine);
+    engine.get_post_recommendations(&agent, count, 0, &CreatorExclusions::none())
+}
+
+#[test]
+fn pinned_posts_top_every_feed_for_their_window() {
+    let mut engine = engine();
+    let (oldest, older) = (engine.content_pool[0].id, engine.content_pool[1].id);
+    let newest = engine.content_pool[19].id;
+    let organic = recommend(&engine, 5);
+    engine.promote(oldest, PromotionKind::Pin, 10).unwrap();
+    engine.promote(older, PromotionKind::Pin, 10).unwrap();
+    engine.mark_breaking(newest, 10);
+
+    let posts = recommend(&engine, 5);
+    assert_eq!(posts[..3], [older, oldest, newest]);
+    assert_eq!(posts.len(), 5);
+    assert_eq!(recommend(&engine, 1)[..], [older]);
+
+    engine.current_step = 10;
+    assert_eq!(engine.active_promotion(oldest), None);
+    assert_eq!(recommend(&engine, 5), organic);
+
+    assert_eq!(
+        engine.promote(PostId(99), PromotionKind::Pin, 20),
+        Err(EngineError::PostNotFound(PostId(99)))
+    );
+}
+
+#[test]
+fn boosted_posts_are_ranked_even_when_retrieval_misses_them() {
+    let mut engine = engine();
+    engine.config.retrieval = RetrievalConfig {
+        sources: vec![RetrievalSource::Recent],
+        per_source: 3,
+    };
+    let oldest = engine.content_pool[0].id;
+    assert!(!recommend(&engine, 3).contains(&oldest));
+
+    engine
+        .promote(oldest, PromotionKind::Boost(100.0), 5)
+        .unwrap();
+    assert_eq!(recommend(&engine, 3)[0], oldest);
+}
+
+#[test]
+fn promoted_exposures_are_kept_out_of_the_cascade() {
+    let mut engine = engine();
+    let post_id = engine.content_pool[0].id;
+    engine.promote(post_id, PromotionKind::Pin, 5).unwrap();
+
+    for (agent, promoted) in [(3, true), (4, true), (3, true), (5, false)] {
+        engine
+            .apply_op(EngineOp::RecordExposure {
+                post_id,
+                agent_id: AgentId(agent),
+                promoted,
+            })
+            .unwrap();
+    }
+
+    let promotion = &engine.promotions[&post_id];
+    assert_eq!(promotion.exposures, 3);
+    assert_eq!(promotion.reach(), 2);
+    let cascade = &engine.get_content_by_id(post_id).unwrap().cascade;
+    assert_eq!(cascade.len(), 1);
+    assert_eq!(cascade[0].agent_id, AgentId(5));
+}
+
+#[test]
+fn announcements_reach_the_population_without_counting_as_organic() {
+    let mut simulation = Simulation::new(SimulationConfig {
+        seed: 8,
+        num_individuals: 15,
+        num_bots: 2,
+        num_organisations: 1,
+        ..SimulationConfig::default()
+    });
+    simulation.run(20);
+    // The newest post, which few have seen yet
+    let post_id = simulation.engine.content_pool.last().unwrap().id;
+    simulation.schedule_intervention(
+        20,
+        Intervention::Promote {
+            post_id,
+            kind: PromotionKind::Pin,
+            ticks: 30,
+        },
+    );
+    simulation.run(60);
+
+    let promotion = &simulation.engine.promotions[&post_id];
+    assert!(promotion.exposures > 0);
+    assert!(promotion.reach() <= 15);
+    let post = simulation.engine.get_content_by_id(post_id).unwrap();
+    assert!(!post
+        .cascade
+        .iter()
+        .any(|event| event.action == CascadeAction::Exposed && (20..50).contains(&event.step)));
+
+    let mut export = MetricsExport::new();
+    export.record(&simulation);
+    let exposures: Vec<_> = export
+        .samples
+        .iter()
+        .filter(|sample| sample.metric == "promoted_exposures")
+        .map(|sample| (sample.dimensions["post"].clone(), sample.value))
+        .collect();
+    assert_eq!(
+        exposures,
+        [(post_id.to_string(), promotion.exposures as f32)]
+    );
+}
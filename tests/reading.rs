@@ -0,0 +1,92 @@
+use social_media_sandbox::{
+    models::{
+        AgentState, ContentKind, InvalidConfig, MediaRates, ReadMedia, ReadTimeModel, Scenario,
+        SimulationConfig,
+    },
+    Simulation,
+};
+
+#[test]
+fn each_model_times_reading_its_own_way() {
+    let post = ReadMedia::Post(ContentKind::Organic);
+
+    assert_eq!(ReadTimeModel::Linear.ticks(60, 0.5, post), 30);
+    assert_eq!(ReadTimeModel::Linear.ticks(60, 0.5, ReadMedia::Comment), 30);
+
+    // Doubling the length adds far less than double the time
+    let log = ReadTimeModel::LogLength { scale: 10.0 };
+    assert_eq!(log.ticks(0, 0.0, post), 0);
+    assert_eq!(log.ticks(30, 0.0, post), 34);
+    assert_eq!(log.ticks(60, 0.0, post), 41);
+
+    let media = ReadTimeModel::PerMedia {
+        rates: MediaRates::default(),
+    };
+    assert_eq!(media.ticks(40, 0.0, post), 40);
+    assert_eq!(
+        media.ticks(40, 0.0, ReadMedia::Post(ContentKind::Breaking)),
+        24
+    );
+    assert_eq!(media.ticks(40, 0.0, ReadMedia::Comment), 20);
+
+    let skim = ReadTimeModel::Skim {
+        full: 20,
+        rate: 0.25,
+    };
+    assert_eq!(skim.ticks(10, 0.0, post), 10);
+    assert_eq!(skim.ticks(60, 0.0, post), 30);
+    assert_eq!(skim.ticks(60, 0.5, post), 15);
+}
+
+#[test]
+fn individuals_read_for_as_long_as_the_model_says() {
+    let mut simulation = Simulation::new(SimulationConfig {
+        seed: 9,
+        num_individuals: 12,
+        num_bots: 2,
+        num_organisations: 2,
+        read_time: ReadTimeModel::Skim { full: 5, rate: 0.0 },
+        ..SimulationConfig::default()
+    });
+
+    let mut reads = 0;
+    for _ in 0..150 {
+        simulation.step();
+        for agent in simulation.agent_views() {
+            if let AgentState::ReadingPost { ticks_required, .. } = agent.state {
+                assert!(*ticks_required <= 5, "{ticks_required}");
+                reads += 1;
+            }
+        }
+    }
+    assert!(reads > 0);
+}
+
+#[test]
+fn models_round_trip_through_scenarios_and_are_validated() {
+    let config = SimulationConfig {
+        read_time: ReadTimeModel::PerMedia {
+            rates: MediaRates {
+                comment: 0.8,
+                ..MediaRates::default()
+            },
+        },
+        ..SimulationConfig::default()
+    };
+    let scenario = Scenario::parse(&Scenario::to_toml(&config).unwrap()).unwrap();
+    assert_eq!(scenario.config.read_time, config.read_time);
+
+    let Err(InvalidConfig(problems)) = SimulationConfig::builder()
+        .with(|config| {
+            config.read_time = ReadTimeModel::Skim {
+                full: -1,
+                rate: 1.5,
+            }
+        })
+        .build()
+    else {
+        panic!("config should be invalid");
+    };
+    let keys: Vec<_> = problems.iter().flat_map(|problem| problem.keys()).collect();
+    assert_eq!(keys, ["read_time.Skim.full", "read_time.Skim.rate"]);
+}
@@ -0,0 +1,425 @@
+use social_media_sandbox::{
+    engine::{Backfill, CreatorExclusions, RankingMode, RetrievalConfig, RetrievalSource},
+    models::{Agent, AgentId, IdAllocator, Individual, PostId, PostIdList, SimulationConfig},
+    test_support::{EngineFixture, PostBuilder, ProfileBuilder},
+    RecommendationEngine,
+};
+
+const AGENT: AgentId = AgentId(1);
+const OTHER: AgentId = AgentId(2);
+
+// Ten posts by OTHER followed by ten by AGENT, all on the same tag
+fn engine_with_own_posts(ids: &mut IdAllocator) -> RecommendationEngine {
+    let mut engine = EngineFixture::with_posts(10)
+        .with_tags(&["science"])
+        .with_creator(OTHER)
+        .build_with_ids(ids);
+
+    for _ in 0..10 {
+        engine.create_post(
+            PostBuilder::new(ids.next_post_id())
+                .creator(AGENT)
+                .profile(ProfileBuilder::new().tag("science", 1.0).build())
+                .build(),
+        );
+    }
+
+    engine
+}
+
+fn recommend(
+    engine: &RecommendationEngine,
+    agent: &Individual,
+    exclusions: &CreatorExclusions,
+) -> PostIdList {
+    engine.get_post_recommendations(agent, engine.content_pool.len(), 0, exclusions)
+}
+
+fn creators(engine: &RecommendationEngine, posts: &[PostId]) -> Vec<AgentId> {
+    posts
+        .iter()
+        .map(|id| engine.get_content_by_id(*id).unwrap().creator_id)
+        .collect()
+}
+
+#[test]
+fn own_posts_are_excluded_by_default() {
+    let mut ids = IdAllocator::new();
+    let engine = engine_with_own_posts(&mut ids);
+    let agent = Individual::new(AGENT, &SimulationConfig::default(), &engine);
+
+    let posts = recommend(&engine, &agent, &CreatorExclusions::for_agent(&agent));
+
+    assert_eq!(posts.len(), 10);
+    assert!(creators(&engine, &posts).iter().all(|id| *id == OTHER));
+}
+
+#[test]
+fn no_exclusions_includes_own_posts() {
+    let mut ids = IdAllocator::new();
+    let engine = engine_with_own_posts(&mut ids);
+    let agent = Individual::new(AGENT, &SimulationConfig::default(), &engine);
+
+    let posts = recommend(&engine, &agent, &CreatorExclusions::none());
+
+    assert_eq!(posts.len(), 20);
+    assert!(creators(&engine, &posts).contains(&AGENT));
+}
+
+#[test]
+fn excluded_creators_are_filtered() {
+    let mut ids = IdAllocator::new();
+    let engine = engine_with_own_posts(&mut ids);
+    let agent = Individual::new(AGENT, &SimulationConfig::default(), &engine);
+
+    let exclusions = CreatorExclusions::for_agent(&agent).with(OTHER);
+
+    assert!(recommend(&engine, &agent, &exclusions).is_empty());
+}
+
+#[test]
+fn banned_creators_are_always_filtered() {
+    let mut ids = IdAllocator::new();
+    let mut engine = engine_with_own_posts(&mut ids);
+    engine.banned_creators.insert(OTHER);
+    let agent = Individual::new(AGENT, &SimulationConfig::default(), &engine);
+
+    let posts = recommend(&engine, &agent, &CreatorExclusions::none());
+
+    assert_eq!(posts.len(), 10);
+    assert!(creators(&engine, &posts).iter().all(|id| *id == AGENT));
+}
+
+const NOW: i64 = 3600 * 1000;
+
+// Ten stale, unrelated posts by OTHER with rising engagement, which all score
+// well under a floor of 0.5
+fn engine_with_weak_posts(ids: &mut IdAllocator, backfill: Backfill) -> RecommendationEngine {
+    let mut engine = RecommendationEngine::new();
+    engine.register_tags(&SimulationConfig::default().sample_tags);
+    engine.config.min_score = 0.5;
+    engine.config.backfill = backfill;
+
+    for i in 0..10 {
+        engine.create_post(
+            PostBuilder::new(ids.next_post_id())
+                .creator(OTHER)
+                .profile(ProfileBuilder::new().tag("unrelated", 1.0).build())
+                .engagement(i as f32 / 16.0)
+                .build(),
+        );
+    }
+
+    engine
+}
+
+fn engagement(engine: &RecommendationEngine, posts: &[PostId]) -> Vec<f32> {
+    posts
+        .iter()
+        .map(|id| engine.get_content_by_id(*id).unwrap().engagement_score)
+        .collect()
+}
+
+#[test]
+fn posts_below_the_floor_are_dropped_without_backfill() {
+    let mut ids = IdAllocator::new();
+    let engine = engine_with_weak_posts(&mut ids, Backfill::None);
+    let agent = Individual::new(AGENT, &SimulationConfig::default(), &engine);
+
+    let posts = engine.get_post_recommendations(&agent, 5, NOW, &CreatorExclusions::none());
+
+    assert!(posts.is_empty());
+}
+
+#[test]
+fn trending_backfill_takes_the_most_engaged_posts() {
+    let mut ids = IdAllocator::new();
+    let engine = engine_with_weak_posts(&mut ids, Backfill::Trending);
+    let agent = Individual::new(AGENT, &SimulationConfig::default(), &engine);
+
+    let posts = engine.get_post_recommendations(&agent, 3, NOW, &CreatorExclusions::none());
+
+    assert_eq!(
+        engagement(&engine, &posts),
+        vec![9.0 / 16.0, 8.0 / 16.0, 7.0 / 16.0]
+    );
+}
+
+#[test]
+fn posts_above_the_floor_come_before_backfill() {
+    let mut ids = IdAllocator::new();
+    let mut engine = engine_with_weak_posts(&mut ids, Backfill::Trending);
+    let agent = Individual::new(AGENT, &SimulationConfig::default(), &engine);
+
+    let relevant = ids.next_post_id();
+    engine.create_post(
+        PostBuilder::new(relevant)
+            .creator(OTHER)
+            .profile(agent.interest_profile().clone())
+            .timestamp(NOW)
+            .build(),
+    );
+
+    let posts = engine.get_post_recommendations(&agent, 3, NOW, &CreatorExclusions::none());
+
+    assert_eq!(posts.len(), 3);
+    assert_eq!(posts[0], relevant);
+    assert_eq!(
+        engagement(&engine, &posts[1..]),
+        vec![9.0 / 16.0, 8.0 / 16.0]
+    );
+}
+
+#[test]
+fn random_backfill_is_reproducible_from_the_seed() {
+    let recommend_with_seed = |seed| {
+        let mut ids = IdAllocator::new();
+        let mut engine = engine_with_weak_posts(&mut ids, Backfill::Random);
+        engine.seed = seed;
+        let agent = Individual::new(AGENT, &SimulationConfig::default(), &engine);
+        engine.get_post_recommendations(&agent, 10, NOW, &CreatorExclusions::none())
+    };
+
+    let posts = recommend_with_seed(7);
+
+    assert_eq!(posts.len(), 10);
+    assert_eq!(posts, recommend_with_seed(7));
+}
+
+#[test]
+fn sampled_ranking_at_low_temperature_matches_top_k() {
+    let mut ids = IdAllocator::new();
+    let mut engine = engine_with_weak_posts(&mut ids, Backfill::None);
+    engine.config.min_score = 0.0;
+    let agent = Individual::new(AGENT, &SimulationConfig::default(), &engine);
+    let top_k = engine.get_post_recommendations(&agent, 5, NOW, &CreatorExclusions::none());
+
+    engine.config.ranking = RankingMode::Sampled;
+    engine.config.temperature = 1e-3;
+    let sampled = engine.get_post_recommendations(&agent, 5, NOW, &CreatorExclusions::none());
+
+    assert_eq!(sampled, top_k);
+}
+
+#[test]
+fn sampled_ranking_draws_each_post_at_most_once() {
+    let mut ids = IdAllocator::new();
+    let mut engine = engine_with_weak_posts(&mut ids, Backfill::None);
+    engine.config.min_score = 0.0;
+    engine.config.ranking = RankingMode::Sampled;
+    engine.config.temperature = 10.0;
+    let agent = Individual::new(AGENT, &SimulationConfig::default(), &engine);
+
+    let mut posts = engine.get_post_recommendations(&agent, 10, NOW, &CreatorExclusions::none());
+    posts.sort();
+    posts.dedup();
+
+    assert_eq!(posts.len(), 10);
+}
+
+#[test]
+fn feeds_hold_at_most_the_cap_per_creator() {
+    let mut ids = IdAllocator::new();
+    let mut engine = engine_with_own_posts(&mut ids);
+    engine.config.max_per_creator = 3;
+    let agent = Individual::new(AgentId(3), &SimulationConfig::default(), &engine);
+
+    let posts = recommend(&engine, &agent, &CreatorExclusions::none());
+    let creators = creators(&engine, &posts);
+
+    assert_eq!(posts.len(), 6);
+    assert_eq!(creators.iter().filter(|id| **id == AGENT).count(), 3);
+    assert_eq!(creators.iter().filter(|id| **id == OTHER).count(), 3);
+}
+
+#[test]
+fn feeds_hold_at_most_the_cap_per_tag() {
+    let mut ids = IdAllocator::new();
+    let mut engine = engine_with_own_posts(&mut ids);
+    engine.config.max_per_tag = 4;
+    let agent = Individual::new(AgentId(3), &SimulationConfig::default(), &engine);
+
+    assert_eq!(
+        recommend(&engine, &agent, &CreatorExclusions::none()).len(),
+        4
+    );
+}
+
+fn retrieving(engine: &mut RecommendationEngine, sources: &[RetrievalSource], per_source: usize) {
+    engine.config.retrieval = RetrievalConfig {
+        sources: sources.to_vec(),
+        per_source,
+    };
+}
+
+#[test]
+fn recent_retrieval_only_ranks_the_newest_posts() {
+    let mut ids = IdAllocator::new();
+    let mut engine = engine_with_own_posts(&mut ids);
+    retrieving(&mut engine, &[RetrievalSource::Recent], 5);
+    let agent = Individual::new(AGENT, &SimulationConfig::default(), &engine);
+
+    let posts = recommend(&engine, &agent, &CreatorExclusions::none());
+
+    assert_eq!(posts.len(), 5);
+    assert!(creators(&engine, &posts).iter().all(|id| *id == AGENT));
+}
+
+#[test]
+fn followed_retrieval_only_ranks_preferred_creators() {
+    let mut ids = IdAllocator::new();
+    let mut engine = engine_with_own_posts(&mut ids);
+    retrieving(&mut engine, &[RetrievalSource::Followed], 100);
+    let mut agent = Individual::new(AGENT, &SimulationConfig::default(), &engine);
+
+    assert!(recommend(&engine, &agent, &CreatorExclusions::none()).is_empty());
+
+    agent.preferred_creators.insert(OTHER, 1.0);
+    let posts = recommend(&engine, &agent, &CreatorExclusions::none());
+
+    assert_eq!(posts.len(), 10);
+    assert!(creators(&engine, &posts).iter().all(|id| *id == OTHER));
+}
+
+#[test]
+fn following_weight_lifts_followed_creators_for_everyone() {
+    // Five posts by OTHER that nobody engaged with, then five by a stranger
+    // that everyone did
+    let mut ids = IdAllocator::new();
+    let mut engine = RecommendationEngine::new();
+    for (creator, engagement) in [(OTHER, 0.0), (AgentId(3), 0.9)] {
+        for _ in 0..5 {
+            engine.create_post(
+                PostBuilder::new(ids.next_post_id())
+                    .creator(creator)
+                    .profile(ProfileBuilder::new().tag("science", 1.0).build())
+                    .engagement(engagement)
+                    .build(),
+            );
+        }
+    }
+    let mut agent = Individual::new(AGENT, &SimulationConfig::default(), &engine);
+    agent.preferred_creators.insert(OTHER, 1.0);
+    agent.preferences.enabled = false;
+    let top = |engine: &RecommendationEngine| {
+        let posts = engine.get_post_recommendations(&agent, 5, 0, &CreatorExclusions::none());
+        creators(engine, &posts)
+    };
+
+    assert!(top(&engine).iter().all(|id| *id == AgentId(3)));
+
+    engine.config.following_weight = 1.0;
+    assert!(top(&engine).iter().all(|id| *id == OTHER));
+}
+
+#[test]
+fn retrieval_sources_are_combined_without_duplicates() {
+    let mut ids = IdAllocator::new();
+    let mut engine = engine_with_own_posts(&mut ids);
+    retrieving(
+        &mut engine,
+        &[RetrievalSource::Recent, RetrievalSource::TagMatched],
+        15,
+    );
+    let agent = Individual::new(AGENT, &SimulationConfig::default(), &engine);
+
+    let posts = recommend(&engine, &agent, &CreatorExclusions::none());
+
+    let mut unique = posts.to_vec();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), posts.len());
+    assert!(posts.len() >= 15);
+}
+
+#[test]
+fn breaking_posts_are_pinned_until_their_window_closes() {
+    let mut ids = IdAllocator::new();
+    let mut engine = engine_with_weak_posts(&mut ids, Backfill::Trending);
+    let agent = Individual::new(AGENT, &SimulationConfig::default(), &engine);
+
+    // The least engaged-with post, which trending backfill would put last
+    let breaking = engine.content_pool[0].id;
+    engine.mark_breaking(breaking, 10);
+
+    let posts = recommend(&engine, &agent, &CreatorExclusions::none());
+    assert_eq!(posts[0], breaking);
+    assert_eq!(posts.len(), 10);
+
+    engine.current_step = 10;
+    let posts = recommend(&engine, &agent, &CreatorExclusions::none());
+    assert_eq!(posts.last(), Some(&breaking));
+}
+
+#[test]
+fn followed_creators_come_first_when_prioritised() {
+    let mut ids = IdAllocator::new();
+    let engine = engine_with_own_posts(&mut ids);
+    let mut agent = Individual::new(AGENT, &SimulationConfig::default(), &engine);
+    agent.preferred_creators.insert(OTHER, 1.0);
+    agent.preferences.enabled = true;
+    agent.preferences.prioritise_followed = true;
+
+    let posts = recommend(&engine, &agent, &CreatorExclusions::none());
+
+    assert_eq!(creators(&engine, &posts[..10]), vec![OTHER; 10]);
+}
+
+#[test]
+fn fewer_posts_are_shown_on_tags_the_agent_turned_down() {
+    let mut ids = IdAllocator::new();
+    let mut engine = RecommendationEngine::new();
+    engine.register_tags(&["science".to_string(), "sports".to_string()]);
+    for tag in ["science", "sports", "science", "sports"] {
+        let mut profile = ProfileBuilder::new().tag(tag, 1.0).build();
+        engine.vectorise(&mut profile);
+        engine.create_post(
+            PostBuilder::new(ids.next_post_id())
+                .creator(OTHER)
+                .profile(profile)
+                .timestamp(NOW)
+                .build(),
+        );
+    }
+
+    let mut agent = Individual::new(AGENT, &SimulationConfig::default(), &engine);
+    agent.core.interest_profile = ProfileBuilder::new()
+        .tag("science", 1.0)
+        .tag("sports", 1.0)
+        .build();
+    engine.vectorise(&mut agent.core.interest_profile);
+    agent.preferences.enabled = true;
+    agent.preferences.fewer_tags.insert("science".to_string());
+
+    let posts = engine.get_post_recommendations(&agent, 4, NOW, &CreatorExclusions::none());
+    let sports = |id: &PostId| {
+        engine
+            .get_content_by_id(*id)
+            .unwrap()
+            .interest_profile
+            .interests
+            .contains_key("sports")
+    };
+
+    assert!(posts[..2].iter().all(sports));
+    assert!(!posts[2..].iter().any(sports));
+}
+
+#[test]
+fn heat_is_the_score_without_interest_and_cools_with_age() {
+    let mut engine = EngineFixture::with_posts(1).with_tags(&["science"]).build();
+    let agent = Individual::new(AGENT, &SimulationConfig::default(), &engine);
+    let post = engine.content_pool[0].clone();
+
+    let fresh = engine.heat(&post, 0);
+    let scored = engine.score_components(&post, &agent, 0);
+    assert_eq!(fresh.interest, 0.0);
+    assert_eq!(
+        (fresh.recency, fresh.engagement),
+        (scored.recency, scored.engagement)
+    );
+    assert!(engine.heat(&post, 36_000).recency < fresh.recency);
+
+    engine.mark_breaking(post.id, 10);
+    assert!(engine.heat(&post, 0).recency > fresh.recency);
+}
@@ -0,0 +1,140 @@
+use social_media_sandbox::{
+    engine::{CreatorExclusions, CreatorQuota, Mmr, RerankContext, Reranker, ScoredPost},
+    models::{AgentId, Individual, PostId, SimulationConfig},
+    test_support::{EngineFixture, PostBuilder, ProfileBuilder},
+    RecommendationEngine,
+};
+
+fn agent(engine: &RecommendationEngine) -> Individual {
+    Individual::new(AgentId(1), &SimulationConfig::default(), engine)
+}
+
+fn slate<'a>(engine: &'a RecommendationEngine, scores: &[(usize, f32)]) -> Vec<ScoredPost<'a>> {
+    scores
+        .iter()
+        .map(|&(index, score)| ScoredPost {
+            post: &engine.content_pool[index],
+            score,
+        })
+        .collect()
+}
+
+fn ids(slate: &[ScoredPost]) -> Vec<PostId> {
+    slate.iter().map(|scored| scored.post.id).collect()
+}
+
+// Turns each feed upside down, to make its effect easy to see
+#[derive(Debug, Clone)]
+struct Reverse(&'static str);
+
+impl Reranker for Reverse {
+    fn name(&self) -> &str {
+        self.0
+    }
+
+    fn rerank(&self, slate: &mut Vec<ScoredPost>, _context: &RerankContext) {
+        slate.reverse();
+    }
+
+    fn clone_box(&self) -> Box<dyn Reranker> {
+        Box::new(self.clone())
+    }
+}
+
+#[test]
+fn mmr_trades_score_for_novelty() {
+    // Even posts are on science, odd ones on sports
+    let engine = EngineFixture::with_posts(6)
+        .with_tags(&["science", "sports"])
+        .build();
+    let agent = agent(&engine);
+    let context = RerankContext {
+        agent: &agent,
+        engine: &engine,
+        slots: 2,
+    };
+    let ranked = [(0, 1.0), (2, 0.9), (4, 0.8), (1, 0.5), (3, 0.4), (5, 0.3)];
+
+    let mut diversified = slate(&engine, &ranked);
+    Mmr { lambda: 0.5 }.rerank(&mut diversified, &context);
+    let pool = |indices: &[usize]| -> Vec<PostId> {
+        indices
+            .iter()
+            .map(|&index| engine.content_pool[index].id)
+            .collect()
+    };
+    assert_eq!(ids(&diversified), pool(&[0, 1, 2, 4, 3, 5]));
+
+    let mut unchanged = slate(&engine, &ranked);
+    Mmr { lambda: 1.0 }.rerank(&mut unchanged, &context);
+    assert_eq!(ids(&unchanged), pool(&[0, 2, 4, 1, 3, 5]));
+}
+
+#[test]
+fn creator_quotas_promote_the_groups_best_posts() {
+    let mut engine = RecommendationEngine::new();
+    let profile = ProfileBuilder::new().tag("science", 1.0).build();
+    for (id, creator) in [1, 1, 1, 2, 2, 2].into_iter().enumerate() {
+        engine.create_post(
+            PostBuilder::new(PostId(id))
+                .creator(AgentId(creator))
+                .profile(profile.clone())
+                .build(),
+        );
+    }
+    let agent = agent(&engine);
+    let context = RerankContext {
+        agent: &agent,
+        engine: &engine,
+        slots: 3,
+    };
+    let quota = CreatorQuota {
+        name: "individuals".to_string(),
+        creators: [AgentId(2)].into(),
+        min_share: 0.5,
+    };
+
+    let mut reranked = slate(
+        &engine,
+        &[(0, 0.9), (1, 0.8), (2, 0.7), (3, 0.6), (4, 0.5), (5, 0.4)],
+    );
+    quota.rerank(&mut reranked, &context);
+    assert_eq!(
+        ids(&reranked),
+        [0, 3, 4, 1, 2, 5].map(PostId),
+        "the two lowest others make way, in rank order"
+    );
+
+    // Nothing moves once the group has its share
+    let mut met = slate(&engine, &[(3, 0.9), (4, 0.8), (0, 0.7), (1, 0.6)]);
+    quota.rerank(&mut met, &context);
+    assert_eq!(ids(&met), [3, 4, 0, 1].map(PostId));
+}
+
+#[test]
+fn rerankers_registered_at_runtime_shape_feeds() {
+    let mut engine = EngineFixture::with_posts(8).with_tags(&["science"]).build();
+    for (age, post) in engine.content_pool.iter_mut().enumerate() {
+        post.engagement_score = 1.0 - age as f32 / 10.0;
+    }
+    let agent = agent(&engine);
+    let recommend = |engine: &RecommendationEngine| {
+        engine.get_post_recommendations(&agent, 3, 0, &CreatorExclusions::none())
+    };
+    let organic = recommend(&engine);
+
+    engine.register_reranker(Reverse("reverse"));
+    engine.register_reranker(Reverse("reverse"));
+    assert_eq!(engine.rerankers.len(), 1);
+    assert!(engine.has_reranker("reverse"));
+    let reversed = recommend(&engine);
+    assert_eq!(reversed.len(), 3);
+    assert!(reversed.iter().all(|post_id| !organic.contains(post_id)));
+
+    // Copies of the engine keep their rerankers
+    assert_eq!(recommend(&engine.clone()), reversed);
+
+    assert!(engine.remove_reranker("reverse"));
+    assert!(!engine.remove_reranker("reverse"));
+    assert_eq!(recommend(&engine), organic);
+}
@@ -0,0 +1,100 @@
+use social_media_sandbox::{
+    engine::Mmr,
+    models::{
+        AdsConfig, AgentId, GraphGenerator, Intervention, MarketplaceConfig, SimulationConfig,
+    },
+    save::{SaveError, SAVE_FORMAT_VERSION},
+    Simulation,
+};
+use std::path::PathBuf;
+
+fn path(test: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("sandbox-{test}-{}.smssave", std::process::id()))
+}
+
+fn simulation() -> Simulation {
+    let mut config = SimulationConfig {
+        seed: 23,
+        num_individuals: 15,
+        num_bots: 2,
+        num_organisations: 2,
+        initial_graph: GraphGenerator::ErdosRenyi { p: 0.2 },
+        community_detection_interval: 40,
+        ads: AdsConfig {
+            per_feed: 1,
+            interval: 20,
+            ..AdsConfig::default()
+        },
+        ..SimulationConfig::default()
+    };
+    config.marketplace = MarketplaceConfig {
+        slots: 1,
+        ..MarketplaceConfig::default()
+    };
+    config.transparency.interval = 50;
+    let mut simulation = Simulation::new(config);
+    simulation.schedule_intervention(30, Intervention::BanCreator(AgentId(1)));
+    simulation.schedule_intervention(150, Intervention::BanCreator(AgentId(2)));
+    simulation
+}
+
+// Everything observable about a run: every post with its readers and
+// comments, every agent's state, and the series recorded along the way
+fn fingerprint(simulation: &Simulation) -> String {
+    format!(
+        "{:?}\n{:?}\n{:?}\n{:?}\n{:?}\n{:?}",
+        simulation.engine.content_pool,
+        simulation.agent_views().collect::<Vec<_>>(),
+        simulation.communities,
+        simulation.revenue,
+        simulation.marketplace,
+        simulation.transparency,
+    )
+}
+
+#[test]
+fn loaded_simulations_carry_on_exactly_where_they_were_saved() {
+    let mut original = simulation();
+    original.run(100);
+    let path = path("round-trip");
+    original.save(&path).unwrap();
+    let mut loaded = Simulation::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.step_count, 100);
+    assert_eq!(fingerprint(&loaded), fingerprint(&original));
+    // Scheduled interventions still to come are kept with the rest
+    original.run(100);
+    loaded.run(100);
+    assert_eq!(fingerprint(&loaded), fingerprint(&original));
+    assert!(loaded.engine.banned_creators.contains(&AgentId(2)));
+}
+
+#[test]
+fn rerankers_are_left_out_of_saves() {
+    let mut simulation = simulation();
+    simulation.engine.register_reranker(Mmr::default());
+    simulation.run(10);
+    let path = path("rerankers");
+    simulation.save(&path).unwrap();
+    let loaded = Simulation::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(loaded.engine.rerankers.is_empty());
+    assert_eq!(
+        loaded.engine.content_pool.len(),
+        simulation.engine.content_pool.len()
+    );
+}
+
+#[test]
+fn saves_from_newer_versions_are_refused() {
+    let path = path("newer");
+    let newer = SAVE_FORMAT_VERSION + 1;
+    std::fs::write(&path, format!("{{\"format_version\": {newer}}}")).unwrap();
+    let result = Simulation::load(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(matches!(result, Err(SaveError::Unsupported(version)) if version == newer));
+    assert!(matches!(Simulation::load(path), Err(SaveError::Io(_))));
+}
@@ -0,0 +1,194 @@
+use social_media_sandbox::models::{
+    validate, GraphGenerator, Scenario, ScenarioError, SimulationConfig,
+};
+use std::path::PathBuf;
+
+// A scratch directory holding the given files, named for the test using it
+fn files(test: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("sandbox-{test}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    for (name, contents) in files {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn the_default_config_is_valid() {
+    assert_eq!(validate(&SimulationConfig::default()), Vec::<String>::new());
+}
+
+#[test]
+fn out_of_range_values_are_flagged() {
+    let scenario = Scenario::parse(
+        "
+        seed = 1
+        feed_control_usage = 1.5
+        recency_weight = -1.0
+        min_content_tags = 4
+        max_content_tags = 2
+
+        [initial_graph.ErdosRenyi]
+        p = 2.0
+        ",
+    )
+    .unwrap();
+
+    let problems = scenario.validate();
+
+    for key in [
+        "feed_control_usage",
+        "recency_weight",
+        "min_content_tags",
+        "initial_graph.ErdosRenyi.p",
+    ] {
+        assert!(
+            problems.iter().any(|problem| problem.contains(key)),
+            "{key} not flagged in {problems:?}"
+        );
+    }
+}
+
+#[test]
+fn unknown_personas_and_tags_are_flagged() {
+    let mut config = SimulationConfig {
+        seed: 1,
+        ..SimulationConfig::default()
+    };
+    config
+        .persona_fractions
+        .insert("night owl".to_string(), 0.1);
+    config.sample_tags.retain(|tag| tag != "sports");
+    config.initial_graph = GraphGenerator::Empty;
+
+    let problems = validate(&config);
+
+    assert!(problems
+        .iter()
+        .any(|problem| problem.contains("`night owl`")));
+    assert!(problems.iter().any(|problem| problem.contains("`sports`")));
+    assert!(problems.iter().any(|problem| problem.contains("add up to")));
+}
+
+#[test]
+fn written_scenarios_read_back_without_warnings() {
+    let config = SimulationConfig {
+        seed: 1,
+        ..SimulationConfig::default()
+    };
+
+    let scenario = Scenario::parse(&Scenario::to_toml(&config).unwrap()).unwrap();
+
+    assert!(scenario.warnings.is_empty(), "{:?}", scenario.warnings);
+    assert_eq!(scenario.config.seed, 1);
+}
+
+#[test]
+fn scenarios_override_what_they_extend() {
+    let dir = files(
+        "extends",
+        &[
+            (
+                "base.toml",
+                "seed = 1\nnum_individuals = 40\n\n[survey]\ninterval = 10\nnoise = 0.2\n",
+            ),
+            (
+                "experiments/more_noise.toml",
+                "extends = \"../base.toml\"\nnum_individuals = 20\n\n[survey]\nnoise = 0.5\n",
+            ),
+        ],
+    );
+
+    let scenario = Scenario::load(dir.join("experiments/more_noise.toml")).unwrap();
+
+    assert!(scenario.warnings.is_empty(), "{:?}", scenario.warnings);
+    assert_eq!(scenario.config.seed, 1);
+    assert_eq!(scenario.config.num_individuals, 20);
+    assert_eq!(scenario.config.survey.interval, 10);
+    assert_eq!(scenario.config.survey.noise, 0.5);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn persona_and_tag_libraries_are_merged_by_name() {
+    let dir = files(
+        "libraries",
+        &[
+            (
+                "tags.toml",
+                "sample_tags = [\"sports\", \"politics\", \"music\"]\n",
+            ),
+            (
+                "personas.toml",
+                "
+                [[personas]]
+                name = \"sports fan\"
+                tags = [{ tag = \"sports\", weight = 1.0, agreement = [0.3, 1.0] }]
+                next_post_likelihood = [0.5, 0.9]
+                attention_span = [0.2, 0.4]
+                read_speed = [0.5, 0.9]
+                content_creation_frequency = [0.05, 0.2]
+                create_speed = [0.4, 0.8]
+
+                [[personas]]
+                name = \"gig goer\"
+                tags = [{ tag = \"music\", weight = 1.0, agreement = [0.0, 1.0] }]
+                next_post_likelihood = [0.5, 0.9]
+                attention_span = [0.2, 0.4]
+                read_speed = [0.5, 0.9]
+                content_creation_frequency = [0.05, 0.2]
+                create_speed = [0.4, 0.8]
+                ",
+            ),
+            (
+                "scenario.toml",
+                "
+                extends = [\"tags.toml\", \"personas.toml\"]
+                seed = 1
+
+                [[personas]]
+                name = \"sports fan\"
+                attention_span = [0.8, 0.9]
+                ",
+            ),
+        ],
+    );
+
+    let scenario = Scenario::load(dir.join("scenario.toml")).unwrap();
+    let config = &scenario.config;
+
+    assert_eq!(config.sample_tags, ["sports", "politics", "music"]);
+    let names: Vec<&str> = config
+        .personas
+        .iter()
+        .map(|persona| persona.name.as_str())
+        .collect();
+    assert_eq!(names, ["sports fan", "gig goer"]);
+    assert_eq!(config.personas[0].attention_span, (0.8, 0.9));
+    assert_eq!(config.personas[0].read_speed, (0.5, 0.9));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn cycles_and_missing_bases_are_errors() {
+    let dir = files(
+        "cycles",
+        &[
+            ("a.toml", "extends = \"b.toml\"\n"),
+            ("b.toml", "extends = \"a.toml\"\n"),
+            ("orphan.toml", "extends = \"missing.toml\"\n"),
+        ],
+    );
+
+    assert!(matches!(
+        Scenario::load(dir.join("a.toml")),
+        Err(ScenarioError::Cycle(_))
+    ));
+    assert!(matches!(
+        Scenario::load(dir.join("orphan.toml")),
+        Err(ScenarioError::Base(..))
+    ));
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+
+use social_media_sandbox::{
+    metrics::MetricsExport,
+    models::{validate, AgentId, SegmentConfig, SegmentStats, SimulationConfig},
+    Simulation,
+};
+
+fn segments(leakage: f32) -> SegmentConfig {
+    SegmentConfig {
+        fractions: BTreeMap::from([("en".to_string(), 0.5), ("fr".to_string(), 0.5)]),
+        leakage,
+    }
+}
+
+fn run(segments: SegmentConfig, ticks: u64) -> Simulation {
+    let mut simulation = Simulation::new(SimulationConfig {
+        seed: 63,
+        num_individuals: 20,
+        num_bots: 4,
+        num_organisations: 4,
+        segments,
+        ..SimulationConfig::default()
+    });
+    for _ in 0..ticks {
+        simulation.step();
+    }
+    simulation
+}
+
+fn cross_exposures(simulation: &Simulation) -> usize {
+    SegmentStats::of(simulation)
+        .values()
+        .map(|stats| stats.cross_exposures)
+        .sum()
+}
+
+#[test]
+fn agents_are_assigned_segments_by_fraction() {
+    let config = segments(0.0);
+    let assigned: Vec<_> = (0..200)
+        .map(|id| config.assign(7, AgentId(id)).unwrap())
+        .collect();
+    let english = assigned.iter().filter(|segment| *segment == "en").count();
+    assert!((70..130).contains(&english), "{english}");
+    assert_eq!(config.assign(7, AgentId(3)), Some(assigned[3].clone()));
+
+    let lopsided = SegmentConfig {
+        fractions: BTreeMap::from([("en".to_string(), 1.0), ("fr".to_string(), 0.0)]),
+        ..SegmentConfig::default()
+    };
+    assert!((0..50).all(|id| lopsided.assign(7, AgentId(id)).as_deref() == Some("en")));
+    assert_eq!(SegmentConfig::default().assign(7, AgentId(0)), None);
+}
+
+#[test]
+fn posts_belong_to_their_creators_segment() {
+    let simulation = run(segments(0.0), 50);
+    let segments: BTreeMap<_, _> = simulation
+        .agent_views()
+        .map(|agent| (agent.id, agent.segment.map(str::to_string)))
+        .collect();
+
+    assert!(!simulation.engine.content_pool.is_empty());
+    for post in &simulation.engine.content_pool {
+        assert!(post.segment.is_some());
+        assert_eq!(post.segment, segments[&post.creator_id]);
+    }
+}
+
+#[test]
+fn sealed_segments_only_see_their_own_posts() {
+    let sealed = run(segments(0.0), 200);
+    let open = run(segments(1.0), 200);
+
+    assert_eq!(cross_exposures(&sealed), 0);
+    assert!(cross_exposures(&open) > 0);
+    for stats in SegmentStats::of(&sealed).values() {
+        assert!(stats.exposures > 0);
+        assert_eq!(stats.reads_from.len(), 1);
+    }
+}
+
+#[test]
+fn leakage_lets_some_posts_spill_over() {
+    let leaky = run(segments(0.2), 200);
+    let open = run(segments(1.0), 200);
+
+    assert!(cross_exposures(&leaky) > 0);
+    assert!(cross_exposures(&leaky) < cross_exposures(&open));
+}
+
+#[test]
+fn unsegmented_populations_share_one_market() {
+    let simulation = run(SegmentConfig::default(), 50);
+
+    assert!(simulation
+        .agent_views()
+        .all(|agent| agent.segment.is_none()));
+    assert!(SegmentStats::of(&simulation).is_empty());
+    assert_eq!(simulation.engine.config.segment_leakage, 1.0);
+}
+
+#[test]
+fn segments_are_exported_and_validated() {
+    let simulation = run(segments(0.1), 100);
+    let mut export = MetricsExport::new();
+    export.record(&simulation);
+
+    let sizes: f32 = export
+        .samples
+        .iter()
+        .filter(|sample| sample.metric == "segment_size")
+        .map(|sample| sample.value)
+        .sum();
+    assert_eq!(sizes, 28.0);
+    assert!(export.samples.iter().any(|sample| {
+        sample.metric == "segment_spillover" && sample.dimensions["from_segment"] == "en"
+    }));
+
+    let mut config = simulation.config.clone();
+    config.segments.leakage = 1.5;
+    config.segments.fractions.insert("de".to_string(), -1.0);
+    let problems = validate(&config);
+    assert!(problems
+        .iter()
+        .any(|problem| problem.contains("segments.leakage")));
+    assert!(problems
+        .iter()
+        .any(|problem| problem.contains("segments.fractions.de")));
+}
@@ -0,0 +1,130 @@
+use social_media_sandbox::{
+    engine::{EngineOp, Inconsistency},
+    metrics::{
+        schema::{RESHARE_BREADTH, RESHARE_DEPTH},
+        MetricsExport,
+    },
+    models::{AgentId, CascadeAction, PostId, ReshareTree, SharingConfig, SimulationConfig},
+    test_support::EngineFixture,
+    Post, Simulation,
+};
+
+#[test]
+fn reshares_grow_a_tree_under_the_original() {
+    let mut engine = EngineFixture::with_posts(1).build();
+    let original = engine.content_pool[0].clone();
+    let mut reshare = |of: &Post, id: usize, sharer: usize| {
+        let post = of.reshare(PostId(id), AgentId(sharer));
+        engine
+            .apply_op(EngineOp::PublishPost(Box::new(post.clone())))
+            .unwrap();
+        post
+    };
+    // Two agents reshare the original, and one of those reshares is passed on
+    let first = reshare(&original, 10, 1);
+    reshare(&original, 11, 2);
+    let second = reshare(&first, 12, 3);
+    engine
+        .apply_op(EngineOp::LikePost {
+            post_id: original.id,
+            agent_id: AgentId(4),
+        })
+        .unwrap();
+
+    assert_eq!(second.shared_by, [AgentId(1), AgentId(3)]);
+    assert_eq!(second.original, Some(original.id));
+    assert_eq!(second.reshare_of, Some(first.id));
+    assert_eq!(second.creator_id, original.creator_id);
+
+    let original = &engine.content_pool[0];
+    assert_eq!(original.reshares, [PostId(10), PostId(11)]);
+    assert_eq!(original.likes, [AgentId(4)]);
+    assert!(original
+        .cascade
+        .iter()
+        .any(|event| event.action == CascadeAction::Reshared(PostId(10))
+            && event.agent_id == AgentId(1)));
+
+    let tree = ReshareTree::of(original, &engine.content_pool);
+    assert_eq!(
+        tree.levels,
+        [vec![PostId(10), PostId(11)], vec![PostId(12)]]
+    );
+    assert_eq!((tree.depth(), tree.breadth(), tree.size()), (2, 2, 3));
+    // Only originals head a tree of their own
+    assert_eq!(ReshareTree::all(&engine.content_pool), [tree]);
+    assert_eq!(engine.verify(), []);
+
+    engine.content_pool[0].reshares.pop();
+    assert_eq!(
+        engine.verify(),
+        [Inconsistency::BrokenReshare {
+            post_id: PostId(0),
+            reshare: PostId(11),
+        }]
+    );
+}
+
+#[test]
+fn reshared_posts_spread_through_a_run() {
+    let config = SimulationConfig {
+        seed: 8,
+        num_individuals: 20,
+        num_bots: 2,
+        num_organisations: 2,
+        sharing: SharingConfig {
+            like_chance: 0.3,
+            reshare_chance: 0.2,
+        },
+        ..SimulationConfig::default()
+    };
+    let mut simulation = Simulation::new(config.clone());
+    simulation.run(150);
+
+    let pool = &simulation.engine.content_pool;
+    assert!(pool.iter().any(|post| !post.likes.is_empty()));
+    let trees = ReshareTree::all(pool);
+    assert!(!trees.is_empty());
+    // Nobody reshares a post twice, or reshares their own reshare
+    for post in pool.iter().filter(|post| post.original.is_some()) {
+        let sharer = post.sharer().unwrap();
+        assert_eq!(
+            post.shared_by
+                .iter()
+                .filter(|&&agent| agent == sharer)
+                .count(),
+            1
+        );
+    }
+    assert_eq!(simulation.engine.verify(), []);
+
+    let mut export = MetricsExport::new();
+    export.record(&simulation);
+    let deepest = export
+        .samples
+        .iter()
+        .filter(|sample| sample.metric == RESHARE_DEPTH)
+        .map(|sample| sample.value as usize)
+        .max();
+    assert_eq!(deepest, trees.iter().map(ReshareTree::depth).max());
+    assert_eq!(
+        export
+            .samples
+            .iter()
+            .filter(|sample| sample.metric == RESHARE_BREADTH)
+            .count(),
+        trees.len()
+    );
+
+    // With sharing off, as by default, nothing is liked or reshared
+    let mut quiet = Simulation::new(SimulationConfig {
+        sharing: SharingConfig::default(),
+        ..config
+    });
+    quiet.run(150);
+    assert!(quiet
+        .engine
+        .content_pool
+        .iter()
+        .all(|post| post.likes.is_empty() && post.original.is_none()));
+}
@@ -0,0 +1,207 @@
+use proptest::prelude::*;
+use rand::RngCore;
+use social_media_sandbox::{
+    engine::EngineOp,
+    models::{
+        Agent, AgentId, AgentState, Choice, IdAllocator, Individual, InterestProfile, RngStreams,
+        SimulationConfig, TickContext, TransitionPolicy,
+    },
+    test_support::{CommentBuilder, PostBuilder, ProfileBuilder},
+    RecommendationEngine,
+};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+// Answers every policy question from a fixed, repeating list of rolls so that
+// proptest controls which transitions the agent takes
+#[derive(Debug)]
+struct ScriptedPolicy {
+    rolls: Vec<bool>,
+    next: Mutex<usize>,
+}
+
+impl ScriptedPolicy {
+    fn new(rolls: Vec<bool>) -> Self {
+        Self {
+            rolls,
+            next: Mutex::new(0),
+        }
+    }
+
+    fn roll(&self) -> bool {
+        let mut next = self.next.lock().unwrap();
+        let roll = self.rolls[*next % self.rolls.len()];
+        *next += 1;
+        roll
+    }
+}
+
+impl TransitionPolicy for ScriptedPolicy {
+    fn should_go_offline(&self, _agent: &Individual, _rng: &mut dyn RngCore) -> Choice {
+        self.roll().into()
+    }
+
+    fn should_select_post(&self, _agent: &Individual, _rng: &mut dyn RngCore) -> Choice {
+        self.roll().into()
+    }
+
+    fn should_read_post(&self, _agent: &Individual, _rng: &mut dyn RngCore) -> Choice {
+        self.roll().into()
+    }
+
+    fn should_read_comments(&self, _agent: &Individual, _rng: &mut dyn RngCore) -> Choice {
+        self.roll().into()
+    }
+
+    fn should_write_comment(&self, _agent: &Individual, _rng: &mut dyn RngCore) -> Choice {
+        self.roll().into()
+    }
+
+    fn should_scroll(&self, _agent: &Individual, _rng: &mut dyn RngCore) -> Choice {
+        self.roll().into()
+    }
+}
+
+fn profile(config: &SimulationConfig, tags: &[usize]) -> InterestProfile {
+    tags.iter()
+        .fold(ProfileBuilder::new(), |profile, &tag| {
+            profile.tag(&config.sample_tags[tag % config.sample_tags.len()], 1.0)
+        })
+        .build()
+}
+
+fn synthetic_engine(
+    config: &SimulationConfig,
+    ids: &mut IdAllocator,
+    posts: &[(Vec<usize>, usize, i32)],
+) -> RecommendationEngine {
+    let mut engine = RecommendationEngine::new();
+    let creator = ids.next_agent_id();
+
+    for (tags, num_comments, length) in posts {
+        let mut post = PostBuilder::new(ids.next_post_id())
+            .creator(creator)
+            .profile(profile(config, tags))
+            .length(*length);
+        for _ in 0..*num_comments {
+            post = post.comment(
+                CommentBuilder::new(ids.next_comment_id())
+                    .commentor(creator)
+                    .profile(profile(config, tags))
+                    .length(length % config.max_comment_length)
+                    .build(),
+            );
+        }
+        engine.create_post(post.build());
+    }
+
+    engine
+}
+
+fn assert_invariants(
+    agent: &Individual,
+    engine: &RecommendationEngine,
+    previous_viewed: usize,
+) -> Result<(), TestCaseError> {
+    match &agent.core.state {
+        AgentState::ReadingPost {
+            ticks_spent,
+            ticks_required,
+            ..
+        }
+        | AgentState::CreatingPost {
+            ticks_spent,
+            ticks_required,
+            ..
+        }
+        | AgentState::CreatingComment {
+            ticks_spent,
+            ticks_required,
+            ..
+        } => prop_assert!(*ticks_spent <= (*ticks_required).max(0) + 1),
+        AgentState::ReadingComments {
+            current_comment_ids,
+            current_comment_index,
+            ticks_spent,
+            ticks_required,
+            ..
+        } => {
+            prop_assert!(*ticks_spent <= (*ticks_required).max(0) + 1);
+            prop_assert!(*current_comment_index < current_comment_ids.len());
+        }
+        AgentState::Offline | AgentState::Scrolling { .. } => {}
+    }
+
+    let viewed: HashSet<_> = agent.viewed_content.iter().collect();
+    prop_assert_eq!(viewed.len(), agent.viewed_content.len());
+    prop_assert!(agent.viewed_content.len() >= previous_viewed);
+    prop_assert!(agent.viewed_content.len() <= engine.content_pool.len());
+    for post_id in &agent.viewed_content {
+        prop_assert!(engine.get_content_by_id(*post_id).is_some());
+    }
+
+    let profile = &agent.core.interest_profile;
+    if !profile.interests.is_empty() {
+        let total: f32 = profile.normalised_weights().map(|(_, weight)| weight).sum();
+        prop_assert!((total - 1.0).abs() < 1e-3, "weights sum to {}", total);
+        for topic in profile.interests.values() {
+            prop_assert!(topic.weighted_interest.is_finite() && topic.weighted_interest >= 0.0);
+        }
+
+        // Incremental updates keep the vector where a full rebuild would put it
+        let mut rebuilt = profile.clone();
+        engine.vectorise(&mut rebuilt);
+        let drift = (&rebuilt.vector_representation - &profile.vector_representation).norm();
+        prop_assert!(
+            drift <= 1e-3 * rebuilt.vector_representation.norm().max(1.0),
+            "vector drifted by {}",
+            drift
+        );
+    }
+
+    Ok(())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn individual_transitions_preserve_invariants(
+        rolls in prop::collection::vec(any::<bool>(), 1..64),
+        posts in prop::collection::vec(
+            (prop::collection::vec(0usize..8, 1..4), 0usize..6, 0i32..60),
+            0..16,
+        ),
+        ticks in 1usize..1500,
+        seed in any::<u64>(),
+    ) {
+        let config = SimulationConfig::default();
+        let mut ids = IdAllocator::new();
+        let mut engine = synthetic_engine(&config, &mut ids, &posts);
+        let mut agent = Individual::new(AgentId(usize::MAX), &config, &engine)
+            .with_policy(Arc::new(ScriptedPolicy::new(rolls)));
+        let mut ops = Vec::new();
+        let mut rng = RngStreams::new(seed);
+
+        for _ in 0..ticks {
+            let previous_viewed = agent.viewed_content.len();
+
+            agent.tick(&mut TickContext {
+                engine: &engine,
+                ops: &mut ops,
+                ids: &mut ids,
+                config: &config,
+                rng: &mut rng,
+            });
+
+            for op in ops.drain(..) {
+                if let EngineOp::PublishPost(post) = &op {
+                    prop_assert_eq!(post.creator_id, agent.core.id);
+                }
+                prop_assert_eq!(engine.apply_op(op), Ok(()));
+            }
+
+            assert_invariants(&agent, &engine, previous_viewed)?;
+        }
+    }
+}
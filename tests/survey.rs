@@ -0,0 +1,88 @@
+use social_media_sandbox::models::{Simulation, SimulationConfig, SurveyConfig};
+
+fn surveyed(survey: SurveyConfig, steps: usize) -> Simulation {
+    let mut simulation = Simulation::new(SimulationConfig {
+        seed: 11,
+        num_individuals: 20,
+        survey,
+        ..SimulationConfig::default()
+    });
+    for _ in 0..steps {
+        simulation.step();
+    }
+    simulation
+}
+
+#[test]
+fn the_same_panel_is_asked_every_wave() {
+    let simulation = surveyed(
+        SurveyConfig {
+            interval: 5,
+            panel_size: 8,
+            ..SurveyConfig::default()
+        },
+        20,
+    );
+    let survey = &simulation.survey;
+
+    assert_eq!(survey.waves.len(), 4);
+    assert_eq!(survey.panel.len(), 8);
+    for wave in &survey.waves {
+        let mut asked: Vec<_> = wave
+            .responses
+            .iter()
+            .map(|response| response.agent_id)
+            .chain(wave.nonrespondents.iter().copied())
+            .collect();
+        asked.sort();
+        assert_eq!(asked, survey.panel);
+    }
+}
+
+#[test]
+fn answers_without_bias_or_noise_match_the_truth() {
+    let simulation = surveyed(
+        SurveyConfig {
+            interval: 10,
+            nonresponse: 0.0,
+            dissatisfied_nonresponse: 0.0,
+            noise: 0.0,
+            acquiescence: 0.0,
+            satisfaction_bias: 0.0,
+            ..SurveyConfig::default()
+        },
+        10,
+    );
+    let wave = &simulation.survey.waves[0];
+
+    assert_eq!(wave.responses.len(), 20);
+    assert_eq!(wave.response_rate(), 1.0);
+    for response in &wave.responses {
+        assert_eq!(response.satisfaction.reported, response.satisfaction.truth);
+        for topic in &response.topics {
+            assert_eq!(topic.interest.reported, topic.interest.truth);
+            assert_eq!(topic.agreement.reported, topic.agreement.truth);
+        }
+    }
+}
+
+#[test]
+fn certain_nonresponse_leaves_waves_empty() {
+    let simulation = surveyed(
+        SurveyConfig {
+            interval: 5,
+            nonresponse: 1.0,
+            ..SurveyConfig::default()
+        },
+        10,
+    );
+
+    for wave in &simulation.survey.waves {
+        assert!(wave.responses.is_empty());
+        assert_eq!(wave.nonrespondents.len(), 20);
+    }
+    assert_eq!(
+        simulation.survey.to_csv(),
+        "step,agent_id,question,tag,reported,truth\n"
+    );
+}
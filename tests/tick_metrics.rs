@@ -0,0 +1,163 @@
+use social_media_sandbox::{
+    engine::metrics::{DownsampledStream, TickAggregate, MAX_TICKS},
+    models::{AgentId, SimulationConfig},
+    Simulation,
+};
+
+fn simulation() -> Simulation {
+    Simulation::new(SimulationConfig {
+        seed: 6,
+        num_individuals: 12,
+        num_bots: 2,
+        num_organisations: 2,
+        ..SimulationConfig::default()
+    })
+}
+
+#[test]
+fn every_tick_is_recorded_for_the_run_and_each_agent() {
+    let mut simulation = simulation();
+    simulation.run(120);
+    let metrics = &simulation.engine.metrics;
+    let pool = &simulation.engine.content_pool;
+
+    for aggregate in TickAggregate::ALL {
+        let series = metrics.series(aggregate).unwrap();
+        assert_eq!(series.len(), 120, "{}", aggregate.name());
+        assert_eq!(series.points.back().unwrap().0, 120);
+    }
+    // Counts per tick add back up to everything in the pool
+    let created = |aggregate| -> f32 { metrics.series(aggregate).unwrap().values().iter().sum() };
+    assert_eq!(created(TickAggregate::PostsCreated) as usize, pool.len());
+    assert_eq!(
+        created(TickAggregate::CommentsCreated) as usize,
+        pool.iter().map(|post| post.comments.len()).sum::<usize>()
+    );
+
+    let concentration = metrics
+        .series(TickAggregate::TopicConcentration)
+        .unwrap()
+        .latest()
+        .unwrap();
+    let tags = simulation.config.sample_tags.len() as f32;
+    assert!((1.0 / tags..=1.0).contains(&concentration));
+
+    assert_eq!(metrics.interest_entropy.len(), 16);
+    let entropy = metrics.agent_entropy(AgentId(0)).unwrap();
+    assert_eq!(entropy.since(101).count(), 20);
+    let tags = metrics.topic_popularity.len();
+    assert_eq!(tags, simulation.config.sample_tags.len());
+    assert_eq!(
+        metrics.to_csv().lines().count(),
+        1 + 120 * (TickAggregate::ALL.len() + 16 + tags)
+    );
+
+    // Every tag's popularity adds up to the whole population's interest
+    let popularity: f32 = metrics
+        .topic_popularity
+        .values()
+        .map(|series| series.latest().unwrap())
+        .sum();
+    assert!((popularity - 1.0).abs() < 1e-4);
+    let online = simulation
+        .agent_views()
+        .filter(|agent| agent.state_name() != "Offline")
+        .count();
+    assert_eq!(
+        metrics
+            .series(TickAggregate::OnlineAgents)
+            .unwrap()
+            .latest(),
+        Some(online as f32)
+    );
+}
+
+#[test]
+fn series_keep_only_the_latest_ticks() {
+    let mut simulation = simulation();
+    simulation.run(MAX_TICKS as u64 + 10);
+    let series = simulation
+        .engine
+        .metrics
+        .series(TickAggregate::MeanEngagement)
+        .unwrap();
+
+    assert_eq!(series.len(), MAX_TICKS);
+    assert_eq!(series.points.front().unwrap().0, 11);
+}
+
+#[test]
+fn downsampled_streams_average_windows_of_ticks() {
+    let mut simulation = simulation();
+    simulation.run(25);
+    // Subscribing part way through fills in from the ticks already kept
+    simulation.engine.metrics.subscribe(10);
+    simulation.run(75);
+    let metrics = &simulation.engine.metrics;
+    let stream = metrics.stream(10).unwrap();
+
+    for aggregate in TickAggregate::ALL {
+        let full = metrics.series(aggregate).unwrap();
+        let downsampled = stream.series(aggregate).unwrap();
+        assert_eq!(
+            downsampled
+                .points
+                .iter()
+                .map(|&(step, _)| step)
+                .collect::<Vec<_>>(),
+            (1..=10).map(|window| window * 10).collect::<Vec<_>>()
+        );
+        for &(step, value) in &downsampled.points {
+            let window: Vec<f32> = full
+                .points
+                .iter()
+                .filter(|&&(at, _)| at + 10 > step && at <= step)
+                .map(|&(_, value)| value)
+                .collect();
+            let mean = window.iter().sum::<f32>() / window.len() as f32;
+            assert!(
+                (value - mean).abs() < 1e-3,
+                "{} at {step}",
+                aggregate.name()
+            );
+        }
+    }
+    for tag in metrics.topic_popularity.keys() {
+        assert_eq!(stream.topic(tag).unwrap().len(), 10);
+    }
+    // Without a stream at that rate, series come at full resolution
+    assert_eq!(
+        metrics
+            .series_every(TickAggregate::PostsCreated, 10)
+            .unwrap()
+            .len(),
+        10
+    );
+    assert_eq!(
+        metrics
+            .series_every(TickAggregate::PostsCreated, 100)
+            .unwrap()
+            .len(),
+        100
+    );
+
+    // Export stays at full resolution
+    assert_eq!(
+        metrics.to_csv().lines().count(),
+        1 + 100 * (TickAggregate::ALL.len() + 16 + metrics.topic_popularity.len())
+    );
+
+    let mut metrics = metrics.clone();
+    let before = metrics.len();
+    metrics.unsubscribe(10);
+    assert!(metrics.stream(10).is_none());
+    assert_eq!(
+        before - metrics.len(),
+        10 * (TickAggregate::ALL.len() + metrics.topic_popularity.len())
+    );
+
+    assert_eq!(
+        [1, 2, 10, 11, 250, 1000].map(DownsampledStream::every_for),
+        [1, 10, 10, 100, 1000, 1000]
+    );
+}
@@ -0,0 +1,127 @@
+use social_media_sandbox::{
+    metrics::MetricsExport,
+    models::{
+        AgentType, GroundTruthConfig, PopulationMetric, PostId, ScenarioError, SimulationConfig,
+        TradeoffCurve, TradeoffPoint, WellBeing, WellBeingConfig,
+    },
+    test_support::PostBuilder,
+    Simulation,
+};
+
+fn config() -> SimulationConfig {
+    SimulationConfig {
+        seed: 12,
+        num_individuals: 6,
+        num_bots: 1,
+        num_organisations: 1,
+        ..SimulationConfig::default()
+    }
+}
+
+fn point(engagement: f32, quality: Option<f32>) -> TradeoffPoint {
+    TradeoffPoint {
+        value: engagement,
+        engagement,
+        quality,
+        wellbeing: None,
+        revenue: 0.0,
+        pareto: false,
+    }
+}
+
+#[test]
+fn wellbeing_drifts_towards_the_quality_read() {
+    let config = WellBeingConfig {
+        initial: 0.5,
+        quality_rate: 0.5,
+        ..WellBeingConfig::default()
+    };
+    let mut wellbeing = WellBeing::new(&config);
+    assert_eq!(wellbeing.mean_read_quality(), None);
+
+    wellbeing.read(1.0, &config);
+    assert_eq!(wellbeing.index, 0.75);
+    wellbeing.read(0.0, &config);
+    assert_eq!(wellbeing.index, 0.375);
+    assert_eq!(wellbeing.mean_read_quality(), Some(0.5));
+}
+
+#[test]
+fn quality_follows_appeal_as_closely_as_configured() {
+    let config = GroundTruthConfig {
+        quality_appeal_correlation: 1.0,
+        ..GroundTruthConfig::default()
+    };
+    let quality = |appeal: f32| {
+        let post = PostBuilder::new(PostId(1)).appeal(appeal).build();
+        config.label(&post, AgentType::Individual, 3).quality
+    };
+
+    assert!(quality(-1.0) < quality(0.0));
+    assert!(quality(0.0) < quality(1.0));
+    assert_eq!(quality(0.0), config.quality.individual);
+}
+
+#[test]
+fn reading_moves_individuals_wellbeing_and_it_is_exported() {
+    let mut simulation = Simulation::new(config());
+    let initial = simulation.config.wellbeing.initial;
+    for _ in 0..200 {
+        simulation.step();
+    }
+
+    let moved = simulation
+        .agent_views()
+        .filter_map(|agent| agent.wellbeing)
+        .filter(|wellbeing| wellbeing.reads > 0)
+        .all(|wellbeing| wellbeing.index != initial);
+    assert!(moved);
+    assert_eq!(PopulationMetric::WellBeing.values(&simulation).len(), 6);
+
+    let mut export = MetricsExport::new();
+    export.record(&simulation);
+    let exported = export
+        .samples
+        .iter()
+        .filter(|sample| sample.metric == "wellbeing")
+        .count();
+    assert_eq!(exported, 6);
+    assert!(export
+        .samples
+        .iter()
+        .any(|sample| sample.metric == "read_quality"));
+}
+
+#[test]
+fn pareto_front_keeps_only_undominated_points() {
+    let mut curve = TradeoffCurve {
+        key: "engagement_weight".to_string(),
+        points: vec![
+            point(1.0, Some(0.6)),
+            point(2.0, Some(0.5)),
+            point(1.5, Some(0.4)),
+            point(3.0, Some(0.5)),
+            point(4.0, None),
+        ],
+    };
+    curve.mark_pareto();
+
+    let front: Vec<_> = curve.front().iter().map(|point| point.engagement).collect();
+    assert_eq!(front, vec![1.0, 3.0]);
+    assert!(curve.to_csv().starts_with(
+        "engagement_weight,engagement,quality,wellbeing,revenue,pareto\n1,1,0.6,,0,true\n"
+    ));
+}
+
+#[test]
+fn sweeps_run_once_per_value() {
+    let curve = TradeoffCurve::sweep(&config(), "engagement_weight", &[0.0, 0.5, 2.0], 60).unwrap();
+
+    let values: Vec<_> = curve.points.iter().map(|point| point.value).collect();
+    assert_eq!(values, vec![0.0, 0.5, 2.0]);
+    assert!(curve.points.iter().all(|point| point.engagement > 0.0));
+    assert!(!curve.front().is_empty());
+
+    let unknown = TradeoffCurve::sweep(&config(), "no_such_weight", &[1.0], 1);
+    assert!(matches!(unknown, Err(ScenarioError::UnknownKey(_))));
+}
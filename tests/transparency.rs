@@ -0,0 +1,102 @@
+use social_media_sandbox::{
+    models::{
+        AdsConfig, AgentId, Intervention, SimulationConfig, TransparencyConfig, TransparencyReport,
+    },
+    Simulation,
+};
+
+fn config() -> SimulationConfig {
+    SimulationConfig {
+        seed: 11,
+        num_individuals: 20,
+        num_bots: 2,
+        num_organisations: 2,
+        transparency: TransparencyConfig {
+            interval: 40,
+            top_tags: 3,
+        },
+        ads: AdsConfig {
+            per_feed: 1,
+            ..AdsConfig::default()
+        },
+        ..SimulationConfig::default()
+    }
+}
+
+#[test]
+fn reports_cover_back_to_back_windows() {
+    let mut simulation = Simulation::new(config());
+    simulation.schedule_intervention(50, Intervention::BanCreator(AgentId(0)));
+    simulation.run(120);
+
+    let reports = &simulation.transparency.reports;
+    assert_eq!(reports.len(), 3);
+    for (index, report) in reports.iter().enumerate() {
+        assert_eq!(report.from_step, index as u64 * 40);
+        assert_eq!(report.to_step, (index as u64 + 1) * 40);
+        assert!(report.top_tags.len() <= 3);
+        assert!(report
+            .top_tags
+            .windows(2)
+            .all(|pair| pair[0].exposures >= pair[1].exposures));
+        if report.organic_exposures > 0 {
+            let creators: usize = report.creator_types.iter().map(|s| s.exposures).sum();
+            assert_eq!(creators, report.organic_exposures);
+            let load = report.ad_load.unwrap();
+            assert!((0.0..1.0).contains(&load), "{load}");
+        }
+        assert_eq!(report.ad_slots_per_feed, 1);
+    }
+
+    // Only the window the ban fell in reports it
+    let actions: Vec<usize> = reports
+        .iter()
+        .map(|report| report.moderation_actions.len())
+        .collect();
+    assert_eq!(actions, [0, 1, 0]);
+    assert!(reports[1].moderation_actions[0].starts_with("Step 50:"));
+    assert_eq!(reports[0].creators_banned, 0);
+    assert_eq!(reports[2].creators_banned, 1);
+
+    // Running totals are counted per window rather than from the start
+    let impressions: usize = reports
+        .iter()
+        .map(|report| report.activity.ad_impressions)
+        .sum();
+    let total = simulation.transparency.tallies.ad_impressions;
+    assert_eq!(impressions, total);
+    assert!(reports
+        .iter()
+        .all(|report| report.activity.ad_impressions < total));
+}
+
+#[test]
+fn reports_export_as_json_and_markdown() {
+    let mut simulation = Simulation::new(config());
+    simulation.run(50);
+    assert_eq!(simulation.transparency.reports.len(), 1);
+
+    // An unpublished report runs from the last published one to now
+    let pending = simulation.transparency_report();
+    assert_eq!((pending.from_step, pending.to_step), (40, 50));
+
+    let report = simulation.transparency.latest().unwrap();
+    let json = report.to_json().unwrap();
+    let parsed: TransparencyReport = serde_json::from_str(&json).unwrap();
+    assert_eq!(&parsed, report);
+    let all: Vec<TransparencyReport> =
+        serde_json::from_str(&simulation.transparency.to_json().unwrap()).unwrap();
+    assert_eq!(all.len(), 1);
+
+    let markdown = simulation.transparency.to_markdown();
+    for heading in [
+        "# Transparency report: steps 0 to 40",
+        "## What was amplified",
+        "## Exposure by creator type",
+        "## Ads and paid reach",
+        "## Moderation",
+    ] {
+        assert!(markdown.contains(heading), "missing {heading}");
+    }
+    assert!(markdown.contains("- Ad slots per feed: 1 of 10"));
+}
@@ -0,0 +1,98 @@
+use social_media_sandbox::{
+    locale::Locale,
+    models::validate,
+    tutorial::{Advance, Highlight, Tutorial, TutorialProgress, STEPS},
+    Simulation,
+};
+
+fn progress(running: bool, agent_windows: usize, step: u64) -> TutorialProgress {
+    TutorialProgress {
+        running,
+        agent_windows,
+        step,
+    }
+}
+
+// Moves the tutorial on to the first step with the given advance
+fn skip_to(tutorial: &mut Tutorial, advance: Advance) {
+    while tutorial.current().unwrap().advance != advance {
+        tutorial.next();
+    }
+}
+
+#[test]
+fn the_tutorial_scenario_is_small_and_valid() {
+    let scenario = Tutorial::scenario();
+    assert!(scenario.warnings.is_empty(), "{:?}", scenario.warnings);
+    assert!(validate(&scenario.config).is_empty());
+
+    let mut simulation = Simulation::new(scenario.config);
+    assert_eq!(simulation.agent_views().count(), 10);
+    for _ in 0..50 {
+        simulation.step();
+    }
+    assert!(!simulation.engine.content_pool.is_empty());
+}
+
+#[test]
+fn steps_wait_for_what_they_ask_for() {
+    let mut tutorial = Tutorial::default();
+    tutorial.update(progress(true, 1, 0));
+    assert_eq!(tutorial.step, 0, "the welcome only moves on with Next");
+
+    skip_to(&mut tutorial, Advance::Running);
+    let running = tutorial.step;
+    tutorial.update(progress(false, 0, 0));
+    assert_eq!(tutorial.step, running);
+    tutorial.update(progress(true, 0, 0));
+    assert_eq!(tutorial.step, running + 1);
+
+    skip_to(&mut tutorial, Advance::AgentWindowOpen);
+    let window = tutorial.step;
+    tutorial.update(progress(true, 1, 5));
+    assert_eq!(tutorial.step, window + 1);
+
+    let Advance::Steps(steps) = tutorial.current().unwrap().advance else {
+        panic!("expected a step that waits on the simulation");
+    };
+    tutorial.update(progress(true, 1, 100));
+    tutorial.update(progress(true, 1, 100 + steps - 1));
+    assert_eq!(tutorial.step, window + 1);
+    tutorial.update(progress(true, 1, 100 + steps));
+    assert_eq!(tutorial.step, window + 2);
+}
+
+#[test]
+fn the_tutorial_can_be_walked_back_and_finished() {
+    let mut tutorial = Tutorial::default();
+    tutorial.back();
+    assert_eq!(tutorial.step, 0);
+
+    for _ in 0..STEPS.len() {
+        assert!(!tutorial.is_finished());
+        tutorial.next();
+    }
+    assert!(tutorial.is_finished());
+    assert!(tutorial.current().is_none());
+    tutorial.next();
+    tutorial.back();
+    assert_eq!(tutorial.current(), STEPS.last());
+}
+
+#[test]
+fn every_panel_is_visited_and_translated() {
+    for highlight in [
+        Highlight::ControlPanel,
+        Highlight::Agents,
+        Highlight::AgentWindow,
+        Highlight::ContentPool,
+        Highlight::Distributions,
+    ] {
+        assert!(STEPS.iter().any(|step| step.highlight == highlight));
+    }
+
+    for step in STEPS {
+        assert_ne!(Locale::Spanish.tr(step.title), step.title);
+        assert_ne!(Locale::Spanish.tr(step.text), step.text);
+    }
+}
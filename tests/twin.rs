@@ -0,0 +1,74 @@
+use social_media_sandbox::models::{
+    with_parameter, Intervention, RankingWeight, ScenarioError, SimulationConfig, Treatment,
+    TwinRun,
+};
+
+fn config() -> SimulationConfig {
+    SimulationConfig {
+        seed: 5,
+        num_individuals: 10,
+        ..SimulationConfig::default()
+    }
+}
+
+#[test]
+fn parameters_are_set_by_dotted_key() {
+    let base = config();
+
+    let changed = with_parameter(&base, "ranking_temperature", "0.5").unwrap();
+    assert_eq!(changed.ranking_temperature, 0.5);
+
+    let changed = with_parameter(&base, "survey.interval", "10").unwrap();
+    assert_eq!(changed.survey.interval, 10);
+
+    assert!(matches!(
+        with_parameter(&base, "no_such_parameter", "1"),
+        Err(ScenarioError::UnknownKey(_))
+    ));
+}
+
+#[test]
+fn twins_start_from_the_same_state() {
+    let twins = TwinRun::new(
+        config(),
+        Treatment::Parameter {
+            key: "min_recommendation_score".to_string(),
+            value: "0.5".to_string(),
+        },
+    )
+    .unwrap();
+
+    assert_eq!(twins.treated.engine.config.min_score, 0.5);
+    assert_eq!(twins.baseline.engine.config.min_score, 0.05);
+    for (baseline, treated) in twins.baseline.agents.iter().zip(&twins.treated.agents) {
+        assert_eq!(baseline.id(), treated.id());
+        assert_eq!(
+            baseline.interest_profile().to_table(),
+            treated.interest_profile().to_table()
+        );
+    }
+}
+
+#[test]
+fn every_step_is_paired() {
+    let mut twins = TwinRun::new(
+        config(),
+        Treatment::Intervention {
+            step: 2,
+            intervention: Intervention::SetWeight {
+                weight: RankingWeight::Recency,
+                value: 0.0,
+            },
+        },
+    )
+    .unwrap();
+
+    for step in 1..=5 {
+        let paired = twins.step();
+        assert_eq!(paired.step, step);
+        assert!(!paired.metrics.is_empty());
+    }
+    assert_eq!(twins.steps.len(), 5);
+    assert_eq!(twins.treated.engine.config.recency_weight, 0.0);
+    assert_ne!(twins.baseline.engine.config.recency_weight, 0.0);
+}
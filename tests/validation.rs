@@ -0,0 +1,104 @@
+use social_media_sandbox::models::{
+    bounds, validation::Reference, Bounds, ConfigBuilder, ConfigError, InvalidConfig, Scenario,
+    SimulationConfig,
+};
+
+#[test]
+fn the_builder_hands_back_valid_configs() {
+    let config = SimulationConfig::builder()
+        .seed(4)
+        .agents(10, 1, 1)
+        .ranking_weights(0.3, 0.4, 0.5)
+        .content_tags(1, 2)
+        .with(|config| config.economy.tip_chance = 0.5)
+        .build()
+        .unwrap();
+
+    assert_eq!(config.seed, 4);
+    assert_eq!(config.num_individuals, 10);
+    assert_eq!(config.engagement_weight, 0.5);
+    assert_eq!(config.economy.tip_chance, 0.5);
+
+    // Posts are still ranked on interest with no recency or engagement
+    let config = SimulationConfig::builder()
+        .ranking_weights(0.0, 0.0, 0.0)
+        .build();
+    assert!(config.is_ok());
+}
+
+#[test]
+fn the_builder_reports_every_problem_by_kind() {
+    let Err(InvalidConfig(problems)) = SimulationConfig::builder()
+        .ranking_weights(1.5, 0.7, 0.6)
+        .sample_tags(&["politics"])
+        .content_tags(3, 2)
+        .steps_per_day(0)
+        .with(|config| {
+            config
+                .persona_fractions
+                .insert("night owl".to_string(), 0.1);
+        })
+        .build()
+    else {
+        panic!("config should be invalid");
+    };
+
+    assert!(problems.contains(&ConfigError::OutOfRange {
+        key: "diversity_weight".to_string(),
+        value: 1.5,
+        bounds: Bounds::FRACTION,
+    }));
+    assert!(problems.contains(&ConfigError::OutOfRange {
+        key: "steps_per_day".to_string(),
+        value: 0.0,
+        bounds: Bounds::at_least(1.0),
+    }));
+    assert!(problems.iter().any(|problem| matches!(
+        problem,
+        ConfigError::Unknown { reference: Reference::Persona, name, .. } if name == "night owl"
+    )));
+    let inconsistent: Vec<Vec<&str>> = problems
+        .iter()
+        .filter(|problem| matches!(problem, ConfigError::Inconsistent { .. }))
+        .map(ConfigError::keys)
+        .collect();
+    assert!(inconsistent.contains(&vec!["recency_weight", "engagement_weight"]));
+    assert!(inconsistent.contains(&vec!["min_content_tags", "max_content_tags"]));
+    assert!(inconsistent.contains(&vec!["max_content_tags", "sample_tags"]));
+
+    let problem = ConfigError::OutOfRange {
+        key: "steps_per_day".to_string(),
+        value: 0.0,
+        bounds: Bounds::at_least(1.0),
+    };
+    assert_eq!(
+        problem.to_string(),
+        "`steps_per_day` is 0, expected at least 1"
+    );
+}
+
+#[test]
+fn sliders_cover_the_valid_range() {
+    let fraction = bounds("engagement_weight").unwrap();
+    assert_eq!(fraction.slider(5.0), 0.0..=1.0);
+    assert!(!fraction.contains(f32::NAN));
+    assert_eq!(bounds("position_bias").unwrap().slider(3.0), 0.0..=3.0);
+    assert_eq!(Bounds::AGREEMENT.to_string(), "-1 to 1");
+    assert_eq!(bounds("no_such_key"), None);
+}
+
+#[test]
+fn scenarios_only_become_configs_when_valid() {
+    let scenario = Scenario::parse("seed = 3\nrecency_weight = 0.4").unwrap();
+    assert_eq!(scenario.into_config().unwrap().recency_weight, 0.4);
+
+    let scenario = Scenario::parse("seed = 3\nrecency_weight = -0.4").unwrap();
+    let problems = scenario.validate();
+    let error = scenario.into_config().unwrap_err();
+    assert_eq!(error.0.len(), problems.len());
+    assert!(error.to_string().contains("`recency_weight` is -0.4"));
+
+    assert!(ConfigBuilder::from(SimulationConfig::default())
+        .build()
+        .is_ok());
+}
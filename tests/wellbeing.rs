@@ -0,0 +1,125 @@
+use social_media_sandbox::{
+    metrics::MetricsExport,
+    models::{SimulationConfig, WellBeing, WellBeingConfig, WellBeingSeries},
+    Simulation,
+};
+
+fn config() -> SimulationConfig {
+    SimulationConfig {
+        seed: 21,
+        num_individuals: 8,
+        num_bots: 1,
+        num_organisations: 1,
+        ..SimulationConfig::default()
+    }
+}
+
+#[test]
+fn only_late_scrolling_costs_wellbeing_and_rest_restores_it() {
+    let config = WellBeingConfig {
+        late_session_ticks: 2,
+        doomscroll_penalty: 0.1,
+        recovery_rate: 0.5,
+        ..WellBeingConfig::default()
+    };
+    let mut wellbeing = WellBeing::new(&config);
+
+    wellbeing.online(1, true, &config);
+    wellbeing.online(2, true, &config);
+    assert_eq!(wellbeing.index, 0.5);
+    wellbeing.online(3, false, &config);
+    assert_eq!(wellbeing.index, 0.5);
+    wellbeing.online(4, true, &config);
+    assert!((wellbeing.index - 0.4).abs() < 1e-6);
+
+    assert_eq!(wellbeing.screen_time, 4);
+    assert_eq!(wellbeing.late_session_share(), Some(0.5));
+
+    wellbeing.offline(&config);
+    assert!((wellbeing.index - 0.45).abs() < 1e-6);
+}
+
+#[test]
+fn conflictual_reads_are_counted_and_cost_wellbeing() {
+    let config = WellBeingConfig {
+        conflict_penalty: 0.6,
+        ..WellBeingConfig::default()
+    };
+    let mut wellbeing = WellBeing::new(&config);
+
+    wellbeing.conflict(&config);
+    assert_eq!(wellbeing.conflicts, 1);
+    assert_eq!(wellbeing.index, 0.0);
+}
+
+#[test]
+fn individuals_track_screen_time_and_conflicts() {
+    let mut config = config();
+    config.wellbeing.conflict_threshold = 0.0;
+    let mut simulation = Simulation::new(config);
+    for _ in 0..300 {
+        simulation.step();
+    }
+
+    let wellbeings: Vec<_> = simulation
+        .agent_views()
+        .filter_map(|agent| agent.wellbeing)
+        .collect();
+    assert_eq!(wellbeings.len(), 8);
+    assert!(wellbeings.iter().any(|wellbeing| wellbeing.screen_time > 0));
+    assert!(wellbeings.iter().any(|wellbeing| wellbeing.conflicts > 0));
+    for wellbeing in wellbeings {
+        assert!(wellbeing.screen_time <= 300);
+        assert!(wellbeing.late_session_time <= wellbeing.screen_time);
+        assert!(wellbeing.conflicts <= wellbeing.reads);
+    }
+}
+
+#[test]
+fn population_series_is_sampled_at_the_interval() {
+    let mut simulation = Simulation::new(config());
+    let interval = simulation.config.wellbeing.interval;
+    for _ in 0..interval * 5 {
+        simulation.step();
+    }
+
+    let steps: Vec<_> = simulation
+        .wellbeing
+        .samples
+        .iter()
+        .map(|sample| sample.step)
+        .collect();
+    assert_eq!(steps, (0..=5).map(|n| n * interval).collect::<Vec<_>>());
+
+    let latest = simulation.wellbeing.latest().unwrap();
+    assert_eq!(latest.agents, 8);
+    assert!(latest.min_index <= latest.mean_index);
+    assert_eq!(simulation.retained()["wellbeing samples"], 6);
+
+    let mut empty = WellBeingSeries::default();
+    let bots = Simulation::new(SimulationConfig {
+        num_individuals: 0,
+        ..config()
+    });
+    empty.record(0, bots.agent_views());
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn screen_time_is_exported_per_individual() {
+    let mut simulation = Simulation::new(config());
+    for _ in 0..50 {
+        simulation.step();
+    }
+    let mut export = MetricsExport::new();
+    export.record(&simulation);
+
+    for metric in ["screen_time", "late_session_share"] {
+        let agents = export
+            .samples
+            .iter()
+            .filter(|sample| sample.metric == metric)
+            .count();
+        assert_eq!(agents, 8, "{metric}");
+    }
+}
@@ -0,0 +1,83 @@
+use instant::Duration;
+use social_media_sandbox::{
+    models::{AgentId, Intervention, SimulationConfig},
+    worker::{SimulationSummary, WorkerCommand, WorkerLoop},
+};
+
+fn config(num_individuals: usize) -> SimulationConfig {
+    SimulationConfig {
+        seed: 1,
+        num_individuals,
+        ..SimulationConfig::default()
+    }
+}
+
+// Commands as the worker receives them, having been through JSON
+fn send(worker: &mut WorkerLoop, command: WorkerCommand) {
+    let json = command.to_json().unwrap();
+    worker.handle(WorkerCommand::from_json(&json).unwrap());
+}
+
+#[test]
+fn paused_workers_only_step_when_asked() {
+    let mut worker = WorkerLoop::new(config(5));
+
+    let summary = worker.run_for(Duration::from_millis(10));
+    assert_eq!(summary.step, 0);
+    assert!(!summary.running);
+
+    send(&mut worker, WorkerCommand::Step);
+    send(&mut worker, WorkerCommand::Step);
+    assert_eq!(worker.run_for(Duration::from_millis(10)).step, 2);
+}
+
+#[test]
+fn running_workers_step_every_slice() {
+    let mut worker = WorkerLoop::new(config(5));
+    send(&mut worker, WorkerCommand::Run);
+
+    let first = worker.run_for(Duration::ZERO);
+    let second = worker.run_for(Duration::from_millis(20));
+
+    assert_eq!(first.step, 1);
+    assert!(second.step > first.step);
+    assert!(second.running);
+    assert!(second.step_rate > 0.0);
+
+    send(&mut worker, WorkerCommand::Pause);
+    send(&mut worker, WorkerCommand::Step);
+    assert_eq!(worker.run_for(Duration::ZERO).step, second.step + 1);
+}
+
+#[test]
+fn resets_and_interventions_reach_the_simulation() {
+    let mut worker = WorkerLoop::new(config(5));
+    send(&mut worker, WorkerCommand::Run);
+    worker.run_for(Duration::ZERO);
+
+    send(&mut worker, WorkerCommand::Reset(Box::new(config(3))));
+    send(
+        &mut worker,
+        WorkerCommand::Schedule {
+            step: 0,
+            intervention: Intervention::BanCreator(AgentId(0)),
+        },
+    );
+    let summary = worker.summary(0.0);
+
+    assert_eq!(summary.step, 0);
+    assert!(!summary.running);
+    assert_eq!(summary.individuals, 3);
+    assert_eq!(worker.simulation.interventions.queued.len(), 1);
+}
+
+#[test]
+fn summaries_round_trip_through_json() {
+    let mut worker = WorkerLoop::new(config(5));
+    send(&mut worker, WorkerCommand::Step);
+    let summary = worker.summary(12.5);
+
+    let json = summary.to_json().unwrap();
+
+    assert_eq!(SimulationSummary::from_json(&json).unwrap(), summary);
+}